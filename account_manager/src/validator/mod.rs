@@ -65,7 +65,7 @@ pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<
         Some((modify::CMD, matches)) => modify::cli_run(matches, validator_base_dir),
         Some((import::CMD, matches)) => import::cli_run(matches, validator_base_dir),
         Some((list::CMD, _)) => list::cli_run(validator_base_dir),
-        Some((recover::CMD, matches)) => recover::cli_run(matches, validator_base_dir),
+        Some((recover::CMD, matches)) => recover::cli_run(matches, env, validator_base_dir),
         Some((slashing_protection::CMD, matches)) => {
             slashing_protection::cli_run(matches, env, validator_base_dir)
         }
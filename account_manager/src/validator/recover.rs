@@ -1,4 +1,5 @@
 use super::create::STORE_WITHDRAW_FLAG;
+use super::exit::{BEACON_SERVER_FLAG, DEFAULT_BEACON_NODE};
 use crate::validator::create::COUNT_FLAG;
 use crate::wallet::create::STDIN_INPUTS_FLAG;
 use crate::SECRETS_DIR_FLAG;
@@ -8,13 +9,27 @@ use clap::{Arg, ArgAction, ArgMatches, Command};
 use clap_utils::FLAG_HEADER;
 use directory::ensure_dir_exists;
 use directory::{parse_path_or_default_with_flag, DEFAULT_SECRET_DIR};
+use environment::Environment;
+use eth2::types::{StateId, ValidatorId};
+use eth2::{BeaconNodeHttpClient, Timeouts};
 use eth2_wallet::bip39::Seed;
 use eth2_wallet::{recover_validator_secret_from_mnemonic, KeyType, ValidatorKeystores};
+use sensitive_url::SensitiveUrl;
+use std::cmp;
+use std::collections::HashSet;
+use std::future::Future;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::time::Duration;
+use types::{EthSpec, PublicKeyBytes};
 use validator_dir::Builder as ValidatorDirBuilder;
 pub const CMD: &str = "recover";
 pub const FIRST_INDEX_FLAG: &str = "first-index";
 pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const DERIVATION_GAP_FLAG: &str = "derivation-gap";
+
+/// The maximum number of public keys to query from the beacon node in a single request.
+const PUBKEYS_PER_REQUEST: u32 = 64;
 
 pub fn cli_app() -> Command {
     Command::new(CMD)
@@ -43,6 +58,29 @@ pub fn cli_app() -> Command {
                 .default_value("1")
                 .display_order(0)
         )
+        .arg(
+            Arg::new(DERIVATION_GAP_FLAG)
+                .long(DERIVATION_GAP_FLAG)
+                .value_name("GAP")
+                .help("If present, only recover the keys of validators which exist on chain. \
+                    Keys are derived consecutively from the provided `--first-index` and checked \
+                    against the `--beacon-node`, stopping once this number of consecutive keys \
+                    have no validator.")
+                .action(ArgAction::Set)
+                .conflicts_with(COUNT_FLAG)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new(BEACON_SERVER_FLAG)
+                .long(BEACON_SERVER_FLAG)
+                .value_name("NETWORK_ADDRESS")
+                .help("Address to a beacon node HTTP API, used to find validators with \
+                    `--derivation-gap`.")
+                .default_value(DEFAULT_BEACON_NODE)
+                .requires(DERIVATION_GAP_FLAG)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new(MNEMONIC_FLAG)
                 .long(MNEMONIC_FLAG)
@@ -87,7 +125,11 @@ pub fn cli_app() -> Command {
         )
 }
 
-pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+pub fn cli_run<E: EthSpec>(
+    matches: &ArgMatches,
+    env: Environment<E>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
     let secrets_dir = if matches.get_one::<String>("datadir").is_some() {
         let path: PathBuf = clap_utils::parse_required(matches, "datadir")?;
         path.join(DEFAULT_SECRET_DIR)
@@ -96,6 +138,7 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
     };
     let first_index: u32 = clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?;
     let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
+    let derivation_gap: Option<u32> = clap_utils::parse_optional(matches, DERIVATION_GAP_FLAG)?;
     let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
     let stdin_inputs = cfg!(windows) || matches.get_flag(STDIN_INPUTS_FLAG);
 
@@ -112,7 +155,44 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
 
     let seed = Seed::new(&mnemonic, "");
 
-    for index in first_index..first_index + count {
+    let indices = if let Some(gap) = derivation_gap {
+        if gap == 0 {
+            return Err(format!(
+                "--{} must be greater than zero",
+                DERIVATION_GAP_FLAG
+            ));
+        }
+
+        let server_url: String = clap_utils::parse_required(matches, BEACON_SERVER_FLAG)?;
+        let client = BeaconNodeHttpClient::new(
+            SensitiveUrl::parse(&server_url)
+                .map_err(|e| format!("Failed to parse beacon http server: {:?}", e))?,
+            Timeouts::set_all(Duration::from_secs(env.eth2_config.spec.seconds_per_slot)),
+        );
+
+        let indices = env.runtime().block_on(find_indices_on_chain(
+            &client,
+            seed.as_bytes(),
+            first_index,
+            gap,
+        ))?;
+        let scan_end = indices
+            .last()
+            .map_or(first_index, |index| index.saturating_add(1))
+            .saturating_add(gap);
+        eprintln!(
+            "Found {} validator(s) on chain between indices {} and {}",
+            indices.len(),
+            first_index,
+            scan_end - 1
+        );
+        indices
+    } else {
+        (first_index..first_index + count).collect()
+    };
+    let count = indices.len();
+
+    for (i, index) in indices.into_iter().enumerate() {
         let voting_password = random_password();
         let withdrawal_password = random_password();
 
@@ -145,14 +225,170 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
             .build()
             .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
 
-        println!(
-            "{}/{}\tIndex: {}\t0x{}",
-            index - first_index + 1,
-            count,
-            index,
-            voting_pubkey
-        );
+        println!("{}/{}\tIndex: {}\t0x{}", i + 1, count, index, voting_pubkey);
     }
 
     Ok(())
 }
+
+/// Derives the voting public key at `index`.
+fn voting_pubkey(seed: &[u8], index: u32) -> Result<PublicKeyBytes, String> {
+    let (secret, _) = recover_validator_secret_from_mnemonic(seed, index, KeyType::Voting)
+        .map_err(|e| format!("Unable to recover validator keys: {:?}", e))?;
+    let keypair = keypair_from_secret(secret.as_bytes())
+        .map_err(|e| format!("Unable to recover validator keys: {:?}", e))?;
+    Ok(PublicKeyBytes::from(&keypair.pk))
+}
+
+/// Returns the indices from `first_index` onwards whose voting keys belong to a validator on
+/// chain, according to the head state of the beacon node.
+///
+/// Scanning stops once `gap` consecutive indices have no validator.
+async fn find_indices_on_chain(
+    client: &BeaconNodeHttpClient,
+    seed: &[u8],
+    first_index: u32,
+    gap: u32,
+) -> Result<Vec<u32>, String> {
+    let is_syncing = client
+        .get_node_syncing()
+        .await
+        .map_err(|e| format!("Failed to get sync status: {:?}", e))?
+        .data
+        .is_syncing;
+    if is_syncing {
+        return Err("Beacon node is still syncing".to_string());
+    }
+
+    scan_derivation_indices(first_index, gap, PUBKEYS_PER_REQUEST, move |indices| {
+        indices_on_chain(client, seed, indices)
+    })
+    .await
+}
+
+/// Returns the indices in `indices` whose voting keys belong to a validator in the head state of
+/// the beacon node.
+async fn indices_on_chain(
+    client: &BeaconNodeHttpClient,
+    seed: &[u8],
+    indices: Range<u32>,
+) -> Result<HashSet<u32>, String> {
+    let pubkeys = indices
+        .clone()
+        .map(|index| voting_pubkey(seed, index))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let ids = pubkeys
+        .iter()
+        .copied()
+        .map(ValidatorId::PublicKey)
+        .collect();
+
+    let on_chain = client
+        .post_beacon_states_validators(StateId::Head, Some(ids), None)
+        .await
+        .map_err(|e| format!("Failed to get validators: {:?}", e))?
+        .ok_or("Head state not found on beacon node")?
+        .data
+        .into_iter()
+        .map(|validator| validator.validator.pubkey)
+        .collect::<HashSet<_>>();
+
+    Ok(indices
+        .zip(&pubkeys)
+        .filter(|(_, pubkey)| on_chain.contains(pubkey))
+        .map(|(index, _)| index)
+        .collect())
+}
+
+/// Scans the indices from `first_index` onwards in batches of `batch_size`, returning those which
+/// `on_chain` reports as belonging to a validator.
+///
+/// Scanning stops once `gap` consecutive indices have no validator, so keys beyond a gap of fewer
+/// than `gap` unused indices are still found.
+async fn scan_derivation_indices<F, R>(
+    first_index: u32,
+    gap: u32,
+    batch_size: u32,
+    mut on_chain: F,
+) -> Result<Vec<u32>, String>
+where
+    F: FnMut(Range<u32>) -> R,
+    R: Future<Output = Result<HashSet<u32>, String>>,
+{
+    let mut found = vec![];
+    let mut next_index = first_index;
+    let mut scan_end = first_index.saturating_add(gap);
+
+    while next_index < scan_end {
+        let batch_end = cmp::min(scan_end, next_index.saturating_add(batch_size));
+        let batch_on_chain = on_chain(next_index..batch_end).await?;
+
+        for index in next_index..batch_end {
+            if batch_on_chain.contains(&index) {
+                found.push(index);
+                scan_end = cmp::max(scan_end, index.saturating_add(1).saturating_add(gap));
+            }
+        }
+
+        next_index = batch_end;
+    }
+
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scans for the indices in `on_chain`, recording the batches which are queried.
+    async fn scan(
+        on_chain: &[u32],
+        first_index: u32,
+        gap: u32,
+        batch_size: u32,
+    ) -> (Vec<u32>, Vec<Range<u32>>) {
+        let mut batches = vec![];
+        let found = scan_derivation_indices(first_index, gap, batch_size, |indices| {
+            batches.push(indices.clone());
+            let batch_on_chain = indices
+                .filter(|index| on_chain.contains(index))
+                .collect::<HashSet<_>>();
+            async move { Ok(batch_on_chain) }
+        })
+        .await
+        .unwrap();
+        (found, batches)
+    }
+
+    #[tokio::test]
+    async fn scan_stops_after_gap() {
+        let on_chain = [0, 1, 2, 5, 9];
+
+        // The gap of three unused indices after index 5 ends the scan before index 9.
+        let (found, batches) = scan(&on_chain, 0, 3, 2).await;
+        assert_eq!(found, vec![0, 1, 2, 5]);
+        assert_eq!(batches, vec![0..2, 2..4, 4..6, 6..8, 8..9]);
+
+        // A larger gap finds index 9.
+        let (found, _) = scan(&on_chain, 0, 4, 2).await;
+        assert_eq!(found, vec![0, 1, 2, 5, 9]);
+
+        // Each request only covers the indices up to the current end of the scan.
+        let (found, batches) = scan(&on_chain, 0, 3, 64).await;
+        assert_eq!(found, vec![0, 1, 2, 5]);
+        assert_eq!(batches, vec![0..3, 3..6, 6..9]);
+    }
+
+    #[tokio::test]
+    async fn scan_starts_at_first_index() {
+        let on_chain = [0, 1, 2, 5, 9];
+
+        let (found, _) = scan(&on_chain, 2, 3, 64).await;
+        assert_eq!(found, vec![2, 5]);
+
+        let (found, batches) = scan(&on_chain, 6, 2, 64).await;
+        assert!(found.is_empty());
+        assert_eq!(batches, vec![6..8]);
+    }
+}
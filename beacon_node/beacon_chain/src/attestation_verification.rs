@@ -65,6 +65,7 @@ use types::{
     SignedAggregateAndProof, Slot, SubnetId,
 };
 
+pub(crate) use batch::find_valid_signature_sets;
 pub use batch::{batch_verify_aggregated_attestations, batch_verify_unaggregated_attestations};
 
 /// Returned when an attestation was not successfully verified. It might not have been verified for
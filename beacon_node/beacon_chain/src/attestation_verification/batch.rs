@@ -4,7 +4,9 @@
 //! In each function, attestations are "indexed" (i.e., the `IndexedAttestation` is computed), to
 //! determine if they should progress to signature verification. Then, all attestations which were
 //! successfully indexed have their signatures verified in a batch. If that signature batch fails
-//! then all attestation signatures are verified independently.
+//! then all aggregated attestation signatures are verified independently, whilst a batch of
+//! unaggregated attestations is repeatedly split in half to find the valid signatures, leaving only
+//! the remainder to be verified independently.
 //!
 //! The outcome of each function is a `Vec<Result>` with a one-to-one mapping to the attestations
 //! supplied as input. Each result provides the exact success or failure result of the corresponding
@@ -14,7 +16,7 @@ use super::{
     VerifiedAggregatedAttestation, VerifiedUnaggregatedAttestation,
 };
 use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes};
-use bls::verify_signature_sets;
+use bls::{verify_signature_sets, SignatureSet};
 use state_processing::signature_sets::{
     indexed_attestation_signature_set_from_pubkeys, signed_aggregate_selection_proof_signature_set,
     signed_aggregate_signature_set,
@@ -22,6 +24,10 @@ use state_processing::signature_sets::{
 use std::borrow::Cow;
 use types::*;
 
+/// A failed batch of signature sets smaller than this is not split any further, instead each of its
+/// signatures is verified individually.
+const MIN_SPLIT_BATCH_SIZE: usize = 4;
+
 /// Verify aggregated attestations using batch BLS signature verification.
 ///
 /// See module-level docs for more info.
@@ -154,8 +160,9 @@ where
         })
         .collect::<Vec<_>>();
 
-    // May be set to `No` if batch verification succeeds.
-    let mut check_signatures = CheckAttestationSignature::Yes;
+    // Whether each partially verified attestation is known to have a valid signature, in the same
+    // order as the `Ok` values of `partial_results`.
+    let mut signature_valid = vec![false; num_partially_verified];
 
     // Perform batch BLS verification, if any attestation signatures are worth checking.
     if num_partially_verified > 0 {
@@ -195,15 +202,25 @@ where
         if verify_signature_sets(signature_sets.iter()) {
             // Since all the signatures verified in a batch, there's no reason for them to be
             // checked again later.
-            check_signatures = CheckAttestationSignature::No
+            signature_valid.fill(true);
+        } else {
+            metrics::inc_counter(&metrics::ATTESTATION_PROCESSING_BATCH_UNAGG_SIGNATURE_FAILURES);
+            find_valid_signature_sets(&signature_sets, &mut signature_valid);
         }
     }
 
-    // Complete the attestation verification, potentially verifying all signatures independently.
+    // Complete the attestation verification, verifying any signatures which are not already known
+    // to be valid independently.
+    let mut signature_valid = signature_valid.into_iter();
     let final_results = partial_results
         .into_iter()
         .map(|result| match result {
             Ok(partial) => {
+                let check_signatures = if signature_valid.next().unwrap_or(false) {
+                    CheckAttestationSignature::No
+                } else {
+                    CheckAttestationSignature::Yes
+                };
                 VerifiedUnaggregatedAttestation::from_indexed(partial, chain, check_signatures)
             }
             Err(e) => Err(e),
@@ -212,3 +229,31 @@ where
 
     Ok(final_results)
 }
+
+/// Given a batch of `signature_sets` which failed verification, recursively split it in half to
+/// find the signature sets which are valid, setting the corresponding entry of `valid` to `true`.
+///
+/// Batches smaller than `MIN_SPLIT_BATCH_SIZE` are not split, their entries are left as `false` so
+/// that each signature is verified individually.
+pub(crate) fn find_valid_signature_sets(signature_sets: &[SignatureSet], valid: &mut [bool]) {
+    if signature_sets.len() < MIN_SPLIT_BATCH_SIZE {
+        return;
+    }
+
+    let mid = signature_sets.len() / 2;
+    let (left_sets, right_sets) = signature_sets.split_at(mid);
+    let (left_valid, right_valid) = valid.split_at_mut(mid);
+
+    if verify_signature_sets(left_sets.iter()) {
+        left_valid.fill(true);
+        // The batch as a whole failed, so the invalid signature(s) must be in the right half.
+        find_valid_signature_sets(right_sets, right_valid);
+    } else {
+        find_valid_signature_sets(left_sets, left_valid);
+        if verify_signature_sets(right_sets.iter()) {
+            right_valid.fill(true);
+        } else {
+            find_valid_signature_sets(right_sets, right_valid);
+        }
+    }
+}
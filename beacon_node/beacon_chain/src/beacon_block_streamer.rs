@@ -1,3 +1,4 @@
+use crate::reconstructed_payload_cache::ReconstructedPayloadCache;
 use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes, BlockProcessStatus};
 use execution_layer::{ExecutionLayer, ExecutionPayloadBodyV1};
 use slog::{crit, debug, error, Logger};
@@ -128,6 +129,7 @@ fn reconstruct_default_header_block<E: EthSpec>(
 fn reconstruct_blocks<E: EthSpec>(
     block_map: &mut HashMap<Hash256, Arc<BlockResult<E>>>,
     block_parts_with_bodies: HashMap<Hash256, BlockParts<E>>,
+    payload_cache: &ReconstructedPayloadCache<E>,
     log: &Logger,
 ) {
     for (root, block_parts) in block_parts_with_bodies {
@@ -136,6 +138,7 @@ fn reconstruct_blocks<E: EthSpec>(
                 Ok(payload) => {
                     let header_from_payload = ExecutionPayloadHeader::from(payload.to_ref());
                     if header_from_payload == *block_parts.header {
+                        payload_cache.insert(payload.clone());
                         block_map.insert(
                             root,
                             Arc::new(
@@ -231,7 +234,12 @@ impl<E: EthSpec> BodiesByRange<E> {
         }
     }
 
-    async fn execute(&mut self, execution_layer: &ExecutionLayer<E>, log: &Logger) {
+    async fn execute(
+        &mut self,
+        execution_layer: &ExecutionLayer<E>,
+        payload_cache: &ReconstructedPayloadCache<E>,
+        log: &Logger,
+    ) {
         if let RequestState::UnSent(blocks_parts_ref) = &mut self.state {
             let block_parts_vec = std::mem::take(blocks_parts_ref);
 
@@ -260,7 +268,7 @@ impl<E: EthSpec> BodiesByRange<E> {
                             });
                     }
 
-                    reconstruct_blocks(&mut block_map, with_bodies, log);
+                    reconstruct_blocks(&mut block_map, with_bodies, payload_cache, log);
                 }
                 Err(e) => {
                     let block_result =
@@ -279,9 +287,10 @@ impl<E: EthSpec> BodiesByRange<E> {
         &mut self,
         root: &Hash256,
         execution_layer: &ExecutionLayer<E>,
+        payload_cache: &ReconstructedPayloadCache<E>,
         log: &Logger,
     ) -> Option<Arc<BlockResult<E>>> {
-        self.execute(execution_layer, log).await;
+        self.execute(execution_layer, payload_cache, log).await;
         if let RequestState::Sent(map) = &self.state {
             return map.get(root).cloned();
         }
@@ -360,6 +369,7 @@ impl<E: EthSpec> EngineRequest<E> {
         &self,
         root: &Hash256,
         execution_layer: &ExecutionLayer<E>,
+        payload_cache: &ReconstructedPayloadCache<E>,
         log: &Logger,
     ) -> Arc<BlockResult<E>> {
         match self {
@@ -367,7 +377,7 @@ impl<E: EthSpec> EngineRequest<E> {
                 by_range
                     .write()
                     .await
-                    .get_block_result(root, execution_layer, log)
+                    .get_block_result(root, execution_layer, payload_cache, log)
                     .await
             }
             Self::NoRequest(map) => map.read().await.get(root).cloned(),
@@ -505,6 +515,17 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
                                     header,
                                     &self.beacon_chain.spec,
                                 )
+                            } else if let Some(payload) = self
+                                .beacon_chain
+                                .reconstructed_payload_cache
+                                .get(&header.block_hash())
+                            {
+                                // Cached payloads have already been verified against their header.
+                                blinded_block
+                                    .try_into_full_block(Some(payload))
+                                    .ok_or(BeaconChainError::AddPayloadLogicError)
+                                    .map(Arc::new)
+                                    .map(Some)
                             } else {
                                 // Add the block to the set requiring a by-range request.
                                 let block_parts = BlockParts::new(blinded_block, header);
@@ -615,7 +636,12 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
             }
 
             let result = request
-                .get_block_result(&root, &self.execution_layer, &self.beacon_chain.log)
+                .get_block_result(
+                    &root,
+                    &self.execution_layer,
+                    &self.beacon_chain.reconstructed_payload_cache,
+                    &self.beacon_chain.log,
+                )
                 .await;
 
             let successful = result
@@ -827,6 +853,8 @@ mod tests {
                 .expect("block should exist");
             expected_blocks.push(block);
         }
+        // the payloads of blinded blocks should have been cached during reconstruction
+        assert!(!harness.chain.reconstructed_payload_cache.is_empty());
 
         for epoch in 0..num_epochs {
             let start = epoch * slots_per_epoch;
@@ -970,6 +998,8 @@ mod tests {
                 .expect("block should exist");
             expected_blocks.push(block);
         }
+        // the payloads of blinded blocks should have been cached during reconstruction
+        assert!(!harness.chain.reconstructed_payload_cache.is_empty());
 
         for epoch in 0..num_epochs {
             let start = epoch * slots_per_epoch;
@@ -20,17 +20,20 @@ use crate::block_verification_types::{
 };
 pub use crate::canonical_head::CanonicalHead;
 use crate::chain_config::ChainConfig;
+use crate::chain_health::ChainHealthMonitor;
 use crate::data_availability_checker::{
     Availability, AvailabilityCheckError, AvailableBlock, DataAvailabilityChecker,
 };
 use crate::data_column_verification::{GossipDataColumnError, GossipVerifiedDataColumn};
 use crate::early_attester_cache::EarlyAttesterCache;
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
-use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
+use crate::eth1_chain::{DepositSnapshotImport, Eth1Chain, Eth1ChainBackend};
 use crate::eth1_finalization_cache::{Eth1FinalizationCache, Eth1FinalizationData};
 use crate::events::ServerSentEventHandler;
 use crate::execution_payload::{get_execution_payload, NotifyExecutionLayer, PreparePayloadHandle};
 use crate::fork_choice_signal::{ForkChoiceSignalRx, ForkChoiceSignalTx, ForkChoiceWaitResult};
+use crate::gossip_arrival_times::GossipArrivalTimes;
+use crate::gossip_recorder::GossipRecorder;
 use crate::graffiti_calculator::GraffitiCalculator;
 use crate::head_tracker::{HeadTracker, HeadTrackerReader, SszHeadTracker};
 use crate::historical_blocks::HistoricalBlockError;
@@ -46,6 +49,7 @@ use crate::naive_aggregation_pool::{
     AggregatedAttestationMap, Error as NaiveAggregationError, NaiveAggregationPool,
     SyncContributionAggregateMap,
 };
+use crate::non_finality::NonFinalityMonitor;
 use crate::observed_aggregates::{
     Error as AttestationObservationError, ObservedAggregateAttestations, ObservedSyncContributions,
 };
@@ -58,10 +62,14 @@ use crate::observed_operations::{ObservationOutcome, ObservedOperations};
 use crate::observed_slashable::ObservedSlashable;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::persisted_observed_caches::PersistedObservedCaches;
 use crate::pre_finalization_cache::PreFinalizationBlockCache;
+use crate::reconstructed_payload_cache::ReconstructedPayloadCache;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
+use crate::state_root_pool::StateRootPool;
 use crate::sync_committee_verification::{
-    Error as SyncCommitteeError, VerifiedSyncCommitteeMessage, VerifiedSyncContribution,
+    batch_verify_sync_committee_messages, Error as SyncCommitteeError,
+    VerifiedSyncCommitteeMessage, VerifiedSyncContribution,
 };
 use crate::validator_monitor::{
     get_slot_delay_ms, timestamp_now, ValidatorMonitor,
@@ -72,6 +80,7 @@ use crate::{
     kzg_utils, metrics, AvailabilityPendingExecutedBlock, BeaconChainError, BeaconForkChoiceStore,
     BeaconSnapshot, CachedHead,
 };
+use eth2::lighthouse::SseBlockEquivocation;
 use eth2::types::{EventKind, SseBlobSidecar, SseBlock, SseExtendedPayloadAttributes};
 use execution_layer::{
     BlockProposalContents, BlockProposalContentsType, BuilderParams, ChainHealth, ExecutionLayer,
@@ -121,6 +130,7 @@ use store::{
 };
 use task_executor::{ShutdownReason, TaskExecutor};
 use tokio_stream::Stream;
+use tracing::{info_span, Instrument};
 use tree_hash::TreeHash;
 use types::blob_sidecar::FixedBlobSidecarList;
 use types::payload::BlockProductionVersion;
@@ -136,6 +146,7 @@ pub const BEACON_CHAIN_DB_KEY: Hash256 = Hash256::zero();
 pub const OP_POOL_DB_KEY: Hash256 = Hash256::zero();
 pub const ETH1_CACHE_DB_KEY: Hash256 = Hash256::zero();
 pub const FORK_CHOICE_DB_KEY: Hash256 = Hash256::zero();
+pub const OBSERVED_CACHES_DB_KEY: Hash256 = Hash256::zero();
 
 /// Defines how old a block can be before it's no longer a candidate for the early attester cache.
 const EARLY_ATTESTER_CACHE_HISTORIC_SLOTS: u64 = 4;
@@ -464,6 +475,18 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) attester_cache: Arc<AttesterCache>,
     /// A cache used when producing attestations whilst the head block is still being imported.
     pub early_attester_cache: EarlyAttesterCache<T::EthSpec>,
+    /// Tracks the health of the chain and whether degraded-mode behaviours are active.
+    pub chain_health: ChainHealthMonitor,
+    /// Tracks the growth of the hot database during periods of non-finality.
+    pub non_finality: NonFinalityMonitor,
+    /// A log of the arrival times of gossip messages, for network analysis.
+    pub gossip_arrival_times: Mutex<GossipArrivalTimes>,
+    /// Records gossip-verified objects for later replay, if enabled.
+    pub gossip_recorder: Option<GossipRecorder>,
+    /// Thread pool used to compute the state roots of post-states during block import.
+    pub state_root_pool: StateRootPool,
+    /// A cache of execution payloads reconstructed from the EL when serving blinded blocks.
+    pub reconstructed_payload_cache: ReconstructedPayloadCache<T::EthSpec>,
     /// Cache gossip verified blocks to serve over ReqResp before they are imported
     pub reqresp_pre_import_cache: Arc<RwLock<ReqRespPreImportCache<T::EthSpec>>>,
     /// A cache used to keep track of various block timings.
@@ -700,6 +723,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Persists the observed block producer, attester and aggregator caches to disk.
+    pub fn persist_observed_caches(&self) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&metrics::PERSIST_OBSERVED_CACHES);
+
+        self.store.put_item(
+            &OBSERVED_CACHES_DB_KEY,
+            &PersistedObservedCaches {
+                block_producers: self.observed_block_producers.read().as_ssz_container(),
+                gossip_attesters: self.observed_gossip_attesters.read().as_ssz_container(),
+                block_attesters: self.observed_block_attesters.read().as_ssz_container(),
+                aggregators: self.observed_aggregators.read().as_ssz_container(),
+            },
+        )?;
+
+        Ok(())
+    }
+
     /// Persists `self.eth1_chain` and its caches to disk.
     pub fn persist_eth1_cache(&self) -> Result<(), Error> {
         let _timer = metrics::start_timer(&metrics::PERSIST_ETH1_CACHE);
@@ -712,6 +752,73 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Returns `true` if the deposit cache is missing deposits which have already been processed
+    /// by the head state, e.g. after checkpoint sync from a node which didn't serve a deposit
+    /// snapshot.
+    pub fn wants_deposit_snapshot(&self) -> bool {
+        self.eth1_chain.as_ref().map_or(false, |eth1_chain| {
+            !eth1_chain.is_dummy_backend()
+                && eth1_chain.deposit_count()
+                    < self
+                        .canonical_head
+                        .cached_head()
+                        .snapshot
+                        .beacon_state
+                        .eth1_deposit_index()
+        })
+    }
+
+    /// Loads a deposit snapshot received from a peer into the deposit cache, so that only the
+    /// deposit logs after the snapshot need to be downloaded from the execution node.
+    ///
+    /// The snapshot is only loaded if it finalizes exactly the deposits voted into the finalized
+    /// state, since older deposits can't be checked against the chain.
+    pub fn import_deposit_snapshot(
+        &self,
+        snapshot: &DepositTreeSnapshot,
+    ) -> Result<DepositSnapshotImport, Error> {
+        let Some(eth1_chain) = self.eth1_chain.as_ref() else {
+            return Ok(DepositSnapshotImport::NotRequired);
+        };
+        if eth1_chain.is_dummy_backend() || snapshot.deposit_count <= eth1_chain.deposit_count() {
+            return Ok(DepositSnapshotImport::NotRequired);
+        }
+        if !snapshot.is_valid() {
+            return Ok(DepositSnapshotImport::Invalid);
+        }
+
+        let finalized_slot = self
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .epoch
+            .start_slot(T::EthSpec::slots_per_epoch());
+        let finalized_state_root = self
+            .state_root_at_slot(finalized_slot)?
+            .ok_or(Error::MissingFinalizedStateRoot(finalized_slot))?;
+        let finalized_state = self
+            .get_state(&finalized_state_root, Some(finalized_slot))?
+            .ok_or(Error::MissingBeaconState(finalized_state_root))?;
+        if !snapshot.matches_eth1_data(finalized_state.eth1_data()) {
+            return Ok(DepositSnapshotImport::Mismatch);
+        }
+
+        if eth1_chain
+            .load_deposit_snapshot(snapshot)
+            .map_err(Error::Eth1ChainError)?
+        {
+            info!(
+                self.log,
+                "Loaded deposit snapshot from peer";
+                "deposit_count" => snapshot.deposit_count,
+                "execution_block_height" => snapshot.execution_block_height,
+            );
+            Ok(DepositSnapshotImport::Imported)
+        } else {
+            Ok(DepositSnapshotImport::NotRequired)
+        }
+    }
+
     /// Returns the slot _right now_ according to `self.slot_clock`. Returns `Err` if the slot is
     /// unavailable.
     ///
@@ -951,6 +1058,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Ok(Some(root));
         }
 
+        if let Some(root) = self.state_root_at_slot_without_states(request_slot)? {
+            return Ok(Some(root));
+        }
+
         process_results(
             self.forwards_iter_state_roots_until(request_slot, request_slot)?,
             |mut iter| {
@@ -1036,6 +1147,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Ok(root_opt);
         }
 
+        if let Some((root, skipped)) = self.block_root_at_slot_without_states(request_slot)? {
+            return Ok((!skipped).then_some(root));
+        }
+
         if let Some(((prev_root, _), (curr_root, curr_slot))) = process_results(
             self.forwards_iter_block_roots_until(prev_slot, request_slot)?,
             |iter| iter.tuple_windows().next(),
@@ -1087,6 +1202,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Ok(Some(root));
         }
 
+        if let Some((root, _)) = self.block_root_at_slot_without_states(request_slot)? {
+            return Ok(Some(root));
+        }
+
         process_results(
             self.forwards_iter_block_roots_until(request_slot, request_slot)?,
             |mut iter| {
@@ -1104,6 +1223,67 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         )?
     }
 
+    /// Returns the canonical block root at `request_slot` and whether `request_slot` was skipped,
+    /// in which case the root is that of the latest prior block.
+    ///
+    /// Roots are read from fork choice or the freezer database without loading any states.
+    /// Returns `Ok(None)` if the root can't be found this way.
+    fn block_root_at_slot_without_states(
+        &self,
+        request_slot: Slot,
+    ) -> Result<Option<(Hash256, bool)>, Error> {
+        if request_slot >= self.store.get_split_slot() {
+            return Ok(self
+                .canonical_block_at_or_before_slot(request_slot)
+                .map(|(root, slot, _)| (root, slot != request_slot)));
+        }
+
+        let root_opt = self.store.get_cold_block_root(request_slot)?;
+        let prev_root_opt = self
+            .store
+            .get_cold_block_root(request_slot.saturating_sub(1_u64))?;
+        Ok(root_opt
+            .zip(prev_root_opt)
+            .map(|(root, prev_root)| (root, root == prev_root)))
+    }
+
+    /// Returns the canonical state root at `request_slot`.
+    ///
+    /// Roots are read from fork choice, the hot state summaries or the freezer database without
+    /// loading any states. Returns `Ok(None)` if the root can't be found this way.
+    fn state_root_at_slot_without_states(
+        &self,
+        request_slot: Slot,
+    ) -> Result<Option<Hash256>, Error> {
+        if request_slot < self.store.get_split_slot() {
+            return Ok(self.store.get_cold_state_root(request_slot)?);
+        }
+
+        match self.canonical_block_at_or_before_slot(request_slot) {
+            Some((_, block_slot, state_root)) if block_slot == request_slot => Ok(Some(state_root)),
+            Some((block_root, _, _)) => {
+                Ok(self.store.get_hot_state_root(request_slot, block_root)?)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the root, slot and state root of the latest canonical block at or before
+    /// `request_slot`, if it is known to fork choice.
+    fn canonical_block_at_or_before_slot(
+        &self,
+        request_slot: Slot,
+    ) -> Option<(Hash256, Slot, Hash256)> {
+        let head_block_root = self.canonical_head.cached_head().head_block_root();
+        let fork_choice = self.canonical_head.fork_choice_read_lock();
+        fork_choice
+            .proto_array()
+            .core_proto_array()
+            .iter_nodes(&head_block_root)
+            .find(|node| node.slot <= request_slot)
+            .map(|node| (node.root, node.slot, node.state_root))
+    }
+
     /// Returns the block at the given root, if any.
     ///
     /// Will also check the early attester cache for the block. Because of this, there's no
@@ -1180,6 +1360,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let exec_block_hash = execution_payload_header.block_hash();
 
+        // Payloads in the cache have already been verified against their header.
+        if let Some(execution_payload) = self.reconstructed_payload_cache.get(&exec_block_hash) {
+            return blinded_block
+                .try_into_full_block(Some(execution_payload))
+                .ok_or(Error::AddPayloadLogicError)
+                .map(Some);
+        }
+
         let execution_payload = self
             .execution_layer
             .as_ref()
@@ -1210,6 +1398,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             });
         }
 
+        self.reconstructed_payload_cache
+            .insert(execution_payload.clone());
+
         // Add the payload to the block to form a full block.
         blinded_block
             .try_into_full_block(Some(execution_payload))
@@ -1799,11 +1990,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let target;
         let current_epoch_attesting_info: Option<(Checkpoint, usize)>;
         let attester_cache_key;
+        let known_fully_verified;
         let head_timer = metrics::start_timer(&metrics::ATTESTATION_PRODUCTION_HEAD_SCRAPE_SECONDS);
         // The following braces are to prevent the `cached_head` Arc from being held for longer than
         // required. It also helps reduce the diff for a very large PR (#3244).
         {
-            let head = self.head_snapshot();
+            let cached_head = self.canonical_head.cached_head();
+            let head = &cached_head.snapshot;
             let head_state = &head.beacon_state;
             head_state_slot = head_state.slot();
 
@@ -1845,6 +2038,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 beacon_state_root = *head_state.get_state_root(request_slot)?;
             };
 
+            // A block which was fully verified when it became the head can never become optimistic
+            // or invalid, so there's no need to contend for the fork choice lock to check it.
+            known_fully_verified = beacon_block_root == head.beacon_block_root
+                && cached_head.head_is_valid_or_irrelevant();
+
             let target_slot = request_epoch.start_slot(T::EthSpec::slots_per_epoch());
             let target_root = if head_state.slot() <= target_slot {
                 // If the state is earlier than the target slot then the target *must* be the head
@@ -1882,20 +2080,22 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         drop(head_timer);
 
         // Only attest to a block if it is fully verified (i.e. not optimistic or invalid).
-        match self
-            .canonical_head
-            .fork_choice_read_lock()
-            .get_block_execution_status(&beacon_block_root)
-        {
-            Some(execution_status) if execution_status.is_valid_or_irrelevant() => (),
-            Some(execution_status) => {
-                return Err(Error::HeadBlockNotFullyVerified {
-                    beacon_block_root,
-                    execution_status,
-                })
-            }
-            None => return Err(Error::HeadMissingFromForkChoice(beacon_block_root)),
-        };
+        if !known_fully_verified {
+            match self
+                .canonical_head
+                .fork_choice_read_lock()
+                .get_block_execution_status(&beacon_block_root)
+            {
+                Some(execution_status) if execution_status.is_valid_or_irrelevant() => (),
+                Some(execution_status) => {
+                    return Err(Error::HeadBlockNotFullyVerified {
+                        beacon_block_root,
+                        execution_status,
+                    })
+                }
+                None => return Err(Error::HeadMissingFromForkChoice(beacon_block_root)),
+            };
+        }
 
         /*
          *  Phase 2/2:
@@ -1968,7 +2168,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     where
         I: Iterator<Item = (&'a Attestation<T::EthSpec>, Option<SubnetId>)> + ExactSizeIterator,
     {
-        batch_verify_unaggregated_attestations(attestations, self)
+        let results = batch_verify_unaggregated_attestations(attestations, self)?;
+        for verified in results.iter().flatten() {
+            self.record_gossip_attestation(verified);
+        }
+        Ok(results)
     }
 
     /// Accepts some `Attestation` from the network and attempts to verify it, returning `Ok(_)` if
@@ -1996,6 +2200,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     }
                 }
                 metrics::inc_counter(&metrics::UNAGGREGATED_ATTESTATION_PROCESSING_SUCCESSES);
+                self.record_gossip_attestation(&v);
                 v
             },
         )
@@ -2011,7 +2216,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     where
         I: Iterator<Item = &'a SignedAggregateAndProof<T::EthSpec>> + ExactSizeIterator,
     {
-        batch_verify_aggregated_attestations(aggregates, self)
+        let results = batch_verify_aggregated_attestations(aggregates, self)?;
+        for verified in results.iter().flatten() {
+            self.record_gossip_aggregate(verified);
+        }
+        Ok(results)
     }
 
     /// Accepts some `SignedAggregateAndProof` from the network and attempts to verify it,
@@ -2034,6 +2243,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 }
             }
             metrics::inc_counter(&metrics::AGGREGATED_ATTESTATION_PROCESSING_SUCCESSES);
+            self.record_gossip_aggregate(&v);
             v
         })
     }
@@ -2054,6 +2264,31 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Performs the same validation as `Self::verify_sync_committee_message_for_gossip`, but for
+    /// multiple messages using batch BLS verification.
+    ///
+    /// The results are returned in the same order as the `messages` iterator.
+    pub fn batch_verify_sync_committee_messages_for_gossip<I>(
+        &self,
+        messages: I,
+    ) -> Result<Vec<Result<VerifiedSyncCommitteeMessage, SyncCommitteeError>>, SyncCommitteeError>
+    where
+        I: Iterator<Item = (SyncCommitteeMessage, SyncSubnetId)> + ExactSizeIterator,
+    {
+        metrics::inc_counter_by(
+            &metrics::SYNC_MESSAGE_PROCESSING_REQUESTS,
+            messages.len() as u64,
+        );
+        let _timer = metrics::start_timer(&metrics::SYNC_MESSAGE_GOSSIP_VERIFICATION_TIMES);
+
+        let results = batch_verify_sync_committee_messages(messages, self)?;
+        metrics::inc_counter_by(
+            &metrics::SYNC_MESSAGE_PROCESSING_SUCCESSES,
+            results.iter().flatten().count() as u64,
+        );
+        Ok(results)
+    }
+
     /// Accepts some `SignedContributionAndProof` from the network and attempts to verify it,
     /// returning `Ok(_)` if it is valid to be (re)broadcast on the gossip network.
     pub fn verify_sync_contribution_for_gossip(
@@ -2114,6 +2349,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let _timer = metrics::start_timer(&metrics::BLOBS_SIDECAR_GOSSIP_VERIFICATION_TIMES);
         GossipVerifiedBlob::new(blob_sidecar, subnet_id, self).map(|v| {
             metrics::inc_counter(&metrics::BLOBS_SIDECAR_PROCESSING_SUCCESSES);
+            self.record_gossip_blob(&v, subnet_id);
             v
         })
     }
@@ -2489,6 +2725,104 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Handle a proposer which has been observed signing two distinct blocks for the same slot.
+    ///
+    /// If the header of the previously observed block is still available a proposer slashing is
+    /// constructed and queued for inclusion in a block. Subscribers to the `block_equivocation`
+    /// event are notified regardless.
+    pub fn process_block_equivocation(
+        &self,
+        signed_header: SignedBeaconBlockHeader,
+        block_root: Hash256,
+        previous_block_root: Hash256,
+    ) {
+        metrics::inc_counter(&metrics::BLOCK_EQUIVOCATIONS);
+
+        let slot = signed_header.message.slot;
+        let proposer_index = signed_header.message.proposer_index;
+        warn!(
+            self.log,
+            "Block proposer equivocation observed";
+            "slot" => slot,
+            "proposer_index" => proposer_index,
+            "previous_block_root" => ?previous_block_root,
+            "block_root" => ?block_root,
+        );
+
+        let previous_header = match self.get_block_process_status(&previous_block_root) {
+            BlockProcessStatus::NotValidated(block)
+            | BlockProcessStatus::ExecutionValidated(block) => Some(block.signed_block_header()),
+            BlockProcessStatus::Unknown => match self.get_blinded_block(&previous_block_root) {
+                Ok(block) => block.map(|block| block.signed_block_header()),
+                Err(e) => {
+                    error!(
+                        self.log,
+                        "Unable to load equivocating block";
+                        "block_root" => ?previous_block_root,
+                        "error" => ?e,
+                    );
+                    None
+                }
+            },
+        };
+
+        let proposer_slashing = previous_header.map(|previous_header| ProposerSlashing {
+            signed_header_1: previous_header,
+            signed_header_2: signed_header,
+        });
+
+        match proposer_slashing
+            .clone()
+            .map(|slashing| self.verify_proposer_slashing_for_gossip(slashing))
+        {
+            Some(Ok(ObservationOutcome::New(verified_slashing))) => {
+                info!(
+                    self.log,
+                    "Importing proposer slashing for equivocation";
+                    "slot" => slot,
+                    "proposer_index" => proposer_index,
+                );
+                self.import_proposer_slashing(verified_slashing);
+            }
+            Some(Ok(ObservationOutcome::AlreadyKnown)) => {
+                debug!(
+                    self.log,
+                    "Proposer slashing for equivocation already known";
+                    "proposer_index" => proposer_index,
+                );
+            }
+            Some(Err(e)) => {
+                debug!(
+                    self.log,
+                    "Unable to verify proposer slashing for equivocation";
+                    "proposer_index" => proposer_index,
+                    "error" => ?e,
+                );
+            }
+            None => {
+                debug!(
+                    self.log,
+                    "Previous block unavailable for proposer slashing";
+                    "previous_block_root" => ?previous_block_root,
+                );
+            }
+        }
+
+        if let Some(event_handler) = self.event_handler.as_ref() {
+            if event_handler.has_block_equivocation_subscribers() {
+                event_handler.register(EventKind::BlockEquivocation(Box::new(
+                    SseBlockEquivocation {
+                        slot,
+                        proposer_index,
+                        previous_block_root,
+                        block_root,
+                        proposer_slashing,
+                    },
+                )));
+            }
+        }
+    }
+
     /// Verify an attester slashing before allowing it to propagate on the gossip network.
     pub fn verify_attester_slashing_for_gossip(
         &self,
@@ -2898,6 +3232,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                                 "root" => ?verified.block_root(),
                                 "commitments" => commitments_formatted,
                             );
+                            chain.record_gossip_block(&verified);
 
                             Ok(verified)
                         }
@@ -2947,7 +3282,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
-        let r = self.check_gossip_blob_availability_and_import(blob).await;
+        let span = info_span!(
+            target: logging::IMPORT_SPAN_TARGET,
+            "process_gossip_blob",
+            slot = blob.slot().as_u64(),
+            block_root = ?block_root,
+            index = blob.index(),
+        );
+        let r = self
+            .check_gossip_blob_availability_and_import(blob)
+            .instrument(span)
+            .await;
         self.remove_notified(&block_root, r)
     }
 
@@ -3117,11 +3462,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // A small closure to group the verification and import errors.
         let chain = self.clone();
         let import_block = async move {
-            let execution_pending = unverified_block.into_execution_pending_block(
-                block_root,
-                &chain,
-                notify_execution_layer,
-            )?;
+            let execution_pending =
+                info_span!(target: logging::IMPORT_SPAN_TARGET, "consensus_verification")
+                    .in_scope(|| {
+                        unverified_block.into_execution_pending_block(
+                            block_root,
+                            &chain,
+                            notify_execution_layer,
+                        )
+                    })?;
             publish_fn()?;
 
             // Record the time it took to complete consensus verification.
@@ -3131,7 +3480,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     .set_time_consensus_verified(block_root, block_slot, timestamp)
             }
 
-            let executed_block = chain.into_executed_block(execution_pending).await?;
+            let executed_block = chain
+                .into_executed_block(execution_pending)
+                .instrument(
+                    info_span!(target: logging::IMPORT_SPAN_TARGET, "execution_verification"),
+                )
+                .await?;
 
             // Record the *additional* time it took to wait for execution layer verification.
             if let Some(timestamp) = self.slot_clock.now_duration() {
@@ -3151,7 +3505,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         };
 
         // Verify and import the block.
-        match import_block.await {
+        let span = info_span!(
+            target: logging::IMPORT_SPAN_TARGET,
+            "process_block",
+            slot = block_slot.as_u64(),
+            block_root = ?block_root,
+            source = %block_source,
+        );
+        match import_block.instrument(span).await {
             // The block was successfully verified and imported. Yay.
             Ok(status @ AvailabilityProcessingStatus::Imported(block_root)) => {
                 debug!(
@@ -3271,9 +3632,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         block: AvailabilityPendingExecutedBlock<T::EthSpec>,
     ) -> Result<AvailabilityProcessingStatus, BlockError<T::EthSpec>> {
         let slot = block.block.slot();
-        let availability = self
-            .data_availability_checker
-            .put_pending_executed_block(block)?;
+        let availability = info_span!(target: logging::IMPORT_SPAN_TARGET, "availability_check")
+            .in_scope(|| {
+                self.data_availability_checker
+                    .put_pending_executed_block(block)
+            })?;
         self.process_availability(slot, availability).await
     }
 
@@ -3287,7 +3650,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         if let Some(slasher) = self.slasher.as_ref() {
             slasher.accept_block_header(blob.signed_block_header());
         }
-        let availability = self.data_availability_checker.put_gossip_blob(blob)?;
+        let availability = info_span!(target: logging::IMPORT_SPAN_TARGET, "availability_check")
+            .in_scope(|| self.data_availability_checker.put_gossip_blob(blob))?;
 
         self.process_availability(slot, availability).await
     }
@@ -3406,9 +3770,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // import
         let chain = self.clone();
+        let span = info_span!(target: logging::IMPORT_SPAN_TARGET, "import_block");
         let block_root = self
             .spawn_blocking_handle(
                 move || {
+                    let _span = span.entered();
                     chain.import_block(
                         block,
                         block_root,
@@ -3514,6 +3880,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // Register the new block with the fork choice service.
         {
+            let _span = info_span!(target: logging::IMPORT_SPAN_TARGET, "fork_choice").entered();
             let _fork_choice_block_timer =
                 metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_BLOCK_TIMES);
             let block_delay = self
@@ -3613,6 +3980,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         );
         self.import_block_update_validator_monitor(
             block,
+            block_root,
             &state,
             &mut consensus_context,
             current_slot,
@@ -3650,7 +4018,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let txn_lock = self.store.hot_db.begin_rw_transaction();
 
-        if let Err(e) = self.store.do_atomically_with_block_and_blobs_cache(ops) {
+        let write_result = info_span!(target: logging::IMPORT_SPAN_TARGET, "store_write")
+            .in_scope(|| self.store.do_atomically_with_block_and_blobs_cache(ops));
+        if let Err(e) = write_result {
             error!(
                 self.log,
                 "Database write failed!";
@@ -3658,9 +4028,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "error" => ?e,
             );
 
-            // Clear the early attester cache to prevent attestations which we would later be unable
-            // to verify due to the failure.
-            self.early_attester_cache.clear();
+            // Remove the block from the early attester cache to prevent attestations which we
+            // would later be unable to verify due to the failure.
+            self.early_attester_cache.remove_block(block_root);
 
             // Since the write failed, try to revert the canonical head back to what was stored
             // in the database. This attempts to prevent inconsistency between the database and
@@ -3816,6 +4186,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     fn import_block_update_validator_monitor(
         &self,
         block: BeaconBlockRef<T::EthSpec>,
+        block_root: Hash256,
         state: &BeaconState<T::EthSpec>,
         ctxt: &mut ConsensusContext<T::EthSpec>,
         current_slot: Slot,
@@ -3872,7 +4243,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         // Attestations.
-        for attestation in block.body().attestations() {
+        for (attestation_index, attestation) in block.body().attestations().enumerate() {
             let indexed_attestation = match ctxt.get_indexed_attestation(state, attestation) {
                 Ok(indexed) => indexed,
                 Err(e) => {
@@ -3888,7 +4259,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             };
             validator_monitor.register_attestation_in_block(
                 indexed_attestation,
+                attestation,
+                attestation_index,
+                block_root,
+                block.slot(),
                 parent_block_slot,
+                state,
                 &self.spec,
             );
         }
@@ -4337,6 +4713,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let re_org_head_threshold = self.config.re_org_head_threshold?;
         let re_org_parent_threshold = self.config.re_org_parent_threshold?;
 
+        // Never re-org whilst the chain is degraded.
+        if self.chain_health.is_degraded() {
+            return None;
+        }
+
         if self.spec.proposer_score_boost.is_none() {
             warn!(
                 self.log,
@@ -4649,6 +5030,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .re_org_parent_threshold
             .ok_or(DoNotReOrg::ReOrgsDisabled)?;
 
+        // Never override whilst the chain is degraded.
+        if self.chain_health.is_degraded() {
+            return Err(DoNotReOrg::ChainHealthDegraded.into());
+        }
+
         let head_block_root = canonical_forkchoice_params.head_root;
 
         // Perform initial checks and load the relevant info from fork choice.
@@ -6575,6 +6961,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Ok(ChainHealth::Healthy);
         }
 
+        if self.chain_health.is_degraded() {
+            return Ok(ChainHealth::Unhealthy(FailedCondition::ChainHealthDegraded));
+        }
+
         let current_slot = self.slot()?;
 
         // Check slots at the head of the chain.
@@ -6809,6 +7199,7 @@ impl<T: BeaconChainTypes> Drop for BeaconChain<T> {
         let drop = || -> Result<(), Error> {
             self.persist_head_and_fork_choice()?;
             self.persist_op_pool()?;
+            self.persist_observed_caches()?;
             self.persist_eth1_cache()
         };
 
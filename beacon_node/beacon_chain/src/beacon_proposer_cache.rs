@@ -7,6 +7,10 @@
 //! The cache is a fairly unintelligent LRU cache that is not pruned after finality. This makes it
 //! very simple to reason about, but it might store values that are useless due to finalization. The
 //! values it stores are very small, so this should not be an issue.
+//!
+//! Proposers for the epoch following the head are stored separately as a single "lookahead" entry
+//! keyed by the head block root. These values change with every new head, so storing them in the
+//! LRU cache would wash out the values required for block verification.
 
 use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
 use fork_choice::ExecutionStatus;
@@ -54,12 +58,15 @@ pub struct EpochBlockProposers {
 /// See the module-level documentation for more information.
 pub struct BeaconProposerCache {
     cache: LruCache<(Epoch, Hash256), EpochBlockProposers>,
+    /// The proposers for an epoch later than the head, keyed by `(epoch, head_block_root)`.
+    lookahead: Option<((Epoch, Hash256), EpochBlockProposers)>,
 }
 
 impl Default for BeaconProposerCache {
     fn default() -> Self {
         Self {
             cache: LruCache::new(CACHE_SIZE),
+            lookahead: None,
         }
     }
 }
@@ -132,6 +139,55 @@ impl BeaconProposerCache {
 
         Ok(())
     }
+
+    /// Returns the proposers for `epoch` if they were computed from the head block with root
+    /// `head_block_root`.
+    ///
+    /// The head block must be from an epoch prior to `epoch`, which makes `head_block_root` the
+    /// shuffling decision block for `epoch` (assuming no further blocks are imported).
+    pub fn get_lookahead(
+        &self,
+        head_block_root: Hash256,
+        epoch: Epoch,
+    ) -> Option<&SmallVec<[usize; TYPICAL_SLOTS_PER_EPOCH]>> {
+        self.lookahead
+            .as_ref()
+            .filter(|(key, _)| *key == (epoch, head_block_root))
+            .map(|(_, cache)| &cache.proposers)
+    }
+
+    /// Drop the lookahead proposers unless they were computed from the block with root
+    /// `head_block_root`.
+    pub fn retain_lookahead(&mut self, head_block_root: Hash256) {
+        if self
+            .lookahead
+            .as_ref()
+            .is_some_and(|((_, root), _)| *root != head_block_root)
+        {
+            self.lookahead = None;
+        }
+    }
+
+    /// Replace the lookahead proposers with those for `epoch`, as computed from the head block
+    /// with root `head_block_root`.
+    ///
+    /// See `Self::get_lookahead` for the requirements on `head_block_root`.
+    pub fn insert_lookahead(
+        &mut self,
+        epoch: Epoch,
+        head_block_root: Hash256,
+        proposers: Vec<usize>,
+        fork: Fork,
+    ) {
+        self.lookahead = Some((
+            (epoch, head_block_root),
+            EpochBlockProposers {
+                epoch,
+                fork,
+                proposers: proposers.into(),
+            },
+        ));
+    }
 }
 
 /// Compute the proposer duties using the head state without cache.
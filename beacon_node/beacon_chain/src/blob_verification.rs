@@ -1,4 +1,6 @@
 use derivative::Derivative;
+use eth2::lighthouse::SseBlobSidecarEquivocation;
+use eth2::types::EventKind;
 use slot_clock::SlotClock;
 use std::sync::Arc;
 
@@ -8,6 +10,7 @@ use crate::block_verification::{
     BlockSlashInfo,
 };
 use crate::kzg_utils::{validate_blob, validate_blobs};
+use crate::observed_blob_sidecars::SeenBlobSidecar;
 use crate::{metrics, BeaconChainError};
 use kzg::{Error as KzgError, Kzg, KzgCommitment};
 use slog::debug;
@@ -115,6 +118,21 @@ pub enum GossipBlobError<E: EthSpec> {
         index: u64,
     },
 
+    /// A blob has already been seen for the given `(sidecar.slot, sidecar.proposer, sidecar.index)`
+    /// tuple with a different block root. The proposer has signed conflicting block headers.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The proposer is slashable. Honest peers shouldn't forward more than one sidecar per index
+    /// from the same proposer, so we penalize the peer.
+    BlobEquivocation {
+        proposer: u64,
+        slot: Slot,
+        index: u64,
+        block_root: Hash256,
+        previous_block_root: Hash256,
+    },
+
     /// `Kzg` struct hasn't been initialized. This is an internal error.
     ///
     /// ## Peer scoring
@@ -441,6 +459,9 @@ pub fn validate_blob_sidecar_for_gossip<T: BeaconChainTypes>(
 
     // Verify that this is the first blob sidecar received for the tuple:
     // (block_header.slot, block_header.proposer_index, blob_sidecar.index)
+    //
+    // A sidecar conflicting with one we've already seen is not considered known here, it
+    // continues through verification so that the equivocation can be detected below.
     if chain
         .observed_blob_sidecars
         .read()
@@ -594,17 +615,49 @@ pub fn validate_blob_sidecar_for_gossip<T: BeaconChainTypes>(
     // retrieval of potentially valid blocks over rpc, but try to punish the proposer for signing
     // invalid messages. Issue for more background
     // https://github.com/ethereum/consensus-specs/issues/3261
-    if chain
+    //
+    // A conflicting sidecar for an index we've already observed is only allowed this far so that
+    // its signature can be checked. Having verified it, we record the equivocation, notify event
+    // subscribers and reject it. The signed header is passed to the slasher by the caller.
+    match chain
         .observed_blob_sidecars
         .write()
         .observe_sidecar(&blob_sidecar)
         .map_err(|e| GossipBlobError::BeaconChainError(e.into()))?
     {
-        return Err(GossipBlobError::RepeatBlob {
-            proposer: proposer_index as u64,
-            slot: blob_slot,
-            index: blob_index,
-        });
+        SeenBlobSidecar::Unique => {}
+        SeenBlobSidecar::Duplicate => {
+            return Err(GossipBlobError::RepeatBlob {
+                proposer: proposer_index as u64,
+                slot: blob_slot,
+                index: blob_index,
+            });
+        }
+        SeenBlobSidecar::Equivocation {
+            previous_block_root,
+        } => {
+            metrics::inc_counter(&metrics::BLOBS_SIDECAR_GOSSIP_EQUIVOCATIONS);
+            if let Some(event_handler) = chain.event_handler.as_ref() {
+                if event_handler.has_blob_sidecar_equivocation_subscribers() {
+                    event_handler.register(EventKind::BlobSidecarEquivocation(
+                        SseBlobSidecarEquivocation {
+                            slot: blob_slot,
+                            proposer_index: proposer_index as u64,
+                            index: blob_index,
+                            previous_block_root,
+                            block_root,
+                        },
+                    ));
+                }
+            }
+            return Err(GossipBlobError::BlobEquivocation {
+                proposer: proposer_index as u64,
+                slot: blob_slot,
+                index: blob_index,
+                block_root,
+                previous_block_root,
+            });
+        }
     }
 
     Ok(GossipVerifiedBlob {
@@ -526,6 +526,8 @@ impl<E: EthSpec> BlockSlashInfo<GossipBlobError<E>> {
     pub fn from_early_error_blob(header: SignedBeaconBlockHeader, e: GossipBlobError<E>) -> Self {
         match e {
             GossipBlobError::ProposalSignatureInvalid => BlockSlashInfo::SignatureInvalid(e),
+            // Equivocations are only detected after the proposer signature has been verified.
+            GossipBlobError::BlobEquivocation { .. } => BlockSlashInfo::SignatureValid(header, e),
             // `InvalidSignature` could indicate any signature in the block, so we want
             // to recheck the proposer signature alone.
             _ => BlockSlashInfo::SignatureNotChecked(header, e),
@@ -975,13 +977,23 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         //
         // It's important to double-check that the proposer still hasn't been observed so we don't
         // have a race-condition when verifying two blocks simultaneously.
-        match chain
+        let (seen_block, newly_observed) = chain
             .observed_block_producers
             .write()
-            .observe_proposal(block_root, block.message())
-            .map_err(|e| BlockError::BeaconChainError(e.into()))?
-        {
-            SeenBlock::Slashable => {
+            .observe_proposal_root(block_root, block.message())
+            .map_err(|e| BlockError::BeaconChainError(e.into()))?;
+        match seen_block {
+            SeenBlock::Slashable {
+                previous_block_root,
+            } => {
+                // Only handle each equivocating block once.
+                if newly_observed {
+                    chain.process_block_equivocation(
+                        block_header.clone(),
+                        block_root,
+                        previous_block_root,
+                    );
+                }
                 return Err(BlockError::Slashable);
             }
             SeenBlock::Duplicate => return Err(BlockError::BlockIsAlreadyKnown(block_root)),
@@ -1297,11 +1309,26 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
             .observe_slashable(block.slot(), block.message().proposer_index(), block_root)
             .map_err(|e| BlockError::BeaconChainError(e.into()))?;
 
-        chain
+        // A block which passed gossip verification has already been observed, in which case any
+        // equivocation has already been handled.
+        let (seen_block, newly_observed) = chain
             .observed_block_producers
             .write()
-            .observe_proposal(block_root, block.message())
+            .observe_proposal_root(block_root, block.message())
             .map_err(|e| BlockError::BeaconChainError(e.into()))?;
+        if let (
+            SeenBlock::Slashable {
+                previous_block_root,
+            },
+            true,
+        ) = (seen_block, newly_observed)
+        {
+            chain.process_block_equivocation(
+                block.as_block().signed_block_header(),
+                block_root,
+                previous_block_root,
+            );
+        }
 
         if let Some(parent) = chain
             .canonical_head
@@ -1629,7 +1656,7 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
 
         let state_root_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_STATE_ROOT);
 
-        let state_root = state.update_tree_hash_cache()?;
+        let state_root = chain.state_root_pool.update_tree_hash_cache(&mut state)?;
 
         metrics::stop_timer(state_root_timer);
 
@@ -1,18 +1,25 @@
 use crate::beacon_chain::{
-    CanonicalHead, LightClientProducerEvent, BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, OP_POOL_DB_KEY,
+    CanonicalHead, LightClientProducerEvent, BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY,
+    OBSERVED_CACHES_DB_KEY, OP_POOL_DB_KEY,
 };
 use crate::beacon_proposer_cache::BeaconProposerCache;
+use crate::blob_verification::verify_kzg_for_blob_list;
 use crate::data_availability_checker::DataAvailabilityChecker;
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::eth1_finalization_cache::Eth1FinalizationCache;
 use crate::fork_choice_signal::ForkChoiceSignalTx;
 use crate::fork_revert::{reset_fork_choice_to_finalization, revert_to_fork_boundary};
+use crate::gossip_recorder::GossipRecorder;
 use crate::graffiti_calculator::{GraffitiCalculator, GraffitiOrigin};
 use crate::head_tracker::HeadTracker;
 use crate::light_client_server_cache::LightClientServerCache;
 use crate::migrate::{BackgroundMigrator, MigratorConfig};
+use crate::observed_attesters::ObservedAttesters;
+use crate::observed_block_producers::ObservedBlockProducers;
 use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::persisted_observed_caches::PersistedObservedCaches;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
+use crate::state_root_pool::StateRootPool;
 use crate::validator_monitor::{ValidatorMonitor, ValidatorMonitorConfig};
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::ChainConfig;
@@ -29,7 +36,7 @@ use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::{Mutex, RwLock};
 use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use slasher::Slasher;
-use slog::{crit, debug, error, info, o, Logger};
+use slog::{crit, debug, error, info, o, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use state_processing::{per_slot_processing, AllCaches};
 use std::marker::PhantomData;
@@ -84,6 +91,7 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
         ForkChoice<BeaconForkChoiceStore<T::EthSpec, T::HotStore, T::ColdStore>, T::EthSpec>,
     >,
     op_pool: Option<OperationPool<T::EthSpec>>,
+    observed_caches: Option<PersistedObservedCaches>,
     eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec>>,
     execution_layer: Option<ExecutionLayer<T::EthSpec>>,
     event_handler: Option<ServerSentEventHandler<T::EthSpec>>,
@@ -127,6 +135,7 @@ where
             genesis_state_root: None,
             fork_choice: None,
             op_pool: None,
+            observed_caches: None,
             eth1_chain: None,
             execution_layer: None,
             event_handler: None,
@@ -316,6 +325,10 @@ where
                 .unwrap_or_else(OperationPool::new),
         );
 
+        self.observed_caches = store
+            .get_item::<PersistedObservedCaches>(&OBSERVED_CACHES_DB_KEY)
+            .map_err(|e| format!("DB error whilst reading persisted observed caches: {:?}", e))?;
+
         let pubkey_cache = ValidatorPubkeyCache::load_from_store(store)
             .map_err(|e| format!("Unable to open persisted pubkey cache: {:?}", e))?;
 
@@ -504,15 +517,50 @@ where
                     blobs.len()
                 ));
             }
-            if commitments
-                .iter()
-                .zip(blobs.iter())
-                .any(|(commitment, blob)| *commitment != blob.kzg_commitment)
-            {
-                return Err("Checkpoint blob does not match block commitment".into());
+            for (index, (commitment, blob)) in commitments.iter().zip(blobs.iter()).enumerate() {
+                if blob.index != index as u64 {
+                    return Err(format!(
+                        "Checkpoint blob has wrong index, expected: {index}, got: {}",
+                        blob.index
+                    ));
+                }
+                if *commitment != blob.kzg_commitment {
+                    return Err("Checkpoint blob does not match block commitment".into());
+                }
+                if blob.block_root() != weak_subj_block_root {
+                    return Err(format!(
+                        "Checkpoint blob {index} is for a different block: {:?}",
+                        blob.block_root()
+                    ));
+                }
+                if !blob.verify_blob_sidecar_inclusion_proof() {
+                    return Err(format!(
+                        "Checkpoint blob {index} has an invalid inclusion proof"
+                    ));
+                }
             }
+            let kzg = self
+                .kzg
+                .as_ref()
+                .ok_or("KZG must be configured to verify checkpoint blobs")?;
+            verify_kzg_for_blob_list(blobs.iter(), kzg)
+                .map_err(|e| format!("Checkpoint blobs failed KZG verification: {e:?}"))?;
         }
 
+        // Blobs for the checkpoint block are only available if they were provided and verified.
+        let oldest_blob_slot =
+            if weak_subj_blobs.is_none() && weak_subj_block.message().body().has_blobs() {
+                warn!(
+                    log,
+                    "Checkpoint blobs not available";
+                    "block_root" => ?weak_subj_block_root,
+                    "impact" => "blobs for the checkpoint block will not be served",
+                );
+                weak_subj_block.slot() + 1
+            } else {
+                weak_subj_block.slot()
+            };
+
         // Set the store's split point *before* storing genesis so that genesis is stored
         // immediately in the freezer DB.
         store.set_split(weak_subj_slot, weak_subj_state_root, weak_subj_block_root);
@@ -567,7 +615,7 @@ where
         );
         self.pending_io_batch.push(
             store
-                .init_blob_info(weak_subj_block.slot())
+                .init_blob_info(oldest_blob_slot)
                 .map_err(|e| format!("Failed to initialize blob info: {:?}", e))?,
         );
 
@@ -802,10 +850,48 @@ where
             ));
         }
 
-        let validator_pubkey_cache = self.validator_pubkey_cache.map(Ok).unwrap_or_else(|| {
-            ValidatorPubkeyCache::new(&head_snapshot.beacon_state, store.clone())
-                .map_err(|e| format!("Unable to init validator pubkey cache: {:?}", e))
-        })?;
+        let mut validator_pubkey_cache =
+            self.validator_pubkey_cache.map(Ok).unwrap_or_else(|| {
+                ValidatorPubkeyCache::new(&head_snapshot.beacon_state, store.clone())
+                    .map_err(|e| format!("Unable to init validator pubkey cache: {:?}", e))
+            })?;
+
+        // The persisted cache should contain every key in the head state, since keys are written
+        // atomically with the blocks which introduce them. Import any which are missing anyway, so
+        // that the node doesn't fall back to reading keys from states.
+        let pubkey_store_ops = validator_pubkey_cache
+            .import_new_pubkeys(&head_snapshot.beacon_state)
+            .map_err(|e| format!("Unable to update validator pubkey cache: {:?}", e))?;
+        if !pubkey_store_ops.is_empty() {
+            info!(
+                log,
+                "Adding missing keys to validator pubkey cache";
+                "count" => pubkey_store_ops.len(),
+            );
+            store
+                .do_atomically_with_block_and_blobs_cache(pubkey_store_ops)
+                .map_err(|e| format!("Unable to persist validator pubkey cache: {:?}", e))?;
+        }
+
+        // Restore the observed caches persisted on the last shutdown, if any. Stale epochs and
+        // slots are pruned as new messages are observed.
+        let (
+            observed_block_producers,
+            observed_gossip_attesters,
+            observed_block_attesters,
+            observed_aggregators,
+        ) = self
+            .observed_caches
+            .as_ref()
+            .map(|persisted| {
+                (
+                    ObservedBlockProducers::from_ssz_container(&persisted.block_producers),
+                    ObservedAttesters::from_ssz_container(&persisted.gossip_attesters),
+                    ObservedAttesters::from_ssz_container(&persisted.block_attesters),
+                    ObservedAttesters::from_ssz_container(&persisted.aggregators),
+                )
+            })
+            .unwrap_or_default();
 
         let migrator_config = self.store_migrator_config.unwrap_or_default();
         let store_migrator = BackgroundMigrator::new(
@@ -859,6 +945,10 @@ where
         let genesis_time = head_snapshot.beacon_state.genesis_time();
         let canonical_head = CanonicalHead::new(fork_choice, Arc::new(head_snapshot));
         let shuffling_cache_size = self.chain_config.shuffling_cache_size;
+        let state_root_pool = StateRootPool::new(
+            self.chain_config.state_root_pool_threads,
+            self.chain_config.state_root_pool_max_queued,
+        )?;
 
         // Calculate the weak subjectivity point in which to backfill blocks to.
         let genesis_backfill_slot = if self.chain_config.genesis_backfill {
@@ -887,6 +977,21 @@ where
             }
         };
 
+        let gossip_recorder = self
+            .chain_config
+            .gossip_record_path
+            .as_deref()
+            .map(|path| {
+                let executor = self
+                    .task_executor
+                    .as_ref()
+                    .ok_or("Cannot record gossip without task executor")?;
+                GossipRecorder::create(path, executor, log.clone())
+            })
+            .transpose()?;
+
+        let graffiti_watermark = self.chain_config.graffiti_watermark;
+
         let beacon_chain = BeaconChain {
             spec: self.spec.clone(),
             config: self.chain_config,
@@ -905,18 +1010,14 @@ where
             observed_attestations: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
             observed_sync_contributions: <_>::default(),
-            // TODO: allow for persisting and loading the pool from disk.
-            observed_gossip_attesters: <_>::default(),
-            // TODO: allow for persisting and loading the pool from disk.
-            observed_block_attesters: <_>::default(),
+            observed_gossip_attesters: RwLock::new(observed_gossip_attesters),
+            observed_block_attesters: RwLock::new(observed_block_attesters),
             // TODO: allow for persisting and loading the pool from disk.
             observed_sync_contributors: <_>::default(),
-            // TODO: allow for persisting and loading the pool from disk.
-            observed_aggregators: <_>::default(),
+            observed_aggregators: RwLock::new(observed_aggregators),
             // TODO: allow for persisting and loading the pool from disk.
             observed_sync_aggregators: <_>::default(),
-            // TODO: allow for persisting and loading the pool from disk.
-            observed_block_producers: <_>::default(),
+            observed_block_producers: RwLock::new(observed_block_producers),
             observed_blob_sidecars: <_>::default(),
             observed_slashable: <_>::default(),
             observed_voluntary_exits: <_>::default(),
@@ -946,7 +1047,13 @@ where
             validator_pubkey_cache: RwLock::new(validator_pubkey_cache),
             attester_cache: <_>::default(),
             early_attester_cache: <_>::default(),
+            chain_health: <_>::default(),
+            non_finality: <_>::default(),
+            gossip_arrival_times: <_>::default(),
+            gossip_recorder,
+            state_root_pool,
             reqresp_pre_import_cache: <_>::default(),
+            reconstructed_payload_cache: <_>::default(),
             light_client_server_cache: LightClientServerCache::new(),
             light_client_server_tx: self.light_client_server_tx,
             shutdown_sender: self
@@ -955,6 +1062,7 @@ where
             log: log.clone(),
             graffiti_calculator: GraffitiCalculator::new(
                 self.beacon_graffiti,
+                graffiti_watermark,
                 self.execution_layer,
                 slot_clock.slot_duration() * E::slots_per_epoch() as u32,
                 log.clone(),
@@ -8,28 +8,31 @@
 //!
 //! ## Deadlock safety
 //!
-//! This module contains three locks:
+//! This module contains two locks:
 //!
 //! 1. `RwLock<BeaconForkChoice>`: Contains `proto_array` fork choice.
-//! 2. `RwLock<CachedHead>`: Contains a cached block/state from the last run of `proto_array`.
-//! 3. `Mutex<()>`: Is used to prevent concurrent execution of `BeaconChain::recompute_head`.
+//! 2. `Mutex<()>`: Is used to prevent concurrent execution of `BeaconChain::recompute_head`.
 //!
-//! This module has to take great efforts to avoid causing a deadlock with these three methods. Any
+//! This module has to take great efforts to avoid causing a deadlock with these two locks. Any
 //! developers working in this module should tread carefully and seek a detailed review.
 //!
 //! To encourage safe use of this module, it should **only ever return a read or write lock for the
-//! fork choice lock (lock 1)**. Whilst public functions might indirectly utilise locks (2) and (3),
-//! the fundamental `RwLockWriteGuard` or `RwLockReadGuard` should never be exposed. This prevents
-//! external functions from acquiring these locks in conflicting orders and causing a deadlock.
+//! fork choice lock (lock 1)**. Whilst public functions might indirectly utilise lock (2), the
+//! fundamental guard should never be exposed. This prevents external functions from acquiring these
+//! locks in conflicting orders and causing a deadlock.
 //!
 //! ## Design Considerations
 //!
-//! We separate the `BeaconForkChoice` and `CachedHead` into two `RwLocks` because we want to ensure
-//! fast access to the `CachedHead`. If we were to put them both under the same lock, we would need
-//! to take an exclusive write-lock on it in order to run `ForkChoice::get_head`. This can take tens
-//! of milliseconds and would block all downstream functions that want to know simple things like
-//! the head block root. This is unacceptable for fast-responding functions like the networking
-//! stack.
+//! We keep the `CachedHead` outside of the `BeaconForkChoice` lock because we want to ensure fast
+//! access to it. If we were to put them both under the same lock, we would need to take an
+//! exclusive write-lock on it in order to run `ForkChoice::get_head` or import a block. This can
+//! take tens of milliseconds and would block all downstream functions that want to know simple
+//! things like the head block root. This is unacceptable for fast-responding functions like the
+//! networking stack and attestation production.
+//!
+//! The `CachedHead` is published read-copy-update style: it is never mutated in place, instead
+//! `BeaconChain::recompute_head` builds a new value and atomically swaps it in. Readers load an
+//! `Arc` of the current value without taking any lock, so they never wait on a writer.
 
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::shuffling_cache::BlockShufflingIds;
@@ -41,6 +44,7 @@ use crate::{
     validator_monitor::{get_slot_delay_ms, timestamp_now},
     BeaconChain, BeaconChainError as Error, BeaconChainTypes, BeaconSnapshot,
 };
+use arc_swap::{ArcSwap, Guard};
 use eth2::types::{EventKind, SseChainReorg, SseFinalizedCheckpoint, SseHead, SseLateHead};
 use fork_choice::{
     ExecutionStatus, ForkChoiceStore, ForkChoiceView, ForkchoiceUpdateParameters, ProtoBlock,
@@ -106,6 +110,12 @@ pub struct CachedHead<E: EthSpec> {
     justified_hash: Option<ExecutionBlockHash>,
     /// The `execution_payload.block_hash` of the finalized block. Set to `None` before Bellatrix.
     finalized_hash: Option<ExecutionBlockHash>,
+    /// The execution status of the head block when it was cached, if it was known to fork choice.
+    ///
+    /// This may be stale if the status has since been updated in fork choice. However, a status
+    /// which is valid or irrelevant can never change, so it can be relied upon without consulting
+    /// fork choice.
+    head_execution_status: Option<ExecutionStatus>,
 }
 
 impl<E: EthSpec> CachedHead<E> {
@@ -212,6 +222,15 @@ impl<E: EthSpec> CachedHead<E> {
         self.justified_checkpoint
     }
 
+    /// Returns `true` if the head block was known to be fully verified (i.e. not optimistic or
+    /// invalid) when it was cached.
+    ///
+    /// A `false` value may be stale, in which case fork choice should be consulted.
+    pub fn head_is_valid_or_irrelevant(&self) -> bool {
+        self.head_execution_status
+            .map_or(false, |status| status.is_valid_or_irrelevant())
+    }
+
     /// Returns the cached values of `ForkChoice::forkchoice_update_parameters`.
     ///
     /// Useful for supplying to the execution layer.
@@ -241,7 +260,9 @@ pub struct CanonicalHead<T: BeaconChainTypes> {
     ///
     /// Although `self.fork_choice` might be slightly more advanced that this value, it is safe to
     /// consider that these values represent the "canonical head" of the beacon chain.
-    pub cached_head: CanonicalHeadRwLock<CachedHead<T::EthSpec>>,
+    ///
+    /// This value is only ever replaced whole, see the module-level documentation.
+    cached_head: ArcSwap<CachedHead<T::EthSpec>>,
     /// A lock used to prevent concurrent runs of `BeaconChain::recompute_head`.
     ///
     /// This lock **should not be made public**, it should only be used inside this module.
@@ -256,6 +277,8 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
     ) -> Self {
         let fork_choice_view = fork_choice.cached_fork_choice_view();
         let forkchoice_update_params = fork_choice.get_forkchoice_update_parameters();
+        let head_execution_status =
+            fork_choice.get_block_execution_status(&snapshot.beacon_block_root);
         let cached_head = CachedHead {
             snapshot,
             justified_checkpoint: fork_choice_view.justified_checkpoint,
@@ -263,11 +286,12 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
             head_hash: forkchoice_update_params.head_hash,
             justified_hash: forkchoice_update_params.justified_hash,
             finalized_hash: forkchoice_update_params.finalized_hash,
+            head_execution_status,
         };
 
         Self {
             fork_choice: CanonicalHeadRwLock::new(fork_choice),
-            cached_head: CanonicalHeadRwLock::new(cached_head),
+            cached_head: ArcSwap::from_pointee(cached_head),
             recompute_head_lock: Mutex::new(()),
         }
     }
@@ -308,6 +332,7 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
         };
 
         let forkchoice_update_params = fork_choice.get_forkchoice_update_parameters();
+        let head_execution_status = fork_choice.get_block_execution_status(&beacon_block_root);
         let cached_head = CachedHead {
             snapshot: Arc::new(snapshot),
             justified_checkpoint: fork_choice_view.justified_checkpoint,
@@ -315,12 +340,12 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
             head_hash: forkchoice_update_params.head_hash,
             justified_hash: forkchoice_update_params.justified_hash,
             finalized_hash: forkchoice_update_params.finalized_hash,
+            head_execution_status,
         };
 
         *fork_choice_write_lock = fork_choice;
-        // Avoid interleaving the fork choice and cached head locks.
         drop(fork_choice_write_lock);
-        *self.cached_head.write() = cached_head;
+        self.cached_head.store(Arc::new(cached_head));
 
         Ok(())
     }
@@ -356,29 +381,18 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
 
     /// Returns a clone of `self.cached_head`.
     ///
-    /// Takes a read-lock on `self.cached_head` for a short time (just long enough to clone it).
-    /// The `CachedHead` is designed to be fast-to-clone so this is preferred to passing back a
-    /// `RwLockReadGuard`, which may cause deadlock issues (see module-level documentation).
-    ///
-    /// This function is safe to be public since it does not expose any locks.
+    /// This never blocks, even whilst the head is being updated. The `CachedHead` is designed to be
+    /// fast-to-clone.
     pub fn cached_head(&self) -> CachedHead<T::EthSpec> {
-        self.cached_head_read_lock().clone()
-    }
-
-    /// Access a read-lock for the cached head.
-    ///
-    /// This function is **not safe** to be public. See the module-level documentation for more
-    /// information about protecting from deadlocks.
-    fn cached_head_read_lock(&self) -> RwLockReadGuard<CachedHead<T::EthSpec>> {
-        self.cached_head.read()
+        CachedHead::clone(&self.cached_head_guard())
     }
 
-    /// Access a write-lock for the cached head.
+    /// Load the current cached head without cloning it, for short-lived reads.
     ///
-    /// This function is **not safe** to be public. See the module-level documentation for more
-    /// information about protecting from deadlocks.
-    fn cached_head_write_lock(&self) -> RwLockWriteGuard<CachedHead<T::EthSpec>> {
-        self.cached_head.write()
+    /// The guard should not be held for long, since it prevents the memory of a replaced head from
+    /// being freed.
+    fn cached_head_guard(&self) -> Guard<Arc<CachedHead<T::EthSpec>>> {
+        self.cached_head.load()
     }
 
     /// Access a read-lock for fork choice.
@@ -423,7 +437,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// See `Self::head` for more information.
     pub fn head_beacon_block_root(&self) -> Hash256 {
         self.canonical_head
-            .cached_head_read_lock()
+            .cached_head_guard()
             .snapshot
             .beacon_block_root
     }
@@ -431,7 +445,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// Returns the slot of the highest block in the canonical chain.
     pub fn best_slot(&self) -> Slot {
         self.canonical_head
-            .cached_head_read_lock()
+            .cached_head_guard()
             .snapshot
             .beacon_block
             .slot()
@@ -441,7 +455,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// See `Self::head` for more information.
     pub fn head_snapshot(&self) -> Arc<BeaconSnapshot<T::EthSpec>> {
-        self.canonical_head.cached_head_read_lock().snapshot.clone()
+        self.canonical_head.cached_head_guard().snapshot.clone()
     }
 
     /// Returns the beacon block at the head of the canonical chain.
@@ -449,7 +463,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// See `Self::head` for more information.
     pub fn head_beacon_block(&self) -> Arc<SignedBeaconBlock<T::EthSpec>> {
         self.canonical_head
-            .cached_head_read_lock()
+            .cached_head_guard()
             .snapshot
             .beacon_block
             .clone()
@@ -629,6 +643,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "No change in canonical head";
                 "head" => ?new_view.head_block_root
             );
+
+            // The head may have been verified by the execution layer since it was cached. Refresh
+            // the cached status so readers can avoid consulting fork choice.
+            if old_cached_head.head_execution_status != Some(new_head_proto_block.execution_status)
+            {
+                let mut new_cached_head = old_cached_head;
+                new_cached_head.head_execution_status = Some(new_head_proto_block.execution_status);
+                self.canonical_head
+                    .cached_head
+                    .store(Arc::new(new_cached_head));
+            }
             return Ok(None);
         }
 
@@ -680,18 +705,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 head_hash: new_forkchoice_update_parameters.head_hash,
                 justified_hash: new_forkchoice_update_parameters.justified_hash,
                 finalized_hash: new_forkchoice_update_parameters.finalized_hash,
+                head_execution_status: Some(new_head_proto_block.execution_status),
             };
 
-            let new_head = {
-                // Now the new snapshot has been obtained, take a write-lock on the cached head so
-                // we can update it quickly.
-                let mut cached_head_write_lock = self.canonical_head.cached_head_write_lock();
-                // Enshrine the new head as the canonical cached head.
-                *cached_head_write_lock = new_cached_head;
-                // Take a clone of the cached head for later use. It is cloned whilst
-                // holding the write-lock to ensure we get exactly the head we just enshrined.
-                cached_head_write_lock.clone()
-            };
+            // Now the new snapshot has been obtained, enshrine it as the canonical cached head. We
+            // keep a clone for later use, rather than loading it again, to ensure we get exactly
+            // the head we just enshrined.
+            let new_head = new_cached_head.clone();
+            self.canonical_head
+                .cached_head
+                .store(Arc::new(new_cached_head));
 
             // Clear the early attester cache in case it conflicts with `self.canonical_head`.
             self.early_attester_cache.clear();
@@ -707,17 +730,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 head_hash: new_forkchoice_update_parameters.head_hash,
                 justified_hash: new_forkchoice_update_parameters.justified_hash,
                 finalized_hash: new_forkchoice_update_parameters.finalized_hash,
+                head_execution_status: Some(new_head_proto_block.execution_status),
             };
 
-            let mut cached_head_write_lock = self.canonical_head.cached_head_write_lock();
-
             // Enshrine the new head as the canonical cached head. Whilst the head block hasn't
-            // changed, the FFG checkpoints must have changed.
-            *cached_head_write_lock = new_cached_head;
-
-            // Take a clone of the cached head for later use. It is cloned whilst
-            // holding the write-lock to ensure we get exactly the head we just enshrined.
-            cached_head_write_lock.clone()
+            // changed, the FFG checkpoints must have changed. Keep a clone to ensure we get
+            // exactly the head we just enshrined.
+            self.canonical_head
+                .cached_head
+                .store(Arc::new(new_cached_head.clone()));
+            new_cached_head
         };
 
         // Alias for readability.
@@ -830,6 +852,21 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
+        // Precompute the duties for the new head, so that the duties endpoints are served from the
+        // caches rather than from a copy of the head state. The proposer lookahead is dropped,
+        // since it was computed from the previous head.
+        self.beacon_proposer_cache
+            .lock()
+            .retain_lookahead(new_snapshot.beacon_block_root);
+        if let Err(e) = self.prime_duties_caches(new_snapshot) {
+            debug!(
+                self.log,
+                "Unable to prime duties caches";
+                "error" => ?e,
+                "head_block_root" => ?new_snapshot.beacon_block_root
+            );
+        }
+
         observe_head_block_delays(
             &mut self.block_times_cache.write(),
             &new_head_proto_block,
@@ -850,6 +887,30 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             self.op_pool.prune_attestations(self.epoch()?);
         }
 
+        // Once per epoch, drop exits which have been applied and persist the op pool so that
+        // exits (and other operations) received since the last restart survive a crash.
+        //
+        // Persisting the op pool is slow, so it is done in the background and a failure is logged
+        // rather than failing the head update.
+        if is_epoch_transition {
+            self.op_pool
+                .prune_applied_voluntary_exits(&new_snapshot.beacon_state);
+
+            let chain = self.clone();
+            self.task_executor.spawn_blocking(
+                move || {
+                    if let Err(e) = chain.persist_op_pool() {
+                        error!(
+                            chain.log,
+                            "Failed to persist op pool";
+                            "error" => ?e
+                        );
+                    }
+                },
+                "persist_op_pool",
+            );
+        }
+
         // Register server-sent-events for a new head.
         if let Some(event_handler) = self
             .event_handler
@@ -901,6 +962,42 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Add the proposers for the current epoch of the head state and its attester shufflings for
+    /// the current and next epochs to the proposer and shuffling caches.
+    ///
+    /// The entries are keyed by their decision roots, so entries for blocks abandoned by a re-org
+    /// are never served for the new head.
+    fn prime_duties_caches(&self, snapshot: &BeaconSnapshot<T::EthSpec>) -> Result<(), Error> {
+        let head_block_root = snapshot.beacon_block_root;
+        let state = &snapshot.beacon_state;
+
+        let proposer_decision_root = state.proposer_shuffling_decision_root(head_block_root)?;
+        let mut proposer_cache = self.beacon_proposer_cache.lock();
+        if proposer_cache
+            .get_epoch::<T::EthSpec>(proposer_decision_root, state.current_epoch())
+            .is_none()
+        {
+            proposer_cache.insert(
+                state.current_epoch(),
+                proposer_decision_root,
+                state.get_beacon_proposer_indices(&self.spec)?,
+                state.fork(),
+            )?;
+        }
+        drop(proposer_cache);
+
+        for relative_epoch in [RelativeEpoch::Current, RelativeEpoch::Next] {
+            let shuffling_id = AttestationShufflingId::new(head_block_root, state, relative_epoch)?;
+            let mut shuffling_cache = self.shuffling_cache.write();
+            if !shuffling_cache.contains(&shuffling_id) {
+                shuffling_cache
+                    .insert_committee_cache(shuffling_id, state.committee_cache(relative_epoch)?);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Perform updates to caches and other components after the finalized checkpoint has been
     /// changed.
     ///
@@ -924,6 +1021,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             self.epoch()?,
             &self.spec,
         );
+        {
+            let fork_choice = self.canonical_head.fork_choice_read_lock();
+            self.op_pool
+                .prune_non_viable_attestations(new_view.finalized_checkpoint, |block_root| {
+                    fork_choice.is_finalized_checkpoint_or_descendant(block_root)
+                });
+        }
 
         self.observed_block_producers.write().prune(
             new_view
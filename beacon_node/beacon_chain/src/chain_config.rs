@@ -1,5 +1,7 @@
+use crate::graffiti_calculator::GraffitiWatermark;
 pub use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
 use types::{Checkpoint, Epoch};
 
@@ -84,6 +86,30 @@ pub struct ChainConfig {
     pub epochs_per_migration: u64,
     /// When set to true Light client server computes and caches state proofs for serving updates
     pub enable_light_client_server: bool,
+    /// Number of epochs between the head and its finalized checkpoint before the chain is
+    /// considered degraded.
+    pub degraded_epochs_since_finalization: u64,
+    /// Number of missed slots in the past `SLOTS_PER_EPOCH` before the chain is considered
+    /// degraded.
+    pub degraded_missed_slots_per_epoch: u64,
+    /// Minimum number of connected peers below which the chain is considered degraded.
+    pub degraded_min_peers: usize,
+    /// Enable the degraded-mode behaviours whilst the chain is degraded.
+    ///
+    /// The health of the chain is reported regardless of this value.
+    pub enable_degraded_mode: bool,
+    /// The number of threads used to compute post-state roots during block import.
+    pub state_root_pool_threads: usize,
+    /// The maximum number of state root jobs queued on the pool before hashing falls back to
+    /// the importing thread.
+    pub state_root_pool_max_queued: usize,
+    /// If set, record gossip-verified blocks, blobs and attestations to this file for replay.
+    pub gossip_record_path: Option<PathBuf>,
+    /// Size of the hot database in bytes above which the states of non-viable forks are pruned
+    /// during long periods of non-finality.
+    pub emergency_pruning_threshold: Option<u64>,
+    /// Whether to append a client version watermark to user-specified graffiti.
+    pub graffiti_watermark: GraffitiWatermark,
 }
 
 impl Default for ChainConfig {
@@ -115,6 +141,15 @@ impl Default for ChainConfig {
             always_prepare_payload: false,
             epochs_per_migration: crate::migrate::DEFAULT_EPOCHS_PER_MIGRATION,
             enable_light_client_server: false,
+            degraded_epochs_since_finalization: 8,
+            degraded_missed_slots_per_epoch: 16,
+            degraded_min_peers: 5,
+            enable_degraded_mode: false,
+            state_root_pool_threads: crate::state_root_pool::DEFAULT_STATE_ROOT_POOL_THREADS,
+            state_root_pool_max_queued: crate::state_root_pool::DEFAULT_STATE_ROOT_POOL_MAX_QUEUED,
+            gossip_record_path: None,
+            emergency_pruning_threshold: None,
+            graffiti_watermark: GraffitiWatermark::Disabled,
         }
     }
 }
@@ -0,0 +1,256 @@
+//! Tracks the health of the chain and drives the beacon node's degraded-mode behaviours.
+//!
+//! The chain is considered *degraded* if any of the following are true:
+//!
+//! - More than `ChainConfig::degraded_epochs_since_finalization` epochs have passed between the
+//!   head's finalized checkpoint and the head.
+//! - More than `ChainConfig::degraded_missed_slots_per_epoch` of the last `SLOTS_PER_EPOCH` slots
+//!   were missed.
+//! - Fewer than `ChainConfig::degraded_min_peers` peers are connected.
+//!
+//! If `ChainConfig::enable_degraded_mode` is set, then whilst the chain is degraded the beacon node
+//! will:
+//!
+//! - Produce execution payloads locally, rather than using a builder.
+//! - Refuse to attempt proposer re-orgs.
+//! - Cull the hot state cache to half of its capacity each slot, limiting memory growth during
+//!   long periods of non-finality.
+//!
+//! The verdict is recomputed once per slot via `BeaconChain::update_chain_health`, and logged when
+//! it changes. The report from that update is served by `BeaconChain::latest_chain_health_report`. Degraded mode is never engaged whilst the node is syncing, since a syncing node has
+//! no reliable view of the chain.
+use crate::{
+    metrics, BeaconChain, BeaconChainError, BeaconChainTypes, ChainConfig, WhenSlotSkipped,
+};
+use eth2::lighthouse::{ChainHealthReport, ChainHealthVerdict, DegradedReason};
+use parking_lot::RwLock;
+use slog::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use types::{EthSpec, Slot};
+
+/// Holds the inputs and the latest verdict of the chain health checks.
+#[derive(Default)]
+pub struct ChainHealthMonitor {
+    /// The number of connected peers, as last reported by the network.
+    connected_peers: RwLock<Option<usize>>,
+    /// `true` if the chain was degraded at the last update.
+    verdict_degraded: AtomicBool,
+    /// `true` if the degraded-mode behaviours are active.
+    degraded: AtomicBool,
+    /// The report computed by the last update.
+    latest_report: RwLock<Option<ChainHealthReport>>,
+}
+
+impl ChainHealthMonitor {
+    /// Returns `true` if the degraded-mode behaviours are active.
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of connected peers, as last reported by the network.
+    pub fn connected_peers(&self) -> Option<usize> {
+        *self.connected_peers.read()
+    }
+
+    /// Returns the report computed by the last update, if any.
+    pub fn latest_report(&self) -> Option<ChainHealthReport> {
+        self.latest_report.read().clone()
+    }
+
+    fn set_connected_peers(&self, connected_peers: usize) {
+        *self.connected_peers.write() = Some(connected_peers);
+    }
+
+    /// Records whether the chain is degraded and whether the degraded-mode behaviours are active,
+    /// returning whether the chain was previously degraded.
+    fn set_verdict(&self, verdict_degraded: bool, degraded: bool) -> bool {
+        metrics::set_gauge(&metrics::CHAIN_HEALTH_DEGRADED, degraded as i64);
+        self.degraded.store(degraded, Ordering::Relaxed);
+        self.verdict_degraded
+            .swap(verdict_degraded, Ordering::Relaxed)
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Returns the health of the chain as of the last call to `Self::update_chain_health`, or
+    /// computes it if there has been no update yet.
+    pub fn latest_chain_health_report(&self) -> Result<ChainHealthReport, BeaconChainError> {
+        match self.chain_health.latest_report() {
+            Some(report) => Ok(report),
+            None => self.chain_health_report(),
+        }
+    }
+
+    /// Computes the current health of the chain.
+    pub fn chain_health_report(&self) -> Result<ChainHealthReport, BeaconChainError> {
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let current_slot = self.slot()?;
+        let cached_head = self.canonical_head.cached_head();
+        let head_slot = cached_head.head_slot();
+
+        let epochs_since_finalization = head_slot
+            .epoch(slots_per_epoch)
+            .saturating_sub(cached_head.finalized_checkpoint().epoch)
+            .as_u64();
+
+        let start_slot = current_slot.saturating_sub(slots_per_epoch);
+        let mut missed_slots_in_last_epoch = 0;
+        for slot in start_slot.as_u64()..current_slot.as_u64() {
+            if self
+                .block_root_at_slot(Slot::new(slot), WhenSlotSkipped::None)?
+                .is_none()
+            {
+                missed_slots_in_last_epoch += 1;
+            }
+        }
+
+        let connected_peers = self.chain_health.connected_peers();
+
+        let reasons = degraded_reasons(
+            &self.config,
+            epochs_since_finalization,
+            missed_slots_in_last_epoch,
+            connected_peers,
+        );
+        let verdict = if reasons.is_empty() {
+            ChainHealthVerdict::Healthy
+        } else {
+            ChainHealthVerdict::Degraded
+        };
+
+        Ok(ChainHealthReport {
+            verdict,
+            reasons,
+            degraded_mode_active: self.chain_health.is_degraded(),
+            current_slot,
+            head_slot,
+            epochs_since_finalization,
+            missed_slots_in_last_epoch,
+            connected_peers,
+        })
+    }
+
+    /// Recomputes the health of the chain and enables or disables the degraded-mode behaviours
+    /// accordingly. Intended to be called once per slot.
+    pub fn update_chain_health(
+        &self,
+        connected_peers: usize,
+        is_syncing: bool,
+    ) -> Result<(), BeaconChainError> {
+        self.chain_health.set_connected_peers(connected_peers);
+
+        let mut report = self.chain_health_report()?;
+        let is_degraded = !is_syncing && report.verdict == ChainHealthVerdict::Degraded;
+        let degraded = self.config.enable_degraded_mode && is_degraded;
+
+        let was_degraded = self.chain_health.set_verdict(is_degraded, degraded);
+        report.degraded_mode_active = degraded;
+        if is_degraded && !was_degraded {
+            let info = if degraded {
+                "entering degraded mode: builder payloads and proposer re-orgs are disabled and \
+                 caches are reduced"
+            } else {
+                "degraded mode is disabled, use --enable-degraded-mode to engage it"
+            };
+            warn!(
+                self.log,
+                "Chain health degraded";
+                "info" => info,
+                "reasons" => ?report.reasons,
+                "epochs_since_finalization" => report.epochs_since_finalization,
+                "missed_slots_in_last_epoch" => report.missed_slots_in_last_epoch,
+                "connected_peers" => ?report.connected_peers,
+            );
+        } else if !is_degraded && was_degraded {
+            info!(self.log, "Chain health restored");
+        }
+
+        if degraded {
+            self.store.shrink_state_cache();
+        }
+
+        *self.chain_health.latest_report.write() = Some(report);
+
+        Ok(())
+    }
+}
+
+/// Returns the conditions which cause the chain to be considered degraded under `config`.
+pub fn degraded_reasons(
+    config: &ChainConfig,
+    epochs_since_finalization: u64,
+    missed_slots_in_last_epoch: u64,
+    connected_peers: Option<usize>,
+) -> Vec<DegradedReason> {
+    let mut reasons = vec![];
+    if epochs_since_finalization > config.degraded_epochs_since_finalization {
+        reasons.push(DegradedReason::EpochsSinceFinalization);
+    }
+    if missed_slots_in_last_epoch > config.degraded_missed_slots_per_epoch {
+        reasons.push(DegradedReason::MissedSlots);
+    }
+    if connected_peers.map_or(false, |peers| peers < config.degraded_min_peers) {
+        reasons.push(DegradedReason::LowPeerCount);
+    }
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ChainConfig {
+        ChainConfig {
+            degraded_epochs_since_finalization: 4,
+            degraded_missed_slots_per_epoch: 8,
+            degraded_min_peers: 3,
+            ..ChainConfig::default()
+        }
+    }
+
+    #[test]
+    fn healthy_at_thresholds() {
+        assert!(degraded_reasons(&config(), 4, 8, Some(3)).is_empty());
+        assert!(degraded_reasons(&config(), 0, 0, Some(50)).is_empty());
+    }
+
+    #[test]
+    fn degraded_beyond_each_threshold() {
+        assert_eq!(
+            degraded_reasons(&config(), 5, 8, Some(3)),
+            vec![DegradedReason::EpochsSinceFinalization]
+        );
+        assert_eq!(
+            degraded_reasons(&config(), 4, 9, Some(3)),
+            vec![DegradedReason::MissedSlots]
+        );
+        assert_eq!(
+            degraded_reasons(&config(), 4, 8, Some(2)),
+            vec![DegradedReason::LowPeerCount]
+        );
+        assert_eq!(
+            degraded_reasons(&config(), 5, 9, Some(0)),
+            vec![
+                DegradedReason::EpochsSinceFinalization,
+                DegradedReason::MissedSlots,
+                DegradedReason::LowPeerCount
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_peer_count_is_not_degraded() {
+        assert!(degraded_reasons(&config(), 0, 0, None).is_empty());
+    }
+
+    #[test]
+    fn monitor_tracks_verdict_and_mode() {
+        let monitor = ChainHealthMonitor::default();
+        assert!(!monitor.set_verdict(true, false));
+        assert!(!monitor.is_degraded());
+        assert!(monitor.set_verdict(true, true));
+        assert!(monitor.is_degraded());
+        assert!(monitor.set_verdict(false, false));
+        assert!(!monitor.is_degraded());
+    }
+}
@@ -138,6 +138,23 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
         Ok(Some(attestation))
     }
 
+    /// Removes the cached item if it is for `block_root`, returning `true` if it was removed.
+    ///
+    /// Used to invalidate a block which was added to the cache but subsequently failed to import.
+    pub fn remove_block(&self, block_root: Hash256) -> bool {
+        let mut item = self.item.write();
+        if item
+            .as_ref()
+            .map_or(false, |item| item.beacon_block_root == block_root)
+        {
+            *item = None;
+            metrics::inc_counter(&metrics::BEACON_EARLY_ATTESTER_CACHE_INVALIDATIONS);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Returns `true` if `block_root` matches the cached item.
     pub fn contains_block(&self, block_root: Hash256) -> bool {
         self.item
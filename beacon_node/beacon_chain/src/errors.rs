@@ -222,6 +222,7 @@ pub enum BeaconChainError {
     MilhouseError(MilhouseError),
     AttestationError(AttestationError),
     AttestationCommitteeIndexNotSet,
+    Eth1ChainError(Eth1ChainError),
 }
 
 easy_from_to!(SlotProcessingError, BeaconChainError);
@@ -14,7 +14,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use store::{DBColumn, Error as StoreError, StoreItem};
 use task_executor::TaskExecutor;
 use types::{
-    BeaconState, BeaconStateError, ChainSpec, Deposit, Eth1Data, EthSpec, Hash256, Slot, Unsigned,
+    BeaconState, BeaconStateError, ChainSpec, Deposit, DepositTreeSnapshot, Eth1Data, EthSpec,
+    Hash256, Slot, Unsigned,
 };
 
 type BlockNumber = u64;
@@ -53,6 +54,22 @@ pub enum Error {
     ArithError(safe_arith::ArithError),
 }
 
+/// The outcome of importing a deposit snapshot received from a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepositSnapshotImport {
+    /// The snapshot was loaded into the deposit cache.
+    Imported,
+    /// The deposit cache already holds at least as many deposits as the snapshot, or there is no
+    /// deposit cache.
+    NotRequired,
+    /// The snapshot's finalized hashes don't produce its deposit root.
+    Invalid,
+    /// The snapshot doesn't finalize the deposits voted into the finalized state.
+    ///
+    /// This may happen without fault if the peer's deposit cache is behind its chain.
+    Mismatch,
+}
+
 impl From<safe_arith::ArithError> for Error {
     fn from(e: safe_arith::ArithError) -> Self {
         Self::ArithError(e)
@@ -309,6 +326,23 @@ where
         self.backend.finalize_eth1_data(eth1_data);
     }
 
+    /// Returns the number of deposits in the deposit cache.
+    pub fn deposit_count(&self) -> u64 {
+        self.backend.deposit_count()
+    }
+
+    /// Returns a snapshot of the finalized deposits in the deposit cache, if any.
+    pub fn deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        self.backend.deposit_snapshot()
+    }
+
+    /// Replaces the deposit cache with `snapshot` if it holds more deposits than the cache.
+    ///
+    /// The snapshot must already have been verified against a finalized state.
+    pub fn load_deposit_snapshot(&self, snapshot: &DepositTreeSnapshot) -> Result<bool, Error> {
+        self.backend.load_deposit_snapshot(snapshot)
+    }
+
     /// Consumes `self`, returning the backend.
     pub fn into_backend(self) -> T {
         self.backend
@@ -347,6 +381,17 @@ pub trait Eth1ChainBackend<E: EthSpec>: Sized + Send + Sync {
     /// an idea of how up-to-date the remote eth1 node is.
     fn head_block(&self) -> Option<Eth1Block>;
 
+    /// Returns the number of deposits in the deposit cache.
+    fn deposit_count(&self) -> u64;
+
+    /// Returns a snapshot of the finalized deposits in the deposit cache, if any.
+    fn deposit_snapshot(&self) -> Option<DepositTreeSnapshot>;
+
+    /// Replaces the deposit cache with `snapshot` if it holds more deposits than the cache.
+    ///
+    /// Returns `Ok(false)` if the cache already holds at least as many deposits.
+    fn load_deposit_snapshot(&self, snapshot: &DepositTreeSnapshot) -> Result<bool, Error>;
+
     /// Encode the `Eth1ChainBackend` instance to bytes.
     fn as_bytes(&self) -> Vec<u8>;
 
@@ -403,6 +448,19 @@ impl<E: EthSpec> Eth1ChainBackend<E> for DummyEth1ChainBackend<E> {
         None
     }
 
+    fn deposit_count(&self) -> u64 {
+        0
+    }
+
+    /// The dummy back-end has no deposit cache.
+    fn deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        None
+    }
+
+    fn load_deposit_snapshot(&self, _snapshot: &DepositTreeSnapshot) -> Result<bool, Error> {
+        Ok(false)
+    }
+
     /// Return empty Vec<u8> for dummy backend.
     fn as_bytes(&self) -> Vec<u8> {
         Vec::new()
@@ -586,6 +644,20 @@ impl<E: EthSpec> Eth1ChainBackend<E> for CachingEth1Backend<E> {
         self.core.head_block()
     }
 
+    fn deposit_count(&self) -> u64 {
+        self.core.deposit_cache_len() as u64
+    }
+
+    fn deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
+        self.core.get_deposit_snapshot()
+    }
+
+    fn load_deposit_snapshot(&self, snapshot: &DepositTreeSnapshot) -> Result<bool, Error> {
+        self.core
+            .load_deposit_snapshot(snapshot)
+            .map_err(|e| Error::BackendError(format!("Failed to load deposit snapshot: {:?}", e)))
+    }
+
     /// Return encoded byte representation of the block and deposit caches.
     fn as_bytes(&self) -> Vec<u8> {
         self.core.as_bytes()
@@ -24,6 +24,10 @@ pub struct ServerSentEventHandler<E: EthSpec> {
     attester_slashing_tx: Sender<EventKind<E>>,
     bls_to_execution_change_tx: Sender<EventKind<E>>,
     block_gossip_tx: Sender<EventKind<E>>,
+    peer_tx: Sender<EventKind<E>>,
+    blob_sidecar_equivocation_tx: Sender<EventKind<E>>,
+    genesis_countdown_tx: Sender<EventKind<E>>,
+    block_equivocation_tx: Sender<EventKind<E>>,
     log: Logger,
 }
 
@@ -53,6 +57,10 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
         let (attester_slashing_tx, _) = broadcast::channel(capacity);
         let (bls_to_execution_change_tx, _) = broadcast::channel(capacity);
         let (block_gossip_tx, _) = broadcast::channel(capacity);
+        let (peer_tx, _) = broadcast::channel(capacity);
+        let (blob_sidecar_equivocation_tx, _) = broadcast::channel(capacity);
+        let (genesis_countdown_tx, _) = broadcast::channel(capacity);
+        let (block_equivocation_tx, _) = broadcast::channel(capacity);
 
         Self {
             attestation_tx,
@@ -72,6 +80,10 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
             attester_slashing_tx,
             bls_to_execution_change_tx,
             block_gossip_tx,
+            peer_tx,
+            blob_sidecar_equivocation_tx,
+            genesis_countdown_tx,
+            block_equivocation_tx,
             log,
         }
     }
@@ -154,6 +166,22 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
                 .block_gossip_tx
                 .send(kind)
                 .map(|count| log_count("block gossip", count)),
+            EventKind::Peer(_) => self
+                .peer_tx
+                .send(kind)
+                .map(|count| log_count("peer", count)),
+            EventKind::BlobSidecarEquivocation(_) => self
+                .blob_sidecar_equivocation_tx
+                .send(kind)
+                .map(|count| log_count("blob sidecar equivocation", count)),
+            EventKind::GenesisCountdown(_) => self
+                .genesis_countdown_tx
+                .send(kind)
+                .map(|count| log_count("genesis countdown", count)),
+            EventKind::BlockEquivocation(_) => self
+                .block_equivocation_tx
+                .send(kind)
+                .map(|count| log_count("block equivocation", count)),
         };
         if let Err(SendError(event)) = result {
             trace!(self.log, "No receivers registered to listen for event"; "event" => ?event);
@@ -228,6 +256,22 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
         self.block_gossip_tx.subscribe()
     }
 
+    pub fn subscribe_peer(&self) -> Receiver<EventKind<E>> {
+        self.peer_tx.subscribe()
+    }
+
+    pub fn subscribe_blob_sidecar_equivocation(&self) -> Receiver<EventKind<E>> {
+        self.blob_sidecar_equivocation_tx.subscribe()
+    }
+
+    pub fn subscribe_genesis_countdown(&self) -> Receiver<EventKind<E>> {
+        self.genesis_countdown_tx.subscribe()
+    }
+
+    pub fn subscribe_block_equivocation(&self) -> Receiver<EventKind<E>> {
+        self.block_equivocation_tx.subscribe()
+    }
+
     pub fn has_attestation_subscribers(&self) -> bool {
         self.attestation_tx.receiver_count() > 0
     }
@@ -287,4 +331,20 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
     pub fn has_block_gossip_subscribers(&self) -> bool {
         self.block_gossip_tx.receiver_count() > 0
     }
+
+    pub fn has_peer_subscribers(&self) -> bool {
+        self.peer_tx.receiver_count() > 0
+    }
+
+    pub fn has_blob_sidecar_equivocation_subscribers(&self) -> bool {
+        self.blob_sidecar_equivocation_tx.receiver_count() > 0
+    }
+
+    pub fn has_genesis_countdown_subscribers(&self) -> bool {
+        self.genesis_countdown_tx.receiver_count() > 0
+    }
+
+    pub fn has_block_equivocation_subscribers(&self) -> bool {
+        self.block_equivocation_tx.receiver_count() > 0
+    }
 }
@@ -0,0 +1,72 @@
+//! Provides a single report on whether a node is ready for the next scheduled fork.
+//!
+//! This combines the fork-specific execution engine checks (e.g. `check_deneb_readiness`) with
+//! checks on the local configuration, so that an operator can verify a node before a hard fork.
+
+use crate::bellatrix_readiness::BellatrixReadiness;
+use crate::capella_readiness::CapellaReadiness;
+use crate::deneb_readiness::DenebReadiness;
+use crate::electra_readiness::ElectraReadiness;
+use crate::{BeaconChain, BeaconChainTypes};
+pub use eth2::lighthouse::{ExecutionReadiness, ForkReadinessReport};
+use slot_clock::SlotClock;
+use types::*;
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Checks whether this node is ready for the fork following the one at the current slot.
+    pub async fn check_fork_readiness(&self) -> ForkReadinessReport {
+        let current_slot = self.slot_clock.now_or_genesis().unwrap_or(Slot::new(0));
+        let current_fork = self.spec.fork_name_at_slot::<T::EthSpec>(current_slot);
+        let next_fork = current_fork.next_fork();
+        let next_fork_epoch = next_fork.and_then(|fork| self.spec.fork_epoch(fork));
+
+        let execution = match next_fork {
+            None | Some(ForkName::Base) | Some(ForkName::Altair) => None,
+            Some(ForkName::Bellatrix) => {
+                let readiness = self.check_bellatrix_readiness(current_slot).await;
+                Some(ExecutionReadiness {
+                    ready: matches!(readiness, BellatrixReadiness::Ready { .. }),
+                    info: readiness.to_string(),
+                })
+            }
+            Some(ForkName::Capella) => {
+                let readiness = self.check_capella_readiness().await;
+                Some(ExecutionReadiness {
+                    ready: matches!(readiness, CapellaReadiness::Ready),
+                    info: readiness.to_string(),
+                })
+            }
+            Some(ForkName::Deneb) => {
+                let readiness = self.check_deneb_readiness().await;
+                Some(ExecutionReadiness {
+                    ready: matches!(readiness, DenebReadiness::Ready),
+                    info: readiness.to_string(),
+                })
+            }
+            Some(ForkName::Electra) => {
+                let readiness = self.check_electra_readiness().await;
+                Some(ExecutionReadiness {
+                    ready: matches!(readiness, ElectraReadiness::Ready),
+                    info: readiness.to_string(),
+                })
+            }
+        };
+
+        let kzg_required = next_fork.map_or(false, ForkName::deneb_enabled);
+        let kzg_loaded = self.kzg.is_some();
+
+        let ready = next_fork_epoch.is_some()
+            && execution.as_ref().map_or(true, |execution| execution.ready)
+            && (!kzg_required || kzg_loaded);
+
+        ForkReadinessReport {
+            current_fork,
+            next_fork,
+            next_fork_epoch,
+            execution,
+            kzg_required,
+            kzg_loaded,
+            ready,
+        }
+    }
+}
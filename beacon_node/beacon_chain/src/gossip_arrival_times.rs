@@ -0,0 +1,144 @@
+//! A bounded log of when gossip blocks, aggregates and blob sidecars arrive, relative to the start
+//! of their slot.
+//!
+//! Only messages which pass gossip verification are recorded, so that peers cannot fill the log
+//! with junk. Once the log is full the oldest arrivals are discarded. Every recorded arrival is also
+//! observed in the `beacon_gossip_arrival_delay_seconds` histogram.
+use crate::metrics;
+use eth2::lighthouse::GossipArrival;
+pub use eth2::lighthouse::GossipArrivalKind;
+use std::collections::VecDeque;
+use std::time::Duration;
+use types::{Hash256, Slot};
+
+/// The number of arrivals to retain.
+///
+/// This is enough for several slots of mainnet aggregates, or many epochs of blocks and blobs.
+pub const GOSSIP_ARRIVAL_TIMES_CAPACITY: usize = 32_768;
+
+pub struct GossipArrivalTimes {
+    arrivals: VecDeque<GossipArrival>,
+    capacity: usize,
+}
+
+impl Default for GossipArrivalTimes {
+    fn default() -> Self {
+        Self::with_capacity(GOSSIP_ARRIVAL_TIMES_CAPACITY)
+    }
+}
+
+impl GossipArrivalTimes {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            arrivals: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Record the arrival of a message relating to `block_root` at `slot`.
+    ///
+    /// The `delay` is the duration between the start of `slot` and the arrival of the message.
+    pub fn record(
+        &mut self,
+        kind: GossipArrivalKind,
+        slot: Slot,
+        block_root: Hash256,
+        index: Option<u64>,
+        delay: Duration,
+    ) {
+        metrics::observe_timer_vec(
+            &metrics::BEACON_GOSSIP_ARRIVAL_DELAY_SECONDS,
+            &[kind.as_str()],
+            delay,
+        );
+
+        if self.capacity == 0 {
+            return;
+        }
+        while self.arrivals.len() >= self.capacity {
+            self.arrivals.pop_front();
+        }
+        self.arrivals.push_back(GossipArrival {
+            kind,
+            slot,
+            block_root,
+            index,
+            delay_ms: delay.as_millis() as u64,
+        });
+    }
+
+    /// Returns the recorded arrivals of `kind` (or of all kinds if `None`) with slots between
+    /// `start_slot` and `end_slot` inclusive, in the order they arrived.
+    pub fn arrivals(
+        &self,
+        kind: Option<GossipArrivalKind>,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> Vec<GossipArrival> {
+        self.arrivals
+            .iter()
+            .filter(|arrival| kind.map_or(true, |kind| arrival.kind == kind))
+            .filter(|arrival| start_slot.map_or(true, |start_slot| arrival.slot >= start_slot))
+            .filter(|arrival| end_slot.map_or(true, |end_slot| arrival.slot <= end_slot))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(log: &mut GossipArrivalTimes, kind: GossipArrivalKind, slot: u64) {
+        log.record(
+            kind,
+            Slot::new(slot),
+            Hash256::from_low_u64_be(slot),
+            None,
+            Duration::from_millis(slot * 100),
+        );
+    }
+
+    #[test]
+    fn discards_oldest_when_full() {
+        let mut log = GossipArrivalTimes::with_capacity(3);
+        for slot in 0..5 {
+            record(&mut log, GossipArrivalKind::Block, slot);
+        }
+
+        let slots = log
+            .arrivals(None, None, None)
+            .into_iter()
+            .map(|arrival| arrival.slot.as_u64())
+            .collect::<Vec<_>>();
+        assert_eq!(slots, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn filters_by_kind_and_slot() {
+        let mut log = GossipArrivalTimes::default();
+        for slot in 0..8 {
+            record(&mut log, GossipArrivalKind::Block, slot);
+            record(&mut log, GossipArrivalKind::Aggregate, slot);
+        }
+
+        let arrivals = log.arrivals(
+            Some(GossipArrivalKind::Aggregate),
+            Some(Slot::new(2)),
+            Some(Slot::new(4)),
+        );
+        assert_eq!(arrivals.len(), 3);
+        for (arrival, slot) in arrivals.iter().zip(2..) {
+            assert_eq!(arrival.kind, GossipArrivalKind::Aggregate);
+            assert_eq!(arrival.slot, Slot::new(slot));
+            assert_eq!(arrival.delay_ms, slot * 100);
+        }
+    }
+
+    #[test]
+    fn zero_capacity_records_nothing() {
+        let mut log = GossipArrivalTimes::with_capacity(0);
+        record(&mut log, GossipArrivalKind::BlobSidecar, 1);
+        assert!(log.arrivals(None, None, None).is_empty());
+    }
+}
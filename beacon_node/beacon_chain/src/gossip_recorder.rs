@@ -0,0 +1,437 @@
+//! Records gossip-verified objects to a file so that they can be replayed into a fresh chain.
+//!
+//! The recording is a sequence of SSZ-encoded `GossipRecord`s, each prefixed by its length as a
+//! little-endian `u32`. Records are written in the order in which the objects passed gossip
+//! verification, along with the slot clock time at which they did so.
+//!
+//! Records are written by a background task through a buffered writer, so recording never blocks
+//! gossip verification on disk I/O. If the writer falls behind, new records are dropped and
+//! counted in the `beacon_gossip_recorder_dropped_total` metric.
+//!
+//! Replaying the records with `BeaconChain::replay_gossip` feeds each object back through the same
+//! gossip verification and import functions used by the network, which makes it possible to
+//! reproduce consensus bugs seen in production offline. `lcli replay-gossip` runs a replay from
+//! the command line.
+
+use crate::attestation_verification::{
+    VerifiedAggregatedAttestation, VerifiedUnaggregatedAttestation,
+};
+use crate::blob_verification::GossipVerifiedBlob;
+use crate::block_verification::GossipVerifiedBlock;
+use crate::{metrics, BeaconChain, BeaconChainTypes, NotifyExecutionLayer};
+use slog::{debug, warn, Logger};
+use slot_clock::{ManualSlotClock, SlotClock};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{sleep_until, Instant};
+use types::*;
+
+/// The maximum number of records waiting to be written before new records are dropped.
+const MAX_QUEUED_RECORDS: usize = 16_384;
+
+/// The kind of object contained in a `GossipRecord`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum GossipRecordKind {
+    Block = 0,
+    BlobSidecar = 1,
+    Attestation = 2,
+    AggregateAndProof = 3,
+}
+
+impl TryFrom<u8> for GossipRecordKind {
+    type Error = String;
+
+    fn try_from(kind: u8) -> Result<Self, Self::Error> {
+        match kind {
+            0 => Ok(GossipRecordKind::Block),
+            1 => Ok(GossipRecordKind::BlobSidecar),
+            2 => Ok(GossipRecordKind::Attestation),
+            3 => Ok(GossipRecordKind::AggregateAndProof),
+            other => Err(format!("Unknown gossip record kind: {}", other)),
+        }
+    }
+}
+
+/// A single object which passed gossip verification.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct GossipRecord {
+    /// The slot clock time at which the object passed verification.
+    pub timestamp_millis: u64,
+    /// A `GossipRecordKind`.
+    pub kind: u8,
+    /// The subnet on which the object was received. Zero for blocks and aggregates.
+    pub subnet_id: u64,
+    /// The fork of the object, required to decode attestations.
+    pub fork_name: ForkName,
+    /// The SSZ bytes of the object.
+    pub payload: Vec<u8>,
+}
+
+/// A message to the background task which writes the recording.
+enum WriterMessage {
+    /// A length-prefixed record to append.
+    Record(Vec<u8>),
+    /// Flush all previously queued records, then notify the sender.
+    Flush(oneshot::Sender<()>),
+}
+
+/// Appends `GossipRecord`s to a file.
+pub struct GossipRecorder {
+    tx: mpsc::Sender<WriterMessage>,
+}
+
+impl GossipRecorder {
+    /// Open `path` for recording, appending to any existing recording, and spawn the task which
+    /// writes to it. The task stops once the recorder is dropped.
+    pub fn create(path: &Path, executor: &TaskExecutor, log: Logger) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| {
+                format!(
+                    "Unable to open gossip recording {}: {:?}",
+                    path.display(),
+                    e
+                )
+            })?;
+
+        let (tx, rx) = mpsc::channel(MAX_QUEUED_RECORDS);
+        executor.spawn_blocking(
+            move || write_records(BufWriter::new(file), rx, log),
+            "gossip_recorder",
+        );
+        Ok(Self { tx })
+    }
+
+    /// Queue `record` to be written. Returns an error without blocking if the writer is behind.
+    pub fn record(&self, record: &GossipRecord) -> Result<(), String> {
+        let bytes = record.as_ssz_bytes();
+        let len = u32::try_from(bytes.len())
+            .map_err(|_| format!("Gossip record too large: {} bytes", bytes.len()))?;
+
+        let mut buf = Vec::with_capacity(4 + bytes.len());
+        buf.extend_from_slice(&len.to_le_bytes());
+        buf.extend_from_slice(&bytes);
+
+        self.tx
+            .try_send(WriterMessage::Record(buf))
+            .map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => {
+                    metrics::inc_counter(&metrics::GOSSIP_RECORDER_DROPPED);
+                    "Gossip recording queue is full".to_string()
+                }
+                mpsc::error::TrySendError::Closed(_) => "Gossip recorder has stopped".to_string(),
+            })
+    }
+
+    /// Wait until every record queued before this call has been written to the file.
+    pub async fn flush(&self) -> Result<(), String> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .send(WriterMessage::Flush(tx))
+            .await
+            .map_err(|_| "Gossip recorder has stopped")?;
+        rx.await.map_err(|_| "Gossip recorder has stopped".into())
+    }
+}
+
+/// Write records to `writer` until every `GossipRecorder` sender is dropped.
+///
+/// Each record is written whole, so a crash can only ever truncate the final record. The writer is
+/// flushed whenever the queue is empty rather than after every record.
+fn write_records(mut writer: BufWriter<File>, mut rx: mpsc::Receiver<WriterMessage>, log: Logger) {
+    while let Some(mut message) = rx.blocking_recv() {
+        loop {
+            let result = match message {
+                WriterMessage::Record(buf) => writer.write_all(&buf),
+                WriterMessage::Flush(done) => {
+                    let result = writer.flush();
+                    let _ = done.send(());
+                    result
+                }
+            };
+            if let Err(e) = result {
+                warn!(log, "Unable to write gossip record"; "error" => ?e);
+            }
+            match rx.try_recv() {
+                Ok(next) => message = next,
+                Err(_) => break,
+            }
+        }
+        if let Err(e) = writer.flush() {
+            warn!(log, "Unable to flush gossip recording"; "error" => ?e);
+        }
+    }
+}
+
+/// Read all of the records in the recording at `path`.
+pub fn read_gossip_records(path: &Path) -> Result<Vec<GossipRecord>, String> {
+    let mut bytes = vec![];
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(|e| {
+            format!(
+                "Unable to read gossip recording {}: {:?}",
+                path.display(),
+                e
+            )
+        })?;
+
+    let mut records = vec![];
+    let mut remaining = bytes.as_slice();
+    while !remaining.is_empty() {
+        let (len_bytes, rest) = remaining
+            .split_first_chunk::<4>()
+            .ok_or("Truncated gossip record length")?;
+        let len = u32::from_le_bytes(*len_bytes) as usize;
+        if rest.len() < len {
+            return Err("Truncated gossip record".into());
+        }
+        let (record_bytes, rest) = rest.split_at(len);
+        records.push(
+            GossipRecord::from_ssz_bytes(record_bytes)
+                .map_err(|e| format!("Invalid gossip record: {:?}", e))?,
+        );
+        remaining = rest;
+    }
+    Ok(records)
+}
+
+/// How quickly `BeaconChain::replay_gossip` should feed records into the chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayTiming {
+    /// Process each record as soon as the previous one has been processed.
+    Immediate,
+    /// Wait between records to reproduce the relative timing with which they were recorded.
+    Relative,
+}
+
+/// The number of records which were successfully processed, or rejected, during a replay.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GossipReplaySummary {
+    pub blocks_imported: usize,
+    pub blobs_imported: usize,
+    pub attestations_imported: usize,
+    pub aggregates_imported: usize,
+    pub rejected: usize,
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Record an object which has passed gossip verification, if recording is enabled.
+    ///
+    /// The `payload` function is only called if there is a recorder.
+    pub(crate) fn record_gossip(
+        &self,
+        kind: GossipRecordKind,
+        subnet_id: u64,
+        slot: Slot,
+        payload: impl FnOnce() -> Vec<u8>,
+    ) {
+        let Some(recorder) = self.gossip_recorder.as_ref() else {
+            return;
+        };
+
+        let record = GossipRecord {
+            timestamp_millis: self
+                .slot_clock
+                .now_duration()
+                .map_or(0, |now| now.as_millis() as u64),
+            kind: kind as u8,
+            subnet_id,
+            fork_name: self.spec.fork_name_at_slot::<T::EthSpec>(slot),
+            payload: payload(),
+        };
+
+        // Drops are counted by the recorder, so this doesn't warn for every record while the
+        // writer is behind.
+        if let Err(e) = recorder.record(&record) {
+            debug!(
+                self.log,
+                "Failed to record gossip";
+                "kind" => ?kind,
+                "error" => e,
+            );
+        }
+    }
+
+    pub(crate) fn record_gossip_block(&self, verified: &GossipVerifiedBlock<T>) {
+        self.record_gossip(GossipRecordKind::Block, 0, verified.block.slot(), || {
+            verified.block.as_ssz_bytes()
+        });
+    }
+
+    pub(crate) fn record_gossip_blob(&self, verified: &GossipVerifiedBlob<T>, subnet_id: u64) {
+        self.record_gossip(
+            GossipRecordKind::BlobSidecar,
+            subnet_id,
+            verified.slot(),
+            || verified.as_blob().as_ssz_bytes(),
+        );
+    }
+
+    pub(crate) fn record_gossip_attestation(&self, verified: &VerifiedUnaggregatedAttestation<T>) {
+        let attestation = verified.attestation();
+        self.record_gossip(
+            GossipRecordKind::Attestation,
+            verified.subnet_id().into(),
+            attestation.data().slot,
+            || attestation.clone_as_attestation().as_ssz_bytes(),
+        );
+    }
+
+    pub(crate) fn record_gossip_aggregate(&self, verified: &VerifiedAggregatedAttestation<T>) {
+        self.record_gossip(
+            GossipRecordKind::AggregateAndProof,
+            0,
+            verified.attestation().data().slot,
+            || verified.aggregate().as_ssz_bytes(),
+        );
+    }
+}
+
+/// Replaying drives the slot clock, so it requires a chain with a manual slot clock.
+impl<T: BeaconChainTypes<SlotClock = ManualSlotClock>> BeaconChain<T> {
+    /// Feed `records` through gossip verification and import, in order.
+    ///
+    /// Before each record is processed, the slot clock is advanced to the time at which it was
+    /// recorded, so that objects are verified against the same slot as they were originally.
+    ///
+    /// Invalid records are counted in the summary and skipped, so a replay can continue past
+    /// objects that this chain rejects.
+    pub async fn replay_gossip(
+        self: &Arc<Self>,
+        records: Vec<GossipRecord>,
+        timing: ReplayTiming,
+    ) -> Result<GossipReplaySummary, String> {
+        let mut summary = GossipReplaySummary::default();
+        let start = Instant::now();
+        let first_timestamp = records.first().map_or(0, |record| record.timestamp_millis);
+
+        for record in records {
+            if timing == ReplayTiming::Relative {
+                let offset = record.timestamp_millis.saturating_sub(first_timestamp);
+                sleep_until(start + Duration::from_millis(offset)).await;
+            }
+
+            // Records from batch verification may be slightly out of order, so never move the
+            // clock backwards.
+            let recorded_at = Duration::from_millis(record.timestamp_millis);
+            if self
+                .slot_clock
+                .now_duration()
+                .map_or(true, |now| recorded_at > now)
+            {
+                self.slot_clock.set_current_time(recorded_at);
+            }
+
+            let kind = GossipRecordKind::try_from(record.kind)?;
+            let result = match kind {
+                GossipRecordKind::Block => self.replay_block(&record).await,
+                GossipRecordKind::BlobSidecar => self.replay_blob(&record).await,
+                GossipRecordKind::Attestation => self.replay_attestation(&record),
+                GossipRecordKind::AggregateAndProof => self.replay_aggregate(&record),
+            };
+
+            match result {
+                Ok(()) => match kind {
+                    GossipRecordKind::Block => summary.blocks_imported += 1,
+                    GossipRecordKind::BlobSidecar => summary.blobs_imported += 1,
+                    GossipRecordKind::Attestation => summary.attestations_imported += 1,
+                    GossipRecordKind::AggregateAndProof => summary.aggregates_imported += 1,
+                },
+                Err(e) => {
+                    debug!(
+                        self.log,
+                        "Replayed gossip rejected";
+                        "kind" => ?kind,
+                        "error" => e,
+                    );
+                    summary.rejected += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    async fn replay_block(self: &Arc<Self>, record: &GossipRecord) -> Result<(), String> {
+        let block = SignedBeaconBlock::from_ssz_bytes(&record.payload, &self.spec)
+            .map_err(|e| format!("{:?}", e))?;
+        let verified = self
+            .verify_block_for_gossip(Arc::new(block))
+            .await
+            .map_err(|e| e.to_string())?;
+        let block_root = verified.block_root;
+        self.process_block(
+            block_root,
+            verified,
+            NotifyExecutionLayer::Yes,
+            BlockImportSource::Gossip,
+            || Ok(()),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        self.recompute_head_at_current_slot().await;
+        Ok(())
+    }
+
+    async fn replay_blob(self: &Arc<Self>, record: &GossipRecord) -> Result<(), String> {
+        let blob = BlobSidecar::from_ssz_bytes(&record.payload).map_err(|e| format!("{:?}", e))?;
+        let verified = self
+            .verify_blob_sidecar_for_gossip(Arc::new(blob), record.subnet_id)
+            .map_err(|e| format!("{:?}", e))?;
+        self.process_gossip_blob(verified)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.recompute_head_at_current_slot().await;
+        Ok(())
+    }
+
+    fn replay_attestation(&self, record: &GossipRecord) -> Result<(), String> {
+        let attestation = if record.fork_name.electra_enabled() {
+            AttestationElectra::from_ssz_bytes(&record.payload).map(Attestation::Electra)
+        } else {
+            AttestationBase::from_ssz_bytes(&record.payload).map(Attestation::Base)
+        }
+        .map_err(|e| format!("{:?}", e))?;
+
+        let verified = self
+            .verify_unaggregated_attestation_for_gossip(
+                &attestation,
+                Some(SubnetId::new(record.subnet_id)),
+            )
+            .map_err(|e| format!("{:?}", e))?;
+        self.apply_attestation_to_fork_choice(&verified)
+            .map_err(|e| format!("{:?}", e))?;
+        self.add_to_naive_aggregation_pool(&verified)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    fn replay_aggregate(&self, record: &GossipRecord) -> Result<(), String> {
+        let aggregate = if record.fork_name.electra_enabled() {
+            SignedAggregateAndProofElectra::from_ssz_bytes(&record.payload)
+                .map(SignedAggregateAndProof::Electra)
+        } else {
+            SignedAggregateAndProofBase::from_ssz_bytes(&record.payload)
+                .map(SignedAggregateAndProof::Base)
+        }
+        .map_err(|e| format!("{:?}", e))?;
+
+        let verified = self
+            .verify_aggregated_attestation_for_gossip(&aggregate)
+            .map_err(|e| format!("{:?}", e))?;
+        self.apply_attestation_to_fork_choice(&verified)
+            .map_err(|e| format!("{:?}", e))?;
+        self.add_to_block_inclusion_pool(verified)
+            .map_err(|e| format!("{:?}", e))
+    }
+}
@@ -1,6 +1,8 @@
 use crate::BeaconChain;
 use crate::BeaconChainTypes;
-use execution_layer::{http::ENGINE_GET_CLIENT_VERSION_V1, CommitPrefix, ExecutionLayer};
+use execution_layer::{
+    http::ENGINE_GET_CLIENT_VERSION_V1, ClientVersionV1, CommitPrefix, ExecutionLayer,
+};
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, warn, Logger};
 use slot_clock::SlotClock;
@@ -47,8 +49,23 @@ impl Debug for GraffitiOrigin {
     }
 }
 
+/// Whether to append a watermark of the EL & CL client versions to user-specified graffiti, to
+/// help measure client diversity.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraffitiWatermark {
+    /// Leave user-specified graffiti unchanged.
+    #[default]
+    Disabled,
+    /// Append the watermark if there is room for it after the user-specified graffiti, shortening
+    /// the watermark if necessary.
+    Append,
+    /// Always append the full watermark, truncating the user-specified graffiti if necessary.
+    Override,
+}
+
 pub struct GraffitiCalculator<T: BeaconChainTypes> {
     pub beacon_graffiti: GraffitiOrigin,
+    pub watermark: GraffitiWatermark,
     execution_layer: Option<ExecutionLayer<T::EthSpec>>,
     pub epoch_duration: Duration,
     log: Logger,
@@ -57,12 +74,14 @@ pub struct GraffitiCalculator<T: BeaconChainTypes> {
 impl<T: BeaconChainTypes> GraffitiCalculator<T> {
     pub fn new(
         beacon_graffiti: GraffitiOrigin,
+        watermark: GraffitiWatermark,
         execution_layer: Option<ExecutionLayer<T::EthSpec>>,
         epoch_duration: Duration,
         log: Logger,
     ) -> Self {
         Self {
             beacon_graffiti,
+            watermark,
             execution_layer,
             epoch_duration,
             log,
@@ -75,70 +94,162 @@ impl<T: BeaconChainTypes> GraffitiCalculator<T> {
     /// 2. Graffiti specified by the user via beacon node CLI options.
     /// 3. The EL & CL client version string, applicable when the EL supports version specification.
     /// 4. The default lighthouse version string, used if the EL lacks version specification support.
+    ///
+    /// Graffiti from 1 or 2 has a watermark of the client versions appended according to
+    /// `self.watermark`.
     pub async fn get_graffiti(&self, validator_graffiti: Option<Graffiti>) -> Graffiti {
-        if let Some(graffiti) = validator_graffiti {
-            return graffiti;
-        }
-
-        match self.beacon_graffiti {
-            GraffitiOrigin::UserSpecified(graffiti) => graffiti,
-            GraffitiOrigin::Calculated(default_graffiti) => {
-                let Some(execution_layer) = self.execution_layer.as_ref() else {
-                    // Return default graffiti if there is no execution layer. This
-                    // shouldn't occur if we're actually producing blocks.
-                    crit!(self.log, "No execution layer available for graffiti calculation during block production!");
-                    return default_graffiti;
-                };
-
-                // The engine version cache refresh service ensures this will almost always retrieve this data from the
-                // cache instead of making a request to the execution engine. A cache miss would only occur if lighthouse
-                // has recently started or the EL recently went offline.
-                let engine_versions = match execution_layer
-                    .get_engine_version(Some(
-                        self.epoch_duration * ENGINE_VERSION_AGE_LIMIT_EPOCH_MULTIPLE,
-                    ))
-                    .await
-                {
-                    Ok(engine_versions) => engine_versions,
-                    Err(el_error) => {
-                        warn!(self.log, "Failed to determine execution engine version for graffiti"; "error" => ?el_error);
-                        return default_graffiti;
-                    }
-                };
-
-                let Some(engine_version) = engine_versions.first() else {
-                    // Got an empty array which indicates the EL doesn't support the method
-                    debug!(
-                        self.log,
-                        "Using default lighthouse graffiti: EL does not support {} method",
-                        ENGINE_GET_CLIENT_VERSION_V1;
-                    );
-                    return default_graffiti;
-                };
-                if engine_versions.len() != 1 {
-                    // More than one version implies lighthouse is connected to
-                    // an EL multiplexer. We don't support modifying the graffiti
-                    // with these configurations.
-                    warn!(
-                        self.log,
-                        "Execution Engine multiplexer detected, using default graffiti"
-                    );
-                    return default_graffiti;
+        let user_graffiti = validator_graffiti.or(match self.beacon_graffiti {
+            GraffitiOrigin::UserSpecified(graffiti) => Some(graffiti),
+            GraffitiOrigin::Calculated(_) => None,
+        });
+
+        match user_graffiti {
+            Some(graffiti) if self.watermark == GraffitiWatermark::Disabled => graffiti,
+            Some(graffiti) => {
+                let engine_version = self.get_engine_version().await;
+                append_watermark(
+                    graffiti,
+                    engine_version.as_ref(),
+                    &self.lighthouse_commit_prefix(),
+                    self.watermark,
+                )
+            }
+            None => match self.get_engine_version().await {
+                Some(engine_version) => {
+                    engine_version.calculate_graffiti(self.lighthouse_commit_prefix())
                 }
+                None => self.beacon_graffiti.graffiti(),
+            },
+        }
+    }
 
-                let lighthouse_commit_prefix = CommitPrefix::try_from(lighthouse_version::COMMIT_PREFIX.to_string())
-                    .unwrap_or_else(|error_message| {
-                        // This really shouldn't happen but we want to definitly log if it does
-                        crit!(self.log, "Failed to parse lighthouse commit prefix"; "error" => error_message);
-                        CommitPrefix("00000000".to_string())
-                    });
+    /// Returns the version of the execution engine, if it is known and there is exactly one.
+    async fn get_engine_version(&self) -> Option<ClientVersionV1> {
+        let Some(execution_layer) = self.execution_layer.as_ref() else {
+            // Return default graffiti if there is no execution layer. This
+            // shouldn't occur if we're actually producing blocks.
+            crit!(
+                self.log,
+                "No execution layer available for graffiti calculation during block production!"
+            );
+            return None;
+        };
 
-                engine_version.calculate_graffiti(lighthouse_commit_prefix)
+        // The engine version cache refresh service ensures this will almost always retrieve this data from the
+        // cache instead of making a request to the execution engine. A cache miss would only occur if lighthouse
+        // has recently started or the EL recently went offline.
+        let engine_versions = match execution_layer
+            .get_engine_version(Some(
+                self.epoch_duration * ENGINE_VERSION_AGE_LIMIT_EPOCH_MULTIPLE,
+            ))
+            .await
+        {
+            Ok(engine_versions) => engine_versions,
+            Err(el_error) => {
+                warn!(self.log, "Failed to determine execution engine version for graffiti"; "error" => ?el_error);
+                return None;
             }
+        };
+
+        let Some(engine_version) = engine_versions.first() else {
+            // Got an empty array which indicates the EL doesn't support the method
+            debug!(
+                self.log,
+                "Using default lighthouse graffiti: EL does not support {} method",
+                ENGINE_GET_CLIENT_VERSION_V1;
+            );
+            return None;
+        };
+        if engine_versions.len() != 1 {
+            // More than one version implies lighthouse is connected to
+            // an EL multiplexer. We don't support modifying the graffiti
+            // with these configurations.
+            warn!(
+                self.log,
+                "Execution Engine multiplexer detected, using default graffiti"
+            );
+            return None;
         }
+
+        Some(engine_version.clone())
+    }
+
+    fn lighthouse_commit_prefix(&self) -> CommitPrefix {
+        CommitPrefix::try_from(lighthouse_version::COMMIT_PREFIX.to_string()).unwrap_or_else(
+            |error_message| {
+                // This really shouldn't happen but we want to definitly log if it does
+                crit!(self.log, "Failed to parse lighthouse commit prefix"; "error" => error_message);
+                CommitPrefix("00000000".to_string())
+            },
+        )
     }
 }
 
+/// Append a watermark of the EL & CL client versions to user-specified `graffiti`.
+///
+/// The watermark is separated from the graffiti by a space and contains the client codes followed
+/// by the first 4 characters of their commits, e.g. ` GEabcdLHabcd`. The EL is omitted if its
+/// version is unknown. With `GraffitiWatermark::Append` the commits are shortened to 2 characters
+/// or omitted to fit into the unused bytes of the graffiti, and the graffiti is unchanged if there
+/// is no room at all.
+fn append_watermark(
+    graffiti: Graffiti,
+    engine_version: Option<&ClientVersionV1>,
+    lighthouse_commit_prefix: &CommitPrefix,
+    watermark: GraffitiWatermark,
+) -> Graffiti {
+    let mut bytes = graffiti.0;
+    let graffiti_len = bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |last| last + 1);
+    let separator = if graffiti_len == 0 { "" } else { " " };
+
+    let watermark_with_commit_len = |commit_len: usize| {
+        let el = engine_version.map_or_else(String::new, |version| {
+            let commit = version
+                .commit
+                .0
+                .get(..commit_len)
+                .unwrap_or(&version.commit.0);
+            format!("{}{}", version.code, commit.to_lowercase())
+        });
+        let cl = lighthouse_commit_prefix
+            .0
+            .get(..commit_len)
+            .unwrap_or(&lighthouse_commit_prefix.0);
+        format!("{separator}{el}LH{}", cl.to_lowercase())
+    };
+
+    let (watermark_str, start) = match watermark {
+        GraffitiWatermark::Disabled => return graffiti,
+        GraffitiWatermark::Append => {
+            let Some(watermark_str) = [4, 2, 0]
+                .into_iter()
+                .map(watermark_with_commit_len)
+                .find(|watermark_str| graffiti_len + watermark_str.len() <= GRAFFITI_BYTES_LEN)
+            else {
+                return graffiti;
+            };
+            (watermark_str, graffiti_len)
+        }
+        GraffitiWatermark::Override => {
+            let watermark_str = watermark_with_commit_len(4);
+            let start = std::cmp::min(
+                graffiti_len,
+                GRAFFITI_BYTES_LEN.saturating_sub(watermark_str.len()),
+            );
+            (watermark_str, start)
+        }
+    };
+
+    let watermark_bytes = watermark_str.as_bytes();
+    let end = std::cmp::min(start + watermark_bytes.len(), GRAFFITI_BYTES_LEN);
+    bytes[start..end].copy_from_slice(&watermark_bytes[..end - start]);
+    bytes[end..].fill(0);
+    Graffiti::from(bytes)
+}
+
 pub fn start_engine_version_cache_refresh_service<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     executor: TaskExecutor,
@@ -153,7 +264,8 @@ pub fn start_engine_version_cache_refresh_service<T: BeaconChainTypes>(
     if matches!(
         chain.graffiti_calculator.beacon_graffiti,
         GraffitiOrigin::UserSpecified(_)
-    ) {
+    ) && chain.graffiti_calculator.watermark == GraffitiWatermark::Disabled
+    {
         debug!(
             chain.log,
             "Graffiti is user-specified, not starting engine version cache refresh service"
@@ -237,10 +349,11 @@ async fn engine_version_cache_refresh_service<T: BeaconChainTypes>(
 
 #[cfg(test)]
 mod tests {
+    use super::{append_watermark, GraffitiWatermark};
     use crate::test_utils::{test_spec, BeaconChainHarness, EphemeralHarnessType};
     use crate::ChainConfig;
     use execution_layer::test_utils::{DEFAULT_CLIENT_VERSION, DEFAULT_ENGINE_CAPABILITIES};
-    use execution_layer::EngineCapabilities;
+    use execution_layer::{ClientCode, ClientVersionV1, CommitPrefix, EngineCapabilities};
     use lazy_static::lazy_static;
     use slog::info;
     use std::time::Duration;
@@ -374,4 +487,72 @@ mod tests {
             "0x6e6963652067726166666974692062726f000000000000000000000000000000"
         );
     }
+
+    fn graffiti(graffiti_str: &str) -> Graffiti {
+        let mut graffiti_bytes = [0u8; GRAFFITI_BYTES_LEN];
+        graffiti_bytes[..graffiti_str.len()].copy_from_slice(graffiti_str.as_bytes());
+        Graffiti::from(graffiti_bytes)
+    }
+
+    #[test]
+    fn watermark_user_graffiti() {
+        let engine_version = ClientVersionV1 {
+            code: ClientCode::GoEthereum,
+            name: "Geth".to_string(),
+            version: "v1.14.0".to_string(),
+            commit: CommitPrefix("ABCD1234".to_string()),
+        };
+        let commit_prefix = CommitPrefix("5678ef00".to_string());
+        let append = |graffiti_str, watermark| {
+            append_watermark(
+                graffiti(graffiti_str),
+                Some(&engine_version),
+                &commit_prefix,
+                watermark,
+            )
+        };
+
+        // The watermark is shortened to fit after the user graffiti.
+        assert_eq!(
+            append("my pool", GraffitiWatermark::Append),
+            graffiti("my pool GEabcdLH5678")
+        );
+        assert_eq!(
+            append("twenty one bytes long", GraffitiWatermark::Append),
+            graffiti("twenty one bytes long GEabLH56")
+        );
+        assert_eq!(
+            append("twenty six bytes long ....", GraffitiWatermark::Append),
+            graffiti("twenty six bytes long .... GELH")
+        );
+        assert_eq!(
+            append("twenty eight bytes long ....", GraffitiWatermark::Append),
+            graffiti("twenty eight bytes long ....")
+        );
+        assert_eq!(
+            append("", GraffitiWatermark::Append),
+            graffiti("GEabcdLH5678")
+        );
+
+        // Overriding truncates the user graffiti to fit the full watermark.
+        assert_eq!(
+            append("twenty eight bytes long ....", GraffitiWatermark::Override),
+            graffiti("twenty eight bytes  GEabcdLH5678")
+        );
+        assert_eq!(
+            append("my pool", GraffitiWatermark::Disabled),
+            graffiti("my pool")
+        );
+
+        // The EL is omitted if its version is unknown.
+        assert_eq!(
+            append_watermark(
+                graffiti("my pool"),
+                None,
+                &commit_prefix,
+                GraffitiWatermark::Append
+            ),
+            graffiti("my pool LH5678")
+        );
+    }
 }
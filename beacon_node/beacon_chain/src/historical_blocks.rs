@@ -1,4 +1,6 @@
+use crate::beacon_chain::BEACON_CHAIN_DB_KEY;
 use crate::data_availability_checker::AvailableBlock;
+use crate::persisted_beacon_chain::PersistedBeaconChain;
 use crate::{errors::BeaconChainError as Error, metrics, BeaconChain, BeaconChainTypes};
 use itertools::Itertools;
 use slog::debug;
@@ -9,8 +11,11 @@ use state_processing::{
 use std::borrow::Cow;
 use std::iter;
 use std::time::Duration;
-use store::{chunked_vector::BlockRoots, AnchorInfo, BlobInfo, ChunkWriter, KeyValueStore};
-use types::{Hash256, Slot};
+use store::{
+    chunked_vector::BlockRoots, AnchorInfo, BlobInfo, ChunkWriter, HotColdDB, ItemStore,
+    KeyValueStore, KeyValueStoreOp,
+};
+use types::{BlobSidecarList, EthSpec, Hash256, SignedBlindedBeaconBlock, Slot};
 
 /// Use a longer timeout on the pubkey cache.
 ///
@@ -90,69 +95,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Ok(0);
         }
 
-        let n_blobs_lists_to_import = blocks_to_import
-            .iter()
-            .filter(|available_block| available_block.blobs().is_some())
-            .count();
-
-        let mut expected_block_root = anchor_info.oldest_block_parent;
-        let mut prev_block_slot = anchor_info.oldest_block_slot;
-        let mut chunk_writer =
-            ChunkWriter::<BlockRoots, _, _>::new(&self.store.cold_db, prev_block_slot.as_usize())?;
-        let mut new_oldest_blob_slot = blob_info.oldest_blob_slot;
-
-        let mut blob_batch = Vec::with_capacity(n_blobs_lists_to_import);
-        let mut cold_batch = Vec::with_capacity(blocks_to_import.len());
-        let mut hot_batch = Vec::with_capacity(blocks_to_import.len());
-        let mut signed_blocks = Vec::with_capacity(blocks_to_import.len());
-
-        for available_block in blocks_to_import.into_iter().rev() {
-            let (block_root, block, maybe_blobs) = available_block.deconstruct();
-
-            if block_root != expected_block_root {
-                return Err(HistoricalBlockError::MismatchedBlockRoot {
-                    block_root,
-                    expected_block_root,
-                }
-                .into());
-            }
-
-            let blinded_block = block.clone_as_blinded();
-            // Store block in the hot database without payload.
-            self.store
-                .blinded_block_as_kv_store_ops(&block_root, &blinded_block, &mut hot_batch);
-            // Store the blobs too
-            if let Some(blobs) = maybe_blobs {
-                new_oldest_blob_slot = Some(block.slot());
-                self.store
-                    .blobs_as_kv_store_ops(&block_root, blobs, &mut blob_batch);
-            }
-
-            // Store block roots, including at all skip slots in the freezer DB.
-            for slot in (block.slot().as_usize()..prev_block_slot.as_usize()).rev() {
-                chunk_writer.set(slot, block_root, &mut cold_batch)?;
-            }
-
-            prev_block_slot = block.slot();
-            expected_block_root = block.message().parent_root();
-            signed_blocks.push(block);
-
-            // If we've reached genesis, add the genesis block root to the batch for all slots
-            // between 0 and the first block slot, and set the anchor slot to 0 to indicate
-            // completion.
-            if expected_block_root == self.genesis_block_root {
-                let genesis_slot = self.spec.genesis_slot;
-                for slot in genesis_slot.as_usize()..prev_block_slot.as_usize() {
-                    chunk_writer.set(slot, self.genesis_block_root, &mut cold_batch)?;
-                }
-                prev_block_slot = genesis_slot;
-                expected_block_root = Hash256::zero();
-                break;
-            }
-        }
-        chunk_writer.write(&mut cold_batch)?;
-        // these were pushed in reverse order so we reverse again
-        signed_blocks.reverse();
+        let batch = HistoricalBlockBatch::new(
+            &self.store,
+            &anchor_info,
+            self.genesis_block_root,
+            blocks_to_import
+                .into_iter()
+                .map(|available_block| {
+                    let (block_root, block, maybe_blobs) = available_block.deconstruct();
+                    (block_root, block.clone_as_blinded(), maybe_blobs)
+                })
+                .collect(),
+        )?;
+        let signed_blocks = &batch.blocks;
 
         // Verify signatures in one batch, holding the pubkey cache lock for the shortest duration
         // possible. For each block fetch the parent root from its successor. Slicing from index 1
@@ -197,52 +152,172 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         drop(verify_timer);
         drop(sig_timer);
 
+        let new_anchor = batch.write(&self.store, anchor_info, blob_info)?;
+        let backfill_complete = new_anchor.block_backfill_complete(self.genesis_backfill_slot);
+
+        // If backfill has completed and the chain is configured to reconstruct historic states,
+        // send a message to the background migrator instructing it to begin reconstruction.
+        // This can only happen if we have backfilled all the way to genesis.
+        if backfill_complete
+            && self.genesis_backfill_slot == Slot::new(0)
+            && self.config.reconstruct_historic_states
+        {
+            self.store_migrator.process_reconstruction();
+        }
+
+        Ok(num_relevant)
+    }
+}
+
+/// The database operations which store a batch of historical blocks, linked by their parent roots
+/// back from the oldest block in the database.
+pub struct HistoricalBlockBatch<E: EthSpec> {
+    /// The linked blocks, in slot-ascending order.
+    pub blocks: Vec<SignedBlindedBeaconBlock<E>>,
+    hot_batch: Vec<KeyValueStoreOp>,
+    cold_batch: Vec<KeyValueStoreOp>,
+    blob_batch: Vec<KeyValueStoreOp>,
+    oldest_block_slot: Slot,
+    oldest_block_parent: Hash256,
+    oldest_blob_slot: Option<Slot>,
+}
+
+impl<E: EthSpec> HistoricalBlockBatch<E> {
+    /// Link `blocks`, which are sorted by slot and all prior to the anchor, back from the oldest
+    /// block in the database, stopping at the genesis block.
+    ///
+    /// Returns `MismatchedBlockRoot` if any block isn't the parent of its successor. Nothing is
+    /// written until `Self::write` is called.
+    pub fn new<Hot: ItemStore<E>, Cold: ItemStore<E>>(
+        store: &HotColdDB<E, Hot, Cold>,
+        anchor_info: &AnchorInfo,
+        genesis_block_root: Hash256,
+        blocks: Vec<(
+            Hash256,
+            SignedBlindedBeaconBlock<E>,
+            Option<BlobSidecarList<E>>,
+        )>,
+    ) -> Result<Self, Error> {
+        let n_blobs_lists_to_import = blocks
+            .iter()
+            .filter(|(_, _, maybe_blobs)| maybe_blobs.is_some())
+            .count();
+
+        let mut expected_block_root = anchor_info.oldest_block_parent;
+        let mut prev_block_slot = anchor_info.oldest_block_slot;
+        let mut chunk_writer =
+            ChunkWriter::<BlockRoots, _, _>::new(&store.cold_db, prev_block_slot.as_usize())?;
+        let mut oldest_blob_slot = None;
+
+        let mut blob_batch = Vec::with_capacity(n_blobs_lists_to_import);
+        let mut cold_batch = Vec::with_capacity(blocks.len());
+        let mut hot_batch = Vec::with_capacity(blocks.len());
+        let mut linked_blocks = Vec::with_capacity(blocks.len());
+
+        for (block_root, block, maybe_blobs) in blocks.into_iter().rev() {
+            if block_root != expected_block_root {
+                return Err(HistoricalBlockError::MismatchedBlockRoot {
+                    block_root,
+                    expected_block_root,
+                }
+                .into());
+            }
+
+            // Store block in the hot database without payload.
+            store.blinded_block_as_kv_store_ops(&block_root, &block, &mut hot_batch)?;
+            // Store the blobs too
+            if let Some(blobs) = maybe_blobs {
+                oldest_blob_slot = Some(block.slot());
+                store.blobs_as_kv_store_ops(&block_root, blobs, &mut blob_batch)?;
+            }
+
+            // Store block roots, including at all skip slots in the freezer DB.
+            for slot in (block.slot().as_usize()..prev_block_slot.as_usize()).rev() {
+                chunk_writer.set(slot, block_root, &mut cold_batch)?;
+            }
+
+            prev_block_slot = block.slot();
+            expected_block_root = block.message().parent_root();
+            linked_blocks.push(block);
+
+            // If we've reached genesis, add the genesis block root to the batch for all slots
+            // between 0 and the first block slot, and set the anchor slot to 0 to indicate
+            // completion.
+            if expected_block_root == genesis_block_root {
+                let genesis_slot = store.get_chain_spec().genesis_slot;
+                for slot in genesis_slot.as_usize()..prev_block_slot.as_usize() {
+                    chunk_writer.set(slot, genesis_block_root, &mut cold_batch)?;
+                }
+                prev_block_slot = genesis_slot;
+                expected_block_root = Hash256::zero();
+                break;
+            }
+        }
+        chunk_writer.write(&mut cold_batch)?;
+        // these were pushed in reverse order so we reverse again
+        linked_blocks.reverse();
+
+        Ok(Self {
+            blocks: linked_blocks,
+            hot_batch,
+            cold_batch,
+            blob_batch,
+            oldest_block_slot: prev_block_slot,
+            oldest_block_parent: expected_block_root,
+            oldest_blob_slot,
+        })
+    }
+
+    /// Write the batch to `store`, then move the anchor and the oldest blob slot back to the
+    /// oldest block in the batch.
+    ///
+    /// Returns the new anchor.
+    pub fn write<Hot: ItemStore<E>, Cold: ItemStore<E>>(
+        self,
+        store: &HotColdDB<E, Hot, Cold>,
+        anchor_info: AnchorInfo,
+        blob_info: BlobInfo,
+    ) -> Result<AnchorInfo, Error> {
         // Write the I/O batches to disk, writing the blocks themselves first, as it's better
         // for the hot DB to contain extra blocks than for the cold DB to point to blocks that
         // do not exist.
-        self.store.blobs_db.do_atomically(blob_batch)?;
-        self.store.hot_db.do_atomically(hot_batch)?;
-        self.store.cold_db.do_atomically(cold_batch)?;
+        store.blobs_db.do_atomically(self.blob_batch)?;
+        store.hot_db.do_atomically(self.hot_batch)?;
+        store.cold_db.do_atomically(self.cold_batch)?;
 
         let mut anchor_and_blob_batch = Vec::with_capacity(2);
 
         // Update the blob info.
-        if new_oldest_blob_slot != blob_info.oldest_blob_slot {
-            if let Some(oldest_blob_slot) = new_oldest_blob_slot {
+        if let Some(oldest_blob_slot) = self.oldest_blob_slot {
+            if blob_info.oldest_blob_slot != Some(oldest_blob_slot) {
                 let new_blob_info = BlobInfo {
                     oldest_blob_slot: Some(oldest_blob_slot),
                     ..blob_info.clone()
                 };
-                anchor_and_blob_batch.push(
-                    self.store
-                        .compare_and_set_blob_info(blob_info, new_blob_info)?,
-                );
+                anchor_and_blob_batch
+                    .push(store.compare_and_set_blob_info(blob_info, new_blob_info)?);
             }
         }
 
         // Update the anchor.
         let new_anchor = AnchorInfo {
-            oldest_block_slot: prev_block_slot,
-            oldest_block_parent: expected_block_root,
-            ..anchor_info
+            oldest_block_slot: self.oldest_block_slot,
+            oldest_block_parent: self.oldest_block_parent,
+            ..anchor_info.clone()
         };
-        let backfill_complete = new_anchor.block_backfill_complete(self.genesis_backfill_slot);
-        anchor_and_blob_batch.push(
-            self.store
-                .compare_and_set_anchor_info(Some(anchor_info), Some(new_anchor))?,
-        );
-        self.store.hot_db.do_atomically(anchor_and_blob_batch)?;
+        anchor_and_blob_batch
+            .push(store.compare_and_set_anchor_info(Some(anchor_info), Some(new_anchor.clone()))?);
+        store.hot_db.do_atomically(anchor_and_blob_batch)?;
 
-        // If backfill has completed and the chain is configured to reconstruct historic states,
-        // send a message to the background migrator instructing it to begin reconstruction.
-        // This can only happen if we have backfilled all the way to genesis.
-        if backfill_complete
-            && self.genesis_backfill_slot == Slot::new(0)
-            && self.config.reconstruct_historic_states
-        {
-            self.store_migrator.process_reconstruction();
-        }
-
-        Ok(num_relevant)
+        Ok(new_anchor)
     }
 }
+
+/// Load the root of the genesis block from the beacon chain persisted in `store`.
+pub fn persisted_genesis_block_root<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: &HotColdDB<E, Hot, Cold>,
+) -> Result<Option<Hash256>, Error> {
+    Ok(store
+        .get_item::<PersistedBeaconChain>(&BEACON_CHAIN_DB_KEY)?
+        .map(|persisted_chain| persisted_chain.genesis_block_root))
+}
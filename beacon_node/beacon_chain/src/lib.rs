@@ -18,6 +18,7 @@ pub mod builder;
 pub mod canonical_head;
 pub mod capella_readiness;
 pub mod chain_config;
+pub mod chain_health;
 pub mod data_availability_checker;
 pub mod data_column_verification;
 pub mod deneb_readiness;
@@ -29,7 +30,10 @@ mod eth1_finalization_cache;
 pub mod events;
 pub mod execution_payload;
 pub mod fork_choice_signal;
+pub mod fork_readiness;
 pub mod fork_revert;
+pub mod gossip_arrival_times;
+pub mod gossip_recorder;
 pub mod graffiti_calculator;
 mod head_tracker;
 pub mod historical_blocks;
@@ -40,6 +44,7 @@ mod light_client_server_cache;
 pub mod metrics;
 pub mod migrate;
 mod naive_aggregation_pool;
+pub mod non_finality;
 pub mod observed_aggregates;
 mod observed_attesters;
 mod observed_blob_sidecars;
@@ -49,11 +54,14 @@ mod observed_slashable;
 pub mod otb_verification_service;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
+mod persisted_observed_caches;
 mod pre_finalization_cache;
 pub mod proposer_prep_service;
+pub mod reconstructed_payload_cache;
 pub mod schema_change;
 pub mod shuffling_cache;
 pub mod state_advance_timer;
+pub mod state_root_pool;
 pub mod sync_committee_rewards;
 pub mod sync_committee_verification;
 pub mod test_utils;
@@ -82,7 +90,7 @@ pub use block_verification::{
 pub use block_verification_types::AvailabilityPendingExecutedBlock;
 pub use block_verification_types::ExecutedBlock;
 pub use canonical_head::{CachedHead, CanonicalHead, CanonicalHeadRwLock};
-pub use eth1_chain::{Eth1Chain, Eth1ChainBackend};
+pub use eth1_chain::{DepositSnapshotImport, Eth1Chain, Eth1ChainBackend};
 pub use events::ServerSentEventHandler;
 pub use execution_layer::EngineState;
 pub use execution_payload::NotifyExecutionLayer;
@@ -75,6 +75,18 @@ lazy_static! {
         "beacon_block_processing_state_root_seconds",
         "Time spent calculating the state root when processing a block."
     );
+    pub static ref STATE_ROOT_POOL_QUEUED: Result<IntGauge> = try_create_int_gauge(
+        "beacon_state_root_pool_queued_total",
+        "Number of state root jobs queued or running on the state root thread pool."
+    );
+    pub static ref STATE_ROOT_POOL_OVERFLOW: Result<IntCounter> = try_create_int_counter(
+        "beacon_state_root_pool_overflow_total",
+        "Count of state roots computed outside the state root pool because it was at capacity."
+    );
+    pub static ref GOSSIP_RECORDER_DROPPED: Result<IntCounter> = try_create_int_counter(
+        "beacon_gossip_recorder_dropped_total",
+        "Count of gossip records dropped because the recording writer was falling behind."
+    );
     pub static ref BLOCK_PROCESSING_POST_EXEC_PROCESSING: Result<Histogram> = try_create_histogram_with_buckets(
         "beacon_block_processing_post_exec_pre_attestable_seconds",
         "Time between finishing execution processing and the block becoming attestable",
@@ -276,6 +288,10 @@ lazy_static! {
         "beacon_attestation_processing_batch_unagg_signature_times",
         "Time spent on the signature verification of batch unaggregate attestation processing"
     );
+    pub static ref ATTESTATION_PROCESSING_BATCH_UNAGG_SIGNATURE_FAILURES: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_processing_batch_unagg_signature_failures_total",
+        "Count of batches of unaggregated attestations which failed signature verification and were split"
+    );
 
     /*
      * Shuffling cache
@@ -289,6 +305,39 @@ lazy_static! {
     pub static ref SHUFFLING_CACHE_PROMISE_FAILS: Result<IntCounter> =
         try_create_int_counter("beacon_shuffling_cache_promise_fails_total", "Count of times shuffling cache detects a failed promise");
 
+    /*
+     * Chain health
+     */
+    pub static ref CHAIN_HEALTH_DEGRADED: Result<IntGauge> = try_create_int_gauge(
+        "beacon_chain_health_degraded",
+        "Set to 1 whilst the chain is degraded and degraded-mode behaviours are active"
+    );
+
+    /*
+     * Non-finality
+     */
+    pub static ref NON_FINALITY_PROLONGED: Result<IntGauge> = try_create_int_gauge(
+        "beacon_non_finality_prolonged",
+        "Set to 1 whilst the epochs since finalization exceed the degraded-mode threshold"
+    );
+    pub static ref HOT_DB_GROWTH_PER_EPOCH: Result<IntGauge> = try_create_int_gauge(
+        "beacon_hot_db_growth_bytes_per_epoch",
+        "Estimated growth of the hot database per epoch, in bytes"
+    );
+    pub static ref EMERGENCY_PRUNING_DELETED_STATES: Result<IntCounter> = try_create_int_counter(
+        "beacon_emergency_pruning_deleted_states_total",
+        "Count of states of non-viable forks deleted by emergency pruning"
+    );
+
+    /*
+     * Abandoned fork pruning
+     */
+    pub static ref ABANDONED_FORK_PRUNED_BYTES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_abandoned_fork_pruned_bytes_total",
+        "Bytes reclaimed by deleting the blocks, payloads and blobs of forks abandoned by finalization",
+        &["type"]
+    );
+
     /*
      * Early attester cache
      */
@@ -296,6 +345,10 @@ lazy_static! {
         "beacon_early_attester_cache_hits",
         "Count of times the early attester cache returns an attestation"
     );
+    pub static ref BEACON_EARLY_ATTESTER_CACHE_INVALIDATIONS: Result<IntCounter> = try_create_int_counter(
+        "beacon_early_attester_cache_invalidations_total",
+        "Count of blocks removed from the early attester cache because they failed to import"
+    );
 
     pub static ref BEACON_REQRESP_PRE_IMPORT_CACHE_SIZE: Result<IntGauge> = try_create_int_gauge(
         "beacon_reqresp_pre_import_cache_size",
@@ -305,6 +358,14 @@ lazy_static! {
         "beacon_reqresp_pre_import_cache_hits",
         "Count of times the reqresp pre import cache returns an item"
     );
+    pub static ref BEACON_RECONSTRUCTED_PAYLOAD_CACHE_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_reconstructed_payload_cache_hits_total",
+        "Count of times a reconstructed execution payload is served from the cache"
+    );
+    pub static ref BEACON_RECONSTRUCTED_PAYLOAD_CACHE_MISSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_reconstructed_payload_cache_misses_total",
+        "Count of times a reconstructed execution payload is not found in the cache"
+    );
 }
 
 // Second lazy-static block is used to account for macro recursion limit.
@@ -401,6 +462,10 @@ lazy_static! {
         try_create_histogram("beacon_persist_head", "Time taken to persist the canonical head");
     pub static ref PERSIST_OP_POOL: Result<Histogram> =
         try_create_histogram("beacon_persist_op_pool", "Time taken to persist the operations pool");
+    pub static ref PERSIST_OBSERVED_CACHES: Result<Histogram> = try_create_histogram(
+        "beacon_persist_observed_caches",
+        "Time taken to persist the observed block producer, attester and aggregator caches"
+    );
     pub static ref PERSIST_ETH1_CACHE: Result<Histogram> =
         try_create_histogram("beacon_persist_eth1_cache", "Time taken to persist the eth1 caches");
     pub static ref PERSIST_FORK_CHOICE: Result<Histogram> =
@@ -412,6 +477,18 @@ lazy_static! {
     pub static ref DEFAULT_ETH1_VOTES: Result<IntCounter> =
         try_create_int_counter("beacon_eth1_default_votes", "Count of times we have voted default value for eth1 data");
 
+    /*
+     * State Advance
+     */
+    pub static ref STATE_ADVANCE_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_state_advance_seconds",
+        "Time taken to advance the head state into the next slot ahead of the slot boundary"
+    );
+    pub static ref STATE_ADVANCE_TOO_SLOW: Result<IntCounter> = try_create_int_counter(
+        "beacon_state_advance_too_slow_total",
+        "Count of state advances which did not complete before the slot they were preparing for"
+    );
+
     /*
      * Chain Head
      */
@@ -477,6 +554,8 @@ lazy_static! {
         try_create_int_gauge("beacon_op_pool_voluntary_exits_total", "Count of voluntary exits in the op pool");
     pub static ref OP_POOL_NUM_SYNC_CONTRIBUTIONS: Result<IntGauge> =
         try_create_int_gauge("beacon_op_pool_sync_contributions_total", "Count of sync contributions in the op pool");
+    pub static ref OP_POOL_NUM_BLS_TO_EXECUTION_CHANGES: Result<IntGauge> =
+        try_create_int_gauge("beacon_op_pool_bls_to_execution_changes_total", "Count of BLS to execution changes in the op pool");
 
 
     /*
@@ -490,6 +569,14 @@ lazy_static! {
         "beacon_attn_observation_epoch_aggregators",
         "Count of aggregators that have been seen by the beacon chain in the previous epoch"
     );
+    pub static ref ATTN_OBSERVATION_ATTESTERS_ALLOCATED: Result<IntGauge> = try_create_int_gauge(
+        "beacon_attn_observation_attesters_allocated",
+        "Number of validator indices allocated across all epochs of the observed attesters cache"
+    );
+    pub static ref ATTN_OBSERVATION_AGGREGATORS_ALLOCATED: Result<IntGauge> = try_create_int_gauge(
+        "beacon_attn_observation_aggregators_allocated",
+        "Number of validator indices allocated across all epochs of the observed aggregators cache"
+    );
 
     /*
      * Sync Committee Observation Metrics
@@ -743,6 +830,11 @@ lazy_static! {
         "validator_monitor_validators_total",
         "Count of validators that are specifically monitored by this beacon node"
     );
+    pub static ref VALIDATOR_MONITOR_INDIVIDUAL_TRACKING: Result<IntGauge> = try_create_int_gauge(
+        "validator_monitor_individual_tracking",
+        "Set to 1 if metrics are being collected for each monitored validator, or 0 if only \
+        aggregate metrics are being collected"
+    );
     pub static ref VALIDATOR_MONITOR_UNAGGREGATED_ATTESTATION_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
         "validator_monitor_unaggregated_attestation_total",
         "Number of unaggregated attestations seen",
@@ -846,6 +938,12 @@ lazy_static! {
     /*
      * Block Delay Metrics
      */
+    pub static ref BEACON_GOSSIP_ARRIVAL_DELAY_SECONDS: Result<HistogramVec> = try_create_histogram_vec_with_buckets(
+        "beacon_gossip_arrival_delay_seconds",
+        "Duration between the start of the slot and the arrival of a valid gossip message for that slot",
+        Ok(vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.75, 1.0, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0, 3.5, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 12.0]),
+        &["kind"]
+    );
     pub static ref BEACON_BLOCK_DELAY_TOTAL: Result<IntGauge> = try_create_int_gauge(
         "beacon_block_delay_total",
         "Duration between the start of the block's slot and the time when it was set as head.",
@@ -998,6 +1096,18 @@ lazy_static! {
         "beacon_sync_committee_message_processing_signature_seconds",
         "Time spent on the signature verification of sync message processing"
     );
+    pub static ref SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_SETUP_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_sync_committee_message_processing_batch_signature_setup_seconds",
+        "Time spent on setting up for the signature verification of batch sync message processing"
+    );
+    pub static ref SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_sync_committee_message_processing_batch_signature_seconds",
+        "Time spent on the signature verification of batch sync message processing"
+    );
+    pub static ref SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_FAILURES: Result<IntCounter> = try_create_int_counter(
+        "beacon_sync_committee_message_processing_batch_signature_failures_total",
+        "Count of subcommittee batches of sync messages which failed signature verification and were split"
+    );
 
     /*
      * Checkpoint sync & backfill
@@ -1044,6 +1154,14 @@ lazy_static! {
         "beacon_blobs_sidecar_gossip_verification_seconds",
         "Full runtime of blob sidecars gossip verification"
     );
+    pub static ref BLOBS_SIDECAR_GOSSIP_EQUIVOCATIONS: Result<IntCounter> = try_create_int_counter(
+        "beacon_blobs_sidecar_gossip_equivocations_total",
+        "Number of conflicting blob sidecars for the same slot, proposer and index seen on gossip"
+    );
+    pub static ref BLOCK_EQUIVOCATIONS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_equivocations_total",
+        "Number of conflicting blocks for the same slot and proposer seen on gossip or via RPC"
+    );
     pub static ref BLOB_SIDECAR_INCLUSION_PROOF_VERIFICATION: Result<Histogram> = try_create_histogram(
         "blob_sidecar_inclusion_proof_verification_seconds",
         "Time taken to verify blob sidecar inclusion proof"
@@ -1140,6 +1258,10 @@ lazy_static! {
         "Number of non-finalized blocks missed",
         &["validator"]
     );
+    pub static ref VALIDATOR_MONITOR_ALERTS_DROPPED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "validator_monitor_alerts_dropped_total",
+        "Number of validator monitor alerts dropped because the queue was full"
+    );
 
     /*
     * Kzg related metrics
@@ -1197,6 +1319,137 @@ lazy_static! {
     );
 }
 
+/// A metric which has a series for each individually tracked validator.
+#[derive(Clone, Copy)]
+pub enum ValidatorMonitorMetric {
+    IntGauge(&'static Result<IntGaugeVec>),
+    IntCounter(&'static Result<IntCounterVec>),
+    Histogram(&'static Result<HistogramVec>),
+}
+
+impl ValidatorMonitorMetric {
+    /// Remove the series with the given `label_values`, if it exists.
+    pub fn remove_label_values(self, label_values: &[&str]) {
+        match self {
+            Self::IntGauge(metric) => remove_label_values(metric, label_values),
+            Self::IntCounter(metric) => remove_label_values(metric, label_values),
+            Self::Histogram(metric) => remove_label_values(metric, label_values),
+        }
+    }
+}
+
+/// The validator monitor metrics which are labelled with `["validator"]`.
+///
+/// Any new per-validator metric must be added here so that its series are removed when the monitor
+/// stops tracking validators individually.
+pub fn validator_monitor_individual_metrics() -> Vec<ValidatorMonitorMetric> {
+    vec![
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_BALANCE_GWEI),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_EFFECTIVE_BALANCE_GWEI),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_SLASHED),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_ACTIVE),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_EXITED),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_WITHDRAWABLE),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_ACTIVATION_ELIGIBILITY_EPOCH),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_ACTIVATION_EPOCH),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_EXIT_EPOCH),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_WITHDRAWABLE_EPOCH),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_ATTESTER_HIT),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_ATTESTER_MISS),
+        ValidatorMonitorMetric::IntCounter(
+            &VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_HEAD_ATTESTER_HIT,
+        ),
+        ValidatorMonitorMetric::IntCounter(
+            &VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_HEAD_ATTESTER_MISS,
+        ),
+        ValidatorMonitorMetric::IntCounter(
+            &VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_TARGET_ATTESTER_HIT,
+        ),
+        ValidatorMonitorMetric::IntCounter(
+            &VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_TARGET_ATTESTER_MISS,
+        ),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_INCLUSION_DISTANCE),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATIONS_TOTAL),
+        ValidatorMonitorMetric::Histogram(
+            &VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATIONS_MIN_DELAY_SECONDS,
+        ),
+        ValidatorMonitorMetric::IntGauge(
+            &VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_AGGREGATE_INCLUSIONS,
+        ),
+        ValidatorMonitorMetric::IntGauge(
+            &VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_BLOCK_INCLUSIONS,
+        ),
+        ValidatorMonitorMetric::IntGauge(
+            &VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_BLOCK_MIN_INCLUSION_DISTANCE,
+        ),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_PREV_EPOCH_BEACON_BLOCKS_TOTAL),
+        ValidatorMonitorMetric::Histogram(
+            &VALIDATOR_MONITOR_PREV_EPOCH_BEACON_BLOCKS_MIN_DELAY_SECONDS,
+        ),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_PREV_EPOCH_AGGREGATES_TOTAL),
+        ValidatorMonitorMetric::Histogram(
+            &VALIDATOR_MONITOR_PREV_EPOCH_AGGREGATES_MIN_DELAY_SECONDS,
+        ),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_PREV_EPOCH_EXITS_TOTAL),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_PREV_EPOCH_PROPOSER_SLASHINGS_TOTAL),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_PREV_EPOCH_ATTESTER_SLASHINGS_TOTAL),
+        ValidatorMonitorMetric::IntGauge(
+            &VALIDATOR_MONITOR_PREV_EPOCH_SYNC_COMMITTEE_MESSAGES_TOTAL,
+        ),
+        ValidatorMonitorMetric::Histogram(
+            &VALIDATOR_MONITOR_PREV_EPOCH_SYNC_COMMITTEE_MESSAGES_MIN_DELAY_SECONDS,
+        ),
+        ValidatorMonitorMetric::IntGauge(
+            &VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTION_INCLUSIONS,
+        ),
+        ValidatorMonitorMetric::IntGauge(
+            &VALIDATOR_MONITOR_PREV_EPOCH_SYNC_SIGNATURE_BLOCK_INCLUSIONS,
+        ),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTIONS_TOTAL),
+        ValidatorMonitorMetric::Histogram(
+            &VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTION_MIN_DELAY_SECONDS,
+        ),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_VALIDATOR_IN_CURRENT_SYNC_COMMITTEE),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_MISSED_BLOCKS_TOTAL),
+    ]
+}
+
+/// The validator monitor metrics which are labelled with `["src", "validator"]`.
+///
+/// Any new per-validator metric must be added here so that its series are removed when the monitor
+/// stops tracking validators individually.
+pub fn validator_monitor_individual_src_metrics() -> Vec<ValidatorMonitorMetric> {
+    vec![
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_UNAGGREGATED_ATTESTATION_TOTAL),
+        ValidatorMonitorMetric::Histogram(
+            &VALIDATOR_MONITOR_UNAGGREGATED_ATTESTATION_DELAY_SECONDS,
+        ),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGES_TOTAL),
+        ValidatorMonitorMetric::Histogram(&VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGES_DELAY_SECONDS),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_SYNC_CONTRIBUTIONS_TOTAL),
+        ValidatorMonitorMetric::Histogram(&VALIDATOR_MONITOR_SYNC_CONTRIBUTIONS_DELAY_SECONDS),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_AGGREGATED_ATTESTATION_TOTAL),
+        ValidatorMonitorMetric::Histogram(&VALIDATOR_MONITOR_AGGREGATED_ATTESTATION_DELAY_SECONDS),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_ATTESTATION_IN_AGGREGATE_TOTAL),
+        ValidatorMonitorMetric::IntCounter(
+            &VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGE_IN_CONTRIBUTION_TOTAL,
+        ),
+        ValidatorMonitorMetric::Histogram(
+            &VALIDATOR_MONITOR_ATTESTATION_IN_AGGREGATE_DELAY_SECONDS,
+        ),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_ATTESTATION_IN_BLOCK_TOTAL),
+        ValidatorMonitorMetric::IntCounter(
+            &VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGE_IN_BLOCK_TOTAL,
+        ),
+        ValidatorMonitorMetric::IntGauge(&VALIDATOR_MONITOR_ATTESTATION_IN_BLOCK_DELAY_SLOTS),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_BEACON_BLOCK_TOTAL),
+        ValidatorMonitorMetric::Histogram(&VALIDATOR_MONITOR_BEACON_BLOCK_DELAY_SECONDS),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_EXIT_TOTAL),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_PROPOSER_SLASHING_TOTAL),
+        ValidatorMonitorMetric::IntCounter(&VALIDATOR_MONITOR_ATTESTER_SLASHING_TOTAL),
+    ]
+}
+
 /// Scrape the `beacon_chain` for metrics that are not constantly updated (e.g., the present slot,
 /// head state info, etc) and update the Prometheus `DEFAULT_REGISTRY`.
 pub fn scrape_for_metrics<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) {
@@ -1266,6 +1519,10 @@ pub fn scrape_for_metrics<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>) {
         &OP_POOL_NUM_SYNC_CONTRIBUTIONS,
         beacon_chain.op_pool.num_sync_contributions(),
     );
+    set_gauge_by_usize(
+        &OP_POOL_NUM_BLS_TO_EXECUTION_CHANGES,
+        beacon_chain.op_pool.num_bls_to_execution_changes(),
+    );
 
     beacon_chain
         .validator_monitor
@@ -1376,6 +1633,15 @@ fn scrape_attestation_observation<T: BeaconChainTypes>(slot_now: Slot, chain: &B
     {
         set_gauge_by_usize(&ATTN_OBSERVATION_PREV_EPOCH_AGGREGATORS, count);
     }
+
+    set_gauge_by_usize(
+        &ATTN_OBSERVATION_ATTESTERS_ALLOCATED,
+        chain.observed_gossip_attesters.read().allocated_len(),
+    );
+    set_gauge_by_usize(
+        &ATTN_OBSERVATION_AGGREGATORS_ALLOCATED,
+        chain.observed_aggregators.read().allocated_len(),
+    );
 }
 
 fn scrape_sync_committee_observation<T: BeaconChainTypes>(slot_now: Slot, chain: &BeaconChain<T>) {
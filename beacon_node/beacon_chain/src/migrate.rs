@@ -1,6 +1,7 @@
 use crate::beacon_chain::BEACON_CHAIN_DB_KEY;
 use crate::errors::BeaconChainError;
 use crate::head_tracker::{HeadTracker, SszHeadTracker};
+use crate::metrics;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use parking_lot::Mutex;
 use slog::{debug, error, info, warn, Logger};
@@ -95,6 +96,14 @@ pub enum PruningOutcome {
     DeferredConcurrentHeadTrackerMutation,
 }
 
+/// Bytes reclaimed from the database by pruning abandoned forks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReclaimedBytes {
+    pub blocks: u64,
+    pub payloads: u64,
+    pub blobs: u64,
+}
+
 /// Logic errors that can occur during pruning, none of these should ever happen.
 #[derive(Debug)]
 pub enum PruningError {
@@ -367,6 +376,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             }
         };
 
+        // Prune block history if the node is configured to keep only recent history.
+        if let Err(e) = db.try_prune_history() {
+            warn!(log, "Block history pruning failed"; "error" => ?e);
+        }
+
         // Finally, compact the database so that new free space is properly reclaimed.
         if let Err(e) = Self::run_compaction(
             db,
@@ -649,6 +663,13 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             }
         }
 
+        // Strip the payloads and blobs of abandoned blocks before deciding whether the blocks
+        // themselves can be deleted. They make up the bulk of an abandoned fork's size and none of
+        // these blocks can ever become canonical, so they are deleted even if pruning is deferred
+        // below. The blinded blocks are left in place, so any of these blocks which remain in the
+        // head tracker are still present in the database.
+        let mut reclaimed = Self::strip_abandoned_payloads(&store, &abandoned_blocks)?;
+
         // Update the head tracker before the database, so that we maintain the invariant
         // that a block present in the head tracker is present in the database.
         // See https://github.com/sigp/lighthouse/issues/1557
@@ -668,22 +689,20 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             head_tracker_lock.remove(&head_hash);
         }
 
-        let mut batch: Vec<StoreOp<E>> = abandoned_blocks
-            .into_iter()
-            .map(Into::into)
-            .flat_map(|block_root: Hash256| {
-                [
-                    StoreOp::DeleteBlock(block_root),
-                    StoreOp::DeleteExecutionPayload(block_root),
-                    StoreOp::DeleteBlobs(block_root),
-                ]
-            })
-            .chain(
-                abandoned_states
-                    .into_iter()
-                    .map(|(slot, state_hash)| StoreOp::DeleteState(state_hash.into(), Some(slot))),
-            )
-            .collect();
+        // Blinded blocks are kept as evidence of slashable proposals if configured.
+        let mut batch: Vec<StoreOp<E>> = vec![];
+        if !store.get_config().retain_abandoned_blocks {
+            for block_root in abandoned_blocks.into_iter().map(Hash256::from) {
+                reclaimed.blocks += store.block_size(&block_root)?.unwrap_or(0) as u64;
+                batch.push(StoreOp::DeleteBlock(block_root));
+            }
+        }
+        let deleted_states = abandoned_states.len();
+        batch.extend(
+            abandoned_states
+                .into_iter()
+                .map(|(slot, state_hash)| StoreOp::DeleteState(state_hash.into(), Some(slot))),
+        );
 
         // Persist the head in case the process is killed or crashes here. This prevents
         // the head tracker reverting after our mutation above.
@@ -704,17 +723,66 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
 
         store.do_atomically_with_block_and_blobs_cache(batch)?;
 
+        metrics::inc_counter_vec_by(
+            &metrics::ABANDONED_FORK_PRUNED_BYTES,
+            &["block"],
+            reclaimed.blocks,
+        );
+
         // Do a quick separate pass to delete obsoleted hot states, usually pre-states from the state
         // advance which are not canonical due to blocks being applied on top.
         store.prune_old_hot_states()?;
 
-        debug!(log, "Database pruning complete");
+        debug!(
+            log,
+            "Database pruning complete";
+            "block_bytes" => reclaimed.blocks,
+            "payload_bytes" => reclaimed.payloads,
+            "blob_bytes" => reclaimed.blobs,
+            "deleted_states" => deleted_states,
+        );
 
         Ok(PruningOutcome::Successful {
             old_finalized_checkpoint,
         })
     }
 
+    /// Delete the execution payloads and blobs of `abandoned_blocks`, leaving the blinded blocks
+    /// in place.
+    fn strip_abandoned_payloads(
+        store: &HotColdDB<E, Hot, Cold>,
+        abandoned_blocks: &HashSet<SignedBeaconBlockHash>,
+    ) -> Result<ReclaimedBytes, BeaconChainError> {
+        let mut reclaimed = ReclaimedBytes::default();
+        let mut batch = vec![];
+
+        for &block_root in abandoned_blocks {
+            let block_root = Hash256::from(block_root);
+            if let Some(size) = store.execution_payload_size(&block_root)? {
+                reclaimed.payloads += size as u64;
+                batch.push(StoreOp::DeleteExecutionPayload(block_root));
+            }
+            if let Some(size) = store.blobs_size(&block_root)? {
+                reclaimed.blobs += size as u64;
+                batch.push(StoreOp::DeleteBlobs(block_root));
+            }
+        }
+
+        store.do_atomically_with_block_and_blobs_cache(batch)?;
+
+        metrics::inc_counter_vec_by(
+            &metrics::ABANDONED_FORK_PRUNED_BYTES,
+            &["payload"],
+            reclaimed.payloads,
+        );
+        metrics::inc_counter_vec_by(
+            &metrics::ABANDONED_FORK_PRUNED_BYTES,
+            &["blobs"],
+            reclaimed.blobs,
+        );
+        Ok(reclaimed)
+    }
+
     /// Compact the database if it has been more than `COMPACTION_PERIOD_SECONDS` since it
     /// was last compacted.
     pub fn run_compaction(
@@ -0,0 +1,260 @@
+//! Surfaces the growth of the hot database during long periods of non-finality.
+//!
+//! Until the chain finalizes, the hot database keeps a full state for every epoch boundary of every
+//! fork and grows without bound. Once per epoch the size of the hot database is sampled and its
+//! growth rate estimated. Whilst more than `ChainConfig::degraded_epochs_since_finalization` epochs
+//! have passed since finalization, a warning including the growth rate is logged each epoch.
+//!
+//! If `ChainConfig::emergency_pruning_threshold` is set and the hot database grows beyond it
+//! whilst the chain isn't finalizing, the full states of forks which aren't viable for the head
+//! are deleted. Their state summaries are kept and removed as normal upon finalization. The
+//! deleted states can't be loaded again, so the pruned forks can no longer be built upon.
+use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes};
+use directory::size_of_dir;
+use parking_lot::{Mutex, RwLockWriteGuard};
+use slog::{debug, error, warn};
+use slot_clock::SlotClock;
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use store::StoreOp;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::{Epoch, EthSpec, Hash256};
+
+/// The number of samples of the hot database size used to estimate its growth rate.
+pub const HOT_DB_SIZE_SAMPLES: usize = 8;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Holds recent samples of the size of the hot database.
+#[derive(Default)]
+pub struct NonFinalityMonitor {
+    samples: Mutex<VecDeque<(Epoch, u64)>>,
+}
+
+impl NonFinalityMonitor {
+    /// Records the size of the hot database at `epoch`, returning the estimated growth in bytes
+    /// per epoch if enough samples have been recorded.
+    fn record_hot_db_size(&self, epoch: Epoch, size: u64) -> Option<i64> {
+        let mut samples = self.samples.lock();
+        if samples
+            .back()
+            .map_or(true, |(last_epoch, _)| *last_epoch < epoch)
+        {
+            samples.push_back((epoch, size));
+        }
+        while samples.len() > HOT_DB_SIZE_SAMPLES {
+            samples.pop_front();
+        }
+
+        let (first_epoch, first_size) = samples.front()?;
+        let (last_epoch, last_size) = samples.back()?;
+        let epochs = last_epoch.saturating_sub(*first_epoch).as_u64();
+        (epochs > 0).then(|| (*last_size as i64 - *first_size as i64) / epochs as i64)
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Records the size of the hot database, warns if the chain isn't finalizing and runs
+    /// emergency pruning if the hot database exceeds `ChainConfig::emergency_pruning_threshold`.
+    /// Intended to be called once per epoch.
+    pub fn update_non_finality(&self, hot_db_size: u64) -> Result<(), BeaconChainError> {
+        let current_epoch = self.epoch()?;
+        let epochs_since_finalization = current_epoch
+            .saturating_sub(
+                self.canonical_head
+                    .cached_head()
+                    .finalized_checkpoint()
+                    .epoch,
+            )
+            .as_u64();
+
+        let growth_per_epoch = self
+            .non_finality
+            .record_hot_db_size(current_epoch, hot_db_size);
+        if let Some(growth_per_epoch) = growth_per_epoch {
+            metrics::set_gauge(&metrics::HOT_DB_GROWTH_PER_EPOCH, growth_per_epoch);
+        }
+
+        let prolonged = epochs_since_finalization > self.config.degraded_epochs_since_finalization;
+        metrics::set_gauge(&metrics::NON_FINALITY_PROLONGED, prolonged as i64);
+        if !prolonged {
+            return Ok(());
+        }
+
+        let threshold = self.config.emergency_pruning_threshold;
+        let epochs_until_emergency_pruning = threshold
+            .zip(growth_per_epoch)
+            .filter(|(_, growth_per_epoch)| *growth_per_epoch > 0)
+            .map(|(threshold, growth_per_epoch)| {
+                threshold.saturating_sub(hot_db_size) / growth_per_epoch as u64
+            });
+        warn!(
+            self.log,
+            "Chain is not finalizing";
+            "info" => "the hot database will grow until the chain finalizes",
+            "epochs_since_finalization" => epochs_since_finalization,
+            "hot_db_size_mb" => hot_db_size / BYTES_PER_MB,
+            "growth_per_epoch_mb" => growth_per_epoch.map(|bytes| bytes / BYTES_PER_MB as i64),
+            "epochs_until_emergency_pruning" => epochs_until_emergency_pruning,
+        );
+
+        if let Some(threshold) = threshold.filter(|threshold| hot_db_size >= *threshold) {
+            let num_deleted_states = self.prune_non_viable_fork_states()?;
+            warn!(
+                self.log,
+                "Emergency pruning of non-viable forks";
+                "info" => "the hot database exceeds the emergency pruning threshold",
+                "hot_db_size_mb" => hot_db_size / BYTES_PER_MB,
+                "threshold_mb" => threshold / BYTES_PER_MB,
+                "deleted_states" => num_deleted_states,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the full epoch boundary states of forks which aren't viable for the head, keeping
+    /// their summaries. Returns the number of states deleted.
+    ///
+    /// The heads of the pruned forks are removed from the head tracker, so that pruning upon
+    /// finalization doesn't attempt to load their states.
+    pub fn prune_non_viable_fork_states(&self) -> Result<usize, BeaconChainError> {
+        let current_slot = self.slot()?;
+        let head_block_root = self.canonical_head.cached_head().head_block_root();
+        let heads = self.head_tracker.heads();
+
+        let (non_viable_heads, non_viable_blocks) = {
+            let fork_choice = self.canonical_head.fork_choice_read_lock();
+            let proto_array = fork_choice.proto_array().core_proto_array();
+
+            // Heads which are unknown to fork choice are left to be pruned upon finalization.
+            let mut viable_heads = vec![head_block_root];
+            let mut non_viable_heads = vec![];
+            for (head, _) in heads {
+                if head == head_block_root || !fork_choice.contains_block(&head) {
+                    continue;
+                }
+                if proto_array
+                    .block_leads_to_viable_head::<T::EthSpec>(&head, current_slot)
+                    .map_err(|e| BeaconChainError::ForkChoiceError(e.into()))?
+                {
+                    viable_heads.push(head);
+                } else {
+                    non_viable_heads.push(head);
+                }
+            }
+
+            let mut viable_blocks = HashSet::new();
+            for head in viable_heads {
+                for (block_root, _) in proto_array.iter_block_roots(&head) {
+                    if !viable_blocks.insert(block_root) {
+                        break;
+                    }
+                }
+            }
+
+            let mut non_viable_blocks = HashSet::<Hash256>::new();
+            for head in &non_viable_heads {
+                for (block_root, _) in proto_array.iter_block_roots(head) {
+                    if viable_blocks.contains(&block_root) || !non_viable_blocks.insert(block_root)
+                    {
+                        break;
+                    }
+                }
+            }
+
+            (non_viable_heads, non_viable_blocks)
+        };
+
+        if non_viable_heads.is_empty() {
+            return Ok(0);
+        }
+
+        let mut batch = self
+            .store
+            .epoch_boundary_state_deletion_ops(&non_viable_blocks)?;
+        let num_deleted_states = batch.len();
+
+        // Hold the head tracker lock until it has been persisted, as in
+        // `persist_head_and_fork_choice`.
+        let mut head_tracker = self.head_tracker.0.write();
+        for head in &non_viable_heads {
+            head_tracker.remove(head);
+        }
+        let head_tracker = RwLockWriteGuard::downgrade(head_tracker);
+        batch.push(StoreOp::KeyValueOp(
+            self.persist_head_in_batch(&head_tracker),
+        ));
+        self.store.do_atomically_with_block_and_blobs_cache(batch)?;
+        drop(head_tracker);
+
+        metrics::inc_counter_by(
+            &metrics::EMERGENCY_PRUNING_DELETED_STATES,
+            num_deleted_states as u64,
+        );
+        debug!(
+            self.log,
+            "Pruned non-viable forks";
+            "heads" => non_viable_heads.len(),
+            "blocks" => non_viable_blocks.len(),
+            "deleted_states" => num_deleted_states,
+        );
+
+        Ok(num_deleted_states)
+    }
+}
+
+/// Spawns a routine which samples the size of the hot database at `hot_db_path` once per epoch and
+/// calls `BeaconChain::update_non_finality`.
+pub fn start_non_finality_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+    hot_db_path: PathBuf,
+) {
+    executor.clone().spawn(
+        async move { non_finality_service(executor, chain, hot_db_path).await },
+        "non_finality_service",
+    );
+}
+
+async fn non_finality_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+    hot_db_path: PathBuf,
+) {
+    let slot_duration = chain.slot_clock.slot_duration();
+
+    loop {
+        match chain
+            .slot_clock
+            .duration_to_next_epoch(T::EthSpec::slots_per_epoch())
+        {
+            Some(duration) => {
+                // Avoid contending with block import at the start of the epoch.
+                sleep(duration + slot_duration / 2).await;
+
+                let inner_chain = chain.clone();
+                let hot_db_path = hot_db_path.clone();
+                executor.spawn_blocking(
+                    move || {
+                        if let Err(e) = inner_chain.update_non_finality(size_of_dir(&hot_db_path)) {
+                            error!(
+                                inner_chain.log,
+                                "Failed to update non-finality monitor";
+                                "error" => ?e
+                            );
+                        }
+                    },
+                    "non_finality_update",
+                );
+            }
+            None => {
+                error!(chain.log, "Failed to read slot clock");
+                // If we can't read the slot clock, just wait another slot.
+                sleep(slot_duration).await;
+            }
+        }
+    }
+}
@@ -16,6 +16,7 @@
 
 use crate::types::consts::altair::TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE;
 use bitvec::vec::BitVec;
+use ssz_derive::{Decode, Encode};
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::marker::PhantomData;
@@ -38,7 +39,7 @@ pub const MAX_CACHED_EPOCHS: u64 = 4;
 pub type ObservedAttesters<E> = AutoPruningEpochContainer<EpochBitfield, E>;
 pub type ObservedSyncContributors<E> =
     AutoPruningSlotContainer<SlotSubcommitteeIndex, Hash256, SyncContributorSlotHashSet<E>, E>;
-pub type ObservedAggregators<E> = AutoPruningEpochContainer<EpochHashSet, E>;
+pub type ObservedAggregators<E> = AutoPruningEpochContainer<EpochBitfield, E>;
 pub type ObservedSyncAggregators<E> =
     AutoPruningSlotContainer<SlotSubcommitteeIndex, (), SyncAggregatorSlotHashSet, E>;
 
@@ -78,10 +79,17 @@ pub trait Item<T> {
 
     /// Returns `Some(T)` if there is an entry for `validator_index`.
     fn get(&self, validator_index: usize) -> Option<T>;
+
+    /// Remove all entries from `self`, retaining its allocation for re-use.
+    fn clear(&mut self);
 }
 
-/// Stores a `BitVec` that represents which validator indices have attested or sent sync committee
-/// signatures during an epoch.
+/// Stores a `BitVec` that represents which validator indices have attested or aggregated during an
+/// epoch.
+///
+/// A bitfield uses one bit per validator, which is more compact than a `HashSet` of indices once
+/// more than ~1/64th of validators are present. This holds for aggregators on large networks as
+/// well as attesters.
 pub struct EpochBitfield {
     bitfield: BitVec,
 }
@@ -103,7 +111,7 @@ impl Item<()> for EpochBitfield {
     }
 
     fn validator_count(&self) -> usize {
-        self.bitfield.iter().filter(|bit| **bit).count()
+        self.bitfield.count_ones()
     }
 
     fn insert(&mut self, validator_index: usize, _value: ()) -> bool {
@@ -133,45 +141,52 @@ impl Item<()> for EpochBitfield {
             .map_or(false, |bit| *bit)
             .then_some(())
     }
-}
 
-/// Stores a `HashSet` of which validator indices have created an aggregate during an
-/// epoch.
-pub struct EpochHashSet {
-    set: HashSet<usize>,
+    fn clear(&mut self) {
+        self.bitfield.fill(false);
+    }
 }
 
-impl Item<()> for EpochHashSet {
-    fn with_capacity(capacity: usize) -> Self {
-        Self {
-            set: HashSet::with_capacity(capacity),
+impl EpochBitfield {
+    /// Packs the bitfield into bytes, with the lowest validator index in the least significant
+    /// bit of the first byte.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; self.bitfield.len().div_ceil(8)];
+        for validator_index in self.bitfield.iter_ones() {
+            if let Some(byte) = bytes.get_mut(validator_index / 8) {
+                *byte |= 1 << (validator_index % 8);
+            }
         }
+        bytes
     }
 
-    /// Defaults to the target number of aggregators per committee (16) multiplied by the expected
-    /// max committee count (64).
-    fn default_capacity() -> usize {
-        16 * 64
-    }
-
-    fn len(&self) -> usize {
-        self.set.len()
-    }
-
-    fn validator_count(&self) -> usize {
-        self.set.len()
+    /// The inverse of `Self::to_bytes`.
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let mut bitfield = BitVec::repeat(false, bytes.len() * 8);
+        for (byte_index, byte) in bytes.iter().enumerate() {
+            for bit in 0..8 {
+                if (byte >> bit) & 1 == 1 {
+                    bitfield.set(byte_index * 8 + bit, true);
+                }
+            }
+        }
+        Self { bitfield }
     }
+}
 
-    /// Inserts the `validator_index` in the set. Returns `true` if the `validator_index` was
-    /// already in the set.
-    fn insert(&mut self, validator_index: usize, _value: ()) -> bool {
-        !self.set.insert(validator_index)
-    }
+/// An SSZ-encodable representation of a single epoch of an `AutoPruningEpochContainer`.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct SszEpochBitfield {
+    epoch: Epoch,
+    bitfield: Vec<u8>,
+}
 
-    /// Returns `true` if the `validator_index` is in the set.
-    fn get(&self, validator_index: usize) -> Option<()> {
-        self.set.contains(&validator_index).then_some(())
-    }
+/// An SSZ-encodable representation of an `AutoPruningEpochContainer<EpochBitfield, _>`, used to
+/// persist the observed attesters and aggregators across restarts.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq)]
+pub struct SszEpochContainer {
+    lowest_permissible_epoch: Epoch,
+    epochs: Vec<SszEpochBitfield>,
 }
 
 /// Stores a `HashSet` of which validator indices have created a sync aggregate during a
@@ -214,6 +229,10 @@ impl<E: EthSpec> Item<Hash256> for SyncContributorSlotHashSet<E> {
     fn get(&self, validator_index: usize) -> Option<Hash256> {
         self.map.get(&validator_index).copied()
     }
+
+    fn clear(&mut self) {
+        self.map.clear();
+    }
 }
 
 /// Stores a `HashSet` of which validator indices have created a sync aggregate during a
@@ -252,6 +271,10 @@ impl Item<()> for SyncAggregatorSlotHashSet {
     fn get(&self, validator_index: usize) -> Option<()> {
         self.set.contains(&validator_index).then_some(())
     }
+
+    fn clear(&mut self) {
+        self.set.clear();
+    }
 }
 
 /// A container that stores some number of `T` items.
@@ -261,10 +284,15 @@ impl Item<()> for SyncAggregatorSlotHashSet {
 /// `Self::max_capacity == 32` and an attestation with `a.data.target.epoch` is supplied, then all
 /// attestations with an epoch prior to `a.data.target.epoch - 32` will be cleared from the cache.
 ///
-/// `T` should be set to a `EpochBitfield` or `EpochHashSet`.
+/// Items are rotated incrementally: when an epoch is pruned its item is cleared and kept aside to
+/// be re-used by the next new epoch, avoiding a fresh allocation every epoch.
+///
+/// `T` should be set to a `EpochBitfield`.
 pub struct AutoPruningEpochContainer<T, E: EthSpec> {
     lowest_permissible_epoch: Epoch,
     items: HashMap<Epoch, T>,
+    /// A pruned item, retained so that its allocation can be re-used.
+    recycled: Option<T>,
     _phantom: PhantomData<E>,
 }
 
@@ -273,6 +301,7 @@ impl<T, E: EthSpec> Default for AutoPruningEpochContainer<T, E> {
         Self {
             lowest_permissible_epoch: Epoch::new(0),
             items: HashMap::new(),
+            recycled: None,
             _phantom: PhantomData,
         }
     }
@@ -298,20 +327,24 @@ impl<T: Item<()>, E: EthSpec> AutoPruningEpochContainer<T, E> {
         if let Some(item) = self.items.get_mut(&epoch) {
             Ok(item.insert(validator_index, ()))
         } else {
-            // To avoid re-allocations, try and determine a rough initial capacity for the new item
-            // by obtaining the mean size of all items in earlier epoch.
-            let (count, sum) = self
-                .items
-                .iter()
-                // Only include epochs that are less than the given slot in the average. This should
-                // generally avoid including recent epochs that are still "filling up".
-                .filter(|(item_epoch, _item)| **item_epoch < epoch)
-                .map(|(_epoch, item)| item.len())
-                .fold((0, 0), |(count, sum), len| (count + 1, sum + len));
-
-            let initial_capacity = sum.checked_div(count).unwrap_or_else(T::default_capacity);
-
-            let mut item = T::with_capacity(initial_capacity);
+            let mut item = if let Some(item) = self.recycled.take() {
+                // Re-use the allocation of a pruned epoch.
+                item
+            } else {
+                // To avoid re-allocations, try and determine a rough initial capacity for the new
+                // item by obtaining the mean size of all items in earlier epoch.
+                let (count, sum) = self
+                    .items
+                    .iter()
+                    // Only include epochs that are less than the given slot in the average. This
+                    // should generally avoid including recent epochs that are still "filling up".
+                    .filter(|(item_epoch, _item)| **item_epoch < epoch)
+                    .map(|(_epoch, item)| item.len())
+                    .fold((0, 0), |(count, sum), len| (count + 1, sum + len));
+
+                let initial_capacity = sum.checked_div(count).unwrap_or_else(T::default_capacity);
+                T::with_capacity(initial_capacity)
+            };
             item.insert(validator_index, ());
             self.items.insert(epoch, item);
 
@@ -347,6 +380,15 @@ impl<T: Item<()>, E: EthSpec> AutoPruningEpochContainer<T, E> {
         self.items.get(&epoch).map(|item| item.validator_count())
     }
 
+    /// Returns the total allocated size of all items in `self`, measured by validator indices.
+    pub fn allocated_len(&self) -> usize {
+        self.items
+            .values()
+            .chain(self.recycled.iter())
+            .map(|item| item.len())
+            .sum()
+    }
+
     fn sanitize_request(&self, epoch: Epoch, validator_index: usize) -> Result<(), Error> {
         if validator_index > E::ValidatorRegistryLimit::to_usize() {
             return Err(Error::ValidatorIndexTooHigh(validator_index));
@@ -379,8 +421,20 @@ impl<T: Item<()>, E: EthSpec> AutoPruningEpochContainer<T, E> {
 
         self.lowest_permissible_epoch = lowest_permissible_epoch;
 
-        self.items
-            .retain(|epoch, _item| *epoch >= lowest_permissible_epoch);
+        let pruned_epochs = self
+            .items
+            .keys()
+            .filter(|epoch| **epoch < lowest_permissible_epoch)
+            .copied()
+            .collect::<Vec<_>>();
+        for epoch in pruned_epochs {
+            if let Some(mut item) = self.items.remove(&epoch) {
+                if self.recycled.is_none() {
+                    item.clear();
+                    self.recycled = Some(item);
+                }
+            }
+        }
     }
 
     #[allow(dead_code)]
@@ -400,6 +454,40 @@ impl<T: Item<()>, E: EthSpec> AutoPruningEpochContainer<T, E> {
     }
 }
 
+impl<E: EthSpec> AutoPruningEpochContainer<EpochBitfield, E> {
+    /// Returns an SSZ-encodable representation of `self`, for persistence to disk.
+    pub fn as_ssz_container(&self) -> SszEpochContainer {
+        let mut epochs = self
+            .items
+            .iter()
+            .map(|(epoch, item)| SszEpochBitfield {
+                epoch: *epoch,
+                bitfield: item.to_bytes(),
+            })
+            .collect::<Vec<_>>();
+        epochs.sort_by_key(|item| item.epoch);
+
+        SszEpochContainer {
+            lowest_permissible_epoch: self.lowest_permissible_epoch,
+            epochs,
+        }
+    }
+
+    /// Restores `Self` from a container produced by `Self::as_ssz_container`.
+    pub fn from_ssz_container(container: &SszEpochContainer) -> Self {
+        Self {
+            lowest_permissible_epoch: container.lowest_permissible_epoch,
+            items: container
+                .epochs
+                .iter()
+                .map(|item| (item.epoch, EpochBitfield::from_bytes(&item.bitfield)))
+                .collect(),
+            recycled: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
 /// A container that stores some number of `V` items.
 ///
 /// This container is "auto-pruning" since it gets an idea of the current slot by which
@@ -619,9 +707,69 @@ impl SlotSubcommitteeIndex {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ssz::{Decode, Encode};
 
     type E = types::MainnetEthSpec;
 
+    #[test]
+    fn epoch_items_are_recycled() {
+        let mut store: ObservedAttesters<E> = <_>::default();
+        let validator_index = 10_000;
+
+        for epoch in 0..MAX_CACHED_EPOCHS {
+            assert_eq!(
+                store.observe_validator(Epoch::new(epoch), validator_index),
+                Ok(false)
+            );
+        }
+        let allocated_len = store.allocated_len();
+
+        // Each new epoch re-uses the cleared item of the epoch it prunes, so the allocation does
+        // not grow and no observations leak between epochs.
+        for epoch in MAX_CACHED_EPOCHS..MAX_CACHED_EPOCHS * 4 {
+            assert_eq!(
+                store.observe_validator(Epoch::new(epoch), validator_index),
+                Ok(false)
+            );
+            assert_eq!(store.observed_validator_count(Epoch::new(epoch)), Some(1));
+            assert_eq!(store.allocated_len(), allocated_len);
+        }
+    }
+
+    #[test]
+    fn epoch_container_ssz_roundtrip() {
+        let mut store: ObservedAttesters<E> = <_>::default();
+        let observed = [(5, 0), (5, 7), (5, 8), (6, 1_000), (7, 63)];
+        for (epoch, validator_index) in observed {
+            assert_eq!(
+                store.observe_validator(Epoch::new(epoch), validator_index),
+                Ok(false)
+            );
+        }
+
+        let container = store.as_ssz_container();
+        let restored = ObservedAttesters::<E>::from_ssz_container(
+            &SszEpochContainer::from_ssz_bytes(&container.as_ssz_bytes()).unwrap(),
+        );
+
+        assert_eq!(restored.as_ssz_container(), container);
+        assert_eq!(
+            restored.get_lowest_permissible(),
+            store.get_lowest_permissible()
+        );
+        for epoch in 4..8 {
+            assert_eq!(
+                restored.observed_validator_count(Epoch::new(epoch)),
+                store.observed_validator_count(Epoch::new(epoch))
+            );
+        }
+        for (epoch, validator_index) in observed {
+            assert!(restored.index_seen_at_epoch(validator_index, Epoch::new(epoch)));
+        }
+        assert!(!restored.index_seen_at_epoch(1, Epoch::new(5)));
+        assert!(!restored.index_seen_at_epoch(7, Epoch::new(6)));
+    }
+
     #[test]
     fn value_storage() {
         type Container = AutoPruningSlotContainer<Slot, Hash256, SyncContributorSlotHashSet<E>, E>;
@@ -2,11 +2,14 @@
 //! that we have already seen over the gossip network.
 //! Only `BlobSidecar`s that have completed proposer signature verification can be added
 //! to this cache to reduce DoS risks.
+//!
+//! The cache also records the block root of each observed sidecar so that conflicting sidecars
+//! for the same `(slot, proposer, index)` can be identified as proposer equivocations.
 
 use crate::observed_block_producers::ProposalKey;
 use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
-use types::{BlobSidecar, EthSpec, Slot};
+use types::{BlobSidecar, EthSpec, Hash256, Slot};
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -19,6 +22,32 @@ pub enum Error {
     InvalidBlobIndex(u64),
 }
 
+/// The outcome of observing a `BlobSidecar`.
+#[derive(Debug, PartialEq)]
+pub enum SeenBlobSidecar {
+    /// A sidecar with the same index and block root has already been observed, or an equivocation
+    /// has already been recorded for this index.
+    Duplicate,
+    /// A sidecar with the same index but a different block root has already been observed. The
+    /// proposer has signed two conflicting block headers for this slot.
+    Equivocation { previous_block_root: Hash256 },
+    /// This is the first sidecar observed for this index.
+    Unique,
+}
+
+impl SeenBlobSidecar {
+    pub fn proposer_previously_observed(&self) -> bool {
+        match self {
+            Self::Duplicate | Self::Equivocation { .. } => true,
+            Self::Unique => false,
+        }
+    }
+
+    pub fn is_equivocation(&self) -> bool {
+        matches!(self, Self::Equivocation { .. })
+    }
+}
+
 /// Maintains a cache of seen `BlobSidecar`s that are received over gossip
 /// and have been gossip verified.
 ///
@@ -29,8 +58,12 @@ pub enum Error {
 /// like checking the proposer signature.
 pub struct ObservedBlobSidecars<E: EthSpec> {
     finalized_slot: Slot,
-    /// Stores all received blob indices for a given `(ValidatorIndex, Slot)` tuple.
-    items: HashMap<ProposalKey, HashSet<u64>>,
+    /// Stores the block roots of all received blobs, by blob index, for a given
+    /// `(ValidatorIndex, Slot)` tuple.
+    ///
+    /// At most two block roots are stored per index: once an equivocation has been observed there
+    /// is nothing to gain from tracking further conflicting sidecars.
+    items: HashMap<ProposalKey, HashMap<u64, HashSet<Hash256>>>,
     _phantom: PhantomData<E>,
 }
 
@@ -50,22 +83,43 @@ impl<E: EthSpec> ObservedBlobSidecars<E> {
     /// This will update `self` so future calls to it indicate that this `blob_sidecar` is known.
     ///
     /// The supplied `blob_sidecar` **MUST** have completed proposer signature verification.
-    pub fn observe_sidecar(&mut self, blob_sidecar: &BlobSidecar<E>) -> Result<bool, Error> {
+    pub fn observe_sidecar(
+        &mut self,
+        blob_sidecar: &BlobSidecar<E>,
+    ) -> Result<SeenBlobSidecar, Error> {
         self.sanitize_blob_sidecar(blob_sidecar)?;
 
-        let blob_indices = self
+        let block_root = blob_sidecar.block_root();
+        let block_roots = self
             .items
             .entry(ProposalKey {
                 slot: blob_sidecar.slot(),
                 proposer: blob_sidecar.block_proposer_index(),
             })
-            .or_insert_with(|| HashSet::with_capacity(E::max_blobs_per_block()));
-        let did_not_exist = blob_indices.insert(blob_sidecar.index);
+            .or_insert_with(|| HashMap::with_capacity(E::max_blobs_per_block()))
+            .entry(blob_sidecar.index)
+            .or_default();
 
-        Ok(!did_not_exist)
+        if block_roots.contains(&block_root) || block_roots.len() > 1 {
+            return Ok(SeenBlobSidecar::Duplicate);
+        }
+
+        let previous_block_root = block_roots.iter().next().copied();
+        block_roots.insert(block_root);
+
+        Ok(match previous_block_root {
+            Some(previous_block_root) => SeenBlobSidecar::Equivocation {
+                previous_block_root,
+            },
+            None => SeenBlobSidecar::Unique,
+        })
     }
 
-    /// Returns `true` if the `blob_sidecar` has already been observed in the cache within the prune window.
+    /// Returns `true` if the `blob_sidecar` has already been observed in the cache within the prune
+    /// window, or if an equivocation has already been observed for its index.
+    ///
+    /// A sidecar which conflicts with a single previously observed sidecar is *not* considered
+    /// known, so that it can be signature verified and identified as an equivocation.
     pub fn proposer_is_known(&self, blob_sidecar: &BlobSidecar<E>) -> Result<bool, Error> {
         self.sanitize_blob_sidecar(blob_sidecar)?;
         let is_known = self
@@ -74,8 +128,9 @@ impl<E: EthSpec> ObservedBlobSidecars<E> {
                 slot: blob_sidecar.slot(),
                 proposer: blob_sidecar.block_proposer_index(),
             })
-            .map_or(false, |blob_indices| {
-                blob_indices.contains(&blob_sidecar.index)
+            .and_then(|blob_indices| blob_indices.get(&blob_sidecar.index))
+            .map_or(false, |block_roots| {
+                block_roots.contains(&blob_sidecar.block_root()) || block_roots.len() > 1
             });
         Ok(is_known)
     }
@@ -136,7 +191,7 @@ mod tests {
 
         assert_eq!(
             cache.observe_sidecar(&sidecar_a),
-            Ok(false),
+            Ok(SeenBlobSidecar::Unique),
             "can observe proposer, indicates proposer unobserved"
         );
 
@@ -221,7 +276,7 @@ mod tests {
 
         assert_eq!(
             cache.observe_sidecar(&block_b),
-            Ok(false),
+            Ok(SeenBlobSidecar::Unique),
             "can insert non-finalized block"
         );
 
@@ -277,7 +332,7 @@ mod tests {
 
         assert_eq!(
             cache.observe_sidecar(&sidecar_a),
-            Ok(false),
+            Ok(SeenBlobSidecar::Unique),
             "can observe proposer, indicates proposer unobserved"
         );
 
@@ -289,7 +344,7 @@ mod tests {
 
         assert_eq!(
             cache.observe_sidecar(&sidecar_a),
-            Ok(true),
+            Ok(SeenBlobSidecar::Duplicate),
             "observing again indicates true"
         );
 
@@ -317,7 +372,7 @@ mod tests {
         );
         assert_eq!(
             cache.observe_sidecar(&sidecar_b),
-            Ok(false),
+            Ok(SeenBlobSidecar::Unique),
             "can observe proposer for new slot, indicates proposer unobserved"
         );
         assert_eq!(
@@ -327,7 +382,7 @@ mod tests {
         );
         assert_eq!(
             cache.observe_sidecar(&sidecar_b),
-            Ok(true),
+            Ok(SeenBlobSidecar::Duplicate),
             "observing slot 1 again indicates true"
         );
 
@@ -362,7 +417,7 @@ mod tests {
         );
         assert_eq!(
             cache.observe_sidecar(&sidecar_c),
-            Ok(false),
+            Ok(SeenBlobSidecar::Unique),
             "can observe new index, indicates sidecar unobserved for new index"
         );
         assert_eq!(
@@ -372,7 +427,7 @@ mod tests {
         );
         assert_eq!(
             cache.observe_sidecar(&sidecar_c),
-            Ok(true),
+            Ok(SeenBlobSidecar::Duplicate),
             "observing new sidecar again indicates true"
         );
 
@@ -400,13 +455,25 @@ mod tests {
         sidecar_d.signed_block_header.message.body_root = Hash256::repeat_byte(7);
         assert_eq!(
             cache.proposer_is_known(&sidecar_d),
-            Ok(true),
-            "there has been an observation for this proposer index"
+            Ok(false),
+            "a conflicting sidecar is not known until it has been observed"
         );
         assert_eq!(
             cache.observe_sidecar(&sidecar_d),
+            Ok(SeenBlobSidecar::Equivocation {
+                previous_block_root: sidecar_c.block_root()
+            }),
+            "indicates the sidecar is an equivocation"
+        );
+        assert_eq!(
+            cache.proposer_is_known(&sidecar_d),
             Ok(true),
-            "indicates sidecar proposer was observed"
+            "observed equivocating sidecar is indicated as true"
+        );
+        assert_eq!(
+            cache.observe_sidecar(&sidecar_d),
+            Ok(SeenBlobSidecar::Duplicate),
+            "observing the equivocating sidecar again indicates a duplicate"
         );
         let cached_blob_indices = cache
             .items
@@ -417,6 +484,28 @@ mod tests {
             2,
             "two blob indices should be present in slot 0"
         );
+        assert_eq!(
+            cached_blob_indices
+                .get(&sidecar_c.index)
+                .expect("index should be present")
+                .len(),
+            2,
+            "two block roots should be present for the equivocating index"
+        );
+
+        // A third conflicting sidecar is treated as a duplicate, the equivocation is already known.
+        let mut sidecar_e = sidecar_d.clone();
+        sidecar_e.signed_block_header.message.body_root = Hash256::repeat_byte(8);
+        assert_eq!(
+            cache.proposer_is_known(&sidecar_e),
+            Ok(true),
+            "an equivocation has already been observed for this index"
+        );
+        assert_eq!(
+            cache.observe_sidecar(&sidecar_e),
+            Ok(SeenBlobSidecar::Duplicate),
+            "further conflicting sidecars are not recorded"
+        );
 
         // Try adding an out of bounds index
         let invalid_index = E::max_blobs_per_block() as u64;
@@ -1,6 +1,7 @@
 //! Provides the `ObservedBlockProducers` struct which allows for rejecting gossip blocks from
 //! validators that have already produced a block.
 
+use ssz_derive::{Decode, Encode};
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
@@ -55,21 +56,42 @@ impl<E: EthSpec> Default for ObservedBlockProducers<E> {
     }
 }
 
+/// An SSZ-encodable representation of the proposals observed for a single `ProposalKey`.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct SszProposal {
+    slot: Slot,
+    proposer: u64,
+    block_roots: Vec<Hash256>,
+}
+
+/// An SSZ-encodable representation of `ObservedBlockProducers`, used to persist the cache across
+/// restarts.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq)]
+pub struct SszObservedBlockProducers {
+    finalized_slot: Slot,
+    proposals: Vec<SszProposal>,
+}
+
+#[derive(Debug, PartialEq)]
 pub enum SeenBlock {
     Duplicate,
-    Slashable,
+    /// The proposer has signed more than one block for this slot.
+    Slashable {
+        /// The root of one of the other blocks observed for this slot and proposer.
+        previous_block_root: Hash256,
+    },
     UniqueNonSlashable,
 }
 
 impl SeenBlock {
     pub fn proposer_previously_observed(self) -> bool {
         match self {
-            Self::Duplicate | Self::Slashable => true,
+            Self::Duplicate | Self::Slashable { .. } => true,
             Self::UniqueNonSlashable => false,
         }
     }
     pub fn is_slashable(&self) -> bool {
-        matches!(self, Self::Slashable)
+        matches!(self, Self::Slashable { .. })
     }
 }
 
@@ -102,10 +124,13 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
                 let block_roots = occupied_entry.get_mut();
                 let newly_inserted = block_roots.insert(block_root);
 
-                let is_equivocation = block_roots.len() > 1;
-
-                if is_equivocation {
-                    SeenBlock::Slashable
+                if let Some(previous_block_root) = block_roots
+                    .iter()
+                    .find(|previous_root| **previous_root != block_root)
+                {
+                    SeenBlock::Slashable {
+                        previous_block_root: *previous_block_root,
+                    }
                 } else if !newly_inserted {
                     SeenBlock::Duplicate
                 } else {
@@ -123,6 +148,27 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
         Ok(slashable_proposal)
     }
 
+    /// Observe the `block` as `Self::observe_proposal` does, also returning `true` if `block_root`
+    /// had not been observed for this slot and proposer before.
+    ///
+    /// This allows an equivocation to be handled once, even though every later observation of
+    /// either block is `SeenBlock::Slashable`.
+    pub fn observe_proposal_root(
+        &mut self,
+        block_root: Hash256,
+        block: BeaconBlockRef<'_, E>,
+    ) -> Result<(SeenBlock, bool), Error> {
+        let newly_observed = self
+            .items
+            .get(&ProposalKey {
+                slot: block.slot(),
+                proposer: block.proposer_index(),
+            })
+            .map_or(true, |block_roots| !block_roots.contains(&block_root));
+        let seen_block = self.observe_proposal(block_root, block)?;
+        Ok((seen_block, newly_observed))
+    }
+
     /// Returns `Ok(true)` if the `block` has been observed before, `Ok(false)` if not. Does not
     /// update the cache, so calling this function multiple times will continue to return
     /// `Ok(false)`, until `Self::observe_proposer` is called.
@@ -145,11 +191,14 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
 
         if let Some(block_roots) = self.items.get(&key) {
             let block_already_known = block_roots.contains(&block_root);
-            let no_prev_known_blocks =
-                block_roots.difference(&HashSet::from([block_root])).count() == 0;
 
-            if !no_prev_known_blocks {
-                Ok(SeenBlock::Slashable)
+            if let Some(previous_block_root) = block_roots
+                .iter()
+                .find(|previous_root| **previous_root != block_root)
+            {
+                Ok(SeenBlock::Slashable {
+                    previous_block_root: *previous_block_root,
+                })
             } else if block_already_known {
                 Ok(SeenBlock::Duplicate)
             } else {
@@ -200,11 +249,53 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
             key.slot.epoch(E::slots_per_epoch()) == epoch && key.proposer == validator_index
         })
     }
+
+    /// Returns an SSZ-encodable representation of `self`, for persistence to disk.
+    pub fn as_ssz_container(&self) -> SszObservedBlockProducers {
+        let mut proposals = self
+            .items
+            .iter()
+            .map(|(key, block_roots)| {
+                let mut block_roots = block_roots.iter().copied().collect::<Vec<_>>();
+                block_roots.sort();
+                SszProposal {
+                    slot: key.slot,
+                    proposer: key.proposer,
+                    block_roots,
+                }
+            })
+            .collect::<Vec<_>>();
+        proposals.sort_by_key(|proposal| (proposal.slot, proposal.proposer));
+
+        SszObservedBlockProducers {
+            finalized_slot: self.finalized_slot,
+            proposals,
+        }
+    }
+
+    /// Restores `Self` from a container produced by `Self::as_ssz_container`.
+    pub fn from_ssz_container(container: &SszObservedBlockProducers) -> Self {
+        Self {
+            finalized_slot: container.finalized_slot,
+            items: container
+                .proposals
+                .iter()
+                .map(|proposal| {
+                    (
+                        ProposalKey::new(proposal.proposer, proposal.slot),
+                        proposal.block_roots.iter().copied().collect(),
+                    )
+                })
+                .collect(),
+            _phantom: PhantomData,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ssz::{Decode, Encode};
     use types::{BeaconBlock, MainnetEthSpec};
 
     type E = MainnetEthSpec;
@@ -533,4 +624,120 @@ mod tests {
             "only one proposer should be present in slot 1"
         );
     }
+
+    #[test]
+    fn ssz_roundtrip() {
+        let mut cache = ObservedBlockProducers::default();
+
+        let block_a = get_block(1, 0);
+        let mut block_b = get_block(1, 0);
+        *block_b.state_root_mut() = Hash256::repeat_byte(1);
+        let block_c = get_block(2, 3);
+        for block in [&block_a, &block_b, &block_c] {
+            cache
+                .observe_proposal(block.canonical_root(), block.to_ref())
+                .expect("can observe proposal");
+        }
+
+        let container = cache.as_ssz_container();
+        let bytes = container.as_ssz_bytes();
+        let restored = ObservedBlockProducers::<E>::from_ssz_container(
+            &SszObservedBlockProducers::from_ssz_bytes(&bytes).unwrap(),
+        );
+
+        assert_eq!(restored.as_ssz_container(), container);
+        assert_eq!(restored.finalized_slot, cache.finalized_slot);
+        assert!(restored
+            .proposer_has_been_observed(block_a.to_ref(), block_a.canonical_root())
+            .unwrap()
+            .is_slashable());
+        assert!(restored
+            .proposer_has_been_observed(block_c.to_ref(), block_c.canonical_root())
+            .unwrap()
+            .proposer_previously_observed());
+        assert!(!restored.index_seen_at_epoch(1, Epoch::new(0)));
+    }
+
+    #[test]
+    fn slashable_observations() {
+        let mut cache = ObservedBlockProducers::default();
+
+        let block_a = get_block(1, 0);
+        let block_root_a = block_a.canonical_root();
+        let mut block_b = get_block(1, 0);
+        *block_b.state_root_mut() = Hash256::repeat_byte(1);
+        let block_root_b = block_b.canonical_root();
+
+        assert_eq!(
+            cache.observe_proposal(block_root_a, block_a.to_ref()),
+            Ok(SeenBlock::UniqueNonSlashable)
+        );
+        assert_eq!(
+            cache.proposer_has_been_observed(block_b.to_ref(), block_root_b),
+            Ok(SeenBlock::Slashable {
+                previous_block_root: block_root_a
+            }),
+            "conflicting block is slashable before being observed"
+        );
+        assert_eq!(
+            cache.observe_proposal(block_root_b, block_b.to_ref()),
+            Ok(SeenBlock::Slashable {
+                previous_block_root: block_root_a
+            }),
+            "observing conflicting block returns the previous root"
+        );
+        assert_eq!(
+            cache.observe_proposal(block_root_a, block_a.to_ref()),
+            Ok(SeenBlock::Slashable {
+                previous_block_root: block_root_b
+            }),
+            "re-observing the first block returns the conflicting root"
+        );
+    }
+
+    #[test]
+    fn slashable_roots_are_only_newly_observed_once() {
+        let mut cache = ObservedBlockProducers::default();
+
+        let block_a = get_block(1, 0);
+        let block_root_a = block_a.canonical_root();
+        let mut block_b = get_block(1, 0);
+        *block_b.state_root_mut() = Hash256::repeat_byte(1);
+        let block_root_b = block_b.canonical_root();
+
+        assert_eq!(
+            cache.observe_proposal_root(block_root_a, block_a.to_ref()),
+            Ok((SeenBlock::UniqueNonSlashable, true))
+        );
+        assert_eq!(
+            cache.observe_proposal_root(block_root_b, block_b.to_ref()),
+            Ok((
+                SeenBlock::Slashable {
+                    previous_block_root: block_root_a
+                },
+                true
+            )),
+            "conflicting block is newly observed"
+        );
+        assert_eq!(
+            cache.observe_proposal_root(block_root_a, block_a.to_ref()),
+            Ok((
+                SeenBlock::Slashable {
+                    previous_block_root: block_root_b
+                },
+                false
+            )),
+            "re-observing the first block is not a new equivocation"
+        );
+        assert_eq!(
+            cache.observe_proposal_root(block_root_b, block_b.to_ref()),
+            Ok((
+                SeenBlock::Slashable {
+                    previous_block_root: block_root_a
+                },
+                false
+            )),
+            "re-observing the conflicting block is not a new equivocation"
+        );
+    }
 }
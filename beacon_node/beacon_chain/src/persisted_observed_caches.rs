@@ -0,0 +1,29 @@
+use crate::observed_attesters::SszEpochContainer;
+use crate::observed_block_producers::SszObservedBlockProducers;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use store::{DBColumn, Error as StoreError, StoreItem};
+
+/// The observed proposer, attester and aggregator caches, persisted on shutdown so that a
+/// restarted node continues to reject duplicate gossip without having to re-learn them.
+#[derive(Clone, Encode, Decode)]
+pub struct PersistedObservedCaches {
+    pub block_producers: SszObservedBlockProducers,
+    pub gossip_attesters: SszEpochContainer,
+    pub block_attesters: SszEpochContainer,
+    pub aggregators: SszEpochContainer,
+}
+
+impl StoreItem for PersistedObservedCaches {
+    fn db_column() -> DBColumn {
+        DBColumn::ObservedCaches
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::metrics;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::num::NonZeroUsize;
+use types::non_zero_usize::new_non_zero_usize;
+use types::{EthSpec, ExecutionBlockHash, ExecutionPayload};
+
+/// Payloads can be several megabytes in size, so only a small number are retained. This is enough
+/// to cover repeated requests for recent blocks (e.g. several peers requesting the same blocks by
+/// root during a lookup) without holding a meaningful amount of memory.
+const RECONSTRUCTED_PAYLOAD_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(32);
+
+/// Caches execution payloads which have been reconstructed from the execution layer.
+///
+/// When the database stores blinded blocks (i.e. payload pruning is enabled) serving a full block
+/// over HTTP or P2P requires fetching the payload from the EL. This cache avoids repeating that
+/// round trip for blocks which are requested several times in quick succession.
+///
+/// Only payloads which have been checked against the header of their blinded block are inserted.
+pub struct ReconstructedPayloadCache<E: EthSpec> {
+    payloads: Mutex<LruCache<ExecutionBlockHash, ExecutionPayload<E>>>,
+}
+
+impl<E: EthSpec> Default for ReconstructedPayloadCache<E> {
+    fn default() -> Self {
+        Self {
+            payloads: Mutex::new(LruCache::new(RECONSTRUCTED_PAYLOAD_CACHE_SIZE)),
+        }
+    }
+}
+
+impl<E: EthSpec> ReconstructedPayloadCache<E> {
+    /// Returns a clone of the payload with the given `block_hash`, if it is cached.
+    pub fn get(&self, block_hash: &ExecutionBlockHash) -> Option<ExecutionPayload<E>> {
+        let payload = self.payloads.lock().get(block_hash).cloned();
+        if payload.is_some() {
+            metrics::inc_counter(&metrics::BEACON_RECONSTRUCTED_PAYLOAD_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::BEACON_RECONSTRUCTED_PAYLOAD_CACHE_MISSES);
+        }
+        payload
+    }
+
+    /// Adds a verified, reconstructed `payload` to the cache.
+    pub fn insert(&self, payload: ExecutionPayload<E>) {
+        self.payloads.lock().put(payload.block_hash(), payload);
+    }
+
+    /// Returns the number of payloads in the cache.
+    pub fn len(&self) -> usize {
+        self.payloads.lock().len()
+    }
+
+    /// Returns `true` if there are no payloads in the cache.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{ExecutionPayloadDeneb, MainnetEthSpec};
+
+    fn payload(block_number: u64) -> ExecutionPayload<MainnetEthSpec> {
+        ExecutionPayloadDeneb {
+            block_number,
+            block_hash: ExecutionBlockHash::repeat_byte(block_number as u8),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    #[test]
+    fn insert_and_get() {
+        let cache = ReconstructedPayloadCache::default();
+        assert!(cache.is_empty());
+
+        let payload = payload(1);
+        cache.insert(payload.clone());
+
+        assert_eq!(cache.get(&payload.block_hash()), Some(payload));
+        assert_eq!(cache.get(&ExecutionBlockHash::repeat_byte(2)), None);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = ReconstructedPayloadCache::default();
+        let capacity = RECONSTRUCTED_PAYLOAD_CACHE_SIZE.get() as u64;
+
+        for i in 1..=capacity {
+            cache.insert(payload(i));
+        }
+        // Touch the first payload so that the second becomes the least recently used.
+        assert!(cache.get(&payload(1).block_hash()).is_some());
+        cache.insert(payload(capacity + 1));
+
+        assert_eq!(cache.len(), capacity as usize);
+        assert!(cache.get(&payload(1).block_hash()).is_some());
+        assert!(cache.get(&payload(2).block_hash()).is_none());
+        assert!(cache.get(&payload(capacity + 1).block_hash()).is_some());
+    }
+}
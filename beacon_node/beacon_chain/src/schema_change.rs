@@ -1,6 +1,10 @@
 //! Utilities for managing database schema changes.
 mod migration_schema_v20;
 mod migration_schema_v21;
+mod migration_schema_v22;
+mod migration_schema_v23;
+mod migration_schema_v24;
+mod migration_schema_v25;
 
 use crate::beacon_chain::BeaconChainTypes;
 use crate::types::ChainSpec;
@@ -8,7 +12,129 @@ use slog::Logger;
 use std::sync::Arc;
 use store::hot_cold_store::{HotColdDB, HotColdDBError};
 use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
-use store::Error as StoreError;
+use store::{DBColumn, Error as StoreError};
+
+/// A description of a single-version migration step, used to plan a migration without applying it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStep {
+    pub from: SchemaVersion,
+    pub to: SchemaVersion,
+    /// Human-readable summary of the operations performed by this step.
+    pub description: &'static str,
+    /// Hot database columns which are iterated by this step, with one operation per key.
+    ///
+    /// Steps which only rewrite a single item have no affected columns.
+    pub affected_columns: &'static [DBColumn],
+    /// A conservative estimate of the number of keys from `affected_columns` processed per second.
+    pub keys_per_second: u64,
+}
+
+/// Return the steps that `migrate_schema` would apply to migrate from `from` to `to`.
+///
+/// This does not read or modify the database.
+pub fn migration_plan(
+    from: SchemaVersion,
+    to: SchemaVersion,
+) -> Result<Vec<MigrationStep>, StoreError> {
+    let unsupported = || {
+        StoreError::from(HotColdDBError::UnsupportedSchemaVersion {
+            target_version: to,
+            current_version: from,
+        })
+    };
+
+    if from == to {
+        return if to == CURRENT_SCHEMA_VERSION {
+            Ok(vec![])
+        } else {
+            Err(unsupported())
+        };
+    }
+
+    let mut steps = vec![];
+    let mut current = from;
+    while current != to {
+        let next = if current < to {
+            SchemaVersion(current.as_u64() + 1)
+        } else {
+            SchemaVersion(current.as_u64() - 1)
+        };
+        steps.push(migration_step(current, next).ok_or_else(unsupported)?);
+        current = next;
+    }
+    Ok(steps)
+}
+
+/// Describe the migration between two adjacent schema versions.
+///
+/// This must be kept in sync with the migrations applied by `migrate_schema`.
+fn migration_step(from: SchemaVersion, to: SchemaVersion) -> Option<MigrationStep> {
+    let (description, affected_columns, keys_per_second): (_, &'static [DBColumn], _) =
+        match (from, to) {
+            (SchemaVersion(19), SchemaVersion(20)) => {
+                ("Convert the persisted operation pool to v20", &[], 0)
+            }
+            (SchemaVersion(20), SchemaVersion(19)) => {
+                ("Convert the persisted operation pool to v15", &[], 0)
+            }
+            (SchemaVersion(20), SchemaVersion(21)) => (
+                "Decompress all public keys in the pubkey cache",
+                &[DBColumn::PubkeyCache],
+                5_000,
+            ),
+            (SchemaVersion(21), SchemaVersion(20)) => (
+                "Compress all public keys in the pubkey cache",
+                &[DBColumn::PubkeyCache],
+                20_000,
+            ),
+            (SchemaVersion(21), SchemaVersion(22)) => (
+                "Drop deposit leaves and roots from the persisted eth1 cache",
+                &[],
+                0,
+            ),
+            (SchemaVersion(22), SchemaVersion(21)) => (
+                "Restore deposit leaves and roots in the persisted eth1 cache",
+                &[],
+                0,
+            ),
+            (SchemaVersion(22), SchemaVersion(23)) => (
+                "Build the block root to slot index from all stored blocks",
+                &[DBColumn::BeaconBlock],
+                2_000,
+            ),
+            (SchemaVersion(23), SchemaVersion(22)) => (
+                "Delete the block root to slot index",
+                &[DBColumn::BeaconBlockSlot],
+                100_000,
+            ),
+            (SchemaVersion(23), SchemaVersion(24)) => (
+                "Compress all blocks and states if compression is enabled",
+                &[DBColumn::BeaconBlock, DBColumn::BeaconState],
+                300,
+            ),
+            (SchemaVersion(24), SchemaVersion(23)) => (
+                "Decompress all compressed blocks and states",
+                &[DBColumn::BeaconBlock, DBColumn::BeaconState],
+                500,
+            ),
+            (SchemaVersion(24), SchemaVersion(25)) => (
+                "Record that checksums are disabled in the store config",
+                &[],
+                0,
+            ),
+            (SchemaVersion(25), SchemaVersion(24)) => {
+                ("Remove the checksums flag from the store config", &[], 0)
+            }
+            _ => return None,
+        };
+    Some(MigrationStep {
+        from,
+        to,
+        description,
+        affected_columns,
+        keys_per_second,
+    })
+}
 
 /// Migrate the database from one schema version to another, applying all requisite mutations.
 #[allow(clippy::only_used_in_recursion)] // spec is not used but likely to be used in future
@@ -69,6 +195,38 @@ pub fn migrate_schema<T: BeaconChainTypes>(
             let ops = migration_schema_v21::downgrade_from_v21::<T>(db.clone(), log)?;
             db.store_schema_version_atomically(to, ops)
         }
+        (SchemaVersion(21), SchemaVersion(22)) => {
+            let ops = migration_schema_v22::upgrade_to_v22::<T>(db.clone(), log)?;
+            db.store_schema_version_atomically(to, ops)
+        }
+        (SchemaVersion(22), SchemaVersion(21)) => {
+            let ops = migration_schema_v22::downgrade_from_v22::<T>(db.clone(), log)?;
+            db.store_schema_version_atomically(to, ops)
+        }
+        (SchemaVersion(22), SchemaVersion(23)) => {
+            let ops = migration_schema_v23::upgrade_to_v23::<T>(db.clone(), log)?;
+            db.store_schema_version_atomically(to, ops)
+        }
+        (SchemaVersion(23), SchemaVersion(22)) => {
+            let ops = migration_schema_v23::downgrade_from_v23::<T>(db.clone(), log)?;
+            db.store_schema_version_atomically(to, ops)
+        }
+        (SchemaVersion(23), SchemaVersion(24)) => {
+            let ops = migration_schema_v24::upgrade_to_v24::<T>(db.clone(), log)?;
+            db.store_schema_version_atomically(to, ops)
+        }
+        (SchemaVersion(24), SchemaVersion(23)) => {
+            let ops = migration_schema_v24::downgrade_from_v24::<T>(db.clone(), log)?;
+            db.store_schema_version_atomically(to, ops)
+        }
+        (SchemaVersion(24), SchemaVersion(25)) => {
+            let ops = migration_schema_v25::upgrade_to_v25::<T>(db.clone(), log)?;
+            db.store_schema_version_atomically(to, ops)
+        }
+        (SchemaVersion(25), SchemaVersion(24)) => {
+            let ops = migration_schema_v25::downgrade_from_v25::<T>(db.clone(), log)?;
+            db.store_schema_version_atomically(to, ops)
+        }
         // Anything else is an error.
         (_, _) => Err(HotColdDBError::UnsupportedSchemaVersion {
             target_version: to,
@@ -77,3 +235,46 @@ pub fn migrate_schema<T: BeaconChainTypes>(
         .into()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_for_current_version_is_empty() {
+        assert!(
+            migration_plan(CURRENT_SCHEMA_VERSION, CURRENT_SCHEMA_VERSION)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn plan_steps_one_version_at_a_time() {
+        let upgrade = migration_plan(SchemaVersion(19), CURRENT_SCHEMA_VERSION).unwrap();
+        assert_eq!(
+            upgrade.len() as u64,
+            CURRENT_SCHEMA_VERSION.as_u64() - 19,
+            "every upgrade step is known"
+        );
+        for (step, next) in upgrade.iter().zip(upgrade.iter().skip(1)) {
+            assert_eq!(step.to, next.from);
+            assert_eq!(step.to.as_u64(), step.from.as_u64() + 1);
+        }
+
+        let downgrade = migration_plan(CURRENT_SCHEMA_VERSION, SchemaVersion(19)).unwrap();
+        assert_eq!(
+            downgrade.len(),
+            upgrade.len(),
+            "every downgrade step is known"
+        );
+        assert_eq!(downgrade.first().unwrap().from, CURRENT_SCHEMA_VERSION);
+        assert_eq!(downgrade.last().unwrap().to, SchemaVersion(19));
+    }
+
+    #[test]
+    fn plan_rejects_unsupported_versions() {
+        assert!(migration_plan(SchemaVersion(18), SchemaVersion(20)).is_err());
+        assert!(migration_plan(SchemaVersion(20), SchemaVersion(20)).is_err());
+    }
+}
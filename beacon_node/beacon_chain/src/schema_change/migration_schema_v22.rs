@@ -0,0 +1,62 @@
+use crate::beacon_chain::{BeaconChainTypes, ETH1_CACHE_DB_KEY};
+use crate::eth1_chain::SszEth1;
+use eth1::{SszEth1CacheV13, SszEth1CacheV22};
+use slog::{info, Logger};
+use ssz::{Decode, Encode};
+use std::sync::Arc;
+use store::{Error, HotColdDB, KeyValueStoreOp, StoreItem};
+
+/// Re-encode the persisted eth1 cache using `convert`.
+///
+/// Dummy backends and missing caches are left unchanged.
+fn convert_eth1_cache<T: BeaconChainTypes>(
+    db: &HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>,
+    convert: impl FnOnce(&[u8]) -> Result<Vec<u8>, String>,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    let Some(persisted) = db.get_item::<SszEth1>(&ETH1_CACHE_DB_KEY)? else {
+        return Ok(vec![]);
+    };
+
+    if persisted.use_dummy_backend || persisted.backend_bytes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let backend_bytes =
+        convert(&persisted.backend_bytes).map_err(|message| Error::DBError { message })?;
+
+    let updated = SszEth1 {
+        backend_bytes,
+        ..persisted
+    };
+
+    Ok(vec![updated.as_kv_store_op(ETH1_CACHE_DB_KEY)])
+}
+
+pub fn upgrade_to_v22<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Upgrading from v21 to v22");
+
+    // Drop the deposit leaves and roots from the eth1 cache, they're recomputed on load.
+    convert_eth1_cache::<T>(&db, |bytes| {
+        SszEth1CacheV13::from_ssz_bytes(bytes)
+            .map_err(|e| format!("Unable to decode eth1 cache: {:?}", e))?
+            .upgrade_to_v22()
+            .map(|cache| cache.as_ssz_bytes())
+    })
+}
+
+pub fn downgrade_from_v22<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Downgrading from v22 to v21");
+
+    convert_eth1_cache::<T>(&db, |bytes| {
+        SszEth1CacheV22::from_ssz_bytes(bytes)
+            .map_err(|e| format!("Unable to decode eth1 cache: {:?}", e))?
+            .downgrade_to_v13()
+            .map(|cache| cache.as_ssz_bytes())
+    })
+}
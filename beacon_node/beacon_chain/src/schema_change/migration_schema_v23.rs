@@ -0,0 +1,71 @@
+use crate::beacon_chain::BeaconChainTypes;
+use slog::{info, Logger};
+use ssz::Encode;
+use std::sync::Arc;
+use store::{get_key_for_col, DBColumn, Error, HotColdDB, KeyValueStore, KeyValueStoreOp};
+use types::{Hash256, SignedBlindedBeaconBlock};
+
+const LOG_EVERY: usize = 200_000;
+
+/// The block slot index is written in batches of this size, rather than in a single batch with
+/// the schema version, to bound memory usage on databases with millions of blocks.
+///
+/// This is safe because index entries are only ever derived from blocks already on disk, so a
+/// partially written index is simply completed when the migration is re-run.
+const BATCH_SIZE: usize = 50_000;
+
+pub fn upgrade_to_v23<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Upgrading from v22 to v23");
+
+    let mut ops = vec![];
+
+    // Index the slot of every block, reading only the slot from each block's bytes.
+    for (i, res) in db
+        .hot_db
+        .iter_column::<Hash256>(DBColumn::BeaconBlock)
+        .enumerate()
+    {
+        let (block_root, bytes) = res?;
+        let slot = SignedBlindedBeaconBlock::<T::EthSpec>::slot_from_ssz_bytes(&bytes)?;
+        let key = get_key_for_col(DBColumn::BeaconBlockSlot.into(), block_root.as_bytes());
+        ops.push(KeyValueStoreOp::PutKeyValue(key, slot.as_ssz_bytes()));
+
+        if ops.len() >= BATCH_SIZE {
+            db.hot_db.do_atomically(std::mem::take(&mut ops))?;
+        }
+
+        if i > 0 && i % LOG_EVERY == 0 {
+            info!(
+                log,
+                "Block slot indexing in progress";
+                "blocks_indexed" => i
+            );
+        }
+    }
+    info!(log, "Block slot indexing complete");
+
+    Ok(ops)
+}
+
+pub fn downgrade_from_v23<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Downgrading from v23 to v22");
+
+    // Delete the index so that it can't become stale while running an older version.
+    let ops = db
+        .hot_db
+        .iter_column_keys::<Hash256>(DBColumn::BeaconBlockSlot)
+        .map(|res| {
+            let block_root = res?;
+            let key = get_key_for_col(DBColumn::BeaconBlockSlot.into(), block_root.as_bytes());
+            Ok(KeyValueStoreOp::DeleteKey(key))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(ops)
+}
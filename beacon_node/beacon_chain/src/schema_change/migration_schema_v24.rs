@@ -0,0 +1,111 @@
+use crate::beacon_chain::BeaconChainTypes;
+use slog::{info, Logger};
+use std::sync::Arc;
+use store::compression::{decompress, is_compressed, CompressionAlgorithm};
+use store::{get_key_for_col, DBColumn, Error, HotColdDB, KeyValueStore, KeyValueStoreOp};
+use types::Hash256;
+
+const LOG_EVERY: usize = 200_000;
+
+/// Rewritten values are written in batches of this size, rather than in a single batch with the
+/// schema version, to bound memory usage on databases with many blocks and states.
+///
+/// This is safe because compressed and uncompressed values can co-exist in the database, so a
+/// partially rewritten column is readable if the migration is interrupted and re-run.
+const BATCH_SIZE: usize = 1_000;
+
+pub fn upgrade_to_v24<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Upgrading from v23 to v24");
+
+    let compression = db.get_config().hot_db_compression;
+    let compression_level = db.get_config().hot_db_compression_level;
+    if compression == CompressionAlgorithm::None {
+        return Ok(vec![]);
+    }
+
+    let mut ops = vec![];
+    let mut num_compressed = 0;
+
+    // Rewrite existing blocks and states with the configured compression algorithm so that the
+    // space savings apply to the whole hot database rather than only to values written later.
+    for column in [DBColumn::BeaconBlock, DBColumn::BeaconState] {
+        for res in db.hot_db.iter_column::<Hash256>(column) {
+            let (key, bytes) = res?;
+            if is_compressed(&bytes) {
+                continue;
+            }
+
+            let db_key = get_key_for_col(column.into(), key.as_bytes());
+            ops.push(KeyValueStoreOp::PutKeyValue(
+                db_key,
+                compression.compress(bytes, compression_level)?,
+            ));
+            num_compressed += 1;
+
+            if ops.len() >= BATCH_SIZE {
+                db.hot_db.do_atomically(std::mem::take(&mut ops))?;
+            }
+
+            if num_compressed % LOG_EVERY == 0 {
+                info!(
+                    log,
+                    "Value compression in progress";
+                    "values_compressed" => num_compressed
+                );
+            }
+        }
+    }
+    info!(
+        log,
+        "Value compression complete";
+        "values_compressed" => num_compressed
+    );
+
+    Ok(ops)
+}
+
+pub fn downgrade_from_v24<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Downgrading from v24 to v23");
+
+    let mut ops = vec![];
+    let mut num_decompressed = 0;
+
+    // Older versions can't read compressed values, so rewrite them all uncompressed.
+    for column in [DBColumn::BeaconBlock, DBColumn::BeaconState] {
+        for res in db.hot_db.iter_column::<Hash256>(column) {
+            let (key, bytes) = res?;
+            if !is_compressed(&bytes) {
+                continue;
+            }
+
+            let db_key = get_key_for_col(column.into(), key.as_bytes());
+            ops.push(KeyValueStoreOp::PutKeyValue(db_key, decompress(bytes)?));
+            num_decompressed += 1;
+
+            if ops.len() >= BATCH_SIZE {
+                db.hot_db.do_atomically(std::mem::take(&mut ops))?;
+            }
+
+            if num_decompressed % LOG_EVERY == 0 {
+                info!(
+                    log,
+                    "Value decompression in progress";
+                    "values_decompressed" => num_decompressed
+                );
+            }
+        }
+    }
+    info!(
+        log,
+        "Value decompression complete";
+        "values_decompressed" => num_decompressed
+    );
+
+    Ok(ops)
+}
@@ -0,0 +1,67 @@
+use crate::beacon_chain::BeaconChainTypes;
+use slog::{info, Logger};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::sync::Arc;
+use store::config::OnDiskStoreConfig;
+use store::metadata::CONFIG_KEY;
+use store::{get_key_for_col, DBColumn, Error, HotColdDB, KeyValueStore, KeyValueStoreOp};
+
+/// The on-disk config prior to v25, which did not record whether checksums are enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+struct OnDiskStoreConfigV24 {
+    slots_per_restore_point: u64,
+}
+
+fn config_op(bytes: Vec<u8>) -> KeyValueStoreOp {
+    let db_key = get_key_for_col(DBColumn::BeaconMeta.into(), CONFIG_KEY.as_bytes());
+    KeyValueStoreOp::PutKeyValue(db_key, bytes)
+}
+
+pub fn upgrade_to_v25<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Upgrading from v24 to v25");
+
+    // Databases created before v25 never have checksums.
+    let Some(bytes) = db
+        .hot_db
+        .get_bytes(DBColumn::BeaconMeta.into(), CONFIG_KEY.as_bytes())?
+    else {
+        return Ok(vec![]);
+    };
+    let old_config = OnDiskStoreConfigV24::from_ssz_bytes(&bytes)?;
+    let new_config = OnDiskStoreConfig {
+        slots_per_restore_point: old_config.slots_per_restore_point,
+        checksums: false,
+    };
+
+    Ok(vec![config_op(new_config.as_ssz_bytes())])
+}
+
+pub fn downgrade_from_v25<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Downgrading from v25 to v24");
+
+    let Some(bytes) = db
+        .hot_db
+        .get_bytes(DBColumn::BeaconMeta.into(), CONFIG_KEY.as_bytes())?
+    else {
+        return Ok(vec![]);
+    };
+    let new_config = OnDiskStoreConfig::from_ssz_bytes(&bytes)?;
+
+    // Older versions can't read values with checksums, and rewriting every value is no faster
+    // than re-syncing.
+    if new_config.checksums {
+        return Err(Error::UnableToDowngrade);
+    }
+
+    let old_config = OnDiskStoreConfigV24 {
+        slots_per_restore_point: new_config.slots_per_restore_point,
+    };
+    Ok(vec![config_op(old_config.as_ssz_bytes())])
+}
@@ -124,6 +124,14 @@ impl ShufflingCache {
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
     pub fn contains(&self, key: &AttestationShufflingId) -> bool {
         self.cache.contains_key(key)
     }
@@ -6,7 +6,9 @@
 //! 1. Removes the burden of a single, mandatory `per_slot_processing` call from the leading-edge of
 //!    block processing. This helps import blocks faster.
 //! 2. Allows the node to learn of the shuffling for the next epoch, before the first block from
-//!    that epoch has arrived. This helps reduce gossip block propagation times.
+//!    that epoch has arrived. This helps reduce gossip block propagation times. If the head state
+//!    has already been advanced into the last slot of an epoch (i.e., that slot is empty), the
+//!    shuffling is learned from a temporary copy of the state instead.
 //!
 //! The downsides to this optimization are:
 //!
@@ -15,7 +17,8 @@
 //! 2. There's a possibility that the head block is never built upon, causing wasted CPU cycles.
 use crate::validator_monitor::HISTORIC_EPOCHS as VALIDATOR_MONITOR_HISTORIC_EPOCHS;
 use crate::{
-    chain_config::FORK_CHOICE_LOOKAHEAD_FACTOR, BeaconChain, BeaconChainError, BeaconChainTypes,
+    chain_config::FORK_CHOICE_LOOKAHEAD_FACTOR, metrics, BeaconChain, BeaconChainError,
+    BeaconChainTypes,
 };
 use slog::{debug, error, warn, Logger};
 use slot_clock::SlotClock;
@@ -27,7 +30,9 @@ use std::sync::{
 use store::KeyValueStore;
 use task_executor::TaskExecutor;
 use tokio::time::{sleep, sleep_until, Instant};
-use types::{AttestationShufflingId, BeaconStateError, EthSpec, Hash256, RelativeEpoch, Slot};
+use types::{
+    AttestationShufflingId, BeaconState, BeaconStateError, EthSpec, Hash256, RelativeEpoch, Slot,
+};
 
 /// If the head slot is more than `MAX_ADVANCE_DISTANCE` from the current slot, then don't perform
 /// the state advancement.
@@ -321,6 +326,19 @@ fn advance_head<T: BeaconChainTypes>(
     match state.slot().cmp(&state.latest_block_header().slot) {
         std::cmp::Ordering::Equal => (),
         std::cmp::Ordering::Greater => {
+            // The state was advanced into the current slot by an earlier run. If the current slot
+            // is the last of its epoch, advance a copy of the state across the epoch boundary so
+            // the next epoch's caches are primed before its first block arrives. The copy is
+            // discarded, so the database never sees a state advanced more than a single slot.
+            let slots_per_epoch = T::EthSpec::slots_per_epoch();
+            if state.slot() == current_slot
+                && (current_slot + 1).epoch(slots_per_epoch) > current_slot.epoch(slots_per_epoch)
+            {
+                per_slot_processing(&mut state, None, &beacon_chain.spec)
+                    .map_err(BeaconChainError::from)?;
+                prime_epoch_caches(beacon_chain, head_block_root, &mut state, current_slot, log)?;
+            }
+
             return Err(Error::StateAlreadyAdvanced {
                 block_root: head_block_root,
             });
@@ -333,6 +351,8 @@ fn advance_head<T: BeaconChainTypes>(
         }
     }
 
+    let _timer = metrics::start_timer(&metrics::STATE_ADVANCE_TIMES);
+
     let initial_slot = state.slot();
     let initial_epoch = state.current_epoch();
 
@@ -388,45 +408,10 @@ fn advance_head<T: BeaconChainTypes>(
         .map_err(BeaconChainError::from)?;
 
     // If the `pre_state` is in a later epoch than `state`, pre-emptively add the proposer shuffling
-    // for the state's current epoch and the committee cache for the state's next epoch.
+    // for the state's current epoch and the committee caches for the state's current and next
+    // epochs.
     if initial_epoch < state.current_epoch() {
-        // Update the proposer cache.
-        //
-        // We supply the `head_block_root` as the decision block since the prior `if` statement guarantees
-        // the head root is the latest block from the prior epoch.
-        beacon_chain
-            .beacon_proposer_cache
-            .lock()
-            .insert(
-                state.current_epoch(),
-                head_block_root,
-                state
-                    .get_beacon_proposer_indices(&beacon_chain.spec)
-                    .map_err(BeaconChainError::from)?,
-                state.fork(),
-            )
-            .map_err(BeaconChainError::from)?;
-
-        // Update the attester cache.
-        let shuffling_id =
-            AttestationShufflingId::new(head_block_root, &state, RelativeEpoch::Next)
-                .map_err(BeaconChainError::from)?;
-        let committee_cache = state
-            .committee_cache(RelativeEpoch::Next)
-            .map_err(BeaconChainError::from)?;
-        beacon_chain
-            .shuffling_cache
-            .write()
-            .insert_committee_cache(shuffling_id.clone(), committee_cache);
-
-        debug!(
-            log,
-            "Primed proposer and attester caches";
-            "head_block_root" => ?head_block_root,
-            "next_epoch_shuffling_root" => ?shuffling_id.shuffling_decision_block,
-            "state_epoch" => state.current_epoch(),
-            "current_epoch" => current_slot.epoch(T::EthSpec::slots_per_epoch()),
-        );
+        prime_epoch_caches(beacon_chain, head_block_root, &mut state, current_slot, log)?;
     }
 
     // Apply the state to the attester cache, if the cache deems it interesting.
@@ -446,6 +431,7 @@ fn advance_head<T: BeaconChainTypes>(
     let starting_slot = current_slot;
     let current_slot = beacon_chain.slot()?;
     if starting_slot < current_slot {
+        metrics::inc_counter(&metrics::STATE_ADVANCE_TOO_SLOW);
         warn!(
             log,
             "State advance too slow";
@@ -483,9 +469,85 @@ fn advance_head<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// Adds the proposer shuffling for the current epoch of `state` and the committee caches for its
+/// current and next epochs to the `beacon_chain` caches.
+///
+/// The `state` must have just been advanced into a new epoch on top of `head_block_root`, so that
+/// `head_block_root` is the latest block from the prior epoch.
+fn prime_epoch_caches<T: BeaconChainTypes>(
+    beacon_chain: &Arc<BeaconChain<T>>,
+    head_block_root: Hash256,
+    state: &mut BeaconState<T::EthSpec>,
+    current_slot: Slot,
+    log: &Logger,
+) -> Result<(), Error> {
+    state
+        .build_committee_cache(RelativeEpoch::Current, &beacon_chain.spec)
+        .map_err(BeaconChainError::from)?;
+    state
+        .build_committee_cache(RelativeEpoch::Next, &beacon_chain.spec)
+        .map_err(BeaconChainError::from)?;
+
+    // Update the proposer cache.
+    //
+    // We supply the `head_block_root` as the decision block since the caller guarantees the head
+    // root is the latest block from the prior epoch.
+    beacon_chain
+        .beacon_proposer_cache
+        .lock()
+        .insert(
+            state.current_epoch(),
+            head_block_root,
+            state
+                .get_beacon_proposer_indices(&beacon_chain.spec)
+                .map_err(BeaconChainError::from)?,
+            state.fork(),
+        )
+        .map_err(BeaconChainError::from)?;
+
+    // Update the attester cache.
+    //
+    // The current epoch shuffling is usually present from the previous epoch's advance, but it may
+    // be missing after a restart or if that advance was skipped.
+    let current_shuffling_id =
+        AttestationShufflingId::new(head_block_root, state, RelativeEpoch::Current)
+            .map_err(BeaconChainError::from)?;
+    let next_shuffling_id =
+        AttestationShufflingId::new(head_block_root, state, RelativeEpoch::Next)
+            .map_err(BeaconChainError::from)?;
+    {
+        let mut shuffling_cache = beacon_chain.shuffling_cache.write();
+        if !shuffling_cache.contains(&current_shuffling_id) {
+            let committee_cache = state
+                .committee_cache(RelativeEpoch::Current)
+                .map_err(BeaconChainError::from)?;
+            shuffling_cache.insert_committee_cache(current_shuffling_id, committee_cache);
+        }
+        let committee_cache = state
+            .committee_cache(RelativeEpoch::Next)
+            .map_err(BeaconChainError::from)?;
+        shuffling_cache.insert_committee_cache(next_shuffling_id.clone(), committee_cache);
+    }
+
+    debug!(
+        log,
+        "Primed proposer and attester caches";
+        "head_block_root" => ?head_block_root,
+        "next_epoch_shuffling_root" => ?next_shuffling_id.shuffling_decision_block,
+        "state_epoch" => state.current_epoch(),
+        "current_epoch" => current_slot.epoch(T::EthSpec::slots_per_epoch()),
+    );
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::BeaconChainHarness;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
 
     #[test]
     fn lock() {
@@ -497,4 +559,54 @@ mod tests {
         assert!(!lock.lock());
         assert!(lock.lock());
     }
+
+    #[tokio::test]
+    async fn advance_head_across_epoch_boundary() {
+        let harness = BeaconChainHarness::builder(E::default())
+            .default_spec()
+            .deterministic_keypairs(8)
+            .logger(logging::test_logger())
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        let chain = &harness.chain;
+        let log = &chain.log;
+
+        // Build a chain up to the last slot of the first epoch.
+        let slots_per_epoch = E::slots_per_epoch();
+        let head_slot = Slot::new(2 * slots_per_epoch - 1);
+        harness.extend_to_slot(head_slot).await;
+        let head = chain.head_snapshot();
+        assert_eq!(head.beacon_block.slot(), head_slot);
+        assert_eq!(chain.slot().unwrap(), head_slot);
+
+        advance_head(chain, log).expect("should advance head");
+
+        // The advanced state should be available for the next slot.
+        let (_, advanced_state) = chain
+            .store
+            .get_advanced_hot_state(
+                head.beacon_block_root,
+                head_slot + 1,
+                head.beacon_state_root(),
+            )
+            .unwrap()
+            .expect("should load advanced state");
+        assert_eq!(advanced_state.slot(), head_slot + 1);
+
+        // The proposers of the next epoch should be known before its first block arrives.
+        let next_epoch = (head_slot + 1).epoch(slots_per_epoch);
+        assert!(chain
+            .beacon_proposer_cache
+            .lock()
+            .get_epoch::<E>(head.beacon_block_root, next_epoch)
+            .is_some());
+
+        // Once the slot has started, the state must not be advanced any further.
+        harness.set_current_slot(head_slot + 1);
+        assert!(matches!(
+            advance_head(chain, log),
+            Err(Error::StateAlreadyAdvanced { block_root }) if block_root == head.beacon_block_root
+        ));
+    }
 }
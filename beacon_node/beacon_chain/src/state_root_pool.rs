@@ -0,0 +1,147 @@
+//! A dedicated thread pool for computing the state roots of post-states during block import.
+//!
+//! Hashing a heavily modified state can take tens of milliseconds on large validator sets. Running
+//! this work on its own pool stops it from competing with the rest of the node for the global
+//! rayon pool, and allows the validators and balances to be hashed in parallel.
+//!
+//! The number of jobs waiting on the pool is bounded. If the bound is reached the state root is
+//! computed on the calling thread instead, so that a burst of blocks can't build an unbounded
+//! backlog.
+//!
+//! The calling thread waits for the job to complete, just as it would if it had computed the root
+//! itself.
+use crate::metrics;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use types::{BeaconState, BeaconStateError, EthSpec, Hash256};
+
+/// The default number of threads in the pool.
+pub const DEFAULT_STATE_ROOT_POOL_THREADS: usize = 2;
+/// The default maximum number of jobs which may be queued or running on the pool.
+pub const DEFAULT_STATE_ROOT_POOL_MAX_QUEUED: usize = 8;
+
+pub struct StateRootPool {
+    pool: ThreadPool,
+    queued: AtomicUsize,
+    max_queued: usize,
+}
+
+impl StateRootPool {
+    pub fn new(num_threads: usize, max_queued: usize) -> Result<Self, String> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|i| format!("state_root_{}", i))
+            .build()
+            .map_err(|e| format!("Unable to build state root thread pool: {:?}", e))?;
+        Ok(Self {
+            pool,
+            queued: AtomicUsize::new(0),
+            max_queued,
+        })
+    }
+
+    /// Compute the tree hash root of `state`, updating its tree hash cache.
+    ///
+    /// The work is run on the pool unless the pool is at capacity, in which case it is run on the
+    /// calling thread.
+    pub fn update_tree_hash_cache<E: EthSpec>(
+        &self,
+        state: &mut BeaconState<E>,
+    ) -> Result<Hash256, BeaconStateError> {
+        let queued = self.queued.fetch_add(1, Ordering::Relaxed) + 1;
+        metrics::set_gauge(&metrics::STATE_ROOT_POOL_QUEUED, queued as i64);
+
+        let result = if queued <= self.max_queued {
+            self.pool
+                .install(|| state.update_tree_hash_cache_parallel())
+        } else {
+            metrics::inc_counter(&metrics::STATE_ROOT_POOL_OVERFLOW);
+            state.update_tree_hash_cache()
+        };
+
+        let queued = self.queued.fetch_sub(1, Ordering::Relaxed) - 1;
+        metrics::set_gauge(&metrics::STATE_ROOT_POOL_QUEUED, queued as i64);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use genesis::{
+        generate_deterministic_keypairs, interop_genesis_state, DEFAULT_ETH1_BLOCK_HASH,
+    };
+    use std::sync::Arc;
+    use tree_hash::TreeHash;
+    use types::{MinimalEthSpec, Slot};
+
+    type E = MinimalEthSpec;
+
+    fn get_state(validator_count: usize) -> BeaconState<E> {
+        let spec = E::default_spec();
+        interop_genesis_state::<E>(
+            &generate_deterministic_keypairs(validator_count),
+            42,
+            Hash256::from_slice(DEFAULT_ETH1_BLOCK_HASH),
+            None,
+            &spec,
+        )
+        .expect("should build state")
+    }
+
+    /// Modify the state after its tree hash cache has been built, as block processing does.
+    fn modify_state(state: &mut BeaconState<E>, seed: u64) {
+        *state.slot_mut() = Slot::new(seed);
+        for i in 0..state.balances().len() {
+            *state.get_balance_mut(i).unwrap() += seed + i as u64;
+        }
+    }
+
+    #[test]
+    fn root_matches_sequential_hashing() {
+        let pool = StateRootPool::new(2, 8).unwrap();
+        let mut state = get_state(64);
+        pool.update_tree_hash_cache(&mut state).unwrap();
+        modify_state(&mut state, 1);
+
+        let mut expected_state = state.clone();
+        let expected = expected_state.update_tree_hash_cache().unwrap();
+
+        assert_eq!(pool.update_tree_hash_cache(&mut state).unwrap(), expected);
+        assert_eq!(state.tree_hash_root(), expected);
+        assert_eq!(pool.queued.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn overflow_computes_root_on_calling_thread() {
+        let pool = StateRootPool::new(1, 0).unwrap();
+        let mut state = get_state(16);
+        let expected = state.clone().update_tree_hash_cache().unwrap();
+
+        assert_eq!(pool.update_tree_hash_cache(&mut state).unwrap(), expected);
+        assert_eq!(pool.queued.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn concurrent_callers() {
+        let pool = Arc::new(StateRootPool::new(2, 2).unwrap());
+        let state = get_state(64);
+
+        let handles = (0..8)
+            .map(|seed| {
+                let pool = pool.clone();
+                let mut state = state.clone();
+                std::thread::spawn(move || {
+                    modify_state(&mut state, seed);
+                    let expected = state.clone().update_tree_hash_cache().unwrap();
+                    assert_eq!(pool.update_tree_hash_cache(&mut state).unwrap(), expected);
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(pool.queued.load(Ordering::Relaxed), 0);
+    }
+}
@@ -25,12 +25,17 @@
 //!                                ▼
 //!                  impl SignatureVerifiedSyncContribution
 //! ```
+//!
+//! Gossip `SyncCommitteeMessage`s may also be verified in batches using
+//! `batch_verify_sync_committee_messages`, which verifies their signatures in one BLS batch per
+//! subcommittee.
 
+use crate::attestation_verification::find_valid_signature_sets;
 use crate::observed_attesters::SlotSubcommitteeIndex;
 use crate::{
     metrics, observed_aggregates::ObserveOutcome, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
-use bls::{verify_signature_sets, PublicKeyBytes};
+use bls::{verify_signature_sets, PublicKey, PublicKeyBytes, SignatureSet};
 use derivative::Derivative;
 use safe_arith::ArithError;
 use slot_clock::SlotClock;
@@ -283,6 +288,15 @@ pub struct VerifiedSyncCommitteeMessage {
     subnet_positions: HashMap<SyncSubnetId, Vec<usize>>,
 }
 
+/// Wraps a `SyncCommitteeMessage` that has passed all gossip checks except for its signature.
+struct PartiallyVerifiedSyncCommitteeMessage {
+    sync_message: SyncCommitteeMessage,
+    subnet_id: SyncSubnetId,
+    subnet_positions: HashMap<SyncSubnetId, Vec<usize>>,
+    pubkey: PublicKeyBytes,
+    head_root: Hash256,
+}
+
 impl<T: BeaconChainTypes> VerifiedSyncContribution<T> {
     /// Returns `Ok(Self)` if the `signed_aggregate` is valid to be (re)published on the gossip
     /// network.
@@ -454,6 +468,16 @@ impl VerifiedSyncCommitteeMessage {
         subnet_id: SyncSubnetId,
         chain: &BeaconChain<T>,
     ) -> Result<Self, Error> {
+        let partial = Self::verify_without_signature(sync_message, subnet_id, chain)?;
+        Self::from_partial(partial, chain, true)
+    }
+
+    /// Run all gossip checks on `sync_message` except for verifying its signature.
+    fn verify_without_signature<T: BeaconChainTypes>(
+        sync_message: SyncCommitteeMessage,
+        subnet_id: SyncSubnetId,
+        chain: &BeaconChain<T>,
+    ) -> Result<PartiallyVerifiedSyncCommitteeMessage, Error> {
         // Ensure sync committee message is for the current slot (within a
         // MAXIMUM_GOSSIP_CLOCK_DISPARITY allowance).
         //
@@ -482,21 +506,6 @@ impl VerifiedSyncCommitteeMessage {
         let validator_index = sync_message.validator_index;
         let head_root = chain.canonical_head.cached_head().head_block_root();
         let new_root = sync_message.beacon_block_root;
-        let should_override_prev = |prev_root: &Hash256, new_root: &Hash256| {
-            let roots_differ = new_root != prev_root;
-            let new_elects_head = new_root == &head_root;
-
-            if roots_differ {
-                // Track sync committee messages that differ from each other.
-                metrics::inc_counter(&metrics::SYNC_MESSAGE_EQUIVOCATIONS);
-                if new_elects_head {
-                    // Track sync committee messages that swap from an old block to a new block.
-                    metrics::inc_counter(&metrics::SYNC_MESSAGE_EQUIVOCATIONS_TO_HEAD);
-                }
-            }
-
-            roots_differ && new_elects_head
-        };
         if let Some(prev_root) = chain
             .observed_sync_contributors
             .read()
@@ -506,7 +515,7 @@ impl VerifiedSyncCommitteeMessage {
             )
             .map_err(BeaconChainError::from)?
         {
-            if !should_override_prev(&prev_root, &new_root) {
+            if !should_override_prev(head_root)(&prev_root, &new_root) {
                 return Err(Error::PriorSyncCommitteeMessageKnown {
                     validator_index,
                     slot: sync_message.slot,
@@ -516,8 +525,36 @@ impl VerifiedSyncCommitteeMessage {
             }
         }
 
+        Ok(PartiallyVerifiedSyncCommitteeMessage {
+            sync_message,
+            subnet_id,
+            subnet_positions,
+            pubkey,
+            head_root,
+        })
+    }
+
+    /// Complete the verification of a partially verified sync committee message, verifying its
+    /// signature only if `check_signature` is `true`.
+    fn from_partial<T: BeaconChainTypes>(
+        partial: PartiallyVerifiedSyncCommitteeMessage,
+        chain: &BeaconChain<T>,
+        check_signature: bool,
+    ) -> Result<Self, Error> {
+        let PartiallyVerifiedSyncCommitteeMessage {
+            sync_message,
+            subnet_id,
+            subnet_positions,
+            pubkey,
+            head_root,
+        } = partial;
+        let validator_index = sync_message.validator_index;
+        let new_root = sync_message.beacon_block_root;
+
         // The aggregate signature of the sync committee message is valid.
-        verify_sync_committee_message(chain, &sync_message, &pubkey)?;
+        if check_signature {
+            verify_sync_committee_message(chain, &sync_message, &pubkey)?;
+        }
 
         // Now that the sync committee message has been fully verified, store that we have received a valid
         // sync committee message from this validator.
@@ -532,7 +569,7 @@ impl VerifiedSyncCommitteeMessage {
                 SlotSubcommitteeIndex::new(sync_message.slot, subnet_id.into()),
                 validator_index as usize,
                 sync_message.beacon_block_root,
-                should_override_prev,
+                should_override_prev(head_root),
             )
             .map_err(BeaconChainError::from)?
         {
@@ -567,6 +604,126 @@ impl VerifiedSyncCommitteeMessage {
     }
 }
 
+/// Returns a closure which decides whether a sync committee message for `new_root` should replace a
+/// previously observed message for `prev_root` from the same validator.
+fn should_override_prev(head_root: Hash256) -> impl Fn(&Hash256, &Hash256) -> bool {
+    move |prev_root: &Hash256, new_root: &Hash256| {
+        let roots_differ = new_root != prev_root;
+        let new_elects_head = new_root == &head_root;
+
+        if roots_differ {
+            // Track sync committee messages that differ from each other.
+            metrics::inc_counter(&metrics::SYNC_MESSAGE_EQUIVOCATIONS);
+            if new_elects_head {
+                // Track sync committee messages that swap from an old block to a new block.
+                metrics::inc_counter(&metrics::SYNC_MESSAGE_EQUIVOCATIONS_TO_HEAD);
+            }
+        }
+
+        roots_differ && new_elects_head
+    }
+}
+
+/// Verify gossip sync committee messages using batch BLS signature verification.
+///
+/// Each message is first checked without its signature. The signatures of the messages which pass
+/// are then verified in one batch per subcommittee. If a subcommittee's batch fails it is
+/// repeatedly split in half to find the valid signatures, leaving only the remainder to be
+/// verified individually.
+///
+/// The outcome is a `Vec<Result>` with a one-to-one mapping to the messages supplied as input.
+pub fn batch_verify_sync_committee_messages<T, I>(
+    messages: I,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<Result<VerifiedSyncCommitteeMessage, Error>>, Error>
+where
+    T: BeaconChainTypes,
+    I: Iterator<Item = (SyncCommitteeMessage, SyncSubnetId)>,
+{
+    let partial_results = messages
+        .map(|(sync_message, subnet_id)| {
+            VerifiedSyncCommitteeMessage::verify_without_signature(sync_message, subnet_id, chain)
+        })
+        .collect::<Vec<_>>();
+
+    // Whether each message is known to have a valid signature, in the same order as
+    // `partial_results`.
+    let mut signature_valid = vec![false; partial_results.len()];
+
+    {
+        let signature_setup_timer =
+            metrics::start_timer(&metrics::SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_SETUP_TIMES);
+
+        let pubkey_cache = chain.validator_pubkey_cache.read();
+
+        // The signature sets borrow their signatures, so these must be created first.
+        let signatures = partial_results
+            .iter()
+            .map(|result| {
+                result
+                    .as_ref()
+                    .ok()
+                    .map(|partial| AggregateSignature::from(&partial.sync_message.signature))
+            })
+            .collect::<Vec<_>>();
+
+        // Group the signature sets by subcommittee, along with the index of their message.
+        let mut subcommittee_sets: HashMap<SyncSubnetId, (Vec<usize>, Vec<SignatureSet>)> =
+            HashMap::new();
+        for (i, (result, signature)) in partial_results.iter().zip(&signatures).enumerate() {
+            let (Ok(partial), Some(signature)) = (result, signature) else {
+                continue;
+            };
+            // Messages with an unknown pubkey are left to individual verification, which will
+            // return the appropriate error.
+            let Some(pubkey) = pubkey_cache.get_pubkey_from_pubkey_bytes(&partial.pubkey) else {
+                continue;
+            };
+            let signature_set = sync_committee_message_signature_set(
+                chain,
+                Cow::Borrowed(pubkey),
+                signature,
+                &partial.sync_message,
+            )?;
+            let (indices, signature_sets) = subcommittee_sets.entry(partial.subnet_id).or_default();
+            indices.push(i);
+            signature_sets.push(signature_set);
+        }
+
+        metrics::stop_timer(signature_setup_timer);
+
+        let _signature_verification_timer =
+            metrics::start_timer(&metrics::SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_TIMES);
+
+        for (indices, signature_sets) in subcommittee_sets.values() {
+            let mut valid = vec![false; signature_sets.len()];
+            if verify_signature_sets(signature_sets.iter()) {
+                valid.fill(true);
+            } else {
+                metrics::inc_counter(&metrics::SYNC_MESSAGE_PROCESSING_BATCH_SIGNATURE_FAILURES);
+                find_valid_signature_sets(signature_sets, &mut valid);
+            }
+            for (&i, valid) in indices.iter().zip(valid) {
+                signature_valid[i] = valid;
+            }
+        }
+    }
+
+    // Complete the verification, verifying any signatures which are not already known to be valid
+    // individually.
+    let results = partial_results
+        .into_iter()
+        .zip(signature_valid)
+        .map(|(result, signature_valid)| {
+            result.and_then(|partial| {
+                VerifiedSyncCommitteeMessage::from_partial(partial, chain, !signature_valid)
+            })
+        })
+        .collect();
+
+    Ok(results)
+}
+
 /// Verify that the `sync_contribution` is within the acceptable gossip propagation range, with reference
 /// to the current slot of the `chain`.
 ///
@@ -686,20 +843,9 @@ pub fn verify_sync_committee_message<T: BeaconChainTypes>(
         .map(Cow::Borrowed)
         .ok_or(Error::UnknownValidatorPubkey(*pubkey_bytes))?;
 
-    let next_slot_epoch = (sync_message.get_slot() + 1).epoch(T::EthSpec::slots_per_epoch());
-    let fork = chain.spec.fork_at_epoch(next_slot_epoch);
-
     let agg_sig = AggregateSignature::from(&sync_message.signature);
-    let signature_set = sync_committee_message_set_from_pubkeys::<T::EthSpec>(
-        pubkey,
-        &agg_sig,
-        sync_message.slot.epoch(T::EthSpec::slots_per_epoch()),
-        sync_message.beacon_block_root,
-        &fork,
-        chain.genesis_validators_root,
-        &chain.spec,
-    )
-    .map_err(BeaconChainError::SignatureSetError)?;
+    let signature_set =
+        sync_committee_message_signature_set(chain, pubkey, &agg_sig, sync_message)?;
 
     metrics::stop_timer(signature_setup_timer);
 
@@ -712,3 +858,25 @@ pub fn verify_sync_committee_message<T: BeaconChainTypes>(
         Err(Error::InvalidSignature)
     }
 }
+
+/// Returns the signature set for `sync_message`, signed by `pubkey`.
+fn sync_committee_message_signature_set<'a, T: BeaconChainTypes>(
+    chain: &'a BeaconChain<T>,
+    pubkey: Cow<'a, PublicKey>,
+    signature: &'a AggregateSignature,
+    sync_message: &SyncCommitteeMessage,
+) -> Result<SignatureSet<'a>, Error> {
+    let next_slot_epoch = (sync_message.get_slot() + 1).epoch(T::EthSpec::slots_per_epoch());
+    let fork = chain.spec.fork_at_epoch(next_slot_epoch);
+
+    sync_committee_message_set_from_pubkeys::<T::EthSpec>(
+        pubkey,
+        signature,
+        sync_message.slot.epoch(T::EthSpec::slots_per_epoch()),
+        sync_message.beacon_block_root,
+        &fork,
+        chain.genesis_validators_root,
+        &chain.spec,
+    )
+    .map_err(|e| BeaconChainError::SignatureSetError(e).into())
+}
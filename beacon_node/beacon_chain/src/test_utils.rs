@@ -28,6 +28,7 @@ use execution_layer::{
     ExecutionLayer,
 };
 use futures::channel::mpsc::Receiver;
+use genesis::{interop_deposit_data, Eth1Config};
 pub use genesis::{interop_genesis_state_with_eth1, DEFAULT_ETH1_BLOCK_HASH};
 use int_to_bytes::int_to_bytes32;
 use kzg::{Kzg, TrustedSetup};
@@ -45,6 +46,7 @@ use slog::{o, Drain, Logger};
 use slog_async::Async;
 use slog_term::{FullFormat, TermDecorator};
 use slot_clock::{SlotClock, TestingSlotClock};
+use state_processing::common::DepositDataTree;
 use state_processing::per_block_processing::compute_timestamp_at_slot;
 use state_processing::state_advance::complete_state_advance;
 use std::borrow::Cow;
@@ -189,6 +191,7 @@ pub struct Builder<T: BeaconChainTypes> {
     mock_execution_layer: Option<MockExecutionLayer<T::EthSpec>>,
     testing_slot_clock: Option<TestingSlotClock>,
     validator_monitor_config: Option<ValidatorMonitorConfig>,
+    caching_eth1_backend: bool,
     runtime: TestRuntime,
     log: Logger,
 }
@@ -325,6 +328,7 @@ where
             mock_execution_layer: None,
             testing_slot_clock: None,
             validator_monitor_config: None,
+            caching_eth1_backend: false,
             runtime,
             log,
         }
@@ -501,6 +505,14 @@ where
         self
     }
 
+    /// Use a caching eth1 backend with an empty deposit cache, rather than the dummy backend.
+    ///
+    /// The backend is never connected to an execution node.
+    pub fn caching_eth1_backend(mut self) -> Self {
+        self.caching_eth1_backend = true;
+        self
+    }
+
     pub fn build(self) -> BeaconChainHarness<BaseHarnessType<E, Hot, Cold>> {
         let (shutdown_tx, shutdown_receiver) = futures::channel::mpsc::channel(1);
 
@@ -526,8 +538,6 @@ where
             )
             .task_executor(self.runtime.task_executor.clone())
             .execution_layer(self.execution_layer)
-            .dummy_eth1_backend()
-            .expect("should build dummy backend")
             .shutdown_sender(shutdown_tx)
             .chain_config(chain_config)
             .event_handler(Some(ServerSentEventHandler::new_with_capacity(
@@ -537,6 +547,16 @@ where
             .validator_monitor_config(validator_monitor_config)
             .kzg(kzg);
 
+        builder = if self.caching_eth1_backend {
+            let backend = CachingEth1Backend::new(Eth1Config::default(), log.clone(), spec.clone())
+                .expect("should build caching eth1 backend");
+            builder.eth1_backend(Some(backend))
+        } else {
+            builder
+                .dummy_eth1_backend()
+                .expect("should build dummy backend")
+        };
+
         builder = if let Some(mutator) = self.initial_mutator {
             mutator(builder)
         } else {
@@ -1749,6 +1769,41 @@ where
         .sign(sk, genesis_validators_root, &self.chain.spec)
     }
 
+    /// Make a consolidation signed by both the source and target validators.
+    pub fn make_consolidation(
+        &self,
+        source_index: u64,
+        target_index: u64,
+        epoch: Epoch,
+    ) -> SignedConsolidation {
+        let message = Consolidation {
+            source_index,
+            target_index,
+            epoch,
+        };
+        let domain = self.spec.compute_domain(
+            Domain::Consolidation,
+            self.spec.genesis_fork_version,
+            self.chain.genesis_validators_root,
+        );
+        let signing_root = message.signing_root(domain);
+
+        let mut signature = AggregateSignature::infinity();
+        for index in [source_index, target_index] {
+            signature.add_assign(
+                &self.validator_keypairs[index as usize]
+                    .sk
+                    .sign(signing_root),
+            );
+        }
+
+        SignedConsolidation {
+            message,
+            signature: Signature::deserialize(&signature.serialize())
+                .expect("aggregate signature should be a valid signature"),
+        }
+    }
+
     pub fn add_proposer_slashing(&self, validator_index: u64) -> Result<(), String> {
         let propposer_slashing = self.make_proposer_slashing(validator_index);
         if let ObservationOutcome::New(verified_proposer_slashing) = self
@@ -1902,6 +1957,42 @@ where
         ((Arc::new(signed_block), blobs), state)
     }
 
+    /// Returns a deposit snapshot which finalizes the first `deposit_count` genesis deposits.
+    pub fn genesis_deposit_snapshot(&self, deposit_count: usize) -> DepositTreeSnapshot {
+        let genesis_state = self
+            .chain
+            .get_state(&self.chain.genesis_state_root, Some(Slot::new(0)))
+            .unwrap()
+            .unwrap();
+        let withdrawal_credentials = genesis_state
+            .validators()
+            .iter()
+            .take(deposit_count)
+            .map(|validator| validator.withdrawal_credentials)
+            .collect::<Vec<_>>();
+        let leaves = interop_deposit_data(
+            &self.validator_keypairs[..deposit_count],
+            &withdrawal_credentials,
+            &self.spec,
+        )
+        .unwrap()
+        .iter()
+        .map(|data| data.tree_hash_root())
+        .collect::<Vec<_>>();
+
+        let depth = self.spec.deposit_contract_tree_depth as usize;
+        let mut tree = DepositDataTree::create(&leaves, deposit_count, depth);
+        let deposit_root = tree.root();
+        tree.finalize(FinalizedExecutionBlock {
+            deposit_root,
+            deposit_count: deposit_count as u64,
+            block_hash: Hash256::from_slice(DEFAULT_ETH1_BLOCK_HASH),
+            block_height: 0,
+        })
+        .unwrap();
+        tree.get_snapshot().unwrap()
+    }
+
     pub fn make_deposits<'a>(
         &self,
         state: &'a mut BeaconState<E>,
@@ -6,6 +6,7 @@ use crate::beacon_proposer_cache::{BeaconProposerCache, TYPICAL_SLOTS_PER_EPOCH}
 use crate::metrics;
 use itertools::Itertools;
 use parking_lot::{Mutex, RwLock};
+use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
@@ -25,7 +26,7 @@ use types::consts::altair::{
     TIMELY_HEAD_FLAG_INDEX, TIMELY_SOURCE_FLAG_INDEX, TIMELY_TARGET_FLAG_INDEX,
 };
 use types::{
-    Attestation, AttestationData, AttesterSlashingRef, BeaconBlockRef, BeaconState,
+    Attestation, AttestationData, AttestationRef, AttesterSlashingRef, BeaconBlockRef, BeaconState,
     BeaconStateError, ChainSpec, Epoch, EthSpec, Hash256, IndexedAttestation,
     IndexedAttestationRef, ProposerSlashing, PublicKeyBytes, SignedAggregateAndProof,
     SignedContributionAndProof, Slot, SyncCommitteeMessage, VoluntaryExit,
@@ -55,12 +56,21 @@ pub const MISSED_BLOCK_LAG_SLOTS: usize = 4;
 /// is only populated for the current and the previous epoch.
 pub const MISSED_BLOCK_LOOKBACK_EPOCHS: u64 = 1;
 
+/// The maximum number of alerts which may be waiting to be sent to the alert webhook. Alerts
+/// produced whilst the queue is full are dropped.
+pub const MAX_PENDING_ALERTS: usize = 1_024;
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 // Initial configuration values for the `ValidatorMonitor`.
 pub struct ValidatorMonitorConfig {
     pub auto_register: bool,
     pub validators: Vec<PublicKeyBytes>,
     pub individual_tracking_threshold: usize,
+    /// If set, alerts about monitored validators are POSTed to this URL.
+    pub alert_webhook: Option<SensitiveUrl>,
+    /// If set, an alert is raised when a validator's attestation effectiveness drops below this
+    /// value (between 0 and 1).
+    pub alert_effectiveness_threshold: Option<f64>,
 }
 
 impl Default for ValidatorMonitorConfig {
@@ -69,10 +79,55 @@ impl Default for ValidatorMonitorConfig {
             auto_register: false,
             validators: vec![],
             individual_tracking_threshold: DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
+            alert_webhook: None,
+            alert_effectiveness_threshold: None,
         }
     }
 }
 
+/// A notable event concerning a monitored validator, intended to be delivered to an external
+/// alerting system.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValidatorMonitorAlert {
+    /// None of the validator's attestations for `epoch` were included on-chain.
+    MissedAttestation {
+        #[serde(with = "serde_utils::quoted_u64")]
+        validator_index: u64,
+        epoch: Epoch,
+    },
+    /// The validator was the proposer at `slot` but no block was produced.
+    MissedBlock {
+        #[serde(with = "serde_utils::quoted_u64")]
+        validator_index: u64,
+        slot: Slot,
+        parent_root: Hash256,
+    },
+    /// The validator's attestation effectiveness dropped below `threshold` at `epoch`.
+    LowEffectiveness {
+        #[serde(with = "serde_utils::quoted_u64")]
+        validator_index: u64,
+        epoch: Epoch,
+        effectiveness: f64,
+        threshold: f64,
+    },
+}
+
+/// The position at which a validator's attestation was included in a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttestationInclusion {
+    pub attestation_slot: Slot,
+    /// The index of the committee in which the validator attested.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub committee_index: u64,
+    pub block_slot: Slot,
+    pub block_root: Hash256,
+    /// The position of the attestation within the block's attestations.
+    pub attestation_index: usize,
+    /// The position of the validator's bit within the attestation's aggregation bits.
+    pub aggregation_bit: usize,
+}
+
 /// The goal is to check the behaviour of the BN if it pretends to attest at each slot
 /// Check the head/target/source once the state.slot is some slots beyond attestation.data.slot
 /// to defend against re-orgs. 16 slots is the minimum to defend against re-orgs of up to 16 slots.
@@ -82,6 +137,10 @@ pub const UNAGGREGATED_ATTESTATION_LAG_SLOTS: usize = 16;
 /// from the current and previous epoch.
 pub const MAX_UNAGGREGATED_ATTESTATION_HASHMAP_LENGTH: usize = 64;
 
+/// The maximum number of validators that can be registered in a single API request, and the
+/// maximum number of monitored validators beyond which API registrations are ignored.
+pub const MAX_API_REGISTERED_VALIDATORS: usize = 65_536;
+
 #[derive(Debug)]
 pub enum Error {
     InvalidPubkey(String),
@@ -155,6 +214,10 @@ pub struct EpochSummary {
      */
     /// The total balance of the validator.
     pub total_balance: Option<u64>,
+    /// Whether an attestation for this epoch made it on-chain, as determined by epoch processing.
+    pub attestation_hit: Option<bool>,
+    /// The positions at which the validator's attestations were included in blocks.
+    pub attestation_inclusions: Vec<AttestationInclusion>,
 }
 
 impl EpochSummary {
@@ -205,12 +268,19 @@ impl EpochSummary {
         self.sync_signature_contribution_inclusions += 1;
     }
 
-    pub fn register_attestation_block_inclusion(&mut self, inclusion_distance: Slot) {
+    pub fn register_attestation_block_inclusion(
+        &mut self,
+        inclusion_distance: Slot,
+        inclusion: Option<AttestationInclusion>,
+    ) {
         self.attestation_block_inclusions += 1;
         Self::update_if_lt(
             &mut self.attestation_min_block_inclusion_distance,
             inclusion_distance,
         );
+        if let Some(inclusion) = inclusion {
+            self.attestation_inclusions.push(inclusion);
+        }
     }
 
     pub fn register_sync_signature_block_inclusions(&mut self) {
@@ -232,6 +302,10 @@ impl EpochSummary {
     pub fn register_validator_total_balance(&mut self, total_balance: u64) {
         self.total_balance = Some(total_balance)
     }
+
+    pub fn register_attestation_hit(&mut self, hit: bool) {
+        self.attestation_hit = Some(hit)
+    }
 }
 
 type SummaryMap = HashMap<Epoch, EpochSummary>;
@@ -275,6 +349,13 @@ impl ValidatorMetrics {
     pub fn set_latest_inclusion_distance(&mut self, distance: u64) {
         self.latest_attestation_inclusion_distance = distance;
     }
+
+    /// Returns the fraction of epochs in which an attestation made it on-chain, or `None` if no
+    /// epochs have been observed.
+    pub fn attestation_effectiveness(&self) -> Option<f64> {
+        let total = self.attestation_hits + self.attestation_misses;
+        (total > 0).then(|| self.attestation_hits as f64 / total as f64)
+    }
 }
 
 /// A validator that is being monitored by the `ValidatorMonitor`.
@@ -366,6 +447,21 @@ impl MonitoredValidator {
             summary_opt.and_then(|summary| summary.total_balance)
         })
     }
+
+    pub fn get_attestation_hit(&self, epoch: Epoch) -> Option<bool> {
+        self.get_from_epoch_summary(epoch, |summary_opt| {
+            summary_opt.and_then(|summary| summary.attestation_hit)
+        })
+    }
+
+    /// Returns the positions at which the validator's attestations for `epoch` were included in
+    /// blocks.
+    pub fn get_attestation_inclusions(&self, epoch: Epoch) -> Vec<AttestationInclusion> {
+        self.get_from_epoch_summary(epoch, |summary_opt| {
+            summary_opt.map(|summary| summary.attestation_inclusions.clone())
+        })
+        .unwrap_or_default()
+    }
 }
 
 #[derive(PartialEq, Hash, Eq)]
@@ -401,6 +497,12 @@ pub struct ValidatorMonitor<E: EthSpec> {
     beacon_proposer_cache: Arc<Mutex<BeaconProposerCache>>,
     // Unaggregated attestations generated by the committee index at each slot.
     unaggregated_attestations: HashMap<Slot, Attestation<E>>,
+    /// The URL to which alerts are delivered. Alerts are only collected if this is set.
+    alert_webhook: Option<SensitiveUrl>,
+    /// Raise an alert when a validator's attestation effectiveness drops below this value.
+    alert_effectiveness_threshold: Option<f64>,
+    /// Alerts which are waiting to be delivered to the `alert_webhook`.
+    pending_alerts: Mutex<Vec<ValidatorMonitorAlert>>,
     log: Logger,
     _phantom: PhantomData<E>,
 }
@@ -415,6 +517,8 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             auto_register,
             validators,
             individual_tracking_threshold,
+            alert_webhook,
+            alert_effectiveness_threshold,
         } = config;
 
         let mut s = Self {
@@ -425,6 +529,9 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             missed_blocks: <_>::default(),
             beacon_proposer_cache,
             unaggregated_attestations: <_>::default(),
+            alert_webhook,
+            alert_effectiveness_threshold,
+            pending_alerts: <_>::default(),
             log,
             _phantom: PhantomData,
         };
@@ -441,6 +548,30 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         self.validators.len() <= self.individual_tracking_threshold
     }
 
+    /// Returns the URL to which alerts should be delivered, if any.
+    pub fn alert_webhook(&self) -> Option<&SensitiveUrl> {
+        self.alert_webhook.as_ref()
+    }
+
+    /// Queue `alert` for delivery to the alert webhook, if one is configured.
+    fn push_alert(&self, alert: ValidatorMonitorAlert) {
+        if self.alert_webhook.is_none() {
+            return;
+        }
+
+        let mut pending_alerts = self.pending_alerts.lock();
+        if pending_alerts.len() < MAX_PENDING_ALERTS {
+            pending_alerts.push(alert);
+        } else {
+            metrics::inc_counter(&metrics::VALIDATOR_MONITOR_ALERTS_DROPPED_TOTAL);
+        }
+    }
+
+    /// Removes and returns all alerts which are waiting to be delivered.
+    pub fn drain_alerts(&self) -> Vec<ValidatorMonitorAlert> {
+        std::mem::take(&mut *self.pending_alerts.lock())
+    }
+
     /// Add some validators to `self` for additional monitoring.
     pub fn add_validator_pubkey(&mut self, pubkey: PublicKeyBytes) {
         let index_opt = self
@@ -449,6 +580,8 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             .find(|(_, candidate_pk)| **candidate_pk == pubkey)
             .map(|(index, _)| *index);
 
+        let was_individual_tracking = self.individual_tracking();
+
         let log = self.log.clone();
         self.validators.entry(pubkey).or_insert_with(|| {
             info!(
@@ -458,6 +591,27 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             );
             MonitoredValidator::new(pubkey, index_opt)
         });
+
+        self.check_individual_tracking(was_individual_tracking);
+    }
+
+    /// Remove the per-validator metrics if newly added validators pushed us over the threshold.
+    ///
+    /// The per-validator metrics will no longer be updated, so remove them rather than leaving
+    /// stale series in Prometheus.
+    fn check_individual_tracking(&self, was_individual_tracking: bool) {
+        if was_individual_tracking && !self.individual_tracking() {
+            info!(
+                self.log,
+                "Switching to aggregate validator metrics";
+                "info" => "per-validator metrics are disabled above the threshold",
+                "threshold" => self.individual_tracking_threshold,
+                "num_validators" => self.validators.len(),
+            );
+            for validator in self.validators.values() {
+                remove_individual_metrics(&validator.id);
+            }
+        }
     }
 
     /// Add an unaggregated attestation
@@ -667,6 +821,11 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                                             "slot" => slot,
                                             "parent block root" => ?prev_block_root,
                                         );
+                                        self.push_alert(ValidatorMonitorAlert::MissedBlock {
+                                            validator_index: i,
+                                            slot,
+                                            parent_root: *prev_block_root,
+                                        });
                                     }
                                 }
                             } else {
@@ -837,6 +996,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
 
                 // Store some metrics directly to be re-exposed on the HTTP API.
                 let mut validator_metrics = monitored_validator.metrics.write();
+                let previous_effectiveness = validator_metrics.attestation_effectiveness();
                 if previous_epoch_matched_any {
                     validator_metrics.increment_hits();
                     if previous_epoch_matched_target {
@@ -852,6 +1012,27 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                 } else {
                     validator_metrics.increment_misses()
                 }
+                monitored_validator.with_epoch_summary(prev_epoch, |summary| {
+                    summary.register_attestation_hit(previous_epoch_matched_any)
+                });
+
+                // Only alert when the effectiveness crosses the threshold, rather than every epoch
+                // that it remains below it.
+                if let (Some(threshold), Some(effectiveness)) = (
+                    self.alert_effectiveness_threshold,
+                    validator_metrics.attestation_effectiveness(),
+                ) {
+                    if effectiveness < threshold
+                        && previous_effectiveness.map_or(true, |previous| previous >= threshold)
+                    {
+                        self.push_alert(ValidatorMonitorAlert::LowEffectiveness {
+                            validator_index: i as u64,
+                            epoch: prev_epoch,
+                            effectiveness,
+                            threshold,
+                        });
+                    }
+                }
 
                 // Indicates if any attestation made it on-chain.
                 //
@@ -884,6 +1065,10 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                         );
                     });
                     attestation_miss.push(id);
+                    self.push_alert(ValidatorMonitorAlert::MissedAttestation {
+                        validator_index: i as u64,
+                        epoch: prev_epoch,
+                    });
                     if self.individual_tracking() {
                         debug!(
                             self.log,
@@ -1128,14 +1313,75 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                     "validator" => %validator_index,
                 );
 
+                let was_individual_tracking = self.individual_tracking();
                 self.validators.insert(
                     *pubkey,
                     MonitoredValidator::new(*pubkey, Some(validator_index)),
                 );
+                self.check_individual_tracking(was_individual_tracking);
             }
         }
     }
 
+    /// Add `pubkeys` to the monitor if `self.auto_register` is true, otherwise do nothing.
+    ///
+    /// Used by validator clients to register all of their keys, including those without duties.
+    /// No more keys are added once `MAX_API_REGISTERED_VALIDATORS` validators are monitored.
+    /// Returns the number of validators that were added.
+    pub fn auto_register_local_pubkeys(&mut self, pubkeys: &[PublicKeyBytes]) -> usize {
+        if !self.auto_register {
+            return 0;
+        }
+
+        let new_pubkeys = pubkeys
+            .iter()
+            .filter(|pubkey| !self.validators.contains_key(*pubkey))
+            .copied()
+            .collect::<HashSet<_>>();
+        if new_pubkeys.is_empty() {
+            return 0;
+        }
+
+        let capacity = MAX_API_REGISTERED_VALIDATORS.saturating_sub(self.validators.len());
+        if new_pubkeys.len() > capacity {
+            warn!(
+                self.log,
+                "Validator monitor is full";
+                "info" => "some validators registered via the API will not be monitored",
+                "ignored" => new_pubkeys.len() - capacity,
+                "limit" => MAX_API_REGISTERED_VALIDATORS,
+            );
+        }
+
+        let indices = self
+            .indices
+            .iter()
+            .filter(|(_, pubkey)| new_pubkeys.contains(*pubkey))
+            .map(|(index, pubkey)| (*pubkey, *index))
+            .collect::<HashMap<_, _>>();
+
+        let was_individual_tracking = self.individual_tracking();
+        let mut added = 0;
+        for pubkey in new_pubkeys.into_iter().take(capacity) {
+            let index_opt = indices.get(&pubkey).copied();
+            self.validators
+                .insert(pubkey, MonitoredValidator::new(pubkey, index_opt));
+            added += 1;
+        }
+
+        if added > 0 {
+            info!(
+                self.log,
+                "Started monitoring validators";
+                "count" => added,
+                "total" => self.validators.len(),
+            );
+        }
+        self.check_individual_tracking(was_individual_tracking);
+
+        added
+    }
+
     /// Process a block received on gossip.
     pub fn register_gossip_block<S: SlotClock>(
         &self,
@@ -1435,10 +1681,18 @@ impl<E: EthSpec> ValidatorMonitor<E> {
     /// We use the parent slot instead of block slot to ignore skip slots when calculating inclusion distance.
     ///
     /// Note: Blocks that get orphaned will skew the inclusion distance calculation.
+    ///
+    /// `attestation_index` is the position of `attestation` within the block's attestations.
+    #[allow(clippy::too_many_arguments)]
     pub fn register_attestation_in_block(
         &self,
         indexed_attestation: IndexedAttestationRef<'_, E>,
+        attestation: AttestationRef<'_, E>,
+        attestation_index: usize,
+        block_root: Hash256,
+        block_slot: Slot,
         parent_slot: Slot,
+        state: &BeaconState<E>,
         spec: &ChainSpec,
     ) {
         let data = indexed_attestation.data();
@@ -1450,10 +1704,36 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         let delay = inclusion_distance - spec.min_attestation_inclusion_delay;
         let epoch = data.slot.epoch(E::slots_per_epoch());
 
+        // Only compute the positions of attesters if a monitored validator attested.
+        let mut bit_positions = None;
+
         indexed_attestation.attesting_indices_iter().for_each(|i| {
             if let Some(validator) = self.get_validator(*i) {
                 let id = &validator.id;
 
+                let bit_positions = bit_positions.get_or_insert_with(|| {
+                    aggregation_bit_positions(attestation, state).unwrap_or_else(|e| {
+                        debug!(
+                            self.log,
+                            "Unable to compute aggregation bits";
+                            "attestation_slot" => data.slot,
+                            "error" => ?e,
+                        );
+                        HashMap::new()
+                    })
+                });
+                let inclusion =
+                    bit_positions
+                        .get(i)
+                        .map(|(committee_index, bit)| AttestationInclusion {
+                            attestation_slot: data.slot,
+                            committee_index: *committee_index,
+                            block_slot,
+                            block_root,
+                            attestation_index,
+                            aggregation_bit: *bit,
+                        });
+
                 self.aggregatable_metric(id, |label| {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_ATTESTATION_IN_BLOCK_TOTAL,
@@ -1505,7 +1785,8 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                 }
 
                 validator.with_epoch_summary(epoch, |summary| {
-                    summary.register_attestation_block_inclusion(inclusion_distance)
+                    summary
+                        .register_attestation_block_inclusion(inclusion_distance, inclusion.clone())
                 });
             }
         })
@@ -1895,6 +2176,10 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             &metrics::VALIDATOR_MONITOR_VALIDATORS_TOTAL,
             self.num_validators() as i64,
         );
+        metrics::set_gauge(
+            &metrics::VALIDATOR_MONITOR_INDIVIDUAL_TRACKING,
+            i64::from(self.individual_tracking()),
+        );
 
         if let Some(slot) = slot_clock.now() {
             let epoch = slot.epoch(E::slots_per_epoch());
@@ -2116,6 +2401,57 @@ pub fn timestamp_now() -> Duration {
         .unwrap_or_else(|_| Duration::from_secs(0))
 }
 
+/// Remove all of the per-validator metrics for the validator with the given `id`.
+///
+/// The aggregate metrics (labelled with `TOTAL_LABEL`) are retained.
+fn remove_individual_metrics(id: &str) {
+    for metric in metrics::validator_monitor_individual_metrics() {
+        metric.remove_label_values(&[id]);
+    }
+    for src in ["gossip", "api", "block"] {
+        for metric in metrics::validator_monitor_individual_src_metrics() {
+            metric.remove_label_values(&[src, id]);
+        }
+    }
+}
+
+/// Returns the committee index and aggregation bit of each validator which participated in
+/// `attestation`, keyed by validator index.
+fn aggregation_bit_positions<E: EthSpec>(
+    attestation: AttestationRef<E>,
+    state: &BeaconState<E>,
+) -> Result<HashMap<u64, (u64, usize)>, BeaconStateError> {
+    let mut positions = HashMap::new();
+    match attestation {
+        AttestationRef::Base(att) => {
+            let committee = state.get_beacon_committee(att.data.slot, att.data.index)?;
+            for (bit, validator_index) in committee.committee.iter().enumerate() {
+                if att.aggregation_bits.get(bit).unwrap_or(false) {
+                    positions.insert(*validator_index as u64, (att.data.index, bit));
+                }
+            }
+        }
+        AttestationRef::Electra(att) => {
+            // The aggregation bits of each committee are concatenated in order of committee index.
+            let committees = state.get_beacon_committees_at_slot(att.data.slot)?;
+            let mut committee_offset = 0;
+            for committee_index in att.get_committee_indices() {
+                let committee = committees
+                    .get(committee_index as usize)
+                    .ok_or(BeaconStateError::NoCommitteeFound(committee_index))?;
+                for (i, validator_index) in committee.committee.iter().enumerate() {
+                    let bit = committee_offset + i;
+                    if att.aggregation_bits.get(bit).unwrap_or(false) {
+                        positions.insert(*validator_index as u64, (committee_index, bit));
+                    }
+                }
+                committee_offset += committee.committee.len();
+            }
+        }
+    }
+    Ok(positions)
+}
+
 fn u64_to_i64(n: impl Into<u64>) -> i64 {
     i64::try_from(n.into()).unwrap_or(i64::MAX)
 }
@@ -1545,6 +1545,62 @@ async fn verify_attestation_for_gossip_doppelganger_detection() {
         .expect("should check if gossip aggregator was observed"));
 }
 
+/// Ensures that a single invalid signature in a batch of unaggregated attestations is attributed to
+/// the correct attestation, without causing the others to be rejected.
+#[tokio::test]
+async fn batch_unaggregated_verification_isolates_invalid_signature() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            MainnetEthSpec::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::SomeValidators(vec![]),
+        )
+        .await;
+    harness.advance_slot();
+
+    let (state, state_root) = harness.get_current_state_and_root();
+    let attesters = (0..VALIDATOR_COUNT).collect::<Vec<_>>();
+    let mut attestations = harness
+        .make_unaggregated_attestations(
+            &attesters,
+            &state,
+            state_root,
+            harness.head_block_root().into(),
+            harness.get_current_slot(),
+        )
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    assert!(
+        attestations.len() > 4,
+        "batch should be large enough to split"
+    );
+
+    // Give one attestation the signature of another, making it invalid.
+    let invalid_index = attestations.len() - 2;
+    let other_signature = attestations[0].0.signature().clone();
+    *attestations[invalid_index].0.signature_mut() = other_signature;
+
+    let results = batch_verify_unaggregated_attestations(
+        attestations
+            .iter()
+            .map(|(attestation, subnet_id)| (attestation, Some(*subnet_id))),
+        &harness.chain,
+    )
+    .expect("should return attestation results");
+
+    assert_eq!(results.len(), attestations.len());
+    for (i, result) in results.into_iter().enumerate() {
+        if i == invalid_index {
+            assert!(matches!(result, Err(Error::InvalidSignature)));
+        } else {
+            assert!(result.is_ok(), "attestation {i} should be valid");
+        }
+    }
+}
+
 #[tokio::test]
 async fn attestation_verification_use_head_state_fork() {
     let (harness, spec) = get_harness_capella_spec(VALIDATOR_COUNT);
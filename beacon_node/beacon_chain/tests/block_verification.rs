@@ -11,6 +11,7 @@ use beacon_chain::{
     BeaconSnapshot, BlockError, ChainConfig, ChainSegmentResult, IntoExecutionPendingBlock,
     NotifyExecutionLayer,
 };
+use eth2::types::EventKind;
 use lazy_static::lazy_static;
 use logging::test_logger;
 use slasher::{Config as SlasherConfig, Slasher};
@@ -1277,6 +1278,107 @@ async fn verify_block_for_gossip_slashing_detection() {
     slasher_dir.close().unwrap();
 }
 
+#[tokio::test]
+async fn verify_block_for_gossip_equivocation_slashing() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let mut event_receiver = harness
+        .chain
+        .event_handler
+        .as_ref()
+        .unwrap()
+        .subscribe_block_equivocation();
+
+    let state = harness.get_current_state();
+    let ((block1, blobs1), _) = harness.make_block(state.clone(), Slot::new(1)).await;
+    let ((block2, _blobs2), _) = harness.make_block(state, Slot::new(1)).await;
+    let block_root1 = block1.canonical_root();
+    let block_root2 = block2.canonical_root();
+
+    harness
+        .process_block(Slot::new(1), block_root1, (block1, blobs1))
+        .await
+        .unwrap();
+    assert!(matches!(
+        unwrap_err(harness.chain.verify_block_for_gossip(block2.clone()).await),
+        BlockError::Slashable
+    ));
+
+    // The conflicting block should have been turned into a slashing for the op pool.
+    let (proposer_slashings, _, _) = harness
+        .chain
+        .op_pool
+        .get_slashings_and_exits(&harness.get_current_state(), &harness.chain.spec);
+    assert_eq!(proposer_slashings.len(), 1);
+    let slashing = &proposer_slashings[0];
+    assert_eq!(
+        slashing.signed_header_1.message.canonical_root(),
+        block_root1
+    );
+    assert_eq!(
+        slashing.signed_header_2.message.canonical_root(),
+        block_root2
+    );
+
+    match event_receiver.try_recv().unwrap() {
+        EventKind::BlockEquivocation(event) => {
+            assert_eq!(event.slot, Slot::new(1));
+            assert_eq!(event.proposer_index, block2.message().proposer_index());
+            assert_eq!(event.previous_block_root, block_root1);
+            assert_eq!(event.block_root, block_root2);
+            assert_eq!(event.proposer_slashing.as_ref(), Some(slashing));
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn block_equivocation_event_is_only_emitted_once() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let mut event_receiver = harness
+        .chain
+        .event_handler
+        .as_ref()
+        .unwrap()
+        .subscribe_block_equivocation();
+
+    let state = harness.get_current_state();
+    let ((block1, _), _) = harness.make_block(state.clone(), Slot::new(1)).await;
+    let ((block2, _), _) = harness.make_block(state, Slot::new(1)).await;
+    let block_root2 = block2.canonical_root();
+
+    let verified_block1 = harness.chain.verify_block_for_gossip(block1).await.unwrap();
+    assert!(matches!(
+        unwrap_err(harness.chain.verify_block_for_gossip(block2.clone()).await),
+        BlockError::Slashable
+    ));
+    match event_receiver.try_recv().unwrap() {
+        EventKind::BlockEquivocation(event) => {
+            assert_eq!(event.previous_block_root, verified_block1.block_root);
+            assert_eq!(event.block_root, block_root2);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    // Importing the block which passed gossip observes it again, and so does gossiping the
+    // conflicting block again, but neither is a new equivocation.
+    harness
+        .chain
+        .process_block(
+            verified_block1.block_root,
+            verified_block1,
+            NotifyExecutionLayer::Yes,
+            BlockImportSource::Gossip,
+            || Ok(()),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(
+        unwrap_err(harness.chain.verify_block_for_gossip(block2).await),
+        BlockError::Slashable
+    ));
+    assert!(event_receiver.try_recv().is_err());
+}
+
 #[tokio::test]
 async fn verify_block_for_gossip_doppelganger_detection() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -0,0 +1,133 @@
+#![cfg(test)]
+
+use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
+use beacon_chain::DepositSnapshotImport;
+use lazy_static::lazy_static;
+use types::{DepositTreeSnapshot, Hash256, Keypair, MinimalEthSpec};
+
+pub const VALIDATOR_COUNT: usize = 16;
+
+type E = MinimalEthSpec;
+
+lazy_static! {
+    static ref KEYPAIRS: Vec<Keypair> =
+        types::test_utils::generate_deterministic_keypairs(VALIDATOR_COUNT);
+}
+
+/// Returns a harness whose deposit cache is empty, as it is after checkpoint sync from a node
+/// that didn't serve a deposit snapshot.
+fn get_harness() -> BeaconChainHarness<EphemeralHarnessType<E>> {
+    BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .caching_eth1_backend()
+        .build()
+}
+
+fn eth1_deposit_count(harness: &BeaconChainHarness<EphemeralHarnessType<E>>) -> u64 {
+    harness.chain.eth1_chain.as_ref().unwrap().deposit_count()
+}
+
+#[test]
+fn wants_deposit_snapshot() {
+    let harness = get_harness();
+    assert_eq!(eth1_deposit_count(&harness), 0);
+    assert!(harness.chain.wants_deposit_snapshot());
+
+    // The dummy backend has no deposit cache to fill.
+    let dummy_harness = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .build();
+    assert!(!dummy_harness.chain.wants_deposit_snapshot());
+}
+
+#[test]
+fn import_deposit_snapshot() {
+    let harness = get_harness();
+    let snapshot = harness.genesis_deposit_snapshot(VALIDATOR_COUNT);
+    assert_eq!(
+        snapshot.deposit_root,
+        harness
+            .chain
+            .head_snapshot()
+            .beacon_state
+            .eth1_data()
+            .deposit_root
+    );
+
+    assert_eq!(
+        harness.chain.import_deposit_snapshot(&snapshot).unwrap(),
+        DepositSnapshotImport::Imported
+    );
+    assert_eq!(eth1_deposit_count(&harness), VALIDATOR_COUNT as u64);
+    assert!(!harness.chain.wants_deposit_snapshot());
+
+    // A second snapshot with the same deposits has nothing to add.
+    assert_eq!(
+        harness.chain.import_deposit_snapshot(&snapshot).unwrap(),
+        DepositSnapshotImport::NotRequired
+    );
+}
+
+#[test]
+fn import_deposit_snapshot_with_invalid_root() {
+    let harness = get_harness();
+    let snapshot = DepositTreeSnapshot {
+        deposit_root: Hash256::repeat_byte(1),
+        ..harness.genesis_deposit_snapshot(VALIDATOR_COUNT)
+    };
+    assert!(!snapshot.is_valid());
+
+    assert_eq!(
+        harness.chain.import_deposit_snapshot(&snapshot).unwrap(),
+        DepositSnapshotImport::Invalid
+    );
+    assert_eq!(eth1_deposit_count(&harness), 0);
+}
+
+#[test]
+fn import_deposit_snapshot_with_mismatched_root() {
+    let harness = get_harness();
+    let snapshot = harness.genesis_deposit_snapshot(VALIDATOR_COUNT);
+
+    // A valid snapshot of the same number of deposits, but from different validators.
+    let other_keypairs = types::test_utils::generate_deterministic_keypairs(2 * VALIDATOR_COUNT);
+    let other_harness = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .keypairs(other_keypairs[VALIDATOR_COUNT..].to_vec())
+        .fresh_ephemeral_store()
+        .build();
+    let other_snapshot = other_harness.genesis_deposit_snapshot(VALIDATOR_COUNT);
+    assert!(other_snapshot.is_valid());
+    assert_eq!(other_snapshot.deposit_count, snapshot.deposit_count);
+    assert_ne!(other_snapshot.deposit_root, snapshot.deposit_root);
+
+    assert_eq!(
+        harness
+            .chain
+            .import_deposit_snapshot(&other_snapshot)
+            .unwrap(),
+        DepositSnapshotImport::Mismatch
+    );
+    assert_eq!(eth1_deposit_count(&harness), 0);
+    assert!(harness.chain.wants_deposit_snapshot());
+}
+
+#[test]
+fn import_deposit_snapshot_with_mismatched_deposit_count() {
+    let harness = get_harness();
+
+    // A valid snapshot of the deposit tree before the final genesis deposit.
+    let snapshot = harness.genesis_deposit_snapshot(VALIDATOR_COUNT - 1);
+    assert!(snapshot.is_valid());
+
+    assert_eq!(
+        harness.chain.import_deposit_snapshot(&snapshot).unwrap(),
+        DepositSnapshotImport::Mismatch
+    );
+    assert_eq!(eth1_deposit_count(&harness), 0);
+    assert!(harness.chain.wants_deposit_snapshot());
+}
@@ -0,0 +1,96 @@
+#![cfg(not(debug_assertions))] // Tests run too slow in debug.
+
+use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
+use execution_layer::test_utils::DEFAULT_ENGINE_CAPABILITIES;
+use execution_layer::EngineCapabilities;
+use std::time::Duration;
+use types::*;
+
+const VALIDATOR_COUNT: usize = 32;
+type E = MainnetEthSpec;
+
+fn get_harness(spec: ChainSpec) -> BeaconChainHarness<EphemeralHarnessType<E>> {
+    BeaconChainHarness::builder(E::default())
+        .spec(spec)
+        .logger(logging::test_logger())
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build()
+}
+
+#[tokio::test]
+async fn scheduled_fork_is_ready() {
+    let mut spec = ForkName::Capella.make_genesis_spec(E::default_spec());
+    spec.deneb_fork_epoch = Some(Epoch::new(2));
+    let harness = get_harness(spec);
+
+    let report = harness.chain.check_fork_readiness().await;
+    assert_eq!(report.current_fork, ForkName::Capella);
+    assert_eq!(report.next_fork, Some(ForkName::Deneb));
+    assert_eq!(report.next_fork_epoch, Some(Epoch::new(2)));
+    assert!(report.execution.unwrap().ready);
+    assert!(report.kzg_required);
+    assert!(report.kzg_loaded);
+    assert!(report.ready);
+}
+
+#[tokio::test]
+async fn unscheduled_fork_is_not_ready() {
+    let harness = get_harness(ForkName::Capella.make_genesis_spec(E::default_spec()));
+
+    let report = harness.chain.check_fork_readiness().await;
+    assert_eq!(report.current_fork, ForkName::Capella);
+    assert_eq!(report.next_fork, Some(ForkName::Deneb));
+    assert_eq!(report.next_fork_epoch, None);
+    // The harness only loads the trusted setup when Deneb is scheduled.
+    assert!(report.kzg_required);
+    assert!(!report.kzg_loaded);
+    assert!(!report.ready);
+}
+
+#[tokio::test]
+async fn unsupported_engine_methods_are_not_ready() {
+    let mut spec = ForkName::Capella.make_genesis_spec(E::default_spec());
+    spec.deneb_fork_epoch = Some(Epoch::new(2));
+    let harness = get_harness(spec);
+
+    harness
+        .mock_execution_layer
+        .as_ref()
+        .unwrap()
+        .server
+        .set_engine_capabilities(EngineCapabilities {
+            new_payload_v3: false,
+            ..DEFAULT_ENGINE_CAPABILITIES
+        });
+    // Refresh the cached capabilities.
+    harness
+        .chain
+        .execution_layer
+        .as_ref()
+        .unwrap()
+        .get_engine_capabilities(Some(Duration::ZERO))
+        .await
+        .unwrap();
+
+    let report = harness.chain.check_fork_readiness().await;
+    let execution = report.execution.unwrap();
+    assert!(!execution.ready);
+    assert!(execution.info.contains("engine_newPayloadV3"));
+    assert!(report.kzg_loaded);
+    assert!(!report.ready);
+}
+
+#[tokio::test]
+async fn latest_fork_has_no_next_fork() {
+    let harness = get_harness(ForkName::latest().make_genesis_spec(E::default_spec()));
+
+    let report = harness.chain.check_fork_readiness().await;
+    assert_eq!(report.current_fork, ForkName::latest());
+    assert_eq!(report.next_fork, None);
+    assert_eq!(report.next_fork_epoch, None);
+    assert_eq!(report.execution, None);
+    assert!(!report.kzg_required);
+    assert!(!report.ready);
+}
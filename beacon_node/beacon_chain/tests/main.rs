@@ -3,10 +3,13 @@ mod attestation_verification;
 mod bellatrix;
 mod block_verification;
 mod capella;
+mod deposit_snapshot;
 mod events;
+mod fork_readiness;
 mod op_verification;
 mod payload_invalidation;
 mod rewards;
+mod schema_migrations;
 mod store_tests;
 mod sync_committee_verification;
 mod tests;
@@ -1179,6 +1179,10 @@ async fn attesting_to_optimistic_head() {
         rig.execution_status(root).is_strictly_optimistic(),
         "the head should be optimistic"
     );
+    assert!(
+        !rig.cached_head().head_is_valid_or_irrelevant(),
+        "the cached head should be optimistic"
+    );
 
     /*
      * Define an attestation for use during testing. It doesn't have a valid signature, but that's
@@ -1265,9 +1269,16 @@ async fn attesting_to_optimistic_head() {
         "the head should no longer be optimistic"
     );
 
+    // The cached head is stale, so production must fall back to fork choice.
+    assert!(!rig.cached_head().head_is_valid_or_irrelevant());
     produce_unaggregated().unwrap();
     get_aggregated().unwrap();
     get_aggregated_by_slot_and_root().unwrap();
+
+    // Once the head is recomputed the cached head reflects the valid status.
+    rig.recompute_head().await;
+    assert!(rig.cached_head().head_is_valid_or_irrelevant());
+    produce_unaggregated().unwrap();
 }
 
 /// A helper struct to build out a chain of some configurable length which undergoes the merge
@@ -0,0 +1,270 @@
+//! Tests which migrate snapshots of the database at each historical `SchemaVersion` to
+//! `CURRENT_SCHEMA_VERSION`.
+//!
+//! Each snapshot is a copy of the database directories alongside an `invariants.json` recording
+//! the head, finalized checkpoint, split and anchor of the chain. Every snapshot is opened using
+//! the real migration chain, and the resumed chain is checked against its invariants.
+//!
+//! Snapshots come from two sources:
+//!
+//! - Fixtures checked in under `tests/schema_fixtures/v<VERSION>`. These should be written by the
+//!   release which introduces each schema version, using the ignored `write_schema_fixture` test:
+//!
+//!   `cargo test --release -p beacon_chain -- --ignored write_schema_fixture`
+//!
+//! - Snapshots generated by downgrading a fresh database one version at a time down to
+//!   `MIN_SCHEMA_VERSION`, which cover versions without a checked-in fixture.
+
+use beacon_chain::schema_change::migrate_schema;
+use beacon_chain::test_utils::{
+    test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, DiskHarnessType,
+};
+use lazy_static::lazy_static;
+use logging::test_logger;
+use serde::{Deserialize, Serialize};
+use slot_clock::{SlotClock, TestingSlotClock};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use store::metadata::{AnchorInfo, SchemaVersion, CURRENT_SCHEMA_VERSION};
+use store::{HotColdDB, LevelDB, StoreConfig};
+use tempfile::tempdir;
+use types::{Checkpoint, EthSpec, Hash256, Keypair, MinimalEthSpec, Slot};
+
+type E = MinimalEthSpec;
+type TestHarness = BeaconChainHarness<DiskHarnessType<E>>;
+type DiskStore = Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>;
+
+const VALIDATOR_COUNT: usize = 24;
+
+/// The oldest schema version which can be migrated to `CURRENT_SCHEMA_VERSION`.
+///
+/// This must be increased as old migrations are deprecated.
+const MIN_SCHEMA_VERSION: SchemaVersion = SchemaVersion(19);
+
+const FIXTURES_DIR: &str = "tests/schema_fixtures";
+const INVARIANTS_FILE: &str = "invariants.json";
+const DATABASE_DIRS: [&str; 3] = ["hot_db", "cold_db", "blobs_db"];
+
+lazy_static! {
+    /// A cached set of keys.
+    static ref KEYPAIRS: Vec<Keypair> =
+        types::test_utils::generate_deterministic_keypairs(VALIDATOR_COUNT);
+}
+
+/// Properties of a database which must be preserved by every migration.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Invariants {
+    genesis_time: u64,
+    /// The slot of the slot clock when the snapshot was taken.
+    current_slot: Slot,
+    head_block_root: Hash256,
+    head_slot: Slot,
+    finalized_checkpoint: Checkpoint,
+    split_slot: Slot,
+    anchor: Option<AnchorInfo>,
+    /// The number of blocks from the head back to genesis.
+    chain_length: usize,
+}
+
+impl Invariants {
+    fn new(harness: &TestHarness) -> Self {
+        let head = harness.chain.head_snapshot();
+        Self {
+            genesis_time: harness.chain.genesis_time,
+            current_slot: harness.chain.slot().unwrap(),
+            head_block_root: head.beacon_block_root,
+            head_slot: head.beacon_block.slot(),
+            finalized_checkpoint: head.beacon_state.finalized_checkpoint(),
+            split_slot: harness.chain.store.get_split_slot(),
+            anchor: harness.chain.store.get_anchor_info(),
+            chain_length: harness.chain.chain_dump().unwrap().len(),
+        }
+    }
+
+    fn load(dir: &Path) -> Self {
+        let bytes = fs::read(dir.join(INVARIANTS_FILE)).expect("should read invariants");
+        serde_json::from_slice(&bytes).expect("should decode invariants")
+    }
+
+    fn save(&self, dir: &Path) {
+        let bytes = serde_json::to_vec_pretty(self).unwrap();
+        fs::write(dir.join(INVARIANTS_FILE), bytes).expect("should write invariants");
+    }
+}
+
+/// Open the database in `dir`, migrating it to `CURRENT_SCHEMA_VERSION` if `migrate` is true.
+fn open_store(dir: &Path, migrate: bool) -> DiskStore {
+    let spec = test_spec::<E>();
+    let log = test_logger();
+    let migrate_spec = spec.clone();
+    let migrate_log = log.clone();
+
+    HotColdDB::open(
+        &dir.join("hot_db"),
+        &dir.join("cold_db"),
+        &dir.join("blobs_db"),
+        move |db, from, to| {
+            if migrate {
+                migrate_schema::<DiskHarnessType<E>>(db, 0, from, to, migrate_log, &migrate_spec)
+            } else {
+                Ok(())
+            }
+        },
+        StoreConfig::default(),
+        spec,
+        log,
+    )
+    .expect("disk store should open")
+}
+
+/// Build a finalized chain in a new database in `dir` and return its invariants.
+///
+/// The database is closed before returning, so that it can be copied.
+async fn build_chain(dir: &Path) -> Invariants {
+    let store = open_store(dir, false);
+    let harness = TestHarness::builder(MinimalEthSpec)
+        .spec(store.get_chain_spec().clone())
+        .keypairs(KEYPAIRS.to_vec())
+        .logger(store.logger().clone())
+        .fresh_disk_store(store)
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize * 4,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let invariants = Invariants::new(&harness);
+    assert_ne!(invariants.split_slot, 0, "the chain should finalize");
+    invariants
+}
+
+/// Resume a chain from `store`, with the slot clock of the snapshot with `invariants`.
+fn resume_harness(store: DiskStore, invariants: &Invariants) -> TestHarness {
+    let slot_clock = TestingSlotClock::new(
+        Slot::new(0),
+        Duration::from_secs(invariants.genesis_time),
+        Duration::from_secs(store.get_chain_spec().seconds_per_slot),
+    );
+    slot_clock.set_slot(invariants.current_slot.as_u64());
+
+    TestHarness::builder(MinimalEthSpec)
+        .spec(store.get_chain_spec().clone())
+        .keypairs(KEYPAIRS.to_vec())
+        .logger(store.logger().clone())
+        .testing_slot_clock(slot_clock)
+        .resumed_disk_store(store)
+        .mock_execution_layer()
+        .build()
+}
+
+/// Copy the database directories from `src` to `dest`.
+fn copy_database(src: &Path, dest: &Path) {
+    for db_dir in DATABASE_DIRS {
+        let dest_dir = dest.join(db_dir);
+        fs::create_dir_all(&dest_dir).unwrap();
+        for entry in fs::read_dir(src.join(db_dir)).unwrap() {
+            let entry = entry.unwrap();
+            fs::copy(entry.path(), dest_dir.join(entry.file_name())).unwrap();
+        }
+    }
+}
+
+/// Migrate a copy of the snapshot in `snapshot_dir` to the current schema version and check that
+/// its invariants hold.
+fn check_snapshot(snapshot_dir: &Path, invariants: &Invariants) {
+    let db_dir = tempdir().unwrap();
+    copy_database(snapshot_dir, db_dir.path());
+
+    let store = open_store(db_dir.path(), true);
+    assert_eq!(
+        store.load_schema_version().unwrap(),
+        Some(CURRENT_SCHEMA_VERSION),
+        "{} should migrate to the current schema version",
+        snapshot_dir.display()
+    );
+
+    let harness = resume_harness(store, invariants);
+    assert_eq!(
+        Invariants::new(&harness),
+        *invariants,
+        "invariants of {} should hold after migration",
+        snapshot_dir.display()
+    );
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(FIXTURES_DIR)
+}
+
+#[tokio::test]
+async fn migrate_generated_snapshots() {
+    let db_dir = tempdir().unwrap();
+    let snapshots_dir = tempdir().unwrap();
+    let invariants = build_chain(db_dir.path()).await;
+    let spec = test_spec::<E>();
+
+    let mut version = CURRENT_SCHEMA_VERSION;
+    while version > MIN_SCHEMA_VERSION {
+        let next = SchemaVersion(version.as_u64() - 1);
+        let store = open_store(db_dir.path(), false);
+        migrate_schema::<DiskHarnessType<E>>(
+            store.clone(),
+            0,
+            version,
+            next,
+            store.logger().clone(),
+            &spec,
+        )
+        .unwrap_or_else(|e| panic!("should downgrade from {:?}: {:?}", version, e));
+        drop(store);
+        version = next;
+
+        let snapshot_dir = snapshots_dir.path().join(format!("v{}", version.as_u64()));
+        copy_database(db_dir.path(), &snapshot_dir);
+        check_snapshot(&snapshot_dir, &invariants);
+    }
+}
+
+#[tokio::test]
+async fn migrate_checked_in_fixtures() {
+    let fixture_dirs = fs::read_dir(fixtures_dir())
+        .expect("schema fixtures should be checked in")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    assert!(
+        !fixture_dirs.is_empty(),
+        "{} should contain at least one fixture",
+        fixtures_dir().display()
+    );
+
+    for fixture_dir in fixture_dirs {
+        let invariants = Invariants::load(&fixture_dir);
+        check_snapshot(&fixture_dir, &invariants);
+    }
+}
+
+/// Write a fixture for `CURRENT_SCHEMA_VERSION` to `tests/schema_fixtures`.
+#[tokio::test]
+#[ignore]
+async fn write_schema_fixture() {
+    let fixture_dir = fixtures_dir().join(format!("v{}", CURRENT_SCHEMA_VERSION.as_u64()));
+    assert!(
+        !fixture_dir.exists(),
+        "{} already exists",
+        fixture_dir.display()
+    );
+
+    let db_dir = tempdir().unwrap();
+    let invariants = build_chain(db_dir.path()).await;
+    copy_database(db_dir.path(), &fixture_dir);
+    invariants.save(&fixture_dir);
+}
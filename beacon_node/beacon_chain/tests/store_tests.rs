@@ -26,12 +26,14 @@ use std::convert::TryInto;
 use std::sync::Arc;
 use std::time::Duration;
 use store::chunked_vector::Chunk;
+use store::compression::{is_compressed, CompressionAlgorithm};
 use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, STATE_UPPER_LIMIT_NO_RETAIN};
 use store::{
     chunked_vector::{chunk_key, Field},
     get_key_for_col,
     iter::{BlockRootsIterator, StateRootsIterator},
-    BlobInfo, DBColumn, HotColdDB, KeyValueStore, KeyValueStoreOp, LevelDB, StoreConfig,
+    BlobInfo, DBColumn, HotColdDB, KeyValueStore, KeyValueStoreOp, LevelDB, ParallelIterConfig,
+    StoreConfig,
 };
 use tempfile::{tempdir, TempDir};
 use tokio::time::sleep;
@@ -352,6 +354,70 @@ fn check_freezer_block_roots(harness: &TestHarness, start_slot: Slot, end_slot:
     }
 }
 
+/// Tests that `prune_history_before` deletes blocks prior to the pin, and that states from the pin
+/// onwards remain available.
+#[tokio::test]
+async fn prune_history_before() {
+    let num_blocks_produced = E::slots_per_epoch() * 20;
+    let slots_per_restore_point = 2 * E::slots_per_epoch();
+    let prune_history_before = Slot::new(9 * E::slots_per_epoch());
+    let db_path = tempdir().unwrap();
+    let mut spec = test_spec::<E>();
+    spec.min_epochs_for_block_requests = 4;
+    let store = get_store_generic(
+        &db_path,
+        StoreConfig {
+            slots_per_restore_point,
+            prune_history_before: Some(prune_history_before),
+            ..Default::default()
+        },
+        spec,
+    );
+    let harness = get_harness_generic(store.clone(), LOW_VALIDATOR_COUNT, ChainConfig::default());
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // The pin is rounded down to a restore point.
+    let target_slot = Slot::new(8 * E::slots_per_epoch());
+    let anchor = store.get_anchor_info().expect("anchor should be set");
+    assert_eq!(anchor.oldest_block_slot, target_slot);
+    assert_eq!(anchor.state_upper_limit, target_slot);
+
+    let block_roots = store
+        .forwards_block_roots_iterator_until(
+            Slot::new(0),
+            store.get_split_slot() - 1,
+            || unreachable!(),
+            &harness.chain.spec,
+        )
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+    for (block_root, slot) in block_roots {
+        let block = store.get_blinded_block(&block_root).unwrap();
+        if slot == 0 || slot >= target_slot {
+            assert!(block.is_some(), "block at slot {} should be retained", slot);
+        } else {
+            assert!(block.is_none(), "block at slot {} should be pruned", slot);
+        }
+        if slot + 1 == target_slot {
+            assert_eq!(anchor.oldest_block_parent, block_root);
+        }
+    }
+
+    for slot in (target_slot.as_u64()..store.get_split_slot().as_u64()).map(Slot::new) {
+        let state_root = harness.chain.state_root_at_slot(slot).unwrap().unwrap();
+        let state = store.get_state(&state_root, Some(slot)).unwrap().unwrap();
+        assert_eq!(state.slot(), slot);
+    }
+}
+
 #[tokio::test]
 async fn full_participation_no_skips() {
     let num_blocks_produced = E::slots_per_epoch() * 5;
@@ -719,6 +785,342 @@ async fn forwards_iter_block_and_state_roots_until() {
     test_range(Slot::new(0), head_state.slot());
 }
 
+#[tokio::test]
+async fn parallel_freezer_iteration() {
+    let num_blocks_produced = E::slots_per_epoch() * 20;
+    let db_path = tempdir().unwrap();
+    let store = get_store_generic(
+        &db_path,
+        StoreConfig {
+            slots_per_restore_point: 2 * E::slots_per_epoch(),
+            ..Default::default()
+        },
+        test_spec::<E>(),
+    );
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    let current_state = harness.get_current_state();
+    let state_root = current_state.canonical_root().unwrap();
+    let all_validators = &harness.get_all_validators();
+    harness
+        .add_attested_blocks_at_slots(
+            current_state,
+            state_root,
+            &(1..=num_blocks_produced)
+                .filter(|i| i % 12 != 0)
+                .map(Slot::new)
+                .collect::<Vec<_>>(),
+            all_validators,
+        )
+        .await;
+
+    let split_slot = store.get_split_slot();
+    let last_finalized_slot = split_slot - 1;
+    let config = ParallelIterConfig {
+        max_concurrent_loads: 3,
+        max_chunks_in_flight: 2,
+        max_bytes_in_flight: usize::MAX,
+        block_chunk_slots: 5,
+        states_per_chunk: 3,
+    };
+
+    // Blocks are yielded in order and once each, even if the first slot of a chunk is skipped.
+    for start_slot in [Slot::new(0), Slot::new(12), Slot::new(13)] {
+        let mut expected_roots = harness
+            .chain
+            .forwards_iter_block_roots_until(start_slot, last_finalized_slot)
+            .unwrap()
+            .map(Result::unwrap)
+            .map(|(root, _)| root)
+            .collect::<Vec<_>>();
+        expected_roots.dedup();
+        if start_slot == 12 {
+            // Slot 12 is skipped, so its root is that of the block at slot 11.
+            expected_roots.remove(0);
+        }
+
+        let blocks = store
+            .par_iter_finalized_blocks(start_slot, last_finalized_slot, config)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            blocks.iter().map(|(root, _)| *root).collect::<Vec<_>>(),
+            expected_roots
+        );
+        for (block_root, block) in &blocks {
+            assert_eq!(block.canonical_root(), *block_root);
+            assert!(block.slot() >= start_slot);
+        }
+    }
+
+    // States are identical to those loaded individually.
+    let states = store
+        .par_iter_finalized_states(
+            Slot::new(0),
+            last_finalized_slot,
+            E::slots_per_epoch() / 2,
+            config,
+        )
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        states.len() as u64,
+        last_finalized_slot.as_u64() / (E::slots_per_epoch() / 2) + 1
+    );
+    for (slot, state) in states {
+        assert_eq!(state.slot(), slot);
+        let expected_state = store.load_cold_state_by_slot(slot).unwrap().unwrap();
+        assert_eq!(state.canonical_root(), expected_state.canonical_root());
+    }
+
+    // A byte limit smaller than any chunk loads one chunk at a time, without stalling.
+    let small_config = ParallelIterConfig {
+        max_bytes_in_flight: 1,
+        ..config
+    };
+    let num_finalized_blocks = store
+        .par_iter_finalized_blocks(Slot::new(0), last_finalized_slot, config)
+        .unwrap()
+        .count();
+    let blocks = store
+        .par_iter_finalized_blocks(Slot::new(0), last_finalized_slot, small_config)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(blocks.len(), num_finalized_blocks);
+
+    // Concurrent iterations share the reader threads without blocking one another, even if the
+    // first is not consumed.
+    let iters = (0..2 * store::FREEZER_READER_THREADS)
+        .map(|_| {
+            store
+                .par_iter_finalized_blocks(Slot::new(0), last_finalized_slot, config)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for iter in iters.into_iter().rev() {
+        assert_eq!(iter.count(), num_finalized_blocks);
+    }
+
+    // Dropping the iterator part way through stops the readers.
+    let mut iter = store
+        .par_iter_finalized_blocks(Slot::new(0), last_finalized_slot, config)
+        .unwrap();
+    assert!(iter.next().unwrap().is_ok());
+    drop(iter);
+
+    // Ranges which extend past the split are rejected.
+    assert!(matches!(
+        store.par_iter_finalized_blocks(Slot::new(0), split_slot, config),
+        Err(store::Error::ParallelIterRangeNotFinalized { .. })
+    ));
+    assert!(matches!(
+        store.par_iter_finalized_states(Slot::new(0), split_slot, 1, config),
+        Err(store::Error::ParallelIterRangeNotFinalized { .. })
+    ));
+}
+
+#[tokio::test]
+async fn root_lookups_without_states() {
+    let num_blocks_produced = E::slots_per_epoch() * 20;
+    let db_path = tempdir().unwrap();
+    let store = get_store_generic(
+        &db_path,
+        StoreConfig {
+            slots_per_restore_point: 2 * E::slots_per_epoch(),
+            ..Default::default()
+        },
+        test_spec::<E>(),
+    );
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    let current_state = harness.get_current_state();
+    let state_root = current_state.canonical_root().unwrap();
+    let all_validators = &harness.get_all_validators();
+    harness
+        .add_attested_blocks_at_slots(
+            current_state,
+            state_root,
+            &(1..=num_blocks_produced)
+                .filter(|i| i % 12 != 0)
+                .map(Slot::new)
+                .collect::<Vec<_>>(),
+            all_validators,
+        )
+        .await;
+
+    let split_slot = store.get_split_slot();
+    let head_slot = harness.head_slot();
+    assert!(split_slot > 0, "chain should have finalized");
+
+    let block_roots = harness
+        .chain
+        .forwards_iter_block_roots_until(Slot::new(0), head_slot)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+    let state_roots = harness
+        .chain
+        .forwards_iter_state_roots_until(Slot::new(0), head_slot)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+    let latest_restore_point_slot = store.get_latest_restore_point_slot().unwrap();
+
+    for ((block_root, slot), (state_root, _)) in block_roots.iter().zip(&state_roots) {
+        if *slot < split_slot {
+            assert_eq!(store.get_cold_block_root(*slot).unwrap(), Some(*block_root));
+            let expected_state_root = (*slot < latest_restore_point_slot).then_some(*state_root);
+            assert_eq!(
+                store.get_cold_state_root(*slot).unwrap(),
+                expected_state_root
+            );
+            assert_eq!(store.get_hot_state_root(*slot, *block_root).unwrap(), None);
+        } else {
+            assert_eq!(store.get_cold_block_root(*slot).unwrap(), None);
+            assert_eq!(store.get_cold_state_root(*slot).unwrap(), None);
+            assert_eq!(
+                store.get_hot_state_root(*slot, *block_root).unwrap(),
+                Some(*state_root)
+            );
+        }
+
+        let skipped = *slot % 12 == 0 && *slot != 0;
+        assert_eq!(
+            harness
+                .chain
+                .block_root_at_slot(*slot, WhenSlotSkipped::None)
+                .unwrap(),
+            (!skipped).then_some(*block_root)
+        );
+        assert_eq!(
+            harness
+                .chain
+                .block_root_at_slot(*slot, WhenSlotSkipped::Prev)
+                .unwrap(),
+            Some(*block_root)
+        );
+        assert_eq!(
+            harness.chain.state_root_at_slot(*slot).unwrap(),
+            Some(*state_root)
+        );
+    }
+}
+
+#[tokio::test]
+async fn epochs_per_state_persist() {
+    let epochs_per_state_persist = 4;
+    let num_blocks_produced = E::slots_per_epoch() * 20;
+    let db_path = tempdir().unwrap();
+    let store = get_store_generic(
+        &db_path,
+        StoreConfig {
+            slots_per_restore_point: 2 * E::slots_per_epoch(),
+            epochs_per_state_persist,
+            ..Default::default()
+        },
+        test_spec::<E>(),
+    );
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split = store.get_split_info();
+    let head_slot = harness.head_slot();
+    assert!(split.slot > 0, "chain should have finalized");
+
+    let state_roots = harness
+        .chain
+        .forwards_iter_state_roots_until(Slot::new(0), head_slot)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+
+    for (state_root, slot) in state_roots {
+        if slot < split.slot {
+            // Restore points are copied to the freezer even if they weren't persisted.
+            if slot % store.get_config().slots_per_restore_point == 0 {
+                let mut state = store.load_cold_state_by_slot(slot).unwrap().unwrap();
+                assert_eq!(state.canonical_root().unwrap(), state_root);
+            }
+            continue;
+        }
+
+        let persisted = store
+            .hot_db
+            .key_exists(DBColumn::BeaconState.into(), state_root.as_bytes())
+            .unwrap();
+        let should_persist =
+            slot == split.slot || slot % (epochs_per_state_persist * E::slots_per_epoch()) == 0;
+        assert_eq!(persisted, should_persist, "slot {slot}");
+
+        // Every hot state can be loaded by replaying from a persisted state.
+        let (mut state, _) = store.load_hot_state(&state_root).unwrap().unwrap();
+        assert_eq!(state.slot(), slot);
+        assert_eq!(state.canonical_root().unwrap(), state_root);
+    }
+}
+
+#[tokio::test]
+async fn state_cache_shares_committee_caches() {
+    let num_blocks_produced = E::slots_per_epoch() * 2 + 1;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let head_state_root = harness.chain.head_snapshot().beacon_state_root();
+    let head_state = store.get_hot_state(&head_state_root).unwrap().unwrap();
+
+    // Advance a copy of the head state with freshly built committee caches through a skipped slot
+    // in the same epoch.
+    let mut state = head_state.clone();
+    state.drop_all_caches().unwrap();
+    state.build_caches(&harness.chain.spec).unwrap();
+    complete_state_advance(
+        &mut state,
+        Some(head_state_root),
+        head_state.slot() + 1,
+        &harness.chain.spec,
+    )
+    .unwrap();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    assert_eq!(state.current_epoch(), head_state.current_epoch());
+
+    let relative_epochs = [RelativeEpoch::Previous, RelativeEpoch::Current];
+    for relative_epoch in relative_epochs {
+        assert!(!Arc::ptr_eq(
+            state.committee_cache(relative_epoch).unwrap(),
+            head_state.committee_cache(relative_epoch).unwrap()
+        ));
+    }
+
+    // Once cached, the advanced state shares the committee caches of the head state.
+    store.put_state(&state_root, &state).unwrap();
+    let cached_state = store.get_hot_state(&state_root).unwrap().unwrap();
+    for relative_epoch in relative_epochs {
+        assert!(Arc::ptr_eq(
+            cached_state.committee_cache(relative_epoch).unwrap(),
+            head_state.committee_cache(relative_epoch).unwrap()
+        ));
+    }
+}
+
 #[tokio::test]
 async fn block_replayer_hooks() {
     let db_path = tempdir().unwrap();
@@ -1261,9 +1663,202 @@ fn check_shuffling_compatible(
     }
 }
 
-// Ensure blocks from abandoned forks are pruned from the Hot DB
+// Ensure blocks from abandoned forks are pruned from the Hot DB
+#[tokio::test]
+async fn prunes_abandoned_fork_between_two_finalized_checkpoints() {
+    const HONEST_VALIDATOR_COUNT: usize = 32;
+    const ADVERSARIAL_VALIDATOR_COUNT: usize = 16;
+    const VALIDATOR_COUNT: usize = HONEST_VALIDATOR_COUNT + ADVERSARIAL_VALIDATOR_COUNT;
+    let honest_validators: Vec<usize> = (0..HONEST_VALIDATOR_COUNT).collect();
+    let adversarial_validators: Vec<usize> = (HONEST_VALIDATOR_COUNT..VALIDATOR_COUNT).collect();
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let rig = get_harness(store.clone(), VALIDATOR_COUNT);
+    let slots_per_epoch = rig.slots_per_epoch();
+    let (mut state, state_root) = rig.get_current_state_and_root();
+
+    let canonical_chain_slots: Vec<Slot> = (1..=rig.epoch_start_slot(1)).map(Slot::new).collect();
+    let (canonical_chain_blocks_pre_finalization, _, _, new_state) = rig
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &canonical_chain_slots,
+            &honest_validators,
+        )
+        .await;
+    state = new_state;
+    let canonical_chain_slot: u64 = rig.get_current_slot().into();
+
+    let stray_slots: Vec<Slot> = (canonical_chain_slot + 1..rig.epoch_start_slot(2))
+        .map(Slot::new)
+        .collect();
+    let (current_state, current_state_root) = rig.get_current_state_and_root();
+    let (stray_blocks, stray_states, stray_head, _) = rig
+        .add_attested_blocks_at_slots(
+            current_state,
+            current_state_root,
+            &stray_slots,
+            &adversarial_validators,
+        )
+        .await;
+
+    // Precondition: Ensure all stray_blocks blocks are still known
+    for &block_hash in stray_blocks.values() {
+        assert!(
+            rig.block_exists(block_hash),
+            "stray block {} should be still present",
+            block_hash
+        );
+    }
+
+    for (&slot, &state_hash) in &stray_states {
+        assert!(
+            rig.hot_state_exists(state_hash),
+            "stray state {} at slot {} should be still present",
+            state_hash,
+            slot
+        );
+    }
+
+    assert_eq!(rig.get_finalized_checkpoints(), hashset! {});
+
+    assert!(rig.chain.knows_head(&stray_head));
+
+    // Trigger finalization
+    let finalization_slots: Vec<Slot> = ((canonical_chain_slot + 1)
+        ..=(canonical_chain_slot + slots_per_epoch * 5))
+        .map(Slot::new)
+        .collect();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    let (canonical_chain_blocks_post_finalization, _, _, _) = rig
+        .add_attested_blocks_at_slots(state, state_root, &finalization_slots, &honest_validators)
+        .await;
+
+    // Postcondition: New blocks got finalized
+    assert_eq!(
+        rig.get_finalized_checkpoints(),
+        hashset! {
+            canonical_chain_blocks_pre_finalization[&rig.epoch_start_slot(1).into()],
+            canonical_chain_blocks_post_finalization[&rig.epoch_start_slot(2).into()],
+        },
+    );
+
+    // Postcondition: Ensure all stray_blocks blocks have been pruned
+    for &block_hash in stray_blocks.values() {
+        assert!(
+            !rig.block_exists(block_hash),
+            "abandoned block {block_hash:?} should have been pruned",
+        );
+        assert!(
+            !rig.chain.store.blobs_exist(&block_hash.into()).unwrap(),
+            "blobs for abandoned block {block_hash:?} should have been pruned"
+        );
+    }
+
+    for (&slot, &state_hash) in &stray_states {
+        assert!(
+            !rig.hot_state_exists(state_hash),
+            "stray state {} at slot {} should have been pruned",
+            state_hash,
+            slot
+        );
+        assert!(
+            !rig.cold_state_exists(state_hash),
+            "stray state {} at slot {} should have been pruned",
+            state_hash,
+            slot
+        );
+    }
+
+    assert!(!rig.chain.knows_head(&stray_head));
+}
+
+// Ensure only the payloads, blobs and states of abandoned forks are pruned when blinded blocks
+// are retained.
+#[tokio::test]
+async fn retains_blinded_blocks_of_abandoned_fork() {
+    const HONEST_VALIDATOR_COUNT: usize = 32;
+    const ADVERSARIAL_VALIDATOR_COUNT: usize = 16;
+    const VALIDATOR_COUNT: usize = HONEST_VALIDATOR_COUNT + ADVERSARIAL_VALIDATOR_COUNT;
+    let honest_validators: Vec<usize> = (0..HONEST_VALIDATOR_COUNT).collect();
+    let adversarial_validators: Vec<usize> = (HONEST_VALIDATOR_COUNT..VALIDATOR_COUNT).collect();
+    let db_path = tempdir().unwrap();
+    let store = get_store_generic(
+        &db_path,
+        StoreConfig {
+            retain_abandoned_blocks: true,
+            ..Default::default()
+        },
+        test_spec::<E>(),
+    );
+    let rig = get_harness(store.clone(), VALIDATOR_COUNT);
+    let slots_per_epoch = rig.slots_per_epoch();
+    let (state, state_root) = rig.get_current_state_and_root();
+
+    let canonical_chain_slots: Vec<Slot> = (1..=rig.epoch_start_slot(1)).map(Slot::new).collect();
+    let (_, _, _, mut state) = rig
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &canonical_chain_slots,
+            &honest_validators,
+        )
+        .await;
+    let canonical_chain_slot: u64 = rig.get_current_slot().into();
+
+    let stray_slots: Vec<Slot> = (canonical_chain_slot + 1..rig.epoch_start_slot(2))
+        .map(Slot::new)
+        .collect();
+    let (current_state, current_state_root) = rig.get_current_state_and_root();
+    let (stray_blocks, stray_states, stray_head, _) = rig
+        .add_attested_blocks_at_slots(
+            current_state,
+            current_state_root,
+            &stray_slots,
+            &adversarial_validators,
+        )
+        .await;
+
+    // Trigger finalization
+    let finalization_slots: Vec<Slot> = ((canonical_chain_slot + 1)
+        ..=(canonical_chain_slot + slots_per_epoch * 5))
+        .map(Slot::new)
+        .collect();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    rig.add_attested_blocks_at_slots(state, state_root, &finalization_slots, &honest_validators)
+        .await;
+    assert!(!rig.chain.knows_head(&stray_head));
+
+    // Postcondition: stray blocks are retained without their payloads and blobs
+    for &block_hash in stray_blocks.values() {
+        let block_root = block_hash.into();
+        assert!(
+            rig.block_exists(block_hash),
+            "abandoned block {block_hash:?} should have been retained",
+        );
+        assert!(
+            !store.execution_payload_exists(&block_root).unwrap(),
+            "payload for abandoned block {block_hash:?} should have been pruned"
+        );
+        assert!(
+            !store.blobs_exist(&block_root).unwrap(),
+            "blobs for abandoned block {block_hash:?} should have been pruned"
+        );
+    }
+
+    for (&slot, &state_hash) in &stray_states {
+        assert!(
+            !rig.hot_state_exists(state_hash),
+            "stray state {} at slot {} should have been pruned",
+            state_hash,
+            slot
+        );
+    }
+}
+
+// Ensure emergency pruning deletes the full states of non-viable forks, but not their summaries.
 #[tokio::test]
-async fn prunes_abandoned_fork_between_two_finalized_checkpoints() {
+async fn emergency_pruning_of_non_viable_fork_states() {
     const HONEST_VALIDATOR_COUNT: usize = 32;
     const ADVERSARIAL_VALIDATOR_COUNT: usize = 16;
     const VALIDATOR_COUNT: usize = HONEST_VALIDATOR_COUNT + ADVERSARIAL_VALIDATOR_COUNT;
@@ -1271,12 +1866,18 @@ async fn prunes_abandoned_fork_between_two_finalized_checkpoints() {
     let adversarial_validators: Vec<usize> = (HONEST_VALIDATOR_COUNT..VALIDATOR_COUNT).collect();
     let db_path = tempdir().unwrap();
     let store = get_store(&db_path);
-    let rig = get_harness(store.clone(), VALIDATOR_COUNT);
+    // Defer migration, so that the stray fork isn't pruned upon finalization.
+    let chain_config = ChainConfig {
+        reconstruct_historic_states: true,
+        epochs_per_migration: 1024,
+        ..ChainConfig::default()
+    };
+    let rig = get_harness_generic(store.clone(), VALIDATOR_COUNT, chain_config);
     let slots_per_epoch = rig.slots_per_epoch();
-    let (mut state, state_root) = rig.get_current_state_and_root();
+    let (state, state_root) = rig.get_current_state_and_root();
 
     let canonical_chain_slots: Vec<Slot> = (1..=rig.epoch_start_slot(1)).map(Slot::new).collect();
-    let (canonical_chain_blocks_pre_finalization, _, _, new_state) = rig
+    let (_, _, _, mut state) = rig
         .add_attested_blocks_at_slots(
             state,
             state_root,
@@ -1284,14 +1885,14 @@ async fn prunes_abandoned_fork_between_two_finalized_checkpoints() {
             &honest_validators,
         )
         .await;
-    state = new_state;
     let canonical_chain_slot: u64 = rig.get_current_slot().into();
 
-    let stray_slots: Vec<Slot> = (canonical_chain_slot + 1..rig.epoch_start_slot(2))
+    // The stray fork crosses an epoch boundary, so it has a full state in the hot database.
+    let stray_slots: Vec<Slot> = (canonical_chain_slot + 1..=rig.epoch_start_slot(2) + 1)
         .map(Slot::new)
         .collect();
     let (current_state, current_state_root) = rig.get_current_state_and_root();
-    let (stray_blocks, stray_states, stray_head, _) = rig
+    let (_, stray_states, stray_head, _) = rig
         .add_attested_blocks_at_slots(
             current_state,
             current_state_root,
@@ -1299,76 +1900,43 @@ async fn prunes_abandoned_fork_between_two_finalized_checkpoints() {
             &adversarial_validators,
         )
         .await;
+    let stray_boundary_state_root: Hash256 =
+        stray_states[&Slot::new(rig.epoch_start_slot(2))].into();
 
-    // Precondition: Ensure all stray_blocks blocks are still known
-    for &block_hash in stray_blocks.values() {
-        assert!(
-            rig.block_exists(block_hash),
-            "stray block {} should be still present",
-            block_hash
-        );
-    }
-
-    for (&slot, &state_hash) in &stray_states {
-        assert!(
-            rig.hot_state_exists(state_hash),
-            "stray state {} at slot {} should be still present",
-            state_hash,
-            slot
-        );
-    }
-
-    assert_eq!(rig.get_finalized_checkpoints(), hashset! {});
-
-    assert!(rig.chain.knows_head(&stray_head));
-
-    // Trigger finalization
     let finalization_slots: Vec<Slot> = ((canonical_chain_slot + 1)
         ..=(canonical_chain_slot + slots_per_epoch * 5))
         .map(Slot::new)
         .collect();
     let state_root = state.update_tree_hash_cache().unwrap();
-    let (canonical_chain_blocks_post_finalization, _, _, _) = rig
+    let (_, canonical_states, _, _) = rig
         .add_attested_blocks_at_slots(state, state_root, &finalization_slots, &honest_validators)
         .await;
+    let canonical_boundary_state_root: Hash256 =
+        canonical_states[&Slot::new(rig.epoch_start_slot(2))].into();
 
-    // Postcondition: New blocks got finalized
-    assert_eq!(
-        rig.get_finalized_checkpoints(),
-        hashset! {
-            canonical_chain_blocks_pre_finalization[&rig.epoch_start_slot(1).into()],
-            canonical_chain_blocks_post_finalization[&rig.epoch_start_slot(2).into()],
-        },
-    );
-
-    // Postcondition: Ensure all stray_blocks blocks have been pruned
-    for &block_hash in stray_blocks.values() {
-        assert!(
-            !rig.block_exists(block_hash),
-            "abandoned block {block_hash:?} should have been pruned",
-        );
-        assert!(
-            !rig.chain.store.blobs_exist(&block_hash.into()).unwrap(),
-            "blobs for abandoned block {block_hash:?} should have been pruned"
-        );
-    }
+    assert!(!rig.get_finalized_checkpoints().is_empty());
+    assert!(rig.chain.knows_head(&stray_head));
+    let full_state_exists = |state_root: Hash256| {
+        store
+            .hot_db
+            .key_exists(DBColumn::BeaconState.into(), state_root.as_bytes())
+            .unwrap()
+    };
+    assert!(full_state_exists(stray_boundary_state_root));
 
-    for (&slot, &state_hash) in &stray_states {
-        assert!(
-            !rig.hot_state_exists(state_hash),
-            "stray state {} at slot {} should have been pruned",
-            state_hash,
-            slot
-        );
-        assert!(
-            !rig.cold_state_exists(state_hash),
-            "stray state {} at slot {} should have been pruned",
-            state_hash,
-            slot
-        );
-    }
+    let num_deleted_states = rig.chain.prune_non_viable_fork_states().unwrap();
+    assert_eq!(num_deleted_states, 1);
 
+    assert!(!full_state_exists(stray_boundary_state_root));
+    assert!(store
+        .load_hot_state_summary(&stray_boundary_state_root)
+        .unwrap()
+        .is_some());
     assert!(!rig.chain.knows_head(&stray_head));
+    assert!(full_state_exists(canonical_boundary_state_root));
+
+    // Pruning again is a no-op, since the stray head is no longer tracked.
+    assert_eq!(rig.chain.prune_non_viable_fork_states().unwrap(), 0);
 }
 
 #[tokio::test]
@@ -2335,6 +2903,108 @@ async fn weak_subjectivity_sync_skips_at_genesis() {
     weak_subjectivity_sync_test(slots, checkpoint_slot).await
 }
 
+#[tokio::test]
+async fn weak_subjectivity_sync_rejects_invalid_blobs() {
+    let spec = test_spec::<E>();
+    if spec.deneb_fork_epoch.is_none() {
+        return;
+    }
+
+    let temp1 = tempdir().unwrap();
+    let full_store = get_store(&temp1);
+    let harness = get_harness(full_store.clone(), LOW_VALIDATOR_COUNT);
+    let genesis_state = harness.get_current_state();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize * 2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Find a block with blobs to use as the checkpoint.
+    let Some((wss_block, wss_blobs)) = harness
+        .chain
+        .chain_dump()
+        .unwrap()
+        .into_iter()
+        .rev()
+        .find_map(|snapshot| {
+            let block_root = snapshot.beacon_block_root;
+            let blobs = full_store.get_blobs(&block_root).unwrap()?;
+            let block = full_store.get_full_block(&block_root).unwrap()?;
+            (!blobs.is_empty()).then_some((block, blobs))
+        })
+    else {
+        return;
+    };
+    let wss_state = full_store
+        .get_state(&wss_block.state_root(), Some(wss_block.slot()))
+        .unwrap()
+        .unwrap();
+
+    let tamper = |f: fn(&mut BlobSidecar<E>)| {
+        let mut blobs = wss_blobs.to_vec();
+        let mut blob = (*blobs[0]).clone();
+        f(&mut blob);
+        blobs[0] = Arc::new(blob);
+        BlobSidecarList::<E>::new(blobs).unwrap()
+    };
+    let mut missing_blob = wss_blobs.to_vec();
+    missing_blob.pop();
+    let invalid_blobs = [
+        (
+            BlobSidecarList::<E>::new(missing_blob).unwrap(),
+            "Wrong number of blobs",
+        ),
+        (
+            tamper(|blob| blob.index += 1),
+            "Checkpoint blob has wrong index",
+        ),
+        (
+            tamper(|blob| blob.blob[0] ^= 1),
+            "Checkpoint blobs failed KZG verification",
+        ),
+        (
+            tamper(|blob| blob.kzg_commitment_inclusion_proof[0] = Hash256::repeat_byte(1)),
+            "has an invalid inclusion proof",
+        ),
+        (
+            tamper(|blob| blob.signed_block_header.message.slot += 1),
+            "is for a different block",
+        ),
+    ];
+
+    let valid_blobs = [(wss_blobs.clone(), None)];
+    let invalid_blobs = invalid_blobs.map(|(blobs, error)| (blobs, Some(error)));
+    for (blobs, expected_error) in valid_blobs.into_iter().chain(invalid_blobs) {
+        let temp2 = tempdir().unwrap();
+        let store = get_store(&temp2);
+        let result = BeaconChainBuilder::<DiskHarnessType<E>>::new(MinimalEthSpec)
+            .store(store)
+            .custom_spec(test_spec::<E>())
+            .task_executor(harness.chain.task_executor.clone())
+            .logger(test_logger())
+            .kzg(Some(KZG.clone()))
+            .weak_subjectivity_state(
+                wss_state.clone(),
+                wss_block.clone(),
+                Some(blobs),
+                genesis_state.clone(),
+            );
+        match (result.err(), expected_error) {
+            (None, None) => (),
+            (Some(error), Some(expected_error)) => assert!(
+                error.contains(expected_error),
+                "expected {expected_error:?}, got {error:?}"
+            ),
+            (error, expected_error) => {
+                panic!("expected error {expected_error:?}, got {error:?}")
+            }
+        }
+    }
+}
+
 async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
     // Build an initial chain on one harness, representing a synced node with full history.
     let num_final_blocks = E::slots_per_epoch() * 2;
@@ -2413,6 +3083,7 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
         .custom_spec(test_spec::<E>())
         .task_executor(harness.chain.task_executor.clone())
         .logger(log.clone())
+        .kzg(kzg)
         .weak_subjectivity_state(
             wss_state,
             wss_block.clone(),
@@ -2431,7 +3102,6 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
             1,
         )))
         .execution_layer(Some(mock.el))
-        .kzg(kzg)
         .build()
         .expect("should build");
 
@@ -2793,11 +3463,26 @@ async fn finalizes_after_resuming_from_db() {
         .chain
         .persist_op_pool()
         .expect("should persist the op pool");
+    harness
+        .chain
+        .persist_observed_caches()
+        .expect("should persist the observed caches");
     harness
         .chain
         .persist_eth1_cache()
         .expect("should persist the eth1 cache");
 
+    let current_epoch = latest_slot.epoch(MinimalEthSpec::slots_per_epoch());
+    assert!(
+        harness
+            .chain
+            .observed_gossip_attesters
+            .read()
+            .observed_validator_count(current_epoch)
+            .is_some(),
+        "gossip attesters should have been observed"
+    );
+
     let original_chain = harness.chain;
 
     let resumed_harness = BeaconChainHarness::<DiskHarnessType<E>>::builder(MinimalEthSpec)
@@ -2810,6 +3495,34 @@ async fn finalizes_after_resuming_from_db() {
 
     assert_chains_pretty_much_the_same(&original_chain, &resumed_harness.chain);
 
+    // The pubkey cache is reloaded from disk in decompressed form, rather than rebuilt from the
+    // head state.
+    for (validator_index, keypair) in KEYPAIRS[0..validator_count].iter().enumerate() {
+        assert_eq!(
+            resumed_harness
+                .chain
+                .validator_pubkey(validator_index)
+                .unwrap(),
+            Some(keypair.pk.clone()),
+            "pubkey {validator_index} should be reloaded"
+        );
+        assert_eq!(
+            resumed_harness
+                .chain
+                .validator_index(&keypair.pk.compress())
+                .unwrap(),
+            Some(validator_index),
+            "index of pubkey {validator_index} should be reloaded"
+        );
+    }
+    assert_eq!(
+        resumed_harness
+            .chain
+            .validator_pubkey(validator_count)
+            .unwrap(),
+        None
+    );
+
     // Set the slot clock of the resumed harness to be in the slot following the previous harness.
     //
     // This allows us to produce the block at the next slot.
@@ -3113,6 +3826,162 @@ async fn schema_downgrade_to_min_version() {
     .expect_err("should not downgrade below minimum version");
 }
 
+/// Check that the block slot index is maintained on import and rebuilt by the v23 migration.
+#[tokio::test]
+async fn block_slot_index() {
+    let num_blocks_produced = E::slots_per_epoch() * 2;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let spec = &harness.chain.spec.clone();
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let check_index = |indexed_blocks: usize| {
+        assert_eq!(
+            store
+                .hot_db
+                .iter_column_keys::<Hash256>(DBColumn::BeaconBlockSlot)
+                .count(),
+            indexed_blocks
+        );
+        for snapshot in harness.chain.chain_dump().unwrap() {
+            assert_eq!(
+                store.get_block_slot(&snapshot.beacon_block_root).unwrap(),
+                Some(snapshot.beacon_block.slot())
+            );
+        }
+        assert_eq!(
+            store.get_block_slot(&Hash256::repeat_byte(0xff)).unwrap(),
+            None
+        );
+    };
+
+    // The genesis block is stored under both its root and the zero hash alias.
+    let num_blocks = num_blocks_produced as usize + 2;
+    check_index(num_blocks);
+
+    // Downgrading removes the index, but slots can still be read from the blocks themselves.
+    let deposit_contract_deploy_block = 0;
+    migrate_schema::<DiskHarnessType<E>>(
+        store.clone(),
+        deposit_contract_deploy_block,
+        CURRENT_SCHEMA_VERSION,
+        SchemaVersion(22),
+        store.logger().clone(),
+        spec,
+    )
+    .expect("schema downgrade to v22 should work");
+    check_index(0);
+
+    // Upgrading rebuilds the index.
+    migrate_schema::<DiskHarnessType<E>>(
+        store.clone(),
+        deposit_contract_deploy_block,
+        SchemaVersion(22),
+        CURRENT_SCHEMA_VERSION,
+        store.logger().clone(),
+        spec,
+    )
+    .expect("schema upgrade to current version should work");
+    check_index(num_blocks);
+}
+
+/// Check that the v24 migration compresses existing hot blocks and states, and that the downgrade
+/// decompresses them again.
+#[tokio::test]
+async fn hot_db_compression_migration() {
+    let num_blocks_produced = E::slots_per_epoch() * 2;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let spec = &harness.chain.spec.clone();
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Save the slot clock so that the new harness doesn't revert in time.
+    let slot_clock = harness.chain.slot_clock.clone();
+
+    // Close the database and re-open it with compression enabled.
+    drop(store);
+    drop(harness);
+    let config = StoreConfig {
+        hot_db_compression: CompressionAlgorithm::Zstd,
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+
+    let count_values = |compressed: bool| {
+        [DBColumn::BeaconBlock, DBColumn::BeaconState]
+            .into_iter()
+            .flat_map(|column| store.hot_db.iter_column::<Hash256>(column))
+            .filter(|res| is_compressed(&res.as_ref().unwrap().1) == compressed)
+            .count()
+    };
+    let num_values = count_values(false);
+    assert!(num_values > 0);
+    assert_eq!(count_values(true), 0);
+
+    // Downgrading and upgrading again compresses every existing value.
+    let deposit_contract_deploy_block = 0;
+    migrate_schema::<DiskHarnessType<E>>(
+        store.clone(),
+        deposit_contract_deploy_block,
+        CURRENT_SCHEMA_VERSION,
+        SchemaVersion(23),
+        store.logger().clone(),
+        spec,
+    )
+    .expect("schema downgrade to v23 should work");
+    migrate_schema::<DiskHarnessType<E>>(
+        store.clone(),
+        deposit_contract_deploy_block,
+        SchemaVersion(23),
+        CURRENT_SCHEMA_VERSION,
+        store.logger().clone(),
+        spec,
+    )
+    .expect("schema upgrade to current version should work");
+    assert_eq!(count_values(true), num_values);
+    assert_eq!(count_values(false), 0);
+
+    // The compressed database is readable.
+    let harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[0..LOW_VALIDATOR_COUNT].to_vec())
+        .logger(store.logger().clone())
+        .testing_slot_clock(slot_clock)
+        .resumed_disk_store(store.clone())
+        .mock_execution_layer()
+        .build();
+    check_chain_dump(&harness, num_blocks_produced + 1);
+
+    // Downgrading decompresses every value so that older versions can read them.
+    migrate_schema::<DiskHarnessType<E>>(
+        store.clone(),
+        deposit_contract_deploy_block,
+        CURRENT_SCHEMA_VERSION,
+        SchemaVersion(23),
+        store.logger().clone(),
+        spec,
+    )
+    .expect("schema downgrade to v23 should work");
+    assert_eq!(count_values(false), num_values);
+    assert_eq!(count_values(true), 0);
+}
+
 /// Check that blob pruning prunes blobs older than the data availability boundary.
 #[tokio::test]
 async fn deneb_prune_blobs_happy_case() {
@@ -3517,6 +4386,26 @@ fn assert_chains_pretty_much_the_same<T: BeaconChainTypes>(a: &BeaconChain<T>, b
         a.genesis_block_root, b.genesis_block_root,
         "genesis_block_root should be equal"
     );
+    assert_eq!(
+        a.observed_block_producers.read().as_ssz_container(),
+        b.observed_block_producers.read().as_ssz_container(),
+        "observed_block_producers should be equal"
+    );
+    assert_eq!(
+        a.observed_gossip_attesters.read().as_ssz_container(),
+        b.observed_gossip_attesters.read().as_ssz_container(),
+        "observed_gossip_attesters should be equal"
+    );
+    assert_eq!(
+        a.observed_block_attesters.read().as_ssz_container(),
+        b.observed_block_attesters.read().as_ssz_container(),
+        "observed_block_attesters should be equal"
+    );
+    assert_eq!(
+        a.observed_aggregators.read().as_ssz_container(),
+        b.observed_aggregators.read().as_ssz_container(),
+        "observed_aggregators should be equal"
+    );
 
     let slot = a.slot().unwrap();
     let spec = T::EthSpec::default_spec();
@@ -9,6 +9,7 @@ use state_processing::{
     per_block_processing::{altair::sync_committee::process_sync_aggregate, VerifySignatures},
     state_advance::complete_state_advance,
 };
+use std::collections::HashSet;
 use store::{SignedContributionAndProof, SyncCommitteeMessage};
 use tree_hash::TreeHash;
 use types::consts::altair::SYNC_COMMITTEE_SUBNET_COUNT;
@@ -812,3 +813,78 @@ async fn unaggregated_gossip_verification() {
         if received == subnet_id && !expected.contains(&subnet_id)
     );
 }
+
+/// Tests that batch verification of sync committee messages returns the same results as
+/// individual verification when one of the signatures in the batch is invalid.
+#[tokio::test]
+async fn batch_unaggregated_gossip_verification() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let state = harness.get_current_state();
+
+    harness
+        .add_attested_blocks_at_slots(
+            state,
+            Hash256::zero(),
+            &[Slot::new(1), Slot::new(2)],
+            (0..VALIDATOR_COUNT).collect::<Vec<_>>().as_slice(),
+        )
+        .await;
+
+    let current_slot = harness.chain.slot().expect("should get slot");
+    let head_state = harness.chain.head_beacon_state_cloned();
+    let head_block_root = harness.chain.head_snapshot().beacon_block_root;
+
+    // Take at most one message per validator, since a validator may only send one message per
+    // slot and subnet.
+    let mut seen_validators = HashSet::new();
+    let mut messages = harness
+        .make_sync_committee_messages(
+            &head_state,
+            head_block_root,
+            current_slot,
+            RelativeSyncCommittee::Current,
+        )
+        .into_iter()
+        .enumerate()
+        .flat_map(|(subcommittee_index, messages)| {
+            messages
+                .into_iter()
+                .map(move |(message, _)| (message, SyncSubnetId::new(subcommittee_index as u64)))
+        })
+        .filter(|(message, _)| seen_validators.insert(message.validator_index))
+        .collect::<Vec<_>>();
+
+    let invalid_index = 3;
+    assert_eq!(
+        messages[invalid_index].1,
+        messages[invalid_index + 1].1,
+        "test pre-condition: same subnet"
+    );
+
+    // Swap in the signature of another validator so that the batch for this subnet fails and must
+    // be bisected.
+    messages[invalid_index].0.signature = messages[invalid_index + 1].0.signature.clone();
+
+    let results = harness
+        .chain
+        .batch_verify_sync_committee_messages_for_gossip(messages.clone().into_iter())
+        .expect("should verify batch");
+
+    assert_eq!(results.len(), messages.len());
+    for (i, result) in results.iter().enumerate() {
+        if i == invalid_index {
+            assert!(
+                matches!(result, Err(SyncCommitteeError::InvalidSignature)),
+                "message {} should have an invalid signature",
+                i
+            );
+        } else {
+            assert!(
+                result.is_ok(),
+                "message {} should be valid: {:?}",
+                i,
+                result.as_ref().err()
+            );
+        }
+    }
+}
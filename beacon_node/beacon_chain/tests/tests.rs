@@ -2,18 +2,20 @@
 
 use beacon_chain::{
     attestation_verification::Error as AttnError,
+    gossip_recorder::{read_gossip_records, ReplayTiming},
     test_utils::{
         AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
         OP_POOL_DB_KEY,
     },
     BeaconChain, ChainConfig, NotifyExecutionLayer, StateSkipConfig, WhenSlotSkipped,
 };
+use eth2::lighthouse::{ChainHealthVerdict, DegradedReason};
 use lazy_static::lazy_static;
 use operation_pool::PersistedOperationPool;
 use state_processing::{per_slot_processing, per_slot_processing::Error as SlotProcessingError};
 use types::{
-    BeaconState, BeaconStateError, BlockImportSource, EthSpec, Hash256, Keypair, MinimalEthSpec,
-    RelativeEpoch, Slot,
+    AttestationShufflingId, BeaconState, BeaconStateError, BlockImportSource, EthSpec, Hash256,
+    Keypair, MinimalEthSpec, RelativeEpoch, Slot,
 };
 
 // Should ideally be divisible by 3.
@@ -871,3 +873,230 @@ async fn block_roots_skip_slot_behaviour() {
         "WhenSlotSkipped::Prev should return None on a future slot"
     );
 }
+
+#[tokio::test]
+async fn new_head_primes_duties_caches() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .extend_chain(
+            MinimalEthSpec::slots_per_epoch() as usize * 2 + 1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // A lookahead computed from the current head.
+    let old_head_root = harness.chain.head_beacon_block_root();
+    let lookahead_epoch = harness.chain.epoch().unwrap() + 1;
+    harness.chain.beacon_proposer_cache.lock().insert_lookahead(
+        lookahead_epoch,
+        old_head_root,
+        vec![0; MinimalEthSpec::slots_per_epoch() as usize],
+        harness.chain.head_snapshot().beacon_state.fork(),
+    );
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let head = harness.chain.head_snapshot();
+    let state = &head.beacon_state;
+    assert_ne!(head.beacon_block_root, old_head_root);
+
+    // The lookahead was computed from the old head, so it is dropped.
+    let mut proposer_cache = harness.chain.beacon_proposer_cache.lock();
+    assert!(proposer_cache
+        .get_lookahead(old_head_root, lookahead_epoch)
+        .is_none());
+
+    // The duties for the new head are cached.
+    let proposer_decision_root = state
+        .proposer_shuffling_decision_root(head.beacon_block_root)
+        .unwrap();
+    assert_eq!(
+        proposer_cache
+            .get_epoch::<MinimalEthSpec>(proposer_decision_root, state.current_epoch())
+            .map(|proposers| proposers.to_vec()),
+        Some(state.get_beacon_proposer_indices(&harness.spec).unwrap())
+    );
+    drop(proposer_cache);
+
+    let shuffling_cache = harness.chain.shuffling_cache.read();
+    for relative_epoch in [RelativeEpoch::Current, RelativeEpoch::Next] {
+        let shuffling_id =
+            AttestationShufflingId::new(head.beacon_block_root, state, relative_epoch).unwrap();
+        assert!(shuffling_cache.contains(&shuffling_id));
+    }
+}
+
+#[tokio::test]
+async fn replay_recorded_gossip() {
+    let num_blocks = MinimalEthSpec::slots_per_epoch() * 2;
+    let record_dir = tempfile::tempdir().unwrap();
+    let record_path = record_dir.path().join("gossip.ssz");
+
+    let recording = BeaconChainHarness::builder(MinimalEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            gossip_record_path: Some(record_path.clone()),
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    // Import blocks and attestations via the gossip verification functions so they're recorded.
+    for _ in 0..num_blocks {
+        recording.advance_slot();
+        let slot = recording.get_current_slot();
+        let (state, _) = recording.get_current_state_and_root();
+        let ((block, _), _) = recording.make_block(state, slot).await;
+
+        let verified = recording
+            .chain
+            .verify_block_for_gossip(block)
+            .await
+            .unwrap();
+        recording
+            .chain
+            .process_block(
+                verified.block_root,
+                verified,
+                NotifyExecutionLayer::Yes,
+                BlockImportSource::Gossip,
+                || Ok(()),
+            )
+            .await
+            .unwrap();
+        recording.chain.recompute_head_at_current_slot().await;
+
+        let (state, state_root) = recording.get_current_state_and_root();
+        let attestations = recording.make_unaggregated_attestations(
+            &recording.get_all_validators(),
+            &state,
+            state_root,
+            recording.head_block_root().into(),
+            slot,
+        );
+        for (attestation, subnet_id) in attestations.iter().flatten() {
+            let verified = recording
+                .chain
+                .verify_unaggregated_attestation_for_gossip(attestation, Some(*subnet_id))
+                .unwrap();
+            recording
+                .chain
+                .apply_attestation_to_fork_choice(&verified)
+                .unwrap();
+        }
+    }
+    recording.chain.recompute_head_at_current_slot().await;
+    recording
+        .chain
+        .gossip_recorder
+        .as_ref()
+        .unwrap()
+        .flush()
+        .await
+        .unwrap();
+
+    let records = read_gossip_records(&record_path).unwrap();
+    let num_attestations = records.len() - num_blocks as usize;
+
+    // The replay advances the slot clock itself.
+    let replaying = get_harness(VALIDATOR_COUNT);
+    assert_eq!(replaying.get_current_slot(), Slot::new(0));
+
+    let summary = replaying
+        .chain
+        .replay_gossip(records, ReplayTiming::Immediate)
+        .await
+        .unwrap();
+    replaying.chain.recompute_head_at_current_slot().await;
+
+    assert_eq!(replaying.get_current_slot(), recording.get_current_slot());
+    assert_eq!(summary.rejected, 0);
+    assert_eq!(summary.blocks_imported, num_blocks as usize);
+    assert_eq!(summary.attestations_imported, num_attestations);
+    assert_eq!(replaying.head_block_root(), recording.head_block_root());
+    assert_eq!(
+        replaying.justified_checkpoint(),
+        recording.justified_checkpoint()
+    );
+}
+
+fn get_chain_health_harness(
+    enable_degraded_mode: bool,
+) -> BeaconChainHarness<EphemeralHarnessType<MinimalEthSpec>> {
+    let harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            enable_degraded_mode,
+            degraded_missed_slots_per_epoch: 4,
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    harness.advance_slot();
+
+    harness
+}
+
+#[tokio::test]
+async fn chain_health_degraded_by_missed_slots() {
+    let slots_per_epoch = MinimalEthSpec::slots_per_epoch() as usize;
+
+    for enable_degraded_mode in [false, true] {
+        let harness = get_chain_health_harness(enable_degraded_mode);
+        harness
+            .extend_chain(
+                slots_per_epoch,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        harness.chain.update_chain_health(50, false).unwrap();
+        let report = harness.chain.chain_health_report().unwrap();
+        assert_eq!(report.verdict, ChainHealthVerdict::Healthy);
+        assert!(report.reasons.is_empty());
+        assert!(!harness.chain.chain_health.is_degraded());
+
+        // Miss more than `degraded_missed_slots_per_epoch` slots.
+        for _ in 0..7 {
+            harness.advance_slot();
+        }
+
+        harness.chain.update_chain_health(50, false).unwrap();
+        let report = harness.chain.chain_health_report().unwrap();
+        assert_eq!(harness.chain.latest_chain_health_report().unwrap(), report);
+        assert_eq!(report.verdict, ChainHealthVerdict::Degraded);
+        assert_eq!(report.reasons, vec![DegradedReason::MissedSlots]);
+        assert!(report.missed_slots_in_last_epoch > 4);
+        assert_eq!(
+            harness.chain.chain_health.is_degraded(),
+            enable_degraded_mode
+        );
+        assert_eq!(report.degraded_mode_active, enable_degraded_mode);
+
+        // Degraded mode is never engaged whilst syncing.
+        harness.chain.update_chain_health(50, true).unwrap();
+        assert!(!harness.chain.chain_health.is_degraded());
+
+        // Too few peers is another reason for the chain to be degraded.
+        harness.chain.update_chain_health(1, false).unwrap();
+        let report = harness.chain.chain_health_report().unwrap();
+        assert_eq!(
+            report.reasons,
+            vec![DegradedReason::MissedSlots, DegradedReason::LowPeerCount]
+        );
+    }
+}
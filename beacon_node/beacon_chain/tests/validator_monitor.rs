@@ -1,7 +1,10 @@
 use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
 };
-use beacon_chain::validator_monitor::{ValidatorMonitorConfig, MISSED_BLOCK_LAG_SLOTS};
+use beacon_chain::validator_monitor::{
+    ValidatorMonitorAlert, ValidatorMonitorConfig, MAX_API_REGISTERED_VALIDATORS,
+    MISSED_BLOCK_LAG_SLOTS,
+};
 use lazy_static::lazy_static;
 use logging::test_logger;
 use types::{Epoch, EthSpec, Keypair, MainnetEthSpec, PublicKeyBytes, Slot};
@@ -40,6 +43,143 @@ fn get_harness(
     harness
 }
 
+#[tokio::test]
+async fn alerts_for_missed_attestations() {
+    let validator_count = 16;
+    let absent_validator = 3;
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[0..validator_count].to_vec())
+        .logger(test_logger())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .validator_monitor_config(ValidatorMonitorConfig {
+            validators: (0..validator_count)
+                .map(|i| PublicKeyBytes::from(KEYPAIRS[i].pk.clone()))
+                .collect(),
+            alert_webhook: Some("http://localhost:9000".parse().unwrap()),
+            alert_effectiveness_threshold: Some(0.5),
+            ..<_>::default()
+        })
+        .build();
+    harness.advance_slot();
+
+    let attesters = (0..validator_count)
+        .filter(|i| *i != absent_validator)
+        .collect::<Vec<_>>();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize * 4,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::SomeValidators(attesters),
+        )
+        .await;
+
+    let alerts = harness.chain.validator_monitor.read().drain_alerts();
+    assert!(alerts.iter().any(|alert| matches!(
+        alert,
+        ValidatorMonitorAlert::MissedAttestation { validator_index, .. }
+            if *validator_index == absent_validator as u64
+    )));
+    assert!(alerts.iter().any(|alert| matches!(
+        alert,
+        ValidatorMonitorAlert::LowEffectiveness { validator_index, .. }
+            if *validator_index == absent_validator as u64
+    )));
+
+    // Draining the alerts empties the queue.
+    assert!(harness
+        .chain
+        .validator_monitor
+        .read()
+        .drain_alerts()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn records_attestation_hit_history() {
+    let validator_count = 16;
+    let absent_validator = 3;
+    let present_validator = 4;
+    let harness = get_harness(validator_count, vec![absent_validator, present_validator]);
+
+    let attesters = (0..validator_count)
+        .filter(|i| *i != absent_validator)
+        .collect::<Vec<_>>();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize * 4,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::SomeValidators(attesters),
+        )
+        .await;
+
+    let epoch = Epoch::new(1);
+    let validator_monitor = harness.chain.validator_monitor.read();
+    let hit = |index: usize| {
+        validator_monitor
+            .get_monitored_validator(index as u64)
+            .unwrap()
+            .get_attestation_hit(epoch)
+    };
+    assert_eq!(hit(absent_validator), Some(false));
+    assert_eq!(hit(present_validator), Some(true));
+}
+
+#[tokio::test]
+async fn records_attestation_inclusion_positions() {
+    let validator_count = 16;
+    let monitored_validator = 4;
+    let harness = get_harness(validator_count, vec![monitored_validator]);
+
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize * 2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let epoch = Epoch::new(1);
+    let inclusions = harness
+        .chain
+        .validator_monitor
+        .read()
+        .get_monitored_validator(monitored_validator as u64)
+        .unwrap()
+        .get_attestation_inclusions(epoch);
+    assert!(!inclusions.is_empty());
+
+    for inclusion in inclusions {
+        assert_eq!(
+            inclusion.attestation_slot.epoch(E::slots_per_epoch()),
+            epoch
+        );
+
+        let block = harness
+            .chain
+            .get_blinded_block(&inclusion.block_root)
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.slot(), inclusion.block_slot);
+
+        let attestation = block
+            .message()
+            .body()
+            .attestations()
+            .nth(inclusion.attestation_index)
+            .unwrap();
+        assert_eq!(attestation.data().slot, inclusion.attestation_slot);
+        assert_eq!(
+            attestation.committee_index(),
+            Some(inclusion.committee_index)
+        );
+        assert!(attestation
+            .set_aggregation_bits()
+            .contains(&inclusion.aggregation_bit));
+    }
+}
+
 // Regression test for off-by-one caching issue in missed block detection.
 #[tokio::test]
 async fn missed_blocks_across_epochs() {
@@ -387,3 +527,171 @@ async fn produces_missed_blocks() {
         );
     }
 }
+
+fn get_auto_register_harness() -> BeaconChainHarness<EphemeralHarnessType<E>> {
+    BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .logger(test_logger())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .validator_monitor_config(ValidatorMonitorConfig {
+            auto_register: true,
+            ..<_>::default()
+        })
+        .build()
+}
+
+/// Returns `count` distinct public keys which don't belong to any validator.
+fn unknown_pubkeys(count: usize) -> Vec<PublicKeyBytes> {
+    (0..count as u64)
+        .map(|i| {
+            let mut bytes = [0; 48];
+            bytes[..8].copy_from_slice(&i.to_le_bytes());
+            PublicKeyBytes::deserialize(&bytes).unwrap()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn auto_register_local_pubkeys() {
+    let harness = get_auto_register_harness();
+    let mut validator_monitor = harness.chain.validator_monitor.write();
+
+    let known_pubkeys = KEYPAIRS[0..4]
+        .iter()
+        .map(|keypair| PublicKeyBytes::from(&keypair.pk))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        validator_monitor.auto_register_local_pubkeys(&known_pubkeys),
+        4
+    );
+    // Keys which are already monitored are not counted again, but pending validators are added.
+    let mut pubkeys = known_pubkeys.clone();
+    pubkeys.extend(unknown_pubkeys(2));
+    assert_eq!(validator_monitor.auto_register_local_pubkeys(&pubkeys), 2);
+    assert_eq!(validator_monitor.get_all_monitored_validators().len(), 6);
+
+    // Known validators are monitored by index.
+    assert!(validator_monitor.get_monitored_validator(0).is_some());
+    assert!(validator_monitor.get_monitored_validator(4).is_none());
+}
+
+#[tokio::test]
+async fn auto_register_local_pubkeys_is_capped() {
+    let harness = get_auto_register_harness();
+    let mut validator_monitor = harness.chain.validator_monitor.write();
+
+    let pubkeys = unknown_pubkeys(MAX_API_REGISTERED_VALIDATORS + 10);
+    assert_eq!(
+        validator_monitor.auto_register_local_pubkeys(&pubkeys[..10]),
+        10
+    );
+    assert_eq!(
+        validator_monitor.auto_register_local_pubkeys(&pubkeys),
+        MAX_API_REGISTERED_VALIDATORS - 10
+    );
+    assert_eq!(
+        validator_monitor.get_all_monitored_validators().len(),
+        MAX_API_REGISTERED_VALIDATORS
+    );
+
+    // The monitor is full.
+    let more_pubkeys = KEYPAIRS[0..4]
+        .iter()
+        .map(|keypair| PublicKeyBytes::from(&keypair.pk))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        validator_monitor.auto_register_local_pubkeys(&more_pubkeys),
+        0
+    );
+}
+
+#[tokio::test]
+async fn auto_register_local_pubkeys_disabled() {
+    let harness = get_harness(VALIDATOR_COUNT, vec![]);
+    let pubkeys = unknown_pubkeys(4);
+    assert_eq!(
+        harness
+            .chain
+            .validator_monitor
+            .write()
+            .auto_register_local_pubkeys(&pubkeys),
+        0
+    );
+}
+
+/// Returns the names of the metrics which have a series labelled with `validator == id`.
+fn metrics_for_validator(id: &str) -> Vec<String> {
+    lighthouse_metrics::gather()
+        .into_iter()
+        .filter(|family| {
+            family.get_metric().iter().any(|metric| {
+                metric
+                    .get_label()
+                    .iter()
+                    .any(|label| label.get_name() == "validator" && label.get_value() == id)
+            })
+        })
+        .map(|family| family.get_name().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn individual_metrics_removed_above_threshold() {
+    let individual_tracking_threshold = 4;
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .logger(test_logger())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .validator_monitor_config(ValidatorMonitorConfig {
+            auto_register: true,
+            individual_tracking_threshold,
+            ..<_>::default()
+        })
+        .build();
+    let head_state = harness.get_current_state();
+    let epoch = head_state.current_epoch();
+    let mut validator_monitor = harness.chain.validator_monitor.write();
+
+    // Learn the validator indices, then register validators up to the threshold. These indices
+    // aren't monitored by any other test, which share the same metrics registry.
+    let first_index = 40;
+    validator_monitor.process_valid_state(epoch, &head_state, &harness.chain.spec);
+    let pubkeys = KEYPAIRS[first_index..first_index + individual_tracking_threshold]
+        .iter()
+        .map(|keypair| PublicKeyBytes::from(&keypair.pk))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        validator_monitor.auto_register_local_pubkeys(&pubkeys),
+        individual_tracking_threshold
+    );
+    validator_monitor.process_valid_state(epoch, &head_state, &harness.chain.spec);
+
+    let ids = (first_index..first_index + individual_tracking_threshold)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>();
+    for id in &ids {
+        assert!(
+            !metrics_for_validator(id).is_empty(),
+            "validator {id} should have individual metrics"
+        );
+    }
+
+    // Crossing the threshold removes every per-validator series.
+    validator_monitor
+        .auto_register_local_validator((first_index + individual_tracking_threshold) as u64);
+    assert_eq!(
+        validator_monitor.get_all_monitored_validators().len(),
+        individual_tracking_threshold + 1
+    );
+    for id in &ids {
+        assert_eq!(
+            metrics_for_validator(id),
+            Vec::<String>::new(),
+            "validator {id} should have no individual metrics"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Tracks the time taken to process each type of gossip work, against a configurable budget.
+//!
+//! On slow hardware, validating a gossip topic may persistently take longer than its peers expect,
+//! which delays propagation and erodes our gossipsub scores. Whilst the moving average of the
+//! processing time of a type of gossip work exceeds the budget, new work of that type may be
+//! started on up to `BeaconProcessorConfig::gossip_overflow_workers` workers in addition to
+//! the usual `max_workers`, rather than waiting in a queue.
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The weight of each new processing time in the moving average.
+const MOVING_AVERAGE_WEIGHT: f64 = 0.1;
+/// The number of processing times which must be recorded before a type of work can be over budget.
+const MIN_SAMPLES: u64 = 16;
+/// A type of work which is over budget returns within budget once its moving average falls below
+/// this fraction of the budget, so that it doesn't flap at the boundary.
+const RECOVERY_FRACTION: f64 = 0.8;
+
+#[derive(Debug, Default)]
+struct WorkLatency {
+    average_secs: f64,
+    samples: u64,
+    over_budget: bool,
+}
+
+/// The processing time of each type of gossip work.
+#[derive(Debug)]
+pub struct LatencyBudget {
+    budget: Duration,
+    overflow_workers: usize,
+    latencies: HashMap<&'static str, WorkLatency>,
+}
+
+impl LatencyBudget {
+    pub fn new(budget: Duration, overflow_workers: usize) -> Self {
+        Self {
+            budget,
+            overflow_workers,
+            latencies: HashMap::new(),
+        }
+    }
+
+    /// Record that processing some work of type `work_id` took `duration`.
+    ///
+    /// Returns the new state if the work type has just gone over budget (`true`) or returned
+    /// within budget (`false`).
+    pub fn record(&mut self, work_id: &'static str, duration: Duration) -> Option<bool> {
+        let budget_secs = self.budget.as_secs_f64();
+        let latency = self.latencies.entry(work_id).or_default();
+
+        latency.samples = latency.samples.saturating_add(1);
+        latency.average_secs = if latency.samples == 1 {
+            duration.as_secs_f64()
+        } else {
+            latency.average_secs * (1.0 - MOVING_AVERAGE_WEIGHT)
+                + duration.as_secs_f64() * MOVING_AVERAGE_WEIGHT
+        };
+
+        let over_budget = if latency.over_budget {
+            latency.average_secs >= budget_secs * RECOVERY_FRACTION
+        } else {
+            latency.samples >= MIN_SAMPLES && latency.average_secs > budget_secs
+        };
+        if over_budget != latency.over_budget {
+            latency.over_budget = over_budget;
+            Some(over_budget)
+        } else {
+            None
+        }
+    }
+
+    /// The moving average of the processing time of `work_id`.
+    pub fn average(&self, work_id: &str) -> Option<Duration> {
+        self.latencies
+            .get(work_id)
+            .map(|latency| Duration::from_secs_f64(latency.average_secs))
+    }
+
+    pub fn is_over_budget(&self, work_id: &str) -> bool {
+        self.latencies
+            .get(work_id)
+            .map_or(false, |latency| latency.over_budget)
+    }
+
+    /// Returns `true` if work of type `work_id` may be started on an overflow worker, given the
+    /// number of `current_workers` and the usual `max_workers`.
+    pub fn can_spawn_overflow(
+        &self,
+        work_id: &str,
+        current_workers: usize,
+        max_workers: usize,
+    ) -> bool {
+        self.is_over_budget(work_id)
+            && current_workers < max_workers.saturating_add(self.overflow_workers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORK: &str = "gossip_block";
+
+    #[test]
+    fn persistent_latency_goes_over_budget() {
+        let mut budget = LatencyBudget::new(Duration::from_millis(100), 2);
+
+        // A single slow message isn't enough to go over budget.
+        assert_eq!(budget.record(WORK, Duration::from_secs(1)), None);
+        assert!(!budget.is_over_budget(WORK));
+
+        let mut transitions = vec![];
+        for _ in 1..MIN_SAMPLES {
+            transitions.extend(budget.record(WORK, Duration::from_millis(200)));
+        }
+        assert_eq!(transitions, vec![true]);
+        assert!(budget.can_spawn_overflow(WORK, 4, 4));
+        assert!(budget.can_spawn_overflow(WORK, 5, 4));
+        assert!(!budget.can_spawn_overflow(WORK, 6, 4));
+        assert!(!budget.can_spawn_overflow("gossip_aggregate", 4, 4));
+
+        // The work type only returns within budget once the average falls well below the budget.
+        let mut transitions = vec![];
+        while !budget
+            .average(WORK)
+            .is_some_and(|average| average < Duration::from_millis(100).mul_f64(RECOVERY_FRACTION))
+        {
+            assert!(budget.is_over_budget(WORK));
+            transitions.extend(budget.record(WORK, Duration::from_millis(10)));
+        }
+        assert_eq!(transitions, vec![false]);
+        assert!(!budget.is_over_budget(WORK));
+    }
+}
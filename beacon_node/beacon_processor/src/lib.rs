@@ -37,7 +37,36 @@
 //! Whenever the manager receives a notification that a worker has finished a parcel of work, it
 //! checks the queues to see if there are more parcels of work that can be spawned in a new worker
 //! task.
+//!
+//! ## Load shedding
+//!
+//! If `BeaconProcessorConfig::load_shedding_threshold` is set, new work which arrives while all
+//! workers are busy is dropped (rather than queued) once the queues hold enough items, lowest
+//! value work first:
+//!
+//! | Tier     | Shed at         | Work                                                          |
+//! |----------|-----------------|---------------------------------------------------------------|
+//! | `Low`    | `threshold`     | Unaggregated attestations on subnets not required by local    |
+//! |          |                 | validators, light client requests, deposit snapshot requests. |
+//! | `Medium` | `2 * threshold` | Other unaggregated attestations (including for unknown        |
+//! |          |                 | blocks), sync committee messages, BLS to execution changes.   |
+//!
+//! All other work is never shed, although it may still be dropped if its queue is full. In
+//! particular, backfill chain segments are never shed, since sync waits for each batch to be
+//! processed. Shed RPC requests receive an error response, and shed work is counted in the
+//! `beacon_processor_work_events_shed_count` metric.
+//!
+//! ## Gossip latency budget
+//!
+//! If `BeaconProcessorConfig::gossip_latency_budget` is set, the time taken to process each type of
+//! gossip work is tracked. New work of a type which is persistently over budget may be started on
+//! one of `BeaconProcessorConfig::gossip_overflow_workers` additional workers, rather than waiting
+//! for one of the usual workers. See the `latency_budget` module.
+//!
+//! Offloading is the only policy for work which is over budget. Messages are never accepted before
+//! their validation completes, since that would forward unvalidated messages to our peers.
 
+use crate::latency_budget::LatencyBudget;
 use crate::work_reprocessing_queue::{
     QueuedBackfillBatch, QueuedGossipBlock, ReprocessQueueMessage,
 };
@@ -50,18 +79,19 @@ use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, trace, warn, Logger};
 use slot_clock::SlotClock;
 use std::cmp;
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Context;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
 use types::{
     Attestation, BeaconState, ChainSpec, Hash256, RelativeEpoch, SignedAggregateAndProof, SubnetId,
+    SyncCommitteeMessage, SyncSubnetId,
 };
 use types::{EthSpec, Slot};
 use work_reprocessing_queue::IgnoredRpcBlock;
@@ -70,6 +100,7 @@ use work_reprocessing_queue::{
     QueuedUnaggregate, ReadyWork,
 };
 
+mod latency_budget;
 mod metrics;
 pub mod work_reprocessing_queue;
 
@@ -123,6 +154,8 @@ pub struct BeaconProcessorQueueLengths {
     lc_bootstrap_queue: usize,
     lc_optimistic_update_queue: usize,
     lc_finality_update_queue: usize,
+    deposit_snapshot_queue: usize,
+    rpc_deposit_snapshot_queue: usize,
     api_request_p0_queue: usize,
     api_request_p1_queue: usize,
 }
@@ -176,10 +209,124 @@ impl BeaconProcessorQueueLengths {
             lc_bootstrap_queue: 1024,
             lc_optimistic_update_queue: 512,
             lc_finality_update_queue: 512,
+            deposit_snapshot_queue: 64,
+            rpc_deposit_snapshot_queue: 8,
             api_request_p0_queue: 1024,
             api_request_p1_queue: 1024,
         })
     }
+
+    /// Replaces the default queue lengths with those in `overrides`, which are keyed by the
+    /// work type of the queue (e.g. `gossip_block`).
+    pub fn with_overrides(mut self, overrides: &HashMap<String, usize>) -> Result<Self, String> {
+        for (work_type, &queue_len) in overrides {
+            let queue = overridable_queue(work_type, queue_len)?;
+            *queue(&mut self) = queue_len;
+        }
+
+        Ok(self)
+    }
+}
+
+/// Returns the length of a queue within `BeaconProcessorQueueLengths`.
+type QueueLength = fn(&mut BeaconProcessorQueueLengths) -> &mut usize;
+
+/// The work types of the queues whose lengths may be overridden.
+const OVERRIDABLE_QUEUES: &[(&str, QueueLength)] = &[
+    (GOSSIP_AGGREGATE, |q| &mut q.aggregate_queue),
+    (GOSSIP_ATTESTATION, |q| &mut q.attestation_queue),
+    (UNKNOWN_BLOCK_AGGREGATE, |q| {
+        &mut q.unknown_block_aggregate_queue
+    }),
+    (UNKNOWN_BLOCK_ATTESTATION, |q| {
+        &mut q.unknown_block_attestation_queue
+    }),
+    (GOSSIP_SYNC_SIGNATURE, |q| &mut q.sync_message_queue),
+    (GOSSIP_SYNC_CONTRIBUTION, |q| &mut q.sync_contribution_queue),
+    (GOSSIP_VOLUNTARY_EXIT, |q| {
+        &mut q.gossip_voluntary_exit_queue
+    }),
+    (GOSSIP_PROPOSER_SLASHING, |q| {
+        &mut q.gossip_proposer_slashing_queue
+    }),
+    (GOSSIP_ATTESTER_SLASHING, |q| {
+        &mut q.gossip_attester_slashing_queue
+    }),
+    (GOSSIP_LIGHT_CLIENT_FINALITY_UPDATE, |q| {
+        &mut q.finality_update_queue
+    }),
+    (GOSSIP_LIGHT_CLIENT_OPTIMISTIC_UPDATE, |q| {
+        &mut q.optimistic_update_queue
+    }),
+    (UNKNOWN_LIGHT_CLIENT_UPDATE, |q| {
+        &mut q.unknown_light_client_update_queue
+    }),
+    (RPC_BLOCK, |q| &mut q.rpc_block_queue),
+    (RPC_BLOBS, |q| &mut q.rpc_blob_queue),
+    (CHAIN_SEGMENT, |q| &mut q.chain_segment_queue),
+    (CHAIN_SEGMENT_BACKFILL, |q| &mut q.backfill_chain_segment),
+    (GOSSIP_BLOCK, |q| &mut q.gossip_block_queue),
+    (GOSSIP_BLOBS_SIDECAR, |q| &mut q.gossip_blob_queue),
+    (GOSSIP_BLOBS_COLUMN_SIDECAR, |q| {
+        &mut q.gossip_data_column_queue
+    }),
+    (DELAYED_IMPORT_BLOCK, |q| &mut q.delayed_block_queue),
+    (STATUS_PROCESSING, |q| &mut q.status_queue),
+    (BLOCKS_BY_RANGE_REQUEST, |q| &mut q.bbrange_queue),
+    (BLOCKS_BY_ROOTS_REQUEST, |q| &mut q.bbroots_queue),
+    (BLOBS_BY_ROOTS_REQUEST, |q| &mut q.blbroots_queue),
+    (BLOBS_BY_RANGE_REQUEST, |q| &mut q.blbrange_queue),
+    (GOSSIP_BLS_TO_EXECUTION_CHANGE, |q| {
+        &mut q.gossip_bls_to_execution_change_queue
+    }),
+    (LIGHT_CLIENT_BOOTSTRAP_REQUEST, |q| {
+        &mut q.lc_bootstrap_queue
+    }),
+    (LIGHT_CLIENT_OPTIMISTIC_UPDATE_REQUEST, |q| {
+        &mut q.lc_optimistic_update_queue
+    }),
+    (LIGHT_CLIENT_FINALITY_UPDATE_REQUEST, |q| {
+        &mut q.lc_finality_update_queue
+    }),
+    (DEPOSIT_SNAPSHOT_REQUEST, |q| &mut q.deposit_snapshot_queue),
+    (RPC_DEPOSIT_SNAPSHOT, |q| &mut q.rpc_deposit_snapshot_queue),
+    (API_REQUEST_P0, |q| &mut q.api_request_p0_queue),
+    (API_REQUEST_P1, |q| &mut q.api_request_p1_queue),
+];
+
+/// Returns the queue length for `work_type`, or an error if `work_type` names an unknown queue or
+/// `queue_len` is zero.
+fn overridable_queue(work_type: &str, queue_len: usize) -> Result<QueueLength, String> {
+    let (_, queue) = OVERRIDABLE_QUEUES
+        .iter()
+        .find(|(name, _)| *name == work_type)
+        .ok_or_else(|| format!("Unknown beacon processor queue: {}", work_type))?;
+    if queue_len == 0 {
+        return Err(format!(
+            "Beacon processor queue {} must have a non-zero length",
+            work_type
+        ));
+    }
+    Ok(*queue)
+}
+
+/// Returns an error if `overrides` names an unknown queue or sets a queue length of zero.
+pub fn validate_queue_length_overrides(overrides: &HashMap<String, usize>) -> Result<(), String> {
+    for (work_type, &queue_len) in overrides {
+        overridable_queue(work_type, queue_len)?;
+    }
+    Ok(())
+}
+
+fn deserialize_queue_length_overrides<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, usize>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let overrides = HashMap::deserialize(deserializer)?;
+    validate_queue_length_overrides(&overrides).map_err(serde::de::Error::custom)?;
+    Ok(overrides)
 }
 
 /// The name of the manager tokio task.
@@ -201,6 +348,7 @@ const WORKER_TASK_NAME: &str = "beacon_processor_worker";
 /// individually verifying each attestation signature.
 const DEFAULT_MAX_GOSSIP_ATTESTATION_BATCH_SIZE: usize = 64;
 const DEFAULT_MAX_GOSSIP_AGGREGATE_BATCH_SIZE: usize = 64;
+const DEFAULT_MAX_GOSSIP_SYNC_SIGNATURE_BATCH_SIZE: usize = 64;
 
 /// Unique IDs used for metrics and testing.
 pub const WORKER_FREED: &str = "worker_freed";
@@ -217,6 +365,7 @@ pub const GOSSIP_VOLUNTARY_EXIT: &str = "gossip_voluntary_exit";
 pub const GOSSIP_PROPOSER_SLASHING: &str = "gossip_proposer_slashing";
 pub const GOSSIP_ATTESTER_SLASHING: &str = "gossip_attester_slashing";
 pub const GOSSIP_SYNC_SIGNATURE: &str = "gossip_sync_signature";
+pub const GOSSIP_SYNC_SIGNATURE_BATCH: &str = "gossip_sync_signature_batch";
 pub const GOSSIP_SYNC_CONTRIBUTION: &str = "gossip_sync_contribution";
 pub const GOSSIP_LIGHT_CLIENT_FINALITY_UPDATE: &str = "light_client_finality_update";
 pub const GOSSIP_LIGHT_CLIENT_OPTIMISTIC_UPDATE: &str = "light_client_optimistic_update";
@@ -233,6 +382,8 @@ pub const BLOBS_BY_ROOTS_REQUEST: &str = "blobs_by_roots_request";
 pub const LIGHT_CLIENT_BOOTSTRAP_REQUEST: &str = "light_client_bootstrap";
 pub const LIGHT_CLIENT_FINALITY_UPDATE_REQUEST: &str = "light_client_finality_update_request";
 pub const LIGHT_CLIENT_OPTIMISTIC_UPDATE_REQUEST: &str = "light_client_optimistic_update_request";
+pub const DEPOSIT_SNAPSHOT_REQUEST: &str = "deposit_snapshot_request";
+pub const RPC_DEPOSIT_SNAPSHOT: &str = "rpc_deposit_snapshot";
 pub const UNKNOWN_BLOCK_ATTESTATION: &str = "unknown_block_attestation";
 pub const UNKNOWN_BLOCK_AGGREGATE: &str = "unknown_block_aggregate";
 pub const UNKNOWN_LIGHT_CLIENT_UPDATE: &str = "unknown_light_client_update";
@@ -241,13 +392,32 @@ pub const API_REQUEST_P0: &str = "api_request_p0";
 pub const API_REQUEST_P1: &str = "api_request_p1";
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BeaconProcessorConfig {
     pub max_workers: usize,
     pub max_work_event_queue_len: usize,
     pub max_scheduled_work_queue_len: usize,
     pub max_gossip_attestation_batch_size: usize,
     pub max_gossip_aggregate_batch_size: usize,
+    pub max_gossip_sync_signature_batch_size: usize,
     pub enable_backfill_rate_limiting: bool,
+    /// Queue lengths which replace the defaults, keyed by work type.
+    #[serde(deserialize_with = "deserialize_queue_length_overrides")]
+    pub queue_length_overrides: HashMap<String, usize>,
+    /// Process gossip blocks and sync committee contributions ahead of all other work.
+    pub enable_high_priority_lane: bool,
+    /// Shed low-value work once this many items are queued and all workers are busy.
+    ///
+    /// Load shedding is disabled if `None`.
+    pub load_shedding_threshold: Option<usize>,
+    /// Gossip work of a type whose average processing time exceeds this budget may be started on
+    /// one of the `gossip_overflow_workers`.
+    ///
+    /// The budget is not enforced if `None`.
+    pub gossip_latency_budget: Option<Duration>,
+    /// The number of workers in addition to `max_workers` which may process gossip work which is
+    /// over budget.
+    pub gossip_overflow_workers: usize,
 }
 
 impl Default for BeaconProcessorConfig {
@@ -258,7 +428,13 @@ impl Default for BeaconProcessorConfig {
             max_scheduled_work_queue_len: DEFAULT_MAX_SCHEDULED_WORK_QUEUE_LEN,
             max_gossip_attestation_batch_size: DEFAULT_MAX_GOSSIP_ATTESTATION_BATCH_SIZE,
             max_gossip_aggregate_batch_size: DEFAULT_MAX_GOSSIP_AGGREGATE_BATCH_SIZE,
+            max_gossip_sync_signature_batch_size: DEFAULT_MAX_GOSSIP_SYNC_SIGNATURE_BATCH_SIZE,
             enable_backfill_rate_limiting: true,
+            queue_length_overrides: HashMap::new(),
+            enable_high_priority_lane: false,
+            load_shedding_threshold: None,
+            gossip_latency_budget: None,
+            gossip_overflow_workers: cmp::max(1, num_cpus::get() / 2),
         }
     }
 }
@@ -313,6 +489,7 @@ impl<T> FifoQueue<T> {
     /// Drops `item` if the queue is full.
     pub fn push(&mut self, item: T, item_desc: &str, log: &Logger) {
         if self.queue.len() == self.max_length {
+            metrics::inc_counter_vec(&metrics::BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL, &[item_desc]);
             error!(
                 log,
                 "Work queue is full";
@@ -354,8 +531,9 @@ impl<T> LifoQueue<T> {
     /// Add a new item to the front of the queue.
     ///
     /// If the queue is full, the item at the back of the queue is dropped.
-    pub fn push(&mut self, item: T) {
+    pub fn push(&mut self, item: T, item_desc: &str) {
         if self.queue.len() == self.max_length {
+            metrics::inc_counter_vec(&metrics::BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL, &[item_desc]);
             self.queue.pop_back();
         }
         self.queue.push_front(item);
@@ -520,6 +698,16 @@ pub struct GossipAggregatePackage<E: EthSpec> {
     pub seen_timestamp: Duration,
 }
 
+/// Items required to verify a batch of gossip sync committee messages.
+#[derive(Debug)]
+pub struct GossipSyncSignaturePackage {
+    pub message_id: MessageId,
+    pub peer_id: PeerId,
+    pub sync_signature: Box<SyncCommitteeMessage>,
+    pub subnet_id: SyncSubnetId,
+    pub seen_timestamp: Duration,
+}
+
 #[derive(Clone)]
 pub struct BeaconProcessorSend<E: EthSpec>(pub mpsc::Sender<WorkEvent<E>>);
 
@@ -589,7 +777,15 @@ pub enum Work<E: EthSpec> {
     GossipVoluntaryExit(BlockingFn),
     GossipProposerSlashing(BlockingFn),
     GossipAttesterSlashing(BlockingFn),
-    GossipSyncSignature(BlockingFn),
+    GossipSyncSignature {
+        sync_signature: Box<GossipSyncSignaturePackage>,
+        process_individual: Box<dyn FnOnce(GossipSyncSignaturePackage) + Send + Sync>,
+        process_batch: Box<dyn FnOnce(Vec<GossipSyncSignaturePackage>) + Send + Sync>,
+    },
+    GossipSyncSignatureBatch {
+        sync_signatures: Vec<GossipSyncSignaturePackage>,
+        process_batch: Box<dyn FnOnce(Vec<GossipSyncSignaturePackage>) + Send + Sync>,
+    },
     GossipSyncContribution(BlockingFn),
     GossipLightClientFinalityUpdate(BlockingFn),
     GossipLightClientOptimisticUpdate(BlockingFn),
@@ -610,13 +806,46 @@ pub enum Work<E: EthSpec> {
     BlobsByRangeRequest(BlockingFn),
     BlobsByRootsRequest(BlockingFn),
     GossipBlsToExecutionChange(BlockingFn),
-    LightClientBootstrapRequest(BlockingFn),
-    LightClientOptimisticUpdateRequest(BlockingFn),
-    LightClientFinalityUpdateRequest(BlockingFn),
+    LightClientBootstrapRequest {
+        process_fn: BlockingFn,
+        reject_fn: BlockingFn,
+    },
+    LightClientOptimisticUpdateRequest {
+        process_fn: BlockingFn,
+        reject_fn: BlockingFn,
+    },
+    LightClientFinalityUpdateRequest {
+        process_fn: BlockingFn,
+        reject_fn: BlockingFn,
+    },
+    DepositSnapshotRequest {
+        process_fn: BlockingFn,
+        reject_fn: BlockingFn,
+    },
+    RpcDepositSnapshot(BlockingFn),
     ApiRequestP0(BlockingOrAsync),
     ApiRequestP1(BlockingOrAsync),
 }
 
+/// The tiers of the load shedding priority table, in the order in which they are shed.
+///
+/// See the module-level documentation for the work in each tier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShedTier {
+    Low,
+    Medium,
+}
+
+impl ShedTier {
+    /// The number of queued items at which work in this tier is shed.
+    fn queued_threshold(self, load_shedding_threshold: usize) -> usize {
+        match self {
+            ShedTier::Low => load_shedding_threshold,
+            ShedTier::Medium => load_shedding_threshold.saturating_mul(2),
+        }
+    }
+}
+
 impl<E: EthSpec> fmt::Debug for Work<E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.str_id())
@@ -638,7 +867,8 @@ impl<E: EthSpec> Work<E> {
             Work::GossipVoluntaryExit(_) => GOSSIP_VOLUNTARY_EXIT,
             Work::GossipProposerSlashing(_) => GOSSIP_PROPOSER_SLASHING,
             Work::GossipAttesterSlashing(_) => GOSSIP_ATTESTER_SLASHING,
-            Work::GossipSyncSignature(_) => GOSSIP_SYNC_SIGNATURE,
+            Work::GossipSyncSignature { .. } => GOSSIP_SYNC_SIGNATURE,
+            Work::GossipSyncSignatureBatch { .. } => GOSSIP_SYNC_SIGNATURE_BATCH,
             Work::GossipSyncContribution(_) => GOSSIP_SYNC_CONTRIBUTION,
             Work::GossipLightClientFinalityUpdate(_) => GOSSIP_LIGHT_CLIENT_FINALITY_UPDATE,
             Work::GossipLightClientOptimisticUpdate(_) => GOSSIP_LIGHT_CLIENT_OPTIMISTIC_UPDATE,
@@ -652,9 +882,13 @@ impl<E: EthSpec> Work<E> {
             Work::BlocksByRootsRequest(_) => BLOCKS_BY_ROOTS_REQUEST,
             Work::BlobsByRangeRequest(_) => BLOBS_BY_RANGE_REQUEST,
             Work::BlobsByRootsRequest(_) => BLOBS_BY_ROOTS_REQUEST,
-            Work::LightClientBootstrapRequest(_) => LIGHT_CLIENT_BOOTSTRAP_REQUEST,
-            Work::LightClientOptimisticUpdateRequest(_) => LIGHT_CLIENT_OPTIMISTIC_UPDATE_REQUEST,
-            Work::LightClientFinalityUpdateRequest(_) => LIGHT_CLIENT_FINALITY_UPDATE_REQUEST,
+            Work::LightClientBootstrapRequest { .. } => LIGHT_CLIENT_BOOTSTRAP_REQUEST,
+            Work::LightClientOptimisticUpdateRequest { .. } => {
+                LIGHT_CLIENT_OPTIMISTIC_UPDATE_REQUEST
+            }
+            Work::LightClientFinalityUpdateRequest { .. } => LIGHT_CLIENT_FINALITY_UPDATE_REQUEST,
+            Work::DepositSnapshotRequest { .. } => DEPOSIT_SNAPSHOT_REQUEST,
+            Work::RpcDepositSnapshot(_) => RPC_DEPOSIT_SNAPSHOT,
             Work::UnknownBlockAttestation { .. } => UNKNOWN_BLOCK_ATTESTATION,
             Work::UnknownBlockAggregate { .. } => UNKNOWN_BLOCK_AGGREGATE,
             Work::GossipBlsToExecutionChange(_) => GOSSIP_BLS_TO_EXECUTION_CHANGE,
@@ -665,10 +899,102 @@ impl<E: EthSpec> Work<E> {
     }
 }
 
+impl<E: EthSpec> Work<E> {
+    /// The tier in which this work is shed when the `BeaconProcessor` is overloaded, or `None` if
+    /// it is never shed.
+    pub fn shed_tier(&self) -> Option<ShedTier> {
+        match self {
+            Work::GossipAttestation { attestation, .. } if !attestation.should_import => {
+                Some(ShedTier::Low)
+            }
+            Work::LightClientBootstrapRequest { .. }
+            | Work::LightClientOptimisticUpdateRequest { .. }
+            | Work::LightClientFinalityUpdateRequest { .. }
+            | Work::DepositSnapshotRequest { .. } => Some(ShedTier::Low),
+            Work::GossipAttestation { .. }
+            | Work::UnknownBlockAttestation { .. }
+            | Work::GossipSyncSignature { .. }
+            | Work::GossipBlsToExecutionChange(_) => Some(ShedTier::Medium),
+            Work::GossipAttestationBatch { .. }
+            | Work::GossipSyncSignatureBatch { .. }
+            | Work::GossipAggregate { .. }
+            | Work::UnknownBlockAggregate { .. }
+            | Work::UnknownLightClientOptimisticUpdate { .. }
+            | Work::GossipAggregateBatch { .. }
+            | Work::GossipBlock(_)
+            | Work::GossipBlobSidecar(_)
+            | Work::GossipDataColumnSidecar(_)
+            | Work::DelayedImportBlock { .. }
+            | Work::GossipVoluntaryExit(_)
+            | Work::GossipProposerSlashing(_)
+            | Work::GossipAttesterSlashing(_)
+            | Work::GossipSyncContribution(_)
+            | Work::GossipLightClientFinalityUpdate(_)
+            | Work::GossipLightClientOptimisticUpdate(_)
+            | Work::RpcBlock { .. }
+            | Work::RpcBlobs { .. }
+            | Work::IgnoredRpcBlock { .. }
+            | Work::ChainSegment(_)
+            | Work::ChainSegmentBackfill(_)
+            | Work::Status(_)
+            | Work::BlocksByRangeRequest(_)
+            | Work::BlocksByRootsRequest(_)
+            | Work::BlobsByRangeRequest(_)
+            | Work::BlobsByRootsRequest(_)
+            | Work::RpcDepositSnapshot(_)
+            | Work::ApiRequestP0(_)
+            | Work::ApiRequestP1(_) => None,
+        }
+    }
+
+    /// Consumes shed work, returning the function which responds to the peer with an error if this
+    /// work is an RPC request.
+    fn into_reject_fn(self) -> Option<BlockingFn> {
+        match self {
+            Work::LightClientBootstrapRequest { reject_fn, .. }
+            | Work::LightClientOptimisticUpdateRequest { reject_fn, .. }
+            | Work::LightClientFinalityUpdateRequest { reject_fn, .. }
+            | Work::DepositSnapshotRequest { reject_fn, .. } => Some(reject_fn),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this work validates a message received on gossip.
+    fn is_gossip(&self) -> bool {
+        matches!(
+            self,
+            Work::GossipAttestation { .. }
+                | Work::GossipAttestationBatch { .. }
+                | Work::GossipAggregate { .. }
+                | Work::GossipAggregateBatch { .. }
+                | Work::GossipBlock(_)
+                | Work::GossipBlobSidecar(_)
+                | Work::GossipDataColumnSidecar(_)
+                | Work::GossipVoluntaryExit(_)
+                | Work::GossipProposerSlashing(_)
+                | Work::GossipAttesterSlashing(_)
+                | Work::GossipSyncSignature { .. }
+                | Work::GossipSyncSignatureBatch { .. }
+                | Work::GossipSyncContribution(_)
+                | Work::GossipLightClientFinalityUpdate(_)
+                | Work::GossipLightClientOptimisticUpdate(_)
+                | Work::GossipBlsToExecutionChange(_)
+        )
+    }
+
+    /// Returns `true` if this work should be shed with `queued` items across all of the queues.
+    fn should_shed(&self, load_shedding_threshold: Option<usize>, queued: usize) -> bool {
+        match (load_shedding_threshold, self.shed_tier()) {
+            (Some(threshold), Some(tier)) => queued >= tier.queued_threshold(threshold),
+            _ => false,
+        }
+    }
+}
+
 /// Unifies all the messages processed by the `BeaconProcessor`.
 enum InboundEvent<E: EthSpec> {
     /// A worker has completed a task and is free.
-    WorkerIdle,
+    WorkerIdle(WorkerIdle),
     /// There is new work to be done.
     WorkEvent(WorkEvent<E>),
     /// A work event that was queued for re-processing has become ready.
@@ -681,7 +1007,7 @@ enum InboundEvent<E: EthSpec> {
 /// control (specifically in the ordering of event processing).
 struct InboundEvents<E: EthSpec> {
     /// Used by workers when they finish a task.
-    idle_rx: mpsc::Receiver<()>,
+    idle_rx: mpsc::Receiver<WorkerIdle>,
     /// Used by upstream processes to send new work to the `BeaconProcessor`.
     event_rx: mpsc::Receiver<WorkEvent<E>>,
     /// Used internally for queuing work ready to be re-processed.
@@ -695,8 +1021,8 @@ impl<E: EthSpec> Stream for InboundEvents<E> {
         // Always check for idle workers before anything else. This allows us to ensure that a big
         // stream of new events doesn't suppress the processing of existing events.
         match self.idle_rx.poll_recv(cx) {
-            Poll::Ready(Some(())) => {
-                return Poll::Ready(Some(InboundEvent::WorkerIdle));
+            Poll::Ready(Some(idle)) => {
+                return Poll::Ready(Some(InboundEvent::WorkerIdle(idle)));
             }
             Poll::Ready(None) => {
                 return Poll::Ready(None);
@@ -766,7 +1092,9 @@ impl<E: EthSpec> BeaconProcessor<E> {
         queue_lengths: BeaconProcessorQueueLengths,
     ) -> Result<(), String> {
         // Used by workers to communicate that they are finished a task.
-        let (idle_tx, idle_rx) = mpsc::channel::<()>(MAX_IDLE_QUEUE_LEN);
+        let (idle_tx, idle_rx) = mpsc::channel::<WorkerIdle>(MAX_IDLE_QUEUE_LEN);
+
+        let queue_lengths = queue_lengths.with_overrides(&self.config.queue_length_overrides)?;
 
         // Using LIFO queues for attestations since validator profits rely upon getting fresh
         // attestations into blocks. Additionally, later attestations contain more information than
@@ -825,6 +1153,10 @@ impl<E: EthSpec> BeaconProcessor<E> {
             FifoQueue::new(queue_lengths.lc_optimistic_update_queue);
         let mut lc_finality_update_queue = FifoQueue::new(queue_lengths.lc_finality_update_queue);
 
+        let mut deposit_snapshot_queue = FifoQueue::new(queue_lengths.deposit_snapshot_queue);
+        let mut rpc_deposit_snapshot_queue =
+            FifoQueue::new(queue_lengths.rpc_deposit_snapshot_queue);
+
         let mut api_request_p0_queue = FifoQueue::new(queue_lengths.api_request_p0_queue);
         let mut api_request_p1_queue = FifoQueue::new(queue_lengths.api_request_p1_queue);
 
@@ -853,11 +1185,27 @@ impl<E: EthSpec> BeaconProcessor<E> {
             };
 
             let enable_backfill_rate_limiting = self.config.enable_backfill_rate_limiting;
+            let enable_high_priority_lane = self.config.enable_high_priority_lane;
+            let load_shedding_threshold = self.config.load_shedding_threshold;
+            let mut latency_budget = self
+                .config
+                .gossip_latency_budget
+                .map(|budget| LatencyBudget::new(budget, self.config.gossip_overflow_workers));
+            // The total number of queued items at the end of the previous event.
+            let mut total_queued = 0;
 
             loop {
+                // Set if the last event freed an overflow worker whilst the usual workers are busy.
+                let mut overflow_worker_freed = false;
                 let work_event = match inbound_events.next().await {
-                    Some(InboundEvent::WorkerIdle) => {
+                    Some(InboundEvent::WorkerIdle(idle)) => {
                         self.current_workers = self.current_workers.saturating_sub(1);
+                        overflow_worker_freed = self.current_workers >= self.config.max_workers;
+                        if let (Some(latency_budget), Some(duration)) =
+                            (latency_budget.as_mut(), idle.gossip_duration)
+                        {
+                            self.record_gossip_latency(latency_budget, idle.work_id, duration);
+                        }
                         None
                     }
                     Some(InboundEvent::WorkEvent(event)) if enable_backfill_rate_limiting => {
@@ -936,6 +1284,15 @@ impl<E: EthSpec> BeaconProcessor<E> {
                 }
 
                 let can_spawn = self.current_workers < self.config.max_workers;
+                let can_spawn_overflow = work_event.as_ref().map_or(false, |event| {
+                    latency_budget.as_ref().map_or(false, |latency_budget| {
+                        latency_budget.can_spawn_overflow(
+                            event.work.str_id(),
+                            self.current_workers,
+                            self.config.max_workers,
+                        )
+                    })
+                });
                 let drop_during_sync = work_event
                     .as_ref()
                     .map_or(false, |event| event.drop_during_sync);
@@ -947,9 +1304,21 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     // We don't check the `work.drop_during_sync` here. We assume that if it made
                     // it into the queue at any point then we should process it.
                     None if can_spawn => {
+                        // When the high-priority lane is enabled, proposals and sync committee
+                        // contributions are processed before anything else.
+                        let high_priority_item = if enable_high_priority_lane {
+                            gossip_block_queue
+                                .pop()
+                                .or_else(|| sync_contribution_queue.pop())
+                        } else {
+                            None
+                        };
+
+                        if let Some(item) = high_priority_item {
+                            self.spawn_worker(item, idle_tx);
                         // Check for chain segments first, they're the most efficient way to get
                         // blocks into the system.
-                        if let Some(item) = chain_segment_queue.pop() {
+                        } else if let Some(item) = chain_segment_queue.pop() {
                             self.spawn_worker(item, idle_tx);
                         // Check sync blocks before gossip blocks, since we've already explicitly
                         // requested these blocks.
@@ -1097,8 +1466,67 @@ impl<E: EthSpec> BeaconProcessor<E> {
                         // and they don't influence fork choice.
                         } else if let Some(item) = sync_contribution_queue.pop() {
                             self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = sync_message_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
+                        // Check the sync committee message queue.
+                        //
+                        // Potentially use batching.
+                        } else if sync_message_queue.len() > 0 {
+                            let batch_size = cmp::min(
+                                sync_message_queue.len(),
+                                self.config.max_gossip_sync_signature_batch_size,
+                            );
+
+                            if batch_size < 2 {
+                                // One single message is in the queue, process it individually.
+                                if let Some(item) = sync_message_queue.pop() {
+                                    self.spawn_worker(item, idle_tx);
+                                }
+                            } else {
+                                // Collect two or more sync committee messages into a batch, so
+                                // they can take advantage of batch signature verification.
+                                //
+                                // Note: this will convert the `Work::GossipSyncSignature` item
+                                // into a `Work::GossipSyncSignatureBatch` item.
+                                let mut sync_signatures = Vec::with_capacity(batch_size);
+                                let mut process_batch_opt = None;
+                                for _ in 0..batch_size {
+                                    if let Some(item) = sync_message_queue.pop() {
+                                        match item {
+                                            Work::GossipSyncSignature {
+                                                sync_signature,
+                                                process_individual: _,
+                                                process_batch,
+                                            } => {
+                                                sync_signatures.push(*sync_signature);
+                                                if process_batch_opt.is_none() {
+                                                    process_batch_opt = Some(process_batch);
+                                                }
+                                            }
+                                            _ => error!(
+                                                self.log,
+                                                "Invalid item in sync message queue"
+                                            ),
+                                        }
+                                    }
+                                }
+
+                                if let Some(process_batch) = process_batch_opt {
+                                    // Process all sync committee messages with a single worker.
+                                    self.spawn_worker(
+                                        Work::GossipSyncSignatureBatch {
+                                            sync_signatures,
+                                            process_batch,
+                                        },
+                                        idle_tx,
+                                    )
+                                } else {
+                                    // There is no good reason for this to
+                                    // happen, it is a serious logic error.
+                                    // Since we only form batches when multiple
+                                    // work items exist, we should always have a
+                                    // work closure at this point.
+                                    crit!(self.log, "Missing sync signatures work");
+                                }
+                            }
                         // Aggregates and unaggregates queued for re-processing are older and we
                         // care about fresher ones, so check those first.
                         } else if let Some(item) = unknown_block_aggregate_queue.pop() {
@@ -1118,6 +1546,10 @@ impl<E: EthSpec> BeaconProcessor<E> {
                             self.spawn_worker(item, idle_tx);
                         } else if let Some(item) = blbroots_queue.pop() {
                             self.spawn_worker(item, idle_tx);
+                        // Deposit snapshots from peers replace a long deposit log download, so
+                        // import them as soon as they arrive.
+                        } else if let Some(item) = rpc_deposit_snapshot_queue.pop() {
+                            self.spawn_worker(item, idle_tx);
                         // Check slashings after all other consensus messages so we prioritize
                         // following head.
                         //
@@ -1149,6 +1581,9 @@ impl<E: EthSpec> BeaconProcessor<E> {
                             self.spawn_worker(item, idle_tx);
                         } else if let Some(item) = lc_finality_update_queue.pop() {
                             self.spawn_worker(item, idle_tx);
+                        // Serve deposit snapshots to peers that are syncing their deposit cache.
+                        } else if let Some(item) = deposit_snapshot_queue.pop() {
+                            self.spawn_worker(item, idle_tx);
                             // This statement should always be the final else statement.
                         } else {
                             // Let the journal know that a worker is freed and there's nothing else
@@ -1160,6 +1595,8 @@ impl<E: EthSpec> BeaconProcessor<E> {
                             }
                         }
                     }
+                    // An overflow worker has finished, but the usual workers are all still busy.
+                    None if overflow_worker_freed => (),
                     // There is no new work event and we are unable to spawn a new worker.
                     //
                     // I cannot see any good reason why this would happen.
@@ -1187,6 +1624,32 @@ impl<E: EthSpec> BeaconProcessor<E> {
                             "work_id" => work_id
                         );
                     }
+                    // All workers are busy and the queues are long enough that this event should be
+                    // shed, according to the load shedding priority table.
+                    Some(work_event)
+                        if !can_spawn
+                            && !can_spawn_overflow
+                            && work_event
+                                .work
+                                .should_shed(load_shedding_threshold, total_queued) =>
+                    {
+                        let work_id = work_event.work.str_id();
+                        metrics::inc_counter_vec(
+                            &metrics::BEACON_PROCESSOR_WORK_EVENTS_SHED_COUNT,
+                            &[work_id],
+                        );
+                        trace!(
+                            self.log,
+                            "Gossip processor shedding work";
+                            "msg" => "processor is overloaded",
+                            "work_id" => work_id,
+                            "queued" => total_queued,
+                        );
+                        // Shed RPC requests are rejected so that the peer still gets a response.
+                        if let Some(reject_fn) = work_event.work.into_reject_fn() {
+                            reject_fn();
+                        }
+                    }
                     // There is a new work event and the chain is not syncing. Process it or queue
                     // it.
                     Some(WorkEvent { work, .. }) => {
@@ -1194,7 +1657,14 @@ impl<E: EthSpec> BeaconProcessor<E> {
 
                         match work {
                             _ if can_spawn => self.spawn_worker(work, idle_tx),
-                            Work::GossipAttestation { .. } => attestation_queue.push(work),
+                            _ if can_spawn_overflow => {
+                                metrics::inc_counter_vec(
+                                    &metrics::BEACON_PROCESSOR_OVERFLOW_WORKERS_SPAWNED_COUNT,
+                                    &[work_id],
+                                );
+                                self.spawn_worker(work, idle_tx)
+                            }
+                            Work::GossipAttestation { .. } => attestation_queue.push(work, work_id),
                             // Attestation batches are formed internally within the
                             // `BeaconProcessor`, they are not sent from external services.
                             Work::GossipAttestationBatch { .. } => crit!(
@@ -1202,7 +1672,7 @@ impl<E: EthSpec> BeaconProcessor<E> {
                                     "Unsupported inbound event";
                                     "type" => "GossipAttestationBatch"
                             ),
-                            Work::GossipAggregate { .. } => aggregate_queue.push(work),
+                            Work::GossipAggregate { .. } => aggregate_queue.push(work, work_id),
                             // Aggregate batches are formed internally within the `BeaconProcessor`,
                             // they are not sent from external services.
                             Work::GossipAggregateBatch { .. } => crit!(
@@ -1231,9 +1701,18 @@ impl<E: EthSpec> BeaconProcessor<E> {
                             Work::GossipAttesterSlashing { .. } => {
                                 gossip_attester_slashing_queue.push(work, work_id, &self.log)
                             }
-                            Work::GossipSyncSignature { .. } => sync_message_queue.push(work),
+                            Work::GossipSyncSignature { .. } => {
+                                sync_message_queue.push(work, work_id)
+                            }
+                            // Sync signature batches are formed internally within the
+                            // `BeaconProcessor`, they are not sent from external services.
+                            Work::GossipSyncSignatureBatch { .. } => crit!(
+                                    self.log,
+                                    "Unsupported inbound event";
+                                    "type" => "GossipSyncSignatureBatch"
+                            ),
                             Work::GossipSyncContribution { .. } => {
-                                sync_contribution_queue.push(work)
+                                sync_contribution_queue.push(work, work_id)
                             }
                             Work::GossipLightClientFinalityUpdate { .. } => {
                                 finality_update_queue.push(work, work_id, &self.log)
@@ -1270,11 +1749,17 @@ impl<E: EthSpec> BeaconProcessor<E> {
                             Work::LightClientFinalityUpdateRequest { .. } => {
                                 lc_finality_update_queue.push(work, work_id, &self.log)
                             }
+                            Work::DepositSnapshotRequest { .. } => {
+                                deposit_snapshot_queue.push(work, work_id, &self.log)
+                            }
+                            Work::RpcDepositSnapshot { .. } => {
+                                rpc_deposit_snapshot_queue.push(work, work_id, &self.log)
+                            }
                             Work::UnknownBlockAttestation { .. } => {
-                                unknown_block_attestation_queue.push(work)
+                                unknown_block_attestation_queue.push(work, work_id)
                             }
                             Work::UnknownBlockAggregate { .. } => {
-                                unknown_block_aggregate_queue.push(work)
+                                unknown_block_aggregate_queue.push(work, work_id)
                             }
                             Work::GossipBlsToExecutionChange { .. } => {
                                 gossip_bls_to_execution_change_queue.push(work, work_id, &self.log)
@@ -1368,6 +1853,44 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     api_request_p1_queue.len() as i64,
                 );
 
+                total_queued = [
+                    aggregate_queue.len(),
+                    attestation_queue.len(),
+                    unknown_block_aggregate_queue.len(),
+                    unknown_block_attestation_queue.len(),
+                    sync_message_queue.len(),
+                    sync_contribution_queue.len(),
+                    gossip_voluntary_exit_queue.len(),
+                    gossip_proposer_slashing_queue.len(),
+                    gossip_attester_slashing_queue.len(),
+                    finality_update_queue.len(),
+                    optimistic_update_queue.len(),
+                    unknown_light_client_update_queue.len(),
+                    rpc_block_queue.len(),
+                    rpc_blob_queue.len(),
+                    chain_segment_queue.len(),
+                    backfill_chain_segment.len(),
+                    gossip_block_queue.len(),
+                    gossip_blob_queue.len(),
+                    gossip_data_column_queue.len(),
+                    delayed_block_queue.len(),
+                    status_queue.len(),
+                    bbrange_queue.len(),
+                    bbroots_queue.len(),
+                    blbroots_queue.len(),
+                    blbrange_queue.len(),
+                    gossip_bls_to_execution_change_queue.len(),
+                    lc_bootstrap_queue.len(),
+                    lc_optimistic_update_queue.len(),
+                    lc_finality_update_queue.len(),
+                    deposit_snapshot_queue.len(),
+                    rpc_deposit_snapshot_queue.len(),
+                    api_request_p0_queue.len(),
+                    api_request_p1_queue.len(),
+                ]
+                .into_iter()
+                .sum();
+
                 if aggregate_queue.is_full() && aggregate_debounce.elapsed() {
                     error!(
                         self.log,
@@ -1393,10 +1916,51 @@ impl<E: EthSpec> BeaconProcessor<E> {
         Ok(())
     }
 
+    /// Record the time taken to process some gossip work against the latency budget, logging when
+    /// its work type goes over or returns within budget.
+    fn record_gossip_latency(
+        &self,
+        latency_budget: &mut LatencyBudget,
+        work_id: &'static str,
+        duration: Duration,
+    ) {
+        match latency_budget.record(work_id, duration) {
+            Some(true) => {
+                warn!(
+                    self.log,
+                    "Gossip processing is over budget";
+                    "info" => "using overflow workers for this work type",
+                    "work_id" => work_id,
+                    "average_ms" => latency_budget.average(work_id).map(|d| d.as_millis()),
+                    "overflow_workers" => self.config.gossip_overflow_workers,
+                );
+                metrics::set_gauge_vec(
+                    &metrics::BEACON_PROCESSOR_GOSSIP_OVER_BUDGET,
+                    &[work_id],
+                    1,
+                );
+            }
+            Some(false) => {
+                debug!(
+                    self.log,
+                    "Gossip processing is within budget";
+                    "work_id" => work_id,
+                    "average_ms" => latency_budget.average(work_id).map(|d| d.as_millis()),
+                );
+                metrics::set_gauge_vec(
+                    &metrics::BEACON_PROCESSOR_GOSSIP_OVER_BUDGET,
+                    &[work_id],
+                    0,
+                );
+            }
+            None => (),
+        }
+    }
+
     /// Spawns a blocking worker thread to process some `Work`.
     ///
     /// Sends an message on `idle_tx` when the work is complete and the task is stopping.
-    fn spawn_worker(&mut self, work: Work<E>, idle_tx: mpsc::Sender<()>) {
+    fn spawn_worker(&mut self, work: Work<E>, idle_tx: mpsc::Sender<WorkerIdle>) {
         let work_id = work.str_id();
         let worker_timer =
             metrics::start_timer_vec(&metrics::BEACON_PROCESSOR_WORKER_TIME, &[work_id]);
@@ -1412,6 +1976,8 @@ impl<E: EthSpec> BeaconProcessor<E> {
         // As such, this instantiation should happen as early in the function as possible.
         let send_idle_on_drop = SendOnDrop {
             tx: idle_tx,
+            work_id,
+            gossip_start: work.is_gossip().then(Instant::now),
             _worker_timer: worker_timer,
             log: self.log.clone(),
         };
@@ -1460,6 +2026,19 @@ impl<E: EthSpec> BeaconProcessor<E> {
             } => task_spawner.spawn_blocking(move || {
                 process_batch(aggregates);
             }),
+            Work::GossipSyncSignature {
+                sync_signature,
+                process_individual,
+                process_batch: _,
+            } => task_spawner.spawn_blocking(move || {
+                process_individual(*sync_signature);
+            }),
+            Work::GossipSyncSignatureBatch {
+                sync_signatures,
+                process_batch,
+            } => task_spawner.spawn_blocking(move || {
+                process_batch(sync_signatures);
+            }),
             Work::ChainSegment(process_fn) => task_spawner.spawn_async(async move {
                 process_fn.await;
             }),
@@ -1497,17 +2076,16 @@ impl<E: EthSpec> BeaconProcessor<E> {
             Work::GossipVoluntaryExit(process_fn)
             | Work::GossipProposerSlashing(process_fn)
             | Work::GossipAttesterSlashing(process_fn)
-            | Work::GossipSyncSignature(process_fn)
             | Work::GossipSyncContribution(process_fn)
             | Work::GossipLightClientFinalityUpdate(process_fn)
             | Work::GossipLightClientOptimisticUpdate(process_fn)
             | Work::Status(process_fn)
             | Work::GossipBlsToExecutionChange(process_fn)
-            | Work::LightClientBootstrapRequest(process_fn)
-            | Work::LightClientOptimisticUpdateRequest(process_fn)
-            | Work::LightClientFinalityUpdateRequest(process_fn) => {
-                task_spawner.spawn_blocking(process_fn)
-            }
+            | Work::LightClientBootstrapRequest { process_fn, .. }
+            | Work::LightClientOptimisticUpdateRequest { process_fn, .. }
+            | Work::LightClientFinalityUpdateRequest { process_fn, .. }
+            | Work::DepositSnapshotRequest { process_fn, .. }
+            | Work::RpcDepositSnapshot(process_fn) => task_spawner.spawn_blocking(process_fn),
         };
     }
 }
@@ -1551,6 +2129,13 @@ impl TaskSpawner {
     }
 }
 
+/// Sent by a worker when it has finished its work.
+struct WorkerIdle {
+    work_id: &'static str,
+    /// The time taken to process the work, if it is gossip work.
+    gossip_duration: Option<Duration>,
+}
+
 /// This struct will send a message on `self.tx` when it is dropped. An error will be logged on
 /// `self.log` if the send fails (this happens when the node is shutting down).
 ///
@@ -1562,7 +2147,10 @@ impl TaskSpawner {
 ///
 /// https://doc.rust-lang.org/std/ops/trait.Drop.html#panics
 pub struct SendOnDrop {
-    tx: mpsc::Sender<()>,
+    tx: mpsc::Sender<WorkerIdle>,
+    work_id: &'static str,
+    /// The time at which the work started, if it is gossip work.
+    gossip_start: Option<Instant>,
     // The field is unused, but it's here to ensure the timer is dropped once the task has finished.
     _worker_timer: Option<metrics::HistogramTimer>,
     log: Logger,
@@ -1570,7 +2158,11 @@ pub struct SendOnDrop {
 
 impl Drop for SendOnDrop {
     fn drop(&mut self) {
-        if let Err(e) = self.tx.try_send(()) {
+        let idle = WorkerIdle {
+            work_id: self.work_id,
+            gossip_duration: self.gossip_start.map(|start| start.elapsed()),
+        };
+        if let Err(e) = self.tx.try_send(idle) {
             warn!(
                 self.log,
                 "Unable to free worker";
@@ -1580,3 +2172,93 @@ impl Drop for SendOnDrop {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    #[test]
+    fn load_shedding_priority_table() {
+        let lc_bootstrap = Work::<E>::LightClientBootstrapRequest {
+            process_fn: Box::new(|| {}),
+            reject_fn: Box::new(|| {}),
+        };
+        let bls_change = Work::<E>::GossipBlsToExecutionChange(Box::new(|| {}));
+        let block = Work::<E>::GossipBlock(Box::pin(async {}));
+        let backfill = Work::<E>::ChainSegmentBackfill(Box::pin(async {}));
+
+        assert_eq!(lc_bootstrap.shed_tier(), Some(ShedTier::Low));
+        assert_eq!(bls_change.shed_tier(), Some(ShedTier::Medium));
+        assert_eq!(block.shed_tier(), None);
+        assert_eq!(backfill.shed_tier(), None);
+
+        // Nothing is shed when load shedding is disabled.
+        assert!(!lc_bootstrap.should_shed(None, usize::MAX));
+
+        assert!(!lc_bootstrap.should_shed(Some(10), 9));
+        assert!(lc_bootstrap.should_shed(Some(10), 10));
+        assert!(!bls_change.should_shed(Some(10), 19));
+        assert!(bls_change.should_shed(Some(10), 20));
+        assert!(!block.should_shed(Some(10), usize::MAX));
+        assert!(!backfill.should_shed(Some(10), usize::MAX));
+
+        // Only RPC requests are rejected when shed.
+        assert!(lc_bootstrap.into_reject_fn().is_some());
+        assert!(bls_change.into_reject_fn().is_none());
+    }
+
+    #[test]
+    fn queue_length_overrides() {
+        let spec = E::default_spec();
+        let state = BeaconState::<E>::new(0, Default::default(), &spec);
+        let queue_lengths = || BeaconProcessorQueueLengths::from_state(&state, &spec).unwrap();
+
+        // Every overridable queue is accepted, both when validating and when applied.
+        for (work_type, _) in OVERRIDABLE_QUEUES {
+            let overrides = HashMap::from([(work_type.to_string(), 7)]);
+            assert_eq!(validate_queue_length_overrides(&overrides), Ok(()));
+            queue_lengths().with_overrides(&overrides).unwrap();
+        }
+
+        let overrides = HashMap::from([(GOSSIP_BLOCK.to_string(), 7)]);
+        assert_eq!(
+            queue_lengths()
+                .with_overrides(&overrides)
+                .unwrap()
+                .gossip_block_queue,
+            7
+        );
+
+        for overrides in [
+            HashMap::from([("gossip_blocks".to_string(), 7)]),
+            HashMap::from([(GOSSIP_BLOCK.to_string(), 0)]),
+        ] {
+            assert!(validate_queue_length_overrides(&overrides).is_err());
+            assert!(queue_lengths().with_overrides(&overrides).is_err());
+        }
+    }
+
+    #[test]
+    fn queue_length_overrides_validated_on_deserialize() {
+        let mut config = serde_json::to_value(BeaconProcessorConfig::default()).unwrap();
+        config["queue_length_overrides"] = serde_json::json!({ "gossip_block": 7 });
+        serde_json::from_value::<BeaconProcessorConfig>(config.clone()).unwrap();
+
+        config["queue_length_overrides"] = serde_json::json!({ "gossip_blocks": 7 });
+        assert!(serde_json::from_value::<BeaconProcessorConfig>(config).is_err());
+
+        // Fields which are missing take their default values.
+        let config = serde_json::json!({ "queue_length_overrides": { "gossip_block": 7 } });
+        let config = serde_json::from_value::<BeaconProcessorConfig>(config).unwrap();
+        assert_eq!(
+            config,
+            BeaconProcessorConfig {
+                queue_length_overrides: HashMap::from([(GOSSIP_BLOCK.to_string(), 7)]),
+                ..BeaconProcessorConfig::default()
+            }
+        );
+    }
+}
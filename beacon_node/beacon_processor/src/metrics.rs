@@ -15,6 +15,21 @@ lazy_static::lazy_static! {
         "Count of work events purposefully ignored",
         &["type"]
     );
+    pub static ref BEACON_PROCESSOR_WORK_EVENTS_SHED_COUNT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_processor_work_events_shed_count",
+        "Count of work events shed because the beacon processor was overloaded",
+        &["type"]
+    );
+    pub static ref BEACON_PROCESSOR_OVERFLOW_WORKERS_SPAWNED_COUNT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_processor_overflow_workers_spawned_count",
+        "Count of work events started on an overflow worker because their type was over the gossip latency budget",
+        &["type"]
+    );
+    pub static ref BEACON_PROCESSOR_GOSSIP_OVER_BUDGET: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "beacon_processor_gossip_over_budget",
+        "Set to 1 whilst the average processing time of a type of gossip work exceeds the latency budget",
+        &["type"]
+    );
     pub static ref BEACON_PROCESSOR_WORK_EVENTS_STARTED_COUNT: Result<IntCounterVec> = try_create_int_counter_vec(
         "beacon_processor_work_events_started_count",
         "Count of work events which have been started by a worker",
@@ -41,6 +56,11 @@ lazy_static::lazy_static! {
         "beacon_processor_event_handling_seconds",
         "Time spent handling a new message and allocating it to a queue or worker."
     );
+    pub static ref BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_processor_queue_dropped_total",
+        "Count of work events dropped because their beacon processor queue was full.",
+        &["type"]
+    );
     // Gossip blocks.
     pub static ref BEACON_PROCESSOR_GOSSIP_BLOCK_QUEUE_TOTAL: Result<IntGauge> = try_create_int_gauge(
         "beacon_processor_gossip_block_queue_total",
@@ -142,6 +162,10 @@ lazy_static::lazy_static! {
         "beacon_processor_reprocessing_queue_matched_attestations",
         "Number of queued attestations where as matching block has been imported."
     );
+    pub static ref BEACON_PROCESSOR_REPROCESSING_QUEUE_RECENTLY_IMPORTED_ATTESTATIONS: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_reprocessing_queue_recently_imported_attestations",
+        "Number of attestations sent for immediate re-processing because their block was imported just before they were queued."
+    );
 
     /*
      * Light client update reprocessing queue metrics.
@@ -9,7 +9,9 @@
 //! case, the block will be sent off for immediate processing (skipping the `DelayQueue`).
 //!
 //! Aggregated and unaggregated attestations that failed verification due to referencing an unknown
-//! block will be re-queued until their block is imported, or until they expire.
+//! block will be re-queued until their block is imported, or until they expire. The roots of
+//! recently imported blocks are remembered for a short window so that attestations which lost the
+//! race with their block's import are re-processed immediately instead of waiting to expire.
 use crate::metrics;
 use crate::{AsyncFn, BlockingFn, Work, WorkEvent};
 use fnv::FnvHashMap;
@@ -44,6 +46,10 @@ pub const ADDITIONAL_QUEUED_BLOCK_DELAY: Duration = Duration::from_millis(5);
 /// For how long to queue aggregated and unaggregated attestations for re-processing.
 pub const QUEUED_ATTESTATION_DELAY: Duration = Duration::from_secs(12);
 
+/// For how long to remember the root of an imported block, so that attestations which failed
+/// verification just before the block was imported can be re-processed straight away.
+pub const RECENTLY_IMPORTED_BLOCK_WINDOW: Duration = Duration::from_secs(4);
+
 /// For how long to queue light client updates for re-processing.
 pub const QUEUED_LIGHT_CLIENT_UPDATE_DELAY: Duration = Duration::from_secs(12);
 
@@ -58,6 +64,9 @@ const MAXIMUM_QUEUED_BLOCKS: usize = 16;
 /// How many attestations we keep before new ones get dropped.
 const MAXIMUM_QUEUED_ATTESTATIONS: usize = 16_384;
 
+/// How many recently imported block roots we remember before new ones get dropped.
+const MAXIMUM_RECENTLY_IMPORTED_BLOCKS: usize = 64;
+
 /// How many light client updates we keep before new ones get dropped.
 const MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES: usize = 128;
 
@@ -193,6 +202,8 @@ enum InboundEvent {
     ReadyAttestation(QueuedAttestationId),
     /// A light client update that is ready for re-processing.
     ReadyLightClientUpdate(QueuedLightClientUpdateId),
+    /// A recently imported block root has left the reprocessing window.
+    ExpiredImportedBlock(Hash256),
     /// A backfill batch that was queued is ready for processing.
     ReadyBackfillSync(QueuedBackfillBatch),
     /// A message sent to the `ReprocessQueue`
@@ -215,6 +226,8 @@ struct ReprocessQueue<S> {
     attestations_delay_queue: DelayQueue<QueuedAttestationId>,
     /// Queue to manage scheduled light client updates.
     lc_updates_delay_queue: DelayQueue<QueuedLightClientUpdateId>,
+    /// Queue to manage the expiry of recently imported block roots.
+    imported_blocks_delay_queue: DelayQueue<Hash256>,
 
     /* Queued items */
     /// Queued blocks.
@@ -225,6 +238,8 @@ struct ReprocessQueue<S> {
     queued_unaggregates: FnvHashMap<usize, (QueuedUnaggregate, DelayKey)>,
     /// Attestations (aggregated and unaggregated) per root.
     awaiting_attestations_per_root: HashMap<Hash256, Vec<QueuedAttestationId>>,
+    /// Roots of blocks imported within the last `RECENTLY_IMPORTED_BLOCK_WINDOW`.
+    recently_imported_block_roots: HashSet<Hash256>,
     /// Queued Light Client Updates.
     queued_lc_updates: FnvHashMap<usize, (QueuedLightClientUpdate, DelayKey)>,
     /// Light Client Updates per parent_root.
@@ -317,6 +332,17 @@ impl<S: SlotClock> Stream for ReprocessQueue<S> {
             Poll::Ready(None) | Poll::Pending => (),
         }
 
+        match self.imported_blocks_delay_queue.poll_expired(cx) {
+            Poll::Ready(Some(block_root)) => {
+                return Poll::Ready(Some(InboundEvent::ExpiredImportedBlock(
+                    block_root.into_inner(),
+                )));
+            }
+            // `Poll::Ready(None)` means that there are no more entries in the delay queue and we
+            // will continue to get this result until something else is added into the queue.
+            Poll::Ready(None) | Poll::Pending => (),
+        }
+
         if let Some(next_backfill_batch_event) = self.next_backfill_batch_event.as_mut() {
             match next_backfill_batch_event.as_mut().poll(cx) {
                 Poll::Ready(_) => {
@@ -388,11 +414,13 @@ impl<S: SlotClock> ReprocessQueue<S> {
             rpc_block_delay_queue: DelayQueue::new(),
             attestations_delay_queue: DelayQueue::new(),
             lc_updates_delay_queue: DelayQueue::new(),
+            imported_blocks_delay_queue: DelayQueue::new(),
             queued_gossip_block_roots: HashSet::new(),
             queued_lc_updates: FnvHashMap::default(),
             queued_aggregates: FnvHashMap::default(),
             queued_unaggregates: FnvHashMap::default(),
             awaiting_attestations_per_root: HashMap::new(),
+            recently_imported_block_roots: HashSet::new(),
             awaiting_lc_updates_per_parent_root: HashMap::new(),
             queued_backfill_batches: Vec::new(),
             next_attestation: 0,
@@ -522,6 +550,19 @@ impl<S: SlotClock> ReprocessQueue<S> {
                 }
             }
             InboundEvent::Msg(UnknownBlockAggregate(queued_aggregate)) => {
+                // The block may have been imported between the attestation failing verification
+                // and it reaching this queue. If so, re-process it immediately.
+                if self
+                    .recently_imported_block_roots
+                    .contains(queued_aggregate.beacon_block_root())
+                {
+                    self.send_recently_imported_attestation(
+                        ReadyWork::Aggregate(queued_aggregate),
+                        log,
+                    );
+                    return;
+                }
+
                 if self.attestations_delay_queue.len() >= MAXIMUM_QUEUED_ATTESTATIONS {
                     if self.attestation_delay_debounce.elapsed() {
                         error!(
@@ -555,6 +596,19 @@ impl<S: SlotClock> ReprocessQueue<S> {
                 self.next_attestation += 1;
             }
             InboundEvent::Msg(UnknownBlockUnaggregate(queued_unaggregate)) => {
+                // The block may have been imported between the attestation failing verification
+                // and it reaching this queue. If so, re-process it immediately.
+                if self
+                    .recently_imported_block_roots
+                    .contains(queued_unaggregate.beacon_block_root())
+                {
+                    self.send_recently_imported_attestation(
+                        ReadyWork::Unaggregate(queued_unaggregate),
+                        log,
+                    );
+                    return;
+                }
+
                 if self.attestations_delay_queue.len() >= MAXIMUM_QUEUED_ATTESTATIONS {
                     if self.attestation_delay_debounce.elapsed() {
                         error!(
@@ -628,6 +682,15 @@ impl<S: SlotClock> ReprocessQueue<S> {
                 block_root,
                 parent_root,
             }) => {
+                // Remember this root for a short while, in case there are attestations for it
+                // still in flight to this queue.
+                if self.recently_imported_block_roots.len() < MAXIMUM_RECENTLY_IMPORTED_BLOCKS
+                    && self.recently_imported_block_roots.insert(block_root)
+                {
+                    self.imported_blocks_delay_queue
+                        .insert(block_root, RECENTLY_IMPORTED_BLOCK_WINDOW);
+                }
+
                 // Unqueue the attestations we have for this root, if any.
                 if let Some(queued_ids) = self.awaiting_attestations_per_root.remove(&block_root) {
                     let mut sent_count = 0;
@@ -845,6 +908,9 @@ impl<S: SlotClock> ReprocessQueue<S> {
                     }
                 }
             }
+            InboundEvent::ExpiredImportedBlock(block_root) => {
+                self.recently_imported_block_roots.remove(&block_root);
+            }
             InboundEvent::ReadyBackfillSync(queued_backfill_batch) => {
                 let millis_from_slot_start = self
                     .slot_clock
@@ -912,6 +978,22 @@ impl<S: SlotClock> ReprocessQueue<S> {
         );
     }
 
+    /// Sends an attestation whose block was imported within the last
+    /// `RECENTLY_IMPORTED_BLOCK_WINDOW` straight back for re-processing.
+    fn send_recently_imported_attestation(&mut self, work: ReadyWork, log: &Logger) {
+        metrics::inc_counter(
+            &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_RECENTLY_IMPORTED_ATTESTATIONS,
+        );
+
+        if self.ready_work_tx.try_send(work).is_err() {
+            error!(
+                log,
+                "Ignored attestation for recently imported block";
+                "hint" => "system may be overloaded",
+            );
+        }
+    }
+
     fn recompute_next_backfill_batch_event(&mut self) {
         // only recompute the `next_backfill_batch_event` if there are backfill batches in the queue
         if !self.queued_backfill_batches.is_empty() {
@@ -1062,6 +1144,62 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn attestation_for_recently_imported_block_is_sent_immediately() {
+        let runtime = TestRuntime::default();
+        let log = test_logger();
+        let (work_reprocessing_tx, work_reprocessing_rx) = mpsc::channel(4);
+        let (ready_work_tx, mut ready_work_rx) = mpsc::channel(4);
+        let slot_clock = Arc::new(testing_slot_clock(12));
+
+        spawn_reprocess_scheduler(
+            ready_work_tx,
+            work_reprocessing_rx,
+            &runtime.task_executor,
+            slot_clock.clone(),
+            log,
+            Duration::from_millis(500),
+        )
+        .unwrap();
+
+        // Pause time so it only advances manually
+        tokio::time::pause();
+
+        let block_root = Hash256::repeat_byte(1);
+        let queued_unaggregate = || {
+            ReprocessQueueMessage::UnknownBlockUnaggregate(QueuedUnaggregate {
+                beacon_block_root: block_root,
+                process_fn: Box::new(|| {}),
+            })
+        };
+
+        // The block is imported before the attestation reaches the queue.
+        work_reprocessing_tx
+            .try_send(ReprocessQueueMessage::BlockImported {
+                block_root,
+                parent_root: Hash256::zero(),
+            })
+            .unwrap();
+        work_reprocessing_tx.try_send(queued_unaggregate()).unwrap();
+        tokio::task::yield_now().await;
+
+        // The attestation should be sent for re-processing without waiting to expire.
+        assert!(matches!(
+            ready_work_rx.try_recv(),
+            Ok(ReadyWork::Unaggregate { .. })
+        ));
+
+        // Once the window has passed, attestations for the root are queued as normal.
+        advance_time(
+            &slot_clock,
+            RECENTLY_IMPORTED_BLOCK_WINDOW + Duration::from_millis(1),
+        )
+        .await;
+        work_reprocessing_tx.try_send(queued_unaggregate()).unwrap();
+        tokio::task::yield_now().await;
+        assert!(ready_work_rx.try_recv().is_err());
+    }
+
     /// Advances slot clock and test clock time by the same duration.
     async fn advance_time(slot_clock: &ManualSlotClock, duration: Duration) {
         slot_clock.advance_time(duration);
@@ -1,12 +1,16 @@
+use crate::address_change_broadcast::broadcast_address_changes_at_capella;
+use crate::clock_drift::monitor_clock_drift;
 use crate::compute_light_client_updates::{
     compute_light_client_updates, LIGHT_CLIENT_SERVER_CHANNEL_CAPACITY,
 };
 use crate::config::{ClientGenesis, Config as ClientConfig};
 use crate::notifier::spawn_notifier;
+use crate::validator_monitor_alerts::send_validator_monitor_alerts;
 use crate::Client;
 use beacon_chain::attestation_simulator::start_attestation_simulator_service;
 use beacon_chain::data_availability_checker::start_availability_cache_maintenance_service;
 use beacon_chain::graffiti_calculator::start_engine_version_cache_refresh_service;
+use beacon_chain::non_finality::start_non_finality_service;
 use beacon_chain::otb_verification_service::start_otb_verification_service;
 use beacon_chain::proposer_prep_service::start_proposer_prep_service;
 use beacon_chain::schema_change::migrate_schema;
@@ -38,6 +42,8 @@ use slasher::Slasher;
 use slasher_service::SlasherService;
 use slog::{debug, info, warn, Logger};
 use ssz::Decode;
+use state_processing::common::weak_subjectivity::compute_weak_subjectivity_period;
+use std::cmp::Ordering;
 use std::net::TcpListener;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -46,8 +52,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use timer::spawn_timer;
 use tokio::sync::oneshot;
 use types::{
-    test_utils::generate_deterministic_keypairs, BeaconState, BlobSidecarList, ChainSpec, EthSpec,
-    ExecutionBlockHash, Hash256, SignedBeaconBlock,
+    test_utils::generate_deterministic_keypairs, BeaconState, BlobSidecarList, ChainSpec, Epoch,
+    EthSpec, ExecutionBlockHash, Hash256, SignedBeaconBlock,
 };
 
 /// Interval between polling the eth1 node for genesis information.
@@ -209,6 +215,18 @@ where
             .execution_layer(execution_layer)
             .validator_monitor_config(config.validator_monitor.clone());
 
+        // KZG is required before genesis so that checkpoint blobs can be verified.
+        let builder = if let Some(trusted_setup) = config.trusted_setup.clone() {
+            let kzg = trusted_setup
+                .try_into()
+                .map(Arc::new)
+                .map(Some)
+                .map_err(|e| format!("Failed to load trusted setup: {:?}", e))?;
+            builder.kzg(kzg)
+        } else {
+            builder
+        };
+
         let builder = if let Some(slasher) = self.slasher.clone() {
             builder.slasher(slasher)
         } else {
@@ -360,6 +378,12 @@ where
                 } else {
                     None
                 };
+                check_weak_subjectivity_period(
+                    &anchor_state,
+                    config.ignore_weak_subjectivity,
+                    &spec,
+                    context.log(),
+                )?;
                 let genesis_state = genesis_state(&runtime_context, &config, log).await?;
 
                 builder
@@ -448,6 +472,33 @@ where
 
                 debug!(context.log(), "Downloaded finalized state"; "slot" => ?state.slot());
 
+                check_weak_subjectivity_period(
+                    &state,
+                    config.ignore_weak_subjectivity,
+                    &spec,
+                    context.log(),
+                )?;
+
+                // The snapshot was downloaded first, so it can't hold more deposits than the
+                // state. A snapshot with the same deposits must have the same deposit root.
+                let deposit_snapshot = deposit_snapshot.filter(|snapshot| {
+                    let eth1_data = state.eth1_data();
+                    let consistent = match snapshot.deposit_count.cmp(&eth1_data.deposit_count) {
+                        Ordering::Less => true,
+                        Ordering::Equal => snapshot.matches_eth1_data(eth1_data),
+                        Ordering::Greater => false,
+                    };
+                    if !consistent {
+                        warn!(
+                            context.log(),
+                            "Remote BN sent deposit snapshot inconsistent with finalized state";
+                            "snapshot_deposit_count" => snapshot.deposit_count,
+                            "state_deposit_count" => eth1_data.deposit_count,
+                        );
+                    }
+                    consistent
+                });
+
                 let finalized_block_slot = state.latest_block_header().slot;
 
                 debug!(context.log(), "Downloading finalized block"; "block_slot" => ?finalized_block_slot);
@@ -566,6 +617,7 @@ where
                         eth1_service: Some(genesis_service.eth1_service.clone()),
                         log: context.log().clone(),
                         sse_logging_components: runtime_context.sse_logging_components.clone(),
+                        log_levels: runtime_context.log_levels.clone(),
                     });
 
                     // Discard the error from the oneshot.
@@ -622,17 +674,6 @@ where
             ClientGenesis::FromStore => builder.resume_from_db().map(|v| (v, None))?,
         };
 
-        let beacon_chain_builder = if let Some(trusted_setup) = config.trusted_setup {
-            let kzg = trusted_setup
-                .try_into()
-                .map(Arc::new)
-                .map(Some)
-                .map_err(|e| format!("Failed to load trusted setup: {:?}", e))?;
-            beacon_chain_builder.kzg(kzg)
-        } else {
-            beacon_chain_builder
-        };
-
         if config.sync_eth1_chain {
             self.eth1_service = eth1_service_option;
         }
@@ -813,6 +854,7 @@ where
                     beacon_processor_channels.work_reprocessing_tx.clone(),
                 ),
                 sse_logging_components: runtime_context.sse_logging_components.clone(),
+                log_levels: runtime_context.log_levels.clone(),
                 log: log.clone(),
             });
 
@@ -858,6 +900,18 @@ where
                 .spawn_without_exit(server, "http-metrics");
 
             Some(listen_addr)
+        } else if self.http_metrics_config.network.is_some() {
+            // This beacon node shares the process with the beacon node for another network, whose
+            // metrics server serves the metrics of both.
+            http_metrics::register_additional_network(Arc::new(http_metrics::Context {
+                config: self.http_metrics_config.clone(),
+                chain: self.beacon_chain.clone(),
+                db_path: self.db_path.clone(),
+                freezer_db_path: self.freezer_db_path.clone(),
+                gossipsub_registry: None,
+                log: log.clone(),
+            }));
+            None
         } else {
             debug!(log, "Metrics server is disabled");
             None
@@ -970,6 +1024,47 @@ where
                 );
             }
 
+            // Spawn a service to publish BLS to execution changes at the Capella fork.
+            if let Some(network_senders) = self.network_senders.clone() {
+                let inner_chain = beacon_chain.clone();
+                let broadcast_context = runtime_context.service_context("addr_bcast".to_string());
+                let log = broadcast_context.log().clone();
+                broadcast_context.executor.spawn(
+                    async move {
+                        broadcast_address_changes_at_capella(
+                            &inner_chain,
+                            network_senders.network_send(),
+                            &log,
+                        )
+                        .await
+                    },
+                    "addr_broadcast",
+                );
+            }
+
+            // Spawn a service to detect drift of the local clock.
+            let clock_drift_context = runtime_context.service_context("clock_drift".to_string());
+            let log = clock_drift_context.log().clone();
+            clock_drift_context.executor.spawn(
+                monitor_clock_drift(beacon_chain.clone(), log),
+                "clock_drift",
+            );
+
+            // Spawn a service to deliver validator monitor alerts to the configured webhook.
+            let alert_webhook = beacon_chain
+                .validator_monitor
+                .read()
+                .alert_webhook()
+                .cloned();
+            if let Some(url) = alert_webhook {
+                let alerts_context = runtime_context.service_context("vm_alerts".to_string());
+                let log = alerts_context.log().clone();
+                alerts_context.executor.spawn(
+                    send_validator_monitor_alerts(beacon_chain.clone(), url, log),
+                    "validator_monitor_alerts",
+                );
+            }
+
             start_proposer_prep_service(runtime_context.executor.clone(), beacon_chain.clone());
             start_otb_verification_service(runtime_context.executor.clone(), beacon_chain.clone());
             start_availability_cache_maintenance_service(
@@ -984,6 +1079,13 @@ where
                 beacon_chain.task_executor.clone(),
                 beacon_chain.clone(),
             );
+            if let Some(db_path) = self.db_path.clone() {
+                start_non_finality_service(
+                    runtime_context.executor.clone(),
+                    beacon_chain.clone(),
+                    db_path,
+                );
+            }
         }
 
         Ok(Client {
@@ -1238,6 +1340,18 @@ async fn genesis_state<E: EthSpec>(
         .eth2_network_config
         .as_ref()
         .ok_or("An eth2_network_config is required to obtain the genesis state")?;
+
+    if !eth2_network_config.genesis_state_is_known() {
+        if let (Some(url), Some(checksum)) = (
+            config.genesis_state_url.as_deref(),
+            config.genesis_state_checksum,
+        ) {
+            return eth2_network_config
+                .download_genesis_state::<E>(url, checksum, config.genesis_state_url_timeout, log)
+                .await;
+        }
+    }
+
     eth2_network_config
         .genesis_state::<E>(
             config.genesis_state_url.as_deref(),
@@ -1247,3 +1361,61 @@ async fn genesis_state<E: EthSpec>(
         .await?
         .ok_or_else(|| "Genesis state is unknown".to_string())
 }
+
+/// Refuse to start from a checkpoint state which is older than its weak subjectivity period,
+/// unless `ignore_weak_subjectivity` is set.
+///
+/// A node syncing from such a state could be fed a long-range attack chain by validators that
+/// have since exited.
+fn check_weak_subjectivity_period<E: EthSpec>(
+    anchor_state: &BeaconState<E>,
+    ignore_weak_subjectivity: bool,
+    spec: &ChainSpec,
+    log: &Logger,
+) -> Result<(), String> {
+    let ws_period = compute_weak_subjectivity_period(anchor_state, spec)
+        .map_err(|e| format!("Unable to compute weak subjectivity period: {e:?}"))?;
+    let anchor_epoch = anchor_state.current_epoch();
+    let safe_until_epoch = anchor_epoch + ws_period;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Unable to read system time: {e:}"))?
+        .as_secs();
+    let current_epoch = Epoch::new(
+        now.saturating_sub(anchor_state.genesis_time())
+            / spec.seconds_per_slot
+            / E::slots_per_epoch(),
+    );
+
+    if current_epoch <= safe_until_epoch {
+        info!(
+            log,
+            "Checkpoint is within weak subjectivity period";
+            "checkpoint_epoch" => anchor_epoch,
+            "weak_subjectivity_period" => ws_period,
+            "safe_until_epoch" => safe_until_epoch,
+            "current_epoch" => current_epoch,
+        );
+        Ok(())
+    } else if ignore_weak_subjectivity {
+        warn!(
+            log,
+            "Checkpoint is outside weak subjectivity period";
+            "checkpoint_epoch" => anchor_epoch,
+            "weak_subjectivity_period" => ws_period,
+            "safe_until_epoch" => safe_until_epoch,
+            "current_epoch" => current_epoch,
+            "info" => "continuing because --ignore-weak-subjectivity is set",
+        );
+        Ok(())
+    } else {
+        Err(format!(
+            "The checkpoint state at epoch {anchor_epoch} is outside its weak subjectivity \
+             period of {ws_period} epochs. It was only safe to sync from until epoch \
+             {safe_until_epoch}, but the current epoch is {current_epoch}. Use a more recent \
+             checkpoint, or use --ignore-weak-subjectivity if the state is known to be \
+             trustworthy."
+        ))
+    }
+}
@@ -0,0 +1,207 @@
+//! Monitors the local slot clock for drift, which would otherwise cause us to produce attestations
+//! and blocks at the wrong time.
+//!
+//! Two sources of information are used:
+//!
+//! - The NTP synchronisation status reported by the operating system (Linux only).
+//! - The times at which blocks were received from peers on gossip, relative to the start of their
+//!   slot (according to our clock).
+//!
+//! Honest proposers publish their blocks shortly after the start of the slot and most blocks
+//! reach us well before the attestation deadline. If the typical block arrives *before* the slot
+//! has started then our clock must be behind, whilst if the typical block arrives after the
+//! attestation deadline then our clock is most likely ahead.
+use crate::metrics;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use slog::{debug, warn, Logger};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use types::EthSpec;
+
+/// The estimated drift beyond which a warning is logged.
+const CLOCK_DRIFT_WARN_THRESHOLD: Duration = Duration::from_millis(500);
+/// The minimum number of gossip blocks required to estimate the clock drift.
+const MIN_BLOCK_SAMPLES: usize = 8;
+
+/// The NTP synchronisation status of the system clock.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NtpStatus {
+    Synchronized {
+        /// The maximum error of the system clock, as estimated by the kernel.
+        max_error: Duration,
+    },
+    Unsynchronized,
+    Unknown,
+}
+
+#[cfg(target_os = "linux")]
+pub fn ntp_status() -> NtpStatus {
+    // Setting `modes` to zero makes `adjtimex` read-only.
+    //
+    // Safety: `timex` is a plain C struct for which all-zeroes is a valid value, and the pointer
+    // passed to `adjtimex` is valid for the duration of the call.
+    let mut timex: libc::timex = unsafe { std::mem::zeroed() };
+    let state = unsafe { libc::adjtimex(&mut timex) };
+
+    if state < 0 {
+        NtpStatus::Unknown
+    } else if state == libc::TIME_ERROR {
+        NtpStatus::Unsynchronized
+    } else {
+        NtpStatus::Synchronized {
+            max_error: Duration::from_micros(timex.maxerror.max(0) as u64),
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn ntp_status() -> NtpStatus {
+    NtpStatus::Unknown
+}
+
+/// Estimates the drift of the local clock in milliseconds from the times at which blocks were
+/// observed, where each time is an offset (in milliseconds) from the start of the block's slot.
+///
+/// A positive value indicates that the local clock is ahead, a negative value that it is behind.
+/// Returns `None` if there are too few samples.
+pub fn estimate_drift_ms(offsets: &mut [i64], attestation_deadline_ms: i64) -> Option<i64> {
+    if offsets.len() < MIN_BLOCK_SAMPLES {
+        return None;
+    }
+
+    offsets.sort_unstable();
+    let median = offsets[offsets.len() / 2];
+
+    // A median in the range `[0, attestation_deadline)` is consistent with an accurate clock, so
+    // only the distance outside of that range is attributed to drift.
+    let drift = if median < 0 {
+        median
+    } else if median > attestation_deadline_ms {
+        median - attestation_deadline_ms
+    } else {
+        0
+    };
+
+    Some(drift)
+}
+
+/// Checks the local clock for drift once per epoch, logging and updating metrics.
+pub async fn monitor_clock_drift<T: BeaconChainTypes>(chain: Arc<BeaconChain<T>>, log: Logger) {
+    let slot_clock = &chain.slot_clock;
+    let threshold_ms = CLOCK_DRIFT_WARN_THRESHOLD.as_millis() as i64;
+
+    loop {
+        match slot_clock.duration_to_next_epoch(T::EthSpec::slots_per_epoch()) {
+            Some(duration) => sleep(duration).await,
+            None => {
+                // We were unable to read the slot clock, wait another slot and then try again.
+                sleep(slot_clock.slot_duration()).await;
+                continue;
+            }
+        }
+
+        let ntp_status = ntp_status();
+        match ntp_status {
+            NtpStatus::Synchronized { max_error } => {
+                metrics::set_gauge(&metrics::CLOCK_NTP_SYNCHRONIZED, 1);
+                metrics::set_gauge(
+                    &metrics::CLOCK_NTP_MAX_ERROR_MS,
+                    max_error.as_millis() as i64,
+                );
+                if max_error > CLOCK_DRIFT_WARN_THRESHOLD {
+                    warn!(
+                        log,
+                        "System clock has a large error";
+                        "info" => "check the NTP configuration of this host",
+                        "max_error_ms" => max_error.as_millis(),
+                    );
+                }
+            }
+            NtpStatus::Unsynchronized => {
+                metrics::set_gauge(&metrics::CLOCK_NTP_SYNCHRONIZED, 0);
+                warn!(
+                    log,
+                    "System clock is not synchronized";
+                    "info" => "an inaccurate clock may cause missed attestations, ensure NTP is running",
+                );
+            }
+            NtpStatus::Unknown => {}
+        }
+
+        let mut offsets = {
+            let block_times_cache = chain.block_times_cache.read();
+            block_times_cache
+                .cache
+                .values()
+                // Only consider blocks which were received from a peer on gossip.
+                .filter(|value| value.peer_info.id.is_some())
+                .filter_map(|value| {
+                    let observed = value.timestamps.observed?;
+                    let slot_start = slot_clock.start_of(value.slot)?;
+                    Some(observed.as_millis() as i64 - slot_start.as_millis() as i64)
+                })
+                .collect::<Vec<_>>()
+        };
+        let attestation_deadline_ms =
+            slot_clock.unagg_attestation_production_delay().as_millis() as i64;
+
+        let Some(drift_ms) = estimate_drift_ms(&mut offsets, attestation_deadline_ms) else {
+            debug!(
+                log,
+                "Insufficient blocks to estimate clock drift";
+                "samples" => offsets.len(),
+            );
+            continue;
+        };
+
+        metrics::set_gauge(&metrics::CLOCK_DRIFT_ESTIMATE_MS, drift_ms);
+
+        if drift_ms.abs() > threshold_ms {
+            metrics::set_gauge(&metrics::CLOCK_DRIFT_EXCEEDS_THRESHOLD, 1);
+            warn!(
+                log,
+                "Local clock appears to have drifted";
+                "info" => "blocks from peers are arriving at unexpected times, this may cause missed attestations",
+                "direction" => if drift_ms > 0 { "ahead" } else { "behind" },
+                "estimated_drift_ms" => drift_ms,
+                "ntp_status" => ?ntp_status,
+                "samples" => offsets.len(),
+            );
+        } else {
+            metrics::set_gauge(&metrics::CLOCK_DRIFT_EXCEEDS_THRESHOLD, 0);
+            debug!(
+                log,
+                "Local clock drift within tolerance";
+                "estimated_drift_ms" => drift_ms,
+                "samples" => offsets.len(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drift_estimate() {
+        let deadline = 4_000;
+
+        // Too few samples.
+        assert_eq!(estimate_drift_ms(&mut [100; 4], deadline), None);
+
+        // Blocks arriving shortly after the start of the slot.
+        let mut offsets = [300, 500, 900, 1_200, 400, 800, 2_500, 600];
+        assert_eq!(estimate_drift_ms(&mut offsets, deadline), Some(0));
+
+        // Blocks arriving before the slot has started.
+        let mut offsets = [-1_500, -1_200, -900, -1_000, -800, 1_000, -1_100, -1_300];
+        assert_eq!(estimate_drift_ms(&mut offsets, deadline), Some(-1_000));
+
+        // Blocks arriving after the attestation deadline.
+        let mut offsets = [5_000, 5_500, 6_000, 5_200, 5_300, 5_100, 5_400, 3_000];
+        assert_eq!(estimate_drift_ms(&mut offsets, deadline), Some(1_300));
+    }
+}
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use std::time::Duration;
+use types::{Hash256, PublicKeyBytes};
 
 /// Default directory name for the freezer database under the top-level data dir.
 const DEFAULT_FREEZER_DB_DIR: &str = "freezer_db";
@@ -84,7 +85,17 @@ pub struct Config {
     pub beacon_processor: BeaconProcessorConfig,
     pub genesis_state_url: Option<String>,
     pub genesis_state_url_timeout: Duration,
+    /// The SHA256 of the genesis state downloaded from `genesis_state_url`, for networks which
+    /// don't include a genesis state.
+    pub genesis_state_checksum: Option<Hash256>,
     pub allow_insecure_genesis_sync: bool,
+    /// Start from a checkpoint state even if it is outside its weak subjectivity period.
+    pub ignore_weak_subjectivity: bool,
+    /// URL of a signed manifest used to refresh the network's boot ENRs and deploy block.
+    pub network_config_update_url: Option<String>,
+    /// Key used to verify the manifest, overriding any key embedded for the network.
+    pub network_config_update_pubkey: Option<PublicKeyBytes>,
+    pub network_config_update_timeout: Duration,
 }
 
 impl Default for Config {
@@ -115,7 +126,13 @@ impl Default for Config {
             genesis_state_url: <_>::default(),
             // This default value should always be overwritten by the CLI default value.
             genesis_state_url_timeout: Duration::from_secs(60),
+            genesis_state_checksum: None,
             allow_insecure_genesis_sync: false,
+            ignore_weak_subjectivity: false,
+            network_config_update_url: None,
+            network_config_update_pubkey: None,
+            // This default value should always be overwritten by the CLI default value.
+            network_config_update_timeout: Duration::from_secs(10),
         }
     }
 }
@@ -1,7 +1,10 @@
+mod address_change_broadcast;
+mod clock_drift;
 mod compute_light_client_updates;
 pub mod config;
 mod metrics;
 mod notifier;
+mod validator_monitor_alerts;
 
 pub mod builder;
 pub mod error;
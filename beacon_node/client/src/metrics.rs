@@ -16,4 +16,25 @@ lazy_static! {
         "notifier_head_slot",
         "The head slot sourced from the beacon chain notifier"
     );
+
+    /*
+     * Clock drift
+     */
+    pub static ref CLOCK_NTP_SYNCHRONIZED: Result<IntGauge> = try_create_int_gauge(
+        "clock_ntp_synchronized",
+        "1 if the system clock is synchronized by NTP, 0 if it is not"
+    );
+    pub static ref CLOCK_NTP_MAX_ERROR_MS: Result<IntGauge> = try_create_int_gauge(
+        "clock_ntp_max_error_milliseconds",
+        "The maximum error of the system clock as estimated by the kernel"
+    );
+    pub static ref CLOCK_DRIFT_ESTIMATE_MS: Result<IntGauge> = try_create_int_gauge(
+        "clock_drift_estimate_milliseconds",
+        "The estimated drift of the local clock based on the arrival times of gossip blocks. \
+        Positive if the clock is ahead, negative if it is behind"
+    );
+    pub static ref CLOCK_DRIFT_EXCEEDS_THRESHOLD: Result<IntGauge> = try_create_int_gauge(
+        "clock_drift_exceeds_threshold",
+        "1 if the estimated drift of the local clock is large enough to cause missed attestations"
+    );
 }
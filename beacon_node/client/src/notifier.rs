@@ -4,8 +4,10 @@ use beacon_chain::{
     capella_readiness::CapellaReadiness,
     deneb_readiness::DenebReadiness,
     electra_readiness::ElectraReadiness,
+    events::EventKind,
     BeaconChain, BeaconChainTypes, ExecutionStatus,
 };
+use eth2::lighthouse::SseGenesisCountdown;
 use lighthouse_network::{types::SyncState, NetworkGlobals};
 use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
@@ -57,12 +59,24 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
                 // If the duration to the next slot is greater than the slot duration, then we are
                 // waiting for genesis.
                 Some(next_slot) if next_slot > slot_duration => {
+                    let connected_peers = network.connected_peers();
                     info!(
                         log,
                         "Waiting for genesis";
-                        "peers" => peer_count_pretty(network.connected_peers()),
+                        "peers" => peer_count_pretty(connected_peers),
                         "wait_time" => estimated_time_pretty(Some(next_slot.as_secs() as f64)),
                     );
+                    if let Some(event_handler) = beacon_chain.event_handler.as_ref() {
+                        if event_handler.has_genesis_countdown_subscribers() {
+                            event_handler.register(EventKind::GenesisCountdown(
+                                SseGenesisCountdown {
+                                    genesis_time: beacon_chain.genesis_time,
+                                    seconds_to_genesis: next_slot.as_secs(),
+                                    connected_peers: connected_peers as u64,
+                                },
+                            ));
+                        }
+                    }
                     eth1_logging(&beacon_chain, &log);
                     bellatrix_readiness_logging(Slot::new(0), &beacon_chain, &log).await;
                     capella_readiness_logging(Slot::new(0), &beacon_chain, &log).await;
@@ -112,6 +126,16 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
                 current_sync_state = sync_state;
             }
 
+            if let Err(e) =
+                beacon_chain.update_chain_health(connected_peer_count, sync_state.is_syncing())
+            {
+                warn!(
+                    log,
+                    "Unable to update chain health";
+                    "error" => ?e
+                );
+            }
+
             let cached_head = beacon_chain.canonical_head.cached_head();
             let head_slot = cached_head.head_slot();
             let head_root = cached_head.head_block_root();
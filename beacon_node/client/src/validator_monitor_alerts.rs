@@ -0,0 +1,65 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use reqwest::Client;
+use sensitive_url::SensitiveUrl;
+use slog::{debug, warn, Logger};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// The timeout applied to each request to the alert webhook.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delivers any alerts raised by the validator monitor to `url`, once per slot.
+///
+/// Alerts are sent as a JSON array in the body of a `POST` request. Alerts which fail to be
+/// delivered are logged and discarded, so that an unreachable webhook can't cause an unbounded
+/// build up of alerts.
+pub async fn send_validator_monitor_alerts<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    url: SensitiveUrl,
+    log: Logger,
+) {
+    let client = Client::new();
+    let slot_clock = &chain.slot_clock;
+
+    loop {
+        match slot_clock.duration_to_next_slot() {
+            Some(duration) => sleep(duration).await,
+            None => {
+                // We were unable to read the slot clock, wait another slot and then try again.
+                sleep(slot_clock.slot_duration()).await;
+                continue;
+            }
+        }
+
+        let alerts = chain.validator_monitor.read().drain_alerts();
+        if alerts.is_empty() {
+            continue;
+        }
+
+        let num_alerts = alerts.len();
+        let result = client
+            .post(url.full.clone())
+            .timeout(WEBHOOK_TIMEOUT)
+            .json(&alerts)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        match result {
+            Ok(_) => debug!(
+                log,
+                "Sent validator monitor alerts";
+                "count" => num_alerts,
+            ),
+            Err(e) => warn!(
+                log,
+                "Failed to send validator monitor alerts";
+                "error" => %e,
+                "url" => %url,
+                "count" => num_alerts,
+            ),
+        }
+    }
+}
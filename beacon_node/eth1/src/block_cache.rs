@@ -93,6 +93,16 @@ impl BlockCache {
         }
     }
 
+    /// Drops all blocks with a block number greater than `block_number`.
+    pub fn prune_after(&mut self, block_number: u64) {
+        let retained = self
+            .blocks
+            .partition_point(|block| block.number <= block_number);
+        for block in self.blocks.drain(retained..) {
+            self.by_hash.remove(&block.hash);
+        }
+    }
+
     /// Returns the range of block numbers stored in the block cache. All blocks in this range can
     /// be accessed.
     fn available_block_numbers(&self) -> Option<RangeInclusive<u64>> {
@@ -43,6 +43,13 @@ pub enum Error {
         currently_finalized: u64,
         deposit_count: u64,
     },
+    /// Deposits can only be truncated to a count between the finalized deposit count and the
+    /// number of deposits in the cache.
+    InvalidTruncateCount {
+        requested_count: u64,
+        finalized_count: u64,
+        deposit_count: u64,
+    },
     /// Error with the merkle tree for deposits.
     DepositTree(merkle_proof::MerkleTreeError),
     /// An unexpected condition was encountered.
@@ -51,24 +58,74 @@ pub enum Error {
     PleaseNotifyTheDevs,
 }
 
-pub type SszDepositCache = SszDepositCacheV13;
+pub type SszDepositCache = SszDepositCacheV22;
 
+/// The on-disk representation of the `DepositCache`.
+///
+/// From V22 the `leaves` and `deposit_roots` are no longer stored, since they can be recomputed
+/// from the `logs` and the `deposit_tree_snapshot`.
 #[superstruct(
-    variants(V13),
+    variants(V13, V22),
     variant_attributes(derive(Encode, Decode, Clone)),
     no_enum
 )]
 pub struct SszDepositCache {
     pub logs: Vec<DepositLog>,
+    #[superstruct(only(V13))]
     pub leaves: Vec<Hash256>,
     pub deposit_contract_deploy_block: u64,
     pub finalized_deposit_count: u64,
     pub finalized_block_height: u64,
     pub deposit_tree_snapshot: Option<DepositTreeSnapshot>,
+    #[superstruct(only(V13))]
     pub deposit_roots: Vec<Hash256>,
 }
 
-impl SszDepositCache {
+impl SszDepositCacheV22 {
+    pub fn from_deposit_cache(cache: &DepositCache) -> Self {
+        Self {
+            logs: cache.logs.clone(),
+            deposit_contract_deploy_block: cache.deposit_contract_deploy_block,
+            finalized_deposit_count: cache.finalized_deposit_count,
+            finalized_block_height: cache.finalized_block_height,
+            deposit_tree_snapshot: cache.deposit_tree.get_snapshot(),
+        }
+    }
+
+    pub fn to_deposit_cache(&self) -> Result<DepositCache, String> {
+        let mut deposit_tree = match &self.deposit_tree_snapshot {
+            Some(snapshot) => DepositDataTree::from_snapshot(snapshot, DEPOSIT_TREE_DEPTH)
+                .map_err(|e| format!("Invalid SszDepositCache: {:?}", e))?,
+            // The tree was never finalized.
+            None => DepositDataTree::create(&[], 0, DEPOSIT_TREE_DEPTH),
+        };
+
+        // Replay the logs to recompute the leaves and the deposit root after each deposit.
+        let mut leaves = Vec::with_capacity(self.logs.len());
+        let mut deposit_roots = Vec::with_capacity(self.logs.len() + 1);
+        deposit_roots.push(deposit_tree.root());
+        for log in &self.logs {
+            let leaf = log.deposit_data.tree_hash_root();
+            deposit_tree
+                .push_leaf(leaf)
+                .map_err(|e| format!("Invalid SszDepositCache: unable to push leaf: {:?}", e))?;
+            leaves.push(leaf);
+            deposit_roots.push(deposit_tree.root());
+        }
+
+        Ok(DepositCache {
+            logs: self.logs.clone(),
+            leaves,
+            deposit_contract_deploy_block: self.deposit_contract_deploy_block,
+            finalized_deposit_count: self.finalized_deposit_count,
+            finalized_block_height: self.finalized_block_height,
+            deposit_tree,
+            deposit_roots,
+        })
+    }
+}
+
+impl SszDepositCacheV13 {
     pub fn from_deposit_cache(cache: &DepositCache) -> Self {
         Self {
             logs: cache.logs.clone(),
@@ -276,6 +333,49 @@ impl DepositCache {
         }
     }
 
+    /// Returns `Some(true)` if the deposit root after `deposit_count` deposits is `deposit_root`,
+    /// `Some(false)` if it differs, or `None` if the cache doesn't hold that deposit root.
+    pub fn check_deposit_root(&self, deposit_count: u64, deposit_root: Hash256) -> Option<bool> {
+        self.get_root(deposit_count as usize)
+            .map(|root| *root == deposit_root)
+    }
+
+    /// Drops all deposits after the first `deposit_count`, returning the number of deposits
+    /// dropped.
+    ///
+    /// Finalized deposits can't be dropped.
+    pub fn truncate(&mut self, deposit_count: u64) -> Result<usize, Error> {
+        if deposit_count < self.finalized_deposit_count || deposit_count > self.len() as u64 {
+            return Err(Error::InvalidTruncateCount {
+                requested_count: deposit_count,
+                finalized_count: self.finalized_deposit_count,
+                deposit_count: self.len() as u64,
+            });
+        }
+
+        let retained = (deposit_count - self.finalized_deposit_count) as usize;
+        let dropped = self.logs.len() - retained;
+
+        // Rebuild the tree first because it's fallible.
+        self.deposit_tree = match self.deposit_tree.get_snapshot() {
+            Some(snapshot) => {
+                let mut tree = DepositDataTree::from_snapshot(&snapshot, DEPOSIT_TREE_DEPTH)
+                    .map_err(Error::DepositTree)?;
+                for leaf in &self.leaves[..retained] {
+                    tree.push_leaf(*leaf).map_err(Error::DepositTree)?;
+                }
+                tree
+            }
+            None => DepositDataTree::create(&self.leaves[..retained], retained, DEPOSIT_TREE_DEPTH),
+        };
+        self.logs.truncate(retained);
+        self.leaves.truncate(retained);
+        // `deposit_roots` also includes the root prior to the first unfinalized deposit.
+        self.deposit_roots.truncate(retained + 1);
+
+        Ok(dropped)
+    }
+
     /// Returns the deposit tree snapshot (if tree is finalized)
     pub fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
         self.deposit_tree.get_snapshot()
@@ -1021,6 +1121,66 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn check_deposit_root() {
+        let n = 16;
+        let mut deposit_cache = get_cache_with_deposits(n);
+        let root = *deposit_cache.get_root(8).expect("should have root");
+
+        assert_eq!(deposit_cache.check_deposit_root(8, root), Some(true));
+        assert_eq!(deposit_cache.check_deposit_root(9, root), Some(false));
+        assert_eq!(deposit_cache.check_deposit_root(n + 1, root), None);
+
+        let block3 = fake_eth1_block(&deposit_cache, 3).expect("should create fake eth1 block");
+        deposit_cache.finalize(block3).expect("should finalize");
+        assert_eq!(deposit_cache.check_deposit_root(8, root), Some(true));
+        assert_eq!(deposit_cache.check_deposit_root(2, root), None);
+    }
+
+    #[test]
+    fn truncate() {
+        let n = 16;
+        let mut deposit_cache = get_cache_with_deposits(n);
+        let block3 = fake_eth1_block(&deposit_cache, 3).expect("should create fake eth1 block");
+        deposit_cache.finalize(block3).expect("should finalize");
+
+        // Deposits can't be truncated into the finalized range or beyond the cache.
+        assert!(deposit_cache.truncate(3).is_err());
+        assert!(deposit_cache.truncate(n + 1).is_err());
+
+        let mut expected = get_cache_with_deposits(8);
+        let block3 = fake_eth1_block(&expected, 3).expect("should create fake eth1 block");
+        expected.finalize(block3).expect("should finalize");
+
+        assert_eq!(deposit_cache.truncate(8), Ok(8));
+        verify_equality(&expected, &deposit_cache);
+
+        // The dropped deposits can be inserted again.
+        let mut repaired = get_cache_with_deposits(n);
+        let block3 = fake_eth1_block(&repaired, 3).expect("should create fake eth1 block");
+        repaired.finalize(block3).expect("should finalize");
+        for i in 8..n {
+            let log = repaired
+                .get_log(i as usize)
+                .expect("should have log")
+                .clone();
+            assert_eq!(
+                deposit_cache.insert_log(log),
+                Ok(DepositCacheInsertOutcome::Inserted)
+            );
+        }
+        verify_equality(&repaired, &deposit_cache);
+
+        // Truncating to the finalized deposit count drops all unfinalized deposits.
+        assert_eq!(deposit_cache.truncate(4), Ok(12));
+        assert_eq!(deposit_cache.len(), 4);
+        assert_eq!(
+            deposit_cache.get_root(4),
+            repaired.get_root(4),
+            "should retain the finalized deposit root"
+        );
+    }
+
     fn verify_equality(original: &DepositCache, copy: &DepositCache) {
         // verify each field individually so that if one field should
         // fail to recover, this test will point right to it
@@ -1061,6 +1221,27 @@ pub mod tests {
         verify_equality(&deposit_cache, &recovered_cache);
     }
 
+    #[test]
+    fn ssz_v13_compatibility() {
+        use ssz::Encode;
+        let mut deposit_cache = get_cache_with_deposits(512);
+        let block383 = fake_eth1_block(&deposit_cache, 383).expect("should create fake eth1 block");
+        deposit_cache.finalize(block383).expect("should finalize");
+
+        let v13 = SszDepositCacheV13::from_deposit_cache(&deposit_cache);
+        let v22 = SszDepositCacheV22::from_deposit_cache(&deposit_cache);
+        assert!(v22.as_ssz_bytes().len() < v13.as_ssz_bytes().len());
+
+        verify_equality(
+            &deposit_cache,
+            &v13.to_deposit_cache().expect("should recover cache"),
+        );
+        verify_equality(
+            &deposit_cache,
+            &v22.to_deposit_cache().expect("should recover cache"),
+        );
+    }
+
     #[test]
     fn ssz_encode_decode_with_finalization() {
         let mut deposit_cache = get_cache_with_deposits(512);
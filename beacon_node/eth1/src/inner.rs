@@ -2,7 +2,7 @@ use crate::service::endpoint_from_config;
 use crate::Config;
 use crate::{
     block_cache::{BlockCache, Eth1Block},
-    deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV13},
+    deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV13, SszDepositCacheV22},
 };
 use execution_layer::HttpJsonRpc;
 use parking_lot::RwLock;
@@ -43,12 +43,54 @@ impl DepositUpdater {
     }
 }
 
+/// Tracks consecutive repairs of the deposit cache, so that a persistent mismatch with the
+/// finalized chain is retried with an increasing delay rather than on every update.
+#[derive(Debug, Default, PartialEq)]
+pub struct DepositCacheRepairs {
+    /// Number of repairs since the deposit cache was last verified.
+    pub consecutive: u32,
+    /// Number of updates to wait before the next attempt to finalize the deposit cache.
+    pub skip_updates: u64,
+}
+
+impl DepositCacheRepairs {
+    /// Records a repair, returning the number of updates to wait before retrying, or `None` once
+    /// `max_repairs` consecutive repairs have been made.
+    pub fn record_repair(&mut self, max_repairs: u32, max_skip_updates: u64) -> Option<u64> {
+        self.consecutive = self.consecutive.saturating_add(1);
+        if self.consecutive > max_repairs {
+            self.skip_updates = 0;
+            return None;
+        }
+        self.skip_updates = 1u64
+            .checked_shl(self.consecutive - 1)
+            .map_or(max_skip_updates, |skip| skip.min(max_skip_updates));
+        Some(self.skip_updates)
+    }
+
+    /// Returns `true` and counts down if the next attempt should wait for a later update.
+    pub fn should_wait(&mut self) -> bool {
+        if self.skip_updates > 0 {
+            self.skip_updates -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Resets the backoff once the deposit cache matches the finalized chain.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
 pub struct Inner {
     pub block_cache: RwLock<BlockCache>,
     pub deposit_cache: RwLock<DepositUpdater>,
     pub endpoint: HttpJsonRpc,
     // this gets set to Some(Eth1Data) when the deposit finalization conditions are met
     pub to_finalize: RwLock<Option<Eth1Data>>,
+    pub deposit_cache_repairs: RwLock<DepositCacheRepairs>,
     pub config: RwLock<Config>,
     pub remote_head_block: RwLock<Option<Eth1Block>>,
     pub spec: ChainSpec,
@@ -71,12 +113,16 @@ impl Inner {
     }
 
     /// Recover `Inner` given byte representation of eth1 deposit and block caches.
+    ///
+    /// The block cache is pruned according to `config`, since it may have been persisted with a
+    /// different configuration.
     pub fn from_bytes(bytes: &[u8], config: Config, spec: ChainSpec) -> Result<Self, String> {
         SszEth1Cache::from_ssz_bytes(bytes)
             .map_err(|e| format!("Ssz decoding error: {:?}", e))?
             .to_inner(config, spec)
             .map(|inner| {
                 inner.block_cache.write().rebuild_by_hash_map();
+                inner.prune_blocks();
                 inner
             })
     }
@@ -87,16 +133,19 @@ impl Inner {
     }
 }
 
-pub type SszEth1Cache = SszEth1CacheV13;
+pub type SszEth1Cache = SszEth1CacheV22;
 
 #[superstruct(
-    variants(V13),
+    variants(V13, V22),
     variant_attributes(derive(Encode, Decode, Clone)),
     no_enum
 )]
 pub struct SszEth1Cache {
     pub block_cache: BlockCache,
-    pub deposit_cache: SszDepositCacheV13,
+    #[superstruct(only(V13))]
+    pub deposit_cache_v13: SszDepositCacheV13,
+    #[superstruct(only(V22))]
+    pub deposit_cache: SszDepositCacheV22,
     #[ssz(with = "four_byte_option_u64")]
     pub last_processed_block: Option<u64>,
 }
@@ -122,6 +171,7 @@ impl SszEth1Cache {
             endpoint: endpoint_from_config(&config)
                 .map_err(|e| format!("Failed to create endpoint: {:?}", e))?,
             to_finalize: RwLock::new(None),
+            deposit_cache_repairs: <_>::default(),
             // Set the remote head_block zero when creating a new instance. We only care about
             // present and future eth1 nodes.
             remote_head_block: RwLock::new(None),
@@ -129,4 +179,26 @@ impl SszEth1Cache {
             spec,
         })
     }
+
+    /// Convert to the V13 format, which stores the deposit leaves and roots.
+    pub fn downgrade_to_v13(&self) -> Result<SszEth1CacheV13, String> {
+        let deposit_cache = self.deposit_cache.to_deposit_cache()?;
+        Ok(SszEth1CacheV13 {
+            block_cache: self.block_cache.clone(),
+            deposit_cache_v13: SszDepositCacheV13::from_deposit_cache(&deposit_cache),
+            last_processed_block: self.last_processed_block,
+        })
+    }
+}
+
+impl SszEth1CacheV13 {
+    /// Convert to the V22 format, which omits the deposit leaves and roots.
+    pub fn upgrade_to_v22(&self) -> Result<SszEth1CacheV22, String> {
+        let deposit_cache = self.deposit_cache_v13.to_deposit_cache()?;
+        Ok(SszEth1CacheV22 {
+            block_cache: self.block_cache.clone(),
+            deposit_cache: SszDepositCacheV22::from_deposit_cache(&deposit_cache),
+            last_processed_block: self.last_processed_block,
+        })
+    }
 }
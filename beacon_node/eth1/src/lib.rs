@@ -5,10 +5,10 @@ mod metrics;
 mod service;
 
 pub use block_cache::{BlockCache, Eth1Block};
-pub use deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV13};
+pub use deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV13, SszDepositCacheV22};
 pub use execution_layer::http::deposit_log::DepositLog;
-pub use inner::{SszEth1Cache, SszEth1CacheV13};
+pub use inner::{SszEth1Cache, SszEth1CacheV13, SszEth1CacheV22};
 pub use service::{
-    BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, Error, Eth1Endpoint, Service,
-    DEFAULT_CHAIN_ID,
+    BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, DepositCacheVerification, Error,
+    Eth1Endpoint, Service, DEFAULT_CHAIN_ID,
 };
@@ -18,6 +18,10 @@ lazy_static! {
         try_create_int_gauge("eth1_deposit_cache_len", "Number of deposits in the eth1 cache");
     pub static ref HIGHEST_PROCESSED_DEPOSIT_BLOCK: Result<IntGauge> =
         try_create_int_gauge("eth1_highest_processed_deposit_block", "Number of the last block checked for deposits");
+    pub static ref DEPOSIT_CACHE_REPAIRS: Result<IntCounter> = try_create_int_counter(
+        "eth1_deposit_cache_repairs_total",
+        "Count of times the deposit cache differed from the finalized chain and was repaired"
+    );
 
     /*
      * Eth1 rpc connection
@@ -47,6 +47,13 @@ const CATCHUP_MIN_FOLLOW_DISTANCE: u64 = 64;
 /// distance would imply, we store `CACHE_FACTOR` more blocks in our cache.
 const CACHE_FACTOR: u64 = 2;
 
+/// The maximum number of consecutive deposit cache repairs which are retried for a finalized
+/// checkpoint before waiting for the next one.
+const MAX_DEPOSIT_CACHE_REPAIRS: u32 = 8;
+
+/// The maximum number of updates to wait between deposit cache repairs.
+const MAX_DEPOSIT_CACHE_REPAIR_BACKOFF_UPDATES: u64 = 32;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum EndpointError {
     RequestFailed(String),
@@ -221,6 +228,11 @@ pub enum Error {
     Internal(String),
     /// Error finalizing deposit
     FailedToFinalizeDeposit(String),
+    /// The finalized deposits in the cache differ from the chain, which can't be repaired without
+    /// resyncing the deposit cache.
+    FinalizedDepositRootMismatch { deposit_count: u64 },
+    /// There was an error dropping the deposits which differ from the chain.
+    FailedToRepairDeposits(DepositCacheError),
     /// There was a problem Initializing from deposit snapshot
     FailedToInitializeFromSnapshot(String),
 }
@@ -238,6 +250,18 @@ pub struct DepositCacheUpdateOutcome {
     pub logs_imported: usize,
 }
 
+/// The result of checking the deposit cache against the `Eth1Data` of a finalized state.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DepositCacheVerification {
+    /// The deposit root in the cache matches the chain.
+    Verified,
+    /// The cache doesn't hold the deposit root for the deposit count, e.g. because it hasn't yet
+    /// downloaded the deposits.
+    Unverifiable,
+    /// The deposit root differed and the unfinalized deposits were dropped to be downloaded again.
+    Repaired { dropped_deposits: usize },
+}
+
 /// Supports either one authenticated jwt JSON-RPC endpoint **or**
 /// multiple non-authenticated endpoints with fallback.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -406,6 +430,7 @@ impl Service {
                 )),
                 endpoint: endpoint_from_config(&config)?,
                 to_finalize: RwLock::new(None),
+                deposit_cache_repairs: <_>::default(),
                 remote_head_block: RwLock::new(None),
                 config: RwLock::new(config),
                 spec,
@@ -436,6 +461,7 @@ impl Service {
                 endpoint: endpoint_from_config(&config)
                     .map_err(Error::FailedToInitializeFromSnapshot)?,
                 to_finalize: RwLock::new(None),
+                deposit_cache_repairs: <_>::default(),
                 remote_head_block: RwLock::new(None),
                 config: RwLock::new(config),
                 spec,
@@ -735,8 +761,61 @@ impl Service {
                 "deposits" => format!("{:?}", deposit),
             ),
         };
+        // Wait for the backoff after a repair to pass before trying to finalize again.
+        if self.inner.to_finalize.read().is_some()
+            && self.inner.deposit_cache_repairs.write().should_wait()
+        {
+            return Ok(());
+        }
+
         let optional_eth1data = self.inner.to_finalize.write().take();
         if let Some(eth1data_to_finalize) = optional_eth1data {
+            match self.verify_deposit_cache(&eth1data_to_finalize) {
+                Ok(DepositCacheVerification::Repaired { dropped_deposits }) => {
+                    let retry_after_updates =
+                        self.inner.deposit_cache_repairs.write().record_repair(
+                            MAX_DEPOSIT_CACHE_REPAIRS,
+                            MAX_DEPOSIT_CACHE_REPAIR_BACKOFF_UPDATES,
+                        );
+                    if let Some(retry_after_updates) = retry_after_updates {
+                        warn!(
+                            self.log,
+                            "Deposit cache differs from finalized chain";
+                            "info" => "re-downloading deposits after the last finalized deposit",
+                            "dropped_deposits" => dropped_deposits,
+                            "deposit_count" => eth1data_to_finalize.deposit_count,
+                            "retry_after_updates" => retry_after_updates,
+                        );
+                        // Finalize once the deposits have been downloaded again, unless a newer
+                        // checkpoint has been finalized in the meantime.
+                        self.inner
+                            .to_finalize
+                            .write()
+                            .get_or_insert(eth1data_to_finalize);
+                    } else {
+                        error!(
+                            self.log,
+                            "Deposit cache repeatedly differs from finalized chain";
+                            "info" => "check that the execution node is synced and on the correct \
+                                       network, waiting for the next finalized checkpoint",
+                            "dropped_deposits" => dropped_deposits,
+                            "deposit_count" => eth1data_to_finalize.deposit_count,
+                            "max_repairs" => MAX_DEPOSIT_CACHE_REPAIRS,
+                        );
+                    }
+                    return Ok(());
+                }
+                Ok(DepositCacheVerification::Verified) => {
+                    self.inner.deposit_cache_repairs.write().reset();
+                }
+                Ok(DepositCacheVerification::Unverifiable) => (),
+                Err(e) => error!(
+                    self.log,
+                    "Failed to verify deposit cache";
+                    "error" => ?e,
+                ),
+            }
+
             let already_finalized = self
                 .inner
                 .deposit_cache
@@ -827,6 +906,96 @@ impl Service {
         self.inner.deposit_cache.read().cache.get_deposit_snapshot()
     }
 
+    /// Replaces the deposit cache with `deposit_snapshot` if the snapshot holds more deposits
+    /// than the cache, so that only the deposit logs after the snapshot need to be downloaded.
+    ///
+    /// The snapshot must already have been verified against the chain.
+    ///
+    /// Returns `Ok(false)` if the cache already holds at least as many deposits.
+    pub fn load_deposit_snapshot(
+        &self,
+        deposit_snapshot: &DepositTreeSnapshot,
+    ) -> Result<bool, Error> {
+        let deploy_block = self.config().deposit_contract_deploy_block;
+        let mut deposit_cache = self.inner.deposit_cache.write();
+        if deposit_snapshot.deposit_count <= deposit_cache.cache.len() as u64 {
+            return Ok(false);
+        }
+
+        *deposit_cache = DepositUpdater::from_snapshot(deploy_block, deposit_snapshot)
+            .map_err(Error::FailedToInitializeFromSnapshot)?;
+        metrics::set_gauge(
+            &metrics::DEPOSIT_CACHE_LEN,
+            deposit_cache.cache.len() as i64,
+        );
+        drop(deposit_cache);
+
+        // Cached blocks before the snapshot are unaffected, but their deposit roots and counts
+        // were computed without the snapshot's deposits.
+        let mut block_cache = self.inner.block_cache.write();
+        *block_cache = BlockCache::default();
+        metrics::set_gauge(&metrics::BLOCK_CACHE_LEN, 0);
+
+        Ok(true)
+    }
+
+    /// Checks the deposit cache against the `deposit_root` and `deposit_count` of `eth1_data`,
+    /// which must be from a finalized state.
+    ///
+    /// If the deposit root differs, the unfinalized deposits are dropped along with the cached
+    /// blocks whose deposit roots were computed from them, so that they're downloaded again from
+    /// the execution node by the next update. Deposits are only finalized at block boundaries, so
+    /// every log after the last finalized deposit is downloaded again, starting no earlier than
+    /// the deposit contract deploy block.
+    pub fn verify_deposit_cache(
+        &self,
+        eth1_data: &Eth1Data,
+    ) -> Result<DepositCacheVerification, Error> {
+        let deploy_block = self.config().deposit_contract_deploy_block;
+        let mut deposit_cache = self.inner.deposit_cache.write();
+        match deposit_cache
+            .cache
+            .check_deposit_root(eth1_data.deposit_count, eth1_data.deposit_root)
+        {
+            Some(true) => return Ok(DepositCacheVerification::Verified),
+            None => return Ok(DepositCacheVerification::Unverifiable),
+            Some(false) => (),
+        }
+
+        let finalized_deposit_count = deposit_cache.cache.finalized_deposit_count();
+        if eth1_data.deposit_count <= finalized_deposit_count {
+            return Err(Error::FinalizedDepositRootMismatch {
+                deposit_count: eth1_data.deposit_count,
+            });
+        }
+
+        let dropped_deposits = deposit_cache
+            .cache
+            .truncate(finalized_deposit_count)
+            .map_err(Error::FailedToRepairDeposits)?;
+        // Caches without finalized deposits may report a height of zero, which would otherwise
+        // restart the crawl from the first block rather than the deploy block.
+        let finalized_block_height = std::cmp::max(
+            deposit_cache.cache.latest_block_number(),
+            deploy_block.saturating_sub(1),
+        );
+        deposit_cache.last_processed_block = Some(finalized_block_height);
+        metrics::set_gauge(
+            &metrics::DEPOSIT_CACHE_LEN,
+            deposit_cache.cache.len() as i64,
+        );
+        drop(deposit_cache);
+
+        let mut block_cache = self.inner.block_cache.write();
+        block_cache.prune_after(finalized_block_height);
+        metrics::set_gauge(&metrics::BLOCK_CACHE_LEN, block_cache.len() as i64);
+        drop(block_cache);
+
+        metrics::inc_counter(&metrics::DEPOSIT_CACHE_REPAIRS);
+
+        Ok(DepositCacheVerification::Repaired { dropped_deposits })
+    }
+
     /// Contacts the remote eth1 node and attempts to import deposit logs up to the configured
     /// follow-distance block.
     ///
@@ -1241,6 +1410,7 @@ async fn download_eth1_block(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inner::DepositCacheRepairs;
     use types::MainnetEthSpec;
 
     #[test]
@@ -1275,4 +1445,25 @@ mod tests {
 
         assert!(len > minimum_len as usize);
     }
+
+    #[test]
+    fn deposit_cache_repairs_back_off() {
+        let mut repairs = DepositCacheRepairs::default();
+        assert!(!repairs.should_wait());
+
+        assert_eq!(repairs.record_repair(4, 4), Some(1));
+        assert!(repairs.should_wait());
+        assert!(!repairs.should_wait());
+
+        assert_eq!(repairs.record_repair(4, 4), Some(2));
+        assert_eq!(repairs.record_repair(4, 4), Some(4));
+        // The backoff is capped.
+        assert_eq!(repairs.record_repair(4, 4), Some(4));
+        // The number of repairs is bounded.
+        assert_eq!(repairs.record_repair(4, 4), None);
+        assert!(!repairs.should_wait());
+
+        repairs.reset();
+        assert_eq!(repairs.record_repair(4, 4), Some(1));
+    }
 }
@@ -1,6 +1,6 @@
 #![cfg(test)]
 use environment::{Environment, EnvironmentBuilder};
-use eth1::{Config, Eth1Endpoint, Service};
+use eth1::{Config, DepositCacheVerification, Eth1Endpoint, Service};
 use eth1::{DepositCache, DEFAULT_CHAIN_ID};
 use eth1_test_rig::{AnvilEth1Instance, Http, Middleware, Provider};
 use execution_layer::http::{deposit_methods::*, HttpJsonRpc, Log};
@@ -11,7 +11,9 @@ use sloggers::{null::NullLoggerBuilder, Build};
 use std::ops::Range;
 use std::time::Duration;
 use tree_hash::TreeHash;
-use types::{DepositData, EthSpec, Hash256, Keypair, MainnetEthSpec, MinimalEthSpec, Signature};
+use types::{
+    DepositData, Eth1Data, EthSpec, Hash256, Keypair, MainnetEthSpec, MinimalEthSpec, Signature,
+};
 
 const DEPOSIT_CONTRACT_TREE_DEPTH: usize = 32;
 
@@ -474,6 +476,103 @@ mod deposit_tree {
         .await;
     }
 
+    #[tokio::test]
+    async fn verify_and_repair() {
+        async {
+            let log = null_logger();
+
+            let n = 8;
+
+            let eth1 = new_anvil_instance()
+                .await
+                .expect("should start eth1 environment");
+            let deposit_contract = &eth1.deposit_contract;
+            let anvil_client = eth1.json_rpc_client();
+
+            let start_block = get_block_number(&anvil_client).await;
+
+            let service = Service::new(
+                Config {
+                    endpoint: Eth1Endpoint::NoAuth(
+                        SensitiveUrl::parse(eth1.endpoint().as_str()).unwrap(),
+                    ),
+                    deposit_contract_address: deposit_contract.address(),
+                    deposit_contract_deploy_block: start_block,
+                    follow_distance: 0,
+                    ..Config::default()
+                },
+                log,
+                MainnetEthSpec::default_spec(),
+            )
+            .unwrap();
+
+            for _ in 0..n {
+                deposit_contract
+                    .deposit(random_deposit_data())
+                    .await
+                    .expect("should perform a deposit");
+            }
+
+            service
+                .update_deposit_cache(None)
+                .await
+                .expect("should perform update");
+
+            let (deposit_root, _) = service
+                .deposits()
+                .read()
+                .cache
+                .get_deposits(0, n, n)
+                .expect("should get deposits");
+            let eth1_data = Eth1Data {
+                deposit_root,
+                deposit_count: n,
+                block_hash: Hash256::zero(),
+            };
+
+            assert_eq!(
+                service.verify_deposit_cache(&eth1_data),
+                Ok(DepositCacheVerification::Verified)
+            );
+            assert_eq!(
+                service.verify_deposit_cache(&Eth1Data {
+                    deposit_count: n + 1,
+                    ..eth1_data.clone()
+                }),
+                Ok(DepositCacheVerification::Unverifiable)
+            );
+
+            // A differing deposit root drops the unfinalized deposits.
+            assert_eq!(
+                service.verify_deposit_cache(&Eth1Data {
+                    deposit_root: Hash256::repeat_byte(42),
+                    ..eth1_data.clone()
+                }),
+                Ok(DepositCacheVerification::Repaired {
+                    dropped_deposits: n as usize
+                })
+            );
+            assert_eq!(service.deposit_cache_len(), 0);
+            // Without finalized deposits, the crawl restarts from the deploy block.
+            assert_eq!(
+                service.deposits().read().last_processed_block,
+                Some(start_block.saturating_sub(1))
+            );
+
+            // The dropped deposits are downloaded again by the next update.
+            service
+                .update_deposit_cache(None)
+                .await
+                .expect("should perform update");
+            assert_eq!(service.deposit_cache_len(), n as usize);
+            assert_eq!(
+                service.verify_deposit_cache(&eth1_data),
+                Ok(DepositCacheVerification::Verified)
+            );
+        }
+        .await;
+    }
+
     #[tokio::test]
     async fn cache_consistency() {
         async {
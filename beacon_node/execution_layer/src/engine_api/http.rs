@@ -3,6 +3,8 @@
 use super::*;
 use crate::auth::Auth;
 use crate::json_structures::*;
+use crate::metrics;
+use crate::trace::EngineApiTraceSender;
 use lazy_static::lazy_static;
 use lighthouse_version::{COMMIT_PREFIX, VERSION};
 use reqwest::header::CONTENT_TYPE;
@@ -600,6 +602,7 @@ pub struct HttpJsonRpc {
     pub engine_capabilities_cache: Mutex<Option<CachedResponse<EngineCapabilities>>>,
     pub engine_version_cache: Mutex<Option<CachedResponse<Vec<ClientVersionV1>>>>,
     auth: Option<Auth>,
+    tracer: Option<EngineApiTraceSender>,
 }
 
 impl HttpJsonRpc {
@@ -614,6 +617,7 @@ impl HttpJsonRpc {
             engine_capabilities_cache: Mutex::new(None),
             engine_version_cache: Mutex::new(None),
             auth: None,
+            tracer: None,
         })
     }
 
@@ -629,9 +633,16 @@ impl HttpJsonRpc {
             engine_capabilities_cache: Mutex::new(None),
             engine_version_cache: Mutex::new(None),
             auth: Some(auth),
+            tracer: None,
         })
     }
 
+    /// Send all requests and responses to `tracer` to be written to disk.
+    pub fn with_tracer(mut self, tracer: EngineApiTraceSender) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
     pub async fn rpc_request<D: DeserializeOwned>(
         &self,
         method: &str,
@@ -657,7 +668,38 @@ impl HttpJsonRpc {
             request = request.bearer_auth(auth.generate_token()?);
         };
 
-        let body: JsonResponseBody = request.send().await?.error_for_status()?.json().await?;
+        let started = Instant::now();
+        let response: Result<JsonResponseBody, Error> = async {
+            Ok::<_, Error>(
+                request
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<JsonResponseBody>()
+                    .await?,
+            )
+        }
+        .await;
+
+        if let Some(tracer) = &self.tracer {
+            let traced_response = match &response {
+                Ok(response) => serde_json::to_value(response).map_err(|e| format!("{:?}", e)),
+                Err(e) => Err(format!("{:?}", e)),
+            };
+            match serde_json::to_value(&body) {
+                Ok(request) => tracer.send(
+                    self.url.to_string(),
+                    method.to_string(),
+                    self.auth.is_some(),
+                    request,
+                    traced_response,
+                    started.elapsed(),
+                ),
+                Err(_) => metrics::inc_counter(&metrics::EXECUTION_LAYER_ENGINE_API_TRACE_ERRORS),
+            }
+        }
+
+        let body = response?;
 
         match (body.result, body.error) {
             (result, None) => serde_json::from_value(result).map_err(Into::into),
@@ -0,0 +1,444 @@
+//! Optional tracing of Engine API requests and responses to disk.
+//!
+//! Each request/response pair is written as a single line of JSON so that complete evidence can
+//! be attached to reports of interop bugs between Lighthouse and an execution client. The JWT used
+//! to authenticate requests is never written, large bodies are truncated and old trace files are
+//! deleted once the trace directory holds `MAX_TRACE_FILES`.
+//!
+//! Files are written by a background task so that disk I/O never delays a request. Entries are
+//! dropped (and counted in a metric) if that task falls more than `TRACE_CHANNEL_CAPACITY`
+//! entries behind.
+
+use crate::metrics;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use task_executor::TaskExecutor;
+use tokio::sync::mpsc;
+
+/// A new trace file is started once the current one would exceed this size.
+pub const MAX_TRACE_FILE_SIZE: u64 = 64 * 1024 * 1024;
+/// The number of trace files to keep, including the file currently being written.
+pub const MAX_TRACE_FILES: usize = 8;
+/// Request and response bodies which serialize to more than this many bytes are truncated.
+///
+/// This is large enough to hold a full `engine_getPayload` response with blobs on mainnet.
+pub const MAX_TRACED_BODY_SIZE: usize = 4 * 1024 * 1024;
+
+/// The number of entries which may be waiting to be written before new entries are dropped.
+pub const TRACE_CHANNEL_CAPACITY: usize = 64;
+
+const TRACE_FILE_PREFIX: &str = "engine_api_trace_";
+const TRACE_FILE_EXTENSION: &str = ".jsonl";
+const REDACTED_AUTHORIZATION: &str = "Bearer <REDACTED>";
+
+/// A single request/response pair, as written to a trace file.
+#[derive(Serialize)]
+struct TraceEntry<'a> {
+    timestamp_ms: u64,
+    endpoint: &'a str,
+    method: &'a str,
+    duration_ms: u64,
+    /// The `Authorization` header sent with the request, which is always redacted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorization: Option<&'static str>,
+    request: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A request/response pair waiting to be written by the background task.
+struct PendingTrace {
+    endpoint: String,
+    method: String,
+    authenticated: bool,
+    request: Value,
+    response: Result<Value, String>,
+    duration: Duration,
+}
+
+/// Sends Engine API request/response pairs to the task which writes them to disk.
+#[derive(Clone)]
+pub struct EngineApiTraceSender {
+    tx: mpsc::Sender<PendingTrace>,
+}
+
+impl EngineApiTraceSender {
+    /// Queue a request and its response to be written, without blocking.
+    ///
+    /// The entry is dropped if the writer has fallen behind or stopped.
+    pub fn send(
+        &self,
+        endpoint: String,
+        method: String,
+        authenticated: bool,
+        request: Value,
+        response: Result<Value, String>,
+        duration: Duration,
+    ) {
+        let trace = PendingTrace {
+            endpoint,
+            method,
+            authenticated,
+            request,
+            response,
+            duration,
+        };
+        if self.tx.try_send(trace).is_err() {
+            metrics::inc_counter(&metrics::EXECUTION_LAYER_ENGINE_API_TRACE_ERRORS);
+        }
+    }
+}
+
+struct TraceFile {
+    file: File,
+    size: u64,
+}
+
+/// Writes Engine API request/response pairs to rotating files in a directory.
+pub struct EngineApiTracer {
+    dir: PathBuf,
+    max_file_size: u64,
+    max_files: usize,
+    max_body_size: usize,
+    current: Mutex<Option<TraceFile>>,
+}
+
+impl EngineApiTracer {
+    /// Create a tracer which writes to `dir` using the default limits.
+    pub fn new(dir: PathBuf) -> Result<Self, String> {
+        Self::with_limits(
+            dir,
+            MAX_TRACE_FILE_SIZE,
+            MAX_TRACE_FILES,
+            MAX_TRACED_BODY_SIZE,
+        )
+    }
+
+    pub fn with_limits(
+        dir: PathBuf,
+        max_file_size: u64,
+        max_files: usize,
+        max_body_size: usize,
+    ) -> Result<Self, String> {
+        fs::create_dir_all(&dir).map_err(|e| {
+            format!(
+                "Unable to create engine API trace directory {}: {:?}",
+                dir.display(),
+                e
+            )
+        })?;
+
+        Ok(Self {
+            dir,
+            max_file_size,
+            max_files: std::cmp::max(max_files, 1),
+            max_body_size,
+            current: Mutex::new(None),
+        })
+    }
+
+    /// Start a task which writes every entry sent to the returned sender.
+    ///
+    /// Writes are made on the blocking thread pool, one at a time and in the order they were sent.
+    pub fn spawn(self, executor: &TaskExecutor) -> EngineApiTraceSender {
+        let (tx, mut rx) = mpsc::channel::<PendingTrace>(TRACE_CHANNEL_CAPACITY);
+        let tracer = Arc::new(self);
+
+        executor.spawn(
+            async move {
+                while let Some(trace) = rx.recv().await {
+                    let tracer = tracer.clone();
+                    let written = tokio::task::spawn_blocking(move || {
+                        tracer.record(
+                            &trace.endpoint,
+                            &trace.method,
+                            trace.authenticated,
+                            trace.request,
+                            trace.response,
+                            trace.duration,
+                        )
+                    })
+                    .await;
+                    if !matches!(written, Ok(Ok(()))) {
+                        metrics::inc_counter(&metrics::EXECUTION_LAYER_ENGINE_API_TRACE_ERRORS);
+                    }
+                }
+            },
+            "engine_api_tracer",
+        );
+
+        EngineApiTraceSender { tx }
+    }
+
+    /// Write a request and its response (or the error which prevented a response) to the current
+    /// trace file.
+    ///
+    /// Only the presence of an `Authorization` header is recorded, never its value.
+    pub fn record(
+        &self,
+        endpoint: &str,
+        method: &str,
+        authenticated: bool,
+        request: Value,
+        response: Result<Value, String>,
+        duration: Duration,
+    ) -> Result<(), String> {
+        let (response, error) = match response {
+            Ok(response) => (Some(truncate_body(response, self.max_body_size)), None),
+            Err(error) => (None, Some(error)),
+        };
+        let entry = TraceEntry {
+            timestamp_ms: unix_timestamp_ms(),
+            endpoint,
+            method,
+            duration_ms: duration.as_millis() as u64,
+            authorization: authenticated.then_some(REDACTED_AUTHORIZATION),
+            request: truncate_body(request, self.max_body_size),
+            response,
+            error,
+        };
+        let mut line = serde_json::to_vec(&entry)
+            .map_err(|e| format!("Unable to serialize trace entry: {:?}", e))?;
+        line.push(b'\n');
+
+        let mut current = self.current.lock();
+        let needs_rotation = current.as_ref().map_or(true, |trace_file| {
+            trace_file.size > 0 && trace_file.size + line.len() as u64 > self.max_file_size
+        });
+        if needs_rotation {
+            *current = Some(self.new_trace_file()?);
+        }
+
+        if let Some(trace_file) = current.as_mut() {
+            trace_file
+                .file
+                .write_all(&line)
+                .map_err(|e| format!("Unable to write trace entry: {:?}", e))?;
+            trace_file.size += line.len() as u64;
+        }
+
+        Ok(())
+    }
+
+    /// Open a new trace file named after the current time and delete the oldest trace files.
+    fn new_trace_file(&self) -> Result<TraceFile, String> {
+        let path = self.dir.join(format!(
+            "{}{}{}",
+            TRACE_FILE_PREFIX,
+            unix_timestamp_ms(),
+            TRACE_FILE_EXTENSION
+        ));
+        let file = File::options()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Unable to open trace file {}: {:?}", path.display(), e))?;
+        let size = file
+            .metadata()
+            .map_err(|e| format!("Unable to read trace file {}: {:?}", path.display(), e))?
+            .len();
+
+        self.prune_trace_files()?;
+
+        Ok(TraceFile { file, size })
+    }
+
+    /// Return the paths of all trace files in the directory, oldest first.
+    pub fn trace_files(&self) -> Result<Vec<PathBuf>, String> {
+        let mut files = fs::read_dir(&self.dir)
+            .map_err(|e| format!("Unable to read trace directory: {:?}", e))?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let name = path.file_name()?.to_str()?;
+                let timestamp = name
+                    .strip_prefix(TRACE_FILE_PREFIX)?
+                    .strip_suffix(TRACE_FILE_EXTENSION)?
+                    .parse::<u64>()
+                    .ok()?;
+                Some((timestamp, path))
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        Ok(files.into_iter().map(|(_, path)| path).collect())
+    }
+
+    fn prune_trace_files(&self) -> Result<(), String> {
+        let files = self.trace_files()?;
+        let excess = files.len().saturating_sub(self.max_files);
+        for path in &files[..excess] {
+            fs::remove_file(path)
+                .map_err(|e| format!("Unable to remove trace file {}: {:?}", path.display(), e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Replace `body` with a summary containing a prefix of its JSON if it is larger than `max_size`.
+fn truncate_body(body: Value, max_size: usize) -> Value {
+    let serialized = body.to_string();
+    if serialized.len() <= max_size {
+        return body;
+    }
+
+    let mut end = max_size;
+    while !serialized.is_char_boundary(end) {
+        end -= 1;
+    }
+    json!({
+        "truncated": true,
+        "size": serialized.len(),
+        "prefix": &serialized[..end],
+    })
+}
+
+fn unix_timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use task_executor::test_utils::TestRuntime;
+    use tempfile::tempdir;
+
+    fn read_entries(tracer: &EngineApiTracer) -> Vec<Value> {
+        tracer
+            .trace_files()
+            .unwrap()
+            .iter()
+            .flat_map(|path| {
+                fs::read_to_string(path)
+                    .unwrap()
+                    .lines()
+                    .map(|line| serde_json::from_str(line).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn records_redacted_request_and_response() {
+        let dir = tempdir().unwrap();
+        let tracer = EngineApiTracer::new(dir.path().to_path_buf()).unwrap();
+
+        tracer
+            .record(
+                "http://localhost:8551/",
+                "engine_exchangeCapabilities",
+                true,
+                json!({"method": "engine_exchangeCapabilities"}),
+                Ok(json!({"result": []})),
+                Duration::from_millis(5),
+            )
+            .unwrap();
+        tracer
+            .record(
+                "http://localhost:8551/",
+                "eth_syncing",
+                true,
+                json!({"method": "eth_syncing"}),
+                Err("timeout".to_string()),
+                Duration::from_millis(1000),
+            )
+            .unwrap();
+
+        let entries = read_entries(&tracer);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["authorization"], REDACTED_AUTHORIZATION);
+        assert_eq!(entries[0]["response"], json!({"result": []}));
+        assert_eq!(entries[0]["duration_ms"], 5);
+        assert_eq!(entries[1]["error"], "timeout");
+        assert!(entries[1].get("response").is_none());
+    }
+
+    #[test]
+    fn truncates_large_bodies() {
+        let dir = tempdir().unwrap();
+        let tracer = EngineApiTracer::with_limits(dir.path().to_path_buf(), 1024, 2, 16).unwrap();
+
+        tracer
+            .record(
+                "http://localhost:8551/",
+                "engine_getPayloadV3",
+                false,
+                json!([]),
+                Ok(json!("0".repeat(64))),
+                Duration::ZERO,
+            )
+            .unwrap();
+
+        let entries = read_entries(&tracer);
+        assert_eq!(entries[0]["request"], json!([]));
+        assert_eq!(entries[0]["response"]["truncated"], true);
+        assert_eq!(entries[0]["response"]["size"], 66);
+        assert!(entries[0].get("authorization").is_none());
+    }
+
+    #[test]
+    fn rotates_and_prunes_files() {
+        let dir = tempdir().unwrap();
+        let tracer = EngineApiTracer::with_limits(dir.path().to_path_buf(), 1, 2, 1024).unwrap();
+
+        for _ in 0..4 {
+            tracer
+                .record(
+                    "http://localhost:8551/",
+                    "eth_syncing",
+                    false,
+                    json!([]),
+                    Ok(json!(false)),
+                    Duration::ZERO,
+                )
+                .unwrap();
+            // Ensure each trace file has a distinct timestamp.
+            std::thread::sleep(Duration::from_millis(2));
+        }
+
+        assert_eq!(tracer.trace_files().unwrap().len(), 2);
+        assert_eq!(read_entries(&tracer).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn writes_sent_entries_in_background() {
+        let runtime = TestRuntime::default();
+        let dir = tempdir().unwrap();
+        let reader = EngineApiTracer::new(dir.path().to_path_buf()).unwrap();
+        let sender = EngineApiTracer::new(dir.path().to_path_buf())
+            .unwrap()
+            .spawn(&runtime.task_executor);
+
+        for i in 0..3 {
+            sender.send(
+                "http://localhost:8551/".to_string(),
+                "eth_syncing".to_string(),
+                true,
+                json!([i]),
+                Ok(json!(false)),
+                Duration::ZERO,
+            );
+        }
+
+        for _ in 0..100 {
+            if read_entries(&reader).len() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let entries = read_entries(&reader);
+        assert_eq!(entries.len(), 3);
+        for (i, entry) in entries.iter().enumerate() {
+            assert_eq!(entry["request"], json!([i]));
+        }
+    }
+}
@@ -26,7 +26,7 @@ use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::future::Future;
 use std::io::Write;
@@ -41,6 +41,7 @@ use tokio::{
     time::sleep,
 };
 use tokio_stream::wrappers::WatchStream;
+use trace::EngineApiTracer;
 use tree_hash::TreeHash;
 use types::beacon_block_body::KzgCommitments;
 use types::builder_bid::BuilderBid;
@@ -75,6 +76,20 @@ pub const DEFAULT_JWT_FILE: &str = "jwt.hex";
 /// in an LRU cache to avoid redundant lookups. This is the size of that cache.
 const EXECUTION_BLOCKS_LRU_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(128);
 
+/// After a builder fails to reveal a payload, blocks are built with local payloads for this many
+/// epochs.
+///
+/// A block whose payload was withheld can't be replaced by a locally built block for the same
+/// slot, since signing a second block would be a slashable double proposal. Falling back for the
+/// following proposals stops a faulty builder from causing a run of missed proposals.
+pub const BUILDER_REVEAL_FAILURE_FALLBACK_EPOCHS: u64 = 2;
+
+/// The number of times to ask the builder to reveal a payload before giving up.
+///
+/// Only failures to reach the builder are retried. Relays accept the same signed blinded block
+/// more than once, so retrying can't cause an equivocation.
+const BUILDER_REVEAL_ATTEMPTS: usize = 2;
+
 /// A fee recipient address for use during block production. Only used as a very last resort if
 /// there is no address provided by the user.
 ///
@@ -144,6 +159,7 @@ pub enum Error {
         transactions_root: Hash256,
     },
     InvalidJWTSecret(String),
+    EngineApiTrace(String),
     InvalidForkForPayload,
     InvalidPayloadBody(String),
     InvalidPayloadConversion,
@@ -151,6 +167,11 @@ pub enum Error {
     BeaconStateError(BeaconStateError),
     PayloadTypeMismatch,
     VerifyingVersionedHashes(versioned_hashes::Error),
+    /// The builder revealed a payload which doesn't match the header in the signed blinded block.
+    InvalidBuilderReveal {
+        expected: Hash256,
+        revealed: Hash256,
+    },
 }
 
 impl From<BeaconStateError> for Error {
@@ -342,6 +363,7 @@ pub enum FailedCondition {
     Skips,
     SkipsPerEpoch,
     EpochsSinceFinalization,
+    ChainHealthDegraded,
 }
 
 type PayloadContentsRefTuple<'a, E> = (ExecutionPayloadRef<'a, E>, Option<&'a BlobsBundle<E>>);
@@ -362,6 +384,9 @@ struct Inner<E: EthSpec> {
     /// This is used *only* in the informational sync status endpoint, so that a VC using this
     /// node can prefer another node with a healthier EL.
     last_new_payload_errored: RwLock<bool>,
+    /// The slot of the most recent block for which the builder failed to reveal a payload.
+    last_builder_reveal_failure: RwLock<Option<Slot>>,
+    builder_bid_checks: BuilderBidChecks,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -387,6 +412,15 @@ pub struct Config {
     /// Default directory for the jwt secret if not provided through cli.
     pub default_datadir: PathBuf,
     pub execution_timeout_multiplier: Option<u32>,
+    /// Reject builder bids which don't pay the suggested fee recipient directly.
+    pub builder_require_fee_recipient: bool,
+    /// Hashes of transactions which a builder bid must not exclude.
+    ///
+    /// Since a blinded bid doesn't reveal its transactions, any bid received whilst the local
+    /// payload includes one of these transactions is rejected in favour of the local payload.
+    pub builder_required_transactions: Vec<Hash256>,
+    /// Write all Engine API requests and responses to files in this directory.
+    pub engine_api_trace_dir: Option<PathBuf>,
 }
 
 /// Provides access to one execution engine and provides a neat interface for consumption by the
@@ -410,6 +444,9 @@ impl<E: EthSpec> ExecutionLayer<E> {
             jwt_version,
             default_datadir,
             execution_timeout_multiplier,
+            builder_require_fee_recipient,
+            builder_required_transactions,
+            engine_api_trace_dir,
         } = config;
 
         let execution_url = url.ok_or(Error::NoEngine)?;
@@ -449,8 +486,18 @@ impl<E: EthSpec> ExecutionLayer<E> {
         let engine: Engine = {
             let auth = Auth::new(jwt_key, jwt_id, jwt_version);
             debug!(log, "Loaded execution endpoint"; "endpoint" => %execution_url, "jwt_path" => ?secret_file.as_path());
-            let api = HttpJsonRpc::new_with_auth(execution_url, auth, execution_timeout_multiplier)
-                .map_err(Error::ApiError)?;
+            let mut api =
+                HttpJsonRpc::new_with_auth(execution_url, auth, execution_timeout_multiplier)
+                    .map_err(Error::ApiError)?;
+            if let Some(trace_dir) = engine_api_trace_dir {
+                info!(
+                    log,
+                    "Tracing Engine API requests";
+                    "dir" => %trace_dir.display(),
+                );
+                let tracer = EngineApiTracer::new(trace_dir).map_err(Error::EngineApiTrace)?;
+                api = api.with_tracer(tracer.spawn(&executor));
+            }
             Engine::new(api, executor.clone(), &log)
         };
 
@@ -466,6 +513,11 @@ impl<E: EthSpec> ExecutionLayer<E> {
             payload_cache: PayloadCache::default(),
             log,
             last_new_payload_errored: RwLock::new(false),
+            last_builder_reveal_failure: RwLock::new(None),
+            builder_bid_checks: BuilderBidChecks {
+                require_fee_recipient: builder_require_fee_recipient,
+                required_transactions: builder_required_transactions.into_iter().collect(),
+            },
         };
 
         let el = Self {
@@ -539,6 +591,14 @@ impl<E: EthSpec> ExecutionLayer<E> {
         self.inner.payload_cache.get(root)
     }
 
+    /// Returns the slot of a builder reveal failure which should stop the builder being used for a
+    /// proposal at `slot`, if any.
+    pub async fn recent_builder_reveal_failure(&self, slot: Slot) -> Option<Slot> {
+        let failed_slot = (*self.inner.last_builder_reveal_failure.read().await)?;
+        let fallback_slots = BUILDER_REVEAL_FAILURE_FALLBACK_EPOCHS * E::slots_per_epoch();
+        (slot <= failed_slot + fallback_slots).then_some(failed_slot)
+    }
+
     pub fn executor(&self) -> &TaskExecutor {
         &self.inner.executor
     }
@@ -1030,6 +1090,31 @@ impl<E: EthSpec> ExecutionLayer<E> {
                 .map(ProvenancedPayload::Local);
         }
 
+        // a builder which recently withheld a payload can't be trusted with this proposal
+        if let Some(failed_slot) = self
+            .recent_builder_reveal_failure(builder_params.slot)
+            .await
+        {
+            info!(
+                self.log(),
+                "Builder recently failed to reveal a payload, using local payload";
+                "info" => "this avoids missing further proposals if the builder is faulty",
+                "fallback_epochs" => BUILDER_REVEAL_FAILURE_FALLBACK_EPOCHS,
+                "failed_slot" => failed_slot,
+                "slot" => builder_params.slot,
+            );
+            return self
+                .get_full_payload_caching(
+                    parent_hash,
+                    payload_attributes,
+                    forkchoice_update_params,
+                    current_fork,
+                )
+                .await
+                .and_then(GetPayloadResponseType::try_into)
+                .map(ProvenancedPayload::Local);
+        }
+
         let (relay_result, local_result) = self
             .fetch_builder_and_local_payloads(
                 builder.as_ref(),
@@ -1107,7 +1192,8 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     &relay,
                     parent_hash,
                     payload_attributes,
-                    Some(local.block_number()),
+                    Some(&local),
+                    &self.inner.builder_bid_checks,
                     current_fork,
                     spec,
                 ) {
@@ -1193,6 +1279,7 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     parent_hash,
                     payload_attributes,
                     None,
+                    &self.inner.builder_bid_checks,
                     current_fork,
                     spec,
                 ) {
@@ -2051,11 +2138,39 @@ impl<E: EthSpec> ExecutionLayer<E> {
         if let Some(builder) = self.builder() {
             let (payload_result, duration) =
                 timed_future(metrics::POST_BLINDED_PAYLOAD_BUILDER, async {
-                    builder
-                        .post_builder_blinded_blocks(block)
-                        .await
-                        .map_err(Error::Builder)
-                        .map(|d| d.data)
+                    let mut attempt = 1;
+                    let revealed = loop {
+                        match builder.post_builder_blinded_blocks(block).await {
+                            Ok(response) => break response.data,
+                            Err(e) if attempt < BUILDER_REVEAL_ATTEMPTS => {
+                                debug!(
+                                    self.log(),
+                                    "Retrying builder payload reveal";
+                                    "error" => ?e,
+                                    "block_root" => ?block_root,
+                                    "attempt" => attempt,
+                                );
+                                attempt += 1;
+                            }
+                            Err(e) => return Err(Error::Builder(e)),
+                        }
+                    };
+
+                    // Check that the builder revealed the payload committed to in the block.
+                    let expected = block
+                        .message()
+                        .execution_payload()
+                        .map_err(|_| Error::InvalidForkForPayload)?
+                        .tree_hash_root();
+                    let revealed_root = revealed.payload_ref().tree_hash_root();
+                    if revealed_root != expected {
+                        return Err(Error::InvalidBuilderReveal {
+                            expected,
+                            revealed: revealed_root,
+                        });
+                    }
+
+                    Ok(revealed)
                 })
                 .await;
 
@@ -2081,6 +2196,7 @@ impl<E: EthSpec> ExecutionLayer<E> {
                         &metrics::EXECUTION_LAYER_BUILDER_REVEAL_PAYLOAD_OUTCOME,
                         &[metrics::FAILURE],
                     );
+                    *self.inner.last_builder_reveal_failure.write().await = Some(block.slot());
                     warn!(
                         self.log(),
                         "Builder failed to reveal payload";
@@ -2135,6 +2251,17 @@ enum InvalidBuilderPayload {
         payload: Option<Hash256>,
         expected: Option<Hash256>,
     },
+    FeeRecipient {
+        payload: Address,
+        expected: Address,
+    },
+    GasLimit {
+        payload: u64,
+        local: u64,
+    },
+    RequiredTransactions {
+        transactions: Vec<Hash256>,
+    },
 }
 
 impl fmt::Display for InvalidBuilderPayload {
@@ -2173,16 +2300,69 @@ impl fmt::Display for InvalidBuilderPayload {
                     opt_string(expected)
                 )
             }
+            InvalidBuilderPayload::FeeRecipient { payload, expected } => {
+                write!(
+                    f,
+                    "payload fee recipient was {:?} not {:?}",
+                    payload, expected
+                )
+            }
+            InvalidBuilderPayload::GasLimit { payload, local } => write!(
+                f,
+                "payload gas limit {} is not reachable from the parent of the local payload \
+                    with gas limit {}",
+                payload, local
+            ),
+            InvalidBuilderPayload::RequiredTransactions { transactions } => write!(
+                f,
+                "local payload includes required transactions {:?}",
+                transactions
+            ),
+        }
+    }
+}
+
+/// Checks applied to builder bids which are enabled by the user.
+#[derive(Default)]
+struct BuilderBidChecks {
+    require_fee_recipient: bool,
+    required_transactions: HashSet<Hash256>,
+}
+
+impl BuilderBidChecks {
+    /// Returns the hashes of any required transactions which are included in `payload`.
+    fn required_transactions_in<E: EthSpec>(
+        &self,
+        payload: ExecutionPayloadRef<E>,
+    ) -> Vec<Hash256> {
+        if self.required_transactions.is_empty() {
+            return vec![];
         }
+
+        payload
+            .transactions()
+            .iter()
+            .map(|transaction| keccak::keccak256(transaction))
+            .filter(|hash| self.required_transactions.contains(hash))
+            .collect()
     }
 }
 
+/// Returns `true` if blocks with gas limits `a` and `b` could share a parent.
+///
+/// A block's gas limit must differ from its parent's by less than `parent / 1024`, so two siblings
+/// may differ by less than `2 * parent / 1024`, which is at most `2 * max(a, b) / 1023`.
+fn is_sibling_gas_limit(a: u64, b: u64) -> bool {
+    a.abs_diff(b) <= a.max(b).saturating_mul(2) / 1023
+}
+
 /// Perform some cursory, non-exhaustive validation of the bid returned from the builder.
 fn verify_builder_bid<E: EthSpec>(
     bid: &ForkVersionedResponse<SignedBuilderBid<E>>,
     parent_hash: ExecutionBlockHash,
     payload_attributes: &PayloadAttributes,
-    block_number: Option<u64>,
+    local: Option<&GetPayloadResponse<E>>,
+    checks: &BuilderBidChecks,
     current_fork: ForkName,
     spec: &ChainSpec,
 ) -> Result<(), Box<InvalidBuilderPayload>> {
@@ -2206,6 +2386,12 @@ fn verify_builder_bid<E: EthSpec>(
         .map(|withdrawals| Withdrawals::<E>::from(withdrawals).tree_hash_root());
     let payload_withdrawals_root = header.withdrawals_root().ok();
 
+    let block_number = local.map(GetPayloadResponse::block_number);
+    let local_gas_limit = local.map(|local| local.execution_payload_ref().gas_limit());
+    let required_transactions = local
+        .map(|local| checks.required_transactions_in(local.execution_payload_ref()))
+        .unwrap_or_default();
+
     if header.parent_hash() != parent_hash {
         Err(Box::new(InvalidBuilderPayload::ParentHash {
             payload: header.parent_hash(),
@@ -2241,6 +2427,24 @@ fn verify_builder_bid<E: EthSpec>(
             payload: payload_withdrawals_root,
             expected: expected_withdrawals_root,
         }))
+    } else if checks.require_fee_recipient
+        && header.fee_recipient() != payload_attributes.suggested_fee_recipient()
+    {
+        Err(Box::new(InvalidBuilderPayload::FeeRecipient {
+            payload: header.fee_recipient(),
+            expected: payload_attributes.suggested_fee_recipient(),
+        }))
+    } else if let Some(local_gas_limit) =
+        local_gas_limit.filter(|local| !is_sibling_gas_limit(header.gas_limit(), *local))
+    {
+        Err(Box::new(InvalidBuilderPayload::GasLimit {
+            payload: header.gas_limit(),
+            local: local_gas_limit,
+        }))
+    } else if !required_transactions.is_empty() {
+        Err(Box::new(InvalidBuilderPayload::RequiredTransactions {
+            transactions: required_transactions,
+        }))
     } else {
         Ok(())
     }
@@ -2276,7 +2480,8 @@ mod test {
     use super::*;
     use crate::test_utils::MockExecutionLayer as GenericMockExecutionLayer;
     use task_executor::test_utils::TestRuntime;
-    use types::MainnetEthSpec;
+    use types::builder_bid::BuilderBidBellatrix;
+    use types::{Keypair, MainnetEthSpec, SignedRoot};
 
     type MockExecutionLayer = GenericMockExecutionLayer<MainnetEthSpec>;
 
@@ -2416,4 +2621,143 @@ mod test {
             })
             .await;
     }
+
+    /// Returns a signed builder bid for `payload` at the Bellatrix fork.
+    fn signed_bid(
+        payload: &ExecutionPayloadBellatrix<MainnetEthSpec>,
+        spec: &ChainSpec,
+    ) -> ForkVersionedResponse<SignedBuilderBid<MainnetEthSpec>> {
+        let keypair = Keypair::random();
+        let message = BuilderBid::Bellatrix(BuilderBidBellatrix {
+            header: payload.into(),
+            value: Uint256::from(1),
+            pubkey: keypair.pk.compress(),
+        });
+        let signature = keypair
+            .sk
+            .sign(message.signing_root(spec.get_builder_domain()));
+        ForkVersionedResponse {
+            version: Some(ForkName::Bellatrix),
+            metadata: Default::default(),
+            data: SignedBuilderBid { message, signature },
+        }
+    }
+
+    #[test]
+    fn builder_bid_checks() {
+        let spec = MainnetEthSpec::default_spec();
+        let required_transaction = VariableList::from(vec![1, 2, 3]);
+        let required_transaction_hash = keccak::keccak256(&required_transaction);
+        let local_payload = ExecutionPayloadBellatrix {
+            parent_hash: ExecutionBlockHash::repeat_byte(1),
+            prev_randao: Hash256::repeat_byte(2),
+            fee_recipient: Address::repeat_byte(3),
+            timestamp: 4,
+            block_number: 5,
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+        let payload_attributes = PayloadAttributes::new(
+            local_payload.timestamp,
+            local_payload.prev_randao,
+            local_payload.fee_recipient,
+            None,
+            None,
+        );
+        let all_checks = BuilderBidChecks {
+            require_fee_recipient: true,
+            required_transactions: [required_transaction_hash].into(),
+        };
+        let verify = |bid_payload: &ExecutionPayloadBellatrix<MainnetEthSpec>,
+                      local_payload: &ExecutionPayloadBellatrix<MainnetEthSpec>,
+                      checks: &BuilderBidChecks| {
+            let local = GetPayloadResponse::Bellatrix(GetPayloadResponseBellatrix {
+                execution_payload: local_payload.clone(),
+                block_value: Uint256::from(1),
+            });
+            verify_builder_bid(
+                &signed_bid(bid_payload, &spec),
+                local_payload.parent_hash,
+                &payload_attributes,
+                Some(&local),
+                checks,
+                ForkName::Bellatrix,
+                &spec,
+            )
+            .map_err(|e| *e)
+        };
+
+        // A bid matching the local payload passes all of the checks.
+        assert!(verify(&local_payload, &local_payload, &all_checks).is_ok());
+
+        // A bid which pays a different fee recipient is only rejected if that is mandated.
+        let other_fee_recipient = ExecutionPayloadBellatrix {
+            fee_recipient: Address::repeat_byte(42),
+            ..local_payload.clone()
+        };
+        assert!(verify(
+            &other_fee_recipient,
+            &local_payload,
+            &BuilderBidChecks::default()
+        )
+        .is_ok());
+        assert!(matches!(
+            verify(&other_fee_recipient, &local_payload, &all_checks),
+            Err(InvalidBuilderPayload::FeeRecipient { payload, expected })
+                if payload == Address::repeat_byte(42) && expected == Address::repeat_byte(3)
+        ));
+
+        // A bid with a gas limit which could be built on the same parent is accepted, but one which
+        // could not is rejected.
+        let higher_gas_limit = ExecutionPayloadBellatrix {
+            gas_limit: local_payload.gas_limit + 1_000,
+            ..local_payload.clone()
+        };
+        assert!(verify(&higher_gas_limit, &local_payload, &all_checks).is_ok());
+        let unreachable_gas_limit = ExecutionPayloadBellatrix {
+            gas_limit: local_payload.gas_limit * 2,
+            ..local_payload.clone()
+        };
+        assert!(matches!(
+            verify(
+                &unreachable_gas_limit,
+                &local_payload,
+                &BuilderBidChecks::default()
+            ),
+            Err(InvalidBuilderPayload::GasLimit {
+                payload: 60_000_000,
+                local: 30_000_000,
+            })
+        ));
+
+        // A bid is rejected if the local payload includes a required transaction, since the bid
+        // might exclude it.
+        let local_with_required_transaction = ExecutionPayloadBellatrix {
+            transactions: VariableList::from(vec![required_transaction]),
+            ..local_payload.clone()
+        };
+        assert!(matches!(
+            verify(&local_payload, &local_with_required_transaction, &all_checks),
+            Err(InvalidBuilderPayload::RequiredTransactions { transactions })
+                if transactions == vec![required_transaction_hash]
+        ));
+        assert!(verify(
+            &local_payload,
+            &local_with_required_transaction,
+            &BuilderBidChecks::default()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn sibling_gas_limits() {
+        let parent = 30_000_000;
+        let max_delta = parent / 1024 - 1;
+
+        assert!(is_sibling_gas_limit(parent, parent));
+        assert!(is_sibling_gas_limit(parent + max_delta, parent - max_delta));
+        assert!(is_sibling_gas_limit(parent - max_delta, parent + max_delta));
+        assert!(!is_sibling_gas_limit(parent, 2 * parent));
+        assert!(!is_sibling_gas_limit(parent + 3 * max_delta, parent));
+    }
 }
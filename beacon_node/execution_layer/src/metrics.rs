@@ -84,4 +84,8 @@ lazy_static::lazy_static! {
         "The gwei bid value of payloads received by local EEs or builders. Only shows values up to i64::MAX.",
         &["source"]
     );
+    pub static ref EXECUTION_LAYER_ENGINE_API_TRACE_ERRORS: Result<IntCounter> = try_create_int_counter(
+        "execution_layer_engine_api_trace_errors_total",
+        "Count of Engine API requests which could not be written to the trace directory",
+    );
 }
@@ -214,6 +214,7 @@ pub struct MockBuilder<E: EthSpec> {
     builder_sk: SecretKey,
     operations: Arc<RwLock<Vec<Operation>>>,
     invalidate_signatures: Arc<RwLock<bool>>,
+    withhold_payloads: Arc<RwLock<bool>>,
 }
 
 impl<E: EthSpec> MockBuilder<E> {
@@ -264,6 +265,7 @@ impl<E: EthSpec> MockBuilder<E> {
             builder_sk: sk,
             operations: Arc::new(RwLock::new(vec![])),
             invalidate_signatures: Arc::new(RwLock::new(false)),
+            withhold_payloads: Arc::new(RwLock::new(false)),
         }
     }
 
@@ -281,6 +283,15 @@ impl<E: EthSpec> MockBuilder<E> {
         *self.invalidate_signatures.write() = false;
     }
 
+    /// Fail every request to reveal a payload, as a faulty builder would.
+    pub fn withhold_payloads(&self) {
+        *self.withhold_payloads.write() = true;
+    }
+
+    pub fn reveal_payloads(&self) {
+        *self.withhold_payloads.write() = false;
+    }
+
     fn apply_operations<B: BidStuff<E>>(&self, bid: &mut B) {
         let mut guard = self.operations.write();
         while let Some(op) = guard.pop() {
@@ -332,6 +343,9 @@ pub fn serve<E: EthSpec>(
                 |block: SignedBlindedBeaconBlock<E>,
                  fork_name: ForkName,
                  builder: MockBuilder<E>| async move {
+                    if *builder.withhold_payloads.read() {
+                        return Err(reject("payload withheld"));
+                    }
                     let root = match block {
                         SignedBlindedBeaconBlock::Base(_) | types::SignedBeaconBlock::Altair(_) => {
                             return Err(reject("invalid fork"));
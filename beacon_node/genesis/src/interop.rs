@@ -4,8 +4,8 @@ use rayon::prelude::*;
 use ssz::Encode;
 use state_processing::initialize_beacon_state_from_eth1;
 use types::{
-    BeaconState, ChainSpec, DepositData, EthSpec, ExecutionPayloadHeader, Hash256, Keypair,
-    PublicKey, Signature,
+    test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, DepositData, EthSpec,
+    ExecutionPayloadHeader, ForkName, Hash256, Keypair, PublicKey, Signature,
 };
 
 pub const DEFAULT_ETH1_BLOCK_HASH: &[u8] = &[0x42; 32];
@@ -96,9 +96,45 @@ pub fn interop_genesis_state_with_withdrawal_credentials<E: EthSpec>(
     }
 
     let eth1_timestamp = 2_u64.pow(40);
+    let datas = interop_deposit_data(keypairs, withdrawal_credentials, spec)?;
+
+    let mut state = initialize_beacon_state_from_eth1(
+        eth1_block_hash,
+        eth1_timestamp,
+        genesis_deposits(datas, spec)?,
+        execution_payload_header,
+        spec,
+    )
+    .map_err(|e| format!("Unable to initialize genesis state: {:?}", e))?;
+
+    *state.genesis_time_mut() = genesis_time;
+
+    // Invalidate all the caches after all the manual state surgery.
+    state
+        .drop_all_caches()
+        .map_err(|e| format!("Unable to drop caches: {:?}", e))?;
+
+    Ok(state)
+}
+
+/// Returns signed `DepositData` for each of `keypairs`, depositing the maximum effective balance
+/// with the corresponding `withdrawal_credentials`.
+pub fn interop_deposit_data(
+    keypairs: &[Keypair],
+    withdrawal_credentials: &[Hash256],
+    spec: &ChainSpec,
+) -> Result<Vec<DepositData>, String> {
+    if keypairs.len() != withdrawal_credentials.len() {
+        return Err(format!(
+            "wrong number of withdrawal credentials, expected: {}, got: {}",
+            keypairs.len(),
+            withdrawal_credentials.len()
+        ));
+    }
+
     let amount = spec.max_effective_balance;
 
-    let datas = keypairs
+    Ok(keypairs
         .into_par_iter()
         .zip(withdrawal_credentials.into_par_iter())
         .map(|(keypair, &withdrawal_credentials)| {
@@ -113,31 +149,67 @@ pub fn interop_genesis_state_with_withdrawal_credentials<E: EthSpec>(
 
             data
         })
-        .collect::<Vec<_>>();
+        .collect())
+}
 
-    let mut state = initialize_beacon_state_from_eth1(
-        eth1_block_hash,
-        eth1_timestamp,
-        genesis_deposits(datas, spec)?,
-        execution_payload_header,
-        spec,
-    )
-    .map_err(|e| format!("Unable to initialize genesis state: {:?}", e))?;
+/// Everything required to start a devnet from the interop validator set.
+pub struct InteropGenesis<E: EthSpec> {
+    /// The spec with every fork up to and including the genesis fork scheduled at genesis.
+    pub spec: ChainSpec,
+    pub keypairs: Vec<Keypair>,
+    pub deposit_data: Vec<DepositData>,
+    pub state: BeaconState<E>,
+}
 
-    *state.genesis_time_mut() = genesis_time;
+/// Generates `validator_count` deterministic interop keypairs along with their deposit data and
+/// a genesis state at `fork`.
+///
+/// All validators use BLS withdrawal credentials. The `execution_payload_header` should be
+/// provided for post-merge forks if the genesis state must match an existing execution chain.
+pub fn interop_genesis<E: EthSpec>(
+    validator_count: usize,
+    genesis_time: u64,
+    fork: ForkName,
+    execution_payload_header: Option<ExecutionPayloadHeader<E>>,
+    spec: ChainSpec,
+) -> Result<InteropGenesis<E>, String> {
+    if let Some(header) = &execution_payload_header {
+        let header_fork = header.fork_name();
+        if header_fork != fork {
+            return Err(format!(
+                "execution payload header is for {header_fork}, expected {fork}"
+            ));
+        }
+    }
 
-    // Invalidate all the caches after all the manual state surgery.
-    state
-        .drop_all_caches()
-        .map_err(|e| format!("Unable to drop caches: {:?}", e))?;
+    let spec = fork.make_genesis_spec(spec);
+    let keypairs = generate_deterministic_keypairs(validator_count);
+    let withdrawal_credentials = keypairs
+        .iter()
+        .map(|keypair| bls_withdrawal_credentials(&keypair.pk, &spec))
+        .collect::<Vec<_>>();
+    let deposit_data = interop_deposit_data(&keypairs, &withdrawal_credentials, &spec)?;
+    let state = interop_genesis_state_with_withdrawal_credentials(
+        &keypairs,
+        &withdrawal_credentials,
+        genesis_time,
+        Hash256::from_slice(DEFAULT_ETH1_BLOCK_HASH),
+        execution_payload_header,
+        &spec,
+    )?;
 
-    Ok(state)
+    Ok(InteropGenesis {
+        spec,
+        keypairs,
+        deposit_data,
+        state,
+    })
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use types::{test_utils::generate_deterministic_keypairs, MinimalEthSpec};
+    use types::MinimalEthSpec;
 
     type TestEthSpec = MinimalEthSpec;
 
@@ -283,4 +355,40 @@ mod test {
             "validator count should be correct"
         );
     }
+
+    #[test]
+    fn interop_genesis_at_fork() {
+        let validator_count = 8;
+        let genesis_time = 42;
+
+        for fork in ForkName::list_all() {
+            let genesis = interop_genesis::<TestEthSpec>(
+                validator_count,
+                genesis_time,
+                fork,
+                None,
+                TestEthSpec::default_spec(),
+            )
+            .expect("should build genesis");
+
+            assert_eq!(genesis.keypairs.len(), validator_count);
+            assert_eq!(genesis.deposit_data.len(), validator_count);
+            assert_eq!(genesis.state.fork_name_unchecked(), fork);
+            assert_eq!(genesis.state.genesis_time(), genesis_time);
+
+            for ((keypair, data), validator) in genesis
+                .keypairs
+                .iter()
+                .zip(&genesis.deposit_data)
+                .zip(genesis.state.validators())
+            {
+                assert_eq!(data.pubkey, keypair.pk.clone().into());
+                assert_eq!(validator.pubkey, data.pubkey);
+                assert_eq!(
+                    validator.withdrawal_credentials,
+                    data.withdrawal_credentials
+                );
+            }
+        }
+    }
 }
@@ -56,6 +56,15 @@ impl BlockId {
                 Ok((justified_checkpoint.root, execution_optimistic, false))
             }
             CoreBlockId::Slot(slot) => {
+                // Blocks between genesis and the oldest block are absent following checkpoint
+                // sync, until backfill sync completes.
+                let oldest_block_slot = chain.store.get_oldest_block_slot();
+                if *slot != chain.spec.genesis_slot && *slot < oldest_block_slot {
+                    return Err(warp_utils::reject::pruned(format!(
+                        "beacon block at slot {} is prior to the oldest block at slot {}",
+                        slot, oldest_block_slot
+                    )));
+                }
                 let execution_optimistic = chain
                     .is_optimistic_or_invalid_head()
                     .map_err(warp_utils::reject::beacon_chain_error)?;
@@ -64,7 +73,7 @@ impl BlockId {
                     .map_err(warp_utils::reject::beacon_chain_error)
                     .and_then(|root_opt| {
                         root_opt.ok_or_else(|| {
-                            warp_utils::reject::custom_not_found(format!(
+                            warp_utils::reject::unknown_block(format!(
                                 "beacon block at slot {}",
                                 slot
                             ))
@@ -82,14 +91,14 @@ impl BlockId {
             CoreBlockId::Root(root) => {
                 // This matches the behaviour of other consensus clients (e.g. Teku).
                 if root == &Hash256::zero() {
-                    return Err(warp_utils::reject::custom_not_found(format!(
+                    return Err(warp_utils::reject::unknown_block(format!(
                         "beacon block with root {}",
                         root
                     )));
                 };
-                if chain
+                if let Some(block_slot) = chain
                     .store
-                    .block_exists(root)
+                    .get_block_slot(root)
                     .map_err(BeaconChainError::DBError)
                     .map_err(warp_utils::reject::beacon_chain_error)?
                 {
@@ -99,22 +108,12 @@ impl BlockId {
                         .is_optimistic_or_invalid_block(root)
                         .map_err(BeaconChainError::ForkChoiceError)
                         .map_err(warp_utils::reject::beacon_chain_error)?;
-                    let blinded_block = chain
-                        .get_blinded_block(root)
-                        .map_err(warp_utils::reject::beacon_chain_error)?
-                        .ok_or_else(|| {
-                            warp_utils::reject::custom_not_found(format!(
-                                "beacon block with root {}",
-                                root
-                            ))
-                        })?;
-                    let block_slot = blinded_block.slot();
                     let finalized = chain
                         .is_finalized_block(root, block_slot)
                         .map_err(warp_utils::reject::beacon_chain_error)?;
                     Ok((*root, execution_optimistic, finalized))
                 } else {
-                    Err(warp_utils::reject::custom_not_found(format!(
+                    Err(warp_utils::reject::unknown_block(format!(
                         "beacon block with root {}",
                         root
                     )))
@@ -149,20 +148,13 @@ impl BlockId {
             }
             CoreBlockId::Slot(slot) => {
                 let (root, execution_optimistic, finalized) = self.root(chain)?;
+                check_slot_not_skipped(chain, &root, *slot)?;
                 chain
                     .get_blinded_block(&root)
                     .map_err(warp_utils::reject::beacon_chain_error)
                     .and_then(|block_opt| match block_opt {
-                        Some(block) => {
-                            if block.slot() != *slot {
-                                return Err(warp_utils::reject::custom_not_found(format!(
-                                    "slot {} was skipped",
-                                    slot
-                                )));
-                            }
-                            Ok((block, execution_optimistic, finalized))
-                        }
-                        None => Err(warp_utils::reject::custom_not_found(format!(
+                        Some(block) => Ok((block, execution_optimistic, finalized)),
+                        None => Err(warp_utils::reject::unknown_block(format!(
                             "beacon block with root {}",
                             root
                         ))),
@@ -175,7 +167,7 @@ impl BlockId {
                     .map_err(warp_utils::reject::beacon_chain_error)
                     .and_then(|root_opt| {
                         root_opt.ok_or_else(|| {
-                            warp_utils::reject::custom_not_found(format!(
+                            warp_utils::reject::unknown_block(format!(
                                 "beacon block with root {}",
                                 root
                             ))
@@ -212,21 +204,14 @@ impl BlockId {
             }
             CoreBlockId::Slot(slot) => {
                 let (root, execution_optimistic, finalized) = self.root(chain)?;
+                check_slot_not_skipped(chain, &root, *slot)?;
                 chain
                     .get_block(&root)
                     .await
-                    .map_err(warp_utils::reject::beacon_chain_error)
+                    .map_err(|e| full_block_error(&root, e))
                     .and_then(|block_opt| match block_opt {
-                        Some(block) => {
-                            if block.slot() != *slot {
-                                return Err(warp_utils::reject::custom_not_found(format!(
-                                    "slot {} was skipped",
-                                    slot
-                                )));
-                            }
-                            Ok((Arc::new(block), execution_optimistic, finalized))
-                        }
-                        None => Err(warp_utils::reject::custom_not_found(format!(
+                        Some(block) => Ok((Arc::new(block), execution_optimistic, finalized)),
+                        None => Err(warp_utils::reject::unknown_block(format!(
                             "beacon block with root {}",
                             root
                         ))),
@@ -237,12 +222,12 @@ impl BlockId {
                 chain
                     .get_block(&root)
                     .await
-                    .map_err(warp_utils::reject::beacon_chain_error)
+                    .map_err(|e| full_block_error(&root, e))
                     .and_then(|block_opt| {
                         block_opt
                             .map(|block| (Arc::new(block), execution_optimistic, finalized))
                             .ok_or_else(|| {
-                                warp_utils::reject::custom_not_found(format!(
+                                warp_utils::reject::unknown_block(format!(
                                     "beacon block with root {}",
                                     root
                                 ))
@@ -258,9 +243,13 @@ impl BlockId {
         chain: &BeaconChain<T>,
     ) -> Result<BlobSidecarList<T::EthSpec>, warp::Rejection> {
         let root = self.root(chain)?.0;
-        chain
+        let blob_sidecar_list = chain
             .get_blobs(&root)
-            .map_err(warp_utils::reject::beacon_chain_error)
+            .map_err(warp_utils::reject::beacon_chain_error)?;
+        if blob_sidecar_list.is_empty() {
+            check_blobs_not_pruned(chain, &root)?;
+        }
+        Ok(blob_sidecar_list)
     }
 
     pub fn blob_sidecar_list_filtered<T: BeaconChainTypes>(
@@ -284,6 +273,74 @@ impl BlockId {
     }
 }
 
+/// Returns an error if `slot` was skipped, i.e. if `root` is that of a block from an earlier slot.
+///
+/// The slot is read from the block root to slot index, so blocks at skipped slots are never
+/// loaded (nor their payloads fetched from the execution layer).
+fn check_slot_not_skipped<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    root: &Hash256,
+    slot: Slot,
+) -> Result<(), warp::Rejection> {
+    let block_slot = chain
+        .store
+        .get_block_slot(root)
+        .map_err(BeaconChainError::DBError)
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+    match block_slot {
+        Some(block_slot) if block_slot != slot => Err(warp_utils::reject::unknown_block(format!(
+            "slot {} was skipped",
+            slot
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Converts an error loading the full block with `root`, reporting a payload which has been pruned
+/// and can't be reconstructed by the execution layer as pruned.
+fn full_block_error(root: &Hash256, e: BeaconChainError) -> warp::Rejection {
+    match e {
+        BeaconChainError::BlockHashMissingFromExecutionLayer(block_hash) => {
+            warp_utils::reject::pruned(format!(
+                "execution payload {:?} of beacon block with root {} has been pruned and is not \
+                 available from the execution layer",
+                block_hash, root
+            ))
+        }
+        e => warp_utils::reject::beacon_chain_error(e),
+    }
+}
+
+/// Returns an error if the block with `root` had blobs which have since been pruned.
+fn check_blobs_not_pruned<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    root: &Hash256,
+) -> Result<(), warp::Rejection> {
+    let Some(oldest_blob_slot) = chain.store.get_blob_info().oldest_blob_slot else {
+        return Ok(());
+    };
+    let Some(block) = chain
+        .get_blinded_block(root)
+        .map_err(warp_utils::reject::beacon_chain_error)?
+    else {
+        return Ok(());
+    };
+    let has_blobs = block
+        .message()
+        .body()
+        .blob_kzg_commitments()
+        .map_or(false, |commitments| !commitments.is_empty());
+    if has_blobs && block.slot() < oldest_blob_slot {
+        return Err(warp_utils::reject::pruned(format!(
+            "blobs of beacon block with root {} at slot {} are prior to the oldest blob slot {}",
+            root,
+            block.slot(),
+            oldest_blob_slot
+        )));
+    }
+    Ok(())
+}
+
 impl FromStr for BlockId {
     type Err = String;
 
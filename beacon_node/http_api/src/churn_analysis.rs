@@ -0,0 +1,422 @@
+//! Projects the activation and exit queues of the head state forward, so that stakers can estimate
+//! how long a pending validator will wait to activate or exit.
+//!
+//! Projections assume that the chain continues to finalize and that the churn limits of the head
+//! state remain fixed. Prior to Electra the number of validators activated per epoch is limited,
+//! whereas from Electra onwards the churn instead limits the deposits which fund new validators.
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{ChurnAnalysis, ProjectedChurn};
+use safe_arith::SafeArith;
+use std::cmp;
+use std::collections::HashMap;
+use std::sync::Arc;
+use types::{BeaconState, BeaconStateError, ChainSpec, Epoch, EthSpec, RelativeEpoch};
+use warp_utils::reject::beacon_state_error;
+
+pub fn get_churn_analysis<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<ChurnAnalysis, warp::Rejection> {
+    let spec = &chain.spec;
+    let mut state = chain.head_beacon_state_cloned();
+    state.build_caches(spec).map_err(beacon_state_error)?;
+
+    let current_epoch = state.current_epoch();
+    let fork_name = state.fork_name_unchecked();
+
+    let active_validators = state
+        .committee_cache(RelativeEpoch::Current)
+        .map_err(beacon_state_error)?
+        .active_validator_count() as u64;
+    let churn_limit = state
+        .get_validator_churn_limit(spec)
+        .map_err(beacon_state_error)?;
+    let activation_churn_limit = state
+        .get_activation_churn_limit(spec)
+        .map_err(beacon_state_error)?;
+    let activation_exit_balance_churn_limit = if fork_name.electra_enabled() {
+        Some(
+            state
+                .get_activation_exit_churn_limit(spec)
+                .map_err(beacon_state_error)?,
+        )
+    } else {
+        None
+    };
+
+    // The epoch at whose end the last pending deposit to each validator will be processed.
+    let deposit_epochs = if let Some(per_epoch_churn) = activation_exit_balance_churn_limit {
+        project_pending_deposits(&state, per_epoch_churn).map_err(beacon_state_error)?
+    } else {
+        HashMap::new()
+    };
+
+    let (pending_activations, pending_exits) =
+        project_churn(&state, &deposit_epochs, activation_churn_limit, spec)
+            .map_err(beacon_state_error)?;
+
+    let next_exit_epoch =
+        compute_next_exit_epoch(&mut state, churn_limit, spec).map_err(beacon_state_error)?;
+
+    Ok(ChurnAnalysis {
+        epoch: current_epoch,
+        active_validators,
+        churn_limit,
+        activation_churn_limit,
+        activation_exit_balance_churn_limit,
+        activation_queue_length: pending_activations.len(),
+        exit_queue_length: pending_exits.len(),
+        next_exit_epoch,
+        pending_activations,
+        pending_exits,
+    })
+}
+
+/// Returns the pending activations and exits of `state`, sorted by their projected epochs.
+///
+/// `deposit_epochs` are the epochs returned by `project_pending_deposits`.
+fn project_churn<E: EthSpec>(
+    state: &BeaconState<E>,
+    deposit_epochs: &HashMap<u64, Epoch>,
+    activation_churn_limit: u64,
+    spec: &ChainSpec,
+) -> Result<(Vec<ProjectedChurn>, Vec<ProjectedChurn>), BeaconStateError> {
+    let current_epoch = state.current_epoch();
+    let fork_name = state.fork_name_unchecked();
+    let finalized_epoch = state.finalized_checkpoint().epoch;
+
+    let mut pending_activations = vec![];
+    let mut activation_queue = vec![];
+    let mut pending_exits = vec![];
+
+    for (index, validator) in state.validators().iter().enumerate() {
+        let validator_index = index as u64;
+
+        if validator.exit_epoch != spec.far_future_epoch && validator.exit_epoch > current_epoch {
+            pending_exits.push(ProjectedChurn {
+                validator_index,
+                projected_epoch: Some(validator.exit_epoch),
+            });
+        }
+
+        if validator.activation_epoch != spec.far_future_epoch {
+            if validator.activation_epoch > current_epoch {
+                pending_activations.push(ProjectedChurn {
+                    validator_index,
+                    projected_epoch: Some(validator.activation_epoch),
+                });
+            }
+            continue;
+        }
+
+        let eligibility_epoch = if validator.activation_eligibility_epoch != spec.far_future_epoch {
+            Some(validator.activation_eligibility_epoch)
+        } else if validator.is_eligible_for_activation_queue(spec, fork_name) {
+            // The validator will be marked as eligible at the end of the current epoch.
+            Some(current_epoch + 1)
+        } else {
+            // The validator will be marked as eligible at the end of the epoch after the one in
+            // which its deposit is processed.
+            deposit_epochs
+                .get(&validator_index)
+                .map(|deposit_epoch| *deposit_epoch + 2)
+        };
+
+        match eligibility_epoch {
+            Some(eligibility_epoch) => activation_queue.push((eligibility_epoch, validator_index)),
+            None => pending_activations.push(ProjectedChurn {
+                validator_index,
+                projected_epoch: None,
+            }),
+        }
+    }
+
+    // Validators are dequeued in order of eligibility, and then index.
+    activation_queue.sort_unstable();
+
+    let mut processing_epoch = current_epoch;
+    let mut activated_in_epoch = 0;
+    for (eligibility_epoch, validator_index) in activation_queue {
+        // A validator may only be dequeued once its eligibility has been finalized.
+        let first_processing_epoch = if eligibility_epoch <= finalized_epoch {
+            current_epoch
+        } else {
+            cmp::max(current_epoch, eligibility_epoch + 1)
+        };
+        if first_processing_epoch > processing_epoch {
+            processing_epoch = first_processing_epoch;
+            activated_in_epoch = 0;
+        }
+        if !fork_name.electra_enabled() {
+            if activated_in_epoch >= activation_churn_limit {
+                processing_epoch += 1;
+                activated_in_epoch = 0;
+            }
+            activated_in_epoch += 1;
+        }
+
+        pending_activations.push(ProjectedChurn {
+            validator_index,
+            projected_epoch: Some(spec.compute_activation_exit_epoch(processing_epoch)?),
+        });
+    }
+
+    let sort_key = |churn: &ProjectedChurn| {
+        (
+            churn.projected_epoch.unwrap_or(spec.far_future_epoch),
+            churn.validator_index,
+        )
+    };
+    pending_activations.sort_by_key(sort_key);
+    pending_exits.sort_by_key(sort_key);
+
+    Ok((pending_activations, pending_exits))
+}
+
+/// Returns the epoch at whose end the last of each validator's pending deposits will be processed.
+fn project_pending_deposits<E: EthSpec>(
+    state: &BeaconState<E>,
+    per_epoch_churn: u64,
+) -> Result<HashMap<u64, Epoch>, BeaconStateError> {
+    let mut epochs = HashMap::new();
+    if per_epoch_churn == 0 {
+        return Ok(epochs);
+    }
+
+    let mut epoch = state.current_epoch();
+    let mut available = state
+        .deposit_balance_to_consume()?
+        .safe_add(per_epoch_churn)?;
+    let mut processed = 0_u64;
+
+    for deposit in state.pending_balance_deposits()?.iter() {
+        while processed.safe_add(deposit.amount)? > available {
+            // Any balance left unconsumed carries over to the next epoch.
+            available = available.safe_sub(processed)?.safe_add(per_epoch_churn)?;
+            processed = 0;
+            epoch.safe_add_assign(1)?;
+        }
+        processed.safe_add_assign(deposit.amount)?;
+        epochs.insert(deposit.index, epoch);
+    }
+
+    Ok(epochs)
+}
+
+/// Returns the exit epoch which would be assigned to a validator with the minimum activation
+/// balance if it initiated an exit in the current epoch.
+///
+/// From Electra onwards this updates the exit churn of `state`, which must not be persisted.
+fn compute_next_exit_epoch<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    churn_limit: u64,
+    spec: &ChainSpec,
+) -> Result<Epoch, BeaconStateError> {
+    if state.fork_name_unchecked().electra_enabled() {
+        return state.compute_exit_epoch_and_update_churn(spec.min_activation_balance, spec);
+    }
+
+    let delayed_epoch = state.compute_activation_exit_epoch(state.current_epoch(), spec)?;
+    let mut exit_queue_epoch = state
+        .exit_cache()
+        .max_epoch()?
+        .map_or(delayed_epoch, |epoch| cmp::max(epoch, delayed_epoch));
+    if state.exit_cache().get_churn_at(exit_queue_epoch)? >= churn_limit {
+        exit_queue_epoch.safe_add_assign(1)?;
+    }
+    Ok(exit_queue_epoch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::BeaconChainHarness;
+    use types::{
+        ForkName, Hash256, MainnetEthSpec, PendingBalanceDeposit, PublicKeyBytes, Validator,
+    };
+
+    type E = MainnetEthSpec;
+
+    const VALIDATOR_COUNT: usize = 32;
+
+    fn genesis_state(fork_name: ForkName) -> (BeaconState<E>, ChainSpec) {
+        let spec = fork_name.make_genesis_spec(E::default_spec());
+        let harness = BeaconChainHarness::builder(E::default())
+            .spec(spec.clone())
+            .logger(logging::test_logger())
+            .deterministic_keypairs(VALIDATOR_COUNT)
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        let mut state = harness.get_current_state();
+        state.build_caches(&spec).unwrap();
+        (state, spec)
+    }
+
+    /// Add a validator which is not yet active, and return its index.
+    fn add_pending_validator(
+        state: &mut BeaconState<E>,
+        activation_eligibility_epoch: Epoch,
+        effective_balance: u64,
+        spec: &ChainSpec,
+    ) -> u64 {
+        state
+            .validators_mut()
+            .push(Validator {
+                pubkey: PublicKeyBytes::empty(),
+                withdrawal_credentials: Hash256::zero(),
+                effective_balance,
+                slashed: false,
+                activation_eligibility_epoch,
+                activation_epoch: spec.far_future_epoch,
+                exit_epoch: spec.far_future_epoch,
+                withdrawable_epoch: spec.far_future_epoch,
+            })
+            .unwrap();
+        state.balances_mut().push(effective_balance).unwrap();
+        state.validators().len() as u64 - 1
+    }
+
+    fn projected(churn: &[ProjectedChurn]) -> Vec<(u64, Option<Epoch>)> {
+        churn
+            .iter()
+            .map(|churn| (churn.validator_index, churn.projected_epoch))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn pending_deposits_carry_unconsumed_churn() {
+        let (mut state, _) = genesis_state(ForkName::Electra);
+        *state.deposit_balance_to_consume_mut().unwrap() = 5;
+        for (index, amount) in [(0, 60), (1, 40), (2, 50), (3, 200), (0, 10)] {
+            state
+                .pending_balance_deposits_mut()
+                .unwrap()
+                .push(PendingBalanceDeposit { index, amount })
+                .unwrap();
+        }
+
+        let epochs = project_pending_deposits(&state, 100).unwrap();
+        // The first two deposits fit within the churn and the carried over balance. The fourth
+        // deposit takes more than one epoch of churn, and the second deposit to validator 0 is
+        // processed with it.
+        assert_eq!(
+            epochs,
+            HashMap::from([
+                (0, Epoch::new(3)),
+                (1, Epoch::new(0)),
+                (2, Epoch::new(1)),
+                (3, Epoch::new(3)),
+            ])
+        );
+
+        assert!(project_pending_deposits(&state, 0).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn activations_are_limited_by_churn_before_electra() {
+        let (mut state, spec) = genesis_state(ForkName::Capella);
+        let queued = (0..6)
+            .map(|_| {
+                add_pending_validator(&mut state, Epoch::new(0), spec.max_effective_balance, &spec)
+            })
+            .collect::<Vec<_>>();
+        // Eligibility which has not been finalized delays activation.
+        let unfinalized =
+            add_pending_validator(&mut state, Epoch::new(2), spec.max_effective_balance, &spec);
+        state.validators_mut().get_mut(0).unwrap().exit_epoch = Epoch::new(6);
+
+        let (activations, exits) = project_churn(&state, &HashMap::new(), 4, &spec).unwrap();
+        let activation_epoch = |epoch| Some(spec.compute_activation_exit_epoch(epoch).unwrap());
+        let mut expected = queued[..4]
+            .iter()
+            .map(|index| (*index, activation_epoch(Epoch::new(0))))
+            .collect::<Vec<_>>();
+        expected.extend(
+            queued[4..]
+                .iter()
+                .map(|index| (*index, activation_epoch(Epoch::new(1)))),
+        );
+        expected.push((unfinalized, activation_epoch(Epoch::new(3))));
+        assert_eq!(projected(&activations), expected);
+        assert_eq!(projected(&exits), vec![(0, Some(Epoch::new(6)))]);
+    }
+
+    #[tokio::test]
+    async fn activations_follow_pending_deposits_from_electra() {
+        let (mut state, spec) = genesis_state(ForkName::Electra);
+        let deposited = add_pending_validator(&mut state, spec.far_future_epoch, 0, &spec);
+        let undeposited = add_pending_validator(&mut state, spec.far_future_epoch, 0, &spec);
+        let eligible = add_pending_validator(
+            &mut state,
+            spec.far_future_epoch,
+            spec.min_activation_balance,
+            &spec,
+        );
+
+        let deposit_epochs = HashMap::from([(deposited, Epoch::new(1))]);
+        let (activations, _) = project_churn(&state, &deposit_epochs, 0, &spec).unwrap();
+        let activation_epoch = |epoch| Some(spec.compute_activation_exit_epoch(epoch).unwrap());
+        assert_eq!(
+            projected(&activations),
+            vec![
+                // Eligible at the end of the current epoch, and dequeued in the next.
+                (eligible, activation_epoch(Epoch::new(2))),
+                // Eligible at the end of the epoch after the deposit is processed.
+                (deposited, activation_epoch(Epoch::new(4))),
+                (undeposited, None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn next_exit_epoch_follows_exit_queue_before_electra() {
+        let (mut state, spec) = genesis_state(ForkName::Capella);
+        let churn_limit = state.get_validator_churn_limit(&spec).unwrap();
+        let queue_epoch = spec.compute_activation_exit_epoch(Epoch::new(0)).unwrap() + 2;
+
+        for index in 0..churn_limit as usize - 1 {
+            state.validators_mut().get_mut(index).unwrap().exit_epoch = queue_epoch;
+        }
+        state.drop_all_caches().unwrap();
+        state.build_exit_cache(&spec).unwrap();
+        assert_eq!(
+            compute_next_exit_epoch(&mut state, churn_limit, &spec).unwrap(),
+            queue_epoch
+        );
+
+        // Once the churn of the last epoch in the queue is full, exits join the next epoch.
+        state
+            .validators_mut()
+            .get_mut(churn_limit as usize - 1)
+            .unwrap()
+            .exit_epoch = queue_epoch;
+        state.drop_all_caches().unwrap();
+        state.build_exit_cache(&spec).unwrap();
+        assert_eq!(
+            compute_next_exit_epoch(&mut state, churn_limit, &spec).unwrap(),
+            queue_epoch + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn next_exit_epoch_follows_exit_churn_from_electra() {
+        let (mut state, spec) = genesis_state(ForkName::Electra);
+        let per_epoch_churn = state.get_activation_exit_churn_limit(&spec).unwrap();
+        assert!(per_epoch_churn >= spec.min_activation_balance);
+
+        let earliest_exit_epoch = Epoch::new(10);
+        *state.earliest_exit_epoch_mut().unwrap() = earliest_exit_epoch;
+        *state.exit_balance_to_consume_mut().unwrap() = spec.min_activation_balance;
+
+        // The remaining churn of the earliest exit epoch fits one more exit, which consumes it.
+        assert_eq!(
+            compute_next_exit_epoch(&mut state, 0, &spec).unwrap(),
+            earliest_exit_epoch
+        );
+        assert_eq!(state.exit_balance_to_consume().unwrap(), 0);
+        assert_eq!(
+            compute_next_exit_epoch(&mut state, 0, &spec).unwrap(),
+            earliest_exit_epoch + 1
+        );
+    }
+}
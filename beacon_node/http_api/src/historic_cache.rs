@@ -0,0 +1,151 @@
+//! A small cache of serialized responses for finalized blocks and states.
+//!
+//! Finalized blocks and states never change, so a response can be identified by the root of the
+//! object it contains. This allows repeated requests for the same historic data (e.g. from block
+//! explorers) to be served without reloading it from the freezer database, and allows clients to
+//! skip re-downloading it entirely by sending `If-None-Match`.
+
+use crate::metrics;
+use crate::version::{add_consensus_version_header, add_ssz_content_type_header};
+use bytes::Bytes;
+use eth2::CONTENT_TYPE_HEADER;
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::num::NonZeroUsize;
+use types::{ForkName, Hash256};
+use warp::http::StatusCode;
+use warp::hyper::Body;
+use warp::reply::{self, Reply, Response};
+
+/// The default number of responses to cache.
+///
+/// Serialized states can be hundreds of megabytes, so the cache is disabled unless the operator
+/// opts in. ETags and `If-None-Match` are honoured regardless.
+pub const DEFAULT_HISTORIC_CACHE_SIZE: usize = 0;
+
+pub const ETAG_HEADER: &str = "ETag";
+pub const IF_NONE_MATCH_HEADER: &str = "If-None-Match";
+const JSON_CONTENT_TYPE_HEADER: &str = "application/json";
+
+/// An endpoint and encoding whose responses may be cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HistoricEndpoint {
+    Block { version: u64, ssz: bool },
+    DebugState { version: u64, ssz: bool },
+}
+
+impl HistoricEndpoint {
+    /// Return the strong ETag for this endpoint's response for the object with `root`.
+    ///
+    /// The endpoint version and encoding are included since each produces a different body.
+    pub fn etag(&self, root: Hash256) -> String {
+        let (name, version, ssz) = match self {
+            Self::Block { version, ssz } => ("block", version, ssz),
+            Self::DebugState { version, ssz } => ("state", version, ssz),
+        };
+        let encoding = if *ssz { "ssz" } else { "json" };
+        format!("\"{:?}-{}-v{}-{}\"", root, name, version, encoding)
+    }
+}
+
+/// A serialized response body, along with the information needed to rebuild its headers.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    body: Bytes,
+    ssz: bool,
+    fork_name: ForkName,
+}
+
+impl CachedResponse {
+    pub fn ssz(body: Vec<u8>, fork_name: ForkName) -> Self {
+        Self {
+            body: body.into(),
+            ssz: true,
+            fork_name,
+        }
+    }
+
+    pub fn json<T: Serialize>(value: &T, fork_name: ForkName) -> Result<Self, warp::Rejection> {
+        let body = serde_json::to_vec(value).map_err(|e| {
+            warp_utils::reject::custom_server_error(format!("failed to serialize response: {}", e))
+        })?;
+        Ok(Self {
+            body: body.into(),
+            ssz: false,
+            fork_name,
+        })
+    }
+
+    /// Build the HTTP response, adding `etag` if the response is cacheable.
+    pub fn into_response(self, etag: Option<String>) -> Response {
+        let response = Response::new(Body::from(self.body));
+        let response = if self.ssz {
+            add_ssz_content_type_header(response)
+        } else {
+            reply::with_header(response, CONTENT_TYPE_HEADER, JSON_CONTENT_TYPE_HEADER)
+                .into_response()
+        };
+        let response = add_consensus_version_header(response, self.fork_name);
+        match etag {
+            Some(etag) => reply::with_header(response, ETAG_HEADER, etag).into_response(),
+            None => response,
+        }
+    }
+}
+
+/// An LRU cache of responses, keyed by endpoint and block or state root.
+///
+/// A cache with a size of zero is disabled and never stores anything.
+pub struct HistoricResponseCache {
+    responses: Option<Mutex<LruCache<(HistoricEndpoint, Hash256), CachedResponse>>>,
+}
+
+impl HistoricResponseCache {
+    pub fn new(size: usize) -> Self {
+        Self {
+            responses: NonZeroUsize::new(size).map(|size| Mutex::new(LruCache::new(size))),
+        }
+    }
+
+    pub fn get(&self, endpoint: HistoricEndpoint, root: Hash256) -> Option<CachedResponse> {
+        let response = self
+            .responses
+            .as_ref()?
+            .lock()
+            .get(&(endpoint, root))
+            .cloned();
+        if response.is_some() {
+            metrics::inc_counter(&metrics::HTTP_API_HISTORIC_CACHE_HITS_TOTAL);
+        } else {
+            metrics::inc_counter(&metrics::HTTP_API_HISTORIC_CACHE_MISSES_TOTAL);
+        }
+        response
+    }
+
+    pub fn insert(&self, endpoint: HistoricEndpoint, root: Hash256, response: CachedResponse) {
+        if let Some(responses) = &self.responses {
+            responses.lock().put((endpoint, root), response);
+        }
+    }
+}
+
+/// Returns `true` if the `If-None-Match` header value matches `etag`.
+///
+/// As per RFC 9110, `If-None-Match` uses weak comparison so any `W/` prefix is ignored.
+fn if_none_match_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// Return a `304 Not Modified` response if the client already holds the response for `etag`.
+pub fn not_modified_response(if_none_match: Option<&str>, etag: &str) -> Option<Response> {
+    if_none_match
+        .filter(|if_none_match| if_none_match_matches(if_none_match, etag))
+        .map(|_| {
+            let response = reply::with_status(reply(), StatusCode::NOT_MODIFIED);
+            reply::with_header(response, ETAG_HEADER, etag).into_response()
+        })
+}
@@ -12,8 +12,11 @@ mod block_packing_efficiency;
 mod block_rewards;
 mod build_block_contents;
 mod builder_states;
+mod churn_analysis;
 mod database;
+mod historic_cache;
 mod metrics;
+mod observed_block_packing;
 mod produce_block;
 mod proposer_duties;
 mod publish_attestations;
@@ -22,6 +25,7 @@ mod standard_block_rewards;
 mod state_id;
 mod sync_committee_rewards;
 mod sync_committees;
+mod system_profile;
 mod task_spawner;
 pub mod test_utils;
 mod ui;
@@ -33,9 +37,11 @@ mod version;
 use crate::produce_block::{produce_blinded_block_v2, produce_block_v2, produce_block_v3};
 use crate::version::fork_versioned_response;
 use beacon_chain::{
-    attestation_verification::VerifiedAttestation, observed_operations::ObservationOutcome,
-    validator_monitor::timestamp_now, AttestationError as AttnError, BeaconChain, BeaconChainError,
-    BeaconChainTypes, WhenSlotSkipped,
+    attestation_verification::VerifiedAttestation,
+    observed_operations::ObservationOutcome,
+    validator_monitor::{timestamp_now, MAX_API_REGISTERED_VALIDATORS},
+    AttestationError as AttnError, BeaconChain, BeaconChainError, BeaconChainTypes,
+    WhenSlotSkipped,
 };
 use beacon_processor::{work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorSend};
 pub use block_id::BlockId;
@@ -47,13 +53,21 @@ use eth2::types::{
     PublishBlockRequest, ValidatorBalancesRequestBody, ValidatorId, ValidatorStatus,
     ValidatorsRequestBody,
 };
-use eth2::{CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
+use eth2::{
+    CONSENSUS_BLOCK_VALUE_HEADER, CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER,
+    EXECUTION_PAYLOAD_BLINDED_HEADER, EXECUTION_PAYLOAD_VALUE_HEADER, SSZ_CONTENT_TYPE_HEADER,
+};
+use historic_cache::{
+    not_modified_response, CachedResponse, HistoricEndpoint, HistoricResponseCache,
+    DEFAULT_HISTORIC_CACHE_SIZE, ETAG_HEADER, IF_NONE_MATCH_HEADER,
+};
 use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
 use lighthouse_version::version_with_platform;
-use logging::SSELoggingComponents;
+use logging::{LogLevels, SSELoggingComponents};
 use network::{NetworkMessage, NetworkSenders, ValidatorSubscriptionMessage};
 use operation_pool::ReceivedPreCapella;
 use parking_lot::RwLock;
+use proto_array::core::{ProtoArray, ProtoNode};
 pub use publish_blocks::{
     publish_blinded_block, publish_block, reconstruct_block, ProvenancedBlock,
 };
@@ -62,6 +76,7 @@ use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
 pub use state_id::StateId;
+use std::fs;
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
@@ -71,18 +86,19 @@ use sysinfo::{System, SystemExt};
 use system_health::{observe_nat, observe_system_health_bn};
 use task_spawner::{Priority, TaskSpawner};
 use tokio::sync::{
+    broadcast::Receiver,
     mpsc::{Sender, UnboundedSender},
     oneshot,
 };
 use tokio_stream::{
     wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
-    StreamExt,
+    Stream, StreamExt,
 };
 use types::{
     fork_versioned_response::EmptyMetadata, Attestation, AttestationData, AttestationShufflingId,
     AttesterSlashing, BeaconStateError, CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName,
-    ForkVersionedResponse, Hash256, ProposerPreparationData, ProposerSlashing, RelativeEpoch,
-    SignedAggregateAndProof, SignedBlindedBeaconBlock, SignedBlsToExecutionChange,
+    ForkVersionedResponse, Hash256, ProposerPreparationData, ProposerSlashing, PublicKeyBytes,
+    RelativeEpoch, SignedAggregateAndProof, SignedBlindedBeaconBlock, SignedBlsToExecutionChange,
     SignedContributionAndProof, SignedValidatorRegistrationData, SignedVoluntaryExit, Slot,
     SyncCommitteeMessage, SyncContributionData,
 };
@@ -133,6 +149,7 @@ pub struct Context<T: BeaconChainTypes> {
     pub beacon_processor_reprocess_send: Option<Sender<ReprocessQueueMessage>>,
     pub eth1_service: Option<eth1::Service>,
     pub sse_logging_components: Option<SSELoggingComponents>,
+    pub log_levels: Option<LogLevels>,
     pub log: Logger,
 }
 
@@ -151,6 +168,16 @@ pub struct Config {
     #[serde(with = "eth2::types::serde_status_code")]
     pub duplicate_block_status_code: StatusCode,
     pub enable_light_client_server: bool,
+    pub historic_cache_size: usize,
+    /// Maximum number of slots to replay when loading a state from the freezer database.
+    pub state_replay_budget: Option<u64>,
+    /// Allow validator clients to add keys to the validator monitor.
+    pub enable_validator_monitor_registration: bool,
+    /// Path to a file containing the token which must be sent in an `Authorization: Bearer` header
+    /// to use authenticated endpoints.
+    pub api_token_path: Option<PathBuf>,
+    /// Allow the log levels to be changed with `POST lighthouse/logging/levels`.
+    pub enable_log_level_changes: bool,
 }
 
 impl Default for Config {
@@ -167,6 +194,11 @@ impl Default for Config {
             enable_beacon_processor: true,
             duplicate_block_status_code: StatusCode::ACCEPTED,
             enable_light_client_server: false,
+            historic_cache_size: DEFAULT_HISTORIC_CACHE_SIZE,
+            state_replay_budget: None,
+            enable_validator_monitor_registration: false,
+            api_token_path: None,
+            enable_log_level_changes: false,
         }
     }
 }
@@ -302,6 +334,27 @@ fn enable(is_enabled: bool) -> impl Filter<Extract = (), Error = warp::Rejection
         .untuple_one()
 }
 
+/// Returns a filter which rejects requests without an `Authorization: Bearer` header carrying
+/// `api_token`. All requests are rejected if there is no `api_token`.
+fn api_token_filter(
+    api_token: Option<String>,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    let api_token = Arc::new(api_token.map(|token| format!("Bearer {}", token)));
+    warp::any()
+        .map(move || api_token.clone())
+        .and(warp::filters::header::header("Authorization"))
+        .and_then(
+            |api_token: Arc<Option<String>>, header: String| async move {
+                if api_token.as_deref() == Some(header.as_str()) {
+                    Ok(())
+                } else {
+                    Err(warp_utils::reject::invalid_auth(header))
+                }
+            },
+        )
+        .untuple_one()
+}
+
 /// Creates a server that will serve requests using information from `ctx`.
 ///
 /// The server will shut down gracefully when the `shutdown` future resolves.
@@ -326,9 +379,22 @@ pub fn serve<T: BeaconChainTypes>(
 
     // Configure CORS.
     let cors_builder = {
+        // Browser-based clients must be permitted to send and read the fork version headers used
+        // by the block endpoints, and the cache validators used by the historic endpoints.
         let builder = warp::cors()
             .allow_methods(vec!["GET", "POST"])
-            .allow_headers(vec!["Content-Type"]);
+            .allow_headers(vec![
+                CONTENT_TYPE_HEADER,
+                CONSENSUS_VERSION_HEADER,
+                IF_NONE_MATCH_HEADER,
+            ])
+            .expose_headers(vec![
+                CONSENSUS_VERSION_HEADER,
+                EXECUTION_PAYLOAD_BLINDED_HEADER,
+                EXECUTION_PAYLOAD_VALUE_HEADER,
+                CONSENSUS_BLOCK_VALUE_HEADER,
+                ETAG_HEADER,
+            ]);
 
         warp_utils::cors::set_builder_origins(
             builder,
@@ -345,6 +411,19 @@ pub fn serve<T: BeaconChainTypes>(
         ));
     }
 
+    let api_token = config
+        .api_token_path
+        .as_ref()
+        .map(|path| {
+            fs::read_to_string(path)
+                .map(|token| token.trim().to_string())
+                .map_err(|e| {
+                    Error::Other(format!("Unable to read API token from {:?}: {:?}", path, e))
+                })
+        })
+        .transpose()?;
+    let api_token_filter = api_token_filter(api_token);
+
     // Create a filter that extracts the endpoint version.
     let any_version = warp::path(API_PREFIX).and(warp::path::param::<EndpointVersion>().or_else(
         |_| async move {
@@ -494,6 +573,19 @@ pub fn serve<T: BeaconChainTypes>(
     let inner_components = ctx.sse_logging_components.clone();
     let sse_component_filter = warp::any().map(move || inner_components.clone());
 
+    // Create a `warp` filter that provides access to the runtime-configurable log levels.
+    let inner_ctx = ctx.clone();
+    let log_levels_filter = warp::any()
+        .map(move || inner_ctx.log_levels.clone())
+        .and_then(|log_levels| async move {
+            match log_levels {
+                Some(log_levels) => Ok(log_levels),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "Log levels cannot be changed at runtime.".to_string(),
+                )),
+            }
+        });
+
     // Create a `warp` filter that provides access to local system information.
     let system_info = Arc::new(RwLock::new(sysinfo::System::new()));
     {
@@ -539,6 +631,12 @@ pub fn serve<T: BeaconChainTypes>(
     let reprocess_send_filter = warp::any().map(move || beacon_processor_reprocess_send.clone());
 
     let duplicate_block_status_code = ctx.config.duplicate_block_status_code;
+    let state_replay_budget = ctx.config.state_replay_budget;
+
+    // Create a `warp` filter that provides access to the cache of finalized block and state
+    // responses.
+    let historic_cache = Arc::new(HistoricResponseCache::new(config.historic_cache_size));
+    let historic_cache_filter = warp::any().map(move || historic_cache.clone());
 
     /*
      *
@@ -1562,40 +1660,63 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path::end())
         .and(warp::header::optional::<api_types::Accept>("accept"))
+        .and(warp::header::optional::<String>(IF_NONE_MATCH_HEADER))
+        .and(historic_cache_filter.clone())
         .then(
             |endpoint_version: EndpointVersion,
              block_id: BlockId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             accept_header: Option<api_types::Accept>| {
+             accept_header: Option<api_types::Accept>,
+             if_none_match: Option<String>,
+             historic_cache: Arc<HistoricResponseCache>| {
                 task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    let ssz = matches!(accept_header, Some(api_types::Accept::Ssz));
+                    let endpoint = HistoricEndpoint::Block {
+                        version: endpoint_version.0,
+                        ssz,
+                    };
+
+                    // Only finalized blocks are immutable, so only they are cached.
+                    let (root, execution_optimistic, finalized) = block_id.root(&chain)?;
+                    let etag = (finalized && !execution_optimistic).then(|| endpoint.etag(root));
+                    let block_id = if let Some(etag) = &etag {
+                        if let Some(response) =
+                            not_modified_response(if_none_match.as_deref(), etag)
+                        {
+                            return Ok(response);
+                        }
+                        if let Some(response) = historic_cache.get(endpoint, root) {
+                            return Ok(response.into_response(etag.clone()));
+                        }
+                        // Load the block by root so the response matches the cache key.
+                        BlockId::from_root(root)
+                    } else {
+                        block_id
+                    };
+
                     let (block, execution_optimistic, finalized) =
                         block_id.full_block(&chain).await?;
                     let fork_name = block
                         .fork_name(&chain.spec)
                         .map_err(inconsistent_fork_rejection)?;
 
-                    match accept_header {
-                        Some(api_types::Accept::Ssz) => Response::builder()
-                            .status(200)
-                            .body(block.as_ssz_bytes().into())
-                            .map(|res: Response<Body>| add_ssz_content_type_header(res))
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            }),
-                        _ => execution_optimistic_finalized_fork_versioned_response(
+                    let response = if ssz {
+                        CachedResponse::ssz(block.as_ssz_bytes(), fork_name)
+                    } else {
+                        let res = execution_optimistic_finalized_fork_versioned_response(
                             endpoint_version,
                             fork_name,
                             execution_optimistic,
                             finalized,
                             block,
-                        )
-                        .map(|res| warp::reply::json(&res).into_response()),
+                        )?;
+                        CachedResponse::json(&res, fork_name)?
+                    };
+                    if etag.is_some() {
+                        historic_cache.insert(endpoint, root, response.clone());
                     }
-                    .map(|resp| add_consensus_version_header(resp, fork_name))
+                    Ok(response.into_response(etag))
                 })
             },
         );
@@ -2647,16 +2768,46 @@ pub fn serve<T: BeaconChainTypes>(
         }))
         .and(warp::path::end())
         .and(warp::header::optional::<api_types::Accept>("accept"))
+        .and(warp::header::optional::<String>(IF_NONE_MATCH_HEADER))
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
+        .and(historic_cache_filter.clone())
         .then(
-            |endpoint_version: EndpointVersion,
-             state_id: StateId,
-             accept_header: Option<api_types::Accept>,
-             task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_response_task(Priority::P1, move || match accept_header {
-                    Some(api_types::Accept::Ssz) => {
+            move |endpoint_version: EndpointVersion,
+                  state_id: StateId,
+                  accept_header: Option<api_types::Accept>,
+                  if_none_match: Option<String>,
+                  task_spawner: TaskSpawner<T::EthSpec>,
+                  chain: Arc<BeaconChain<T>>,
+                  historic_cache: Arc<HistoricResponseCache>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let ssz = matches!(accept_header, Some(api_types::Accept::Ssz));
+                    let endpoint = HistoricEndpoint::DebugState {
+                        version: endpoint_version.0,
+                        ssz,
+                    };
+
+                    state_id.check_replay_budget(&chain, state_replay_budget)?;
+
+                    // Only finalized states are immutable, so only they are cached.
+                    let (root, execution_optimistic, finalized) = state_id.root(&chain)?;
+                    let etag = (finalized && !execution_optimistic).then(|| endpoint.etag(root));
+                    let state_id = if let Some(etag) = &etag {
+                        if let Some(response) =
+                            not_modified_response(if_none_match.as_deref(), etag)
+                        {
+                            return Ok(response);
+                        }
+                        if let Some(response) = historic_cache.get(endpoint, root) {
+                            return Ok(response.into_response(etag.clone()));
+                        }
+                        // Load the state by root so the response matches the cache key.
+                        StateId(api_types::StateId::Root(root))
+                    } else {
+                        state_id
+                    };
+
+                    let response = if ssz {
                         // We can ignore the optimistic status for the "fork" since it's a
                         // specification constant that doesn't change across competing heads of the
                         // beacon chain.
@@ -2664,39 +2815,29 @@ pub fn serve<T: BeaconChainTypes>(
                         let fork_name = state
                             .fork_name(&chain.spec)
                             .map_err(inconsistent_fork_rejection)?;
-                        Response::builder()
-                            .status(200)
-                            .body(state.as_ssz_bytes().into())
-                            .map(|res: Response<Body>| add_ssz_content_type_header(res))
-                            .map(|resp: warp::reply::Response| {
-                                add_consensus_version_header(resp, fork_name)
-                            })
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            })
+                        CachedResponse::ssz(state.as_ssz_bytes(), fork_name)
+                    } else {
+                        state_id.map_state_and_execution_optimistic_and_finalized(
+                            &chain,
+                            |state, execution_optimistic, finalized| {
+                                let fork_name = state
+                                    .fork_name(&chain.spec)
+                                    .map_err(inconsistent_fork_rejection)?;
+                                let res = execution_optimistic_finalized_fork_versioned_response(
+                                    endpoint_version,
+                                    fork_name,
+                                    execution_optimistic,
+                                    finalized,
+                                    &state,
+                                )?;
+                                CachedResponse::json(&res, fork_name)
+                            },
+                        )?
+                    };
+                    if etag.is_some() {
+                        historic_cache.insert(endpoint, root, response.clone());
                     }
-                    _ => state_id.map_state_and_execution_optimistic_and_finalized(
-                        &chain,
-                        |state, execution_optimistic, finalized| {
-                            let fork_name = state
-                                .fork_name(&chain.spec)
-                                .map_err(inconsistent_fork_rejection)?;
-                            let res = execution_optimistic_finalized_fork_versioned_response(
-                                endpoint_version,
-                                fork_name,
-                                execution_optimistic,
-                                finalized,
-                                &state,
-                            )?;
-                            Ok(add_consensus_version_header(
-                                warp::reply::json(&res).into_response(),
-                                fork_name,
-                            ))
-                        },
-                    ),
+                    Ok(response.into_response(etag))
                 })
             },
         );
@@ -2758,30 +2899,7 @@ pub fn serve<T: BeaconChainTypes>(
                     let fork_choice_nodes = proto_array
                         .nodes
                         .iter()
-                        .map(|node| {
-                            let execution_status = if node.execution_status.is_execution_enabled() {
-                                Some(node.execution_status.to_string())
-                            } else {
-                                None
-                            };
-
-                            ForkChoiceNode {
-                                slot: node.slot,
-                                block_root: node.root,
-                                parent_root: node
-                                    .parent
-                                    .and_then(|index| proto_array.nodes.get(index))
-                                    .map(|parent| parent.root),
-                                justified_epoch: node.justified_checkpoint.epoch,
-                                finalized_epoch: node.finalized_checkpoint.epoch,
-                                weight: node.weight,
-                                validity: execution_status,
-                                execution_block_hash: node
-                                    .execution_status
-                                    .block_hash()
-                                    .map(|block_hash| block_hash.into_root()),
-                            }
-                        })
+                        .map(|node| fork_choice_node(proto_array, node))
                         .collect::<Vec<_>>();
                     Ok(ForkChoice {
                         justified_checkpoint: proto_array.justified_checkpoint,
@@ -2907,11 +3025,13 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("node"))
         .and(warp::path("health"))
         .and(warp::path::end())
+        .and(warp::query::<api_types::NodeHealthQuery>())
         .and(task_spawner_filter.clone())
         .and(network_globals.clone())
         .and(chain_filter.clone())
         .then(
-            |task_spawner: TaskSpawner<T::EthSpec>,
+            |query: api_types::NodeHealthQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
              network_globals: Arc<NetworkGlobals<T::EthSpec>>,
              chain: Arc<BeaconChain<T>>| {
                 async move {
@@ -2923,21 +3043,43 @@ pub fn serve<T: BeaconChainTypes>(
 
                     task_spawner
                         .blocking_response_task(Priority::P0, move || {
+                            let syncing_status = match query.syncing_status {
+                                Some(code) => (100..=599)
+                                    .contains(&code)
+                                    .then(|| warp::http::StatusCode::from_u16(code).ok())
+                                    .flatten()
+                                    .ok_or_else(|| {
+                                        warp_utils::reject::custom_bad_request(format!(
+                                            "invalid syncing_status: {}",
+                                            code
+                                        ))
+                                    })?,
+                                None => warp::http::StatusCode::PARTIAL_CONTENT,
+                            };
+
                             let is_optimistic = chain
                                 .is_optimistic_or_invalid_head()
                                 .map_err(warp_utils::reject::beacon_chain_error)?;
 
-                            let is_syncing = !network_globals.sync_state.read().is_synced();
+                            let is_syncing = if let Some(tolerance) = query.syncing_tolerance {
+                                let head_slot = chain.canonical_head.cached_head().head_slot();
+                                let current_slot =
+                                    chain.slot_clock.now_or_genesis().ok_or_else(|| {
+                                        warp_utils::reject::custom_server_error(
+                                            "Unable to read slot clock".into(),
+                                        )
+                                    })?;
+                                head_slot + tolerance < current_slot
+                            } else {
+                                !network_globals.sync_state.read().is_synced()
+                            };
 
                             if el_offline {
                                 Err(warp_utils::reject::not_synced(
                                     "execution layer is offline".to_string(),
                                 ))
                             } else if is_syncing || is_optimistic {
-                                Ok(warp::reply::with_status(
-                                    warp::reply(),
-                                    warp::http::StatusCode::PARTIAL_CONTENT,
-                                ))
+                                Ok(warp::reply::with_status(warp::reply(), syncing_status))
                             } else {
                                 Ok(warp::reply::with_status(
                                     warp::reply(),
@@ -3486,6 +3628,9 @@ pub fn serve<T: BeaconChainTypes>(
 
                     // Import aggregate attestations
                     for (index, verified_aggregate) in verified_aggregates {
+                        // Report at most one failure per aggregate, as required by the indexed
+                        // error format.
+                        let mut errors = vec![];
                         if let Err(e) = chain.apply_attestation_to_fork_choice(&verified_aggregate) {
                             error!(log,
                                     "Failure applying verified aggregate attestation to fork choice";
@@ -3495,7 +3640,7 @@ pub fn serve<T: BeaconChainTypes>(
                                     "attestation_index" => verified_aggregate.attestation().committee_index(),
                                     "attestation_slot" => verified_aggregate.attestation().data().slot,
                                 );
-                            failures.push(api_types::Failure::new(index, format!("Fork choice: {:?}", e)));
+                            errors.push(format!("Fork choice: {:?}", e));
                         }
                         if let Err(e) = chain.add_to_block_inclusion_pool(verified_aggregate) {
                             warn!(
@@ -3504,11 +3649,15 @@ pub fn serve<T: BeaconChainTypes>(
                                 "error" => ?e,
                                 "request_index" => index,
                             );
-                            failures.push(api_types::Failure::new(index, format!("Op pool: {:?}", e)));
+                            errors.push(format!("Op pool: {:?}", e));
+                        }
+                        if !errors.is_empty() {
+                            failures.push(api_types::Failure::new(index, errors.join(", ")));
                         }
                     }
 
                     if !failures.is_empty() {
+                        failures.sort_by_key(|failure| failure.index);
                         Err(warp_utils::reject::indexed_bad_request("error processing aggregate and proofs".to_string(),
                                                                     failures,
                         ))
@@ -3625,8 +3774,12 @@ pub fn serve<T: BeaconChainTypes>(
                         .ok_or(BeaconChainError::ExecutionLayerMissing)
                         .map_err(warp_utils::reject::beacon_chain_error)?;
 
+                    // Accept preparations prior to genesis so that the first proposers are
+                    // known to the execution layer in time for the first slot.
                     let current_slot = chain
-                        .slot()
+                        .slot_clock
+                        .now_or_genesis()
+                        .ok_or(BeaconChainError::UnableToReadSlot)
                         .map_err(warp_utils::reject::beacon_chain_error)?;
                     let current_epoch = current_slot.epoch(T::EthSpec::slots_per_epoch());
 
@@ -3972,13 +4125,21 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("health"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .then(|task_spawner: TaskSpawner<T::EthSpec>| {
-            task_spawner.blocking_json_task(Priority::P0, move || {
-                eth2::lighthouse::Health::observe()
-                    .map(api_types::GenericResponse::from)
-                    .map_err(warp_utils::reject::custom_bad_request)
-            })
-        });
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    let health = eth2::lighthouse::Health::observe()
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    let chain = chain
+                        .latest_chain_health_report()
+                        .map_err(warp_utils::reject::beacon_chain_error)?;
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::BeaconNodeHealth { health, chain },
+                    ))
+                })
+            },
+        );
 
     // GET lighthouse/ui/health
     let get_lighthouse_ui_health = warp::path("lighthouse")
@@ -4158,6 +4319,48 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/fork_choice
+    let get_lighthouse_fork_choice = warp::path("lighthouse")
+        .and(warp::path("fork_choice"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let beacon_fork_choice = chain.canonical_head.fork_choice_read_lock();
+
+                    let proto_array = beacon_fork_choice.proto_array().core_proto_array();
+
+                    let fork_choice_nodes = proto_array
+                        .nodes
+                        .iter()
+                        .map(|node| eth2::lighthouse::LighthouseForkChoiceNode {
+                            node: fork_choice_node(proto_array, node),
+                            unrealized_justified_epoch: node
+                                .unrealized_justified_checkpoint
+                                .map(|checkpoint| checkpoint.epoch),
+                            unrealized_finalized_epoch: node
+                                .unrealized_finalized_checkpoint
+                                .map(|checkpoint| checkpoint.epoch),
+                        })
+                        .collect::<Vec<_>>();
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::LighthouseForkChoice {
+                            justified_checkpoint: proto_array.justified_checkpoint,
+                            finalized_checkpoint: proto_array.finalized_checkpoint,
+                            unrealized_justified_checkpoint: beacon_fork_choice
+                                .unrealized_justified_checkpoint(),
+                            unrealized_finalized_checkpoint: beacon_fork_choice
+                                .unrealized_finalized_checkpoint(),
+                            proposer_boost_root: beacon_fork_choice.proposer_boost_root(),
+                            fork_choice_nodes,
+                        },
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
     let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
         .and(warp::path("validator_inclusion"))
@@ -4309,6 +4512,24 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/system/profile
+    let inner_beacon_processor_send = ctx.beacon_processor_send.clone();
+    let get_lighthouse_system_profile = warp::path("lighthouse")
+        .and(warp::path("system"))
+        .and(warp::path("profile"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            move |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                let beacon_processor_send = inner_beacon_processor_send.clone();
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    system_profile::profile(chain, beacon_processor_send)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     // POST lighthouse/database/reconstruct
     let post_lighthouse_database_reconstruct = database_path
         .and(warp::path("reconstruct"))
@@ -4328,6 +4549,134 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // POST lighthouse/validator_monitor/register
+    let post_lighthouse_validator_monitor_register = warp::path("lighthouse")
+        .and(warp::path("validator_monitor"))
+        .and(warp::path("register"))
+        .and(warp::path::end())
+        .and(api_token_filter.clone())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |pubkeys: Vec<PublicKeyBytes>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    if pubkeys.len() > MAX_API_REGISTERED_VALIDATORS {
+                        return Err(warp_utils::reject::custom_bad_request(format!(
+                            "too many validators: {} > {}",
+                            pubkeys.len(),
+                            MAX_API_REGISTERED_VALIDATORS
+                        )));
+                    }
+                    chain
+                        .validator_monitor
+                        .write()
+                        .auto_register_local_pubkeys(&pubkeys);
+                    Ok(())
+                })
+            },
+        );
+
+    // GET lighthouse/logging/levels
+    let get_lighthouse_logging_levels = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path("levels"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(log_levels_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, log_levels: LogLevels| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::LogLevelsData {
+                            levels: log_levels.to_string(),
+                        },
+                    ))
+                })
+            },
+        );
+
+    // POST lighthouse/logging/levels
+    let post_lighthouse_logging_levels = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path("levels"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(log_levels_filter)
+        .and(log_filter.clone())
+        .then(
+            |data: eth2::lighthouse::LogLevelsData,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             log_levels: LogLevels,
+             log: Logger| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    log_levels
+                        .update(&data.levels)
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    info!(
+                        log,
+                        "Updated log levels";
+                        "levels" => %log_levels,
+                    );
+                    Ok(())
+                })
+            },
+        );
+
+    // POST lighthouse/peers/target
+    let post_lighthouse_peers_target = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("target"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(network_tx_filter.clone())
+        .then(
+            |data: eth2::lighthouse::TargetPeersData,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    if data.target_peers == 0 {
+                        return Err(warp_utils::reject::custom_bad_request(
+                            "target_peers must be greater than zero".to_string(),
+                        ));
+                    }
+                    publish_network_message(
+                        &network_tx,
+                        NetworkMessage::UpdateTargetPeers {
+                            target_peers: data.target_peers,
+                            max_pruned_peers_per_heartbeat: data.max_pruned_peers_per_heartbeat,
+                        },
+                    )
+                })
+            },
+        );
+
+    // GET lighthouse/analysis/gossip_arrival_times
+    let get_lighthouse_gossip_arrival_times = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("gossip_arrival_times"))
+        .and(warp::query::<eth2::lighthouse::GossipArrivalTimesQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::GossipArrivalTimesQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(chain.gossip_arrival_times.lock().arrivals(
+                        query.kind,
+                        query.start_slot,
+                        query.end_slot,
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/analysis/block_rewards
     let get_lighthouse_block_rewards = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4393,6 +4742,37 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/analysis/block_packing
+    let get_lighthouse_observed_block_packing = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("block_packing"))
+        .and(warp::query::<eth2::lighthouse::BlockPackingEfficiencyQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    observed_block_packing::get_observed_block_packing(query, chain)
+                })
+            },
+        );
+
+    // GET lighthouse/analysis/churn
+    let get_lighthouse_churn_analysis = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("churn"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    churn_analysis::get_churn_analysis(chain)
+                })
+            },
+        );
+
     // GET lighthouse/merge_readiness
     let get_lighthouse_merge_readiness = warp::path("lighthouse")
         .and(warp::path("merge_readiness"))
@@ -4412,12 +4792,30 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/fork_readiness
+    let get_lighthouse_fork_readiness = warp::path("lighthouse")
+        .and(warp::path("fork_readiness"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    let fork_readiness = chain.check_fork_readiness().await;
+                    Ok::<_, warp::reject::Rejection>(
+                        warp::reply::json(&api_types::GenericResponse::from(fork_readiness))
+                            .into_response(),
+                    )
+                })
+            },
+        );
+
     let get_events = eth_v1
         .and(warp::path("events"))
         .and(warp::path::end())
         .and(multi_key_query::<api_types::EventQuery>())
         .and(task_spawner_filter.clone())
-        .and(chain_filter)
+        .and(chain_filter.clone())
         .then(
             |topics_res: Result<api_types::EventQuery, warp::Rejection>,
              task_spawner: TaskSpawner<T::EthSpec>,
@@ -4479,29 +4877,7 @@ pub fn serve<T: BeaconChainTypes>(
                                 }
                             };
 
-                            receivers.push(
-                                BroadcastStream::new(receiver)
-                                    .map(|msg| {
-                                        match msg {
-                                            Ok(data) => Event::default()
-                                                .event(data.topic_name())
-                                                .json_data(data)
-                                                .unwrap_or_else(|e| {
-                                                    Event::default()
-                                                        .comment(format!("error - bad json: {e:?}"))
-                                                }),
-                                            // Do not terminate the stream if the channel fills
-                                            // up. Just drop some messages and send a comment to
-                                            // the client.
-                                            Err(BroadcastStreamRecvError::Lagged(n)) => {
-                                                Event::default().comment(format!(
-                                                    "error - dropped {n} messages"
-                                                ))
-                                            }
-                                        }
-                                    })
-                                    .map(Ok::<_, std::convert::Infallible>),
-                            );
+                            receivers.push(sse_event_stream(receiver));
                         }
                     } else {
                         return Err(warp_utils::reject::custom_server_error(
@@ -4516,6 +4892,54 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/events
+    let get_lighthouse_events = warp::path("lighthouse")
+        .and(warp::path("events"))
+        .and(warp::path::end())
+        .and(multi_key_query::<api_types::LighthouseEventQuery>())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter)
+        .then(
+            |topics_res: Result<api_types::LighthouseEventQuery, warp::Rejection>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_response_task(Priority::P0, move || {
+                    let topics = topics_res?;
+                    let event_handler = chain.event_handler.as_ref().ok_or_else(|| {
+                        warp_utils::reject::custom_server_error(
+                            "event handler was not initialized".to_string(),
+                        )
+                    })?;
+
+                    let receivers = topics
+                        .topics
+                        .into_iter()
+                        .map(|topic| {
+                            let receiver = match topic {
+                                api_types::LighthouseEventTopic::Peer => {
+                                    event_handler.subscribe_peer()
+                                }
+                                api_types::LighthouseEventTopic::BlobSidecarEquivocation => {
+                                    event_handler.subscribe_blob_sidecar_equivocation()
+                                }
+                                api_types::LighthouseEventTopic::GenesisCountdown => {
+                                    event_handler.subscribe_genesis_countdown()
+                                }
+                                api_types::LighthouseEventTopic::BlockEquivocation => {
+                                    event_handler.subscribe_block_equivocation()
+                                }
+                            };
+                            sse_event_stream(receiver)
+                        })
+                        .collect::<Vec<_>>();
+
+                    let s = futures::stream::select_all(receivers);
+
+                    Ok(warp::sse::reply(warp::sse::keep_alive().stream(s)))
+                })
+            },
+        );
+
     // Subscribe to logs via Server Side Events
     // /lighthouse/logs
     let lighthouse_log_events = warp::path("lighthouse")
@@ -4610,6 +5034,7 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_validator_aggregate_attestation)
                 .uor(get_validator_sync_committee_contribution)
                 .uor(get_lighthouse_health)
+                .uor(get_lighthouse_logging_levels)
                 .uor(get_lighthouse_ui_health)
                 .uor(get_lighthouse_ui_validator_count)
                 .uor(get_lighthouse_syncing)
@@ -4617,6 +5042,7 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
                 .uor(get_lighthouse_proto_array)
+                .uor(get_lighthouse_fork_choice)
                 .uor(get_lighthouse_validator_inclusion_global)
                 .uor(get_lighthouse_validator_inclusion)
                 .uor(get_lighthouse_eth1_syncing)
@@ -4624,7 +5050,9 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_eth1_deposit_cache)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
+                .uor(get_lighthouse_system_profile)
                 .uor(get_lighthouse_block_rewards)
+                .uor(get_lighthouse_gossip_arrival_times)
                 .uor(get_lighthouse_attestation_performance)
                 .uor(
                     enable(ctx.config.enable_light_client_server)
@@ -4639,8 +5067,12 @@ pub fn serve<T: BeaconChainTypes>(
                         .and(get_beacon_light_client_bootstrap),
                 )
                 .uor(get_lighthouse_block_packing_efficiency)
+                .uor(get_lighthouse_observed_block_packing)
+                .uor(get_lighthouse_churn_analysis)
                 .uor(get_lighthouse_merge_readiness)
+                .uor(get_lighthouse_fork_readiness)
                 .uor(get_events)
+                .uor(get_lighthouse_events)
                 .uor(get_expected_withdrawals)
                 .uor(lighthouse_log_events.boxed())
                 .recover(warp_utils::reject::handle_rejection),
@@ -4681,6 +5113,15 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_validator_liveness_epoch)
                     .uor(post_lighthouse_liveness)
                     .uor(post_lighthouse_database_reconstruct)
+                    .uor(
+                        enable(ctx.config.enable_validator_monitor_registration)
+                            .and(post_lighthouse_validator_monitor_register),
+                    )
+                    .uor(
+                        enable(ctx.config.enable_log_level_changes)
+                            .and(post_lighthouse_logging_levels),
+                    )
+                    .uor(post_lighthouse_peers_target)
                     .uor(post_lighthouse_block_rewards)
                     .uor(post_lighthouse_ui_validator_metrics)
                     .uor(post_lighthouse_ui_validator_info)
@@ -4728,6 +5169,55 @@ pub fn serve<T: BeaconChainTypes>(
     Ok(http_server)
 }
 
+/// Convert a receiver of beacon chain events into a stream of server-sent events.
+fn sse_event_stream<E: EthSpec>(
+    receiver: Receiver<api_types::EventKind<E>>,
+) -> impl Stream<Item = Result<Event, std::convert::Infallible>> {
+    BroadcastStream::new(receiver)
+        .map(|msg| {
+            match msg {
+                Ok(data) => Event::default()
+                    .event(data.topic_name())
+                    .json_data(data)
+                    .unwrap_or_else(|e| {
+                        Event::default().comment(format!("error - bad json: {e:?}"))
+                    }),
+                // Do not terminate the stream if the channel fills up. Just drop some messages and
+                // send a comment to the client.
+                Err(BroadcastStreamRecvError::Lagged(n)) => {
+                    Event::default().comment(format!("error - dropped {n} messages"))
+                }
+            }
+        })
+        .map(Ok::<_, std::convert::Infallible>)
+}
+
+/// Convert a proto-array node into the representation used by `GET debug/fork_choice`.
+fn fork_choice_node(proto_array: &ProtoArray, node: &ProtoNode) -> ForkChoiceNode {
+    let execution_status = if node.execution_status.is_execution_enabled() {
+        Some(node.execution_status.to_string())
+    } else {
+        None
+    };
+
+    ForkChoiceNode {
+        slot: node.slot,
+        block_root: node.root,
+        parent_root: node
+            .parent
+            .and_then(|index| proto_array.nodes.get(index))
+            .map(|parent| parent.root),
+        justified_epoch: node.justified_checkpoint.epoch,
+        finalized_epoch: node.finalized_checkpoint.epoch,
+        weight: node.weight,
+        validity: execution_status,
+        execution_block_hash: node
+            .execution_status
+            .block_hash()
+            .map(|block_hash| block_hash.into_root()),
+    }
+}
+
 /// Publish a message to the libp2p pubsub network.
 fn publish_pubsub_message<E: EthSpec>(
     network_tx: &UnboundedSender<NetworkMessage<E>>,
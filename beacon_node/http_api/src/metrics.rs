@@ -48,4 +48,12 @@ lazy_static::lazy_static! {
         "http_api_block_published_very_late_total",
         "The count of times a block was published beyond the attestation deadline"
     );
+    pub static ref HTTP_API_HISTORIC_CACHE_HITS_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "http_api_historic_cache_hits_total",
+        "Count of times a finalized block or state response was served from the cache",
+    );
+    pub static ref HTTP_API_HISTORIC_CACHE_MISSES_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "http_api_historic_cache_misses_total",
+        "Count of times a finalized block or state response was missing from the cache",
+    );
 }
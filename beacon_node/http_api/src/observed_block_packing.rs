@@ -0,0 +1,184 @@
+//! Compares the attestations included in each block against the aggregates this node had observed
+//! on gossip by the start of the block's slot.
+//!
+//! Unlike `block_packing_efficiency`, which reconstructs the attestations that were available from
+//! those eventually included on chain, this uses the records in `BeaconChain::gossip_arrival_times`
+//! and so reflects what a proposer with this node's view of the network could have included. The
+//! arrival records don't identify committees, so votes are compared at the granularity of
+//! `(slot, beacon_block_root)` pairs.
+use beacon_chain::gossip_arrival_times::GossipArrivalKind;
+use beacon_chain::store::ParallelIterConfig;
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::{
+    BlockPackingEfficiencyQuery, ObservedBlockPacking, ObservedBlockPackingResponse,
+    ObservedProposerPacking,
+};
+use std::cmp;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use types::{EthSpec, ForkName, Hash256, Slot};
+use warp_utils::reject::{beacon_chain_error, custom_bad_request};
+
+pub fn get_observed_block_packing<T: BeaconChainTypes>(
+    query: BlockPackingEfficiencyQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<ObservedBlockPackingResponse, warp::Rejection> {
+    let spec = &chain.spec;
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+
+    if query.start_epoch > query.end_epoch {
+        return Err(custom_bad_request(format!(
+            "invalid start and end epochs: {}, {}",
+            query.start_epoch, query.end_epoch
+        )));
+    }
+
+    let start_slot = query.start_epoch.start_slot(slots_per_epoch);
+    let end_slot = cmp::min(
+        query.end_epoch.end_slot(slots_per_epoch),
+        chain.canonical_head.cached_head().head_slot(),
+    );
+    if start_slot > end_slot {
+        return Ok(ObservedBlockPackingResponse::default());
+    }
+
+    // Finalized blocks are loaded from the freezer on the shared pool of reader threads, whilst
+    // the remainder of the range is read from the hot database.
+    let split_slot = chain.store.get_split_slot();
+    let finalized_blocks = if start_slot < split_slot {
+        let finalized_end_slot = cmp::min(end_slot, split_slot - 1);
+        Some(
+            chain
+                .store
+                .par_iter_finalized_blocks(
+                    start_slot,
+                    finalized_end_slot,
+                    ParallelIterConfig::default(),
+                )
+                .map_err(|e| beacon_chain_error(BeaconChainError::DBError(e)))?
+                .map(|result| result.map_err(BeaconChainError::DBError)),
+        )
+    } else {
+        None
+    };
+
+    let hot_start_slot = cmp::max(start_slot, split_slot);
+    let mut hot_block_roots = if hot_start_slot <= end_slot {
+        chain
+            .forwards_iter_block_roots_until(hot_start_slot, end_slot)
+            .map_err(beacon_chain_error)?
+            .map(|result| result.map(|(root, _)| root))
+            .collect::<Result<Vec<Hash256>, _>>()
+            .map_err(beacon_chain_error)?
+    } else {
+        vec![]
+    };
+    hot_block_roots.dedup();
+    let hot_blocks = hot_block_roots.into_iter().filter_map(|block_root| {
+        // The first root may be that of a block prior to `hot_start_slot` if it was skipped, in
+        // which case the slot index lets us skip it without loading the block.
+        match chain.store.get_block_slot(&block_root) {
+            Ok(Some(slot)) if slot < hot_start_slot => return None,
+            Ok(_) => (),
+            Err(e) => return Some(Err(BeaconChainError::DBError(e))),
+        }
+        chain
+            .get_blinded_block(&block_root)
+            .transpose()
+            .map(|result| result.map(|block| (block_root, block)))
+    });
+
+    // Aggregates from the epoch prior to `start_slot` may be included in the first blocks.
+    let arrivals = chain.gossip_arrival_times.lock().arrivals(
+        Some(GossipArrivalKind::Aggregate),
+        Some(start_slot.saturating_sub(2 * slots_per_epoch)),
+        Some(end_slot),
+    );
+
+    let slot_duration_ms = spec.seconds_per_slot * 1000;
+    let mut blocks = vec![];
+    let mut proposers = BTreeMap::<u64, ObservedProposerPacking>::new();
+
+    for result in finalized_blocks.into_iter().flatten().chain(hot_blocks) {
+        let (block_root, block) = result.map_err(beacon_chain_error)?;
+        let slot = block.slot();
+
+        let fork_name = spec.fork_name_at_slot::<T::EthSpec>(slot);
+        let available = arrivals
+            .iter()
+            .filter(|arrival| {
+                is_includable::<T::EthSpec>(
+                    arrival.slot,
+                    slot,
+                    fork_name,
+                    spec.min_attestation_inclusion_delay,
+                ) && arrival.delay_ms <= (slot - arrival.slot).as_u64() * slot_duration_ms
+            })
+            .map(|arrival| (arrival.slot, arrival.block_root))
+            .collect::<HashSet<_>>();
+
+        let included = block
+            .message()
+            .body()
+            .attestations()
+            .map(|attestation| {
+                let data = attestation.data();
+                (data.slot, data.beacon_block_root)
+            })
+            .collect::<HashSet<_>>();
+
+        let included_available_votes = included.intersection(&available).count();
+        let packing = ObservedBlockPacking {
+            slot,
+            block_root,
+            proposer_index: block.message().proposer_index(),
+            available_votes: available.len(),
+            included_available_votes,
+            included_unobserved_votes: included.len() - included_available_votes,
+        };
+
+        let proposer =
+            proposers
+                .entry(packing.proposer_index)
+                .or_insert_with(|| ObservedProposerPacking {
+                    proposer_index: packing.proposer_index,
+                    ..<_>::default()
+                });
+        proposer.blocks += 1;
+        proposer.available_votes += packing.available_votes;
+        proposer.included_available_votes += packing.included_available_votes;
+
+        blocks.push(packing);
+    }
+
+    let proposers = proposers
+        .into_values()
+        .map(|mut proposer| {
+            proposer.efficiency = (proposer.available_votes > 0).then(|| {
+                proposer.included_available_votes as f64 / proposer.available_votes as f64
+            });
+            proposer
+        })
+        .collect();
+
+    Ok(ObservedBlockPackingResponse { blocks, proposers })
+}
+
+/// Returns `true` if an attestation from `attestation_slot` may be included in a block at
+/// `block_slot`.
+fn is_includable<E: EthSpec>(
+    attestation_slot: Slot,
+    block_slot: Slot,
+    fork_name: ForkName,
+    min_attestation_inclusion_delay: u64,
+) -> bool {
+    if attestation_slot + min_attestation_inclusion_delay > block_slot {
+        return false;
+    }
+    if fork_name.deneb_enabled() {
+        // EIP-7045: attestations from the previous epoch may be included at any slot.
+        attestation_slot.epoch(E::slots_per_epoch()) + 1 >= block_slot.epoch(E::slots_per_epoch())
+    } else {
+        block_slot <= attestation_slot + E::slots_per_epoch()
+    }
+}
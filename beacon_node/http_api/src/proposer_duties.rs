@@ -64,16 +64,16 @@ pub fn proposer_duties<T: BeaconChainTypes>(
             .safe_add(1)
             .map_err(warp_utils::reject::arith_error)?
     {
-        let (proposers, dependent_root, execution_status, _fork) =
-            compute_proposer_duties_from_head(request_epoch, chain)
-                .map_err(warp_utils::reject::beacon_chain_error)?;
-        convert_to_api_response(
-            chain,
-            request_epoch,
-            dependent_root,
-            execution_status.is_optimistic_or_invalid(),
-            proposers,
-        )
+        if let Some(duties) = try_proposer_lookahead_from_cache(request_epoch, chain)? {
+            Ok(duties)
+        } else {
+            debug!(
+                log,
+                "Proposer lookahead cache miss";
+                "request_epoch" =>  request_epoch,
+            );
+            compute_and_cache_proposer_lookahead(request_epoch, chain)
+        }
     } else if request_epoch
         > current_epoch
             .safe_add(1)
@@ -182,6 +182,81 @@ fn compute_and_cache_proposer_duties<T: BeaconChainTypes>(
     )
 }
 
+/// Attempt to load the proposer duties for an epoch after the head from the lookahead entry of the
+/// `chain.beacon_proposer_cache`, returning `Ok(None)` if there is a cache miss.
+fn try_proposer_lookahead_from_cache<T: BeaconChainTypes>(
+    request_epoch: Epoch,
+    chain: &BeaconChain<T>,
+) -> Result<Option<ApiDuties>, warp::reject::Rejection> {
+    let head = chain.canonical_head.cached_head();
+    let head_block = &head.snapshot.beacon_block;
+    let head_block_root = head.head_block_root();
+    let head_epoch = head_block.slot().epoch(T::EthSpec::slots_per_epoch());
+
+    // The head block is only the decision block for epochs after the head.
+    if head_epoch >= request_epoch {
+        return Ok(None);
+    }
+
+    // The proposers may also have been primed by the state advance timer at the end of the head's
+    // epoch, in which case they are keyed by the head block root as their decision root.
+    let mut cache = chain.beacon_proposer_cache.lock();
+    let Some(indices) = cache
+        .get_lookahead(head_block_root, request_epoch)
+        .cloned()
+        .or_else(|| {
+            cache
+                .get_epoch::<T::EthSpec>(head_block_root, request_epoch)
+                .cloned()
+        })
+    else {
+        return Ok(None);
+    };
+    drop(cache);
+
+    let execution_optimistic = chain
+        .is_optimistic_or_invalid_head_block(head_block)
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+
+    convert_to_api_response(
+        chain,
+        request_epoch,
+        head_block_root,
+        execution_optimistic,
+        indices.to_vec(),
+    )
+    .map(Some)
+}
+
+/// Compute the proposer duties for an epoch after the current epoch using the head state, storing
+/// them as the lookahead entry of the proposer cache.
+///
+/// Unlike `compute_and_cache_proposer_duties` this does not insert into the main proposer cache,
+/// since these values are replaced whenever the head changes.
+fn compute_and_cache_proposer_lookahead<T: BeaconChainTypes>(
+    request_epoch: Epoch,
+    chain: &BeaconChain<T>,
+) -> Result<ApiDuties, warp::reject::Rejection> {
+    let (indices, dependent_root, execution_status, fork) =
+        compute_proposer_duties_from_head(request_epoch, chain)
+            .map_err(warp_utils::reject::beacon_chain_error)?;
+
+    chain.beacon_proposer_cache.lock().insert_lookahead(
+        request_epoch,
+        dependent_root,
+        indices.clone(),
+        fork,
+    );
+
+    convert_to_api_response(
+        chain,
+        request_epoch,
+        dependent_root,
+        execution_status.is_optimistic_or_invalid(),
+        indices,
+    )
+}
+
 /// Compute some proposer duties by reading a `BeaconState` from disk, completely ignoring the
 /// `beacon_proposer_cache`.
 fn compute_historic_proposer_duties<T: BeaconChainTypes>(
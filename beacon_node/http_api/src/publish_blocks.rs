@@ -138,7 +138,9 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlockConten
                     warp::reply::json(&ErrorMessage {
                         code: duplicate_status_code.as_u16(),
                         message: "duplicate block".to_string(),
+                        error_code: None,
                         stacktraces: vec![],
+                        nearest_stored_slot: None,
                     }),
                     duplicate_status_code,
                 )
@@ -112,7 +112,7 @@ impl StateId {
                         .map_err(warp_utils::reject::beacon_chain_error)?;
                     return Ok((*root, execution_optimistic, true));
                 } else {
-                    return Err(warp_utils::reject::custom_not_found(format!(
+                    return Err(warp_utils::reject::unknown_state(format!(
                         "beacon state for state root {}",
                         root
                     )));
@@ -124,7 +124,7 @@ impl StateId {
             .state_root_at_slot(slot)
             .map_err(warp_utils::reject::beacon_chain_error)?
             .ok_or_else(|| {
-                warp_utils::reject::custom_not_found(format!("beacon state at slot {}", slot))
+                warp_utils::reject::unknown_state(format!("beacon state at slot {}", slot))
             })?;
 
         Ok((root, execution_optimistic, finalized))
@@ -187,12 +187,32 @@ impl StateId {
             _ => (self.root(chain)?, None),
         };
 
+        // States between the state lower and upper limits are absent following checkpoint sync,
+        // until they are reconstructed.
+        if let Some(slot) = slot_opt {
+            let (lower_limit, upper_limit) = chain.store.get_historic_state_limits();
+            if slot > lower_limit && slot < upper_limit {
+                return Err(warp_utils::reject::pruned(format!(
+                    "beacon state at slot {} is unavailable until state reconstruction reaches it",
+                    slot
+                )));
+            }
+        }
+
         let state = chain
             .get_state(&state_root, slot_opt)
-            .map_err(warp_utils::reject::beacon_chain_error)
+            .map_err(|e| match e {
+                BeaconChainError::DBError(store::Error::HistoryUnavailable) => {
+                    warp_utils::reject::pruned(format!(
+                        "beacon state at root {} is unavailable",
+                        state_root
+                    ))
+                }
+                e => warp_utils::reject::beacon_chain_error(e),
+            })
             .and_then(|opt| {
                 opt.ok_or_else(|| {
-                    warp_utils::reject::custom_not_found(format!(
+                    warp_utils::reject::unknown_state(format!(
                         "beacon state at root {}",
                         state_root
                     ))
@@ -202,6 +222,73 @@ impl StateId {
         Ok((state, execution_optimistic, finalized))
     }
 
+    /// Return an error if loading the state identified by `self` would require replaying more
+    /// than `replay_budget` slots.
+    ///
+    /// Only states in the freezer database are checked, as hot states are never more than an
+    /// epoch away from a stored state. The error suggests the nearest slot at which a state is
+    /// stored.
+    pub fn check_replay_budget<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+        replay_budget: Option<u64>,
+    ) -> Result<(), warp::Rejection> {
+        let Some(replay_budget) = replay_budget else {
+            return Ok(());
+        };
+        let slot = match &self.0 {
+            CoreStateId::Slot(slot) => *slot,
+            CoreStateId::Root(root) => {
+                match chain
+                    .store
+                    .load_cold_state_slot(root)
+                    .map_err(BeaconChainError::DBError)
+                    .map_err(warp_utils::reject::beacon_chain_error)?
+                {
+                    Some(slot) => slot,
+                    None => return Ok(()),
+                }
+            }
+            _ => return Ok(()),
+        };
+
+        let split_slot = chain.store.get_split_slot();
+        let (lower_limit, upper_limit) = chain.store.get_historic_state_limits();
+        let unavailable = |slot: Slot| slot > lower_limit && slot < upper_limit;
+        // Unavailable states are rejected when they are loaded.
+        if slot >= split_slot || unavailable(slot) {
+            return Ok(());
+        }
+
+        // Frozen states are replayed from the restore point before them.
+        let slots_per_restore_point = chain.store.get_config().slots_per_restore_point;
+        let replay_distance = slot.as_u64() % slots_per_restore_point;
+        if replay_distance <= replay_budget {
+            return Ok(());
+        }
+
+        // The next restore point may not have been stored yet, in which case the split state is
+        // the next stored state.
+        let prev_stored_slot = slot - replay_distance;
+        let next_stored_slot =
+            std::cmp::min(prev_stored_slot + slots_per_restore_point, split_slot);
+        let nearest_stored_slot =
+            if next_stored_slot - slot < replay_distance || unavailable(prev_stored_slot) {
+                next_stored_slot
+            } else {
+                prev_stored_slot
+            };
+
+        Err(warp_utils::reject::replay_budget_exceeded(
+            format!(
+                "beacon state at slot {} requires replaying {} slots, which exceeds the replay \
+                 budget of {} slots",
+                slot, replay_distance, replay_budget
+            ),
+            nearest_stored_slot,
+        ))
+    }
+
     /// Map a function across the `BeaconState` identified by `self`.
     ///
     /// The optimistic and finalization status of the requested state is also provided to the `func`
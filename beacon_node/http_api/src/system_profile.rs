@@ -0,0 +1,140 @@
+//! Provides rough estimates of the memory used by the beacon node's in-memory caches, along with
+//! the depths of the beacon processor queues and any allocator statistics.
+//!
+//! The estimates are derived from the number of items in each cache and the SSZ size of those
+//! items, so they ignore overheads such as tree hash caches and allocator fragmentation. They are
+//! intended for spotting regressions and trends, rather than accounting for every byte.
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use beacon_processor::BeaconProcessorSend;
+use eth2::lighthouse::{BeaconProcessorProfile, CacheProfile, SystemProfile};
+use ssz::Encode;
+use std::collections::BTreeMap;
+use std::mem;
+use std::sync::Arc;
+use types::{
+    AttestationData, EthSpec, ProposerSlashing, SignatureBytes, SignedBlsToExecutionChange,
+    SignedVoluntaryExit, SyncCommitteeContribution, Unsigned,
+};
+
+/// The prefix and suffix of the names of the metrics which track the beacon processor queues.
+const WORK_QUEUE_METRIC_PREFIX: &str = "beacon_processor_";
+const WORK_QUEUE_METRIC_SUFFIX: &str = "_queue_total";
+
+pub fn profile<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    beacon_processor_send: Option<BeaconProcessorSend<T::EthSpec>>,
+) -> Result<SystemProfile, warp::Rejection> {
+    let head_state_bytes = chain.head_snapshot().beacon_state.ssz_bytes_len() as u64;
+    let num_validators = chain.head_snapshot().beacon_state.validators().len() as u64;
+
+    let state_cache_len = chain.store.state_cache_len() as u64;
+    let shuffling_cache_len = chain.shuffling_cache.read().len() as u64;
+
+    let caches = vec![
+        CacheProfile {
+            name: "state_cache".into(),
+            len: state_cache_len,
+            estimated_bytes: state_cache_len.saturating_mul(head_state_bytes),
+        },
+        CacheProfile {
+            name: "shuffling_cache".into(),
+            len: shuffling_cache_len,
+            // Each committee cache holds the shuffling and the position of each validator within
+            // it.
+            estimated_bytes: shuffling_cache_len
+                .saturating_mul(num_validators)
+                .saturating_mul(2 * mem::size_of::<usize>() as u64),
+        },
+        op_pool_profile(&chain),
+    ];
+
+    let beacon_processor = BeaconProcessorProfile {
+        event_channel_depth: beacon_processor_send
+            .map(|send| send.0.max_capacity().saturating_sub(send.0.capacity()) as u64),
+        work_queues: work_queue_depths(),
+    };
+
+    let allocator =
+        malloc_utils::allocator_stats().map_err(warp_utils::reject::custom_server_error)?;
+    let allocator = (!allocator.is_empty()).then(|| {
+        allocator
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    });
+
+    Ok(SystemProfile {
+        caches,
+        beacon_processor,
+        allocator,
+    })
+}
+
+fn op_pool_profile<T: BeaconChainTypes>(chain: &BeaconChain<T>) -> CacheProfile {
+    let op_pool = &chain.op_pool;
+
+    // Assume each attestation carries a full aggregation bitfield.
+    let attestation_bytes = <AttestationData as Encode>::ssz_fixed_len()
+        + <SignatureBytes as Encode>::ssz_fixed_len()
+        + <T::EthSpec as EthSpec>::MaxValidatorsPerCommittee::to_usize() / 8;
+
+    let items = [
+        (op_pool.num_attestations(), attestation_bytes),
+        (
+            op_pool.num_sync_contributions(),
+            <SyncCommitteeContribution<T::EthSpec> as Encode>::ssz_fixed_len(),
+        ),
+        (op_pool.num_attester_slashings(), 2 * attestation_bytes),
+        (
+            op_pool.num_proposer_slashings(),
+            <ProposerSlashing as Encode>::ssz_fixed_len(),
+        ),
+        (
+            op_pool.num_voluntary_exits(),
+            <SignedVoluntaryExit as Encode>::ssz_fixed_len(),
+        ),
+        (
+            op_pool.num_bls_to_execution_changes(),
+            <SignedBlsToExecutionChange as Encode>::ssz_fixed_len(),
+        ),
+    ];
+
+    CacheProfile {
+        name: "op_pool".into(),
+        len: items.iter().map(|(len, _)| *len as u64).sum(),
+        estimated_bytes: items
+            .iter()
+            .map(|(len, item_bytes)| (*len as u64).saturating_mul(*item_bytes as u64))
+            .sum(),
+    }
+}
+
+/// Reads the depth of each beacon processor work queue from the metrics registry.
+///
+/// The beacon processor owns its queues, so its metrics are the only view of them available
+/// outside of the processor itself.
+fn work_queue_depths() -> BTreeMap<String, u64> {
+    let mut depths = BTreeMap::new();
+
+    for family in lighthouse_metrics::gather() {
+        let Some(queue) = family
+            .get_name()
+            .strip_prefix(WORK_QUEUE_METRIC_PREFIX)
+            .and_then(|name| name.strip_suffix(WORK_QUEUE_METRIC_SUFFIX))
+        else {
+            continue;
+        };
+
+        for metric in family.get_metric() {
+            let name = metric
+                .get_label()
+                .iter()
+                .fold(queue.to_string(), |name, label| {
+                    format!("{}_{}", name, label.get_value())
+                });
+            depths.insert(name, metric.get_gauge().get_value().max(0.) as u64);
+        }
+    }
+
+    depths
+}
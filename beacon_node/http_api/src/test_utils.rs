@@ -18,12 +18,13 @@ use lighthouse_network::{
     types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield, SyncState},
     ConnectedPoint, Enr, NetworkGlobals, PeerId, PeerManager,
 };
-use logging::test_logger;
+use logging::{test_logger, LogLevels};
 use network::{NetworkReceivers, NetworkSenders};
 use sensitive_url::SensitiveUrl;
-use slog::Logger;
+use slog::{Level, Logger};
 use std::future::Future;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use store::MemoryStore;
@@ -34,6 +35,8 @@ pub const TCP_PORT: u16 = 42;
 pub const UDP_PORT: u16 = 42;
 pub const SEQ_NUMBER: u64 = 0;
 pub const EXTERNAL_ADDR: &str = "/ip4/0.0.0.0/tcp/9000";
+/// The token for the authenticated endpoints of servers created with `create_api_server`.
+pub const API_TOKEN: &str = "test-api-token";
 
 /// HTTP API tester that allows interaction with the underlying beacon chain harness.
 pub struct InteractiveTester<E: EthSpec> {
@@ -131,6 +134,36 @@ pub async fn create_api_server<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     test_runtime: &TestRuntime,
     log: Logger,
+) -> ApiServer<T, impl Future<Output = ()>> {
+    // Each server reads its own token file, so that servers started concurrently can't observe a
+    // partially written file.
+    static NEXT_API_TOKEN_FILE: AtomicUsize = AtomicUsize::new(0);
+    let api_token_path = std::env::temp_dir().join(format!(
+        "lighthouse_http_api_token_{}_{}",
+        std::process::id(),
+        NEXT_API_TOKEN_FILE.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&api_token_path, API_TOKEN).unwrap();
+
+    let config = Config {
+        data_dir: std::path::PathBuf::from(DEFAULT_ROOT_DIR),
+        enable_light_client_server: true,
+        enable_validator_monitor_registration: true,
+        api_token_path: Some(api_token_path),
+        enable_log_level_changes: true,
+        // Exercise the historic response cache, which is disabled by default.
+        historic_cache_size: 4,
+        ..Config::default()
+    };
+    create_api_server_with_config(chain, test_runtime, log, config).await
+}
+
+/// Like `create_api_server`, but serving the HTTP API with the given `config`.
+pub async fn create_api_server_with_config<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    test_runtime: &TestRuntime,
+    log: Logger,
+    config: Config,
 ) -> ApiServer<T, impl Future<Output = ()>> {
     // Use port 0 to allocate a new unused port.
     let port = 0;
@@ -154,8 +187,8 @@ pub async fn create_api_server<T: BeaconChainTypes>(
     ));
 
     // Only a peer manager can add peers, so we create a dummy manager.
-    let config = lighthouse_network::peer_manager::config::Config::default();
-    let mut pm = PeerManager::new(config, network_globals.clone(), &log).unwrap();
+    let pm_config = lighthouse_network::peer_manager::config::Config::default();
+    let mut pm = PeerManager::new(pm_config, network_globals.clone(), &log).unwrap();
 
     // add a peer
     let peer_id = PeerId::random();
@@ -220,9 +253,7 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         config: Config {
             enabled: true,
             listen_port: port,
-            data_dir: std::path::PathBuf::from(DEFAULT_ROOT_DIR),
-            enable_light_client_server: true,
-            ..Config::default()
+            ..config
         },
         chain: Some(chain),
         network_senders: Some(network_senders),
@@ -231,6 +262,7 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         beacon_processor_reprocess_send: Some(reprocess_send),
         eth1_service: Some(eth1_service),
         sse_logging_components: None,
+        log_levels: Some(LogLevels::new(Level::Info)),
         log,
     });
 
@@ -1,5 +1,6 @@
 use beacon_chain::{
-    validator_monitor::HISTORIC_EPOCHS, BeaconChain, BeaconChainError, BeaconChainTypes,
+    validator_monitor::{AttestationInclusion, HISTORIC_EPOCHS},
+    BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use eth2::types::{Epoch, ValidatorStatus};
 use serde::{Deserialize, Serialize};
@@ -154,8 +155,19 @@ pub struct ValidatorMetricsRequestData {
     indices: Vec<u64>,
 }
 
+#[derive(PartialEq, Serialize, Deserialize)]
+pub struct ValidatorAttestationHistory {
+    #[serde(with = "serde_utils::quoted_u64")]
+    epoch: u64,
+    hit: bool,
+}
+
 #[derive(PartialEq, Serialize, Deserialize)]
 pub struct ValidatorMetrics {
+    balance: Option<u64>,
+    effective_balance: Option<u64>,
+    current_sync_committee: bool,
+    next_sync_committee: bool,
     attestation_hits: u64,
     attestation_misses: u64,
     attestation_hit_percentage: f64,
@@ -166,6 +178,8 @@ pub struct ValidatorMetrics {
     attestation_target_misses: u64,
     attestation_target_hit_percentage: f64,
     latest_attestation_inclusion_distance: u64,
+    attestation_history: Vec<ValidatorAttestationHistory>,
+    attestation_inclusions: Vec<AttestationInclusion>,
 }
 
 #[derive(PartialEq, Serialize, Deserialize)]
@@ -173,10 +187,60 @@ pub struct ValidatorMetricsResponse {
     validators: HashMap<String, ValidatorMetrics>,
 }
 
+/// Balances and sync committee membership of a validator in the head state.
+struct HeadValidatorInfo {
+    balance: u64,
+    effective_balance: u64,
+    current_sync_committee: bool,
+    next_sync_committee: bool,
+}
+
 pub fn post_validator_monitor_metrics<T: BeaconChainTypes>(
     request_data: ValidatorMetricsRequestData,
     chain: Arc<BeaconChain<T>>,
 ) -> Result<ValidatorMetricsResponse, warp::Rejection> {
+    let current_epoch = chain.epoch().map_err(beacon_chain_error)?;
+    let epochs = current_epoch.saturating_sub(HISTORIC_EPOCHS).as_u64()..=current_epoch.as_u64();
+
+    // Read everything required from the head state in a single pass, rather than once per
+    // validator.
+    let head_info = chain
+        .with_head(|head| {
+            let state = &head.beacon_state;
+            // Sync committees do not exist prior to Altair.
+            let current_sync_committee = state
+                .current_sync_committee()
+                .ok()
+                .map(|committee| committee.pubkeys.iter().collect::<HashSet<_>>())
+                .unwrap_or_default();
+            let next_sync_committee = state
+                .next_sync_committee()
+                .ok()
+                .map(|committee| committee.pubkeys.iter().collect::<HashSet<_>>())
+                .unwrap_or_default();
+
+            let mut head_info = HashMap::new();
+            for &index in &request_data.indices {
+                let (Some(validator), Some(balance)) = (
+                    state.validators().get(index as usize),
+                    state.balances().get(index as usize),
+                ) else {
+                    continue;
+                };
+                head_info.insert(
+                    index,
+                    HeadValidatorInfo {
+                        balance: *balance,
+                        effective_balance: validator.effective_balance,
+                        current_sync_committee: current_sync_committee.contains(&validator.pubkey),
+                        next_sync_committee: next_sync_committee.contains(&validator.pubkey),
+                    },
+                );
+            }
+            Ok::<_, BeaconChainError>(head_info)
+        })
+        .map_err(beacon_chain_error)?;
+
     let validator_ids = chain
         .validator_monitor
         .read()
@@ -235,7 +299,26 @@ pub fn post_validator_monitor_metrics<T: BeaconChainTypes>(
                     (100 * attestation_target_hits / target_attestations) as f64
                 };
 
+                let attestation_history = epochs
+                    .clone()
+                    .filter_map(|epoch| {
+                        validator
+                            .get_attestation_hit(Epoch::new(epoch))
+                            .map(|hit| ValidatorAttestationHistory { epoch, hit })
+                    })
+                    .collect();
+
+                let attestation_inclusions = epochs
+                    .clone()
+                    .flat_map(|epoch| validator.get_attestation_inclusions(Epoch::new(epoch)))
+                    .collect();
+
+                let head = head_info.get(&index);
                 let metrics = ValidatorMetrics {
+                    balance: head.map(|info| info.balance),
+                    effective_balance: head.map(|info| info.effective_balance),
+                    current_sync_committee: head.map_or(false, |info| info.current_sync_committee),
+                    next_sync_committee: head.map_or(false, |info| info.next_sync_committee),
                     attestation_hits,
                     attestation_misses,
                     attestation_hit_percentage,
@@ -246,6 +329,8 @@ pub fn post_validator_monitor_metrics<T: BeaconChainTypes>(
                     attestation_target_misses,
                     attestation_target_hit_percentage,
                     latest_attestation_inclusion_distance,
+                    attestation_history,
+                    attestation_inclusions,
                 };
 
                 validators.insert(id.clone(), metrics);
@@ -1,10 +1,12 @@
 use crate::state_id::StateId;
+use crate::validator::pubkey_to_validator_index;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2::types::{
     self as api_types, ExecutionOptimisticFinalizedResponse, ValidatorBalanceData, ValidatorData,
     ValidatorId, ValidatorStatus,
 };
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::BTreeSet, sync::Arc};
+use types::{BeaconState, EthSpec, Validator};
 
 pub fn get_beacon_state_validators<T: BeaconChainTypes>(
     state_id: StateId,
@@ -18,22 +20,13 @@ pub fn get_beacon_state_validators<T: BeaconChainTypes>(
             |state, execution_optimistic, finalized| {
                 let epoch = state.current_epoch();
                 let far_future_epoch = chain.spec.far_future_epoch;
-                let ids_filter_set: Option<HashSet<&ValidatorId>> =
-                    query_ids.as_ref().map(HashSet::from_iter);
+                let indices = query_ids
+                    .as_deref()
+                    .map(|ids| validator_indices(&chain, state, ids))
+                    .transpose()?;
 
                 Ok((
-                    state
-                        .validators()
-                        .iter()
-                        .zip(state.balances().iter())
-                        .enumerate()
-                        // filter by validator id(s) if provided
-                        .filter(|(index, (validator, _))| {
-                            ids_filter_set.as_ref().map_or(true, |ids_set| {
-                                ids_set.contains(&ValidatorId::PublicKey(validator.pubkey))
-                                    || ids_set.contains(&ValidatorId::Index(*index as u64))
-                            })
-                        })
+                    validators_with_balances(state, indices)
                         // filter by status(es) if provided and map the result
                         .filter_map(|(index, (validator, balance))| {
                             let status = api_types::ValidatorStatus::from_validator(
@@ -81,22 +74,12 @@ pub fn get_beacon_state_validator_balances<T: BeaconChainTypes>(
         .map_state_and_execution_optimistic_and_finalized(
             &chain,
             |state, execution_optimistic, finalized| {
-                let ids_filter_set: Option<HashSet<&ValidatorId>> =
-                    optional_ids.map(|f| HashSet::from_iter(f.iter()));
+                let indices = optional_ids
+                    .map(|ids| validator_indices(&chain, state, ids))
+                    .transpose()?;
 
                 Ok((
-                    state
-                        .validators()
-                        .iter()
-                        .zip(state.balances().iter())
-                        .enumerate()
-                        // filter by validator id(s) if provided
-                        .filter(|(index, (validator, _))| {
-                            ids_filter_set.as_ref().map_or(true, |ids_set| {
-                                ids_set.contains(&ValidatorId::PublicKey(validator.pubkey))
-                                    || ids_set.contains(&ValidatorId::Index(*index as u64))
-                            })
-                        })
+                    validators_with_balances(state, indices)
                         .map(|(index, (_, balance))| ValidatorBalanceData {
                             index: index as u64,
                             balance: *balance,
@@ -114,3 +97,51 @@ pub fn get_beacon_state_validator_balances<T: BeaconChainTypes>(
         finalized: Some(finalized),
     })
 }
+
+/// Resolves `ids` to the sorted and de-duplicated indices of the validators which exist in `state`.
+///
+/// Pubkeys are resolved using the pubkey cache rather than by scanning the validator registry.
+fn validator_indices<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    state: &BeaconState<T::EthSpec>,
+    ids: &[ValidatorId],
+) -> Result<Vec<usize>, warp::Rejection> {
+    let mut indices = BTreeSet::new();
+    for id in ids {
+        let index = match id {
+            ValidatorId::PublicKey(pubkey) => pubkey_to_validator_index(chain, state, pubkey)
+                .map_err(|e| {
+                    warp_utils::reject::custom_not_found(format!(
+                        "unable to access pubkey cache: {e:?}",
+                    ))
+                })?,
+            ValidatorId::Index(index) => Some(*index as usize),
+        };
+        if let Some(index) = index.filter(|&index| index < state.validators().len()) {
+            indices.insert(index);
+        }
+    }
+    Ok(indices.into_iter().collect())
+}
+
+/// Iterates the validators and balances at `indices`, or the whole registry if `indices` is
+/// `None`.
+fn validators_with_balances<E: EthSpec>(
+    state: &BeaconState<E>,
+    indices: Option<Vec<usize>>,
+) -> Box<dyn Iterator<Item = (usize, (&Validator, &u64))> + '_> {
+    match indices {
+        Some(indices) => Box::new(indices.into_iter().filter_map(|index| {
+            let validator = state.validators().get(index)?;
+            let balance = state.balances().get(index)?;
+            Some((index, (validator, balance)))
+        })),
+        None => Box::new(
+            state
+                .validators()
+                .iter()
+                .zip(state.balances().iter())
+                .enumerate(),
+        ),
+    }
+}
@@ -2,14 +2,18 @@
 use beacon_chain::{
     chain_config::{DisallowedReOrgOffsets, ReOrgThreshold},
     test_utils::{AttestationStrategy, BlockStrategy, SyncCommitteeStrategy},
-    ChainConfig,
+    ChainConfig, WhenSlotSkipped,
 };
 use beacon_processor::work_reprocessing_queue::ReprocessQueueMessage;
+use eth2::lighthouse::LogLevelsData;
 use eth2::types::ProduceBlockV3Response;
-use eth2::types::{DepositContractData, StateId};
+use eth2::types::{BlockId, DepositContractData, ErrorCode, StateId};
+use eth2::{BeaconNodeHttpClient, StatusCode, Timeouts};
 use execution_layer::{ForkchoiceState, PayloadAttributes};
-use http_api::test_utils::InteractiveTester;
+use http_api::test_utils::{create_api_server_with_config, ApiServer, InteractiveTester};
+use http_api::Config;
 use parking_lot::Mutex;
+use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
 use state_processing::{
     per_block_processing::get_expected_withdrawals, state_advance::complete_state_advance,
@@ -17,6 +21,7 @@ use state_processing::{
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
+use store::StoreOp;
 use types::{
     Address, Epoch, EthSpec, ExecPayload, ExecutionBlockHash, ForkName, MainnetEthSpec,
     MinimalEthSpec, ProposerPreparationData, Slot,
@@ -120,6 +125,133 @@ async fn state_by_root_pruned_from_fork_choice() {
     }
 }
 
+// Test that endpoints which change the node's behaviour are only served when enabled.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn opt_in_endpoints_disabled_by_default() {
+    type E = MinimalEthSpec;
+
+    let validator_count = 24;
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+
+    let ApiServer {
+        server,
+        listening_socket,
+        ..
+    } = create_api_server_with_config(
+        harness.chain.clone(),
+        &harness.runtime,
+        harness.logger().clone(),
+        Config::default(),
+    )
+    .await;
+    tokio::spawn(server);
+    let client = BeaconNodeHttpClient::new(
+        SensitiveUrl::parse(&format!("http://{}", listening_socket)).unwrap(),
+        Timeouts::set_all(Duration::from_secs(1)),
+    );
+
+    // The log levels can be read, but not changed.
+    let levels = client.get_lighthouse_logging_levels().await.unwrap().data;
+    let err = client
+        .post_lighthouse_logging_levels(&LogLevelsData {
+            levels: "debug".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+    assert_eq!(
+        client.get_lighthouse_logging_levels().await.unwrap().data,
+        levels
+    );
+
+    let err = client
+        .post_lighthouse_validator_monitor_register(&[], "")
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+// Test that frozen states which require too much replay are rejected with the nearest stored slot.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn debug_state_replay_budget() {
+    type E = MinimalEthSpec;
+
+    let validator_count = 24;
+    let replay_budget = 8;
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+
+    let ApiServer {
+        server,
+        listening_socket,
+        ..
+    } = create_api_server_with_config(
+        harness.chain.clone(),
+        &harness.runtime,
+        harness.logger().clone(),
+        Config {
+            state_replay_budget: Some(replay_budget),
+            ..Config::default()
+        },
+    )
+    .await;
+    tokio::spawn(server);
+    let client = BeaconNodeHttpClient::new(
+        SensitiveUrl::parse(&format!("http://{}", listening_socket)).unwrap(),
+        Timeouts::set_all(Duration::from_secs(1)),
+    );
+
+    // Finalize and migrate beyond the first restore point.
+    harness.advance_slot();
+    harness
+        .extend_chain_with_sync(
+            12 * E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::NoValidators,
+        )
+        .await;
+    let slots_per_restore_point = harness.chain.store.get_config().slots_per_restore_point;
+    assert!(harness.chain.store.get_split_slot() > slots_per_restore_point);
+
+    // States within the budget of a restore point can be loaded.
+    for slot in [
+        0,
+        replay_budget,
+        slots_per_restore_point,
+        slots_per_restore_point + replay_budget,
+    ] {
+        client
+            .get_debug_beacon_states::<E>(StateId::Slot(Slot::new(slot)))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    // States further from a restore point are rejected, by slot and by root.
+    for (slot, nearest_stored_slot) in [
+        (replay_budget + 1, 0),
+        (slots_per_restore_point - 1, slots_per_restore_point),
+    ] {
+        let slot = Slot::new(slot);
+        let state_root = harness.chain.state_root_at_slot(slot).unwrap().unwrap();
+        for state_id in [StateId::Slot(slot), StateId::Root(state_root)] {
+            match client.get_debug_beacon_states::<E>(state_id).await {
+                Err(eth2::Error::ServerMessage(error)) => {
+                    assert_eq!(error.code, 413);
+                    assert_eq!(error.error_code, Some(ErrorCode::ReplayBudgetExceeded));
+                    assert_eq!(
+                        error.nearest_stored_slot,
+                        Some(Slot::new(nearest_stored_slot))
+                    );
+                }
+                other => panic!("expected replay budget error, got {:?}", other.map(|_| ())),
+            }
+        }
+    }
+}
+
 /// Data structure for tracking fork choice updates received by the mock execution layer.
 #[derive(Debug, Default)]
 struct ForkChoiceUpdates {
@@ -922,3 +1054,109 @@ async fn queue_attestations_from_http() {
 
     attestation_future.await.unwrap();
 }
+
+/// Attester duties for the genesis epoch are served before genesis, so that validators can attest
+/// in the genesis slot.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn attester_duties_before_genesis() {
+    let validator_count = 32;
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let chain = &tester.harness.chain;
+    let indices = (0..validator_count as u64).collect::<Vec<_>>();
+    let genesis_epoch = Epoch::new(0);
+
+    // The duties served at genesis, for comparison.
+    let expected = tester
+        .client
+        .post_validator_duties_attester(genesis_epoch, &indices)
+        .await
+        .unwrap();
+
+    let genesis_time = Duration::from_secs(chain.genesis_time);
+    chain
+        .slot_clock
+        .set_current_time(genesis_time - Duration::from_secs(60));
+    assert_eq!(chain.slot_clock.is_prior_to_genesis(), Some(true));
+
+    let duties = tester
+        .client
+        .post_validator_duties_attester(genesis_epoch, &indices)
+        .await
+        .unwrap();
+    assert_eq!(duties.dependent_root, expected.dependent_root);
+    assert_eq!(duties.data, expected.data);
+    assert_eq!(duties.data.len(), validator_count);
+
+    // The epoch after genesis may be requested, but no later.
+    tester
+        .client
+        .post_validator_duties_attester(genesis_epoch + 1, &indices)
+        .await
+        .unwrap();
+    tester
+        .client
+        .post_validator_duties_attester(genesis_epoch + 2, &indices)
+        .await
+        .unwrap_err();
+}
+
+// Test that a block stripped of its payload and blobs, as the blocks of abandoned forks are when
+// they are retained, is still served by the block endpoints.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn stripped_block_is_served() {
+    type E = MinimalEthSpec;
+
+    let validator_count = 24;
+    let spec = ForkName::latest().make_genesis_spec(E::default_spec());
+
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    harness.advance_slot();
+    harness
+        .extend_chain_with_sync(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::NoValidators,
+        )
+        .await;
+
+    let block_root = harness
+        .chain
+        .block_root_at_slot(Slot::new(1), WhenSlotSkipped::None)
+        .unwrap()
+        .unwrap();
+    let expected_block = harness.chain.get_block(&block_root).await.unwrap().unwrap();
+    harness
+        .chain
+        .store
+        .do_atomically_with_block_and_blobs_cache(vec![
+            StoreOp::DeleteExecutionPayload(block_root),
+            StoreOp::DeleteBlobs(block_root),
+        ])
+        .unwrap();
+    assert!(!harness
+        .chain
+        .store
+        .execution_payload_exists(&block_root)
+        .unwrap());
+
+    let blinded_block = client
+        .get_beacon_blinded_blocks::<E>(BlockId::Root(block_root))
+        .await
+        .unwrap()
+        .expect("stripped block should be found")
+        .data;
+    assert_eq!(blinded_block, expected_block.clone_as_blinded());
+
+    // The payload of the full block is reconstructed from the execution layer.
+    let full_block = client
+        .get_beacon_blocks::<E>(BlockId::Root(block_root))
+        .await
+        .unwrap()
+        .expect("stripped block should be found")
+        .data;
+    assert_eq!(full_block, expected_block);
+}
@@ -3,9 +3,10 @@ use beacon_chain::{
     test_utils::{AttestationStrategy, BlockStrategy, SyncCommitteeStrategy},
     BlockError,
 };
-use eth2::StatusCode;
+use eth2::{types::NodeHealthQuery, StatusCode};
 use execution_layer::{PayloadStatusV1, PayloadStatusV1Status};
 use http_api::test_utils::InteractiveTester;
+use lighthouse_network::types::SyncState;
 use types::{EthSpec, ExecPayload, ForkName, MinimalEthSpec, Slot};
 
 type E = MinimalEthSpec;
@@ -226,3 +227,75 @@ async fn node_health_el_online_and_not_synced() {
         }
     }
 }
+
+/// Check `node health` endpoint returns the requested `syncing_status` whilst optimistic.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn node_health_custom_syncing_status() {
+    let num_blocks = E::slots_per_epoch() / 2;
+    let num_validators = E::slots_per_epoch();
+    let tester = post_merge_tester(num_blocks, num_validators).await;
+    let harness = &tester.harness;
+    let mock_el = harness.mock_execution_layer.as_ref().unwrap();
+
+    // EL not synced
+    harness.advance_slot();
+    mock_el.server.all_payloads_syncing(true);
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let query = NodeHealthQuery {
+        syncing_status: Some(503),
+        ..<_>::default()
+    };
+    let status = tester.client.get_node_health_with_query(&query).await;
+    assert_eq!(status.unwrap(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // An invalid status code is rejected.
+    let query = NodeHealthQuery {
+        syncing_status: Some(42),
+        ..<_>::default()
+    };
+    let status = tester.client.get_node_health_with_query(&query).await;
+    assert_eq!(status.unwrap_err().status().unwrap(), 400);
+}
+
+/// Check `node health` endpoint treats a node within the `syncing_tolerance` as synced.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn node_health_syncing_tolerance() {
+    let num_blocks = E::slots_per_epoch() / 2;
+    let num_validators = E::slots_per_epoch();
+    let tester = post_merge_tester(num_blocks, num_validators).await;
+    let harness = &tester.harness;
+    let mock_el = harness.mock_execution_layer.as_ref().unwrap();
+
+    // EL synced
+    mock_el.server.set_syncing_response(Ok(false));
+    mock_el.el.upcheck().await;
+
+    // Pretend that the node has fallen behind its peers.
+    let head_slot = harness.chain.head_snapshot().beacon_block.slot();
+    tester
+        .ctx
+        .network_globals
+        .as_ref()
+        .unwrap()
+        .set_sync_state(SyncState::SyncingHead {
+            start_slot: head_slot,
+            target_slot: head_slot + 1,
+        });
+
+    let status = tester.client.get_node_health().await;
+    assert_eq!(status.unwrap(), StatusCode::PARTIAL_CONTENT);
+
+    let query = NodeHealthQuery {
+        syncing_tolerance: Some(E::slots_per_epoch()),
+        ..<_>::default()
+    };
+    let status = tester.client.get_node_health_with_query(&query).await;
+    assert_eq!(status.unwrap(), StatusCode::OK);
+}
@@ -1,10 +1,16 @@
+use beacon_chain::store::{BlobInfo, StoreOp};
 use beacon_chain::test_utils::RelativeSyncCommittee;
 use beacon_chain::{
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
+    validator_monitor::MAX_API_REGISTERED_VALIDATORS,
     BeaconChain, ChainConfig, StateSkipConfig, WhenSlotSkipped,
 };
 use environment::null_logger;
 use eth2::{
+    lighthouse::{
+        GossipArrival, GossipArrivalKind, GossipArrivalTimesQuery, LogLevelsData, PeerEventKind,
+        SseGenesisCountdown, SsePeer, TargetPeersData,
+    },
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
     types::{
@@ -17,14 +23,15 @@ use eth2::{
 use execution_layer::test_utils::{
     MockBuilder, Operation, DEFAULT_BUILDER_PAYLOAD_VALUE_WEI, DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
 };
+use execution_layer::BUILDER_REVEAL_FAILURE_FALLBACK_EPOCHS;
 use futures::stream::{Stream, StreamExt};
 use futures::FutureExt;
 use http_api::{
-    test_utils::{create_api_server, ApiServer},
+    test_utils::{create_api_server, ApiServer, API_TOKEN},
     BlockId, StateId,
 };
 use lighthouse_network::{types::SyncState, Enr, EnrExt, PeerId};
-use network::NetworkReceivers;
+use network::{NetworkMessage, NetworkReceivers};
 use proto_array::ExecutionStatus;
 use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
@@ -1558,6 +1565,116 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_error_codes(self) -> Self {
+        let unknown_root = Hash256::repeat_byte(0x42);
+
+        let url = self
+            .client
+            .get_beacon_blocks_path(CoreBlockId::Root(unknown_root))
+            .unwrap();
+        let err = self.client.get_response(url, |b| b).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+        assert_eq!(err.error_code(), Some(ErrorCode::UnknownBlock));
+
+        let url = self
+            .client
+            .get_debug_beacon_states_path(CoreStateId::Root(unknown_root))
+            .unwrap();
+        let err = self.client.get_response(url, |b| b).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+        assert_eq!(err.error_code(), Some(ErrorCode::UnknownState));
+
+        let mut url = self
+            .client
+            .get_beacon_blocks_path(CoreBlockId::Head)
+            .unwrap();
+        url.set_path("/eth/v2/beacon/unknown_route");
+        let err = self.client.get_response(url, |b| b).await.unwrap_err();
+        assert_eq!(err.error_code(), Some(ErrorCode::NotFound));
+
+        self
+    }
+
+    pub async fn test_pruned_error_codes(self) -> Self {
+        // The earliest block with blobs, which is no longer in the block cache.
+        let block = (1..CHAIN_LENGTH)
+            .filter_map(|slot| {
+                self.chain
+                    .block_at_slot(Slot::new(slot), WhenSlotSkipped::None)
+                    .unwrap()
+            })
+            .find(|block| {
+                block
+                    .message()
+                    .body()
+                    .blob_kzg_commitments()
+                    .map_or(false, |commitments| !commitments.is_empty())
+            })
+            .expect("a block should have blobs");
+        let block_root = block.canonical_root();
+
+        // Prune the block's blobs.
+        let store = &self.chain.store;
+        store
+            .do_atomically_with_block_and_blobs_cache(vec![StoreOp::DeleteBlobs(block_root)])
+            .unwrap();
+        let blob_info = store.get_blob_info();
+        store
+            .compare_and_set_blob_info_with_write(
+                blob_info.clone(),
+                BlobInfo {
+                    oldest_blob_slot: Some(block.slot() + 1),
+                    ..blob_info
+                },
+            )
+            .unwrap();
+
+        let url = self
+            .client
+            .get_blobs_path(CoreBlockId::Root(block_root))
+            .unwrap();
+        let err = self.client.get_response(url, |b| b).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+        assert_eq!(err.error_code(), Some(ErrorCode::Pruned));
+
+        // The payloads of finalized blocks are pruned from the database, so they can't be
+        // reconstructed once the execution layer has also lost them.
+        assert!(
+            block.slot()
+                < self
+                    .chain
+                    .canonical_head
+                    .cached_head()
+                    .finalized_checkpoint()
+                    .epoch
+                    .start_slot(E::slots_per_epoch())
+        );
+        self.harness
+            .mock_execution_layer
+            .as_ref()
+            .unwrap()
+            .server
+            .execution_block_generator()
+            .drop_all_blocks();
+
+        let url = self
+            .client
+            .get_beacon_blocks_path(CoreBlockId::Root(block_root))
+            .unwrap();
+        let err = self.client.get_response(url, |b| b).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+        assert_eq!(err.error_code(), Some(ErrorCode::Pruned));
+
+        // The blinded block remains available.
+        self.client
+            .get_beacon_blinded_blocks::<E>(CoreBlockId::Root(block_root))
+            .await
+            .unwrap()
+            .unwrap();
+
+        self
+    }
+
     pub async fn test_beacon_blinded_blocks(self) -> Self {
         for block_id in self.interesting_block_ids() {
             let expected = block_id
@@ -1756,9 +1873,14 @@ impl ApiTester {
                 code,
                 message: _,
                 failures,
+                error_code,
             }) => {
                 assert_eq!(code, 400);
+                assert_eq!(error_code, Some(ErrorCode::BadRequest));
                 assert_eq!(failures.len(), self.attestations.len());
+                assert!(failures
+                    .iter()
+                    .all(|failure| failure.error_code == Some(ErrorCode::InvalidObject)));
             }
             _ => panic!("query did not fail correctly"),
         }
@@ -1798,6 +1920,7 @@ impl ApiTester {
                 code,
                 message: _,
                 failures,
+                ..
             }) => {
                 assert_eq!(code, 400);
                 assert_eq!(failures.len(), self.attestations.len());
@@ -2384,6 +2507,71 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_historic_etags(self) -> Self {
+        // The genesis state is finalized, so it is served with an ETag.
+        let url = self
+            .client
+            .get_debug_beacon_states_path(CoreStateId::Genesis)
+            .unwrap();
+        let response = self
+            .client
+            .get_response(url.clone(), |b| b.accept(Accept::Ssz))
+            .await
+            .unwrap();
+        let etag = response.headers()["etag"].to_str().unwrap().to_string();
+        let body = response.bytes().await.unwrap();
+
+        // A repeated request is served from the cache and is identical.
+        let cached = self
+            .client
+            .get_response(url.clone(), |b| b.accept(Accept::Ssz))
+            .await
+            .unwrap();
+        assert_eq!(cached.headers()["etag"], etag.as_str());
+        assert_eq!(cached.bytes().await.unwrap(), body);
+
+        // A request with a matching `If-None-Match` is not sent the state again.
+        let error = self
+            .client
+            .get_response(url, |b| {
+                b.accept(Accept::Ssz).header("If-None-Match", etag.as_str())
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::NOT_MODIFIED));
+
+        // The JSON and SSZ encodings of a block have different ETags.
+        let url = self
+            .client
+            .get_beacon_blocks_path(CoreBlockId::Genesis)
+            .unwrap();
+        let json = self
+            .client
+            .get_response(url.clone(), |b| b.accept(Accept::Json))
+            .await
+            .unwrap();
+        let ssz = self
+            .client
+            .get_response(url, |b| b.accept(Accept::Ssz))
+            .await
+            .unwrap();
+        assert_ne!(json.headers()["etag"], ssz.headers()["etag"]);
+
+        // The head is not finalized, so it is never cached.
+        let url = self
+            .client
+            .get_debug_beacon_states_path(CoreStateId::Head)
+            .unwrap();
+        let response = self
+            .client
+            .get_response(url, |b| b.accept(Accept::Ssz))
+            .await
+            .unwrap();
+        assert!(response.headers().get("etag").is_none());
+
+        self
+    }
+
     pub async fn test_get_debug_beacon_heads(self) -> Self {
         let result = self
             .client
@@ -2714,6 +2902,26 @@ impl ApiTester {
 
                 assert_eq!(result, expected);
             }
+
+            // If it's the next epoch, check the function with a primed lookahead.
+            if epoch == current_epoch + 1 {
+                assert!(
+                    self.chain
+                        .beacon_proposer_cache
+                        .lock()
+                        .get_lookahead(self.chain.head_beacon_block_root(), epoch)
+                        .is_some(),
+                    "a next-epoch request should prime the proposer lookahead"
+                );
+
+                let result = self
+                    .client
+                    .get_validator_duties_proposer(epoch)
+                    .await
+                    .unwrap();
+
+                assert_eq!(result, expected);
+            }
         }
 
         // Requests to the epochs after the next epoch should fail.
@@ -3607,6 +3815,52 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_validator_aggregate_and_proofs_mixed_v2(mut self) -> Self {
+        let aggregate = self.get_aggregate().await;
+        let mut invalid_aggregate = aggregate.clone();
+        match &mut invalid_aggregate {
+            SignedAggregateAndProof::Base(ref mut aggregate) => {
+                aggregate.message.aggregate.data.slot += 1;
+            }
+            SignedAggregateAndProof::Electra(ref mut aggregate) => {
+                aggregate.message.aggregate.data.slot += 1;
+            }
+        }
+
+        let fork_name = self
+            .chain
+            .spec
+            .fork_name_at_slot::<E>(aggregate.message().aggregate().data().slot);
+        let err = self
+            .client
+            .post_validator_aggregate_and_proof_v2::<E>(&[aggregate, invalid_aggregate], fork_name)
+            .await
+            .unwrap_err();
+
+        match err {
+            Error::ServerIndexedMessage(IndexedErrorMessage {
+                code,
+                message: _,
+                failures,
+                ..
+            }) => {
+                assert_eq!(code, 400);
+                assert_eq!(
+                    failures.iter().map(|f| f.index).collect::<Vec<_>>(),
+                    vec![1]
+                );
+            }
+            _ => panic!("query did not fail correctly"),
+        }
+
+        assert!(
+            self.network_rx.network_recv.recv().await.is_some(),
+            "the valid aggregate should be sent to the network"
+        );
+
+        self
+    }
+
     pub async fn test_get_validator_beacon_committee_subscriptions(mut self) -> Self {
         let subscription = BeaconCommitteeSubscription {
             validator_index: 0,
@@ -3846,6 +4100,18 @@ impl ApiTester {
 
         assert_eq!(result, expected);
 
+        // Epochs more than one epoch from the current epoch are rejected.
+        assert_eq!(
+            self.client
+                .post_validator_liveness_epoch(epoch + 2, &indices)
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(400),
+            "should not check liveness more than one epoch ahead"
+        );
+
         self
     }
 
@@ -5027,6 +5293,79 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_builder_reveal_failure_falls_back_to_local(self) -> Self {
+        let mock_builder = self.mock_builder.as_ref().unwrap();
+        let el = self.chain.execution_layer.as_ref().unwrap();
+        let fork = self.chain.canonical_head.cached_head().head_fork();
+        let genesis_validators_root = self.chain.genesis_validators_root;
+
+        // Make the builder's payload more profitable so that it is chosen.
+        mock_builder.add_operation(Operation::Value(Uint256::from(
+            DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+        )));
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (proposer_index, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let block = self
+            .client
+            .get_validator_blinded_blocks::<E>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data;
+        let payload: BlindedPayload<E> = block.body().execution_payload().unwrap().into();
+        assert!(el.get_payload_by_root(&payload.tree_hash_root()).is_none());
+
+        // The builder withholds the payload, so the proposal fails.
+        let sk = &self.validator_keypairs()[proposer_index as usize].sk;
+        let signed_block = block.sign(sk, &fork, genesis_validators_root, &self.chain.spec);
+        mock_builder.withhold_payloads();
+        assert!(self
+            .client
+            .post_beacon_blinded_blocks(&signed_block)
+            .await
+            .is_err());
+        mock_builder.reveal_payloads();
+        assert_eq!(el.recent_builder_reveal_failure(slot + 1).await, Some(slot));
+
+        // The next proposal uses a local payload, even though the builder's is more profitable.
+        self.harness.advance_slot();
+        mock_builder.add_operation(Operation::Value(Uint256::from(
+            DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+        )));
+
+        let next_slot = self.chain.slot().unwrap();
+        let next_epoch = self.chain.epoch().unwrap();
+        let (_, randao_reveal) = self.get_test_randao(next_slot, next_epoch).await;
+
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks::<E>(next_slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+        assert!(el.get_payload_by_root(&payload.tree_hash_root()).is_some());
+
+        // The builder is used again once the fallback period has passed.
+        let fallback_slots = BUILDER_REVEAL_FAILURE_FALLBACK_EPOCHS * E::slots_per_epoch();
+        assert_eq!(
+            el.recent_builder_reveal_failure(slot + fallback_slots)
+                .await,
+            Some(slot)
+        );
+        assert_eq!(
+            el.recent_builder_reveal_failure(slot + fallback_slots + 1)
+                .await,
+            None
+        );
+        self
+    }
+
     pub async fn test_builder_payload_v3_chosen_when_more_profitable(self) -> Self {
         // Mutate value.
         self.mock_builder
@@ -5324,6 +5663,19 @@ impl ApiTester {
 
     #[cfg(target_os = "linux")]
     pub async fn test_get_lighthouse_health(self) -> Self {
+        let health = self
+            .client
+            .get_lighthouse_beacon_node_health()
+            .await
+            .unwrap()
+            .data;
+
+        let report = self.chain.chain_health_report().unwrap();
+        assert_eq!(health.chain.verdict, report.verdict);
+        assert_eq!(health.chain.head_slot, report.head_slot);
+        assert!(!health.chain.degraded_mode_active);
+
+        // Clients which only expect the process and system health are still supported.
         self.client.get_lighthouse_health().await.unwrap();
 
         self
@@ -5332,6 +5684,108 @@ impl ApiTester {
     #[cfg(not(target_os = "linux"))]
     pub async fn test_get_lighthouse_health(self) -> Self {
         self.client.get_lighthouse_health().await.unwrap_err();
+        self.client
+            .get_lighthouse_beacon_node_health()
+            .await
+            .unwrap_err();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_system_profile(self) -> Self {
+        let profile = self
+            .client
+            .get_lighthouse_system_profile()
+            .await
+            .unwrap()
+            .data;
+
+        let cache_names = profile
+            .caches
+            .iter()
+            .map(|cache| cache.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(cache_names, ["state_cache", "shuffling_cache", "op_pool"]);
+        assert!(profile.beacon_processor.event_channel_depth.is_some());
+
+        self
+    }
+
+    pub async fn test_lighthouse_logging_levels(self) -> Self {
+        let levels = LogLevelsData {
+            levels: "debug,network=trace".to_string(),
+        };
+        self.client
+            .post_lighthouse_logging_levels(&levels)
+            .await
+            .unwrap();
+        assert_eq!(
+            self.client
+                .get_lighthouse_logging_levels()
+                .await
+                .unwrap()
+                .data,
+            levels
+        );
+
+        // Invalid levels are rejected and leave the current levels unchanged.
+        self.client
+            .post_lighthouse_logging_levels(&LogLevelsData {
+                levels: "network=loud".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(
+            self.client
+                .get_lighthouse_logging_levels()
+                .await
+                .unwrap()
+                .data,
+            levels
+        );
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_peers_target(mut self) -> Self {
+        self.client
+            .post_lighthouse_peers_target(&TargetPeersData {
+                target_peers: 25,
+                max_pruned_peers_per_heartbeat: Some(5),
+            })
+            .await
+            .unwrap();
+        assert!(
+            matches!(
+                self.network_rx.network_recv.recv().await,
+                Some(NetworkMessage::UpdateTargetPeers {
+                    target_peers: 25,
+                    max_pruned_peers_per_heartbeat: Some(5),
+                })
+            ),
+            "target peers update should be sent to network"
+        );
+
+        // A target of zero peers is rejected.
+        self.client
+            .post_lighthouse_peers_target(&TargetPeersData {
+                target_peers: 0,
+                max_pruned_peers_per_heartbeat: None,
+            })
+            .await
+            .unwrap_err();
+
+        // A limit of zero pruned peers is rejected when the request is parsed.
+        let err = self
+            .client
+            .post_lighthouse_peers_target(&TargetPeersData {
+                target_peers: 25,
+                max_pruned_peers_per_heartbeat: Some(0),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+        assert!(self.network_rx.network_recv.try_recv().is_err());
 
         self
     }
@@ -5348,6 +5802,239 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_gossip_arrival_times(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let block_root = self.chain.head_beacon_block_root();
+        {
+            let mut arrivals = self.chain.gossip_arrival_times.lock();
+            arrivals.record(
+                GossipArrivalKind::Block,
+                slot,
+                block_root,
+                None,
+                Duration::from_millis(1_500),
+            );
+            arrivals.record(
+                GossipArrivalKind::Aggregate,
+                slot,
+                block_root,
+                Some(3),
+                Duration::from_millis(8_100),
+            );
+        }
+
+        let all = self
+            .client
+            .get_lighthouse_analysis_gossip_arrival_times(&GossipArrivalTimesQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+
+        let blocks = self
+            .client
+            .get_lighthouse_analysis_gossip_arrival_times(&GossipArrivalTimesQuery {
+                kind: Some(GossipArrivalKind::Block),
+                start_slot: Some(slot),
+                end_slot: Some(slot),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            blocks,
+            vec![GossipArrival {
+                kind: GossipArrivalKind::Block,
+                slot,
+                block_root,
+                index: None,
+                delay_ms: 1_500,
+            }]
+        );
+
+        let later = self
+            .client
+            .get_lighthouse_analysis_gossip_arrival_times(&GossipArrivalTimesQuery {
+                start_slot: Some(slot + 1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(later.is_empty());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_observed_block_packing(self) -> Self {
+        let head = self.chain.head_snapshot();
+        let block = &head.beacon_block;
+        let slot = block.slot();
+        let epoch = slot.epoch(E::slots_per_epoch());
+        let attestation = block
+            .message()
+            .body()
+            .attestations()
+            .next()
+            .expect("head block should contain attestations");
+        let included_vote = attestation.data().clone();
+        let missed_vote_root = Hash256::repeat_byte(0xaa);
+        {
+            let mut arrivals = self.chain.gossip_arrival_times.lock();
+            arrivals.record(
+                GossipArrivalKind::Aggregate,
+                included_vote.slot,
+                included_vote.beacon_block_root,
+                Some(0),
+                Duration::from_millis(0),
+            );
+            arrivals.record(
+                GossipArrivalKind::Aggregate,
+                slot - 1,
+                missed_vote_root,
+                Some(0),
+                Duration::from_millis(0),
+            );
+        }
+
+        let response = self
+            .client
+            .get_lighthouse_analysis_observed_block_packing(epoch, epoch)
+            .await
+            .unwrap();
+
+        let packing = response
+            .blocks
+            .iter()
+            .find(|packing| packing.slot == slot)
+            .expect("head block should be analysed");
+        assert_eq!(packing.block_root, head.beacon_block_root);
+        assert_eq!(packing.proposer_index, block.message().proposer_index());
+        assert!(packing.included_available_votes >= 1);
+        assert!(packing.available_votes > packing.included_available_votes);
+
+        let proposer = response
+            .proposers
+            .iter()
+            .find(|proposer| proposer.proposer_index == packing.proposer_index)
+            .expect("head proposer should be summarised");
+        assert!(proposer.efficiency.unwrap() < 1.0);
+
+        // The start epoch must not be after the end epoch.
+        self.client
+            .get_lighthouse_analysis_observed_block_packing(epoch + 1, epoch)
+            .await
+            .unwrap_err();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_churn_analysis(self) -> Self {
+        let result = self.client.get_lighthouse_analysis_churn().await.unwrap();
+
+        let state = self.chain.head_beacon_state_cloned();
+        let current_epoch = state.current_epoch();
+        let validator_count = state.validators().len() as u64;
+        assert_eq!(result.epoch, current_epoch);
+        assert_eq!(result.active_validators, validator_count);
+        assert_eq!(
+            result.churn_limit,
+            std::cmp::max(
+                self.chain.spec.min_per_epoch_churn_limit,
+                validator_count / self.chain.spec.churn_limit_quotient
+            )
+        );
+
+        // All of the harness validators were active at genesis and none have exited.
+        assert_eq!(result.activation_queue_length, 0);
+        assert!(result.pending_activations.is_empty());
+        assert_eq!(result.exit_queue_length, 0);
+        assert!(result.pending_exits.is_empty());
+        assert_eq!(
+            result.next_exit_epoch,
+            self.chain
+                .spec
+                .compute_activation_exit_epoch(current_epoch)
+                .unwrap()
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_fork_readiness(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_fork_readiness()
+            .await
+            .unwrap()
+            .data;
+
+        let expected = self.chain.check_fork_readiness().await;
+        assert_eq!(result, expected);
+        assert_eq!(result.next_fork, result.current_fork.next_fork());
+        assert_eq!(
+            result.next_fork_epoch,
+            result
+                .next_fork
+                .and_then(|fork| self.chain.spec.fork_epoch(fork))
+        );
+        if result.next_fork_epoch.is_none() {
+            assert!(!result.ready);
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_fork_choice(self) -> Self {
+        let result = self.client.get_lighthouse_fork_choice().await.unwrap().data;
+        let standard = self.client.get_debug_fork_choice().await.unwrap();
+
+        let beacon_fork_choice = self.chain.canonical_head.fork_choice_read_lock();
+        let expected_proto_array = beacon_fork_choice.proto_array().core_proto_array();
+
+        assert_eq!(result.justified_checkpoint, standard.justified_checkpoint);
+        assert_eq!(result.finalized_checkpoint, standard.finalized_checkpoint);
+        assert_eq!(
+            result.unrealized_justified_checkpoint,
+            beacon_fork_choice.unrealized_justified_checkpoint()
+        );
+        assert_eq!(
+            result.unrealized_finalized_checkpoint,
+            beacon_fork_choice.unrealized_finalized_checkpoint()
+        );
+        assert_eq!(
+            result.proposer_boost_root,
+            beacon_fork_choice.proposer_boost_root()
+        );
+
+        // The extended nodes should be a superset of the standard nodes.
+        assert_eq!(
+            result.fork_choice_nodes.len(),
+            standard.fork_choice_nodes.len()
+        );
+        for ((node, standard_node), proto_node) in result
+            .fork_choice_nodes
+            .iter()
+            .zip(&standard.fork_choice_nodes)
+            .zip(&expected_proto_array.nodes)
+        {
+            assert_eq!(&node.node, standard_node);
+            assert_eq!(
+                node.unrealized_justified_epoch,
+                proto_node
+                    .unrealized_justified_checkpoint
+                    .map(|checkpoint| checkpoint.epoch)
+            );
+            assert_eq!(
+                node.unrealized_finalized_epoch,
+                proto_node
+                    .unrealized_finalized_checkpoint
+                    .map(|checkpoint| checkpoint.epoch)
+            );
+        }
+
+        drop(beacon_fork_choice);
+
+        self
+    }
+
     pub async fn test_get_lighthouse_validator_inclusion_global(self) -> Self {
         let epoch = self.chain.epoch().unwrap() - 1;
         self.client
@@ -5425,6 +6112,38 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_lighthouse_validator_monitor_register(self) -> Self {
+        let pubkeys = self
+            .chain
+            .head_beacon_state_cloned()
+            .validators()
+            .iter()
+            .map(|validator| validator.pubkey)
+            .collect::<Vec<_>>();
+
+        self.client
+            .post_lighthouse_validator_monitor_register(&pubkeys, API_TOKEN)
+            .await
+            .unwrap();
+
+        // Requests exceeding the cap are rejected.
+        let too_many = vec![pubkeys[0]; MAX_API_REGISTERED_VALIDATORS + 1];
+        self.client
+            .post_lighthouse_validator_monitor_register(&too_many, API_TOKEN)
+            .await
+            .unwrap_err();
+
+        // Requests without the API token are rejected.
+        let err = self
+            .client
+            .post_lighthouse_validator_monitor_register(&pubkeys, "wrong-token")
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+
+        self
+    }
+
     pub async fn test_post_lighthouse_liveness(self) -> Self {
         let epoch = self.chain.epoch().unwrap();
         let head_state = self.chain.head_beacon_state_cloned();
@@ -5804,6 +6523,89 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_events(self) -> Self {
+        let mut events_future = self
+            .client
+            .get_lighthouse_events::<E>(&[LighthouseEventTopic::Peer])
+            .await
+            .unwrap();
+
+        let event_handler = self.chain.event_handler.as_ref().unwrap();
+        assert!(event_handler.has_peer_subscribers());
+
+        let events = [PeerEventKind::Connected, PeerEventKind::Disconnected].map(|kind| {
+            EventKind::Peer(SsePeer {
+                peer_id: "16Uiu2HAm7s2Vb4eTz1FfpGDSDkGWNdS7QVNsE3sVN8KzsTvxCHoV".to_string(),
+                kind,
+                direction: Some("incoming".to_string()),
+                reason_code: (kind == PeerEventKind::Disconnected).then_some(3),
+                reason: (kind == PeerEventKind::Disconnected).then(|| "Fault".to_string()),
+                goodbye_sent: (kind == PeerEventKind::Disconnected).then_some(true),
+                client: "Lighthouse".to_string(),
+                score: -12.5,
+                enr: None,
+            })
+        });
+        for event in &events {
+            event_handler.register(event.clone());
+        }
+
+        let received = poll_events(
+            &mut events_future,
+            events.len(),
+            Duration::from_millis(10000),
+        )
+        .await;
+        assert_eq!(received.as_slice(), events.as_slice());
+
+        // Lighthouse events are not sent on the standard event stream.
+        let mut standard_events = self
+            .client
+            .get_events::<E>(&[EventTopic::Head])
+            .await
+            .unwrap();
+        event_handler.register(events[0].clone());
+        assert!(
+            poll_events(&mut standard_events, 1, Duration::from_millis(500))
+                .await
+                .is_empty()
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_genesis_countdown_events(self) -> Self {
+        let mut events_future = self
+            .client
+            .get_lighthouse_events::<E>(&[LighthouseEventTopic::GenesisCountdown])
+            .await
+            .unwrap();
+
+        let event_handler = self.chain.event_handler.as_ref().unwrap();
+        assert!(event_handler.has_genesis_countdown_subscribers());
+
+        let events = [60, 48].map(|seconds_to_genesis| {
+            EventKind::GenesisCountdown(SseGenesisCountdown {
+                genesis_time: self.chain.genesis_time,
+                seconds_to_genesis,
+                connected_peers: 3,
+            })
+        });
+        for event in &events {
+            event_handler.register(event.clone());
+        }
+
+        let received = poll_events(
+            &mut events_future,
+            events.len(),
+            Duration::from_millis(10000),
+        )
+        .await;
+        assert_eq!(received.as_slice(), events.as_slice());
+
+        self
+    }
+
     pub async fn test_get_events_from_genesis(self) -> Self {
         let topics = vec![EventTopic::Block, EventTopic::Head];
         let mut events_future = self
@@ -5916,6 +6718,19 @@ async fn get_events_altair() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_lighthouse_events() {
+    ApiTester::new().await.test_get_lighthouse_events().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_lighthouse_genesis_countdown_events() {
+    ApiTester::new()
+        .await
+        .test_get_lighthouse_genesis_countdown_events()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_events_from_genesis() {
     ApiTester::new_from_genesis()
@@ -5992,6 +6807,11 @@ async fn beacon_get() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn error_codes() {
+    ApiTester::new().await.test_error_codes().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn post_beacon_blocks_valid() {
     ApiTester::new().await.test_post_beacon_blocks_valid().await;
@@ -6143,6 +6963,8 @@ async fn debug_get() {
         .await
         .test_get_debug_beacon_states()
         .await
+        .test_get_historic_etags()
+        .await
         .test_get_debug_beacon_heads()
         .await
         .test_get_debug_fork_choice()
@@ -6477,6 +7299,14 @@ async fn get_validator_aggregate_and_proofs_invalid_v2() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_validator_aggregate_and_proofs_mixed_v2() {
+    ApiTester::new()
+        .await
+        .test_get_validator_aggregate_and_proofs_mixed_v2()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_validator_aggregate_and_proofs_invalid_with_skip_slots_v2() {
     ApiTester::new()
@@ -6730,6 +7560,14 @@ async fn builder_payload_chosen_by_profit() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_reveal_failure_falls_back_to_local() {
+    ApiTester::new_mev_tester_default_payload_value()
+        .await
+        .test_builder_reveal_failure_falls_back_to_local()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn builder_payload_chosen_by_profit_v3() {
     ApiTester::new_mev_tester_default_payload_value()
@@ -6804,6 +7642,23 @@ async fn get_blob_sidecars() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pruned_error_codes() {
+    let mut config = ApiTesterConfig {
+        retain_historic_states: false,
+        spec: E::default_spec(),
+    };
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+    config.spec.deneb_fork_epoch = Some(Epoch::new(0));
+
+    ApiTester::new_from_config(config)
+        .await
+        .test_pruned_error_codes()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn post_validator_liveness_epoch() {
     ApiTester::new()
@@ -6820,8 +7675,24 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_syncing()
         .await
+        .test_lighthouse_logging_levels()
+        .await
+        .test_post_lighthouse_peers_target()
+        .await
+        .test_get_lighthouse_system_profile()
+        .await
         .test_get_lighthouse_proto_array()
         .await
+        .test_get_lighthouse_fork_choice()
+        .await
+        .test_get_lighthouse_gossip_arrival_times()
+        .await
+        .test_get_lighthouse_observed_block_packing()
+        .await
+        .test_get_lighthouse_churn_analysis()
+        .await
+        .test_get_lighthouse_fork_readiness()
+        .await
         .test_get_lighthouse_validator_inclusion()
         .await
         .test_get_lighthouse_validator_inclusion_global()
@@ -6839,6 +7710,8 @@ async fn lighthouse_endpoints() {
         .test_post_lighthouse_database_reconstruct()
         .await
         .test_post_lighthouse_liveness()
+        .await
+        .test_post_lighthouse_validator_monitor_register()
         .await;
 }
 
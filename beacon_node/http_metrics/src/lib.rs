@@ -3,6 +3,8 @@
 //! For other endpoints, see the `http_api` crate.
 mod metrics;
 
+pub use metrics::register_additional_network;
+
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use lighthouse_network::prometheus_client::registry::Registry;
 use lighthouse_version::version_with_platform;
@@ -52,6 +54,9 @@ pub struct Config {
     pub listen_port: u16,
     pub allow_origin: Option<String>,
     pub allocator_metrics_enabled: bool,
+    /// The value of the `network` label added to every metric, set when beacon nodes for several
+    /// networks share the process.
+    pub network: Option<String>,
 }
 
 impl Default for Config {
@@ -62,6 +67,7 @@ impl Default for Config {
             listen_port: 5054,
             allow_origin: None,
             allocator_metrics_enabled: true,
+            network: None,
         }
     }
 }
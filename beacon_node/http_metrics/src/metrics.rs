@@ -1,8 +1,32 @@
 use crate::Context;
 use beacon_chain::BeaconChainTypes;
-use lighthouse_metrics::TextEncoder;
+use lazy_static::lazy_static;
+use lighthouse_metrics::{LabelPair, MetricFamily, TextEncoder};
 use lighthouse_network::prometheus_client::encoding::text::encode;
 use malloc_utils::scrape_allocator_metrics;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+type GatherFn = Box<dyn Fn() -> Vec<MetricFamily> + Send + Sync>;
+
+lazy_static! {
+    /// Gathers the labelled metrics of each additional network in the process.
+    ///
+    /// The lock is held for the whole of each scrape, since every network scrapes its metrics into
+    /// the same global registry.
+    static ref ADDITIONAL_NETWORKS: Mutex<Vec<GatherFn>> = Mutex::new(vec![]);
+}
+
+/// Serve the metrics of the beacon node in `ctx` from the metrics server of the process, under
+/// its own `network` label.
+///
+/// Metrics which are updated as events occur are shared by every network in the process, so they
+/// are reported with the same value under each `network` label.
+pub fn register_additional_network<T: BeaconChainTypes>(ctx: Arc<Context<T>>) {
+    ADDITIONAL_NETWORKS
+        .lock()
+        .push(Box::new(move || scrape_and_gather(&ctx)));
+}
 
 pub fn gather_prometheus_metrics<T: BeaconChainTypes>(
     ctx: &Context<T>,
@@ -10,6 +34,27 @@ pub fn gather_prometheus_metrics<T: BeaconChainTypes>(
     let mut buffer = String::new();
     let encoder = TextEncoder::new();
 
+    let additional_networks = ADDITIONAL_NETWORKS.lock();
+    let mut metric_families = scrape_and_gather(ctx);
+    for gather in additional_networks.iter() {
+        merge_metric_families(&mut metric_families, gather());
+    }
+    drop(additional_networks);
+
+    encoder.encode_utf8(&metric_families, &mut buffer).unwrap();
+    // encode gossipsub metrics also if they exist
+    if let Some(registry) = ctx.gossipsub_registry.as_ref() {
+        if let Ok(registry_locked) = registry.lock() {
+            let _ = encode(&mut buffer, &registry_locked);
+        }
+    }
+
+    Ok(buffer)
+}
+
+/// Scrape the metrics of the beacon node in `ctx` and gather them, labelled with its network if
+/// one is configured.
+fn scrape_and_gather<T: BeaconChainTypes>(ctx: &Context<T>) -> Vec<MetricFamily> {
     // There are two categories of metrics:
     //
     // - Dynamically updated: things like histograms and event counters that are updated on the
@@ -23,8 +68,7 @@ pub fn gather_prometheus_metrics<T: BeaconChainTypes>(
     // things need to be "scraped".
     //
     // We proceed by, first updating all the static metrics using `scrape_for_metrics(..)`. Then,
-    // using `lighthouse_metrics::gather(..)` to collect the global `DEFAULT_REGISTRY` metrics into
-    // a string that can be returned via HTTP.
+    // using `lighthouse_metrics::gather(..)` to collect the global `DEFAULT_REGISTRY` metrics.
 
     if let Some(beacon_chain) = ctx.chain.as_ref() {
         slot_clock::scrape_for_metrics::<T::EthSpec, T::SlotClock>(&beacon_chain.slot_clock);
@@ -47,15 +91,39 @@ pub fn gather_prometheus_metrics<T: BeaconChainTypes>(
         scrape_allocator_metrics();
     }
 
-    encoder
-        .encode_utf8(&lighthouse_metrics::gather(), &mut buffer)
-        .unwrap();
-    // encode gossipsub metrics also if they exist
-    if let Some(registry) = ctx.gossipsub_registry.as_ref() {
-        if let Ok(registry_locked) = registry.lock() {
-            let _ = encode(&mut buffer, &registry_locked);
-        }
+    let mut metric_families = lighthouse_metrics::gather();
+    if let Some(network) = &ctx.config.network {
+        add_network_label(&mut metric_families, network);
     }
+    metric_families
+}
 
-    Ok(buffer)
+/// Add a `network` label to every metric in `metric_families`.
+fn add_network_label(metric_families: &mut [MetricFamily], network: &str) {
+    for metric in metric_families
+        .iter_mut()
+        .flat_map(|metric_family| metric_family.mut_metric().iter_mut())
+    {
+        let mut label = LabelPair::default();
+        label.set_name("network".to_string());
+        label.set_value(network.to_string());
+        metric.mut_label().push(label);
+    }
+}
+
+/// Merge `other` into `metric_families`, so that each metric family is only encoded once.
+fn merge_metric_families(metric_families: &mut Vec<MetricFamily>, other: Vec<MetricFamily>) {
+    for mut metric_family in other {
+        match metric_families
+            .iter_mut()
+            .find(|existing| existing.get_name() == metric_family.get_name())
+        {
+            Some(existing) => {
+                for metric in metric_family.take_metric().into_vec() {
+                    existing.mut_metric().push(metric);
+                }
+            }
+            None => metric_families.push(metric_family),
+        }
+    }
 }
@@ -22,6 +22,7 @@ async fn returns_200_ok() {
                 listen_port: 0,
                 allow_origin: None,
                 allocator_metrics_enabled: true,
+                network: None,
             },
             chain: None,
             db_path: None,
@@ -56,3 +57,59 @@ async fn returns_200_ok() {
     }
     .await
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn labels_metrics_with_each_network() {
+    async {
+        let log = null_logger().unwrap();
+        let context = |network: &str, enabled: bool| {
+            Arc::new(Context {
+                config: Config {
+                    enabled,
+                    listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                    listen_port: 0,
+                    allow_origin: None,
+                    allocator_metrics_enabled: true,
+                    network: Some(network.to_string()),
+                },
+                chain: None,
+                db_path: None,
+                freezer_db_path: None,
+                gossipsub_registry: None,
+                log: log.clone(),
+            })
+        };
+
+        http_metrics::register_additional_network(context("holesky", false));
+
+        let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server_shutdown = async {
+            let _ = shutdown_rx.await;
+        };
+        let (listening_socket, server) =
+            http_metrics::serve(context("mainnet", true), server_shutdown).unwrap();
+
+        tokio::spawn(server);
+
+        let url = format!(
+            "http://{}:{}/metrics",
+            listening_socket.ip(),
+            listening_socket.port()
+        );
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.text().await.unwrap();
+
+        assert!(body.contains("network=\"mainnet\""));
+        assert!(body.contains("network=\"holesky\""));
+        // Each metric family is only described once, whichever networks report it.
+        let type_lines = body
+            .lines()
+            .filter(|line| line.starts_with("# TYPE "))
+            .collect::<Vec<_>>();
+        let unique_type_lines = type_lines.iter().collect::<std::collections::HashSet<_>>();
+        assert_eq!(type_lines.len(), unique_type_lines.len());
+    }
+    .await
+}
@@ -85,6 +85,9 @@ pub struct Config {
     /// List of trusted libp2p nodes which are not scored and marked as explicit.
     pub trusted_peers: Vec<PeerIdSerialized>,
 
+    /// A file of allow and deny lists of IP ranges and node IDs applied to all connections.
+    pub peer_filter_file: Option<PathBuf>,
+
     /// Disables peer scoring altogether.
     pub disable_peer_scoring: bool,
 
@@ -130,6 +133,9 @@ pub struct Config {
     /// Whether light client protocols should be enabled.
     pub enable_light_client_server: bool,
 
+    /// Whether the Lighthouse-specific deposit snapshot protocol should be enabled.
+    pub enable_deposit_snapshot_rpc: bool,
+
     /// Configuration for the outbound rate limiter (requests made by this node).
     pub outbound_rate_limiter_config: Option<OutboundRateLimiterConfig>,
 
@@ -331,6 +337,7 @@ impl Default for Config {
             boot_nodes_multiaddr: vec![],
             libp2p_nodes: vec![],
             trusted_peers: vec![],
+            peer_filter_file: None,
             disable_peer_scoring: false,
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
@@ -345,6 +352,7 @@ impl Default for Config {
             proposer_only: false,
             metrics_enabled: false,
             enable_light_client_server: false,
+            enable_deposit_snapshot_rpc: false,
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
             inbound_rate_limiter_config: None,
@@ -31,10 +31,10 @@ pub use libp2p::{
 };
 use lru::LruCache;
 use slog::{crit, debug, error, info, trace, warn};
-use ssz::Encode;
+use ssz::{Decode, Encode};
 use std::num::NonZeroUsize;
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     net::{IpAddr, SocketAddr},
     path::Path,
     pin::Pin,
@@ -47,6 +47,11 @@ use types::{ChainSpec, EnrForkId, EthSpec};
 
 mod subnet_predicate;
 pub use subnet_predicate::subnet_predicate;
+mod topics;
+use topics::{
+    advertised_subnets, handle_topic_request, topic_node_id, TopicAds, TopicRegistration,
+    TopicRequest, TopicResponse, REGISTRATION_INTERVAL, TOPIC_PROTOCOL, TOPIC_REGISTRARS,
+};
 use types::non_zero_usize::new_non_zero_usize;
 
 /// Local ENR storage filename.
@@ -71,6 +76,9 @@ pub const FIND_NODE_QUERY_CLOSEST_PEERS: usize = 16;
 const DURATION_DIFFERENCE: Duration = Duration::from_millis(1);
 /// The capacity of the Discovery ENR cache.
 const ENR_CACHE_CAPACITY: NonZeroUsize = new_non_zero_usize(50);
+/// The duration an ENR is kept in the Discovery ENR cache. Peers change the subnets they
+/// advertise, so stale entries are not used to find subnet peers.
+const CACHED_ENR_TTL: Duration = Duration::from_secs(10 * 60);
 
 /// A query has completed. This result contains a mapping of discovered peer IDs to the `min_ttl`
 /// of the peer if it is specified.
@@ -129,6 +137,9 @@ impl std::fmt::Debug for SubnetQuery {
 enum QueryType {
     /// We are searching for subnet peers.
     Subnet(Vec<SubnetQuery>),
+    /// We are searching for the nodes closest to a subnet's topic id, to query them for peers
+    /// advertising the subnet.
+    TopicLookup(SubnetQuery),
     /// We are searching for more peers without ENR or time constraints.
     FindPeers,
 }
@@ -155,8 +166,9 @@ enum EventStream {
 /// The main discovery service. This can be disabled via CLI arguements. When disabled the
 /// underlying processes are not started, but this struct still maintains our current ENR.
 pub struct Discovery<E: EthSpec> {
-    /// A collection of seen live ENRs for quick lookup and to map peer-id's to ENRs.
-    cached_enrs: LruCache<PeerId, Enr>,
+    /// A collection of seen live ENRs for quick lookup and to map peer-id's to ENRs, along with
+    /// the time they were cached.
+    cached_enrs: LruCache<PeerId, (Enr, Instant)>,
 
     /// The directory where the ENR is stored.
     enr_dir: String,
@@ -180,6 +192,24 @@ pub struct Discovery<E: EthSpec> {
     /// Active discovery queries.
     active_queries: FuturesUnordered<std::pin::Pin<Box<dyn Future<Output = QueryResult> + Send>>>,
 
+    /// Peers found in the local routing table or ENR cache which advertise a subnet we are
+    /// searching for. These are returned to the peer manager on the next poll.
+    local_subnet_results: HashMap<Enr, Option<Instant>>,
+
+    /// The subnet advertisements we hold as a registrar for other nodes.
+    topic_ads: TopicAds,
+
+    /// Lookups for the nodes to register our subnet advertisements with.
+    topic_registrations: FuturesUnordered<
+        Pin<Box<dyn Future<Output = (Subnet, Result<Vec<Enr>, discv5::QueryError>)> + Send>>,
+    >,
+
+    /// In-flight advertisement registrations.
+    topic_requests: FuturesUnordered<Pin<Box<dyn Future<Output = ()> + Send>>>,
+
+    /// Triggers re-advertising our subnets before the registrars expire them.
+    registration_interval: tokio::time::Interval,
+
     /// The discv5 event stream.
     event_stream: EventStream,
 
@@ -321,6 +351,11 @@ impl<E: EthSpec> Discovery<E> {
             find_peer_active: false,
             queued_queries: VecDeque::with_capacity(10),
             active_queries: FuturesUnordered::new(),
+            local_subnet_results: HashMap::new(),
+            topic_ads: TopicAds::default(),
+            topic_registrations: FuturesUnordered::new(),
+            topic_requests: FuturesUnordered::new(),
+            registration_interval: tokio::time::interval(REGISTRATION_INTERVAL),
             discv5,
             event_stream,
             started: !config.disable_discovery,
@@ -336,14 +371,41 @@ impl<E: EthSpec> Discovery<E> {
         self.discv5.local_enr()
     }
 
-    /// Return the cached enrs.
+    /// Return the cached enrs which have not expired.
     pub fn cached_enrs(&self) -> impl Iterator<Item = (&PeerId, &Enr)> {
-        self.cached_enrs.iter()
+        self.cached_enrs
+            .iter()
+            .filter(|(_, (_, cached_at))| cached_at.elapsed() < CACHED_ENR_TTL)
+            .map(|(peer_id, (enr, _))| (peer_id, enr))
     }
 
     /// Removes a cached ENR from the list.
     pub fn remove_cached_enr(&mut self, peer_id: &PeerId) -> Option<Enr> {
-        self.cached_enrs.pop(peer_id)
+        self.cached_enrs.pop(peer_id).map(|(enr, _)| enr)
+    }
+
+    /// Adds an ENR to the cache, refreshing its expiry. An older version of an ENR already in the
+    /// cache does not replace the newer one.
+    fn cache_enr(&mut self, enr: Enr) {
+        let peer_id = enr.peer_id();
+        let enr = match self.cached_enrs.pop(&peer_id) {
+            Some((cached, _)) if cached.seq() > enr.seq() => cached,
+            _ => enr,
+        };
+        self.cached_enrs.put(peer_id, (enr, Instant::now()));
+    }
+
+    /// Removes expired ENRs from the cache.
+    fn prune_cached_enrs(&mut self) {
+        let expired: Vec<PeerId> = self
+            .cached_enrs
+            .iter()
+            .filter(|(_, (_, cached_at))| cached_at.elapsed() >= CACHED_ENR_TTL)
+            .map(|(peer_id, _)| *peer_id)
+            .collect();
+        for peer_id in expired {
+            self.cached_enrs.pop(&peer_id);
+        }
     }
 
     /// This adds a new `FindPeers` query to the queue if one doesn't already exist.
@@ -380,7 +442,7 @@ impl<E: EthSpec> Discovery<E> {
     /// Add an ENR to the routing table of the discovery mechanism.
     pub fn add_enr(&mut self, enr: Enr) {
         // add the enr to seen caches
-        self.cached_enrs.put(enr.peer_id(), enr.clone());
+        self.cache_enr(enr.clone());
 
         if let Err(e) = self.discv5.add_enr(enr) {
             debug!(
@@ -560,6 +622,11 @@ impl<E: EthSpec> Discovery<E> {
 
         // persist modified enr to disk
         enr::save_enr_to_disk(Path::new(&self.enr_dir), &self.local_enr(), &self.log);
+
+        // advertise the new subnet
+        if value {
+            self.start_topic_registration(subnet);
+        }
         Ok(())
     }
 
@@ -595,6 +662,11 @@ impl<E: EthSpec> Discovery<E> {
 
         // persist modified enr to disk
         enr::save_enr_to_disk(Path::new(&self.enr_dir), &self.local_enr(), &self.log);
+
+        // Topic ids depend on the fork digest. Drop the advertisements we hold for the old fork
+        // and advertise our subnets on the new one.
+        self.topic_ads.clear();
+        self.register_local_topics();
     }
 
     // Bans a peer and it's associated seen IP addresses.
@@ -723,37 +795,56 @@ impl<E: EthSpec> Discovery<E> {
         let mut filtered_subnets: Vec<Subnet> = Vec::new();
 
         // find subnet queries that are still necessary
-        let filtered_subnet_queries: Vec<SubnetQuery> = subnet_queries
-            .into_iter()
-            .filter(|subnet_query| {
-                // Determine if we have sufficient peers, which may make this discovery unnecessary.
-                let peers_on_subnet = self
-                    .network_globals
-                    .peers
-                    .read()
-                    .good_peers_on_subnet(subnet_query.subnet)
-                    .count();
-
-                if peers_on_subnet >= TARGET_SUBNET_PEERS {
-                    debug!(self.log, "Discovery ignored";
-                        "reason" => "Already connected to desired peers",
-                        "connected_peers_on_subnet" => peers_on_subnet,
-                        "target_subnet_peers" => TARGET_SUBNET_PEERS,
-                    );
-                    return false;
-                }
+        let mut filtered_subnet_queries: Vec<SubnetQuery> = Vec::new();
+        for subnet_query in subnet_queries {
+            // Determine if we have sufficient peers, which may make this discovery unnecessary.
+            let peers_on_subnet = self
+                .network_globals
+                .peers
+                .read()
+                .good_peers_on_subnet(subnet_query.subnet)
+                .count();
+
+            if peers_on_subnet >= TARGET_SUBNET_PEERS {
+                debug!(self.log, "Discovery ignored";
+                    "reason" => "Already connected to desired peers",
+                    "connected_peers_on_subnet" => peers_on_subnet,
+                    "target_subnet_peers" => TARGET_SUBNET_PEERS,
+                );
+                continue;
+            }
+
+            let target_peers = TARGET_SUBNET_PEERS.saturating_sub(peers_on_subnet);
 
-                let target_peers = TARGET_SUBNET_PEERS.saturating_sub(peers_on_subnet);
-                trace!(self.log, "Discovery query started for subnet";
+            // Peers advertise the subnets they are subscribed to in their ENR. If we already know
+            // of enough such peers there is no need to wait on a random walk of the DHT.
+            let peers_found_locally = self.find_local_subnet_peers(&subnet_query);
+            if peers_found_locally >= target_peers {
+                debug!(self.log, "Discovery query satisfied locally";
                     "subnet_query" => ?subnet_query,
-                    "connected_peers_on_subnet" => peers_on_subnet,
+                    "peers_found" => peers_found_locally,
                     "peers_to_find" => target_peers,
                 );
+                continue;
+            }
 
-                filtered_subnets.push(subnet_query.subnet);
-                true
-            })
-            .collect();
+            trace!(self.log, "Discovery query started for subnet";
+                "subnet_query" => ?subnet_query,
+                "connected_peers_on_subnet" => peers_on_subnet,
+                "peers_found_locally" => peers_found_locally,
+                "peers_to_find" => target_peers,
+            );
+
+            // On the first attempt, ask the registrars of the subnet's topic for peers advertising
+            // it. If they don't have enough, the query is retried with a random walk.
+            if subnet_query.retries == 0 && !matches!(subnet_query.subnet, Subnet::DataColumn(_)) {
+                self.start_topic_query(subnet_query);
+                continue;
+            }
+
+            filtered_subnets.push(subnet_query.subnet);
+            filtered_subnet_queries.push(subnet_query);
+        }
 
         // Only start a discovery query if we have a subnet to look for.
         if !filtered_subnet_queries.is_empty() {
@@ -774,6 +865,187 @@ impl<E: EthSpec> Discovery<E> {
         }
     }
 
+    /// Searches the local routing table and ENR cache for peers which advertise the queried subnet
+    /// in their ENR and which we are not already connected to. Any that are found are queued to be
+    /// returned to the peer manager.
+    ///
+    /// Returns the number of peers found.
+    fn find_local_subnet_peers(&mut self, subnet_query: &SubnetQuery) -> usize {
+        let fork_digest = match self.local_enr().eth2() {
+            Ok(enr_fork_id) => enr_fork_id.fork_digest,
+            Err(_) => return 0,
+        };
+        let subnet_predicate =
+            subnet_predicate::<E>(vec![subnet_query.subnet], &self.log, self.spec.clone());
+
+        // Discv5 keeps the ENRs in its routing table up to date, so use the newest version of each
+        // ENR rather than one cached before the peer changed its subnets.
+        let mut candidates: HashMap<NodeId, Enr> = HashMap::new();
+        for enr in self
+            .discv5
+            .table_entries_enr()
+            .into_iter()
+            .chain(self.cached_enrs().map(|(_, enr)| enr.clone()))
+        {
+            match candidates.entry(enr.node_id()) {
+                Entry::Occupied(mut entry) => {
+                    if enr.seq() > entry.get().seq() {
+                        entry.insert(enr);
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(enr);
+                }
+            }
+        }
+
+        let found: HashSet<Enr> = {
+            let peers = self.network_globals.peers.read();
+            candidates
+                .into_values()
+                .filter(|enr| {
+                    eth2_fork_predicate(enr, fork_digest)
+                        && !peers.is_connected_or_dialing(&enr.peer_id())
+                        && subnet_predicate(enr)
+                })
+                .collect()
+        };
+
+        if !found.is_empty() {
+            let query_str = match subnet_query.subnet {
+                Subnet::Attestation(_) => "attestation",
+                Subnet::SyncCommittee(_) => "sync_committee",
+                Subnet::DataColumn(_) => "data_column",
+            };
+            if let Some(v) =
+                metrics::get_int_counter(&metrics::SUBNET_PEERS_FOUND_LOCALLY, &[query_str])
+            {
+                v.inc_by(found.len() as u64);
+            }
+        }
+
+        let peers_found = found.len();
+        for enr in found {
+            // Retain the min_ttl furthest in the future.
+            let min_ttl = self.local_subnet_results.entry(enr).or_insert(None);
+            *min_ttl = std::cmp::max(*min_ttl, subnet_query.min_ttl);
+        }
+        peers_found
+    }
+
+    /// Starts a lookup of the nodes closest to the topic id of the queried subnet. Once they are
+    /// found they are queried for peers advertising the subnet.
+    fn start_topic_query(&mut self, subnet_query: SubnetQuery) {
+        let fork_digest = match self.local_enr().eth2() {
+            Ok(enr_fork_id) => enr_fork_id.fork_digest,
+            Err(e) => {
+                crit!(self.log, "Local ENR has no fork id"; "error" => e);
+                return;
+            }
+        };
+        debug!(self.log, "Starting subnet topic query"; "subnet_query" => ?subnet_query);
+        let query_future = self
+            .discv5
+            .find_node_predicate(
+                topic_node_id(fork_digest, subnet_query.subnet),
+                Box::new(move |enr: &Enr| eth2_fork_predicate(enr, fork_digest)),
+                TOPIC_REGISTRARS,
+            )
+            .map(|result| QueryResult {
+                query_type: QueryType::TopicLookup(subnet_query),
+                result,
+            });
+        self.active_queries.push(Box::pin(query_future));
+    }
+
+    /// Queries the registrars of a subnet's topic for peers advertising the subnet. The peers
+    /// they return are handled as the result of a subnet query.
+    fn query_topic_registrars(&mut self, subnet_query: SubnetQuery, registrars: Vec<Enr>) {
+        let Ok(enr_fork_id) = self.local_enr().eth2() else {
+            return;
+        };
+        let subnet_predicate =
+            subnet_predicate::<E>(vec![subnet_query.subnet], &self.log, self.spec.clone());
+        let request = TopicRequest::Query(subnet_query.subnet.into()).as_ssz_bytes();
+        let requests = registrars
+            .into_iter()
+            .map(|enr| {
+                self.discv5
+                    .talk_req(enr, TOPIC_PROTOCOL.to_vec(), request.clone())
+            })
+            .collect::<Vec<_>>();
+
+        let query_future = future::join_all(requests).map(move |responses| {
+            let enrs: HashSet<Enr> = responses
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter_map(|response| TopicResponse::from_ssz_bytes(&response).ok())
+                .flat_map(|response| response.decode_enrs())
+                .filter(|enr| {
+                    eth2_fork_predicate(enr, enr_fork_id.fork_digest) && subnet_predicate(enr)
+                })
+                .collect();
+            QueryResult {
+                query_type: QueryType::Subnet(vec![subnet_query]),
+                result: Ok(enrs.into_iter().collect()),
+            }
+        });
+        self.active_queries.push(Box::pin(query_future));
+    }
+
+    /// Advertises each of the subnets in our ENR.
+    fn register_local_topics(&mut self) {
+        for subnet in advertised_subnets::<E>(&self.local_enr()) {
+            self.start_topic_registration(subnet);
+        }
+    }
+
+    /// Starts a lookup of the nodes closest to the topic id of `subnet`, to register our
+    /// advertisement for the subnet with.
+    fn start_topic_registration(&mut self, subnet: Subnet) {
+        if !self.started {
+            return;
+        }
+        let Ok(enr_fork_id) = self.local_enr().eth2() else {
+            return;
+        };
+        let fork_digest = enr_fork_id.fork_digest;
+        let lookup = self
+            .discv5
+            .find_node_predicate(
+                topic_node_id(fork_digest, subnet),
+                Box::new(move |enr: &Enr| eth2_fork_predicate(enr, fork_digest)),
+                TOPIC_REGISTRARS,
+            )
+            .map(move |result| (subnet, result));
+        self.topic_registrations.push(Box::pin(lookup));
+    }
+
+    /// Sends our advertisement for `subnet` to the registrars found for its topic.
+    fn send_topic_registrations(&mut self, subnet: Subnet, registrars: Vec<Enr>) {
+        // Our ENR may have changed while the lookup was running.
+        let local_enr = self.local_enr();
+        if !advertised_subnets::<E>(&local_enr).contains(&subnet) {
+            return;
+        }
+        trace!(self.log, "Registering subnet advertisement";
+            "subnet" => ?subnet,
+            "registrars" => registrars.len(),
+        );
+        let request = TopicRequest::Register(TopicRegistration {
+            topic: subnet.into(),
+            enr: local_enr.to_base64().into_bytes(),
+        })
+        .as_ssz_bytes();
+        for registrar in registrars {
+            let registration = self
+                .discv5
+                .talk_req(registrar, TOPIC_PROTOCOL.to_vec(), request.clone())
+                .map(|_| ());
+            self.topic_requests.push(Box::pin(registration));
+        }
+    }
+
     /// Search for a specified number of new peers using the underlying discovery mechanism.
     ///
     /// This can optionally search for peers for a given predicate. Regardless of the predicate
@@ -792,18 +1064,10 @@ impl<E: EthSpec> Discovery<E> {
                 return;
             }
         };
-        // predicate for finding nodes with a matching fork and valid tcp port
-        let eth2_fork_predicate = move |enr: &Enr| {
-            // `next_fork_epoch` and `next_fork_version` can be different so that
-            // we can connect to peers who aren't compatible with an upcoming fork.
-            // `fork_digest` **must** be same.
-            enr.eth2().map(|e| e.fork_digest) == Ok(enr_fork_id.fork_digest)
-                && (enr.tcp4().is_some() || enr.tcp6().is_some())
-        };
-
         // General predicate
-        let predicate: Box<dyn Fn(&Enr) -> bool + Send> =
-            Box::new(move |enr: &Enr| eth2_fork_predicate(enr) && additional_predicate(enr));
+        let predicate: Box<dyn Fn(&Enr) -> bool + Send> = Box::new(move |enr: &Enr| {
+            eth2_fork_predicate(enr, enr_fork_id.fork_digest) && additional_predicate(enr)
+        });
 
         // Build the future
         let query_future = self
@@ -837,7 +1101,7 @@ impl<E: EthSpec> Discovery<E> {
                             .into_iter()
                             .map(|enr| {
                                 // cache the found ENR's
-                                self.cached_enrs.put(enr.peer_id(), enr.clone());
+                                self.cache_enr(enr.clone());
                                 (enr, None)
                             })
                             .collect();
@@ -848,6 +1112,23 @@ impl<E: EthSpec> Discovery<E> {
                     }
                 }
             }
+            QueryType::TopicLookup(subnet_query) => match query.result {
+                Ok(registrars) if !registrars.is_empty() => {
+                    self.query_topic_registrars(subnet_query, registrars);
+                }
+                result => {
+                    debug!(self.log, "Subnet topic lookup found no registrars";
+                        "subnet_query" => ?subnet_query,
+                        "error" => ?result.err(),
+                    );
+                    // Fall back to a random walk.
+                    self.add_subnet_query(
+                        subnet_query.subnet,
+                        subnet_query.min_ttl,
+                        subnet_query.retries + 1,
+                    );
+                }
+            },
             QueryType::Subnet(queries) => {
                 let subnets_searched_for: Vec<Subnet> =
                     queries.iter().map(|query| query.subnet).collect();
@@ -865,7 +1146,7 @@ impl<E: EthSpec> Discovery<E> {
 
                         // cache the found ENR's
                         for enr in r.iter().cloned() {
-                            self.cached_enrs.put(enr.peer_id(), enr);
+                            self.cache_enr(enr);
                         }
 
                         // Map each subnet query's min_ttl to the set of ENR's returned for that subnet.
@@ -1004,6 +1285,29 @@ impl<E: EthSpec> NetworkBehaviour for Discovery<E> {
         // Process the query queue
         self.process_queue();
 
+        // Renew our subnet advertisements and expire those we hold for others.
+        if self.registration_interval.poll_tick(cx).is_ready() {
+            self.topic_ads.prune(Instant::now());
+            self.prune_cached_enrs();
+            self.register_local_topics();
+        }
+        while let Poll::Ready(Some((subnet, result))) = self.topic_registrations.poll_next_unpin(cx)
+        {
+            match result {
+                Ok(registrars) => self.send_topic_registrations(subnet, registrars),
+                Err(e) => {
+                    debug!(self.log, "Subnet topic registration lookup failed"; "subnet" => ?subnet, "error" => %e)
+                }
+            }
+        }
+        while let Poll::Ready(Some(())) = self.topic_requests.poll_next_unpin(cx) {}
+
+        // Return any subnet peers that were found without a discovery query
+        if !self.local_subnet_results.is_empty() {
+            let peers = std::mem::take(&mut self.local_subnet_results);
+            return Poll::Ready(ToSwarm::GenerateEvent(DiscoveredPeers { peers }));
+        }
+
         // Drive the queries and return any results from completed queries
         if let Some(peers) = self.poll_queries(cx) {
             // return the result to the peer manager
@@ -1070,6 +1374,17 @@ impl<E: EthSpec> NetworkBehaviour for Discovery<E> {
                             // NOTE: We assume libp2p itself can keep track of IP changes and we do
                             // not inform it about IP changes found via discovery.
                         }
+                        discv5::Event::TalkRequest(request)
+                            if request.protocol() == TOPIC_PROTOCOL =>
+                        {
+                            handle_topic_request::<E>(
+                                &mut self.topic_ads,
+                                request,
+                                &self.discv5.local_enr(),
+                                &self.spec,
+                                &self.log,
+                            );
+                        }
                         discv5::Event::EnrAdded { .. }
                         | discv5::Event::TalkRequest(_)
                         | discv5::Event::NodeInserted { .. }
@@ -1192,6 +1507,15 @@ impl<E: EthSpec> Discovery<E> {
     }
 }
 
+/// Returns true if the ENR is on the fork given by `fork_digest` and has a TCP port to dial.
+fn eth2_fork_predicate(enr: &Enr, fork_digest: [u8; 4]) -> bool {
+    // `next_fork_epoch` and `next_fork_version` can be different so that
+    // we can connect to peers who aren't compatible with an upcoming fork.
+    // `fork_digest` **must** be same.
+    enr.eth2().map(|e| e.fork_digest) == Ok(fork_digest)
+        && (enr.tcp4().is_some() || enr.tcp6().is_some())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1293,6 +1617,52 @@ mod tests {
         builder.build(&enr_key).unwrap()
     }
 
+    /// Makes an ENR on the given fork which can be dialed over TCP.
+    fn make_dialable_enr(subnet_ids: Vec<usize>, enr_fork_id: &EnrForkId) -> Enr {
+        let mut builder = Enr::builder();
+        let keypair = secp256k1::Keypair::generate();
+        let enr_key: CombinedKey = CombinedKey::from_secp256k1(&keypair);
+
+        let mut bitfield = BitVector::<ssz_types::typenum::U64>::new();
+        for id in subnet_ids {
+            bitfield.set(id, true).unwrap();
+        }
+
+        builder.add_value(ATTESTATION_BITFIELD_ENR_KEY, &bitfield.as_ssz_bytes());
+        builder.add_value(ETH2_ENR_KEY, &enr_fork_id.as_ssz_bytes());
+        builder.ip4(std::net::Ipv4Addr::LOCALHOST);
+        builder.tcp4(9000);
+        builder.build(&enr_key).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_subnet_peers_found_locally() {
+        let mut discovery = build_discovery().await;
+        let enr_fork_id = discovery.local_enr().eth2().unwrap();
+
+        let on_subnet = make_dialable_enr(vec![1], &enr_fork_id);
+        let other_subnet = make_dialable_enr(vec![2], &enr_fork_id);
+        // Advertises the subnet, but has no fork or TCP port.
+        let undialable = make_enr(vec![1]);
+        for enr in [&on_subnet, &other_subnet, &undialable] {
+            discovery.cache_enr(enr.clone());
+        }
+
+        let min_ttl = Some(Instant::now() + Duration::from_secs(10));
+        let peers_found = discovery.find_local_subnet_peers(&SubnetQuery {
+            subnet: Subnet::Attestation(SubnetId::new(1)),
+            min_ttl,
+            retries: 0,
+        });
+
+        assert_eq!(peers_found, 1);
+        assert_eq!(discovery.local_subnet_results.len(), 1);
+        assert_eq!(
+            discovery.local_subnet_results.get(&on_subnet),
+            Some(&min_ttl)
+        );
+    }
+
     #[tokio::test]
     async fn test_completed_subnet_queries() {
         let mut discovery = build_discovery().await;
@@ -1333,4 +1703,66 @@ mod tests {
         // when a peer belongs to multiple subnet ids, we use the highest ttl.
         assert_eq!(results.get(&enr1).unwrap(), &instant1);
     }
+
+    #[tokio::test]
+    async fn test_first_subnet_query_uses_topic_ads() {
+        let mut discovery = build_discovery().await;
+        let subnet_query = SubnetQuery {
+            subnet: Subnet::Attestation(SubnetId::new(1)),
+            min_ttl: Some(Instant::now() + Duration::from_secs(10)),
+            retries: 0,
+        };
+
+        // The first attempt queries the topic's registrars rather than walking the DHT.
+        discovery.start_subnet_query(vec![subnet_query.clone()]);
+        assert_eq!(discovery.active_queries.len(), 1);
+
+        // Without registrars, the query falls back to a random walk.
+        let results = discovery.process_completed_queries(QueryResult {
+            query_type: QueryType::TopicLookup(subnet_query.clone()),
+            result: Ok(vec![]),
+        });
+        assert!(results.is_none());
+        assert_eq!(
+            discovery.queued_queries.back(),
+            Some(&SubnetQuery {
+                retries: 1,
+                ..subnet_query
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cached_enrs_expire_and_refresh() {
+        let mut discovery = build_discovery().await;
+        let keypair = secp256k1::Keypair::generate();
+        let enr_key: CombinedKey = CombinedKey::from_secp256k1(&keypair);
+        let old_enr = Enr::builder().build(&enr_key).unwrap();
+        let mut new_enr = old_enr.clone();
+        let mut bitfield = BitVector::<ssz_types::typenum::U64>::new();
+        bitfield.set(1, true).unwrap();
+        new_enr
+            .insert(
+                ATTESTATION_BITFIELD_ENR_KEY,
+                &bitfield.as_ssz_bytes(),
+                &enr_key,
+            )
+            .unwrap();
+        let peer_id = new_enr.peer_id();
+
+        // An older version of a cached ENR does not replace the newer one.
+        discovery.cache_enr(new_enr.clone());
+        discovery.cache_enr(old_enr);
+        assert_eq!(
+            discovery.cached_enrs().collect::<Vec<_>>(),
+            vec![(&peer_id, &new_enr)]
+        );
+
+        // Expired ENRs are not returned and are pruned.
+        discovery.cached_enrs.peek_mut(&peer_id).unwrap().1 =
+            Instant::now().checked_sub(CACHED_ENR_TTL).unwrap();
+        assert_eq!(discovery.cached_enrs().count(), 0);
+        discovery.prune_cached_enrs();
+        assert_eq!(discovery.cached_enrs.len(), 0);
+    }
 }
@@ -0,0 +1,423 @@
+//! Subnet topic advertisement over discv5.
+//!
+//! discv5 0.4 does not implement the topic advertisement extension, so a minimal version of it is
+//! run over TALKREQ. A node registers an advertisement for each subnet it is subscribed to with the
+//! nodes closest to the subnet's topic id. A node searching for peers on a subnet queries the
+//! same nodes for advertisements before falling back to a random walk of the DHT.
+//!
+//! Registrars only accept an advertisement if the ENR is on our fork, belongs to the node that
+//! sent it and advertises the subnet in its bitfields. Advertisements expire after
+//! [`AD_LIFETIME`] and are renewed by the advertiser every [`REGISTRATION_INTERVAL`].
+use super::*;
+use sha2::{Digest, Sha256};
+use ssz_derive::{Decode, Encode};
+use std::str::FromStr;
+use types::{DataColumnSubnetId, SubnetId, SyncSubnetId};
+
+/// The TALKREQ protocol identifier for topic advertisement messages.
+pub const TOPIC_PROTOCOL: &[u8] = b"eth2-topics";
+/// The duration a registrar keeps an advertisement for.
+pub const AD_LIFETIME: Duration = Duration::from_secs(10 * 60);
+/// How often the subnets in our ENR are re-advertised.
+pub const REGISTRATION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// The number of nodes closest to a topic id which advertisements are registered with and
+/// queried from.
+pub const TOPIC_REGISTRARS: usize = 8;
+/// The maximum number of advertisements a registrar keeps for a single topic.
+const MAX_ADS_PER_TOPIC: usize = 16;
+/// The maximum number of topics a registrar keeps advertisements for.
+const MAX_TOPICS: usize = 256;
+/// The maximum size of the ENRs in a query response, which must fit in a single discv5 packet.
+const MAX_RESPONSE_ENR_BYTES: usize = 1_000;
+
+/// A subnet, as it is encoded on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct TopicId {
+    /// `0` for attestation subnets, `1` for sync committee subnets and `2` for data column subnets.
+    pub kind: u8,
+    pub subnet_id: u64,
+}
+
+impl From<Subnet> for TopicId {
+    fn from(subnet: Subnet) -> Self {
+        match subnet {
+            Subnet::Attestation(id) => TopicId {
+                kind: 0,
+                subnet_id: *id,
+            },
+            Subnet::SyncCommittee(id) => TopicId {
+                kind: 1,
+                subnet_id: *id,
+            },
+            Subnet::DataColumn(id) => TopicId {
+                kind: 2,
+                subnet_id: *id,
+            },
+        }
+    }
+}
+
+impl TryFrom<TopicId> for Subnet {
+    type Error = String;
+
+    fn try_from(topic: TopicId) -> Result<Self, Self::Error> {
+        match topic.kind {
+            0 => Ok(Subnet::Attestation(SubnetId::new(topic.subnet_id))),
+            1 => Ok(Subnet::SyncCommittee(SyncSubnetId::new(topic.subnet_id))),
+            2 => Ok(Subnet::DataColumn(DataColumnSubnetId::new(topic.subnet_id))),
+            kind => Err(format!("Unknown topic kind: {}", kind)),
+        }
+    }
+}
+
+/// An advertisement registration. The ENR is base64 encoded.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct TopicRegistration {
+    pub topic: TopicId,
+    pub enr: Vec<u8>,
+}
+
+/// A request sent in the body of a TALKREQ.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+#[ssz(enum_behaviour = "union")]
+pub enum TopicRequest {
+    /// Request the advertisements held for a topic.
+    Query(TopicId),
+    /// Register an advertisement for a topic.
+    Register(TopicRegistration),
+}
+
+/// The response to a `TopicRequest`. Registrations are answered with an empty response.
+#[derive(Debug, Clone, PartialEq, Default, Encode, Decode)]
+pub struct TopicResponse {
+    /// Base64 encoded ENRs advertising the queried topic.
+    pub enrs: Vec<Vec<u8>>,
+}
+
+impl TopicResponse {
+    /// Decodes the ENRs in the response, dropping any that are invalid.
+    pub fn decode_enrs(&self) -> Vec<Enr> {
+        self.enrs
+            .iter()
+            .filter_map(|bytes| std::str::from_utf8(bytes).ok())
+            .filter_map(|enr| Enr::from_str(enr).ok())
+            .collect()
+    }
+}
+
+/// Returns the id of the topic for `subnet` on the fork given by `fork_digest`. Advertisements
+/// for the topic are kept by the nodes closest to this id.
+pub fn topic_node_id(fork_digest: [u8; 4], subnet: Subnet) -> NodeId {
+    let topic = TopicId::from(subnet);
+    let mut hasher = Sha256::new();
+    hasher.update(TOPIC_PROTOCOL);
+    hasher.update(fork_digest);
+    hasher.update([topic.kind]);
+    hasher.update(topic.subnet_id.to_le_bytes());
+    NodeId::new(&hasher.finalize().into())
+}
+
+/// Returns the subnets advertised in the bitfields of `enr`, which are the topics it registers.
+pub fn advertised_subnets<E: EthSpec>(enr: &Enr) -> Vec<Subnet> {
+    let mut subnets = vec![];
+    if let Ok(bitfield) = enr.attestation_bitfield::<E>() {
+        subnets.extend(
+            bitfield
+                .iter()
+                .enumerate()
+                .filter(|(_, set)| *set)
+                .map(|(id, _)| Subnet::Attestation(SubnetId::new(id as u64))),
+        );
+    }
+    if let Ok(bitfield) = enr.sync_committee_bitfield::<E>() {
+        subnets.extend(
+            bitfield
+                .iter()
+                .enumerate()
+                .filter(|(_, set)| *set)
+                .map(|(id, _)| Subnet::SyncCommittee(SyncSubnetId::new(id as u64))),
+        );
+    }
+    subnets
+}
+
+/// The advertisements held by this node as a registrar.
+#[derive(Default)]
+pub struct TopicAds {
+    ads: HashMap<Subnet, VecDeque<(Enr, Instant)>>,
+}
+
+impl TopicAds {
+    /// Stores an advertisement for `subnet`, replacing any previous one from the same node.
+    ///
+    /// When the topic is full the oldest advertisement is dropped. Returns `false` if the
+    /// advertisement was rejected because we already hold the maximum number of topics.
+    pub fn register(&mut self, subnet: Subnet, enr: Enr, now: Instant) -> bool {
+        if !self.ads.contains_key(&subnet) && self.ads.len() >= MAX_TOPICS {
+            return false;
+        }
+        let ads = self.ads.entry(subnet).or_default();
+        ads.retain(|(ad, _)| ad.node_id() != enr.node_id());
+        if ads.len() >= MAX_ADS_PER_TOPIC {
+            ads.pop_front();
+        }
+        ads.push_back((enr, now + AD_LIFETIME));
+        true
+    }
+
+    /// Returns the unexpired advertisements for `subnet`, newest first.
+    pub fn get(&self, subnet: Subnet, now: Instant) -> impl Iterator<Item = &Enr> {
+        self.ads
+            .get(&subnet)
+            .into_iter()
+            .flat_map(|ads| ads.iter().rev())
+            .filter(move |(_, expiry)| *expiry > now)
+            .map(|(enr, _)| enr)
+    }
+
+    /// Builds the response to a query for `subnet`, keeping it within a single packet.
+    pub fn response(&self, subnet: Subnet, now: Instant) -> TopicResponse {
+        let mut response = TopicResponse::default();
+        let mut size = 0;
+        for enr in self.get(subnet, now) {
+            let bytes = enr.to_base64().into_bytes();
+            size += bytes.len();
+            if size > MAX_RESPONSE_ENR_BYTES {
+                break;
+            }
+            response.enrs.push(bytes);
+        }
+        response
+    }
+
+    /// Removes expired advertisements.
+    pub fn prune(&mut self, now: Instant) {
+        self.ads.retain(|_, ads| {
+            ads.retain(|(_, expiry)| *expiry > now);
+            !ads.is_empty()
+        });
+    }
+
+    /// Removes all advertisements. Used when our fork changes.
+    pub fn clear(&mut self) {
+        self.ads.clear();
+    }
+
+    /// The number of advertisements held.
+    pub fn num_ads(&self) -> usize {
+        self.ads.values().map(VecDeque::len).sum()
+    }
+}
+
+/// Handles a TALKREQ for the topic advertisement protocol.
+pub fn handle_topic_request<E: EthSpec>(
+    topic_ads: &mut TopicAds,
+    request: discv5::TalkRequest,
+    local_enr: &Enr,
+    spec: &Arc<ChainSpec>,
+    log: &slog::Logger,
+) {
+    let response = match process_topic_request::<E>(
+        topic_ads,
+        request.node_id(),
+        request.body(),
+        local_enr,
+        spec,
+        log,
+        Instant::now(),
+    ) {
+        Ok(response) => response,
+        Err(e) => {
+            trace!(log, "Rejected topic request"; "node_id" => %request.node_id(), "error" => e);
+            // Dropping the request answers it with an empty response.
+            return;
+        }
+    };
+    if let Err(e) = request.respond(response.as_ssz_bytes()) {
+        debug!(log, "Failed to respond to topic request"; "error" => ?e);
+    }
+}
+
+/// Validates a topic request and returns the response to send.
+fn process_topic_request<E: EthSpec>(
+    topic_ads: &mut TopicAds,
+    node_id: &NodeId,
+    body: &[u8],
+    local_enr: &Enr,
+    spec: &Arc<ChainSpec>,
+    log: &slog::Logger,
+    now: Instant,
+) -> Result<TopicResponse, String> {
+    let request = TopicRequest::from_ssz_bytes(body)
+        .map_err(|e| format!("Invalid topic request: {:?}", e))?;
+    match request {
+        TopicRequest::Query(topic) => {
+            let subnet = Subnet::try_from(topic)?;
+            Ok(topic_ads.response(subnet, now))
+        }
+        TopicRequest::Register(registration) => {
+            let subnet = Subnet::try_from(registration.topic)?;
+            let enr = std::str::from_utf8(&registration.enr)
+                .map_err(|e| format!("Invalid ENR encoding: {}", e))
+                .and_then(Enr::from_str)?;
+            if enr.node_id() != *node_id {
+                return Err("ENR does not belong to the sender".into());
+            }
+            let fork_digest = local_enr.eth2().map_err(String::from)?.fork_digest;
+            if !eth2_fork_predicate(&enr, fork_digest) {
+                return Err("ENR is not on our fork or is not dialable".into());
+            }
+            if !subnet_predicate::<E>(vec![subnet], log, spec.clone())(&enr) {
+                return Err("ENR does not advertise the subnet".into());
+            }
+            if !topic_ads.register(subnet, enr, now) {
+                return Err("Too many topics".into());
+            }
+            Ok(TopicResponse::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::identity::secp256k1;
+    use slog::o;
+    use types::{BitVector, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn make_enr(subnet_ids: &[usize], enr_fork_id: &EnrForkId) -> (Enr, CombinedKey) {
+        let keypair = secp256k1::Keypair::generate();
+        let enr_key: CombinedKey = CombinedKey::from_secp256k1(&keypair);
+        let mut bitfield = BitVector::<ssz_types::typenum::U64>::new();
+        for id in subnet_ids {
+            bitfield.set(*id, true).unwrap();
+        }
+        let mut builder = Enr::builder();
+        builder.add_value(ATTESTATION_BITFIELD_ENR_KEY, &bitfield.as_ssz_bytes());
+        builder.add_value(ETH2_ENR_KEY, &enr_fork_id.as_ssz_bytes());
+        builder.ip4(std::net::Ipv4Addr::LOCALHOST);
+        builder.tcp4(9000);
+        (builder.build(&enr_key).unwrap(), enr_key)
+    }
+
+    fn register(enr: &Enr, subnet: Subnet) -> Vec<u8> {
+        TopicRequest::Register(TopicRegistration {
+            topic: subnet.into(),
+            enr: enr.to_base64().into_bytes(),
+        })
+        .as_ssz_bytes()
+    }
+
+    #[test]
+    fn request_round_trip() {
+        let (enr, _) = make_enr(&[1], &EnrForkId::default());
+        let subnet = Subnet::SyncCommittee(SyncSubnetId::new(3));
+        for request in [
+            TopicRequest::Query(subnet.into()),
+            TopicRequest::Register(TopicRegistration {
+                topic: subnet.into(),
+                enr: enr.to_base64().into_bytes(),
+            }),
+        ] {
+            assert_eq!(
+                TopicRequest::from_ssz_bytes(&request.as_ssz_bytes()),
+                Ok(request)
+            );
+        }
+        assert_eq!(Subnet::try_from(TopicId::from(subnet)), Ok(subnet));
+    }
+
+    #[test]
+    fn topic_ids_differ_per_fork_and_subnet() {
+        let subnet = Subnet::Attestation(SubnetId::new(1));
+        let id = topic_node_id([0; 4], subnet);
+        assert_eq!(id, topic_node_id([0; 4], subnet));
+        assert_ne!(id, topic_node_id([1; 4], subnet));
+        assert_ne!(
+            id,
+            topic_node_id([0; 4], Subnet::SyncCommittee(SyncSubnetId::new(1)))
+        );
+    }
+
+    #[test]
+    fn registrations_are_validated() {
+        let log = slog::Logger::root(slog::Discard, o!());
+        let spec = Arc::new(ChainSpec::minimal());
+        let enr_fork_id = EnrForkId::default();
+        let (local_enr, _) = make_enr(&[], &enr_fork_id);
+        let (enr, _) = make_enr(&[1], &enr_fork_id);
+        let (other_enr, _) = make_enr(&[1], &enr_fork_id);
+        let (wrong_fork, _) = make_enr(
+            &[1],
+            &EnrForkId {
+                fork_digest: [1; 4],
+                ..enr_fork_id.clone()
+            },
+        );
+        let subnet = Subnet::Attestation(SubnetId::new(1));
+        let now = Instant::now();
+
+        let mut ads = TopicAds::default();
+        let mut process = |node_id: NodeId, body: Vec<u8>| {
+            process_topic_request::<E>(&mut ads, &node_id, &body, &local_enr, &spec, &log, now)
+        };
+
+        // The ENR must belong to the sender, be on our fork and advertise the subnet.
+        assert!(process(other_enr.node_id(), register(&enr, subnet)).is_err());
+        assert!(process(wrong_fork.node_id(), register(&wrong_fork, subnet)).is_err());
+        let other_subnet = Subnet::Attestation(SubnetId::new(2));
+        assert!(process(enr.node_id(), register(&enr, other_subnet)).is_err());
+        assert!(process(enr.node_id(), vec![0xff]).is_err());
+
+        assert_eq!(
+            process(enr.node_id(), register(&enr, subnet)),
+            Ok(TopicResponse::default())
+        );
+        let response = process(
+            other_enr.node_id(),
+            TopicRequest::Query(subnet.into()).as_ssz_bytes(),
+        )
+        .unwrap();
+        assert_eq!(response.decode_enrs(), vec![enr]);
+    }
+
+    #[test]
+    fn ads_expire_and_are_capped() {
+        let enr_fork_id = EnrForkId::default();
+        let subnet = Subnet::Attestation(SubnetId::new(1));
+        let now = Instant::now();
+        let mut ads = TopicAds::default();
+
+        let enrs: Vec<Enr> = (0..MAX_ADS_PER_TOPIC + 1)
+            .map(|_| make_enr(&[1], &enr_fork_id).0)
+            .collect();
+        for enr in &enrs {
+            assert!(ads.register(subnet, enr.clone(), now));
+        }
+        // Re-registering replaces the previous advertisement.
+        assert!(ads.register(subnet, enrs[1].clone(), now));
+        assert_eq!(ads.num_ads(), MAX_ADS_PER_TOPIC);
+        // The oldest advertisement was dropped.
+        assert!(ads.get(subnet, now).all(|enr| *enr != enrs[0]));
+        assert_eq!(ads.get(subnet, now).next(), Some(&enrs[1]));
+
+        // Responses fit in a single packet.
+        let response = ads.response(subnet, now);
+        assert!(!response.enrs.is_empty());
+        assert!(response.enrs.iter().map(Vec::len).sum::<usize>() <= MAX_RESPONSE_ENR_BYTES);
+
+        let expired = now + AD_LIFETIME;
+        assert_eq!(ads.get(subnet, expired).count(), 0);
+        ads.prune(expired);
+        assert_eq!(ads.num_ads(), 0);
+
+        // Registrations for new topics are rejected once the topic limit is reached.
+        for id in 0..MAX_TOPICS as u64 {
+            let subnet = Subnet::DataColumn(DataColumnSubnetId::new(id));
+            assert!(ads.register(subnet, enrs[0].clone(), now));
+        }
+        assert!(!ads.register(subnet, enrs[0].clone(), now));
+    }
+}
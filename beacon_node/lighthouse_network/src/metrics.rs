@@ -131,6 +131,12 @@ lazy_static! {
             "Total number of peers found in attestation subnets and sync subnets",
             &["type"]
         );
+    pub static ref SUBNET_PEERS_FOUND_LOCALLY: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "discovery_query_peers_found_locally",
+            "Total number of subnet peers found in the local routing table or ENR cache",
+            &["type"]
+        );
     pub static ref TOTAL_SUBNET_QUERIES: Result<IntCounterVec> =
         try_create_int_counter_vec(
             "discovery_total_queries",
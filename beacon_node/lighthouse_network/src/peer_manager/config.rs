@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 /// The time in seconds between re-status's peers.
 pub const DEFAULT_STATUS_INTERVAL: u64 = 300;
 
@@ -32,6 +34,10 @@ pub struct Config {
     pub ping_interval_inbound: u64,
     /// Interval between PING events for peers dialed by us.
     pub ping_interval_outbound: u64,
+
+    /* Connection filtering */
+    /// A file of allow and deny lists applied to all connections. See `peer_filter`.
+    pub peer_filter_file: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -44,6 +50,7 @@ impl Default for Config {
             status_interval: DEFAULT_STATUS_INTERVAL,
             ping_interval_inbound: DEFAULT_PING_INTERVAL_INBOUND,
             ping_interval_outbound: DEFAULT_PING_INTERVAL_OUTBOUND,
+            peer_filter_file: None,
         }
     }
 }
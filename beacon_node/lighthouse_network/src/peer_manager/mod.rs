@@ -10,9 +10,10 @@ use delay_map::HashSetDelay;
 use discv5::Enr;
 use libp2p::identify::Info as IdentifyInfo;
 use lru_cache::LRUTimeCache;
+use peer_filter::PeerFilterFile;
 use peerdb::{BanOperation, BanResult, ScoreUpdateResult};
 use rand::seq::SliceRandom;
-use slog::{debug, error, trace, warn};
+use slog::{debug, error, info, trace, warn};
 use smallvec::SmallVec;
 use std::{
     sync::Arc,
@@ -29,7 +30,7 @@ pub mod peerdb;
 use crate::peer_manager::peerdb::client::ClientKind;
 use libp2p::multiaddr;
 pub use peerdb::peer_info::{
-    ConnectionDirection, PeerConnectionStatus, PeerConnectionStatus::*, PeerInfo,
+    ConnectionDirection, Goodbye, PeerConnectionStatus, PeerConnectionStatus::*, PeerInfo,
 };
 use peerdb::score::{PeerAction, ReportSource};
 pub use peerdb::sync_status::{SyncInfo, SyncStatus};
@@ -39,6 +40,7 @@ use strum::IntoEnumIterator;
 
 pub mod config;
 mod network_behaviour;
+pub mod peer_filter;
 
 /// The heartbeat performs regular updates such as updating reputations and performing discovery
 /// requests. This defines the interval in seconds.
@@ -80,6 +82,11 @@ pub struct PeerManager<E: EthSpec> {
     status_peers: HashSetDelay<PeerId>,
     /// The target number of peers we would like to connect to.
     target_peers: usize,
+    /// The target number of peers at startup, from which the libp2p connection limits are
+    /// derived. The target can't be raised beyond this at runtime.
+    max_target_peers: usize,
+    /// The maximum number of excess peers to disconnect in a single heartbeat, if limited.
+    max_pruned_peers_per_heartbeat: Option<usize>,
     /// Peers queued to be dialed.
     peers_to_dial: Vec<Enr>,
     /// The number of temporarily banned peers. This is used to prevent instantaneous
@@ -109,6 +116,8 @@ pub struct PeerManager<E: EthSpec> {
     metrics_enabled: bool,
     /// Keeps track of whether the QUIC protocol is enabled or not.
     quic_enabled: bool,
+    /// Allow and deny lists applied to all connections, if configured.
+    peer_filter: Option<PeerFilterFile>,
     /// The logger associated with the `PeerManager`.
     log: slog::Logger,
 }
@@ -155,8 +164,11 @@ impl<E: EthSpec> PeerManager<E> {
             ping_interval_inbound,
             ping_interval_outbound,
             quic_enabled,
+            peer_filter_file,
         } = cfg;
 
+        let peer_filter = peer_filter_file.map(PeerFilterFile::load).transpose()?;
+
         // Set up the peer manager heartbeat interval
         let heartbeat = tokio::time::interval(tokio::time::Duration::from_secs(HEARTBEAT_INTERVAL));
 
@@ -168,12 +180,15 @@ impl<E: EthSpec> PeerManager<E> {
             outbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_outbound)),
             status_peers: HashSetDelay::new(Duration::from_secs(status_interval)),
             target_peers: target_peer_count,
+            max_target_peers: target_peer_count,
+            max_pruned_peers_per_heartbeat: None,
             temporary_banned_peers: LRUTimeCache::new(PEER_RECONNECTION_TIMEOUT),
             sync_committee_subnets: Default::default(),
             heartbeat,
             discovery_enabled,
             metrics_enabled,
             quic_enabled,
+            peer_filter,
             log: log.clone(),
         })
     }
@@ -363,6 +378,14 @@ impl<E: EthSpec> PeerManager<E> {
         self.status_peers.insert(*peer_id);
     }
 
+    /// Records a goodbye sent to or received from a peer, so that the reason for the
+    /// disconnection can be reported once it completes.
+    pub fn register_goodbye(&mut self, peer_id: &PeerId, reason: GoodbyeReason, sent: bool) {
+        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            info.set_goodbye(Goodbye { reason, sent });
+        }
+    }
+
     /// Insert the sync subnet into list of long lived sync committee subnets that we need to
     /// maintain adequate number of peers for.
     pub fn add_sync_subnet(&mut self, subnet_id: SyncSubnetId, min_ttl: Instant) {
@@ -378,6 +401,43 @@ impl<E: EthSpec> PeerManager<E> {
         }
     }
 
+    /// The target number of peers we would like to connect to.
+    pub fn target_peers(&self) -> usize {
+        self.target_peers
+    }
+
+    /// Updates the target number of peers at runtime.
+    ///
+    /// Excess peers are pruned and new peers discovered over the following heartbeats. If
+    /// `max_pruned_peers_per_heartbeat` is set, at most that many peers are disconnected per
+    /// heartbeat, so that a large reduction is reached gradually. Trusted peers are never pruned.
+    ///
+    /// The target is capped at its value at startup, as the libp2p connection limits are fixed.
+    pub fn set_target_peers(
+        &mut self,
+        target_peers: usize,
+        max_pruned_peers_per_heartbeat: Option<usize>,
+    ) {
+        let new_target_peers = std::cmp::min(target_peers, self.max_target_peers);
+        if new_target_peers < target_peers {
+            warn!(
+                self.log,
+                "Target peers exceeds the startup limit";
+                "requested" => target_peers,
+                "limit" => self.max_target_peers,
+            );
+        }
+        info!(
+            self.log,
+            "Updated target peers";
+            "previous" => self.target_peers,
+            "target_peers" => new_target_peers,
+            "max_pruned_peers_per_heartbeat" => max_pruned_peers_per_heartbeat,
+        );
+        self.target_peers = new_target_peers;
+        self.max_pruned_peers_per_heartbeat = max_pruned_peers_per_heartbeat;
+    }
+
     /// The maximum number of peers we allow to connect to us. This is `target_peers` * (1 +
     /// PEER_EXCESS_FACTOR)
     fn max_peers(&self) -> usize {
@@ -414,6 +474,15 @@ impl<E: EthSpec> PeerManager<E> {
     /// A peer is being dialed.
     /// Returns true, if this peer will be dialed.
     pub fn dial_peer(&mut self, peer: Enr) -> bool {
+        let ip = peer
+            .ip4()
+            .map(IpAddr::V4)
+            .or_else(|| peer.ip6().map(IpAddr::V6));
+        if !self.is_permitted(ip.as_ref(), &peer.peer_id()) {
+            debug!(self.log, "Not dialing peer excluded by the peer filter"; "peer_id" => %peer.peer_id());
+            return false;
+        }
+
         if self
             .network_globals
             .peers
@@ -427,6 +496,21 @@ impl<E: EthSpec> PeerManager<E> {
         }
     }
 
+    /// Returns `true` if the peer filter denies all connections from `ip`.
+    pub fn is_ip_denied(&self, ip: &IpAddr) -> bool {
+        self.peer_filter
+            .as_ref()
+            .map_or(false, |peer_filter| peer_filter.filter().is_ip_denied(ip))
+    }
+
+    /// Returns `true` if the peer filter permits a connection to `peer_id` at `ip`, or if there is
+    /// no peer filter.
+    pub fn is_permitted(&self, ip: Option<&IpAddr>, peer_id: &PeerId) -> bool {
+        self.peer_filter.as_ref().map_or(true, |peer_filter| {
+            peer_filter.filter().is_permitted(ip, peer_id)
+        })
+    }
+
     /// Reports if a peer is banned or not.
     ///
     /// This is used to determine if we should accept incoming connections.
@@ -524,6 +608,11 @@ impl<E: EthSpec> PeerManager<E> {
                         return;
                     }
 
+                    // Peers without a finalized deposit snapshot are expected, we ask another.
+                    if matches!(protocol, Protocol::DepositSnapshot) {
+                        return;
+                    }
+
                     // NOTE: This error only makes sense for the `BlocksByRange` and `BlocksByRoot`
                     // protocols.
                     //
@@ -558,6 +647,7 @@ impl<E: EthSpec> PeerManager<E> {
                     Protocol::LightClientBootstrap => return,
                     Protocol::LightClientOptimisticUpdate => return,
                     Protocol::LightClientFinalityUpdate => return,
+                    Protocol::DepositSnapshot => PeerAction::LowToleranceError,
                     Protocol::BlobsByRoot => PeerAction::MidToleranceError,
                     Protocol::Goodbye => PeerAction::LowToleranceError,
                     Protocol::MetaData => PeerAction::LowToleranceError,
@@ -581,6 +671,7 @@ impl<E: EthSpec> PeerManager<E> {
                     Protocol::LightClientBootstrap => return,
                     Protocol::LightClientOptimisticUpdate => return,
                     Protocol::LightClientFinalityUpdate => return,
+                    Protocol::DepositSnapshot => return,
                     Protocol::MetaData => PeerAction::Fatal,
                     Protocol::Status => PeerAction::Fatal,
                 }
@@ -600,6 +691,7 @@ impl<E: EthSpec> PeerManager<E> {
                     Protocol::LightClientBootstrap => return,
                     Protocol::LightClientOptimisticUpdate => return,
                     Protocol::LightClientFinalityUpdate => return,
+                    Protocol::DepositSnapshot => return,
                     Protocol::Goodbye => return,
                     Protocol::MetaData => return,
                     Protocol::Status => return,
@@ -936,6 +1028,15 @@ impl<E: EthSpec> PeerManager<E> {
             return;
         }
 
+        // The number of peers to prune in this heartbeat, which may be limited so that a large
+        // reduction in the target is reached gradually.
+        let excess_peer_count = connected_peer_count.saturating_sub(self.target_peers);
+        let prune_count = self
+            .max_pruned_peers_per_heartbeat
+            .map_or(excess_peer_count, |max_pruned| {
+                std::cmp::min(excess_peer_count, max_pruned)
+            });
+
         // Keep a list of peers we are pruning.
         let mut peers_to_prune = std::collections::HashSet::new();
         let connected_outbound_peer_count = self.network_globals.connected_outbound_only_peers();
@@ -956,9 +1057,7 @@ impl<E: EthSpec> PeerManager<E> {
                         !info.has_future_duty() && !info.is_trusted() && filter(*info)
                     })
                 {
-                    if peers_to_prune.len()
-                        >= connected_peer_count.saturating_sub(self.target_peers)
-                    {
+                    if peers_to_prune.len() >= prune_count {
                         // We have found all the peers we need to drop, end.
                         break;
                     }
@@ -985,13 +1084,13 @@ impl<E: EthSpec> PeerManager<E> {
 
         // 2. Attempt to remove peers that are not subscribed to a subnet, if we still need to
         //    prune more.
-        if peers_to_prune.len() < connected_peer_count.saturating_sub(self.target_peers) {
+        if peers_to_prune.len() < prune_count {
             prune_peers!(|info: &PeerInfo<E>| { !info.has_long_lived_subnet() });
         }
 
         // 3. and 4. Remove peers that are too grouped on any given subnet. If all subnets are
         //    uniformly distributed, remove random peers.
-        if peers_to_prune.len() < connected_peer_count.saturating_sub(self.target_peers) {
+        if peers_to_prune.len() < prune_count {
             // Of our connected peers, build a map from subnet_id -> Vec<(PeerId, PeerInfo)>
             let mut subnet_to_peer: HashMap<Subnet, Vec<(PeerId, PeerInfo<E>)>> = HashMap::new();
             // These variables are used to track if a peer is in a long-lived sync-committee as we
@@ -1036,7 +1135,7 @@ impl<E: EthSpec> PeerManager<E> {
             }
 
             // Add to the peers to prune mapping
-            while peers_to_prune.len() < connected_peer_count.saturating_sub(self.target_peers) {
+            while peers_to_prune.len() < prune_count {
                 if let Some((_, peers_on_subnet)) = subnet_to_peer
                     .iter_mut()
                     .max_by_key(|(_, peers)| peers.len())
@@ -1182,10 +1281,46 @@ impl<E: EthSpec> PeerManager<E> {
         // Unban any peers that have served their temporary ban timeout
         self.unban_temporary_banned_peers();
 
+        // Reload the peer filter if it has been modified.
+        self.reload_peer_filter();
+
         // Maintains memory by shrinking mappings
         self.shrink_mappings();
     }
 
+    /// Reload the peer filter file if it has been modified, disconnecting any connected peers
+    /// which it no longer permits.
+    fn reload_peer_filter(&mut self) {
+        let Some(peer_filter) = self.peer_filter.as_mut() else {
+            return;
+        };
+        if !peer_filter.reload(&self.log) {
+            return;
+        }
+
+        let peer_filter = peer_filter.filter();
+        let excluded_peers = self
+            .network_globals
+            .peers
+            .read()
+            .connected_peers()
+            .filter(|(peer_id, info)| {
+                let mut ips = info.seen_ip_addresses().peekable();
+                if ips.peek().is_none() {
+                    !peer_filter.is_permitted(None, peer_id)
+                } else {
+                    ips.any(|ip| !peer_filter.is_permitted(Some(&ip), peer_id))
+                }
+            })
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+
+        for peer_id in excluded_peers {
+            debug!(self.log, "Disconnecting peer excluded by the peer filter"; "peer_id" => %peer_id);
+            self.disconnect_peer(peer_id, GoodbyeReason::Banned);
+        }
+    }
+
     // Reduce memory footprint by routinely shrinking associating mappings.
     fn shrink_mappings(&mut self) {
         self.inbound_ping_peers.shrink_to(5);
@@ -1478,6 +1613,46 @@ mod tests {
         assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 3);
     }
 
+    #[tokio::test]
+    async fn test_peer_manager_prunes_gradually_after_target_update() {
+        let trusted_peer = PeerId::random();
+        let mut peer_manager = build_peer_manager_with_trusted_peers(vec![trusted_peer], 10).await;
+
+        // Connect to 10 ingoing peers and a trusted peer.
+        for _i in 0..10 {
+            let peer = PeerId::random();
+            peer_manager.inject_connect_ingoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        }
+        peer_manager.inject_connect_ingoing(&trusted_peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        assert_eq!(
+            peer_manager.network_globals.connected_or_dialing_peers(),
+            11
+        );
+
+        // The target can't be raised beyond its value at startup.
+        peer_manager.set_target_peers(20, None);
+        assert_eq!(peer_manager.target_peers(), 10);
+
+        // Lower the target, pruning at most 3 peers per heartbeat.
+        peer_manager.set_target_peers(3, Some(3));
+        assert_eq!(peer_manager.target_peers(), 3);
+
+        for expected_peers in [8, 5, 3, 3] {
+            peer_manager.heartbeat();
+            assert_eq!(
+                peer_manager.network_globals.connected_or_dialing_peers(),
+                expected_peers
+            );
+        }
+
+        // The trusted peer remains connected.
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .is_connected(&trusted_peer));
+    }
+
     #[tokio::test]
     async fn test_peer_manager_not_enough_outbound_peers_no_panic_during_heartbeat() {
         let mut peer_manager = build_peer_manager(20).await;
@@ -1571,6 +1746,40 @@ mod tests {
         assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 3);
     }
 
+    #[tokio::test]
+    async fn test_peer_manager_does_not_penalise_missing_deposit_snapshot() {
+        let mut peer_manager = build_peer_manager(3).await;
+        let peer = PeerId::random();
+        peer_manager.inject_connect_outgoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+
+        // A peer without a deposit snapshot is not penalised.
+        let err = RPCError::ErrorResponse(
+            RPCResponseErrorCode::ResourceUnavailable,
+            "No deposit snapshot".to_string(),
+        );
+        peer_manager.handle_rpc_error(
+            &peer,
+            Protocol::DepositSnapshot,
+            &err,
+            ConnectionDirection::Outgoing,
+        );
+        assert_eq!(peer_manager.network_globals.peers.read().score(&peer), 0.0);
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .is_connected(&peer));
+
+        // Whereas a peer that can't serve the blocks we request is.
+        peer_manager.handle_rpc_error(
+            &peer,
+            Protocol::BlocksByRoot,
+            &err,
+            ConnectionDirection::Outgoing,
+        );
+        assert!(peer_manager.network_globals.peers.read().score(&peer) < 0.0);
+    }
+
     #[tokio::test]
     async fn test_peer_manager_removes_enough_peers_when_one_is_unhealthy() {
         let mut peer_manager = build_peer_manager(3).await;
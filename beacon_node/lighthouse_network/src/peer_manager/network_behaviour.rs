@@ -172,14 +172,10 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
         remote_addr: &libp2p::Multiaddr,
     ) -> Result<(), ConnectionDenied> {
         // get the IP address to verify it's not banned.
-        let ip = match remote_addr.iter().next() {
-            Some(libp2p::multiaddr::Protocol::Ip6(ip)) => IpAddr::V6(ip),
-            Some(libp2p::multiaddr::Protocol::Ip4(ip)) => IpAddr::V4(ip),
-            _ => {
-                return Err(ConnectionDenied::new(format!(
-                    "Connection to peer rejected: invalid multiaddr: {remote_addr}"
-                )))
-            }
+        let Some(ip) = multiaddr_ip(remote_addr) else {
+            return Err(ConnectionDenied::new(format!(
+                "Connection to peer rejected: invalid multiaddr: {remote_addr}"
+            )));
         };
 
         if self.network_globals.peers.read().is_ip_banned(&ip) {
@@ -188,6 +184,12 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
             )));
         }
 
+        if self.is_ip_denied(&ip) {
+            return Err(ConnectionDenied::new(format!(
+                "Connection to peer rejected: {ip} is denied by the peer filter"
+            )));
+        }
+
         Ok(())
     }
 
@@ -205,6 +207,11 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
                 "Connection to peer rejected: peer has a bad score",
             ));
         }
+        if !self.is_permitted(multiaddr_ip(remote_addr).as_ref(), &peer_id) {
+            return Err(ConnectionDenied::new(
+                "Connection to peer rejected: peer is excluded by the peer filter",
+            ));
+        }
         Ok(ConnectionHandler)
     }
 
@@ -216,6 +223,12 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
         _role_override: libp2p::core::Endpoint,
     ) -> Result<libp2p::swarm::THandler<Self>, libp2p::swarm::ConnectionDenied> {
         trace!(self.log, "Outbound connection"; "peer_id" => %peer_id, "multiaddr" => %addr);
+        if !self.is_permitted(multiaddr_ip(addr).as_ref(), &peer_id) {
+            debug!(self.log, "Connected a peer excluded by the peer filter. Rejecting connection"; "peer_id" => %peer_id);
+            return Err(ConnectionDenied::new(
+                "Connection to peer rejected: peer is excluded by the peer filter",
+            ));
+        }
         match self.ban_status(&peer_id) {
             Some(cause) => {
                 error!(self.log, "Connected a banned peer. Rejecting connection"; "peer_id" => %peer_id);
@@ -226,6 +239,15 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
     }
 }
 
+/// The IP address of a multiaddr, if it starts with one.
+fn multiaddr_ip(addr: &libp2p::Multiaddr) -> Option<IpAddr> {
+    match addr.iter().next() {
+        Some(libp2p::multiaddr::Protocol::Ip6(ip)) => Some(IpAddr::V6(ip)),
+        Some(libp2p::multiaddr::Protocol::Ip4(ip)) => Some(IpAddr::V4(ip)),
+        _ => None,
+    }
+}
+
 impl<E: EthSpec> PeerManager<E> {
     fn on_connection_established(
         &mut self,
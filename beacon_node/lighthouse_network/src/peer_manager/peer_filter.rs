@@ -0,0 +1,298 @@
+//! Allow and deny lists of IP ranges and node IDs, applied to inbound and outbound connections.
+//!
+//! The lists are read from a file with one entry per line, e.g.
+//!
+//! ```text
+//! # Only connect to our own beacon nodes, apart from a misbehaving one.
+//! allow 10.0.0.0/8
+//! allow 16Uiu2HAmKLhW7HiWkVNSbsTCAgXVkjcaVLwWvEdp94tdNDnddAhf
+//! deny 10.0.4.1
+//! ```
+//!
+//! Each entry is an IP address, a CIDR range, a libp2p peer ID or a hex-encoded discv5 node ID.
+//! A peer matching any `deny` entry is rejected. If there are any `allow` entries, a peer must also
+//! match one of them. The file is reloaded upon each heartbeat of the peer manager if it has been
+//! modified, and connected peers which are no longer permitted are disconnected.
+use crate::discovery::enr_ext::peer_id_to_node_id;
+use crate::PeerId;
+use discv5::enr::NodeId;
+use slog::{info, warn, Logger};
+use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// A range of IP addresses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(addr), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u32::from(addr) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(addr), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u128::from(addr) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s, None),
+        };
+        let addr = IpAddr::from_str(addr).map_err(|e| format!("Invalid IP address {addr}: {e}"))?;
+        let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix_len| *prefix_len <= max_prefix_len)
+                .ok_or_else(|| format!("Invalid prefix length in {s}"))?,
+            None => max_prefix_len,
+        };
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+/// An entry in an allow or deny list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PeerFilterEntry {
+    Ip(IpCidr),
+    Peer(PeerId),
+    Node(NodeId),
+}
+
+impl PeerFilterEntry {
+    fn matches(&self, ip: Option<&IpAddr>, peer_id: Option<&PeerId>) -> bool {
+        match self {
+            PeerFilterEntry::Ip(cidr) => ip.map_or(false, |ip| cidr.contains(ip)),
+            PeerFilterEntry::Peer(entry) => peer_id.map_or(false, |peer_id| entry == peer_id),
+            PeerFilterEntry::Node(entry) => peer_id
+                .and_then(|peer_id| peer_id_to_node_id(peer_id).ok())
+                .map_or(false, |node_id| *entry == node_id),
+        }
+    }
+}
+
+impl FromStr for PeerFilterEntry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(peer_id) = PeerId::from_str(s) {
+            return Ok(PeerFilterEntry::Peer(peer_id));
+        }
+        let hex_str = s.strip_prefix("0x").unwrap_or(s);
+        if hex_str.len() == 64 {
+            if let Ok(bytes) = hex::decode(hex_str) {
+                let raw = <[u8; 32]>::try_from(bytes.as_slice())
+                    .map_err(|_| format!("Invalid node ID {s}"))?;
+                return Ok(PeerFilterEntry::Node(NodeId::new(&raw)));
+            }
+        }
+        IpCidr::from_str(s).map(PeerFilterEntry::Ip)
+    }
+}
+
+/// The allow and deny lists.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeerFilter {
+    allow: Vec<PeerFilterEntry>,
+    deny: Vec<PeerFilterEntry>,
+}
+
+impl PeerFilter {
+    /// Parse the contents of a peer filter file.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut filter = PeerFilter::default();
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (kind, entry) = line.split_once(char::is_whitespace).ok_or_else(|| {
+                format!("Line {}: expected `allow` or `deny` and an entry", i + 1)
+            })?;
+            let entry = PeerFilterEntry::from_str(entry.trim())
+                .map_err(|e| format!("Line {}: {e}", i + 1))?;
+            match kind {
+                "allow" => filter.allow.push(entry),
+                "deny" => filter.deny.push(entry),
+                _ => return Err(format!("Line {}: unknown list `{kind}`", i + 1)),
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Returns `true` if the IP address is denied, regardless of the peer ID.
+    pub fn is_ip_denied(&self, ip: &IpAddr) -> bool {
+        self.deny.iter().any(|entry| entry.matches(Some(ip), None))
+    }
+
+    /// Returns `true` if a connection to `peer_id` is permitted. The IP address may be unknown
+    /// before an outbound connection is established.
+    pub fn is_permitted(&self, ip: Option<&IpAddr>, peer_id: &PeerId) -> bool {
+        !self
+            .deny
+            .iter()
+            .any(|entry| entry.matches(ip, Some(peer_id)))
+            && (self.allow.is_empty()
+                || self
+                    .allow
+                    .iter()
+                    .any(|entry| entry.matches(ip, Some(peer_id))))
+    }
+}
+
+/// A `PeerFilter` loaded from a file, which is reloaded when the file is modified.
+pub struct PeerFilterFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    filter: PeerFilter,
+}
+
+impl PeerFilterFile {
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let modified = Self::modified(&path)?;
+        let filter = Self::read(&path)?;
+        Ok(Self {
+            path,
+            modified,
+            filter,
+        })
+    }
+
+    pub fn filter(&self) -> &PeerFilter {
+        &self.filter
+    }
+
+    /// Reload the file if it has been modified since it was last read, returning `true` if the
+    /// filter has changed. The previous filter is kept if the file can't be read.
+    pub fn reload(&mut self, log: &Logger) -> bool {
+        let result = Self::modified(&self.path).and_then(|modified| {
+            if modified == self.modified {
+                return Ok(None);
+            }
+            Self::read(&self.path).map(|filter| Some((modified, filter)))
+        });
+        match result {
+            Ok(Some((modified, filter))) => {
+                self.modified = modified;
+                let changed = filter != self.filter;
+                if changed {
+                    info!(
+                        log,
+                        "Reloaded peer filter";
+                        "path" => %self.path.display(),
+                        "allow" => filter.allow.len(),
+                        "deny" => filter.deny.len(),
+                    );
+                }
+                self.filter = filter;
+                changed
+            }
+            Ok(None) => false,
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to reload peer filter";
+                    "path" => %self.path.display(),
+                    "error" => e,
+                );
+                false
+            }
+        }
+    }
+
+    fn modified(path: &PathBuf) -> Result<Option<SystemTime>, String> {
+        fs::metadata(path)
+            .map(|metadata| metadata.modified().ok())
+            .map_err(|e| format!("Unable to read {}: {e}", path.display()))
+    }
+
+    fn read(path: &PathBuf) -> Result<PeerFilter, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read {}: {e}", path.display()))?;
+        PeerFilter::parse(&contents).map_err(|e| format!("Invalid {}: {e}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::identity::Keypair;
+
+    #[test]
+    fn cidr_contains() {
+        let cidr = IpCidr::from_str("10.1.0.0/16").unwrap();
+        assert!(cidr.contains(&"10.1.2.3".parse().unwrap()));
+        assert!(!cidr.contains(&"10.2.0.1".parse().unwrap()));
+        assert!(cidr.contains(&"::ffff:10.1.0.1".parse().unwrap()));
+        assert!(!cidr.contains(&"::1".parse().unwrap()));
+
+        let cidr = IpCidr::from_str("2001:db8::/32").unwrap();
+        assert!(cidr.contains(&"2001:db8:1::1".parse().unwrap()));
+        assert!(!cidr.contains(&"2001:db9::1".parse().unwrap()));
+
+        assert!(IpCidr::from_str("0.0.0.0/0")
+            .unwrap()
+            .contains(&"1.2.3.4".parse().unwrap()));
+        assert!(IpCidr::from_str("10.0.0.0/33").is_err());
+    }
+
+    #[test]
+    fn allow_and_deny() {
+        let allowed = Keypair::generate_secp256k1().public().to_peer_id();
+        let denied = Keypair::generate_secp256k1().public().to_peer_id();
+        let other = Keypair::generate_secp256k1().public().to_peer_id();
+        let denied_node_id = hex::encode(peer_id_to_node_id(&denied).unwrap().raw());
+
+        let filter = PeerFilter::parse(&format!(
+            "# comment\n\
+             allow 10.0.0.0/8\n\
+             allow {allowed} # trailing comment\n\
+             deny 10.0.4.1\n\
+             deny 0x{denied_node_id}\n"
+        ))
+        .unwrap();
+
+        let private_ip = "10.0.0.1".parse().unwrap();
+        let public_ip = "1.2.3.4".parse().unwrap();
+        let denied_ip = "10.0.4.1".parse().unwrap();
+
+        assert!(filter.is_permitted(Some(&private_ip), &other));
+        assert!(filter.is_permitted(Some(&public_ip), &allowed));
+        assert!(filter.is_permitted(None, &allowed));
+        assert!(!filter.is_permitted(Some(&public_ip), &other));
+        assert!(!filter.is_permitted(Some(&denied_ip), &allowed));
+        assert!(!filter.is_permitted(Some(&private_ip), &denied));
+        assert!(filter.is_ip_denied(&denied_ip));
+        assert!(!filter.is_ip_denied(&private_ip));
+
+        // Without an allow list, everything that isn't denied is permitted.
+        let filter = PeerFilter::parse("deny 10.0.4.0/24").unwrap();
+        assert!(filter.is_permitted(Some(&public_ip), &other));
+        assert!(!filter.is_permitted(Some(&denied_ip), &other));
+
+        assert!(PeerFilter::parse("permit 10.0.0.1").is_err());
+        assert!(PeerFilter::parse("allow").is_err());
+        assert!(PeerFilter::parse("allow not-an-entry").is_err());
+    }
+}
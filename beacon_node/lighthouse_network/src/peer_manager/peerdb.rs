@@ -1265,7 +1265,9 @@ impl BannedPeersCount {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rpc::GoodbyeReason;
     use libp2p::core::multiaddr::Protocol;
+    use peer_info::Goodbye;
     use slog::{o, Drain};
     use std::net::{Ipv4Addr, Ipv6Addr};
     use types::MinimalEthSpec;
@@ -1347,6 +1349,28 @@ mod tests {
         assert_eq!(pdb.connected_outbound_only_peers().count(), 1);
     }
 
+    #[test]
+    fn test_goodbye_cleared_on_reconnect() {
+        let mut pdb = get_db();
+        let peer = PeerId::random();
+        let goodbye = Goodbye {
+            reason: GoodbyeReason::TooManyPeers,
+            sent: false,
+        };
+
+        pdb.connect_ingoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        pdb.peer_info_mut(&peer)
+            .unwrap()
+            .set_goodbye(goodbye.clone());
+        pdb.inject_disconnect(&peer);
+
+        // The goodbye is retained after disconnection so that it can be reported.
+        assert_eq!(pdb.peer_info(&peer).unwrap().goodbye(), Some(&goodbye));
+
+        pdb.connect_outgoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        assert_eq!(pdb.peer_info(&peer).unwrap().goodbye(), None);
+    }
+
     #[test]
     fn test_disconnected_removed_in_correct_order() {
         let mut pdb = get_db();
@@ -2,7 +2,8 @@ use super::client::Client;
 use super::score::{PeerAction, Score, ScoreState};
 use super::sync_status::SyncStatus;
 use crate::discovery::Eth2Enr;
-use crate::{rpc::MetaData, types::Subnet};
+use crate::rpc::{GoodbyeReason, MetaData};
+use crate::types::Subnet;
 use discv5::Enr;
 use libp2p::core::multiaddr::{Multiaddr, Protocol};
 use serde::{
@@ -51,6 +52,9 @@ pub struct PeerInfo<E: EthSpec> {
     connection_direction: Option<ConnectionDirection>,
     /// The enr of the peer, if known.
     enr: Option<Enr>,
+    /// The goodbye that ended the last connected session with this peer, if any.
+    #[serde(skip)]
+    goodbye: Option<Goodbye>,
 }
 
 impl<E: EthSpec> Default for PeerInfo<E> {
@@ -68,6 +72,7 @@ impl<E: EthSpec> Default for PeerInfo<E> {
             is_trusted: false,
             connection_direction: None,
             enr: None,
+            goodbye: None,
         }
     }
 }
@@ -149,6 +154,11 @@ impl<E: EthSpec> PeerInfo<E> {
         self.enr.as_ref()
     }
 
+    /// The goodbye that ended the last connected session with this peer, if one was exchanged.
+    pub fn goodbye(&self) -> Option<&Goodbye> {
+        self.goodbye.as_ref()
+    }
+
     /// An iterator over all the subnets this peer is subscribed to.
     pub fn subnets(&self) -> impl Iterator<Item = &Subnet> {
         self.subnets.iter()
@@ -362,6 +372,11 @@ impl<E: EthSpec> PeerInfo<E> {
         self.connection_status = connection_status
     }
 
+    /// Records the goodbye that is ending the current session with this peer.
+    pub(in crate::peer_manager) fn set_goodbye(&mut self, goodbye: Goodbye) {
+        self.goodbye = Some(goodbye)
+    }
+
     /// Sets the ENR of the peer if one is known.
     pub(super) fn set_enr(&mut self, enr: Enr) {
         self.enr = Some(enr)
@@ -440,6 +455,8 @@ impl<E: EthSpec> PeerInfo<E> {
             | Dialing { .. }
             | Disconnecting { .. }
             | Unknown => {
+                // A new session has started, any previous goodbye no longer applies.
+                self.goodbye = None;
                 self.connection_status = Connected {
                     n_in: 1,
                     n_out: 0,
@@ -461,6 +478,8 @@ impl<E: EthSpec> PeerInfo<E> {
             | Dialing { .. }
             | Disconnecting { .. }
             | Unknown => {
+                // A new session has started, any previous goodbye no longer applies.
+                self.goodbye = None;
                 self.connection_status = Connected {
                     n_in: 0,
                     n_out: 1,
@@ -495,6 +514,15 @@ pub enum ConnectionDirection {
     Outgoing,
 }
 
+/// A goodbye exchanged with a peer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Goodbye {
+    /// The reason given for the goodbye.
+    pub reason: GoodbyeReason,
+    /// Whether we sent the goodbye, as opposed to receiving it from the peer.
+    pub sent: bool,
+}
+
 /// Connection Status of the peer.
 #[derive(Debug, Clone, Default)]
 pub enum PeerConnectionStatus {
@@ -16,10 +16,11 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use tokio_util::codec::{Decoder, Encoder};
 use types::{
-    BlobSidecar, ChainSpec, EthSpec, ForkContext, ForkName, Hash256, LightClientBootstrap,
-    LightClientFinalityUpdate, LightClientOptimisticUpdate, RuntimeVariableList, SignedBeaconBlock,
-    SignedBeaconBlockAltair, SignedBeaconBlockBase, SignedBeaconBlockBellatrix,
-    SignedBeaconBlockCapella, SignedBeaconBlockDeneb, SignedBeaconBlockElectra,
+    BlobSidecar, ChainSpec, DepositTreeSnapshot, EthSpec, ForkContext, ForkName, Hash256,
+    LightClientBootstrap, LightClientFinalityUpdate, LightClientOptimisticUpdate,
+    RuntimeVariableList, SignedBeaconBlock, SignedBeaconBlockAltair, SignedBeaconBlockBase,
+    SignedBeaconBlockBellatrix, SignedBeaconBlockCapella, SignedBeaconBlockDeneb,
+    SignedBeaconBlockElectra,
 };
 use unsigned_varint::codec::Uvi;
 
@@ -74,6 +75,7 @@ impl<E: EthSpec> Encoder<RPCCodedResponse<E>> for SSZSnappyInboundCodec<E> {
                 RPCResponse::LightClientOptimisticUpdate(res) => res.as_ssz_bytes(),
                 RPCResponse::LightClientFinalityUpdate(res) => res.as_ssz_bytes(),
                 RPCResponse::Pong(res) => res.data.as_ssz_bytes(),
+                RPCResponse::DepositSnapshot(res) => res.as_ssz_bytes(),
                 RPCResponse::MetaData(res) =>
                 // Encode the correct version of the MetaData response based on the negotiated version.
                 {
@@ -133,6 +135,9 @@ impl<E: EthSpec> Decoder for SSZSnappyInboundCodec<E> {
         if self.protocol.versioned_protocol == SupportedProtocol::MetaDataV2 {
             return Ok(Some(InboundRequest::MetaData(MetadataRequest::new_v2())));
         }
+        if self.protocol.versioned_protocol == SupportedProtocol::DepositSnapshotV1 {
+            return Ok(Some(InboundRequest::DepositSnapshot));
+        }
         let Some(length) = handle_length(&mut self.inner, &mut self.len, src)? else {
             return Ok(None);
         };
@@ -226,6 +231,7 @@ impl<E: EthSpec> Encoder<OutboundRequest<E>> for SSZSnappyOutboundCodec<E> {
             OutboundRequest::BlobsByRoot(req) => req.blob_ids.as_ssz_bytes(),
             OutboundRequest::Ping(req) => req.as_ssz_bytes(),
             OutboundRequest::MetaData(_) => return Ok(()), // no metadata to encode
+            OutboundRequest::DepositSnapshot => return Ok(()), // no deposit snapshot to encode
         };
         // SSZ encoded bytes should be within `max_packet_size`
         if bytes.len() > self.max_packet_size {
@@ -430,7 +436,10 @@ fn context_bytes<E: EthSpec>(
                         .map_with_fork_name(|fork_name| fork_context.to_context_bytes(fork_name));
                 }
                 // These will not pass the has_context_bytes() check
-                RPCResponse::Status(_) | RPCResponse::Pong(_) | RPCResponse::MetaData(_) => {
+                RPCResponse::Status(_)
+                | RPCResponse::Pong(_)
+                | RPCResponse::MetaData(_)
+                | RPCResponse::DepositSnapshot(_) => {
                     return None;
                 }
             }
@@ -526,6 +535,7 @@ fn handle_rpc_request<E: EthSpec>(
         SupportedProtocol::LightClientFinalityUpdateV1 => {
             Ok(Some(InboundRequest::LightClientFinalityUpdate))
         }
+        SupportedProtocol::DepositSnapshotV1 => Ok(Some(InboundRequest::DepositSnapshot)),
         // MetaData requests return early from InboundUpgrade and do not reach the decoder.
         // Handle this case just for completeness.
         SupportedProtocol::MetaDataV2 => {
@@ -650,6 +660,9 @@ fn handle_rpc_response<E: EthSpec>(
         SupportedProtocol::MetaDataV2 => Ok(Some(RPCResponse::MetaData(MetaData::V2(
             MetaDataV2::from_ssz_bytes(decoded_buffer)?,
         )))),
+        SupportedProtocol::DepositSnapshotV1 => Ok(Some(RPCResponse::DepositSnapshot(Arc::new(
+            DepositTreeSnapshot::from_ssz_bytes(decoded_buffer)?,
+        )))),
         SupportedProtocol::BlocksByRangeV2 => match fork_name {
             Some(ForkName::Altair) => Ok(Some(RPCResponse::BlocksByRange(Arc::new(
                 SignedBeaconBlock::Altair(SignedBeaconBlockAltair::from_ssz_bytes(decoded_buffer)?),
@@ -1018,6 +1031,9 @@ mod tests {
             OutboundRequest::MetaData(metadata) => {
                 assert_eq!(decoded, InboundRequest::MetaData(metadata))
             }
+            OutboundRequest::DepositSnapshot => {
+                assert_eq!(decoded, InboundRequest::DepositSnapshot)
+            }
         }
     }
 
@@ -1119,6 +1135,21 @@ mod tests {
             Ok(Some(RPCResponse::MetaData(metadata()))),
         );
 
+        let deposit_snapshot = Arc::new(DepositTreeSnapshot {
+            finalized: vec![Hash256::repeat_byte(1); 3],
+            deposit_count: 7,
+            ..DepositTreeSnapshot::default()
+        });
+        assert_eq!(
+            encode_then_decode_response(
+                SupportedProtocol::DepositSnapshotV1,
+                RPCCodedResponse::Success(RPCResponse::DepositSnapshot(deposit_snapshot.clone())),
+                ForkName::Base,
+                &chain_spec,
+            ),
+            Ok(Some(RPCResponse::DepositSnapshot(deposit_snapshot))),
+        );
+
         assert_eq!(
             encode_then_decode_response(
                 SupportedProtocol::BlobsByRangeV1,
@@ -1492,6 +1523,7 @@ mod tests {
             OutboundRequest::BlobsByRange(blbrange_request()),
             OutboundRequest::BlobsByRoot(blbroot_request(&chain_spec)),
             OutboundRequest::MetaData(MetadataRequest::new_v2()),
+            OutboundRequest::DepositSnapshot,
         ];
 
         for req in requests.iter() {
@@ -1677,4 +1709,18 @@ mod tests {
             RPCError::InvalidData(_)
         ));
     }
+
+    /// The deposit snapshot protocol is specific to Lighthouse, so it must not use the namespace of
+    /// the spec protocols.
+    #[test]
+    fn test_deposit_snapshot_protocol_id() {
+        assert_eq!(
+            ProtocolId::new(SupportedProtocol::DepositSnapshotV1, Encoding::SSZSnappy).as_ref(),
+            "/lighthouse/req/deposit_snapshot/1/ssz_snappy"
+        );
+        assert_eq!(
+            ProtocolId::new(SupportedProtocol::StatusV1, Encoding::SSZSnappy).as_ref(),
+            "/eth2/beacon_chain/req/status/1/ssz_snappy"
+        );
+    }
 }
@@ -14,9 +14,9 @@ use strum::IntoStaticStr;
 use superstruct::superstruct;
 use types::blob_sidecar::BlobIdentifier;
 use types::{
-    blob_sidecar::BlobSidecar, ChainSpec, Epoch, EthSpec, Hash256, LightClientBootstrap,
-    LightClientFinalityUpdate, LightClientOptimisticUpdate, RuntimeVariableList, SignedBeaconBlock,
-    Slot,
+    blob_sidecar::BlobSidecar, ChainSpec, DepositTreeSnapshot, Epoch, EthSpec, Hash256,
+    LightClientBootstrap, LightClientFinalityUpdate, LightClientOptimisticUpdate,
+    RuntimeVariableList, SignedBeaconBlock, Slot,
 };
 
 /// Maximum length of error message.
@@ -405,6 +405,9 @@ pub enum RPCResponse<E: EthSpec> {
 
     /// A response to a META_DATA request.
     MetaData(MetaData<E>),
+
+    /// A response to a DEPOSIT_SNAPSHOT request.
+    DepositSnapshot(Arc<DepositTreeSnapshot>),
 }
 
 /// Indicates which response is being terminated by a stream termination response.
@@ -516,6 +519,7 @@ impl<E: EthSpec> RPCResponse<E> {
             RPCResponse::LightClientBootstrap(_) => Protocol::LightClientBootstrap,
             RPCResponse::LightClientOptimisticUpdate(_) => Protocol::LightClientOptimisticUpdate,
             RPCResponse::LightClientFinalityUpdate(_) => Protocol::LightClientFinalityUpdate,
+            RPCResponse::DepositSnapshot(_) => Protocol::DepositSnapshot,
         }
     }
 }
@@ -575,6 +579,13 @@ impl<E: EthSpec> std::fmt::Display for RPCResponse<E> {
                     update.signature_slot()
                 )
             }
+            RPCResponse::DepositSnapshot(snapshot) => {
+                write!(
+                    f,
+                    "DepositSnapshot: Deposit count: {}",
+                    snapshot.deposit_count
+                )
+            }
         }
     }
 }
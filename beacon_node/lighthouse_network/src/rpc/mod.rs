@@ -125,6 +125,7 @@ pub struct RPC<Id: ReqId, E: EthSpec> {
     events: Vec<BehaviourAction<Id, E>>,
     fork_context: Arc<ForkContext>,
     enable_light_client_server: bool,
+    enable_deposit_snapshot_rpc: bool,
     /// Slog logger for RPC behaviour.
     log: slog::Logger,
     /// Networking constant values
@@ -135,6 +136,7 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
     pub fn new(
         fork_context: Arc<ForkContext>,
         enable_light_client_server: bool,
+        enable_deposit_snapshot_rpc: bool,
         inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
         outbound_rate_limiter_config: Option<OutboundRateLimiterConfig>,
         log: slog::Logger,
@@ -158,6 +160,7 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
             events: Vec::new(),
             fork_context,
             enable_light_client_server,
+            enable_deposit_snapshot_rpc,
             log,
             network_params,
         }
@@ -233,6 +236,7 @@ where
                 fork_context: self.fork_context.clone(),
                 max_rpc_size: max_rpc_size(&self.fork_context, self.network_params.max_chunk_size),
                 enable_light_client_server: self.enable_light_client_server,
+                enable_deposit_snapshot_rpc: self.enable_deposit_snapshot_rpc,
                 phantom: PhantomData,
                 ttfb_timeout: self.network_params.ttfb_timeout,
             },
@@ -263,6 +267,7 @@ where
                 fork_context: self.fork_context.clone(),
                 max_rpc_size: max_rpc_size(&self.fork_context, self.network_params.max_chunk_size),
                 enable_light_client_server: self.enable_light_client_server,
+                enable_deposit_snapshot_rpc: self.enable_deposit_snapshot_rpc,
                 phantom: PhantomData,
                 ttfb_timeout: self.network_params.ttfb_timeout,
             },
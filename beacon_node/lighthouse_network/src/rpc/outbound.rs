@@ -38,6 +38,7 @@ pub enum OutboundRequest<E: EthSpec> {
     BlobsByRoot(BlobsByRootRequest),
     Ping(Ping),
     MetaData(MetadataRequest<E>),
+    DepositSnapshot,
 }
 
 impl<E: EthSpec> UpgradeInfo for OutboundRequestContainer<E> {
@@ -87,6 +88,10 @@ impl<E: EthSpec> OutboundRequest<E> {
                 ProtocolId::new(SupportedProtocol::MetaDataV2, Encoding::SSZSnappy),
                 ProtocolId::new(SupportedProtocol::MetaDataV1, Encoding::SSZSnappy),
             ],
+            OutboundRequest::DepositSnapshot => vec![ProtocolId::new(
+                SupportedProtocol::DepositSnapshotV1,
+                Encoding::SSZSnappy,
+            )],
         }
     }
     /* These functions are used in the handler for stream management */
@@ -102,6 +107,7 @@ impl<E: EthSpec> OutboundRequest<E> {
             OutboundRequest::BlobsByRoot(req) => req.blob_ids.len() as u64,
             OutboundRequest::Ping(_) => 1,
             OutboundRequest::MetaData(_) => 1,
+            OutboundRequest::DepositSnapshot => 1,
         }
     }
 
@@ -115,6 +121,7 @@ impl<E: EthSpec> OutboundRequest<E> {
             OutboundRequest::BlobsByRoot(_) => false,
             OutboundRequest::Ping(_) => true,
             OutboundRequest::MetaData(_) => true,
+            OutboundRequest::DepositSnapshot => true,
         }
     }
 
@@ -138,6 +145,7 @@ impl<E: EthSpec> OutboundRequest<E> {
                 MetadataRequest::V1(_) => SupportedProtocol::MetaDataV1,
                 MetadataRequest::V2(_) => SupportedProtocol::MetaDataV2,
             },
+            OutboundRequest::DepositSnapshot => SupportedProtocol::DepositSnapshotV1,
         }
     }
 
@@ -155,6 +163,7 @@ impl<E: EthSpec> OutboundRequest<E> {
             OutboundRequest::Goodbye(_) => unreachable!(),
             OutboundRequest::Ping(_) => unreachable!(),
             OutboundRequest::MetaData(_) => unreachable!(),
+            OutboundRequest::DepositSnapshot => unreachable!(),
         }
     }
 }
@@ -210,6 +219,7 @@ impl<E: EthSpec> std::fmt::Display for OutboundRequest<E> {
             OutboundRequest::BlobsByRoot(req) => write!(f, "Blobs by root: {:?}", req),
             OutboundRequest::Ping(ping) => write!(f, "Ping: {}", ping.data),
             OutboundRequest::MetaData(_) => write!(f, "MetaData request"),
+            OutboundRequest::DepositSnapshot => write!(f, "Deposit snapshot request"),
         }
     }
 }
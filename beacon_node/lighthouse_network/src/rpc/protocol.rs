@@ -19,10 +19,11 @@ use tokio_util::{
 };
 use types::{
     BeaconBlock, BeaconBlockAltair, BeaconBlockBase, BeaconBlockBellatrix, BeaconBlockCapella,
-    BeaconBlockElectra, BlobSidecar, ChainSpec, EmptyBlock, EthSpec, ForkContext, ForkName,
-    LightClientBootstrap, LightClientBootstrapAltair, LightClientFinalityUpdate,
-    LightClientFinalityUpdateAltair, LightClientOptimisticUpdate,
+    BeaconBlockElectra, BlobSidecar, ChainSpec, DepositTreeSnapshot, EmptyBlock, EthSpec,
+    ForkContext, ForkName, Hash256, LightClientBootstrap, LightClientBootstrapAltair,
+    LightClientFinalityUpdate, LightClientFinalityUpdateAltair, LightClientOptimisticUpdate,
     LightClientOptimisticUpdateAltair, MainnetEthSpec, Signature, SignedBeaconBlock,
+    DEPOSIT_TREE_DEPTH,
 };
 
 lazy_static! {
@@ -123,10 +124,22 @@ lazy_static! {
     pub static ref LIGHT_CLIENT_BOOTSTRAP_CAPELLA_MAX: usize = LightClientBootstrap::<MainnetEthSpec>::ssz_max_len_for_fork(ForkName::Capella);
     pub static ref LIGHT_CLIENT_BOOTSTRAP_DENEB_MAX: usize = LightClientBootstrap::<MainnetEthSpec>::ssz_max_len_for_fork(ForkName::Deneb);
     pub static ref LIGHT_CLIENT_BOOTSTRAP_ELECTRA_MAX: usize = LightClientBootstrap::<MainnetEthSpec>::ssz_max_len_for_fork(ForkName::Electra);
+
+    pub static ref DEPOSIT_TREE_SNAPSHOT_MIN: usize = DepositTreeSnapshot::default().as_ssz_bytes().len();
+    // A snapshot holds at most one finalized hash for each level of the deposit tree.
+    pub static ref DEPOSIT_TREE_SNAPSHOT_MAX: usize = DepositTreeSnapshot {
+        finalized: vec![Hash256::zero(); DEPOSIT_TREE_DEPTH],
+        ..DepositTreeSnapshot::default()
+    }
+    .as_ssz_bytes()
+    .len();
 }
 
 /// The protocol prefix the RPC protocol id.
 const PROTOCOL_PREFIX: &str = "/eth2/beacon_chain/req";
+/// The protocol prefix for RPC protocols which are specific to Lighthouse and not part of the
+/// consensus specs.
+const LIGHTHOUSE_PROTOCOL_PREFIX: &str = "/lighthouse/req";
 /// The number of seconds to wait for the first bytes of a request once a protocol has been
 /// established before the stream is terminated.
 const REQUEST_TIMEOUT: u64 = 15;
@@ -264,6 +277,9 @@ pub enum Protocol {
     /// The `LightClientFinalityUpdate` protocol name.
     #[strum(serialize = "light_client_finality_update")]
     LightClientFinalityUpdate,
+    /// The `DepositSnapshot` protocol name.
+    #[strum(serialize = "deposit_snapshot")]
+    DepositSnapshot,
 }
 
 impl Protocol {
@@ -280,6 +296,7 @@ impl Protocol {
             Protocol::LightClientBootstrap => None,
             Protocol::LightClientOptimisticUpdate => None,
             Protocol::LightClientFinalityUpdate => None,
+            Protocol::DepositSnapshot => None,
         }
     }
 }
@@ -307,6 +324,7 @@ pub enum SupportedProtocol {
     LightClientBootstrapV1,
     LightClientOptimisticUpdateV1,
     LightClientFinalityUpdateV1,
+    DepositSnapshotV1,
 }
 
 impl SupportedProtocol {
@@ -326,6 +344,7 @@ impl SupportedProtocol {
             SupportedProtocol::LightClientBootstrapV1 => "1",
             SupportedProtocol::LightClientOptimisticUpdateV1 => "1",
             SupportedProtocol::LightClientFinalityUpdateV1 => "1",
+            SupportedProtocol::DepositSnapshotV1 => "1",
         }
     }
 
@@ -347,6 +366,7 @@ impl SupportedProtocol {
                 Protocol::LightClientOptimisticUpdate
             }
             SupportedProtocol::LightClientFinalityUpdateV1 => Protocol::LightClientFinalityUpdate,
+            SupportedProtocol::DepositSnapshotV1 => Protocol::DepositSnapshot,
         }
     }
 
@@ -387,6 +407,7 @@ pub struct RPCProtocol<E: EthSpec> {
     pub fork_context: Arc<ForkContext>,
     pub max_rpc_size: usize,
     pub enable_light_client_server: bool,
+    pub enable_deposit_snapshot_rpc: bool,
     pub phantom: PhantomData<E>,
     pub ttfb_timeout: Duration,
 }
@@ -412,6 +433,12 @@ impl<E: EthSpec> UpgradeInfo for RPCProtocol<E> {
                 Encoding::SSZSnappy,
             ));
         }
+        if self.enable_deposit_snapshot_rpc {
+            supported_protocols.push(ProtocolId::new(
+                SupportedProtocol::DepositSnapshotV1,
+                Encoding::SSZSnappy,
+            ));
+        }
         supported_protocols
     }
 }
@@ -488,6 +515,7 @@ impl ProtocolId {
             Protocol::LightClientOptimisticUpdate => RpcLimits::new(0, 0),
             Protocol::LightClientFinalityUpdate => RpcLimits::new(0, 0),
             Protocol::MetaData => RpcLimits::new(0, 0), // Metadata requests are empty
+            Protocol::DepositSnapshot => RpcLimits::new(0, 0),
         }
     }
 
@@ -520,6 +548,9 @@ impl ProtocolId {
             Protocol::LightClientFinalityUpdate => {
                 rpc_light_client_finality_update_limits_by_fork(fork_context.current_fork())
             }
+            Protocol::DepositSnapshot => {
+                RpcLimits::new(*DEPOSIT_TREE_SNAPSHOT_MIN, *DEPOSIT_TREE_SNAPSHOT_MAX)
+            }
         }
     }
 
@@ -540,7 +571,8 @@ impl ProtocolId {
             | SupportedProtocol::PingV1
             | SupportedProtocol::MetaDataV1
             | SupportedProtocol::MetaDataV2
-            | SupportedProtocol::GoodbyeV1 => false,
+            | SupportedProtocol::GoodbyeV1
+            | SupportedProtocol::DepositSnapshotV1 => false,
         }
     }
 }
@@ -548,9 +580,13 @@ impl ProtocolId {
 /// An RPC protocol ID.
 impl ProtocolId {
     pub fn new(versioned_protocol: SupportedProtocol, encoding: Encoding) -> Self {
+        let prefix = match versioned_protocol.protocol() {
+            Protocol::DepositSnapshot => LIGHTHOUSE_PROTOCOL_PREFIX,
+            _ => PROTOCOL_PREFIX,
+        };
         let protocol_id = format!(
             "{}/{}/{}/{}",
-            PROTOCOL_PREFIX,
+            prefix,
             versioned_protocol.protocol(),
             versioned_protocol.version_string(),
             encoding
@@ -623,6 +659,9 @@ where
                 SupportedProtocol::LightClientFinalityUpdateV1 => {
                     Ok((InboundRequest::LightClientFinalityUpdate, socket))
                 }
+                SupportedProtocol::DepositSnapshotV1 => {
+                    Ok((InboundRequest::DepositSnapshot, socket))
+                }
                 _ => {
                     match tokio::time::timeout(
                         Duration::from_secs(REQUEST_TIMEOUT),
@@ -655,6 +694,7 @@ pub enum InboundRequest<E: EthSpec> {
     LightClientFinalityUpdate,
     Ping(Ping),
     MetaData(MetadataRequest<E>),
+    DepositSnapshot,
 }
 
 /// Implements the encoding per supported protocol for `RPCRequest`.
@@ -675,6 +715,7 @@ impl<E: EthSpec> InboundRequest<E> {
             InboundRequest::LightClientBootstrap(_) => 1,
             InboundRequest::LightClientOptimisticUpdate => 1,
             InboundRequest::LightClientFinalityUpdate => 1,
+            InboundRequest::DepositSnapshot => 1,
         }
     }
 
@@ -705,6 +746,7 @@ impl<E: EthSpec> InboundRequest<E> {
             InboundRequest::LightClientFinalityUpdate => {
                 SupportedProtocol::LightClientFinalityUpdateV1
             }
+            InboundRequest::DepositSnapshot => SupportedProtocol::DepositSnapshotV1,
         }
     }
 
@@ -725,6 +767,7 @@ impl<E: EthSpec> InboundRequest<E> {
             InboundRequest::LightClientBootstrap(_) => unreachable!(),
             InboundRequest::LightClientFinalityUpdate => unreachable!(),
             InboundRequest::LightClientOptimisticUpdate => unreachable!(),
+            InboundRequest::DepositSnapshot => unreachable!(),
         }
     }
 }
@@ -839,6 +882,7 @@ impl<E: EthSpec> std::fmt::Display for InboundRequest<E> {
             InboundRequest::LightClientFinalityUpdate => {
                 write!(f, "Light client finality update request")
             }
+            InboundRequest::DepositSnapshot => write!(f, "Deposit snapshot request"),
         }
     }
 }
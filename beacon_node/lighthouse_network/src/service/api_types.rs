@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use libp2p::swarm::ConnectionId;
 use types::{
-    BlobSidecar, EthSpec, LightClientBootstrap, LightClientFinalityUpdate,
+    BlobSidecar, DepositTreeSnapshot, EthSpec, LightClientBootstrap, LightClientFinalityUpdate,
     LightClientOptimisticUpdate, SignedBeaconBlock,
 };
 
@@ -75,6 +75,8 @@ pub enum Request {
     LightClientFinalityUpdate,
     /// A request blobs root request.
     BlobsByRoot(BlobsByRootRequest),
+    /// A deposit snapshot request.
+    DepositSnapshot,
 }
 
 impl<E: EthSpec> std::convert::From<Request> for OutboundRequest<E> {
@@ -105,6 +107,7 @@ impl<E: EthSpec> std::convert::From<Request> for OutboundRequest<E> {
             Request::BlobsByRange(r) => OutboundRequest::BlobsByRange(r),
             Request::BlobsByRoot(r) => OutboundRequest::BlobsByRoot(r),
             Request::Status(s) => OutboundRequest::Status(s),
+            Request::DepositSnapshot => OutboundRequest::DepositSnapshot,
         }
     }
 }
@@ -133,6 +136,8 @@ pub enum Response<E: EthSpec> {
     LightClientOptimisticUpdate(Arc<LightClientOptimisticUpdate<E>>),
     /// A response to a LightClientFinalityUpdate request.
     LightClientFinalityUpdate(Arc<LightClientFinalityUpdate<E>>),
+    /// A response to a DepositSnapshot request.
+    DepositSnapshot(Arc<DepositTreeSnapshot>),
 }
 
 impl<E: EthSpec> std::convert::From<Response<E>> for RPCCodedResponse<E> {
@@ -164,6 +169,9 @@ impl<E: EthSpec> std::convert::From<Response<E>> for RPCCodedResponse<E> {
             Response::LightClientFinalityUpdate(f) => {
                 RPCCodedResponse::Success(RPCResponse::LightClientFinalityUpdate(f))
             }
+            Response::DepositSnapshot(s) => {
+                RPCCodedResponse::Success(RPCResponse::DepositSnapshot(s))
+            }
         }
     }
 }
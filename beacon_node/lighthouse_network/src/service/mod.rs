@@ -65,6 +65,8 @@ pub enum NetworkEvent<E: EthSpec> {
     PeerConnectedIncoming(PeerId),
     /// A peer has disconnected.
     PeerDisconnected(PeerId),
+    /// A peer has been banned.
+    PeerBanned(PeerId),
     /// An RPC Request that was sent failed.
     RPCFailed {
         /// The id of the failed request.
@@ -315,6 +317,7 @@ impl<E: EthSpec> Network<E> {
         let eth2_rpc = RPC::new(
             ctx.fork_context.clone(),
             config.enable_light_client_server,
+            config.enable_deposit_snapshot_rpc,
             config.inbound_rate_limiter_config.clone(),
             config.outbound_rate_limiter_config.clone(),
             log.clone(),
@@ -358,6 +361,7 @@ impl<E: EthSpec> Network<E> {
                 quic_enabled: !config.disable_quic_support,
                 metrics_enabled: config.metrics_enabled,
                 target_peer_count: config.target_peers,
+                peer_filter_file: config.peer_filter_file.clone(),
                 ..Default::default()
             };
             PeerManager::new(peer_manager_cfg, network_globals.clone(), &log)?
@@ -1191,6 +1195,9 @@ impl<E: EthSpec> Network<E> {
                 &metrics::TOTAL_RPC_REQUESTS,
                 &["light_client_finality_update"],
             ),
+            Request::DepositSnapshot => {
+                metrics::inc_counter_vec(&metrics::TOTAL_RPC_REQUESTS, &["deposit_snapshot"])
+            }
             Request::BlocksByRange { .. } => {
                 metrics::inc_counter_vec(&metrics::TOTAL_RPC_REQUESTS, &["blocks_by_range"])
             }
@@ -1249,7 +1256,29 @@ impl<E: EthSpec> Network<E> {
             } => {
                 // Note: We are keeping track here of the peer that sent us the message, not the
                 // peer that originally published the message.
-                match PubsubMessage::decode(&gs_msg.topic, &gs_msg.data, &self.fork_context) {
+                // Blocks and blobs enter the import pipeline here. The span is correlated with
+                // the later stages of the pipeline by its slot.
+                let decode_span = tracing::info_span!(
+                    target: logging::IMPORT_SPAN_TARGET,
+                    "gossip_decode",
+                    topic = %gs_msg.topic,
+                    slot = tracing::field::Empty,
+                );
+                let decoded = decode_span.in_scope(|| {
+                    PubsubMessage::decode(&gs_msg.topic, &gs_msg.data, &self.fork_context)
+                });
+                match &decoded {
+                    Ok(PubsubMessage::BeaconBlock(block)) => {
+                        decode_span.record("slot", block.slot().as_u64());
+                    }
+                    Ok(PubsubMessage::BlobSidecar(blob)) => {
+                        decode_span.record("slot", blob.1.slot().as_u64());
+                    }
+                    _ => {}
+                }
+                drop(decode_span);
+
+                match decoded {
                     Err(e) => {
                         debug!(self.log, "Could not decode gossipsub message"; "topic" => ?gs_msg.topic,"error" => e);
                         //reject the message
@@ -1457,6 +1486,8 @@ impl<E: EthSpec> Network<E> {
                             "reason" => %reason,
                             "client" => %self.network_globals.client(&peer_id),
                         );
+                        self.peer_manager_mut()
+                            .register_goodbye(&peer_id, reason, false);
                         // NOTE: We currently do not inform the application that we are
                         // disconnecting here. The RPC handler will automatically
                         // disconnect for us.
@@ -1546,6 +1577,11 @@ impl<E: EthSpec> Network<E> {
                         );
                         Some(event)
                     }
+                    InboundRequest::DepositSnapshot => {
+                        let event =
+                            self.build_request(peer_request_id, peer_id, Request::DepositSnapshot);
+                        Some(event)
+                    }
                 }
             }
             HandlerEvent::Ok(RPCReceived::Response(id, resp)) => {
@@ -1593,6 +1629,9 @@ impl<E: EthSpec> Network<E> {
                         peer_id,
                         Response::LightClientFinalityUpdate(update),
                     ),
+                    RPCResponse::DepositSnapshot(snapshot) => {
+                        self.build_response(id, peer_id, Response::DepositSnapshot(snapshot))
+                    }
                 }
             }
             HandlerEvent::Ok(RPCReceived::EndOfStream(id, termination)) => {
@@ -1646,7 +1685,7 @@ impl<E: EthSpec> Network<E> {
             }
             PeerManagerEvent::Banned(peer_id, associated_ips) => {
                 self.discovery_mut().ban_peer(&peer_id, associated_ips);
-                None
+                Some(NetworkEvent::PeerBanned(peer_id))
             }
             PeerManagerEvent::UnBanned(peer_id, associated_ips) => {
                 self.discovery_mut().unban_peer(&peer_id, associated_ips);
@@ -1679,6 +1718,8 @@ impl<E: EthSpec> Network<E> {
             PeerManagerEvent::DisconnectPeer(peer_id, reason) => {
                 debug!(self.log, "Peer Manager disconnecting peer";
                        "peer_id" => %peer_id, "reason" => %reason);
+                self.peer_manager_mut()
+                    .register_goodbye(&peer_id, reason.clone(), true);
                 // send one goodbye
                 self.eth2_rpc_mut()
                     .shutdown(peer_id, RequestId::Internal, reason);
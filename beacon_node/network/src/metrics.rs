@@ -42,6 +42,10 @@ lazy_static! {
         "validator_subnet_subscriptions_aggregator_total",
         "Count of validator subscription requests where the subscriber is an aggregator."
     );
+    pub static ref SUBNET_SUBSCRIPTION_DUPLICATE_REQUESTS: Result<IntCounter> = try_create_int_counter(
+        "validator_attestation_subnet_subscriptions_duplicate_total",
+        "Count of validator attestation subscription requests ignored as duplicates."
+    );
     pub static ref SYNC_COMMITTEE_SUBSCRIPTION_REQUESTS: Result<IntCounter> = try_create_int_counter(
         "validator_sync_committee_subnet_subscriptions_total",
         "Count of validator sync committee subscription requests."
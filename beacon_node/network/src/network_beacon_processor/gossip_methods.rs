@@ -7,6 +7,7 @@ use crate::{
 use beacon_chain::blob_verification::{GossipBlobError, GossipVerifiedBlob};
 use beacon_chain::block_verification_types::AsBlock;
 use beacon_chain::data_column_verification::GossipVerifiedDataColumn;
+use beacon_chain::gossip_arrival_times::GossipArrivalKind;
 use beacon_chain::store::Error;
 use beacon_chain::{
     attestation_verification::{self, Error as AttnError, VerifiedAttestation},
@@ -14,7 +15,9 @@ use beacon_chain::{
     light_client_finality_update_verification::Error as LightClientFinalityUpdateError,
     light_client_optimistic_update_verification::Error as LightClientOptimisticUpdateError,
     observed_operations::ObservationOutcome,
-    sync_committee_verification::{self, Error as SyncCommitteeError},
+    sync_committee_verification::{
+        self, Error as SyncCommitteeError, VerifiedSyncCommitteeMessage,
+    },
     validator_monitor::{get_block_delay_ms, get_slot_delay_ms},
     AvailabilityProcessingStatus, BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError,
     GossipVerifiedBlock, NotifyExecutionLayer,
@@ -31,6 +34,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use store::hot_cold_store::HotColdDBError;
 use tokio::sync::mpsc;
+use tracing::{info_span, Instrument};
 use types::{
     beacon_block::BlockImportSource, Attestation, AttestationRef, AttesterSlashing, BlobSidecar,
     DataColumnSidecar, DataColumnSubnetId, EthSpec, Hash256, IndexedAttestation,
@@ -45,7 +49,7 @@ use beacon_processor::{
         QueuedAggregate, QueuedGossipBlock, QueuedLightClientUpdate, QueuedUnaggregate,
         ReprocessQueueMessage,
     },
-    DuplicateCache, GossipAggregatePackage, GossipAttestationPackage,
+    DuplicateCache, GossipAggregatePackage, GossipAttestationPackage, GossipSyncSignaturePackage,
 };
 
 /// Set to `true` to introduce stricter penalties for peers who send some types of late consensus
@@ -526,6 +530,15 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     peer_id,
                 );
 
+                let attestation_slot = aggregate.message().aggregate().data().slot;
+                self.chain.gossip_arrival_times.lock().record(
+                    GossipArrivalKind::Aggregate,
+                    attestation_slot,
+                    beacon_block_root,
+                    Some(aggregate.message().aggregator_index()),
+                    get_slot_delay_ms(seen_timestamp, attestation_slot, &self.chain.slot_clock),
+                );
+
                 // Register the attestation with any monitored validators.
                 self.chain
                     .validator_monitor
@@ -679,13 +692,31 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let delay = get_slot_delay_ms(seen_duration, slot, &self.chain.slot_clock);
         // Log metrics to track delay from other nodes on the network.
         metrics::set_gauge(&metrics::BEACON_BLOB_DELAY_GOSSIP, delay.as_millis() as i64);
-        match self
-            .chain
-            .verify_blob_sidecar_for_gossip(blob_sidecar, blob_index)
-        {
+        let span = info_span!(
+            target: logging::IMPORT_SPAN_TARGET,
+            "gossip_blob",
+            slot = slot.as_u64(),
+            block_root = ?root,
+            index,
+        );
+        let verification_result = span.in_scope(|| {
+            let _span =
+                info_span!(target: logging::IMPORT_SPAN_TARGET, "gossip_verification").entered();
+            self.chain
+                .verify_blob_sidecar_for_gossip(blob_sidecar, blob_index)
+        });
+        match verification_result {
             Ok(gossip_verified_blob) => {
                 metrics::inc_counter(&metrics::BEACON_PROCESSOR_GOSSIP_BLOB_VERIFIED_TOTAL);
 
+                self.chain.gossip_arrival_times.lock().record(
+                    GossipArrivalKind::BlobSidecar,
+                    slot,
+                    root,
+                    Some(index),
+                    delay,
+                );
+
                 if delay >= self.chain.slot_clock.unagg_attestation_production_delay() {
                     metrics::inc_counter(&metrics::BEACON_BLOB_GOSSIP_ARRIVED_LATE_TOTAL);
                     debug!(
@@ -722,6 +753,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     );
                 }
                 self.process_gossip_verified_blob(peer_id, gossip_verified_blob, seen_duration)
+                    .instrument(span)
                     .await
             }
             Err(err) => {
@@ -775,6 +807,30 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                             MessageAcceptance::Reject,
                         );
                     }
+                    GossipBlobError::BlobEquivocation {
+                        proposer,
+                        previous_block_root,
+                        ..
+                    } => {
+                        warn!(
+                            self.log,
+                            "Equivocating blob sidecar received. Ignoring the blob sidecar";
+                            "proposer" => proposer,
+                            "previous_root" => %previous_block_root,
+                            "slot" => %slot,
+                            "root" => %root,
+                            "index" => %index,
+                            "commitment" => %commitment,
+                        );
+                        // The spec IGNOREs all but the first sidecar for each slot, proposer and
+                        // index. Honest peers may relay an equivocation before seeing the first
+                        // sidecar, so the forwarding peer is not penalized.
+                        self.propagate_validation_result(
+                            message_id,
+                            peer_id,
+                            MessageAcceptance::Ignore,
+                        );
+                    }
                     GossipBlobError::FutureSlot { .. } | GossipBlobError::RepeatBlob { .. } => {
                         debug!(
                             self.log,
@@ -994,6 +1050,12 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         invalid_block_storage: InvalidBlockStorage,
         seen_duration: Duration,
     ) {
+        let span = info_span!(
+            target: logging::IMPORT_SPAN_TARGET,
+            "gossip_block",
+            slot = block.slot().as_u64(),
+            block_root = tracing::field::Empty,
+        );
         if let Some(gossip_verified_block) = self
             .process_gossip_unverified_block(
                 message_id,
@@ -1003,9 +1065,11 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 reprocess_tx.clone(),
                 seen_duration,
             )
+            .instrument(span.clone())
             .await
         {
             let block_root = gossip_verified_block.block_root;
+            span.record("block_root", tracing::field::debug(block_root));
 
             if let Some(handle) = duplicate_cache.check_and_insert(block_root) {
                 self.process_gossip_verified_block(
@@ -1015,6 +1079,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     invalid_block_storage,
                     seen_duration,
                 )
+                .instrument(span)
                 .await;
                 // Drop the handle to remove the entry from the cache
                 drop(handle);
@@ -1049,13 +1114,21 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             .chain
             .clone()
             .verify_block_for_gossip(block.clone())
+            .instrument(info_span!(target: logging::IMPORT_SPAN_TARGET, "gossip_verification"))
             .await;
 
-        if verification_result.is_ok() {
+        if let Ok(verified_block) = &verification_result {
             metrics::set_gauge(
                 &metrics::BEACON_BLOCK_DELAY_GOSSIP,
                 block_delay.as_millis() as i64,
             );
+            self.chain.gossip_arrival_times.lock().record(
+                GossipArrivalKind::Block,
+                block.slot(),
+                verified_block.block_root,
+                None,
+                block_delay,
+            );
         }
 
         let block_root = if let Ok(verified_block) = &verification_result {
@@ -1741,10 +1814,92 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         seen_timestamp: Duration,
     ) {
         let message_slot = sync_signature.slot;
-        let sync_signature = match self
+        let result = self
+            .chain
+            .verify_sync_committee_message_for_gossip(sync_signature, subnet_id);
+
+        self.process_gossip_sync_committee_signature_result(
+            result,
+            message_id,
+            peer_id,
+            message_slot,
+            seen_timestamp,
+        );
+    }
+
+    /// Process a batch of sync committee signatures received from the gossip network.
+    ///
+    /// The signatures are verified per subcommittee using batch BLS verification. Otherwise, the
+    /// handling of each signature is the same as `Self::process_gossip_sync_committee_signature`.
+    pub fn process_gossip_sync_committee_signature_batch(
+        self: Arc<Self>,
+        packages: Vec<GossipSyncSignaturePackage>,
+    ) {
+        let (metadata, messages): (Vec<_>, Vec<_>) = packages
+            .into_iter()
+            .map(|package| {
+                (
+                    (
+                        package.message_id,
+                        package.peer_id,
+                        package.sync_signature.slot,
+                        package.seen_timestamp,
+                    ),
+                    (*package.sync_signature, package.subnet_id),
+                )
+            })
+            .unzip();
+
+        let results = match self
             .chain
-            .verify_sync_committee_message_for_gossip(sync_signature, subnet_id)
+            .batch_verify_sync_committee_messages_for_gossip(messages.into_iter())
+        {
+            Ok(results) => results,
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Batch sync signature verification failed";
+                    "error" => ?e
+                );
+                return;
+            }
+        };
+
+        // Sanity check.
+        if results.len() != metadata.len() {
+            // The log is `crit` since in this scenario we might be penalizing/rewarding the wrong
+            // peer.
+            crit!(
+                self.log,
+                "Batch sync signature result mismatch";
+                "results" => results.len(),
+                "packages" => metadata.len(),
+            )
+        }
+
+        for (result, (message_id, peer_id, message_slot, seen_timestamp)) in
+            results.into_iter().zip(metadata)
         {
+            self.process_gossip_sync_committee_signature_result(
+                result,
+                message_id,
+                peer_id,
+                message_slot,
+                seen_timestamp,
+            );
+        }
+    }
+
+    /// Propagate and import a sync committee signature, or handle its verification failure.
+    fn process_gossip_sync_committee_signature_result(
+        self: &Arc<Self>,
+        result: Result<VerifiedSyncCommitteeMessage, SyncCommitteeError>,
+        message_id: MessageId,
+        peer_id: PeerId,
+        message_slot: Slot,
+        seen_timestamp: Duration,
+    ) {
+        let sync_signature = match result {
             Ok(sync_signature) => sync_signature,
             Err(e) => {
                 self.handle_sync_committee_message_failure(
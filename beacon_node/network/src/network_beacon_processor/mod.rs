@@ -5,12 +5,15 @@ use beacon_chain::{builder::Witness, eth1_chain::CachingEth1Backend, BeaconChain
 use beacon_chain::{BeaconChainTypes, NotifyExecutionLayer};
 use beacon_processor::{
     work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorChannels, BeaconProcessorSend,
-    DuplicateCache, GossipAggregatePackage, GossipAttestationPackage, Work,
-    WorkEvent as BeaconWorkEvent,
+    BlockingFn, DuplicateCache, GossipAggregatePackage, GossipAttestationPackage,
+    GossipSyncSignaturePackage, Work, WorkEvent as BeaconWorkEvent,
 };
 use lighthouse_network::rpc::methods::{BlobsByRangeRequest, BlobsByRootRequest};
 use lighthouse_network::{
-    rpc::{BlocksByRangeRequest, BlocksByRootRequest, LightClientBootstrapRequest, StatusMessage},
+    rpc::{
+        BlocksByRangeRequest, BlocksByRootRequest, LightClientBootstrapRequest,
+        RPCResponseErrorCode, StatusMessage,
+    },
     Client, MessageId, NetworkGlobals, PeerId, PeerRequestId,
 };
 use slog::{debug, Logger};
@@ -262,20 +265,37 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         subnet_id: SyncSubnetId,
         seen_timestamp: Duration,
     ) -> Result<(), Error<T::EthSpec>> {
+        // Define a closure for processing individual sync committee signatures.
         let processor = self.clone();
-        let process_fn = move || {
+        let process_individual = move |package: GossipSyncSignaturePackage| {
             processor.process_gossip_sync_committee_signature(
-                message_id,
-                peer_id,
-                sync_signature,
-                subnet_id,
-                seen_timestamp,
+                package.message_id,
+                package.peer_id,
+                *package.sync_signature,
+                package.subnet_id,
+                package.seen_timestamp,
             )
         };
 
+        // Define a closure for processing batches of sync committee signatures.
+        let processor = self.clone();
+        let process_batch = move |sync_signatures| {
+            processor.process_gossip_sync_committee_signature_batch(sync_signatures)
+        };
+
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
-            work: Work::GossipSyncSignature(Box::new(process_fn)),
+            work: Work::GossipSyncSignature {
+                sync_signature: Box::new(GossipSyncSignaturePackage {
+                    message_id,
+                    peer_id,
+                    sync_signature: Box::new(sync_signature),
+                    subnet_id,
+                    seen_timestamp,
+                }),
+                process_individual: Box::new(process_individual),
+                process_batch: Box::new(process_batch),
+            },
         })
     }
 
@@ -602,6 +622,20 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         })
     }
 
+    /// Returns a function which responds to an RPC request with an error if the beacon processor
+    /// sheds it under load.
+    fn reject_shed_request_fn(self: &Arc<Self>, peer_id: PeerId, id: PeerRequestId) -> BlockingFn {
+        let processor = self.clone();
+        Box::new(move || {
+            processor.send_error_response(
+                peer_id,
+                RPCResponseErrorCode::ResourceUnavailable,
+                "Node is overloaded".to_string(),
+                id,
+            )
+        })
+    }
+
     /// Create a new work event to process `LightClientBootstrap`s from the RPC network.
     pub fn send_light_client_bootstrap_request(
         self: &Arc<Self>,
@@ -615,7 +649,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
-            work: Work::LightClientBootstrapRequest(Box::new(process_fn)),
+            work: Work::LightClientBootstrapRequest {
+                process_fn: Box::new(process_fn),
+                reject_fn: self.reject_shed_request_fn(peer_id, request_id),
+            },
         })
     }
 
@@ -631,7 +668,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
-            work: Work::LightClientOptimisticUpdateRequest(Box::new(process_fn)),
+            work: Work::LightClientOptimisticUpdateRequest {
+                process_fn: Box::new(process_fn),
+                reject_fn: self.reject_shed_request_fn(peer_id, request_id),
+            },
         })
     }
 
@@ -646,7 +686,43 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
-            work: Work::LightClientFinalityUpdateRequest(Box::new(process_fn)),
+            work: Work::LightClientFinalityUpdateRequest {
+                process_fn: Box::new(process_fn),
+                reject_fn: self.reject_shed_request_fn(peer_id, request_id),
+            },
+        })
+    }
+
+    /// Create a new work event to process a `DepositSnapshot` request from the RPC network.
+    pub fn send_deposit_snapshot_request(
+        self: &Arc<Self>,
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+    ) -> Result<(), Error<T::EthSpec>> {
+        let processor = self.clone();
+        let process_fn = move || processor.handle_deposit_snapshot_request(peer_id, request_id);
+
+        self.try_send(BeaconWorkEvent {
+            drop_during_sync: true,
+            work: Work::DepositSnapshotRequest {
+                process_fn: Box::new(process_fn),
+                reject_fn: self.reject_shed_request_fn(peer_id, request_id),
+            },
+        })
+    }
+
+    /// Create a new work event to import a `DepositTreeSnapshot` received from the RPC network.
+    pub fn send_rpc_deposit_snapshot(
+        self: &Arc<Self>,
+        peer_id: PeerId,
+        snapshot: Arc<DepositTreeSnapshot>,
+    ) -> Result<(), Error<T::EthSpec>> {
+        let processor = self.clone();
+        let process_fn = move || processor.process_rpc_deposit_snapshot(peer_id, snapshot);
+
+        self.try_send(BeaconWorkEvent {
+            drop_during_sync: false,
+            work: Work::RpcDepositSnapshot(Box::new(process_fn)),
         })
     }
 
@@ -2,18 +2,21 @@ use crate::network_beacon_processor::{NetworkBeaconProcessor, FUTURE_SLOT_TOLERA
 use crate::service::NetworkMessage;
 use crate::status::ToStatusMessage;
 use crate::sync::SyncMessage;
-use beacon_chain::{BeaconChainError, BeaconChainTypes, HistoricalBlockError, WhenSlotSkipped};
+use beacon_chain::{
+    BeaconChainError, BeaconChainTypes, DepositSnapshotImport, HistoricalBlockError,
+    WhenSlotSkipped,
+};
 use itertools::process_results;
 use lighthouse_network::rpc::methods::{BlobsByRangeRequest, BlobsByRootRequest};
 use lighthouse_network::rpc::*;
-use lighthouse_network::{PeerId, PeerRequestId, ReportSource, Response, SyncInfo};
+use lighthouse_network::{PeerAction, PeerId, PeerRequestId, ReportSource, Response, SyncInfo};
 use slog::{debug, error, warn};
 use slot_clock::SlotClock;
 use std::collections::{hash_map::Entry, HashMap};
 use std::sync::Arc;
 use tokio_stream::StreamExt;
 use types::blob_sidecar::BlobIdentifier;
-use types::{Epoch, EthSpec, ForkName, Hash256, Slot};
+use types::{DepositTreeSnapshot, Epoch, EthSpec, ForkName, Hash256, Slot};
 
 impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     /* Auxiliary functions */
@@ -394,6 +397,85 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         );
     }
 
+    /// Handle a `DepositSnapshot` request from the peer.
+    pub fn handle_deposit_snapshot_request(
+        self: &Arc<Self>,
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+    ) {
+        self.terminate_response_single_item(
+            peer_id,
+            request_id,
+            match self
+                .chain
+                .eth1_chain
+                .as_ref()
+                .and_then(|eth1_chain| eth1_chain.deposit_snapshot())
+            {
+                Some(snapshot) => Ok(Arc::new(snapshot)),
+                None => Err((
+                    RPCResponseErrorCode::ResourceUnavailable,
+                    "Deposit snapshot not available",
+                )),
+            },
+            Response::DepositSnapshot,
+        );
+    }
+
+    /// Import a `DepositTreeSnapshot` received from a peer into the eth1 cache.
+    pub fn process_rpc_deposit_snapshot(
+        self: &Arc<Self>,
+        peer_id: PeerId,
+        snapshot: Arc<DepositTreeSnapshot>,
+    ) {
+        match self.chain.import_deposit_snapshot(&snapshot) {
+            Ok(DepositSnapshotImport::Imported) => {
+                debug!(
+                    self.log,
+                    "Imported deposit snapshot from peer";
+                    "peer" => %peer_id,
+                    "deposit_count" => snapshot.deposit_count,
+                );
+            }
+            Ok(DepositSnapshotImport::NotRequired) => {
+                debug!(
+                    self.log,
+                    "Ignoring unrequired deposit snapshot";
+                    "peer" => %peer_id,
+                );
+            }
+            Ok(DepositSnapshotImport::Mismatch) => {
+                debug!(
+                    self.log,
+                    "Deposit snapshot does not match finalized eth1 data";
+                    "peer" => %peer_id,
+                    "deposit_count" => snapshot.deposit_count,
+                );
+            }
+            Ok(DepositSnapshotImport::Invalid) => {
+                debug!(
+                    self.log,
+                    "Invalid deposit snapshot from peer";
+                    "peer" => %peer_id,
+                );
+                self.send_network_message(NetworkMessage::ReportPeer {
+                    peer_id,
+                    action: PeerAction::LowToleranceError,
+                    source: ReportSource::Processor,
+                    msg: "invalid_deposit_snapshot",
+                });
+            }
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Error importing deposit snapshot";
+                    "peer" => %peer_id,
+                    "error" => ?e,
+                );
+            }
+        }
+    }
+
     /// Handle a `BlocksByRange` request from the peer.
     pub async fn handle_blocks_by_range_request(
         self: Arc<Self>,
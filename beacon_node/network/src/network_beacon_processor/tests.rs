@@ -15,18 +15,19 @@ use beacon_chain::test_utils::{
 use beacon_chain::{BeaconChain, WhenSlotSkipped};
 use beacon_processor::{work_reprocessing_queue::*, *};
 use lighthouse_network::discovery::ConnectionId;
-use lighthouse_network::rpc::methods::BlobsByRangeRequest;
-use lighthouse_network::rpc::SubstreamId;
+use lighthouse_network::rpc::methods::{BlobsByRangeRequest, BlocksByRootRequest};
+use lighthouse_network::rpc::{RPCResponseErrorCode, SubstreamId};
 use lighthouse_network::{
     discv5::enr::{self, CombinedKey},
     rpc::methods::{MetaData, MetaDataV2},
     types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield},
-    Client, MessageId, NetworkGlobals, PeerId, Response,
+    Client, MessageAcceptance, MessageId, NetworkGlobals, PeerId, Response,
 };
 use slot_clock::SlotClock;
 use std::iter::Iterator;
 use std::sync::Arc;
 use std::time::Duration;
+use store::StoreOp;
 use tokio::sync::mpsc;
 use types::blob_sidecar::FixedBlobSidecarList;
 use types::{
@@ -61,8 +62,8 @@ struct TestRig {
     voluntary_exit: SignedVoluntaryExit,
     beacon_processor_tx: BeaconProcessorSend<E>,
     work_journal_rx: mpsc::Receiver<&'static str>,
-    _network_rx: mpsc::UnboundedReceiver<NetworkMessage<E>>,
-    _sync_rx: mpsc::UnboundedReceiver<SyncMessage<E>>,
+    network_rx: mpsc::UnboundedReceiver<NetworkMessage<E>>,
+    sync_rx: mpsc::UnboundedReceiver<SyncMessage<E>>,
     duplicate_cache: DuplicateCache,
     network_beacon_processor: Arc<NetworkBeaconProcessor<T>>,
     _harness: BeaconChainHarness<T>,
@@ -88,6 +89,17 @@ impl TestRig {
     }
 
     pub async fn new_parametric(chain_length: u64, enable_backfill_rate_limiting: bool) -> Self {
+        let beacon_processor_config = BeaconProcessorConfig {
+            enable_backfill_rate_limiting,
+            ..Default::default()
+        };
+        Self::new_with_config(chain_length, beacon_processor_config).await
+    }
+
+    pub async fn new_with_config(
+        chain_length: u64,
+        beacon_processor_config: BeaconProcessorConfig,
+    ) -> Self {
         // This allows for testing voluntary exits without building out a massive chain.
         let mut spec = test_spec::<E>();
         spec.shard_committee_period = 2;
@@ -179,14 +191,10 @@ impl TestRig {
 
         let chain = harness.chain.clone();
 
-        let (network_tx, _network_rx) = mpsc::unbounded_channel();
+        let (network_tx, network_rx) = mpsc::unbounded_channel();
 
         let log = harness.logger().clone();
 
-        let beacon_processor_config = BeaconProcessorConfig {
-            enable_backfill_rate_limiting,
-            ..Default::default()
-        };
         let BeaconProcessorChannels {
             beacon_processor_tx,
             beacon_processor_rx,
@@ -194,7 +202,7 @@ impl TestRig {
             work_reprocessing_rx,
         } = BeaconProcessorChannels::new(&beacon_processor_config);
 
-        let (sync_tx, _sync_rx) = mpsc::unbounded_channel();
+        let (sync_tx, sync_rx) = mpsc::unbounded_channel();
 
         // Default metadata
         let meta_data = MetaData::V2(MetaDataV2 {
@@ -265,8 +273,8 @@ impl TestRig {
             voluntary_exit,
             beacon_processor_tx,
             work_journal_rx,
-            _network_rx,
-            _sync_rx,
+            network_rx,
+            sync_rx,
             duplicate_cache,
             network_beacon_processor,
             _harness: harness,
@@ -359,6 +367,16 @@ impl TestRig {
             .unwrap();
     }
 
+    pub fn enqueue_blocks_by_root_request(&self, block_roots: Vec<Hash256>) {
+        self.network_beacon_processor
+            .send_blocks_by_roots_request(
+                PeerId::random(),
+                (ConnectionId::new_unchecked(42), SubstreamId::new(24)),
+                BlocksByRootRequest::new(block_roots, &self.chain.spec),
+            )
+            .unwrap();
+    }
+
     pub fn enqueue_backfill_batch(&self) {
         self.network_beacon_processor
             .send_chain_segment(
@@ -368,6 +386,27 @@ impl TestRig {
             .unwrap();
     }
 
+    pub fn enqueue_light_client_optimistic_update_request(&self) {
+        self.network_beacon_processor
+            .send_light_client_optimistic_update_request(
+                PeerId::random(),
+                (ConnectionId::new_unchecked(42), SubstreamId::new(24)),
+            )
+            .unwrap();
+    }
+
+    /// Occupies a worker for `duration`.
+    pub fn enqueue_busy_work(&self, duration: Duration) {
+        self.beacon_processor_tx
+            .try_send(WorkEvent {
+                drop_during_sync: false,
+                work: Work::ApiRequestP0(BlockingOrAsync::Blocking(Box::new(move || {
+                    std::thread::sleep(duration)
+                }))),
+            })
+            .unwrap();
+    }
+
     pub fn enqueue_unaggregated_attestation(&self) {
         let (attestation, subnet_id) = self.attestations.first().unwrap().clone();
         self.network_beacon_processor
@@ -1077,6 +1116,165 @@ async fn test_backfill_sync_processing_rate_limiting_disabled() {
     .await;
 }
 
+/// Ensure that backfill batches are never shed, so that sync always receives a result, and that
+/// shed RPC requests receive an error response.
+#[tokio::test]
+async fn test_load_shedding() {
+    let beacon_processor_config = BeaconProcessorConfig {
+        max_workers: 1,
+        enable_backfill_rate_limiting: false,
+        load_shedding_threshold: Some(0),
+        ..Default::default()
+    };
+    let mut rig = TestRig::new_with_config(SMALL_CHAIN, beacon_processor_config).await;
+
+    // Occupy the only worker, so that new work is shed if it can be.
+    rig.enqueue_busy_work(Duration::from_millis(500));
+    rig.assert_event_journal(&[API_REQUEST_P0]).await;
+
+    rig.enqueue_light_client_optimistic_update_request();
+    rig.enqueue_backfill_batch();
+
+    match tokio::time::timeout(STANDARD_TIMEOUT, rig.network_rx.recv()).await {
+        Ok(Some(NetworkMessage::SendErrorResponse { error, .. })) => {
+            assert_eq!(error, RPCResponseErrorCode::ResourceUnavailable)
+        }
+        other => panic!("expected an error response, got {:?}", other),
+    }
+
+    // The backfill batch is processed once the worker is free.
+    match tokio::time::timeout(STANDARD_TIMEOUT, rig.sync_rx.recv()).await {
+        Ok(Some(SyncMessage::BatchProcessed { .. })) => {}
+        other => panic!("expected a batch result, got {:?}", other),
+    }
+}
+
+/// Gossip work which is persistently over the latency budget is started on an overflow worker,
+/// rather than waiting for the usual workers to become free.
+#[tokio::test]
+async fn gossip_over_latency_budget_uses_overflow_workers() {
+    let beacon_processor_config = BeaconProcessorConfig {
+        max_workers: 1,
+        gossip_latency_budget: Some(Duration::ZERO),
+        gossip_overflow_workers: 1,
+        ..Default::default()
+    };
+    let mut rig = TestRig::new_with_config(SMALL_CHAIN, beacon_processor_config).await;
+
+    // Process enough slashings for their average processing time to be over budget.
+    for _ in 0..32 {
+        rig.enqueue_gossip_attester_slashing();
+        rig.assert_event_journal(&[GOSSIP_ATTESTER_SLASHING, WORKER_FREED, NOTHING_TO_DO])
+            .await;
+    }
+
+    // Occupy the only usual worker.
+    rig.enqueue_busy_work(Duration::from_secs(2));
+    rig.assert_event_journal(&[API_REQUEST_P0]).await;
+
+    // The slashing is processed on an overflow worker before the usual worker is free.
+    rig.enqueue_gossip_attester_slashing();
+    rig.assert_event_journal_with_timeout(
+        &[GOSSIP_ATTESTER_SLASHING, WORKER_FREED],
+        Duration::from_secs(1),
+    )
+    .await;
+
+    // Work which is within budget still waits for the usual worker.
+    rig.enqueue_gossip_voluntary_exit();
+    rig.assert_event_journal(&[
+        GOSSIP_VOLUNTARY_EXIT,
+        WORKER_FREED,
+        WORKER_FREED,
+        NOTHING_TO_DO,
+    ])
+    .await;
+}
+
+/// A blob sidecar which conflicts with one already seen for the same slot, proposer and index is
+/// ignored, and the peer which forwarded it is not penalized.
+#[tokio::test]
+async fn gossip_blob_equivocation_is_ignored_without_penalty() {
+    if test_spec::<E>().deneb_fork_epoch.is_none() {
+        return;
+    };
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+    let head = rig.chain.head_snapshot();
+    let slot = rig.chain.slot().unwrap();
+
+    // The mock execution layer includes a random number of blobs, so keep producing blocks until
+    // one has some.
+    let ((block, blobs), state) = loop {
+        let (contents, state) = rig
+            ._harness
+            .make_block(head.beacon_state.clone(), slot)
+            .await;
+        if matches!(&contents.1, Some((_, blobs)) if !blobs.is_empty()) {
+            break (contents, state);
+        }
+    };
+    let (kzg_proofs, blobs) = blobs.unwrap();
+
+    // Sign a second block with the same blobs so that its sidecars conflict with the first.
+    let (mut conflicting_block, _) = (*block).clone().deconstruct();
+    *conflicting_block.state_root_mut() = Hash256::repeat_byte(0xff);
+    let proposer_index = conflicting_block.proposer_index() as usize;
+    let conflicting_block = conflicting_block.sign(
+        &rig._harness.validator_keypairs[proposer_index].sk,
+        &state.fork(),
+        state.genesis_validators_root(),
+        &rig.chain.spec,
+    );
+
+    let sidecars = BlobSidecar::build_sidecars(blobs.clone(), &block, kzg_proofs.clone()).unwrap();
+    let conflicting_sidecars =
+        BlobSidecar::build_sidecars(blobs, &conflicting_block, kzg_proofs).unwrap();
+
+    let first_peer = PeerId::random();
+    let second_peer = PeerId::random();
+    for (peer_id, sidecar) in [
+        (first_peer, sidecars[0].clone()),
+        (second_peer, conflicting_sidecars[0].clone()),
+    ] {
+        rig.network_beacon_processor
+            .send_gossip_blob_sidecar(
+                junk_message_id(),
+                peer_id,
+                Client::default(),
+                sidecar.index,
+                sidecar,
+                Duration::from_secs(0),
+            )
+            .unwrap();
+        rig.assert_event_journal(&[GOSSIP_BLOBS_SIDECAR, WORKER_FREED, NOTHING_TO_DO])
+            .await;
+    }
+
+    let mut results = vec![];
+    while let Ok(message) = rig.network_rx.try_recv() {
+        match message {
+            NetworkMessage::ValidationResult {
+                propagation_source,
+                validation_result,
+                ..
+            } => results.push((propagation_source, validation_result)),
+            NetworkMessage::ReportPeer { peer_id, .. } => {
+                panic!("peer {peer_id} was penalized")
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(results.len(), 2, "{results:?}");
+    assert!(
+        matches!(results[0], (peer, MessageAcceptance::Accept) if peer == first_peer),
+        "{results:?}"
+    );
+    assert!(
+        matches!(results[1], (peer, MessageAcceptance::Ignore) if peer == second_peer),
+        "{results:?}"
+    );
+}
+
 #[tokio::test]
 async fn test_blobs_by_range() {
     if test_spec::<E>().deneb_fork_epoch.is_none() {
@@ -1097,7 +1295,7 @@ async fn test_blobs_by_range() {
             .unwrap_or(0);
     }
     let mut actual_count = 0;
-    while let Some(next) = rig._network_rx.recv().await {
+    while let Some(next) = rig.network_rx.recv().await {
         if let NetworkMessage::SendResponse {
             peer_id: _,
             response: Response::BlobsByRange(blob),
@@ -1115,3 +1313,54 @@ async fn test_blobs_by_range() {
     }
     assert_eq!(blob_count, actual_count);
 }
+
+#[tokio::test]
+async fn test_blocks_by_root_serves_stripped_block() {
+    if test_spec::<E>().bellatrix_fork_epoch.is_none() {
+        return;
+    };
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+
+    let mut block_roots = vec![];
+    let mut expected_blocks = vec![];
+    for slot in 1..=SMALL_CHAIN {
+        let root = rig
+            .chain
+            .block_root_at_slot(Slot::new(slot), WhenSlotSkipped::None)
+            .unwrap()
+            .unwrap();
+        let block = rig.chain.get_block(&root).await.unwrap().unwrap();
+        block_roots.push(root);
+        expected_blocks.push(Arc::new(block));
+    }
+
+    // Strip the payload and blobs of the first block, as they are for the retained blocks of
+    // abandoned forks.
+    rig.chain
+        .store
+        .do_atomically_with_block_and_blobs_cache(vec![
+            StoreOp::DeleteExecutionPayload(block_roots[0]),
+            StoreOp::DeleteBlobs(block_roots[0]),
+        ])
+        .unwrap();
+
+    rig.enqueue_blocks_by_root_request(block_roots);
+
+    let mut actual_blocks = vec![];
+    while let Some(next) = rig.network_rx.recv().await {
+        if let NetworkMessage::SendResponse {
+            peer_id: _,
+            response: Response::BlocksByRoot(block),
+            id: _,
+        } = next
+        {
+            match block {
+                Some(block) => actual_blocks.push(block),
+                None => break,
+            }
+        } else {
+            panic!("unexpected message {:?}", next);
+        }
+    }
+    assert_eq!(actual_blocks, expected_blocks);
+}
@@ -24,10 +24,16 @@ use logging::TimeLatch;
 use slog::{crit, debug, o, trace};
 use slog::{error, warn};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
-use types::{BlobSidecar, EthSpec, SignedBeaconBlock};
+use types::{BlobSidecar, DepositTreeSnapshot, EthSpec, SignedBeaconBlock};
+
+#[cfg(test)]
+mod tests;
+
+/// The minimum time between two deposit snapshot requests sent to peers.
+const DEPOSIT_SNAPSHOT_REQUEST_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Handles messages from the network and routes them to the appropriate service to be handled.
 pub struct Router<T: BeaconChainTypes> {
@@ -45,6 +51,10 @@ pub struct Router<T: BeaconChainTypes> {
     log: slog::Logger,
     /// Provides de-bounce functionality for logging.
     logger_debounce: TimeLatch,
+    /// Whether deposit snapshots may be requested from peers.
+    enable_deposit_snapshot_rpc: bool,
+    /// The last time a deposit snapshot was requested from a peer.
+    deposit_snapshot_requested_at: Option<Instant>,
 }
 
 /// Types of messages the router can receive.
@@ -87,6 +97,7 @@ impl<T: BeaconChainTypes> Router<T> {
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
         executor: task_executor::TaskExecutor,
         invalid_block_storage: InvalidBlockStorage,
+        enable_deposit_snapshot_rpc: bool,
         beacon_processor_send: BeaconProcessorSend<T::EthSpec>,
         beacon_processor_reprocess_tx: mpsc::Sender<ReprocessQueueMessage>,
         log: slog::Logger,
@@ -133,6 +144,8 @@ impl<T: BeaconChainTypes> Router<T> {
             network_beacon_processor,
             log: message_handler_log,
             logger_debounce: TimeLatch::default(),
+            enable_deposit_snapshot_rpc,
+            deposit_snapshot_requested_at: None,
         };
 
         // spawn handler task and move the message handler instance into the spawned thread
@@ -228,6 +241,10 @@ impl<T: BeaconChainTypes> Router<T> {
                 self.network_beacon_processor
                     .send_light_client_finality_update_request(peer_id, request_id),
             ),
+            Request::DepositSnapshot => self.handle_beacon_processor_send_result(
+                self.network_beacon_processor
+                    .send_deposit_snapshot_request(peer_id, request_id),
+            ),
         }
     }
 
@@ -241,6 +258,7 @@ impl<T: BeaconChainTypes> Router<T> {
         match response {
             Response::Status(status_message) => {
                 debug!(self.log, "Received Status Response"; "peer_id" => %peer_id, &status_message);
+                self.maybe_request_deposit_snapshot(peer_id, &status_message);
                 self.handle_beacon_processor_send_result(
                     self.network_beacon_processor
                         .send_status_message(peer_id, status_message),
@@ -258,6 +276,9 @@ impl<T: BeaconChainTypes> Router<T> {
             Response::BlobsByRoot(blob) => {
                 self.on_blobs_by_root_response(peer_id, request_id, blob);
             }
+            Response::DepositSnapshot(snapshot) => {
+                self.on_deposit_snapshot_response(peer_id, snapshot);
+            }
             // Light client responses should not be received
             Response::LightClientBootstrap(_)
             | Response::LightClientOptimisticUpdate(_)
@@ -491,12 +512,62 @@ impl<T: BeaconChainTypes> Router<T> {
             request_id,
         );
 
+        self.maybe_request_deposit_snapshot(peer_id, &status);
         self.handle_beacon_processor_send_result(
             self.network_beacon_processor
                 .send_status_message(peer_id, status),
         )
     }
 
+    /// Requests a deposit snapshot from `peer_id` if our eth1 cache lacks deposits that are
+    /// already included in the finalized chain and the peer shares our finalized checkpoint.
+    ///
+    /// This is typically the case after checkpoint sync from a server that did not provide a
+    /// deposit snapshot. Requests are sent to a single peer at a time, and only if the deposit
+    /// snapshot protocol is enabled.
+    fn maybe_request_deposit_snapshot(&mut self, peer_id: PeerId, status: &StatusMessage) {
+        if !self.enable_deposit_snapshot_rpc
+            || self
+                .deposit_snapshot_requested_at
+                .map_or(false, |requested_at| {
+                    requested_at.elapsed() < DEPOSIT_SNAPSHOT_REQUEST_INTERVAL
+                })
+        {
+            return;
+        }
+
+        let local = status_message(&self.chain);
+        if status.finalized_epoch != local.finalized_epoch
+            || status.finalized_root != local.finalized_root
+            || !self.chain.wants_deposit_snapshot()
+        {
+            return;
+        }
+
+        debug!(self.log, "Requesting deposit snapshot"; "peer_id" => %peer_id);
+        self.deposit_snapshot_requested_at = Some(Instant::now());
+        self.network
+            .send_processor_request(peer_id, Request::DepositSnapshot);
+    }
+
+    /// Handle a `DepositSnapshot` response from the peer.
+    pub fn on_deposit_snapshot_response(
+        &mut self,
+        peer_id: PeerId,
+        snapshot: Arc<DepositTreeSnapshot>,
+    ) {
+        debug!(
+            self.log,
+            "Received DepositSnapshot Response";
+            "peer" => %peer_id,
+            "deposit_count" => snapshot.deposit_count,
+        );
+        self.handle_beacon_processor_send_result(
+            self.network_beacon_processor
+                .send_rpc_deposit_snapshot(peer_id, snapshot),
+        )
+    }
+
     /// Handle a `BlocksByRange` response from the peer.
     /// A `beacon_block` behaves as a stream which is terminated on a `None` response.
     pub fn on_blocks_by_range_response(
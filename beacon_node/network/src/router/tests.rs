@@ -0,0 +1,231 @@
+#![cfg(not(debug_assertions))]
+
+use super::*;
+use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
+use beacon_processor::{BeaconProcessorChannels, Work, WorkEvent};
+use lighthouse_network::discv5::enr::{self, CombinedKey};
+use lighthouse_network::rpc::methods::{MetaData, MetaDataV2};
+use lighthouse_network::types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield};
+use lighthouse_network::PeerAction;
+use types::{Hash256, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+type T = EphemeralHarnessType<E>;
+
+const VALIDATOR_COUNT: usize = 16;
+
+struct TestRouter {
+    router: Router<T>,
+    harness: BeaconChainHarness<T>,
+    network_rx: mpsc::UnboundedReceiver<NetworkMessage<E>>,
+    beacon_processor_rx: mpsc::Receiver<WorkEvent<E>>,
+    _sync_rx: mpsc::UnboundedReceiver<SyncMessage<E>>,
+}
+
+impl TestRouter {
+    /// Returns a router for a chain whose deposit cache is empty, as it is after checkpoint sync
+    /// from a node that didn't serve a deposit snapshot.
+    fn new(enable_deposit_snapshot_rpc: bool) -> Self {
+        let harness = BeaconChainHarness::builder(E::default())
+            .default_spec()
+            .deterministic_keypairs(VALIDATOR_COUNT)
+            .fresh_ephemeral_store()
+            .caching_eth1_backend()
+            .build();
+        let log = harness.logger().clone();
+
+        let (network_send, network_rx) = mpsc::unbounded_channel();
+        let (sync_send, sync_rx) = mpsc::unbounded_channel();
+        let BeaconProcessorChannels {
+            beacon_processor_tx,
+            beacon_processor_rx,
+            work_reprocessing_tx,
+            ..
+        } = BeaconProcessorChannels::default();
+
+        let meta_data = MetaData::V2(MetaDataV2 {
+            seq_number: 0,
+            attnets: EnrAttestationBitfield::<E>::default(),
+            syncnets: EnrSyncCommitteeBitfield::<E>::default(),
+        });
+        let enr_key = CombinedKey::generate_secp256k1();
+        let enr = enr::Enr::builder().build(&enr_key).unwrap();
+        let network_globals = Arc::new(NetworkGlobals::new(enr, meta_data, vec![], false, &log));
+
+        let network_beacon_processor = Arc::new(NetworkBeaconProcessor {
+            beacon_processor_send: beacon_processor_tx,
+            duplicate_cache: DuplicateCache::default(),
+            chain: harness.chain.clone(),
+            network_tx: network_send.clone(),
+            sync_tx: sync_send.clone(),
+            reprocess_tx: work_reprocessing_tx,
+            network_globals: network_globals.clone(),
+            invalid_block_storage: InvalidBlockStorage::Disabled,
+            executor: harness.runtime.task_executor.clone(),
+            log: log.clone(),
+        });
+
+        let router = Router {
+            network_globals,
+            chain: harness.chain.clone(),
+            sync_send,
+            network: HandlerNetworkContext::new(network_send, log.clone()),
+            network_beacon_processor,
+            log,
+            logger_debounce: TimeLatch::default(),
+            enable_deposit_snapshot_rpc,
+            deposit_snapshot_requested_at: None,
+        };
+
+        Self {
+            router,
+            harness,
+            network_rx,
+            beacon_processor_rx,
+            _sync_rx: sync_rx,
+        }
+    }
+
+    /// Returns a status message from a peer which shares our finalized checkpoint.
+    fn peer_status(&self) -> StatusMessage {
+        status_message(&self.harness.chain)
+    }
+
+    /// Returns `true` if a deposit snapshot was requested from `peer_id`.
+    fn requested_deposit_snapshot(&mut self, peer_id: PeerId) -> bool {
+        match self.network_rx.try_recv() {
+            Ok(NetworkMessage::SendRequest {
+                peer_id: request_peer_id,
+                request: Request::DepositSnapshot,
+                ..
+            }) => {
+                assert_eq!(request_peer_id, peer_id);
+                true
+            }
+            Ok(other) => panic!("unexpected network message {:?}", other),
+            Err(_) => false,
+        }
+    }
+
+    /// Runs the next work event sent to the beacon processor, which must import a deposit
+    /// snapshot.
+    fn process_rpc_deposit_snapshot(&mut self) {
+        match self.beacon_processor_rx.try_recv() {
+            Ok(WorkEvent {
+                work: Work::RpcDepositSnapshot(process_fn),
+                ..
+            }) => process_fn(),
+            Ok(other) => panic!("unexpected work event {}", other.work_type()),
+            Err(e) => panic!("no work event: {:?}", e),
+        }
+    }
+
+    fn deposit_count(&self) -> u64 {
+        self.harness
+            .chain
+            .eth1_chain
+            .as_ref()
+            .unwrap()
+            .deposit_count()
+    }
+}
+
+#[test]
+fn maybe_request_deposit_snapshot() {
+    let mut rig = TestRouter::new(true);
+    let peer_id = PeerId::random();
+    let status = rig.peer_status();
+
+    rig.router.maybe_request_deposit_snapshot(peer_id, &status);
+    assert!(rig.requested_deposit_snapshot(peer_id));
+
+    // Requests are rate limited across all peers.
+    rig.router
+        .maybe_request_deposit_snapshot(PeerId::random(), &status);
+    assert!(!rig.requested_deposit_snapshot(peer_id));
+
+    rig.router.deposit_snapshot_requested_at = None;
+    rig.router.maybe_request_deposit_snapshot(peer_id, &status);
+    assert!(rig.requested_deposit_snapshot(peer_id));
+}
+
+#[test]
+fn maybe_request_deposit_snapshot_disabled() {
+    let mut rig = TestRouter::new(false);
+    let peer_id = PeerId::random();
+    let status = rig.peer_status();
+
+    rig.router.maybe_request_deposit_snapshot(peer_id, &status);
+    assert!(!rig.requested_deposit_snapshot(peer_id));
+}
+
+#[test]
+fn maybe_request_deposit_snapshot_from_peer_on_other_chain() {
+    let mut rig = TestRouter::new(true);
+    let peer_id = PeerId::random();
+    let status = StatusMessage {
+        finalized_root: Hash256::repeat_byte(1),
+        ..rig.peer_status()
+    };
+
+    rig.router.maybe_request_deposit_snapshot(peer_id, &status);
+    assert!(!rig.requested_deposit_snapshot(peer_id));
+}
+
+#[test]
+fn on_deposit_snapshot_response() {
+    let mut rig = TestRouter::new(true);
+    let peer_id = PeerId::random();
+    let snapshot = rig.harness.genesis_deposit_snapshot(VALIDATOR_COUNT);
+
+    rig.router
+        .on_deposit_snapshot_response(peer_id, Arc::new(snapshot));
+    rig.process_rpc_deposit_snapshot();
+    assert_eq!(rig.deposit_count(), VALIDATOR_COUNT as u64);
+
+    // The deposit cache is full, so no further snapshots are requested.
+    let status = rig.peer_status();
+    rig.router.maybe_request_deposit_snapshot(peer_id, &status);
+    assert!(!rig.requested_deposit_snapshot(peer_id));
+}
+
+#[test]
+fn on_invalid_deposit_snapshot_response() {
+    let mut rig = TestRouter::new(true);
+    let peer_id = PeerId::random();
+    let snapshot = DepositTreeSnapshot {
+        deposit_root: Hash256::repeat_byte(1),
+        ..rig.harness.genesis_deposit_snapshot(VALIDATOR_COUNT)
+    };
+
+    rig.router
+        .on_deposit_snapshot_response(peer_id, Arc::new(snapshot));
+    rig.process_rpc_deposit_snapshot();
+    assert_eq!(rig.deposit_count(), 0);
+
+    match rig.network_rx.try_recv() {
+        Ok(NetworkMessage::ReportPeer {
+            peer_id: reported_peer_id,
+            action: PeerAction::LowToleranceError,
+            ..
+        }) => assert_eq!(reported_peer_id, peer_id),
+        other => panic!("expected peer report, got {:?}", other),
+    }
+}
+
+#[test]
+fn on_mismatched_deposit_snapshot_response() {
+    let mut rig = TestRouter::new(true);
+    let peer_id = PeerId::random();
+
+    // A valid snapshot which doesn't finalize all of the deposits in the finalized state.
+    let snapshot = rig.harness.genesis_deposit_snapshot(VALIDATOR_COUNT - 1);
+
+    rig.router
+        .on_deposit_snapshot_response(peer_id, Arc::new(snapshot));
+    rig.process_rpc_deposit_snapshot();
+    assert_eq!(rig.deposit_count(), 0);
+
+    // The peer's deposit cache may be behind its chain, so it isn't penalized.
+    assert!(rig.network_rx.try_recv().is_err());
+}
@@ -10,6 +10,8 @@ use crate::{
 };
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use beacon_processor::{work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorSend};
+use eth2::lighthouse::{PeerEventKind, SsePeer};
+use eth2::types::EventKind;
 use futures::channel::mpsc::Sender;
 use futures::future::OptionFuture;
 use futures::prelude::*;
@@ -101,6 +103,12 @@ pub enum NetworkMessage<E: EthSpec> {
         reason: GoodbyeReason,
         source: ReportSource,
     },
+    /// Updates the target number of peers, optionally limiting the number of excess peers pruned
+    /// per heartbeat.
+    UpdateTargetPeers {
+        target_peers: usize,
+        max_pruned_peers_per_heartbeat: Option<usize>,
+    },
 }
 
 /// Messages triggered by validators that may trigger a subscription to a subnet.
@@ -307,6 +315,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             network_senders.network_send(),
             executor.clone(),
             invalid_block_storage,
+            config.enable_deposit_snapshot_rpc,
             beacon_processor_send,
             beacon_processor_reprocess_tx,
             network_log.clone(),
@@ -429,6 +438,41 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         }
     }
 
+    /// Publish a change in a peer's connection state on the `peer` event topic, if there are any
+    /// subscribers.
+    fn register_peer_event(&self, peer_id: &PeerId, kind: PeerEventKind) {
+        let Some(event_handler) = self
+            .beacon_chain
+            .event_handler
+            .as_ref()
+            .filter(|event_handler| event_handler.has_peer_subscribers())
+        else {
+            return;
+        };
+
+        let peers = self.network_globals.peers.read();
+        let Some(peer_info) = peers.peer_info(peer_id) else {
+            return;
+        };
+        let goodbye = peer_info.goodbye();
+        let event = SsePeer {
+            peer_id: peer_id.to_string(),
+            kind,
+            direction: peer_info
+                .connection_direction()
+                .map(|direction| direction.as_ref().to_string()),
+            reason_code: goodbye.map(|goodbye| goodbye.reason.clone().into()),
+            reason: goodbye.map(|goodbye| goodbye.reason.to_string()),
+            goodbye_sent: goodbye.map(|goodbye| goodbye.sent),
+            client: peer_info.client().to_string(),
+            score: peer_info.score().score(),
+            enr: peer_info.enr().map(|enr| enr.to_base64()),
+        };
+        drop(peers);
+
+        event_handler.register(EventKind::Peer(event));
+    }
+
     fn spawn_service(mut self, executor: task_executor::TaskExecutor) {
         let mut shutdown_sender = executor.shutdown_sender();
 
@@ -497,14 +541,19 @@ impl<T: BeaconChainTypes> NetworkService<T> {
     ) {
         match ev {
             NetworkEvent::PeerConnectedOutgoing(peer_id) => {
+                self.register_peer_event(&peer_id, PeerEventKind::Connected);
                 self.send_to_router(RouterMessage::StatusPeer(peer_id));
             }
-            NetworkEvent::PeerConnectedIncoming(_) => {
-                // No action required for this event.
+            NetworkEvent::PeerConnectedIncoming(peer_id) => {
+                self.register_peer_event(&peer_id, PeerEventKind::Connected);
             }
             NetworkEvent::PeerDisconnected(peer_id) => {
+                self.register_peer_event(&peer_id, PeerEventKind::Disconnected);
                 self.send_to_router(RouterMessage::PeerDisconnected(peer_id));
             }
+            NetworkEvent::PeerBanned(peer_id) => {
+                self.register_peer_event(&peer_id, PeerEventKind::Banned);
+            }
             NetworkEvent::RequestReceived {
                 peer_id,
                 id,
@@ -673,6 +722,13 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 reason,
                 source,
             } => self.libp2p.goodbye_peer(&peer_id, reason, source),
+            NetworkMessage::UpdateTargetPeers {
+                target_peers,
+                max_pruned_peers_per_heartbeat,
+            } => self
+                .libp2p
+                .peer_manager_mut()
+                .set_target_peers(target_peers, max_pruned_peers_per_heartbeat),
             NetworkMessage::SubscribeCoreTopics => {
                 if self.subscribed_core_topics() {
                     return;
@@ -6,9 +6,11 @@ mod tests {
     use beacon_chain::test_utils::BeaconChainHarness;
     use beacon_chain::BeaconChainTypes;
     use beacon_processor::{BeaconProcessorChannels, BeaconProcessorConfig};
+    use eth2::lighthouse::PeerEventKind;
+    use eth2::types::EventKind;
     use futures::StreamExt;
     use lighthouse_network::types::{GossipEncoding, GossipKind};
-    use lighthouse_network::{Enr, GossipTopic};
+    use lighthouse_network::{Enr, GossipTopic, NetworkEvent, PeerId};
     use slog::{o, Drain, Level, Logger};
     use sloggers::{null::NullLoggerBuilder, Build};
     use std::str::FromStr;
@@ -233,4 +235,83 @@ mod tests {
             .expect("topic score params");
         assert_eq!(0.0, old_topic_params2.topic_weight);
     }
+
+    #[test]
+    fn test_peer_events() {
+        let runtime = Arc::new(Runtime::new().unwrap());
+
+        let beacon_chain = BeaconChainHarness::builder(MinimalEthSpec)
+            .default_spec()
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build()
+            .chain;
+
+        let (mut network_service, network_globals, _network_senders) = runtime.block_on(async {
+            let (_, exit) = async_channel::bounded(1);
+            let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+            let executor = task_executor::TaskExecutor::new(
+                Arc::downgrade(&runtime),
+                exit,
+                get_logger(false),
+                shutdown_tx,
+            );
+
+            let mut config = NetworkConfig::default();
+            config.set_ipv4_listening_address(std::net::Ipv4Addr::UNSPECIFIED, 21216, 21216, 21217);
+            config.upnp_enabled = false;
+
+            let beacon_processor_channels =
+                BeaconProcessorChannels::new(&BeaconProcessorConfig::default());
+            NetworkService::build(
+                beacon_chain.clone(),
+                &config,
+                executor.clone(),
+                None,
+                beacon_processor_channels.beacon_processor_tx,
+                beacon_processor_channels.work_reprocessing_tx,
+            )
+            .await
+            .unwrap()
+        });
+
+        let mut events = beacon_chain
+            .event_handler
+            .as_ref()
+            .expect("harness should have an event handler")
+            .subscribe_peer();
+
+        let peer_id = PeerId::random();
+        network_globals
+            .peers
+            .write()
+            .__add_connected_peer_testing_only(&peer_id);
+
+        runtime.block_on(async {
+            let (mut shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
+            for event in [
+                // Peers unknown to the peer DB are not reported.
+                NetworkEvent::PeerBanned(PeerId::random()),
+                NetworkEvent::PeerConnectedIncoming(peer_id),
+                NetworkEvent::PeerBanned(peer_id),
+            ] {
+                network_service
+                    .on_libp2p_event(event, &mut shutdown_tx)
+                    .await;
+            }
+        });
+
+        for kind in [PeerEventKind::Connected, PeerEventKind::Banned] {
+            let Ok(EventKind::Peer(event)) = events.try_recv() else {
+                panic!("expected a {kind:?} peer event");
+            };
+            assert_eq!(event.peer_id, peer_id.to_string());
+            assert_eq!(event.kind, kind);
+            assert_eq!(event.direction.as_deref(), Some("outgoing"));
+            assert!(event.enr.is_some());
+            assert_eq!(event.reason_code, None);
+            assert_eq!(event.goodbye_sent, None);
+        }
+        assert!(events.try_recv().is_err(), "no other events should be sent");
+    }
 }
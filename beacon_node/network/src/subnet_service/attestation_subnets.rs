@@ -77,6 +77,13 @@ pub struct AttestationService<T: BeaconChainTypes> {
     /// Short lived subscriptions that need to be executed in the future.
     scheduled_short_lived_subscriptions: HashSetDelay<ExactSubnet>,
 
+    /// Validator subscriptions that have already been processed, mapped to whether any of them
+    /// was for an aggregator. Entries expire once the duty slot has passed.
+    ///
+    /// Validator clients resend their subscriptions every slot and several of them may share a
+    /// beacon node, so this is used to ignore subscriptions that would have no further effect.
+    known_subscriptions: HashMapDelay<ExactSubnet, bool>,
+
     /// A collection timeouts to track the existence of aggregate validator subscriptions at an
     /// `ExactSubnet`.
     aggregate_validators_on_subnet: Option<HashSetDelay<ExactSubnet>>,
@@ -132,6 +139,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             short_lived_subscriptions: HashMapDelay::new(slot_duration),
             long_lived_subscriptions: HashSet::default(),
             scheduled_short_lived_subscriptions: HashSetDelay::default(),
+            known_subscriptions: HashMapDelay::new(slot_duration),
             aggregate_validators_on_subnet,
             waker: None,
             discovery_disabled: config.disable_discovery,
@@ -192,6 +200,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     ///
     /// This will:
     /// - Register new validators as being known.
+    /// - Ignore subscriptions that duplicate one already processed.
     /// - Search for peers for required subnets.
     /// - Request subscriptions for subnets on specific slots when required.
     /// - Build the timeouts for each of these events.
@@ -235,25 +244,42 @@ impl<T: BeaconChainTypes> AttestationService<T> {
                     continue;
                 }
             };
-            // Ensure each subnet_id inserted into the map has the highest slot as it's value.
-            // Higher slot corresponds to higher min_ttl in the `SubnetDiscovery` entry.
-            if let Some(slot) = subnets_to_discover.get(&subnet_id) {
-                if subscription.slot > *slot {
-                    subnets_to_discover.insert(subnet_id, subscription.slot);
-                }
-            } else if !self.discovery_disabled {
-                subnets_to_discover.insert(subnet_id, subscription.slot);
-            }
-
             let exact_subnet = ExactSubnet {
                 subnet_id,
                 slot: subscription.slot,
             };
 
+            // A subscription only has an effect if we have not yet seen this exact subnet, or if
+            // it is the first aggregator for it.
+            let known_aggregator = self.known_subscriptions.get(&exact_subnet).copied();
+            match known_aggregator {
+                Some(true) => {
+                    metrics::inc_counter(&metrics::SUBNET_SUBSCRIPTION_DUPLICATE_REQUESTS);
+                    continue;
+                }
+                Some(false) if !subscription.is_aggregator => {
+                    metrics::inc_counter(&metrics::SUBNET_SUBSCRIPTION_DUPLICATE_REQUESTS);
+                    continue;
+                }
+                _ => {}
+            }
+            // Peers for this exact subnet have already been searched for if it is known.
+            if known_aggregator.is_none() {
+                // Ensure each subnet_id inserted into the map has the highest slot as it's value.
+                // Higher slot corresponds to higher min_ttl in the `SubnetDiscovery` entry.
+                if let Some(slot) = subnets_to_discover.get(&subnet_id) {
+                    if subscription.slot > *slot {
+                        subnets_to_discover.insert(subnet_id, subscription.slot);
+                    }
+                } else if !self.discovery_disabled {
+                    subnets_to_discover.insert(subnet_id, subscription.slot);
+                }
+            }
+
             // Determine if the validator is an aggregator. If so, we subscribe to the subnet and
             // if successful add the validator to a mapping of known aggregators for that exact
             // subnet.
-
+            let mut subscribed_as_aggregator = false;
             if subscription.is_aggregator {
                 metrics::inc_counter(&metrics::SUBNET_SUBSCRIPTION_AGGREGATOR_REQUESTS);
                 if let Err(e) = self.subscribe_to_short_lived_subnet(exact_subnet) {
@@ -266,8 +292,22 @@ impl<T: BeaconChainTypes> AttestationService<T> {
                         "Subscribed to subnet for aggregator duties";
                         "exact_subnet" => ?exact_subnet,
                     );
+                    subscribed_as_aggregator = true;
                 }
             }
+
+            // Only an aggregator subscription which succeeded is remembered as such, so that a
+            // failed one is retried when the validator client resends it.
+            let time_to_duty_end = self
+                .beacon_chain
+                .slot_clock
+                .duration_to_slot(subscription.slot + 1)
+                .unwrap_or_default();
+            self.known_subscriptions.insert_at(
+                exact_subnet,
+                subscribed_as_aggregator,
+                time_to_duty_end,
+            );
         }
 
         // If the discovery mechanism isn't disabled, attempt to set up a peer discovery for the
@@ -303,16 +343,14 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     /// Gets the long lived subnets the node should be subscribed to during the current epoch and
     /// the remaining duration for which they remain valid.
     fn recompute_long_lived_subnets_inner(&mut self) -> Result<Duration, ()> {
-        let current_epoch = self.beacon_chain.epoch().map_err(|e| {
-            if !self
-                .beacon_chain
-                .slot_clock
-                .is_prior_to_genesis()
-                .unwrap_or(false)
-            {
-                error!(self.log, "Failed to get the current epoch from clock"; "err" => ?e)
-            }
-        })?;
+        // Prior to genesis, subscribe to the subnets for the genesis epoch so that the node has
+        // peers on them by the time the first attestations are published.
+        let current_epoch = self
+            .beacon_chain
+            .slot_clock
+            .now_or_genesis()
+            .map(|slot| slot.epoch(T::EthSpec::slots_per_epoch()))
+            .ok_or_else(|| error!(self.log, "Failed to get the current epoch from clock"))?;
 
         let (subnets, next_subscription_epoch) = SubnetId::compute_subnets_for_epoch::<T::EthSpec>(
             self.node_id.raw().into(),
@@ -420,7 +458,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
         let current_slot = self
             .beacon_chain
             .slot_clock
-            .now()
+            .now_or_genesis()
             .ok_or("Could not get the current slot")?;
 
         let discovery_subnets: Vec<SubnetDiscovery> = exact_subnets
@@ -580,6 +618,49 @@ impl<T: BeaconChainTypes> AttestationService<T> {
         Ok(())
     }
 
+    /// Handles the expiry of a short lived subscription.
+    ///
+    /// If an aggregator duty on the same subnet is about to begin, the subscription is extended
+    /// rather than unsubscribing now and resubscribing when the duty's subscription starts.
+    pub(crate) fn handle_expired_short_lived_subnet(&mut self, subnet_id: SubnetId) {
+        let next_end_slot = self.beacon_chain.slot_clock.now().and_then(|current_slot| {
+            // Duties within the advance subscription window would resubscribe us immediately.
+            let window_end = current_slot + 1;
+            self.known_subscriptions
+                .keys()
+                .filter(|exact_subnet| {
+                    exact_subnet.subnet_id == subnet_id
+                        && exact_subnet.slot >= current_slot
+                        && exact_subnet.slot <= window_end
+                        && self.known_subscriptions.get(exact_subnet) == Some(&true)
+                })
+                .map(|exact_subnet| exact_subnet.slot + 1)
+                .max()
+        });
+
+        if let Some(end_slot) = next_end_slot {
+            let time_to_subscription_end = self
+                .beacon_chain
+                .slot_clock
+                .duration_to_slot(end_slot)
+                .unwrap_or_default();
+            if !time_to_subscription_end.is_zero() {
+                trace!(self.log, "Extending expired subscription to subnet";
+                    "subnet" => ?subnet_id,
+                    "new_end_slot" => end_slot,
+                );
+                self.short_lived_subscriptions.insert_at(
+                    subnet_id,
+                    end_slot,
+                    time_to_subscription_end,
+                );
+                return;
+            }
+        }
+
+        self.handle_removed_subnet(subnet_id, SubscriptionKind::ShortLived);
+    }
+
     // Unsubscribes from a subnet that was removed if it does not continue to exist as a
     // subscription of the other kind. For long lived subscriptions, it also removes the
     // advertisement from our ENR.
@@ -662,7 +743,7 @@ impl<T: BeaconChainTypes> Stream for AttestationService<T> {
         // Finally process any expired subscriptions.
         match self.short_lived_subscriptions.poll_next_unpin(cx) {
             Poll::Ready(Some(Ok((subnet_id, _end_slot)))) => {
-                self.handle_removed_subnet(subnet_id, SubscriptionKind::ShortLived);
+                self.handle_expired_short_lived_subnet(subnet_id);
                 // We re-wake the task as there could be other subscriptions to process
                 self.waker
                     .as_ref()
@@ -681,6 +762,9 @@ impl<T: BeaconChainTypes> Stream for AttestationService<T> {
                 error!(self.log, "Failed to check for aggregate validator on subnet expirations"; "error"=> e);
             }
         }
+        if let Poll::Ready(Some(Err(e))) = self.known_subscriptions.poll_next_unpin(cx) {
+            error!(self.log, "Failed to check for validator subscription expirations"; "error"=> e);
+        }
 
         Poll::Pending
     }
@@ -194,7 +194,7 @@ impl<T: BeaconChainTypes> SyncCommitteeService<T> {
         let current_slot = self
             .beacon_chain
             .slot_clock
-            .now()
+            .now_or_genesis()
             .ok_or("Could not get the current slot")?;
 
         let slots_per_epoch = T::EthSpec::slots_per_epoch();
@@ -257,7 +257,7 @@ impl<T: BeaconChainTypes> SyncCommitteeService<T> {
         let current_slot = self
             .beacon_chain
             .slot_clock
-            .now()
+            .now_or_genesis()
             .ok_or("Could not get the current slot")?;
 
         let slots_per_epoch = T::EthSpec::slots_per_epoch();
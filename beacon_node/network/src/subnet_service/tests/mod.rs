@@ -526,6 +526,123 @@ mod attestation_service {
         assert_eq!(unexpected_msg_count, 0);
     }
 
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn duplicate_subscriptions_are_ignored() {
+        let committee_index = 1;
+        let committee_count = 1;
+
+        let mut attestation_service = get_attestation_service(None);
+        let current_slot = attestation_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+        let subscription_slot = current_slot + MIN_PEER_DISCOVERY_SLOT_LOOK_AHEAD + 1;
+        let subnet_id = SubnetId::compute_subnet::<MainnetEthSpec>(
+            subscription_slot,
+            committee_index,
+            committee_count,
+            &attestation_service.beacon_chain.spec,
+        )
+        .unwrap();
+
+        // The same subscription sent by two validator clients, then resent by one of them.
+        let subscription =
+            get_subscription(committee_index, subscription_slot, committee_count, false);
+        for subscriptions in [
+            vec![subscription.clone(), subscription.clone()],
+            vec![subscription],
+        ] {
+            attestation_service
+                .validator_subscriptions(subscriptions.into_iter())
+                .unwrap();
+        }
+
+        let events = get_events(&mut attestation_service, None, 1).await;
+        // Discoveries for long lived subnets have no `min_ttl`, so they are not counted.
+        let discoveries = events
+            .iter()
+            .filter(|event| match event {
+                SubnetServiceMessage::DiscoverPeers(discoveries) => discoveries
+                    .iter()
+                    .any(|d| d.subnet == Subnet::Attestation(subnet_id) && d.min_ttl.is_some()),
+                _ => false,
+            })
+            .count();
+
+        // Peers are only searched for once.
+        assert_eq!(discoveries, 1);
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn expired_subscription_is_extended_for_upcoming_aggregator_duty() {
+        let committee_count = 1;
+
+        let mut attestation_service = get_attestation_service(None);
+        // Let the long lived subscriptions be established first.
+        get_events(&mut attestation_service, None, 1).await;
+
+        let current_slot = attestation_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+        let duty_slot = current_slot + 1;
+        let subnet_id = SubnetId::compute_subnet::<MainnetEthSpec>(
+            duty_slot,
+            0,
+            committee_count,
+            &attestation_service.beacon_chain.spec,
+        )
+        .unwrap();
+        attestation_service
+            .validator_subscriptions(
+                vec![get_subscription(0, duty_slot, committee_count, true)].into_iter(),
+            )
+            .unwrap();
+
+        // A subscription to the subnet expiring now should be extended to cover the duty.
+        attestation_service.handle_expired_short_lived_subnet(subnet_id);
+        assert!(attestation_service.is_subscribed(
+            &subnet_id,
+            attestation_subnets::SubscriptionKind::ShortLived
+        ));
+
+        let events = get_events(&mut attestation_service, None, 1).await;
+        assert!(
+            !events.contains(&SubnetServiceMessage::Unsubscribe(Subnet::Attestation(
+                subnet_id
+            )))
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn expired_subscription_without_upcoming_duty_is_removed() {
+        let mut attestation_service = get_attestation_service(None);
+        // Let the long lived subscriptions be established first.
+        get_events(&mut attestation_service, None, 1).await;
+
+        let subnet_id = (0..MainnetEthSpec::default_spec().attestation_subnet_count)
+            .map(SubnetId::new)
+            .find(|subnet_id| {
+                !attestation_service
+                    .is_subscribed(subnet_id, attestation_subnets::SubscriptionKind::LongLived)
+            })
+            .expect("some subnet should not be long lived");
+
+        attestation_service.handle_expired_short_lived_subnet(subnet_id);
+
+        let events = get_events(&mut attestation_service, None, 1).await;
+        assert!(
+            events.contains(&SubnetServiceMessage::Unsubscribe(Subnet::Attestation(
+                subnet_id
+            )))
+        );
+    }
+
     #[cfg(not(windows))]
     #[tokio::test]
     async fn test_subscribe_same_subnet_several_slots_apart() {
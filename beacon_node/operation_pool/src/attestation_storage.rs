@@ -429,9 +429,53 @@ impl<E: EthSpec> AttestationMap<E> {
     }
 
     /// Prune attestations that are from before the previous epoch.
-    pub fn prune(&mut self, current_epoch: Epoch) {
+    ///
+    /// Return the number of attestations pruned.
+    pub fn prune(&mut self, current_epoch: Epoch) -> usize {
+        let mut num_pruned = 0;
         self.checkpoint_map
-            .retain(|checkpoint_key, _| current_epoch <= checkpoint_key.target_epoch + 1);
+            .retain(|checkpoint_key, attestation_map| {
+                let keep = current_epoch <= checkpoint_key.target_epoch + 1;
+                if !keep {
+                    num_pruned += attestation_map.stats().num_attestations;
+                }
+                keep
+            });
+        num_pruned
+    }
+
+    /// Prune attestations that can never be included in a block descending from the finalized
+    /// checkpoint.
+    ///
+    /// An attestation is only includable if its source is the justified checkpoint of the state it
+    /// is included in, which can't be older than the finalized checkpoint. Attestations to blocks
+    /// for which `is_viable_block` returns `false` (e.g. blocks on forks pruned by finalization)
+    /// are also removed.
+    ///
+    /// Return the number of attestations pruned.
+    pub fn prune_non_viable(
+        &mut self,
+        finalized_checkpoint: Checkpoint,
+        is_viable_block: impl Fn(Hash256) -> bool,
+    ) -> usize {
+        let mut num_pruned = 0;
+        self.checkpoint_map
+            .retain(|checkpoint_key, attestation_map| {
+                if checkpoint_key.source.epoch < finalized_checkpoint.epoch {
+                    num_pruned += attestation_map.stats().num_attestations;
+                    return false;
+                }
+                attestation_map.attestations.retain(|data, attestations| {
+                    let keep = is_viable_block(data.beacon_block_root)
+                        && is_viable_block(data.target_root);
+                    if !keep {
+                        num_pruned += attestations.len();
+                    }
+                    keep
+                });
+                !attestation_map.attestations.is_empty()
+            });
+        num_pruned
     }
 
     /// Statistics about all attestations stored in the map.
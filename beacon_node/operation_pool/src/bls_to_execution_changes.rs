@@ -31,6 +31,15 @@ pub struct BlsToExecutionChanges<E: EthSpec> {
 }
 
 impl<E: EthSpec> BlsToExecutionChanges<E> {
+    /// The number of changes in the pool.
+    pub fn len(&self) -> usize {
+        self.by_validator_index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_validator_index.is_empty()
+    }
+
     pub fn existing_change_equals(
         &self,
         address_change: &SignedBlsToExecutionChange,
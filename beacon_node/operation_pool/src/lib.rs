@@ -24,7 +24,7 @@ use crate::attestation_storage::{AttestationMap, CheckpointKey};
 use crate::bls_to_execution_changes::BlsToExecutionChanges;
 use crate::sync_aggregate_id::SyncAggregateId;
 use attester_slashing::AttesterSlashingMaxCover;
-use max_cover::maximum_cover;
+use max_cover::{maximum_cover, maximum_cover_with_budget};
 use parking_lot::{RwLock, RwLockWriteGuard};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
@@ -36,13 +36,21 @@ use state_processing::{SigVerifiedOp, VerifyOperation};
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::ptr;
+use std::time::Duration;
 use types::{
     sync_aggregate::Error as SyncAggregateError, typenum::Unsigned, AbstractExecPayload,
     Attestation, AttestationData, AttesterSlashing, BeaconState, BeaconStateError, ChainSpec,
-    Epoch, EthSpec, ProposerSlashing, SignedBeaconBlock, SignedBlsToExecutionChange,
-    SignedVoluntaryExit, Slot, SyncAggregate, SyncCommitteeContribution, Validator,
+    Checkpoint, Epoch, EthSpec, Hash256, ProposerSlashing, SignedBeaconBlock,
+    SignedBlsToExecutionChange, SignedVoluntaryExit, Slot, SyncAggregate,
+    SyncCommitteeContribution, Validator,
 };
 
+/// Time allowed for improving upon the greedy attestation packing, per epoch of attestations.
+///
+/// The previous and current epoch attestations are packed in parallel, so this is roughly the
+/// maximum latency added to block production.
+const ATTESTATION_PACKING_BUDGET: Duration = Duration::from_millis(20);
+
 type SyncContributions<E> = RwLock<HashMap<SyncAggregateId, Vec<SyncCommitteeContribution<E>>>>;
 
 #[derive(Default, Debug)]
@@ -122,9 +130,10 @@ impl<E: EthSpec> OperationPool<E> {
             }
             Entry::Occupied(mut entry) => {
                 // If contributions exists for this key, check whether there exists a contribution
-                // with a matching `subcommittee_index`. If one exists, check whether the new or
-                // old contribution has more aggregation bits set. If the new one does, add it to the
-                // pool in place of the old one.
+                // with a matching `subcommittee_index`. If one exists and the two contributions are
+                // disjoint, merge them. Otherwise, check whether the new or old contribution has
+                // more aggregation bits set. If the new one does, add it to the pool in place of
+                // the old one.
                 let existing_contributions = entry.get_mut();
                 match existing_contributions
                     .iter_mut()
@@ -132,11 +141,20 @@ impl<E: EthSpec> OperationPool<E> {
                         existing_contribution.subcommittee_index == contribution.subcommittee_index
                     }) {
                     Some(existing_contribution) => {
-                        // Only need to replace the contribution if the new contribution has more
-                        // bits set.
-                        if existing_contribution.aggregation_bits.num_set_bits()
+                        if existing_contribution
+                            .aggregation_bits
+                            .intersection(&contribution.aggregation_bits)
+                            .is_zero()
+                        {
+                            // Partial contributions from different aggregators can be combined
+                            // into a single contribution covering both.
+                            existing_contribution.aggregate(&contribution);
+                            metrics::inc_counter(&metrics::SYNC_CONTRIBUTIONS_MERGED);
+                        } else if existing_contribution.aggregation_bits.num_set_bits()
                             < contribution.aggregation_bits.num_set_bits()
                         {
+                            // Only need to replace the contribution if the new contribution has
+                            // more bits set.
                             *existing_contribution = contribution;
                         }
                     }
@@ -164,12 +182,21 @@ impl<E: EthSpec> OperationPool<E> {
             .get_block_root(slot)
             .map_err(OpPoolError::GetBlockRootError)?;
         let id = SyncAggregateId::new(slot, block_root);
-        self.sync_contributions
+        let sync_aggregate = self
+            .sync_contributions
             .read()
             .get(&id)
             .map(|contributions| SyncAggregate::from_contributions(contributions))
-            .transpose()
-            .map_err(|e| e.into())
+            .transpose()?;
+
+        if let Some(sync_aggregate) = &sync_aggregate {
+            metrics::set_gauge(
+                &metrics::SYNC_AGGREGATE_PARTICIPATION,
+                sync_aggregate.num_set_bits() as i64,
+            );
+        }
+
+        Ok(sync_aggregate)
     }
 
     /// Total number of sync contributions in the pool.
@@ -338,12 +365,22 @@ impl<E: EthSpec> OperationPool<E> {
                 if prev_epoch_key == curr_epoch_key {
                     vec![]
                 } else {
-                    maximum_cover(prev_epoch_att, prev_epoch_limit, "prev_epoch_attestations")
+                    maximum_cover_with_budget(
+                        prev_epoch_att,
+                        prev_epoch_limit,
+                        "prev_epoch_attestations",
+                        ATTESTATION_PACKING_BUDGET,
+                    )
                 }
             },
             move || {
                 let _timer = metrics::start_timer(&metrics::ATTESTATION_CURR_EPOCH_PACKING_TIME);
-                maximum_cover(curr_epoch_att, curr_epoch_limit, "curr_epoch_attestations")
+                maximum_cover_with_budget(
+                    curr_epoch_att,
+                    curr_epoch_limit,
+                    "curr_epoch_attestations",
+                    ATTESTATION_PACKING_BUDGET,
+                )
             },
         );
 
@@ -359,7 +396,33 @@ impl<E: EthSpec> OperationPool<E> {
 
     /// Remove attestations which are too old to be included in a block.
     pub fn prune_attestations(&self, current_epoch: Epoch) {
-        self.attestations.write().prune(current_epoch);
+        let num_pruned = self.attestations.write().prune(current_epoch);
+        metrics::inc_counter_vec_by(
+            &metrics::ATTESTATIONS_PRUNED,
+            &["inclusion_window"],
+            num_pruned as u64,
+        );
+    }
+
+    /// Remove attestations which can never be included in a block descending from
+    /// `finalized_checkpoint`.
+    ///
+    /// Attestations are removed if their source is older than the finalized checkpoint, or if
+    /// their head or target block is not accepted by `is_viable_block`.
+    pub fn prune_non_viable_attestations(
+        &self,
+        finalized_checkpoint: Checkpoint,
+        is_viable_block: impl Fn(Hash256) -> bool,
+    ) {
+        let num_pruned = self
+            .attestations
+            .write()
+            .prune_non_viable(finalized_checkpoint, is_viable_block);
+        metrics::inc_counter_vec_by(
+            &metrics::ATTESTATIONS_PRUNED,
+            &["non_viable_fork"],
+            num_pruned as u64,
+        );
     }
 
     /// Insert a proposer slashing into the pool.
@@ -509,6 +572,9 @@ impl<E: EthSpec> OperationPool<E> {
     }
 
     /// Get a list of voluntary exits for inclusion in a block.
+    ///
+    /// Exits are included in order of their exit epoch (earliest first), so that exits which have
+    /// been valid for longest are not starved by newer ones.
     fn get_voluntary_exits<F>(
         &self,
         state: &BeaconState<E>,
@@ -518,8 +584,15 @@ impl<E: EthSpec> OperationPool<E> {
     where
         F: Fn(&SignedVoluntaryExit) -> bool,
     {
+        let voluntary_exits = self.voluntary_exits.read();
+        let mut sorted_exits = voluntary_exits.values().collect::<Vec<_>>();
+        sorted_exits.sort_by_key(|exit| {
+            let message = &exit.as_inner().message;
+            (message.epoch, message.validator_index)
+        });
+
         filter_limit_operations(
-            self.voluntary_exits.read().values(),
+            sorted_exits,
             |exit| {
                 filter(exit.as_inner())
                     && exit.signature_is_still_valid(&state.fork())
@@ -545,6 +618,19 @@ impl<E: EthSpec> OperationPool<E> {
         );
     }
 
+    /// Prune exits for validators which have already exited in the head state.
+    ///
+    /// Unlike `prune_voluntary_exits` this doesn't wait for the exit to be finalized. Once a
+    /// validator's exit epoch has been reached, the block which initiated its exit is several
+    /// epochs deep and the exit is no longer useful for block production.
+    pub fn prune_applied_voluntary_exits(&self, head_state: &BeaconState<E>) {
+        prune_validator_hash_map(
+            &mut self.voluntary_exits.write(),
+            |_, validator| validator.exit_epoch <= head_state.current_epoch(),
+            head_state,
+        );
+    }
+
     /// Check if an address change equal to `address_change` is already in the pool.
     ///
     /// Return `None` if no address change for the validator index exists in the pool.
@@ -570,6 +656,11 @@ impl<E: EthSpec> OperationPool<E> {
             .insert(verified_change, received_pre_capella)
     }
 
+    /// Total number of BLS to execution changes in the pool.
+    pub fn num_bls_to_execution_changes(&self) -> usize {
+        self.bls_to_execution_changes.read().len()
+    }
+
     /// Get a list of execution changes for inclusion in a block.
     ///
     /// They're in random `HashMap` order, which isn't exactly fair, but isn't unfair either.
@@ -1036,6 +1127,60 @@ mod release_tests {
         assert_eq!(op_pool.num_attestations(), 0);
     }
 
+    /// Attestations which can't be included on a chain descending from finality are pruned.
+    #[test]
+    fn attestation_prune_non_viable() {
+        let (harness, ref spec) = attestation_test_state::<MainnetEthSpec>(1);
+
+        let state = get_current_state_initialize_epoch_cache(&harness, &spec);
+        let slot = state.slot();
+        let num_validators =
+            MainnetEthSpec::slots_per_epoch() as usize * spec.target_committee_size;
+        let head_block_root = Hash256::repeat_byte(0xaa);
+
+        let attestations = harness.make_attestations(
+            (0..num_validators).collect::<Vec<_>>().as_slice(),
+            &state,
+            Hash256::zero(),
+            SignedBeaconBlockHash::from(head_block_root),
+            slot,
+        );
+
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+        let other_op_pool = OperationPool::<MainnetEthSpec>::new();
+        for (atts, _) in attestations {
+            for (att, _) in atts {
+                let attesting_indices =
+                    get_attesting_indices_from_state(&state, att.to_ref()).unwrap();
+                other_op_pool
+                    .insert_attestation(att.clone(), attesting_indices.clone())
+                    .unwrap();
+                op_pool.insert_attestation(att, attesting_indices).unwrap();
+            }
+        }
+        let num_attestations = op_pool.num_attestations();
+        assert!(num_attestations > 0);
+
+        // Attestations to viable blocks which use a justified source are retained.
+        let finalized_checkpoint = state.finalized_checkpoint();
+        op_pool.prune_non_viable_attestations(finalized_checkpoint, |_| true);
+        assert_eq!(op_pool.num_attestations(), num_attestations);
+
+        // Attestations with a source older than the finalized checkpoint are pruned.
+        let later_finalized_checkpoint = Checkpoint {
+            epoch: finalized_checkpoint.epoch + 1,
+            root: Hash256::repeat_byte(0xbb),
+        };
+        other_op_pool.prune_non_viable_attestations(later_finalized_checkpoint, |_| true);
+        assert_eq!(other_op_pool.num_attestations(), 0);
+
+        // Attestations to blocks on non-viable forks are pruned.
+        op_pool.prune_non_viable_attestations(finalized_checkpoint, |block_root| {
+            block_root != head_block_root
+        });
+        assert_eq!(op_pool.num_attestations(), 0);
+    }
+
     /// Adding an attestation already in the pool should not increase the size of the pool.
     #[test]
     fn attestation_duplicate() {
@@ -1871,6 +2016,56 @@ mod release_tests {
         );
     }
 
+    /// Adding a sync contribution which is disjoint from the one in the pool should merge the two.
+    #[tokio::test]
+    async fn sync_contribution_disjoint_merged() {
+        let (harness, _) = sync_contribution_test_state::<MainnetEthSpec>(1).await;
+
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+        let state = harness.get_current_state();
+        let block_root = *state
+            .get_block_root(state.slot() - Slot::new(1))
+            .ok()
+            .expect("block root should exist at slot");
+        let contributions = harness.make_sync_contributions(
+            &state,
+            block_root,
+            state.slot() - Slot::new(1),
+            RelativeSyncCommittee::Current,
+        );
+
+        let contribution = contributions[0]
+            .1
+            .as_ref()
+            .unwrap()
+            .message
+            .contribution
+            .clone();
+        let num_bits = contribution.aggregation_bits.len();
+
+        // Split the contribution into two halves with disjoint aggregation bits.
+        let mut first_half = contribution.clone();
+        let mut second_half = contribution;
+        for i in 0..num_bits {
+            if i < num_bits / 2 {
+                second_half.aggregation_bits.set(i, false).expect("set bit");
+            } else {
+                first_half.aggregation_bits.set(i, false).expect("set bit");
+            }
+        }
+
+        op_pool.insert_sync_contribution(first_half).unwrap();
+        op_pool.insert_sync_contribution(second_half).unwrap();
+
+        // The halves should be merged into a single contribution with all bits set.
+        assert_eq!(op_pool.num_sync_contributions(), 1);
+        let sync_aggregate = op_pool
+            .get_sync_aggregate(&state)
+            .expect("Should calculate the sync aggregate")
+            .expect("Should have block sync aggregate");
+        assert_eq!(sync_aggregate.sync_committee_bits.num_set_bits(), num_bits);
+    }
+
     fn cross_fork_harness<E: EthSpec>() -> (BeaconChainHarness<EphemeralHarnessType<E>>, ChainSpec)
     {
         let mut spec = E::default_spec();
@@ -1965,6 +2160,78 @@ mod release_tests {
         assert_eq!(&exits, &[exit2]);
     }
 
+    /// Exits should be packed in order of their epoch, then validator index.
+    #[tokio::test]
+    async fn voluntary_exits_ordered_by_epoch() {
+        let (harness, spec) = cross_fork_harness::<MainnetEthSpec>();
+        let slots_per_epoch = MainnetEthSpec::slots_per_epoch();
+        harness
+            .extend_to_slot(Epoch::new(3).start_slot(slots_per_epoch))
+            .await;
+        let head = harness.chain.canonical_head.cached_head().snapshot;
+
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+        let exits = [(5, 2), (3, 0), (7, 1), (4, 0)]
+            .into_iter()
+            .map(|(validator_index, epoch)| {
+                let exit = harness.make_voluntary_exit(validator_index, Epoch::new(epoch));
+                op_pool.insert_voluntary_exit(
+                    exit.clone().validate(&head.beacon_state, &spec).unwrap(),
+                );
+                exit
+            })
+            .collect::<Vec<_>>();
+
+        let packed = op_pool.get_voluntary_exits(&head.beacon_state, |_| true, &spec);
+        assert_eq!(
+            packed,
+            vec![
+                exits[1].clone(),
+                exits[3].clone(),
+                exits[2].clone(),
+                exits[0].clone()
+            ]
+        );
+    }
+
+    /// Exits should be pruned once the validator's exit epoch has been reached in the head state,
+    /// without waiting for finalization.
+    #[tokio::test]
+    async fn prune_applied_voluntary_exits() {
+        let (harness, spec) = cross_fork_harness::<MainnetEthSpec>();
+        let slots_per_epoch = MainnetEthSpec::slots_per_epoch();
+        harness
+            .extend_to_slot(Epoch::new(1).start_slot(slots_per_epoch))
+            .await;
+        let head = harness.chain.canonical_head.cached_head().snapshot;
+
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+        for validator_index in 0..3 {
+            let exit = harness.make_voluntary_exit(validator_index, Epoch::new(0));
+            op_pool.insert_voluntary_exit(exit.validate(&head.beacon_state, &spec).unwrap());
+        }
+
+        // Nothing has been applied yet.
+        op_pool.prune_applied_voluntary_exits(&head.beacon_state);
+        assert_eq!(op_pool.num_voluntary_exits(), 3);
+
+        // Validator 0 has exited, validator 1 has initiated an exit which hasn't been reached yet
+        // and validator 2 is still active.
+        let mut state = head.beacon_state.clone();
+        let current_epoch = state.current_epoch();
+        state.validators_mut().get_mut(0).unwrap().exit_epoch = current_epoch;
+        state.validators_mut().get_mut(1).unwrap().exit_epoch = current_epoch + 1;
+
+        op_pool.prune_applied_voluntary_exits(&state);
+        let mut remaining = op_pool
+            .get_all_voluntary_exits()
+            .into_iter()
+            .map(|exit| exit.message.validator_index)
+            .collect::<Vec<_>>();
+        remaining.sort_unstable();
+        assert_eq!(remaining, vec![1, 2]);
+    }
+
     /// Test several cross-fork proposer slashings:
     ///
     /// - phase0 slashing (not valid after Bellatrix)
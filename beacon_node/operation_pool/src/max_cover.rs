@@ -1,5 +1,6 @@
 use crate::metrics;
 use itertools::Itertools;
+use std::time::{Duration, Instant};
 
 /// Trait for types that we can compute a maximum cover for.
 ///
@@ -32,6 +33,7 @@ pub trait MaxCover: Clone {
 
 /// Helper struct to track which items of the input are still available for inclusion.
 /// Saves removing elements from the work vector.
+#[derive(Clone)]
 struct MaxCoverItem<T> {
     item: T,
     available: bool,
@@ -55,8 +57,94 @@ where
     I: IntoIterator<Item = T>,
     T: MaxCover,
 {
-    // Construct an initial vec of all items, marked available.
-    let mut all_items: Vec<_> = items_iter
+    greedy_cover(non_zero_items(items_iter, label), limit)
+}
+
+/// Compute an approximate maximum cover, improving on the greedy solution by partial enumeration.
+///
+/// The greedy solution is computed first. Then, until `budget` has elapsed, each item is forced
+/// into the solution in turn (highest score first) and the rest of the solution is completed
+/// greedily. The best solution found is returned, so the result is never worse than greedy.
+///
+/// The enumeration is bounded: a solution seeded with an item can score no more than that item's
+/// score plus the sum of the `limit - 1` best scores of the other items. Once this bound can no
+/// longer beat the best solution found, the search stops.
+///
+/// * Time complexity: `O(min(budget, items_iter.len() * limit * items_iter.len()))`
+/// * Space complexity: `O(item_iter.len())`
+pub fn maximum_cover_with_budget<I, T>(
+    items_iter: I,
+    limit: usize,
+    label: &str,
+    budget: Duration,
+) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    T: MaxCover,
+{
+    let deadline = Instant::now() + budget;
+
+    let mut all_items = non_zero_items(items_iter, label);
+    all_items.sort_by_key(|x| std::cmp::Reverse(x.item.score()));
+
+    let mut best = greedy_cover(all_items.clone(), limit);
+    let mut best_score = solution_score(&best);
+
+    if limit == 0 {
+        return best;
+    }
+
+    // The sum of the best `limit - 1` scores, used to bound the score of each seeded solution.
+    let top_scores = all_items
+        .iter()
+        .take(limit)
+        .map(|x| x.item.score())
+        .collect::<Vec<_>>();
+    let top_sum = top_scores.iter().sum::<usize>();
+    let top_sum_excluding_last = top_sum - top_scores.last().copied().unwrap_or(0);
+
+    for seed_index in 0..all_items.len() {
+        if Instant::now() >= deadline {
+            metrics::inc_counter_vec(&metrics::MAX_COVER_ENUMERATION_TIMEOUTS, &[label]);
+            break;
+        }
+
+        let upper_bound = if seed_index < limit {
+            top_sum
+        } else {
+            all_items[seed_index].item.score() + top_sum_excluding_last
+        };
+        if upper_bound <= best_score {
+            break;
+        }
+
+        let mut items = all_items.clone();
+        items[seed_index].available = false;
+        let seed = items[seed_index].item.clone();
+        update_covering_sets(&mut items, &seed);
+
+        let mut solution = vec![seed];
+        solution.extend(greedy_cover(items, limit - 1));
+
+        let score = solution_score(&solution);
+        if score > best_score {
+            best = solution;
+            best_score = score;
+        }
+    }
+
+    // Callers expect solutions ordered by descending score, as produced by the greedy algorithm.
+    best.sort_by_key(|item| std::cmp::Reverse(item.score()));
+    best
+}
+
+/// Construct a vec of all items with non-zero scores, marked available.
+fn non_zero_items<I, T>(items_iter: I, label: &str) -> Vec<MaxCoverItem<T>>
+where
+    I: IntoIterator<Item = T>,
+    T: MaxCover,
+{
+    let all_items: Vec<_> = items_iter
         .into_iter()
         .map(MaxCoverItem::new)
         .filter(|x| x.item.score() != 0)
@@ -68,6 +156,10 @@ where
         all_items.len() as i64,
     );
 
+    all_items
+}
+
+fn greedy_cover<T: MaxCover>(mut all_items: Vec<MaxCoverItem<T>>, limit: usize) -> Vec<T> {
     let mut result = vec![];
 
     for _ in 0..limit {
@@ -84,15 +176,7 @@ where
             None => return result,
         };
 
-        // Update the covering sets of the other items, for the inclusion of the selected item.
-        // Items covered by the selected item can't be re-covered.
-        all_items
-            .iter_mut()
-            .filter(|x| x.available && x.item.score() != 0)
-            .for_each(|x| {
-                x.item
-                    .update_covering_set(best.intermediate(), best.covering_set())
-            });
+        update_covering_sets(&mut all_items, &best);
 
         result.push(best);
     }
@@ -100,6 +184,22 @@ where
     result
 }
 
+/// Update the covering sets of the available items, for the inclusion of `best`.
+/// Items covered by the selected item can't be re-covered.
+fn update_covering_sets<T: MaxCover>(all_items: &mut [MaxCoverItem<T>], best: &T) {
+    all_items
+        .iter_mut()
+        .filter(|x| x.available && x.item.score() != 0)
+        .for_each(|x| {
+            x.item
+                .update_covering_set(best.intermediate(), best.covering_set())
+        });
+}
+
+fn solution_score<T: MaxCover>(solution: &[T]) -> usize {
+    solution.iter().map(MaxCover::score).sum()
+}
+
 /// Perform a greedy merge of two max cover solutions, preferring higher-score values.
 pub fn merge_solutions<I1, I2, T>(cover1: I1, cover2: I2, limit: usize) -> Vec<T::Object>
 where
@@ -207,6 +307,38 @@ mod test {
         assert_eq!(quality(&cover), 11);
     }
 
+    // With enough time to enumerate, the optimal solution to the above system is found.
+    #[test]
+    fn suboptimal_with_budget() {
+        let sets = vec![
+            HashSet::from_iter(vec![0, 1, 8, 11, 14]),
+            HashSet::from_iter(vec![2, 3, 7, 9, 10]),
+            HashSet::from_iter(vec![4, 5, 6, 12, 13]),
+            HashSet::from_iter(vec![9, 10]),
+            HashSet::from_iter(vec![5, 6, 7, 8]),
+            HashSet::from_iter(vec![0, 1, 2, 3, 4]),
+        ];
+        let cover = maximum_cover_with_budget(sets.clone(), 3, "test", Duration::from_secs(60));
+        assert_eq!(quality(&cover), 15);
+        assert_eq!(cover.len(), 3);
+
+        // Without any time to enumerate, the greedy solution is returned.
+        let cover = maximum_cover_with_budget(sets, 3, "test", Duration::ZERO);
+        assert_eq!(quality(&cover), 11);
+    }
+
+    #[test]
+    fn budget_matches_greedy_when_greedy_optimal() {
+        let sets = example_system();
+        for k in 0..10 {
+            let cover = maximum_cover_with_budget(sets.clone(), k, "test", Duration::from_secs(60));
+            let greedy = maximum_cover(sets.clone(), k, "test");
+            // Ties may be broken differently, so compare the covered weight and solution size.
+            assert_eq!(quality(&cover), quality(&greedy), "k = {k}");
+            assert_eq!(cover.len(), greedy.len(), "k = {k}");
+        }
+    }
+
     #[test]
     fn intersecting_ok() {
         let sets = vec![
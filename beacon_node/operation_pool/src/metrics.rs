@@ -23,9 +23,27 @@ lazy_static! {
         "op_pool_curr_epoch_attestations",
         "Number of valid attestations considered for packing from the current epoch"
     );
+    pub static ref SYNC_CONTRIBUTIONS_MERGED: Result<IntCounter> = try_create_int_counter(
+        "op_pool_sync_contributions_merged_total",
+        "Count of disjoint sync contributions merged into an existing contribution"
+    );
+    pub static ref SYNC_AGGREGATE_PARTICIPATION: Result<IntGauge> = try_create_int_gauge(
+        "op_pool_sync_aggregate_participation",
+        "Number of sync committee bits set in the most recently packed sync aggregate"
+    );
+    pub static ref ATTESTATIONS_PRUNED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "op_pool_attestations_pruned_total",
+        "Count of attestations pruned from the op pool",
+        &["reason"]
+    );
     pub static ref MAX_COVER_NON_ZERO_ITEMS: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "op_pool_max_cover_non_zero_items",
         "Number of non-trivial items considered in a max coverage optimisation",
         &["label"]
     );
+    pub static ref MAX_COVER_ENUMERATION_TIMEOUTS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "op_pool_max_cover_enumeration_timeouts_total",
+        "Count of max coverage optimisations which exhausted their time budget before completing",
+        &["label"]
+    );
 }
@@ -26,6 +26,20 @@ pub fn cli_app() -> Command {
             .display_order(0)
             .help_heading(FLAG_HEADER)
         )
+        .arg(
+            Arg::new("additional-network")
+                .long("additional-network")
+                .value_name("FILE")
+                .help("Run a beacon node for another network in this process, sharing its runtime \
+                       and metrics server, which labels the metrics of each network with a \
+                       `network` label. The file contains the Lighthouse arguments for the \
+                       other network, e.g. `--network holesky --datadir /data/holesky \
+                       beacon_node --port 9100 --http-port 5153`. It must use a separate data \
+                       directory and ports, must not enable --metrics and must have the same \
+                       spec preset as this network. May be set multiple times.")
+                .action(ArgAction::Append)
+                .display_order(0)
+        )
         .arg(
             Arg::new("network-dir")
                 .long("network-dir")
@@ -353,6 +367,17 @@ pub fn cli_app() -> Command {
                 .display_order(0)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("peer-filter-file")
+                .long("peer-filter-file")
+                .value_name("FILE")
+                .help("Path to a file of allow and deny lists applied to all inbound and outbound \
+                       connections. Each line is `allow` or `deny` followed by an IP address, a \
+                       CIDR range, a peer id or a hex-encoded node id. If any peers are allowed, \
+                       all other peers are rejected. The file is reloaded when modified.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("genesis-backfill")
                 .long("genesis-backfill")
@@ -487,6 +512,7 @@ pub fn cli_app() -> Command {
                 .requires("enable_http")
                 .value_name("ORIGIN")
                 .help("Set the value of the Access-Control-Allow-Origin response HTTP header. \
+                    Multiple origins may be provided as a comma-separated list. \
                     Use * to allow any origin (not recommended in production). \
                     If no value is supplied, the CORS allowed origin is set to the listen \
                     address of this server (e.g., http://localhost:5052).")
@@ -514,12 +540,47 @@ pub fn cli_app() -> Command {
                 .requires("http-tls-key")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("http-enable-log-level-changes")
+                .long("http-enable-log-level-changes")
+                .help("Allow the log levels to be changed at runtime using the \
+                    POST /lighthouse/logging/levels endpoint.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .requires("enable_http")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-enable-validator-monitor-registration")
+                .long("http-enable-validator-monitor-registration")
+                .help("Allow validator clients to register their keys with the validator monitor \
+                    using the POST /lighthouse/validator_monitor/register endpoint. Keys are \
+                    only monitored if --validator-monitor-auto is also supplied. Requests must \
+                    carry the --http-api-token-file token.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .requires("enable_http")
+                .requires("http-api-token-file")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-api-token-file")
+                .long("http-api-token-file")
+                .value_name("PATH")
+                .help("Path to a file containing a token which clients must send in an \
+                    `Authorization: Bearer` header to use authenticated endpoints, such as \
+                    POST /lighthouse/validator_monitor/register.")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("http-tls-cert")
                 .long("http-tls-cert")
                 .requires("enable_http")
+                .requires("http-tls-key")
                 .help("The path of the certificate to be used when serving the HTTP API server \
-                    over TLS.")
+                    over TLS. Providing a certificate and key enables TLS.")
                 .action(ArgAction::Set)
                 .display_order(0)
         )
@@ -527,8 +588,10 @@ pub fn cli_app() -> Command {
             Arg::new("http-tls-key")
                 .long("http-tls-key")
                 .requires("enable_http")
+                .requires("http-tls-cert")
                 .help("The path of the private key to be used when serving the HTTP API server \
-                    over TLS. Must not be password-protected.")
+                    over TLS. Must not be password-protected. Providing a certificate and key \
+                    enables TLS.")
                 .action(ArgAction::Set)
                 .display_order(0)
         )
@@ -564,6 +627,29 @@ pub fn cli_app() -> Command {
                        HTTP API.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("http-historic-cache-size")
+                .long("http-historic-cache-size")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .default_value_if("enable_http", ArgPredicate::IsPresent, "0")
+                .value_name("N")
+                .help("Number of finalized block and state responses to cache in the HTTP API. \
+                       Serialized states can be hundreds of megabytes, so each cached entry may \
+                       use a lot of memory. Disabled (0) by default.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-state-replay-budget")
+                .long("http-state-replay-budget")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .value_name("SLOTS")
+                .help("Maximum number of slots to replay when serving a state from the freezer \
+                       database on the debug state API. Requests which would replay more slots \
+                       are rejected with the nearest stored slot. By default there is no limit.")
+                .display_order(0)
+        )
         .arg(
             Arg::new("http-enable-beacon-processor")
                 .long("http-enable-beacon-processor")
@@ -655,6 +741,26 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("monitoring-endpoint-token-file")
+                .long("monitoring-endpoint-token-file")
+                .value_name("PATH")
+                .help("Path to a file containing a token which will be sent to the \
+                       monitoring-endpoint in an `Authorization: Bearer` header.")
+                .requires("monitoring-endpoint")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("otlp-endpoint")
+                .long("otlp-endpoint")
+                .value_name("URL")
+                .help("Exports tracing spans for blocks and blobs passing through the import \
+                       pipeline, from gossip decoding to the database write, to an OpenTelemetry \
+                       collector at this gRPC endpoint, e.g. http://localhost:4317.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
 
         /*
          * Standard staking flags
@@ -744,6 +850,18 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("epochs-per-state-persist")
+                .long("epochs-per-state-persist")
+                .value_name("N")
+                .help("The number of epochs between the epoch boundary states which are written \
+                       to the hot DB in full. Other states are reconstructed by replaying blocks, \
+                       so higher values reduce disk writes at the cost of slower state loads, e.g. \
+                       after a restart. Must be at most the number of epochs per historical root")
+                .default_value("1")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("block-cache-size")
                 .long("block-cache-size")
@@ -862,6 +980,17 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("engine-api-trace-dir")
+                .long("engine-api-trace-dir")
+                .value_name("DIR")
+                .help("Write all Engine API requests and responses to JSON files in this \
+                       directory, for inclusion in bug reports. The JWT is redacted, large \
+                       bodies are truncated and old files are deleted automatically.")
+                .requires("execution-endpoint")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("builder-header-timeout")
                 .long("builder-header-timeout")
@@ -919,6 +1048,17 @@ pub fn cli_app() -> Command {
                        It is generally not recommended unless auto-compaction is disabled.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("db-checksums")
+                .long("db-checksums")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("If present, store a checksum with each value written to a new database \
+                       and verify it when the value is read, so that disk corruption is \
+                       detected. Checksums can only be enabled when the database is created, \
+                       and remain enabled afterwards.")
+                .display_order(0)
+        )
         .arg(
             Arg::new("auto-compact-db")
                 .long("auto-compact-db")
@@ -968,6 +1108,102 @@ pub fn cli_app() -> Command {
                 .default_value("0")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("prune-history-before")
+                .long("prune-history-before")
+                .value_name("SLOT")
+                .help("Delete blocks and blobs older than SLOT from the database, keeping only \
+                       recent history. Blocks within MIN_EPOCHS_FOR_BLOCK_REQUESTS of the current \
+                       epoch are always kept, and historic states from SLOT onwards remain \
+                       available.")
+                .conflicts_with_all(["genesis-backfill", "reconstruct-historic-states"])
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("retain-abandoned-blocks")
+                .long("retain-abandoned-blocks")
+                .help("Keep the blinded blocks of forks abandoned by finalization as evidence of \
+                       slashable proposals. Their execution payloads, blobs and states are \
+                       still deleted. Retained blocks are never deleted afterwards, so the \
+                       database grows with every abandoned fork.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("emergency-pruning-threshold")
+                .long("emergency-pruning-threshold")
+                .value_name("GIGABYTES")
+                .help("Delete the states of forks which are not viable for the head if the hot \
+                       database grows beyond this size during a long period of non-finality. \
+                       Pruned forks can no longer be built upon. Disabled by default.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("hot-db-compression")
+                .long("hot-db-compression")
+                .value_name("ALGORITHM")
+                .help("Compress blocks and states written to the hot database. Existing values \
+                       are left as they are and compressed when they are next written. The \
+                       algorithm may be changed between restarts.")
+                .value_parser(["none", "snappy", "zstd"])
+                .action(ArgAction::Set)
+                .default_value("none")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("hot-db-compression-level")
+                .long("hot-db-compression-level")
+                .value_name("LEVEL")
+                .help("Compression level to use with --hot-db-compression and \
+                       --blobs-db-compression. Only applies to zstd, where higher levels trade \
+                       CPU time for a smaller database.")
+                .action(ArgAction::Set)
+                .default_value("1")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("blobs-db-compression")
+                .long("blobs-db-compression")
+                .value_name("ALGORITHM")
+                .help("Compress blob sidecars written to the blobs database. Blobs which are \
+                       partly zero padding compress well; blobs full of rollup data do not. \
+                       Existing blobs are left as they are. The algorithm may be changed between \
+                       restarts.")
+                .value_parser(["none", "snappy", "zstd"])
+                .action(ArgAction::Set)
+                .default_value("none")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("hot-db-write-buffer-size")
+                .long("hot-db-write-buffer-size")
+                .value_name("MEGABYTES")
+                .help("Size of the in-memory write buffer of the hot database. Larger buffers \
+                       reduce compaction work at the cost of memory. [default: 4]")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("freezer-db-write-buffer-size")
+                .long("freezer-db-write-buffer-size")
+                .value_name("MEGABYTES")
+                .help("Size of the in-memory write buffer of the freezer database. Larger \
+                       buffers reduce compaction work at the cost of memory. [default: 64]")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("blobs-db-write-buffer-size")
+                .long("blobs-db-write-buffer-size")
+                .value_name("MEGABYTES")
+                .help("Size of the in-memory write buffer of the blobs database. Larger \
+                       buffers reduce compaction work at the cost of memory. [default: 64]")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
 
         /*
          * Misc.
@@ -983,6 +1219,29 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("graffiti-watermark")
+                .long("graffiti-watermark")
+                .help("If present, append a watermark of the execution and consensus client \
+                       versions to user-specified graffiti if there is room for it, e.g. \
+                       `my graffiti GEabcdLHabcd`. The watermark is shortened to fit, and omitted \
+                       if the graffiti is too long. Helps to measure client diversity.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .conflicts_with("private")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("graffiti-watermark-override")
+                .long("graffiti-watermark-override")
+                .help("If present, always append the full client version watermark to \
+                       user-specified graffiti, truncating the graffiti if necessary. Implies \
+                       --graffiti-watermark.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .conflicts_with("private")
+                .display_order(0)
+        )
         .arg(
             Arg::new("max-skip-slots")
                 .long("max-skip-slots")
@@ -1193,6 +1452,61 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("ignore-weak-subjectivity")
+                .long("ignore-weak-subjectivity")
+                .help("Start syncing from a checkpoint state even if it is older than its weak \
+                    subjectivity period. Syncing from such a state leaves the node open to \
+                    long-range attacks, so only use this flag if the state is known to be \
+                    trustworthy.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("genesis-state-checksum")
+                .long("genesis-state-checksum")
+                .value_name("SHA256")
+                .help("The 0x-prefixed SHA256 checksum of the SSZ genesis state served by \
+                    --genesis-state-url. When supplied on a network which does not include a \
+                    genesis state, the genesis state is downloaded from --genesis-state-url \
+                    at first startup instead of being computed from the deposit contract.")
+                .requires("genesis-state-url")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("network-config-update-url")
+                .long("network-config-update-url")
+                .value_name("URL")
+                .help("URL of a signed manifest used to refresh the boot ENRs and deposit \
+                    contract deploy block of a built-in network at startup. The manifest must \
+                    be signed by the update key embedded in Lighthouse for the network (or \
+                    --network-config-update-pubkey), otherwise it is ignored and the built-in \
+                    values are used.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("network-config-update-pubkey")
+                .long("network-config-update-pubkey")
+                .value_name("PUBKEY")
+                .help("The 0x-prefixed BLS public key used to verify the manifest at \
+                    --network-config-update-url. Overrides any key embedded in Lighthouse.")
+                .requires("network-config-update-url")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("network-config-update-timeout")
+                .long("network-config-update-timeout")
+                .value_name("SECONDS")
+                .help("The timeout in seconds for the request to --network-config-update-url.")
+                .requires("network-config-update-url")
+                .default_value("10")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("reconstruct-historic-states")
                 .long("reconstruct-historic-states")
@@ -1201,6 +1515,18 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("reconstruct-historic-states-workers")
+                .long("reconstruct-historic-states-workers")
+                .value_name("COUNT")
+                .help("The number of threads loading blocks ahead of the replay during historic \
+                       state reconstruction. Blocks are loaded one restore point range at a time, \
+                       split between the threads.")
+                .requires("reconstruct-historic-states")
+                .default_value("4")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("validator-monitor-auto")
                 .long("validator-monitor-auto")
@@ -1243,6 +1569,29 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("validator-monitor-alert-webhook")
+                .long("validator-monitor-alert-webhook")
+                .help("A URL to which alerts about monitored validators are POSTed as JSON. \
+                    Alerts are raised when a validator misses an attestation or a block, or \
+                    when its attestation effectiveness drops below \
+                    --validator-monitor-alert-effectiveness-threshold.")
+                .value_name("URL")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("validator-monitor-alert-effectiveness-threshold")
+                .long("validator-monitor-alert-effectiveness-threshold")
+                .help("Raise an alert when the fraction of epochs in which a monitored \
+                    validator's attestations were included on-chain drops below this value. \
+                    Must be between 0 and 1. Only has an effect when \
+                    --validator-monitor-alert-webhook is set.")
+                .value_name("FLOAT")
+                .requires("validator-monitor-alert-webhook")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-lock-timeouts")
                 .long("disable-lock-timeouts")
@@ -1326,6 +1675,16 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("record-gossip")
+                .long("record-gossip")
+                .value_name("FILE")
+                .help("Record every block, blob and attestation which passes gossip verification \
+                       to FILE, so that they can be replayed into a fresh node for debugging. \
+                       Records are appended if FILE already exists.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("always-prepare-payload")
                 .long("always-prepare-payload")
@@ -1395,6 +1754,46 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("enable-degraded-mode")
+                .long("enable-degraded-mode")
+                .help("Enter degraded mode whilst chain health is poor. In degraded mode the beacon \
+                        node produces payloads locally, does not attempt proposer re-orgs and \
+                        reduces the size of its state cache. Chain health is reported by the \
+                        /lighthouse/health endpoint regardless of this flag.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("degraded-epochs-since-finalization")
+                .long("degraded-epochs-since-finalization")
+                .value_name("EPOCHS")
+                .help("Consider the chain degraded if the head's finalized checkpoint is more than \
+                        this many epochs behind the head.")
+                .default_value("8")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("degraded-missed-slots-per-epoch")
+                .long("degraded-missed-slots-per-epoch")
+                .value_name("SLOTS")
+                .help("Consider the chain degraded if more than this many of the last epoch's \
+                        worth of slots were missed.")
+                .default_value("16")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("degraded-min-peers")
+                .long("degraded-min-peers")
+                .value_name("PEERS")
+                .help("Consider the chain degraded if fewer than this many peers are connected.")
+                .default_value("5")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("builder-fallback-disable-checks")
                 .long("builder-fallback-disable-checks")
@@ -1415,6 +1814,28 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("builder-require-fee-recipient")
+                .long("builder-require-fee-recipient")
+                .help("Reject builder payloads which do not pay the suggested fee recipient \
+                       directly, using the locally produced payload instead.")
+                .requires("builder")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("builder-required-transactions")
+                .long("builder-required-transactions")
+                .value_name("HASHES")
+                .help("A comma-separated list of transaction hashes which builders must not \
+                       exclude. If the locally produced payload includes any of these \
+                       transactions then builder payloads are rejected, since their inclusion \
+                       cannot be verified, and the local payload is used instead.")
+                .requires("builder")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("reset-payload-statuses")
                 .long("reset-payload-statuses")
@@ -1454,6 +1875,16 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("deposit-snapshot-rpc")
+                .long("deposit-snapshot-rpc")
+                .help("Serve and request deposit snapshots over a Lighthouse-specific req/resp \
+                       protocol, so that a node which checkpoint synced without a deposit \
+                       snapshot can fill its deposit cache from peers [experimental]")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("gui")
                 .long("gui")
@@ -1551,6 +1982,76 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("beacon-processor-sync-signature-batch-size")
+                .long("beacon-processor-sync-signature-batch-size")
+                .value_name("INTEGER")
+                .help("Specifies the number of gossip sync committee messages in a signature \
+                       verification batch. \
+                       Higher values may reduce CPU usage in a healthy network while lower values may \
+                       increase CPU usage in an unhealthy or hostile network.")
+                .hide(true)
+                .default_value("64")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-processor-queue-lengths")
+                .long("beacon-processor-queue-lengths")
+                .value_name("WORK_TYPE=LENGTH,...")
+                .help("Overrides the maximum length of one or more beacon processor queues, as \
+                       a comma-separated list of work type and length pairs (e.g. \
+                       gossip_block=2048,gossip_aggregate=8192). Work beyond these lengths \
+                       is dropped.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-processor-high-priority-lane")
+                .long("beacon-processor-high-priority-lane")
+                .help("Process gossip blocks and sync committee contributions ahead of all \
+                       other beacon processor work, including sync.")
+                .hide(true)
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-processor-load-shedding-threshold")
+                .long("beacon-processor-load-shedding-threshold")
+                .value_name("INTEGER")
+                .help("Drop low-value work, such as attestations on subnets not required by \
+                       local validators and backfill batches, once this many items are queued \
+                       and all beacon processor workers are busy. Higher-value work is dropped \
+                       at multiples of this threshold. Disabled by default.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-processor-gossip-latency-budget")
+                .long("beacon-processor-gossip-latency-budget")
+                .value_name("MILLIS")
+                .help("Start new gossip messages of a type whose average processing time \
+                       persistently exceeds this many milliseconds on additional beacon \
+                       processor workers, rather than queueing them. Disabled by default.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-processor-gossip-overflow-workers")
+                .long("beacon-processor-gossip-overflow-workers")
+                .value_name("INTEGER")
+                .requires("beacon-processor-gossip-latency-budget")
+                .help("The number of beacon processor workers in addition to the maximum which \
+                       may process gossip messages that are over the latency budget. \
+                       Default is half the number of CPUs, with a minimum of 1.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-duplicate-warn-logs")
                 .long("disable-duplicate-warn-logs")
@@ -3,7 +3,7 @@ use beacon_chain::chain_config::{
     DEFAULT_RE_ORG_HEAD_THRESHOLD, DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
     DEFAULT_RE_ORG_PARENT_THRESHOLD,
 };
-use beacon_chain::graffiti_calculator::GraffitiOrigin;
+use beacon_chain::graffiti_calculator::{GraffitiOrigin, GraffitiWatermark};
 use beacon_chain::TrustedSetup;
 use clap::{parser::ValueSource, ArgMatches, Id};
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
@@ -28,7 +28,7 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 use types::graffiti::GraffitiString;
-use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes};
+use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes, Slot};
 
 /// Gets the fully-initialized global client.
 ///
@@ -137,7 +137,11 @@ pub fn get_config<E: EthSpec>(
             );
         }
 
-        if cli_args.get_flag("http-enable-tls") {
+        // TLS is enabled by providing a certificate and key. The `--http-enable-tls` flag is
+        // retained for backwards compatibility.
+        if cli_args.get_flag("http-enable-tls")
+            || cli_args.get_one::<String>("http-tls-cert").is_some()
+        {
             client_config.http_api.tls_config = Some(TlsConfig {
                 cert: cli_args
                     .get_one::<String>("http-tls-cert")
@@ -166,11 +170,26 @@ pub fn get_config<E: EthSpec>(
         client_config.http_api.enable_beacon_processor =
             parse_required(cli_args, "http-enable-beacon-processor")?;
 
+        client_config.http_api.historic_cache_size =
+            parse_required(cli_args, "http-historic-cache-size")?;
+
+        client_config.http_api.state_replay_budget =
+            clap_utils::parse_optional(cli_args, "http-state-replay-budget")?;
+
         client_config.http_api.duplicate_block_status_code =
             parse_required(cli_args, "http-duplicate-block-status")?;
 
         client_config.http_api.enable_light_client_server =
             cli_args.get_flag("light-client-server");
+
+        client_config.http_api.enable_validator_monitor_registration =
+            cli_args.get_flag("http-enable-validator-monitor-registration");
+
+        client_config.http_api.api_token_path =
+            clap_utils::parse_optional(cli_args, "http-api-token-file")?;
+
+        client_config.http_api.enable_log_level_changes =
+            cli_args.get_flag("http-enable-log-level-changes");
     }
 
     if cli_args.get_flag("light-client-server") {
@@ -216,11 +235,14 @@ pub fn get_config<E: EthSpec>(
     if let Some(monitoring_endpoint) = cli_args.get_one::<String>("monitoring-endpoint") {
         let update_period_secs =
             clap_utils::parse_optional(cli_args, "monitoring-endpoint-period")?;
+        let bearer_token_path =
+            clap_utils::parse_optional(cli_args, "monitoring-endpoint-token-file")?;
 
         client_config.monitoring_api = Some(monitoring_api::Config {
             db_path: None,
             freezer_db_path: None,
             update_period_secs,
+            bearer_token_path,
             monitoring_endpoint: monitoring_endpoint.to_string(),
         });
     }
@@ -333,6 +355,20 @@ pub fn get_config<E: EthSpec>(
             el_config.builder_header_timeout =
                 clap_utils::parse_optional(cli_args, "builder-header-timeout")?
                     .map(Duration::from_millis);
+
+            el_config.builder_require_fee_recipient =
+                cli_args.get_flag("builder-require-fee-recipient");
+
+            if let Some(hashes) = cli_args.get_one::<String>("builder-required-transactions") {
+                el_config.builder_required_transactions = hashes
+                    .split(',')
+                    .map(|hash| {
+                        hash.trim()
+                            .parse()
+                            .map_err(|e| format!("Invalid transaction hash {}: {:?}", hash, e))
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
         }
 
         if cli_args.get_flag("always-prefer-builder-payload") {
@@ -356,6 +392,8 @@ pub fn get_config<E: EthSpec>(
         let execution_timeout_multiplier =
             clap_utils::parse_required(cli_args, "execution-timeout-multiplier")?;
         el_config.execution_timeout_multiplier = Some(execution_timeout_multiplier);
+        el_config.engine_api_trace_dir =
+            clap_utils::parse_optional(cli_args, "engine-api-trace-dir")?;
 
         client_config.eth1.endpoint = Eth1Endpoint::Auth {
             endpoint: execution_endpoint,
@@ -422,6 +460,7 @@ pub fn get_config<E: EthSpec>(
     }
 
     client_config.store.compact_on_init = cli_args.get_flag("compact-db");
+    client_config.store.db_checksums = cli_args.get_flag("db-checksums");
     if let Some(compact_on_prune) = cli_args.get_one::<String>("auto-compact-db") {
         client_config.store.compact_on_prune = compact_on_prune
             .parse()
@@ -438,6 +477,12 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.epochs_per_migration = epochs_per_migration;
     }
 
+    if let Some(epochs_per_state_persist) =
+        clap_utils::parse_optional(cli_args, "epochs-per-state-persist")?
+    {
+        client_config.store.epochs_per_state_persist = epochs_per_state_persist;
+    }
+
     if let Some(prune_blobs) = clap_utils::parse_optional(cli_args, "prune-blobs")? {
         client_config.store.prune_blobs = prune_blobs;
     }
@@ -454,6 +499,49 @@ pub fn get_config<E: EthSpec>(
         client_config.store.blob_prune_margin_epochs = blob_prune_margin_epochs;
     }
 
+    client_config.store.prune_history_before =
+        clap_utils::parse_optional(cli_args, "prune-history-before")?.map(Slot::new);
+
+    client_config.store.retain_abandoned_blocks = cli_args.get_flag("retain-abandoned-blocks");
+
+    if let Some(hot_db_compression) = clap_utils::parse_optional(cli_args, "hot-db-compression")? {
+        client_config.store.hot_db_compression = hot_db_compression;
+    }
+
+    if let Some(hot_db_compression_level) =
+        clap_utils::parse_optional(cli_args, "hot-db-compression-level")?
+    {
+        client_config.store.hot_db_compression_level = hot_db_compression_level;
+    }
+
+    if let Some(blobs_db_compression) =
+        clap_utils::parse_optional(cli_args, "blobs-db-compression")?
+    {
+        client_config.store.blobs_db_compression = blobs_db_compression;
+    }
+
+    for (flag, tuning) in [
+        (
+            "hot-db-write-buffer-size",
+            &mut client_config.store.hot_db_tuning,
+        ),
+        (
+            "freezer-db-write-buffer-size",
+            &mut client_config.store.cold_db_tuning,
+        ),
+        (
+            "blobs-db-write-buffer-size",
+            &mut client_config.store.blobs_db_tuning,
+        ),
+    ] {
+        if let Some(megabytes) = clap_utils::parse_optional::<usize>(cli_args, flag)? {
+            tuning.write_buffer_size = megabytes
+                .checked_mul(1024 * 1024)
+                .filter(|bytes| *bytes > 0)
+                .ok_or_else(|| format!("Invalid --{flag}: {megabytes}"))?;
+        }
+    }
+
     /*
      * Zero-ports
      *
@@ -503,6 +591,14 @@ pub fn get_config<E: EthSpec>(
                 .extend_from_slice(boot_nodes)
         }
     }
+    client_config.network_config_update_url =
+        clap_utils::parse_optional(cli_args, "network-config-update-url")?;
+    client_config.network_config_update_pubkey =
+        clap_utils::parse_optional(cli_args, "network-config-update-pubkey")?;
+    client_config.network_config_update_timeout =
+        clap_utils::parse_required(cli_args, "network-config-update-timeout")
+            .map(Duration::from_secs)?;
+
     client_config.chain.checkpoint_sync_url_timeout =
         clap_utils::parse_required::<u64>(cli_args, "checkpoint-sync-url-timeout")?;
 
@@ -530,6 +626,10 @@ pub fn get_config<E: EthSpec>(
     };
 
     client_config.allow_insecure_genesis_sync = cli_args.get_flag("allow-insecure-genesis-sync");
+    client_config.ignore_weak_subjectivity = cli_args.get_flag("ignore-weak-subjectivity");
+
+    client_config.genesis_state_checksum =
+        clap_utils::parse_optional(cli_args, "genesis-state-checksum")?;
 
     client_config.genesis = if eth2_network_config.genesis_state_is_known() {
         // Set up weak subjectivity sync, or start from the hardcoded genesis state.
@@ -574,7 +674,13 @@ pub fn get_config<E: EthSpec>(
                     .to_string(),
             );
         }
-        ClientGenesis::DepositContract
+
+        if client_config.genesis_state_checksum.is_some() {
+            // The genesis state will be downloaded from `genesis_state_url`.
+            ClientGenesis::GenesisState
+        } else {
+            ClientGenesis::DepositContract
+        }
     };
 
     if cli_args.get_flag("reconstruct-historic-states") {
@@ -582,6 +688,12 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.genesis_backfill = true;
     }
 
+    if let Some(reconstruct_workers) =
+        clap_utils::parse_optional(cli_args, "reconstruct-historic-states-workers")?
+    {
+        client_config.store.reconstruct_workers = reconstruct_workers;
+    }
+
     let beacon_graffiti = if let Some(graffiti) = cli_args.get_one::<String>("graffiti") {
         GraffitiOrigin::UserSpecified(GraffitiString::from_str(graffiti)?.into())
     } else if cli_args.get_flag("private") {
@@ -593,6 +705,14 @@ pub fn get_config<E: EthSpec>(
     };
     client_config.beacon_graffiti = beacon_graffiti;
 
+    client_config.chain.graffiti_watermark = if cli_args.get_flag("graffiti-watermark-override") {
+        GraffitiWatermark::Override
+    } else if cli_args.get_flag("graffiti-watermark") {
+        GraffitiWatermark::Append
+    } else {
+        GraffitiWatermark::Disabled
+    };
+
     if let Some(wss_checkpoint) = cli_args.get_one::<String>("wss-checkpoint") {
         let mut split = wss_checkpoint.split(':');
         let root_str = split
@@ -752,6 +872,25 @@ pub fn get_config<E: EthSpec>(
             .individual_tracking_threshold = count;
     }
 
+    if let Some(url) = clap_utils::parse_optional(cli_args, "validator-monitor-alert-webhook")? {
+        client_config.validator_monitor.alert_webhook = Some(url);
+    }
+
+    if let Some(threshold) = clap_utils::parse_optional::<f64>(
+        cli_args,
+        "validator-monitor-alert-effectiveness-threshold",
+    )? {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(
+                "--validator-monitor-alert-effectiveness-threshold must be between 0 and 1"
+                    .to_string(),
+            );
+        }
+        client_config
+            .validator_monitor
+            .alert_effectiveness_threshold = Some(threshold);
+    }
+
     if cli_args.get_flag("disable-lock-timeouts") {
         warn!(
             log,
@@ -835,6 +974,23 @@ pub fn get_config<E: EthSpec>(
         clap_utils::parse_required(cli_args, "builder-fallback-epochs-since-finalization")?;
     client_config.chain.builder_fallback_disable_checks =
         cli_args.get_flag("builder-fallback-disable-checks");
+    client_config.chain.enable_degraded_mode = cli_args.get_flag("enable-degraded-mode");
+    client_config.chain.degraded_epochs_since_finalization =
+        clap_utils::parse_required(cli_args, "degraded-epochs-since-finalization")?;
+    client_config.chain.degraded_missed_slots_per_epoch =
+        clap_utils::parse_required(cli_args, "degraded-missed-slots-per-epoch")?;
+    client_config.chain.degraded_min_peers =
+        clap_utils::parse_required(cli_args, "degraded-min-peers")?;
+    client_config.chain.emergency_pruning_threshold =
+        clap_utils::parse_optional::<u64>(cli_args, "emergency-pruning-threshold")?
+            .map(|gigabytes| {
+                gigabytes
+                    .checked_mul(1024 * 1024 * 1024)
+                    .ok_or("--emergency-pruning-threshold is too large")
+            })
+            .transpose()?;
+
+    client_config.chain.gossip_record_path = clap_utils::parse_optional(cli_args, "record-gossip")?;
 
     // Graphical user interface config.
     if cli_args.get_flag("gui") {
@@ -888,6 +1044,43 @@ pub fn get_config<E: EthSpec>(
         .beacon_processor
         .max_gossip_aggregate_batch_size =
         clap_utils::parse_required(cli_args, "beacon-processor-aggregate-batch-size")?;
+    client_config
+        .beacon_processor
+        .max_gossip_sync_signature_batch_size =
+        clap_utils::parse_required(cli_args, "beacon-processor-sync-signature-batch-size")?;
+
+    if let Some(queue_lengths) = cli_args.get_one::<String>("beacon-processor-queue-lengths") {
+        for pair in queue_lengths.split(',') {
+            let (work_type, queue_len) = pair.split_once('=').ok_or_else(|| {
+                format!(
+                    "Invalid --beacon-processor-queue-lengths entry {}, expected WORK_TYPE=LENGTH",
+                    pair
+                )
+            })?;
+            let queue_len = queue_len
+                .parse::<usize>()
+                .map_err(|e| format!("Invalid queue length for {}: {:?}", work_type, e))?;
+            client_config
+                .beacon_processor
+                .queue_length_overrides
+                .insert(work_type.to_string(), queue_len);
+        }
+        beacon_processor::validate_queue_length_overrides(
+            &client_config.beacon_processor.queue_length_overrides,
+        )?;
+    }
+    client_config.beacon_processor.enable_high_priority_lane =
+        cli_args.get_flag("beacon-processor-high-priority-lane");
+    client_config.beacon_processor.load_shedding_threshold =
+        clap_utils::parse_optional(cli_args, "beacon-processor-load-shedding-threshold")?;
+    client_config.beacon_processor.gossip_latency_budget =
+        clap_utils::parse_optional(cli_args, "beacon-processor-gossip-latency-budget")?
+            .map(Duration::from_millis);
+    if let Some(overflow_workers) =
+        clap_utils::parse_optional(cli_args, "beacon-processor-gossip-overflow-workers")?
+    {
+        client_config.beacon_processor.gossip_overflow_workers = overflow_workers;
+    }
 
     Ok(client_config)
 }
@@ -1212,6 +1405,10 @@ pub fn set_network_config(
         }
     }
 
+    if let Some(peer_filter_file) = cli_args.get_one::<String>("peer-filter-file") {
+        config.peer_filter_file = Some(PathBuf::from(peer_filter_file));
+    }
+
     if let Some(enr_udp_port_str) = cli_args.get_one::<String>("enr-udp-port") {
         config.enr_udp4_port = Some(
             enr_udp_port_str
@@ -1413,6 +1610,8 @@ pub fn set_network_config(
     // Light client server config.
     config.enable_light_client_server = parse_flag(cli_args, "light-client-server");
 
+    config.enable_deposit_snapshot_rpc = parse_flag(cli_args, "deposit-snapshot-rpc");
+
     // The self limiter is enabled by default. If the `self-limiter-protocols` flag is not provided,
     // the default params will be used.
     config.outbound_rate_limiter_config = if parse_flag(cli_args, "disable-self-limiter") {
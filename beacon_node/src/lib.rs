@@ -12,10 +12,15 @@ pub use client::{Client, ClientBuilder, ClientConfig, ClientGenesis};
 pub use config::{get_config, get_data_dir, get_slots_per_restore_point, set_network_config};
 use environment::RuntimeContext;
 pub use eth2_config::Eth2Config;
+use eth2_network_config::{
+    download_network_config_update, network_config_update_pubkey,
+    read_network_config_update_version, write_network_config_update_version,
+};
 use slasher::{DatabaseBackendOverride, Slasher};
-use slog::{info, warn};
+use slog::{info, warn, Logger};
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use types::{ChainSpec, Epoch, EthSpec, ForkName};
 
 /// A type-alias to the tighten the definition of a production-intended `Client`.
@@ -72,6 +77,10 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             )
         }
 
+        if let Some(url) = client_config.network_config_update_url.clone() {
+            apply_network_config_update(&mut client_config, &url, &spec, &log).await;
+        }
+
         if let Err(misaligned_forks) = validator_fork_epochs(&spec) {
             warn!(
                 log,
@@ -187,6 +196,103 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
     }
 }
 
+/// Refresh the boot ENRs and deposit contract deploy block from the signed manifest at `url`.
+///
+/// Failures are logged rather than returned, the node continues with its built-in values.
+async fn apply_network_config_update(
+    client_config: &mut ClientConfig,
+    url: &str,
+    spec: &ChainSpec,
+    log: &Logger,
+) {
+    let Some(network_name) = spec.config_name.as_deref() else {
+        warn!(log, "Ignoring network config update"; "reason" => "network has no name");
+        return;
+    };
+    let pubkey = match client_config.network_config_update_pubkey {
+        Some(pubkey) => pubkey
+            .decompress()
+            .map(Some)
+            .map_err(|e| format!("Invalid network update key: {:?}", e)),
+        None => network_config_update_pubkey(network_name),
+    };
+    let pubkey = match pubkey {
+        Ok(Some(pubkey)) => pubkey,
+        Ok(None) => {
+            warn!(
+                log,
+                "Ignoring network config update";
+                "reason" => "no update key for network",
+                "network" => network_name,
+            );
+            return;
+        }
+        Err(e) => {
+            warn!(log, "Ignoring network config update"; "error" => e);
+            return;
+        }
+    };
+
+    let data_dir = client_config.get_data_dir();
+    let min_version = match read_network_config_update_version(&data_dir) {
+        Ok(version) => version,
+        Err(e) => {
+            warn!(log, "Ignoring network config update"; "error" => e);
+            return;
+        }
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    let manifest = match download_network_config_update(
+        url,
+        client_config.network_config_update_timeout,
+        &pubkey,
+        network_name,
+        min_version,
+        now,
+        log,
+    )
+    .await
+    {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            warn!(
+                log,
+                "Unable to update network config";
+                "info" => "continuing with built-in values",
+                "error" => e,
+            );
+            return;
+        }
+    };
+
+    if let Err(e) = write_network_config_update_version(&data_dir, manifest.version) {
+        warn!(log, "Unable to record network config update version"; "error" => e);
+    }
+
+    if !client_config.network.disable_discovery {
+        for enr in manifest.boot_enr {
+            if !client_config.network.boot_nodes_enr.contains(&enr) {
+                client_config.network.boot_nodes_enr.push(enr);
+            }
+        }
+    }
+    if let Some(deploy_block) = manifest.deposit_contract_deploy_block {
+        client_config.eth1.deposit_contract_deploy_block = deploy_block;
+        client_config.eth1.lowest_cached_block_number = deploy_block;
+    }
+
+    info!(
+        log,
+        "Applied network config update";
+        "version" => manifest.version,
+        "boot_nodes" => client_config.network.boot_nodes_enr.len(),
+        "deploy_block" => client_config.eth1.deposit_contract_deploy_block,
+    );
+}
+
 fn validator_fork_epochs(spec: &ChainSpec) -> Result<(), Vec<(ForkName, Epoch)>> {
     // @dapplion: "We try to schedule forks such that the fork epoch is a multiple of 256, to keep
     // historical vectors in the same fork. Indirectly that makes light client periods align with
@@ -0,0 +1,103 @@
+//! Optional per-value CRC32 checksums for on-disk databases.
+//!
+//! When enabled, every value is written with a little-endian CRC32 of its column, key and value
+//! appended. The checksum is verified and removed when the value is read, so that a value which
+//! has been silently corrupted on disk surfaces as `Error::Corruption` rather than as an obscure
+//! decoding failure.
+//!
+//! Checksums are a property of the whole database and are fixed when the database is created.
+//! Values in the `BeaconMeta` column are never checksummed, so that the configuration recording
+//! whether checksums are enabled can always be read.
+use crate::{get_col_from_key, DBColumn, Error};
+
+/// Number of bytes appended to each checksummed value.
+pub const CHECKSUM_LEN: usize = 4;
+
+/// Returns `true` if values in `column` are checksummed when checksums are enabled.
+pub fn is_checksummed_column(column: &str) -> bool {
+    column != DBColumn::BeaconMeta.as_str()
+}
+
+fn checksum(column_key: &[u8], value: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(column_key);
+    hasher.update(value);
+    hasher.finalize().to_le_bytes()
+}
+
+/// Append the checksum of `value`, stored at `column_key`, to `value`.
+pub fn append_checksum(column_key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.len() + CHECKSUM_LEN);
+    bytes.extend_from_slice(value);
+    bytes.extend_from_slice(&checksum(column_key, value));
+    bytes
+}
+
+/// Verify and remove the checksum from `bytes`, read from `column_key`.
+pub fn verify_checksum(column_key: &[u8], mut bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let corruption = || {
+        let column = get_col_from_key(column_key).unwrap_or_else(|| "unknown".to_owned());
+        let key = column_key.get(column.len()..).unwrap_or_default().to_vec();
+        Error::Corruption { column, key }
+    };
+
+    let value_len = bytes
+        .len()
+        .checked_sub(CHECKSUM_LEN)
+        .ok_or_else(corruption)?;
+    if bytes[value_len..] != checksum(column_key, &bytes[..value_len]) {
+        return Err(corruption());
+    }
+    bytes.truncate(value_len);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_key_for_col;
+
+    #[test]
+    fn roundtrip() {
+        let column_key = get_key_for_col(DBColumn::BeaconBlock.into(), &[1; 32]);
+        for value in [vec![], vec![0], vec![42; 1000]] {
+            let bytes = append_checksum(&column_key, &value);
+            assert_eq!(bytes.len(), value.len() + CHECKSUM_LEN);
+            assert_eq!(verify_checksum(&column_key, bytes).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let column_key = get_key_for_col(DBColumn::BeaconBlock.into(), &[1; 32]);
+        let bytes = append_checksum(&column_key, &[42; 100]);
+
+        let assert_corrupt = |result: Result<Vec<u8>, Error>| match result {
+            Err(Error::Corruption { column, key }) => {
+                assert_eq!(column, DBColumn::BeaconBlock.as_str());
+                assert_eq!(key, vec![1; 32]);
+            }
+            other => panic!("expected corruption, got {other:?}"),
+        };
+
+        // A flipped bit in the value.
+        let mut flipped = bytes.clone();
+        flipped[10] ^= 1;
+        assert_corrupt(verify_checksum(&column_key, flipped));
+
+        // A truncated value.
+        assert_corrupt(verify_checksum(&column_key, bytes[..50].to_vec()));
+        assert_corrupt(verify_checksum(&column_key, vec![0; 2]));
+
+        // A value stored under a different key.
+        let other_key = get_key_for_col(DBColumn::BeaconBlock.into(), &[2; 32]);
+        assert!(verify_checksum(&other_key, bytes).is_err());
+    }
+
+    #[test]
+    fn metadata_is_not_checksummed() {
+        assert!(!is_checksummed_column(DBColumn::BeaconMeta.as_str()));
+        assert!(is_checksummed_column(DBColumn::BeaconBlock.as_str()));
+        assert!(is_checksummed_column(DBColumn::BeaconState.as_str()));
+    }
+}
@@ -0,0 +1,185 @@
+//! Optional per-value compression for blocks, states and blobs.
+//!
+//! Compressed values are self-describing: snappy values are written in the snappy framing format
+//! (which begins with a fixed stream identifier) and zstd values are written as a single zstd
+//! frame (which begins with a fixed magic number). Uncompressed block and state values are SSZ
+//! containers that begin with a small little-endian offset, and blob sidecar lists begin with a
+//! small blob index, so they can never be mistaken for a compressed value. This allows compressed and uncompressed values to co-exist in the database,
+//! and for the compression algorithm to be changed between restarts.
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use strum::{EnumString, IntoStaticStr};
+
+/// The default zstd compression level, favouring speed over compression ratio.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 1;
+
+/// Magic number at the start of every zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Stream identifier chunk at the start of every snappy framed stream.
+const SNAPPY_STREAM_IDENTIFIER: [u8; 10] =
+    [0xff, 0x06, 0x00, 0x00, b's', b'N', b'a', b'P', b'p', b'Y'];
+
+/// The algorithm used to compress values written to the database.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumString, IntoStaticStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    /// Store values uncompressed.
+    #[default]
+    None,
+    Snappy,
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    /// Compress `bytes` for storage.
+    ///
+    /// The `level` is only used by zstd.
+    pub fn compress(self, bytes: Vec<u8>, level: i32) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::None => Ok(bytes),
+            Self::Snappy => {
+                let mut encoder = snap::write::FrameEncoder::new(vec![]);
+                encoder
+                    .write_all(&bytes)
+                    .map_err(|e| Error::CompressionError(format!("{e:?}")))?;
+                encoder
+                    .into_inner()
+                    .map_err(|e| Error::CompressionError(format!("{:?}", e.error())))
+            }
+            Self::Zstd => zstd::bulk::compress(&bytes, level)
+                .map_err(|e| Error::CompressionError(format!("{e:?}"))),
+        }
+    }
+}
+
+/// Returns `true` if `bytes` were compressed by `CompressionAlgorithm::compress`.
+pub fn is_compressed(bytes: &[u8]) -> bool {
+    bytes.starts_with(&ZSTD_MAGIC) || bytes.starts_with(&SNAPPY_STREAM_IDENTIFIER)
+}
+
+/// Decompress a value read from the database.
+///
+/// Values which were stored uncompressed are returned as-is.
+pub fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+    if bytes.starts_with(&ZSTD_MAGIC) {
+        zstd::stream::decode_all(bytes.as_slice())
+            .map_err(|e| Error::CompressionError(format!("{e:?}")))
+    } else if bytes.starts_with(&SNAPPY_STREAM_IDENTIFIER) {
+        let mut decompressed = vec![];
+        snap::read::FrameDecoder::new(bytes.as_slice())
+            .read_to_end(&mut decompressed)
+            .map_err(|e| Error::CompressionError(format!("{e:?}")))?;
+        Ok(decompressed)
+    } else {
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssz::Encode;
+    use std::sync::Arc;
+    use types::{
+        BeaconBlock, BlobSidecar, BlobSidecarList, MainnetEthSpec, Signature, SignedBeaconBlock,
+    };
+
+    fn block_bytes() -> Vec<u8> {
+        let spec = MainnetEthSpec::default_spec();
+        SignedBeaconBlock::from_block(
+            BeaconBlock::<MainnetEthSpec>::empty(&spec),
+            Signature::empty(),
+        )
+        .as_ssz_bytes()
+    }
+
+    #[test]
+    fn roundtrip() {
+        let bytes = block_bytes();
+        for algorithm in [
+            CompressionAlgorithm::None,
+            CompressionAlgorithm::Snappy,
+            CompressionAlgorithm::Zstd,
+        ] {
+            let compressed = algorithm
+                .compress(bytes.clone(), DEFAULT_COMPRESSION_LEVEL)
+                .unwrap();
+            assert_eq!(
+                is_compressed(&compressed),
+                algorithm != CompressionAlgorithm::None
+            );
+            assert_eq!(decompress(compressed).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn uncompressed_ssz_is_not_detected_as_compressed() {
+        assert!(!is_compressed(&block_bytes()));
+    }
+
+    /// A list holding one blob whose first `filled` bytes are pseudo-random and the rest zero.
+    fn blob_list_bytes(filled: usize) -> Vec<u8> {
+        let mut sidecar = BlobSidecar::<MainnetEthSpec>::empty();
+        let mut x = 0x2545_f491_4f6c_dd1d_u64;
+        for byte in sidecar.blob.iter_mut().take(filled) {
+            // xorshift64
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            *byte = x as u8;
+        }
+        BlobSidecarList::<MainnetEthSpec>::new(vec![Arc::new(sidecar)])
+            .unwrap()
+            .as_ssz_bytes()
+    }
+
+    #[test]
+    fn uncompressed_blobs_are_not_detected_as_compressed() {
+        assert!(!is_compressed(&blob_list_bytes(0)));
+        assert!(!is_compressed(&blob_list_bytes(usize::MAX)));
+    }
+
+    /// Measures the saving which justifies compressing blobs per value: a blob which is half
+    /// zero padding shrinks by almost half, whereas a blob full of already-compressed data does
+    /// not shrink at all.
+    #[test]
+    fn blob_compression_ratio() {
+        let half = blob_list_bytes(65_536);
+        let full = blob_list_bytes(usize::MAX);
+
+        for algorithm in [CompressionAlgorithm::Snappy, CompressionAlgorithm::Zstd] {
+            let compressed = algorithm
+                .compress(half.clone(), DEFAULT_COMPRESSION_LEVEL)
+                .unwrap();
+            assert!(
+                compressed.len() * 10 < half.len() * 6,
+                "{algorithm:?}: {} of {} bytes",
+                compressed.len(),
+                half.len()
+            );
+
+            let compressed = algorithm
+                .compress(full.clone(), DEFAULT_COMPRESSION_LEVEL)
+                .unwrap();
+            assert!(
+                compressed.len() * 100 > full.len() * 99,
+                "{algorithm:?}: {} of {} bytes",
+                compressed.len(),
+                full.len()
+            );
+        }
+    }
+
+    #[test]
+    fn parse_algorithm() {
+        assert_eq!("none".parse(), Ok(CompressionAlgorithm::None));
+        assert_eq!("snappy".parse(), Ok(CompressionAlgorithm::Snappy));
+        assert_eq!("zstd".parse(), Ok(CompressionAlgorithm::Zstd));
+        assert!("lz4".parse::<CompressionAlgorithm>().is_err());
+    }
+}
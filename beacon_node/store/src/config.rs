@@ -1,10 +1,11 @@
+use crate::compression::{CompressionAlgorithm, DEFAULT_COMPRESSION_LEVEL};
 use crate::{DBColumn, Error, StoreItem};
 use serde::{Deserialize, Serialize};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::num::NonZeroUsize;
 use types::non_zero_usize::new_non_zero_usize;
-use types::{EthSpec, MinimalEthSpec};
+use types::{EthSpec, MinimalEthSpec, Slot};
 
 pub const PREV_DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 2048;
 pub const DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 8192;
@@ -12,7 +13,43 @@ pub const DEFAULT_BLOCK_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(5);
 pub const DEFAULT_STATE_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(128);
 pub const DEFAULT_HISTORIC_STATE_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(1);
 pub const DEFAULT_EPOCHS_PER_BLOB_PRUNE: u64 = 1;
+pub const DEFAULT_EPOCHS_PER_STATE_PERSIST: u64 = 1;
 pub const DEFAULT_BLOB_PUNE_MARGIN_EPOCHS: u64 = 0;
+pub const DEFAULT_RECONSTRUCT_WORKERS: NonZeroUsize = new_non_zero_usize(4);
+
+/// Backend tuning for a database holding small, frequently rewritten values.
+pub const HOT_DB_TUNING: DBTuning = DBTuning {
+    write_buffer_size: 4 * 1024 * 1024,
+    block_size: 4 * 1024,
+    compression: true,
+};
+/// Backend tuning for a database holding large values which are written once and rarely deleted.
+///
+/// A larger write buffer means fewer, larger level-0 files and so less rewriting during
+/// compaction, whilst larger blocks reduce index overhead for values that are always read whole.
+/// Compression is left on: LevelDB stores a block uncompressed whenever compressing it saves less
+/// than an eighth of its size, so incompressible values only cost the attempt.
+pub const IMMUTABLE_DB_TUNING: DBTuning = DBTuning {
+    write_buffer_size: 64 * 1024 * 1024,
+    block_size: 64 * 1024,
+    compression: true,
+};
+
+/// Options applied to a database by the storage backend when it is opened.
+///
+/// LevelDB applies these to a whole database, so columns which need different treatment are
+/// tuned per column with value compression instead (see `StoreConfig::column_compression`).
+///
+/// These may be changed between restarts without affecting the data on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DBTuning {
+    /// Bytes of writes to buffer in memory before flushing them to a new table on disk.
+    pub write_buffer_size: usize,
+    /// Approximate size in bytes of the uncompressed blocks that tables are divided into.
+    pub block_size: usize,
+    /// Whether the backend should compress blocks.
+    pub compression: bool,
+}
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -40,17 +77,50 @@ pub struct StoreConfig {
     /// The margin for blob pruning in epochs. The oldest blobs are pruned up until
     /// data_availability_boundary - blob_prune_margin_epochs. Default: 0.
     pub blob_prune_margin_epochs: u64,
+    /// Frequency in epochs with which full states are written to the hot database. Default: 1.
+    ///
+    /// Other epoch boundary states are stored as summaries and reconstructed by block replay.
+    pub epochs_per_state_persist: u64,
+    /// Number of threads loading blocks ahead of the replay during historic state reconstruction.
+    pub reconstruct_workers: NonZeroUsize,
+    /// Delete blocks and blobs prior to this slot, subject to the spec's minimum retention period.
+    pub prune_history_before: Option<Slot>,
+    /// Whether to keep the blinded blocks of abandoned forks after finalization, as evidence of
+    /// slashable proposals. Their payloads, blobs and states are deleted regardless.
+    ///
+    /// Off by default, because nothing deletes the retained blocks once their forks have been
+    /// pruned, so they accumulate in the hot database for as long as it exists.
+    pub retain_abandoned_blocks: bool,
+    /// Algorithm used to compress blocks and states written to the hot database.
+    pub hot_db_compression: CompressionAlgorithm,
+    /// Compression level used by `hot_db_compression`, if the algorithm supports levels.
+    pub hot_db_compression_level: i32,
+    /// Whether to store a checksum with each value, which can only be enabled for a new database.
+    pub db_checksums: bool,
+    /// Algorithm used to compress blob sidecars written to the blobs database.
+    ///
+    /// Blobs are only partly filled by most rollups and the remainder is zero padding, which
+    /// compresses well. Blob compression uses `hot_db_compression_level`.
+    pub blobs_db_compression: CompressionAlgorithm,
+    /// Backend tuning for the hot database.
+    pub hot_db_tuning: DBTuning,
+    /// Backend tuning for the freezer database.
+    pub cold_db_tuning: DBTuning,
+    /// Backend tuning for the blobs database.
+    pub blobs_db_tuning: DBTuning,
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct OnDiskStoreConfig {
     pub slots_per_restore_point: u64,
+    pub checksums: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum StoreConfigError {
     MismatchedSlotsPerRestorePoint { config: u64, on_disk: u64 },
+    MismatchedChecksums { config: bool, on_disk: bool },
 }
 
 impl Default for StoreConfig {
@@ -68,14 +138,50 @@ impl Default for StoreConfig {
             prune_blobs: true,
             epochs_per_blob_prune: DEFAULT_EPOCHS_PER_BLOB_PRUNE,
             blob_prune_margin_epochs: DEFAULT_BLOB_PUNE_MARGIN_EPOCHS,
+            epochs_per_state_persist: DEFAULT_EPOCHS_PER_STATE_PERSIST,
+            reconstruct_workers: DEFAULT_RECONSTRUCT_WORKERS,
+            prune_history_before: None,
+            retain_abandoned_blocks: false,
+            hot_db_compression: CompressionAlgorithm::None,
+            hot_db_compression_level: DEFAULT_COMPRESSION_LEVEL,
+            db_checksums: false,
+            blobs_db_compression: CompressionAlgorithm::None,
+            hot_db_tuning: HOT_DB_TUNING,
+            cold_db_tuning: IMMUTABLE_DB_TUNING,
+            blobs_db_tuning: IMMUTABLE_DB_TUNING,
         }
     }
 }
 
 impl StoreConfig {
+    /// The algorithm used to compress values written to `column`.
+    ///
+    /// Only columns whose uncompressed values can never be mistaken for compressed ones (see
+    /// `compression::is_compressed`) may be compressed.
+    pub fn column_compression(&self, column: DBColumn) -> CompressionAlgorithm {
+        match column {
+            DBColumn::BeaconBlock | DBColumn::BeaconState => self.hot_db_compression,
+            DBColumn::BeaconBlob => self.blobs_db_compression,
+            _ => CompressionAlgorithm::None,
+        }
+    }
+
+    /// Backend tuning for the blobs database.
+    ///
+    /// Backend compression is disabled when blobs are already compressed per value, to avoid
+    /// compressing them twice.
+    pub fn effective_blobs_db_tuning(&self) -> DBTuning {
+        DBTuning {
+            compression: self.blobs_db_tuning.compression
+                && self.blobs_db_compression == CompressionAlgorithm::None,
+            ..self.blobs_db_tuning
+        }
+    }
+
     pub fn as_disk_config(&self) -> OnDiskStoreConfig {
         OnDiskStoreConfig {
             slots_per_restore_point: self.slots_per_restore_point,
+            checksums: self.db_checksums,
         }
     }
 
@@ -89,6 +195,12 @@ impl StoreConfig {
                 on_disk: on_disk_config.slots_per_restore_point,
             });
         }
+        if self.db_checksums != on_disk_config.checksums {
+            return Err(StoreConfigError::MismatchedChecksums {
+                config: self.db_checksums,
+                on_disk: on_disk_config.checksums,
+            });
+        }
         Ok(())
     }
 }
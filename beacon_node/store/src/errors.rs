@@ -57,6 +57,19 @@ pub enum Error {
         state_root: Hash256,
         slot: Slot,
     },
+    CompressionError(String),
+    /// The checksum of the value at `key` in `column` doesn't match, so it was corrupted on disk.
+    Corruption {
+        column: String,
+        key: Vec<u8>,
+    },
+    /// Parallel iteration was requested over slots which are not yet in the freezer database.
+    ParallelIterRangeNotFinalized {
+        end_slot: Slot,
+        split_slot: Slot,
+    },
+    ParallelIterSpawnFailed(String),
+    ParallelIterReaderPanicked,
 }
 
 pub trait HandleUnavailable<T> {
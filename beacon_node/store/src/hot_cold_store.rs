@@ -1,6 +1,7 @@
 use crate::chunked_vector::{
     store_updated_vector, BlockRoots, HistoricalRoots, HistoricalSummaries, RandaoMixes, StateRoots,
 };
+use crate::compression::decompress;
 use crate::config::{
     OnDiskStoreConfig, StoreConfig, DEFAULT_SLOTS_PER_RESTORE_POINT,
     PREV_DEFAULT_SLOTS_PER_RESTORE_POINT,
@@ -35,6 +36,7 @@ use state_processing::{
     SlotProcessingError,
 };
 use std::cmp::min;
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
 use std::path::Path;
@@ -42,6 +44,9 @@ use std::sync::Arc;
 use std::time::Duration;
 use types::*;
 
+/// The number of blocks deleted per database write by `try_prune_history`.
+const PRUNE_HISTORY_BATCH_SIZE: usize = 1024;
+
 /// On-disk database that stores finalized states efficiently.
 ///
 /// Stores vector fields like the `block_roots` and `state_roots` separately, and only stores
@@ -155,6 +160,10 @@ pub enum HotColdDBError {
         slots_per_epoch: u64,
     },
     ZeroEpochsPerBlobPrune,
+    InvalidEpochsPerStatePersist {
+        epochs_per_state_persist: u64,
+        max_epochs_per_state_persist: u64,
+    },
     BlobPruneLogicError,
     RestorePointBlockHashError(BeaconStateError),
     IterationError {
@@ -211,14 +220,18 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         log: Logger,
     ) -> Result<Arc<Self>, Error> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
+        Self::verify_epochs_per_state_persist(config.epochs_per_state_persist)?;
 
         let mut db = HotColdDB {
             split: RwLock::new(Split::default()),
             anchor_info: RwLock::new(None),
             blob_info: RwLock::new(BlobInfo::default()),
-            cold_db: LevelDB::open(cold_path)?,
-            blobs_db: LevelDB::open(blobs_db_path)?,
-            hot_db: LevelDB::open(hot_path)?,
+            cold_db: LevelDB::open_with_tuning(cold_path, &config.cold_db_tuning)?,
+            blobs_db: LevelDB::open_with_tuning(
+                blobs_db_path,
+                &config.effective_blobs_db_tuning(),
+            )?,
+            hot_db: LevelDB::open_with_tuning(hot_path, &config.hot_db_tuning)?,
             block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
             state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
             historic_state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
@@ -248,6 +261,25 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             }
         }
 
+        // Checksums are fixed when the database is created, so use the on-disk value for an
+        // existing database. A config which fails to decode predates checksums and needs migrating.
+        let checksums = if db.load_schema_version()?.is_some() {
+            let on_disk = db
+                .load_config()
+                .ok()
+                .flatten()
+                .map_or(false, |disk_config| disk_config.checksums);
+            // Keep checksums enabled even if the flag is omitted. Enabling them for an existing
+            // database is rejected by the compatibility check below.
+            db.config.db_checksums |= on_disk;
+            on_disk
+        } else {
+            db.config.db_checksums
+        };
+        db.hot_db.set_checksums(checksums);
+        db.cold_db.set_checksums(checksums);
+        db.blobs_db.set_checksums(checksums);
+
         // Load the previous split slot from the database (if any). This ensures we can
         // stop and restart correctly. This needs to occur *before* running any migrations
         // because some migrations load states and depend on the split.
@@ -373,6 +405,19 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.state_cache.lock().len()
     }
 
+    /// Cull the state cache down to half of its capacity, if it is any larger.
+    pub fn shrink_state_cache(&self) {
+        let mut state_cache = self.state_cache.lock();
+        let target_len = state_cache.capacity() / 2;
+        if let Some(excess) = state_cache
+            .len()
+            .checked_sub(target_len)
+            .filter(|&excess| excess > 0)
+        {
+            state_cache.cull(excess);
+        }
+    }
+
     /// Store a block and update the LRU cache.
     pub fn put_block(
         &self,
@@ -402,7 +447,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         let (blinded_block, payload) = block.into();
 
         // Store blinded block.
-        self.blinded_block_as_kv_store_ops(key, &blinded_block, ops);
+        self.blinded_block_as_kv_store_ops(key, &blinded_block, ops)?;
 
         // Store execution payload if present.
         if let Some(ref execution_payload) = payload {
@@ -421,12 +466,49 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         key: &Hash256,
         blinded_block: &SignedBeaconBlock<E, BlindedPayload<E>>,
         ops: &mut Vec<KeyValueStoreOp>,
-    ) {
+    ) -> Result<(), Error> {
         let db_key = get_key_for_col(DBColumn::BeaconBlock.into(), key.as_bytes());
-        ops.push(KeyValueStoreOp::PutKeyValue(
-            db_key,
-            blinded_block.as_ssz_bytes(),
-        ));
+        let bytes = self
+            .config
+            .column_compression(DBColumn::BeaconBlock)
+            .compress(
+                blinded_block.as_ssz_bytes(),
+                self.config.hot_db_compression_level,
+            )?;
+        ops.push(KeyValueStoreOp::PutKeyValue(db_key, bytes));
+        ops.push(Self::block_slot_as_kv_store_op(key, blinded_block.slot()));
+        Ok(())
+    }
+
+    /// Prepare an entry for the block root to slot index.
+    pub fn block_slot_as_kv_store_op(block_root: &Hash256, slot: Slot) -> KeyValueStoreOp {
+        let db_key = get_key_for_col(DBColumn::BeaconBlockSlot.into(), block_root.as_bytes());
+        KeyValueStoreOp::PutKeyValue(db_key, slot.as_ssz_bytes())
+    }
+
+    /// Fetch the slot of a block without loading the block itself.
+    ///
+    /// Blocks stored prior to the introduction of the block slot index have their slot read
+    /// directly from the block's SSZ bytes.
+    pub fn get_block_slot(&self, block_root: &Hash256) -> Result<Option<Slot>, Error> {
+        if let Some(block) = self.block_cache.lock().get_block(block_root) {
+            return Ok(Some(block.slot()));
+        }
+
+        if let Some(bytes) = self
+            .hot_db
+            .get_bytes(DBColumn::BeaconBlockSlot.into(), block_root.as_bytes())?
+        {
+            return Ok(Some(Slot::from_ssz_bytes(&bytes)?));
+        }
+
+        self.hot_db
+            .get_bytes(DBColumn::BeaconBlock.into(), block_root.as_bytes())?
+            .map(|bytes| {
+                let bytes = decompress(bytes)?;
+                Ok(SignedBlindedBeaconBlock::<E>::slot_from_ssz_bytes(&bytes)?)
+            })
+            .transpose()
     }
 
     pub fn try_get_full_block(
@@ -547,9 +629,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ) -> Result<Option<SignedBeaconBlock<E, Payload>>, Error> {
         self.hot_db
             .get_bytes(DBColumn::BeaconBlock.into(), block_root.as_bytes())?
-            .map(|block_bytes| decoder(&block_bytes))
+            .map(|block_bytes| Ok(decoder(&decompress(block_bytes)?)?))
             .transpose()
-            .map_err(|e| e.into())
     }
 
     /// Load the execution payload for a block from disk.
@@ -595,11 +676,31 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             .key_exists(DBColumn::BeaconBlock.into(), block_root.as_bytes())
     }
 
+    /// Return the number of bytes stored on disk for a block, excluding its payload, if any.
+    pub fn block_size(&self, block_root: &Hash256) -> Result<Option<usize>, Error> {
+        self.hot_db
+            .value_size(DBColumn::BeaconBlock.into(), block_root.as_bytes())
+    }
+
+    /// Return the number of bytes stored on disk for the execution payload of a block, if any.
+    pub fn execution_payload_size(&self, block_root: &Hash256) -> Result<Option<usize>, Error> {
+        self.hot_db
+            .value_size(DBColumn::ExecPayload.into(), block_root.as_bytes())
+    }
+
+    /// Return the number of bytes stored on disk for the blobs of a block, if any.
+    pub fn blobs_size(&self, block_root: &Hash256) -> Result<Option<usize>, Error> {
+        self.blobs_db
+            .value_size(DBColumn::BeaconBlob.into(), block_root.as_bytes())
+    }
+
     /// Delete a block from the store and the block cache.
     pub fn delete_block(&self, block_root: &Hash256) -> Result<(), Error> {
         self.block_cache.lock().delete(block_root);
         self.hot_db
             .key_delete(DBColumn::BeaconBlock.into(), block_root.as_bytes())?;
+        self.hot_db
+            .key_delete(DBColumn::BeaconBlockSlot.into(), block_root.as_bytes())?;
         self.hot_db
             .key_delete(DBColumn::ExecPayload.into(), block_root.as_bytes())?;
         self.blobs_db
@@ -610,7 +711,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.blobs_db.put_bytes(
             DBColumn::BeaconBlob.into(),
             block_root.as_bytes(),
-            &blobs.as_ssz_bytes(),
+            &self.compress_blobs(&blobs)?,
         )?;
         self.block_cache.lock().put_blobs(*block_root, blobs);
         Ok(())
@@ -621,9 +722,23 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         key: &Hash256,
         blobs: BlobSidecarList<E>,
         ops: &mut Vec<KeyValueStoreOp>,
-    ) {
+    ) -> Result<(), Error> {
         let db_key = get_key_for_col(DBColumn::BeaconBlob.into(), key.as_bytes());
-        ops.push(KeyValueStoreOp::PutKeyValue(db_key, blobs.as_ssz_bytes()));
+        ops.push(KeyValueStoreOp::PutKeyValue(
+            db_key,
+            self.compress_blobs(&blobs)?,
+        ));
+        Ok(())
+    }
+
+    /// Serialize and compress `blobs` for the blobs database.
+    ///
+    /// Blob sidecars are fixed-size SSZ containers beginning with a small index, so uncompressed
+    /// lists are never mistaken for compressed ones.
+    fn compress_blobs(&self, blobs: &BlobSidecarList<E>) -> Result<Vec<u8>, Error> {
+        self.config
+            .column_compression(DBColumn::BeaconBlob)
+            .compress(blobs.as_ssz_bytes(), self.config.hot_db_compression_level)
     }
 
     pub fn put_state_summary(
@@ -851,10 +966,17 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         state_root: &Hash256,
     ) -> Result<Option<BeaconState<E>>, Error> {
         if let Some(HotStateSummary {
+            slot,
             epoch_boundary_state_root,
             ..
         }) = self.load_hot_state_summary(state_root)?
         {
+            // The summary of an unpersisted epoch boundary state points to an earlier state.
+            let epoch_boundary_state_root = if slot % E::slots_per_epoch() == 0 {
+                *state_root
+            } else {
+                epoch_boundary_state_root
+            };
             // NOTE: minor inefficiency here because we load an unnecessary hot state summary
             let (state, _) = self.load_hot_state(&epoch_boundary_state_root)?.ok_or(
                 HotColdDBError::MissingEpochBoundaryState(epoch_boundary_state_root),
@@ -906,7 +1028,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 }
 
                 StoreOp::PutBlobs(block_root, blobs) => {
-                    self.blobs_as_kv_store_ops(&block_root, blobs, &mut key_value_batch);
+                    self.blobs_as_kv_store_ops(&block_root, blobs, &mut key_value_batch)?;
                 }
 
                 StoreOp::PutStateSummary(state_root, summary) => {
@@ -926,6 +1048,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 StoreOp::DeleteBlock(block_root) => {
                     let key = get_key_for_col(DBColumn::BeaconBlock.into(), block_root.as_bytes());
                     key_value_batch.push(KeyValueStoreOp::DeleteKey(key));
+
+                    let slot_key =
+                        get_key_for_col(DBColumn::BeaconBlockSlot.into(), block_root.as_bytes());
+                    key_value_batch.push(KeyValueStoreOp::DeleteKey(slot_key));
                 }
 
                 StoreOp::DeleteBlobs(block_root) => {
@@ -1053,7 +1179,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
                 StoreOp::DeleteBlobs(_) => (),
 
-                StoreOp::DeleteExecutionPayload(_) => (),
+                // The cached block is full, so it must not outlive its payload.
+                StoreOp::DeleteExecutionPayload(block_root) => {
+                    guard.delete_block(&block_root);
+                }
 
                 StoreOp::KeyValueOp(_) => (),
             }
@@ -1080,8 +1209,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
     /// Store a post-finalization state efficiently in the hot database.
     ///
-    /// On an epoch boundary, store a full state. On an intermediate slot, store
-    /// just a backpointer to the nearest epoch boundary.
+    /// On an epoch boundary which is a multiple of `StoreConfig::epochs_per_state_persist`, store a
+    /// full state. On any other slot, store just a backpointer to the state that block replay
+    /// should start from.
     pub fn store_hot_state(
         &self,
         state_root: &Hash256,
@@ -1108,27 +1238,99 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             return Ok(());
         }
 
-        // On the epoch boundary, store the full state.
+        // On the epoch boundary, store the full state if it is due to be persisted. Otherwise,
+        // point the summary at the latest persisted state to replay from.
+        let mut hot_state_summary = HotStateSummary::new(state_root, state)?;
         if state.slot() % E::slots_per_epoch() == 0 {
-            trace!(
-                self.log,
-                "Storing full state on epoch boundary";
-                "slot" => state.slot().as_u64(),
-                "state_root" => format!("{:?}", state_root)
-            );
-            store_full_state(state_root, state, ops)?;
+            if let Some(persisted_slot) = self.previous_persisted_slot(state.slot()) {
+                trace!(
+                    self.log,
+                    "Storing summary of epoch boundary state";
+                    "slot" => state.slot().as_u64(),
+                    "persisted_slot" => persisted_slot.as_u64(),
+                    "state_root" => format!("{:?}", state_root)
+                );
+                hot_state_summary.epoch_boundary_state_root = *state
+                    .get_state_root(persisted_slot)
+                    .map_err(HotColdDBError::HotStateSummaryError)?;
+            } else {
+                trace!(
+                    self.log,
+                    "Storing full state on epoch boundary";
+                    "slot" => state.slot().as_u64(),
+                    "state_root" => format!("{:?}", state_root)
+                );
+                store_full_state(
+                    state_root,
+                    state,
+                    self.config.column_compression(DBColumn::BeaconState),
+                    self.config.hot_db_compression_level,
+                    ops,
+                )?;
+            }
         }
 
         // Store a summary of the state.
         // We store one even for the epoch boundary states, as we may need their slots
         // when doing a look up by state root.
-        let hot_state_summary = HotStateSummary::new(state_root, state)?;
         let op = hot_state_summary.as_kv_store_op(*state_root);
         ops.push(op);
 
         Ok(())
     }
 
+    /// Return the slot of the latest persisted full state prior to the epoch boundary `slot`, or
+    /// `None` if the full state at `slot` should itself be persisted.
+    ///
+    /// The split state is always persisted, so no state prior to it is returned.
+    fn previous_persisted_slot(&self, slot: Slot) -> Option<Slot> {
+        let slots_per_persist = E::slots_per_epoch() * self.config.epochs_per_state_persist;
+        if slot % slots_per_persist == 0 {
+            return None;
+        }
+        let persisted_slot = std::cmp::max(
+            slot / slots_per_persist * slots_per_persist,
+            self.get_split_slot(),
+        );
+        (persisted_slot < slot).then_some(persisted_slot)
+    }
+
+    /// Load the full state that block replay to the hot state at `slot` should start from.
+    ///
+    /// This is the state at `epoch_boundary_state_root`, which is itself replayed if it is an epoch
+    /// boundary state which wasn't persisted. If finalization has since pruned the persisted
+    /// state that replay would start from, the split state is used instead.
+    fn load_hot_replay_base(
+        &self,
+        slot: Slot,
+        epoch_boundary_state_root: Hash256,
+    ) -> Result<BeaconState<E>, Error> {
+        if let Some(state) = get_full_state(&self.hot_db, &epoch_boundary_state_root, &self.spec)? {
+            return Ok(state);
+        }
+
+        if let Some(HotStateSummary {
+            epoch_boundary_state_root: persisted_state_root,
+            ..
+        }) = self.load_hot_state_summary(&epoch_boundary_state_root)?
+        {
+            if persisted_state_root != epoch_boundary_state_root {
+                if let Some((state, _)) = self.load_hot_state(&epoch_boundary_state_root)? {
+                    return Ok(state);
+                }
+            }
+        }
+
+        let split = self.get_split_info();
+        if slot >= split.slot {
+            if let Some(state) = get_full_state(&self.hot_db, &split.state_root, &self.spec)? {
+                return Ok(state);
+            }
+        }
+
+        Err(HotColdDBError::MissingEpochBoundaryState(epoch_boundary_state_root).into())
+    }
+
     /// Get a post-finalization state from the database or store.
     pub fn get_hot_state(&self, state_root: &Hash256) -> Result<Option<BeaconState<E>>, Error> {
         if let Some(state) = self.state_cache.lock().get_by_state_root(*state_root) {
@@ -1166,7 +1368,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
     /// Load a post-finalization state from the hot database.
     ///
-    /// Will replay blocks from the nearest epoch boundary.
+    /// Will replay blocks from the nearest persisted state.
     ///
     /// Return the `(state, latest_block_root)` where `latest_block_root` is the root of the last
     /// block applied to `state`.
@@ -1188,10 +1390,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             epoch_boundary_state_root,
         }) = self.load_hot_state_summary(state_root)?
         {
-            let mut boundary_state =
-                get_full_state(&self.hot_db, &epoch_boundary_state_root, &self.spec)?.ok_or(
-                    HotColdDBError::MissingEpochBoundaryState(epoch_boundary_state_root),
-                )?;
+            let mut boundary_state = self.load_hot_replay_base(slot, epoch_boundary_state_root)?;
 
             // Immediately rebase the state from disk on the finalized state so that we can reuse
             // parts of the tree for state root calculation in `replay_blocks`.
@@ -1200,14 +1399,14 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 .rebase_on_finalized(&mut boundary_state, &self.spec)?;
 
             // Optimization to avoid even *thinking* about replaying blocks if we're already
-            // on an epoch boundary.
-            let mut state = if slot % E::slots_per_epoch() == 0 {
+            // on a persisted state.
+            let mut state = if boundary_state.slot() == slot {
                 boundary_state
             } else {
                 // Cache ALL intermediate states that are reached during block replay. We may want
                 // to restrict this in future to only cache epoch boundary states. At worst we will
-                // cache up to 32 states for each state loaded, which should not flush out the cache
-                // entirely.
+                // cache up to 32 states for each epoch replayed, which should not flush out the
+                // cache entirely.
                 let state_cache_hook = |state_root, state: &mut BeaconState<E>| {
                     // Ensure all caches are built before attempting to cache.
                     state.update_tree_hash_cache()?;
@@ -1541,8 +1740,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             .blobs_db
             .get_bytes(DBColumn::BeaconBlob.into(), block_root.as_bytes())?
         {
-            Some(ref blobs_bytes) => {
-                let blobs = BlobSidecarList::from_ssz_bytes(blobs_bytes)?;
+            Some(blobs_bytes) => {
+                let blobs = BlobSidecarList::from_ssz_bytes(&decompress(blobs_bytes)?)?;
                 self.block_cache
                     .lock()
                     .put_blobs(*block_root, blobs.clone());
@@ -1605,7 +1804,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Load the database schema version from disk.
-    fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
+    pub fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
         self.hot_db.get(&SCHEMA_VERSION_KEY)
     }
 
@@ -1931,6 +2130,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// Verify that a parsed config is valid.
     fn verify_config(config: &StoreConfig) -> Result<(), HotColdDBError> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
+        Self::verify_epochs_per_state_persist(config.epochs_per_state_persist)?;
         Self::verify_epochs_per_blob_prune(config.epochs_per_blob_prune)
     }
 
@@ -1964,6 +2164,24 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Check that full states are persisted at least once per `SlotsPerHistoricalRoot`, so that the
+    /// root of the latest persisted state can be read from the `state_roots` of every other state.
+    fn verify_epochs_per_state_persist(
+        epochs_per_state_persist: u64,
+    ) -> Result<(), HotColdDBError> {
+        let max_epochs_per_state_persist =
+            E::SlotsPerHistoricalRoot::to_u64() / E::slots_per_epoch();
+        if epochs_per_state_persist > 0 && epochs_per_state_persist <= max_epochs_per_state_persist
+        {
+            Ok(())
+        } else {
+            Err(HotColdDBError::InvalidEpochsPerStatePersist {
+                epochs_per_state_persist,
+                max_epochs_per_state_persist,
+            })
+        }
+    }
+
     // Check that epochs_per_blob_prune is at least 1 epoch to avoid attempting to prune the same
     // epochs over and over again.
     fn verify_epochs_per_blob_prune(epochs_per_blob_prune: u64) -> Result<(), HotColdDBError> {
@@ -2318,6 +2536,189 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(())
     }
 
+    /// Try to prune blocks and blobs older than the configured `prune_history_before` slot,
+    /// moving the anchor's `oldest_block_slot` forward.
+    ///
+    /// Blocks within `MIN_EPOCHS_FOR_BLOCK_REQUESTS` of the current epoch (approximated from the
+    /// split slot) are always retained. The pin is also rounded down to a restore point, so that
+    /// historic states from the pin onwards can still be reconstructed from the remaining blocks.
+    ///
+    /// Like `try_prune_blobs`, this function assumes that the split is stationary while it runs.
+    pub fn try_prune_history(&self) -> Result<(), Error> {
+        let Some(prune_history_before) = self.config.prune_history_before else {
+            return Ok(());
+        };
+
+        let split = self.get_split_info();
+        let min_current_epoch = split.slot.epoch(E::slots_per_epoch()) + 2;
+        let min_block_request_slot = min_current_epoch
+            .saturating_sub(self.spec.min_epochs_for_block_requests)
+            .start_slot(E::slots_per_epoch());
+        let target_slot = std::cmp::min(
+            std::cmp::min(prune_history_before, min_block_request_slot),
+            split.slot,
+        ) / self.config.slots_per_restore_point
+            * self.config.slots_per_restore_point;
+
+        let oldest_block_slot = self.get_oldest_block_slot();
+        if target_slot <= oldest_block_slot {
+            debug!(
+                self.log,
+                "Block history is pruned";
+                "oldest_block_slot" => oldest_block_slot,
+                "prune_history_before" => prune_history_before,
+                "target_slot" => target_slot,
+            );
+            return Ok(());
+        }
+
+        info!(
+            self.log,
+            "Pruning block history";
+            "oldest_block_slot" => oldest_block_slot,
+            "target_slot" => target_slot,
+            "info" => "you may notice degraded I/O performance while this runs"
+        );
+
+        // Each block root occupies a contiguous range of slots, so a block can be deleted as soon
+        // as the next root is reached. The block at `target_slot` (or the most recent block prior
+        // to it, if the slot was skipped) is retained, as is the genesis block.
+        //
+        // Deletions are written in batches of `PRUNE_HISTORY_BATCH_SIZE` blocks, each of which
+        // moves the anchor forward to the oldest block it retains.
+        let oldest_blob_slot = self.get_blob_info().oldest_blob_slot;
+        let mut ops = vec![];
+        let mut blocks_pruned = 0;
+        let mut genesis_block_root = None;
+        let mut prev_block = None;
+        let mut new_oldest_block_slot = oldest_block_slot;
+
+        for res in self.forwards_block_roots_iterator_until(
+            oldest_block_slot,
+            target_slot,
+            || {
+                let (_, split_state) = self
+                    .get_advanced_hot_state(split.block_root, split.slot, split.state_root)?
+                    .ok_or(HotColdDBError::MissingSplitState(
+                        split.state_root,
+                        split.slot,
+                    ))?;
+
+                Ok((split_state, split.block_root))
+            },
+            &self.spec,
+        )? {
+            let (block_root, slot) = res?;
+
+            if slot == self.spec.genesis_slot {
+                genesis_block_root = Some(block_root);
+            }
+
+            match prev_block {
+                Some((prev_root, _)) if prev_root == block_root => (),
+                Some((prev_root, prev_slot)) => {
+                    if Some(prev_root) != genesis_block_root {
+                        ops.push(StoreOp::DeleteBlock(prev_root));
+                        ops.push(StoreOp::DeleteExecutionPayload(prev_root));
+                        // Blobs prior to the oldest blob slot have already been pruned.
+                        if oldest_blob_slot.map_or(false, |blob_slot| prev_slot >= blob_slot) {
+                            ops.push(StoreOp::DeleteBlobs(prev_root));
+                        }
+                        blocks_pruned += 1;
+                    }
+                    prev_block = Some((block_root, slot));
+
+                    if blocks_pruned % PRUNE_HISTORY_BATCH_SIZE == 0 && !ops.is_empty() {
+                        new_oldest_block_slot = self.write_prune_history_batch(
+                            std::mem::take(&mut ops),
+                            block_root,
+                            target_slot,
+                        )?;
+                    }
+                }
+                None => prev_block = Some((block_root, slot)),
+            }
+
+            if slot >= target_slot {
+                break;
+            }
+        }
+
+        if let Some((oldest_block_root, _)) = prev_block.filter(|_| !ops.is_empty()) {
+            new_oldest_block_slot =
+                self.write_prune_history_batch(ops, oldest_block_root, target_slot)?;
+        }
+
+        info!(
+            self.log,
+            "Block history pruning complete";
+            "blocks_pruned" => blocks_pruned,
+            "oldest_block_slot" => new_oldest_block_slot,
+        );
+
+        Ok(())
+    }
+
+    /// Write a batch of block deletions from `try_prune_history`, moving the anchor forward to
+    /// `oldest_block_root` and pruning the blob info to match.
+    ///
+    /// Return the new oldest block slot.
+    fn write_prune_history_batch(
+        &self,
+        mut ops: Vec<StoreOp<E>>,
+        oldest_block_root: Hash256,
+        target_slot: Slot,
+    ) -> Result<Slot, Error> {
+        let oldest_block = self
+            .get_blinded_block(&oldest_block_root)?
+            .ok_or(Error::BlockNotFound(oldest_block_root))?;
+
+        let old_anchor = self.get_anchor_info();
+        let new_anchor = if let Some(old_anchor) = old_anchor.clone() {
+            AnchorInfo {
+                oldest_block_slot: oldest_block.slot(),
+                oldest_block_parent: oldest_block.parent_root(),
+                state_upper_limit: std::cmp::max(old_anchor.state_upper_limit, target_slot),
+                ..old_anchor
+            }
+        } else {
+            AnchorInfo {
+                anchor_slot: self.spec.genesis_slot,
+                oldest_block_slot: oldest_block.slot(),
+                oldest_block_parent: oldest_block.parent_root(),
+                state_upper_limit: target_slot,
+                state_lower_limit: self.spec.genesis_slot,
+            }
+        };
+        let new_oldest_block_slot = new_anchor.oldest_block_slot;
+        ops.push(StoreOp::KeyValueOp(
+            self.compare_and_set_anchor_info(old_anchor, Some(new_anchor))?,
+        ));
+
+        // Blobs are never retained for blocks which have been pruned.
+        let blob_info = self.get_blob_info();
+        if let Some(oldest_blob_slot) = blob_info.oldest_blob_slot {
+            if oldest_blob_slot < new_oldest_block_slot {
+                let new_blob_info = BlobInfo {
+                    oldest_blob_slot: Some(new_oldest_block_slot),
+                    blobs_db: blob_info.blobs_db,
+                };
+                ops.push(StoreOp::KeyValueOp(
+                    self.compare_and_set_blob_info(blob_info, new_blob_info)?,
+                ));
+            }
+        }
+
+        self.do_atomically_with_block_and_blobs_cache(ops)?;
+        debug!(
+            self.log,
+            "Wrote block history pruning batch";
+            "oldest_block_slot" => new_oldest_block_slot,
+        );
+
+        Ok(new_oldest_block_slot)
+    }
+
     /// This function fills in missing block roots between last restore point slot and split
     /// slot, if any.
     pub fn heal_freezer_block_roots_at_split(&self) -> Result<(), Error> {
@@ -2486,6 +2887,37 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(())
     }
 
+    /// Return operations which delete the full epoch boundary states whose latest block is one of
+    /// `block_roots` from the hot database, keeping their summaries.
+    ///
+    /// States deleted by these operations can no longer be loaded, so `block_roots` must only
+    /// contain blocks which will never be built upon. The summaries are deleted as normal once
+    /// the split passes them.
+    pub fn epoch_boundary_state_deletion_ops(
+        &self,
+        block_roots: &HashSet<Hash256>,
+    ) -> Result<Vec<StoreOp<'static, E>>, Error> {
+        let split = self.get_split_info();
+        let mut ops = vec![];
+        for res in self
+            .hot_db
+            .iter_column::<Hash256>(DBColumn::BeaconStateSummary)
+        {
+            let (state_root, summary_bytes) = res?;
+            let summary = HotStateSummary::from_ssz_bytes(&summary_bytes)?;
+
+            if summary.slot > split.slot
+                && summary.slot % E::slots_per_epoch() == 0
+                && block_roots.contains(&summary.latest_block_root)
+            {
+                let state_key =
+                    get_key_for_col(DBColumn::BeaconState.into(), state_root.as_bytes());
+                ops.push(StoreOp::KeyValueOp(KeyValueStoreOp::DeleteKey(state_key)));
+            }
+        }
+        Ok(ops)
+    }
+
     /// Prune states from the hot database which are prior to the split.
     ///
     /// This routine is important for cleaning up advanced states which are stored in the database
@@ -2625,8 +3057,15 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
         cold_db_ops.push(op);
 
         if slot % store.config.slots_per_restore_point == 0 {
-            let state: BeaconState<E> = get_full_state(&store.hot_db, &state_root, &store.spec)?
-                .ok_or(HotColdDBError::MissingStateToFreeze(state_root))?;
+            // The state may not have been persisted in full, in which case it is replayed.
+            let state: BeaconState<E> =
+                match get_full_state(&store.hot_db, &state_root, &store.spec)? {
+                    Some(state) => state,
+                    None => store
+                        .load_hot_state(&state_root)?
+                        .map(|(state, _)| state)
+                        .ok_or(HotColdDBError::MissingStateToFreeze(state_root))?,
+                };
 
             store.store_cold_state(&state_root, &state, &mut cold_db_ops)?;
 
@@ -2655,6 +3094,23 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     // Flush to disk all the states that have just been migrated to the cold store.
     store.cold_db.sync()?;
 
+    // Block replay to hot states falls back to the split state, so it must be persisted in full
+    // before the split is updated.
+    if !store.hot_db.key_exists(
+        DBColumn::BeaconState.into(),
+        finalized_state_root.as_bytes(),
+    )? {
+        let mut ops = vec![];
+        store_full_state(
+            &finalized_state_root,
+            finalized_state,
+            store.config.column_compression(DBColumn::BeaconState),
+            store.config.hot_db_compression_level,
+            &mut ops,
+        )?;
+        store.hot_db.do_atomically(ops)?;
+    }
+
     {
         let mut split_guard = store.split.write();
         let latest_split_slot = split_guard.slot;
@@ -2751,6 +3207,10 @@ fn no_state_root_iter() -> Option<std::iter::Empty<Result<(Hash256, Slot), Error
 pub struct HotStateSummary {
     pub slot: Slot,
     pub latest_block_root: Hash256,
+    /// The root of the state that block replay starts from.
+    ///
+    /// For an epoch boundary state which wasn't persisted in full, this is the latest persisted
+    /// state prior to it.
     epoch_boundary_state_root: Hash256,
 }
 
@@ -1,3 +1,4 @@
+use crate::compression::{decompress, CompressionAlgorithm};
 use crate::*;
 use ssz::{DecodeError, Encode};
 use ssz_derive::Encode;
@@ -5,11 +6,16 @@ use ssz_derive::Encode;
 pub fn store_full_state<E: EthSpec>(
     state_root: &Hash256,
     state: &BeaconState<E>,
+    compression: CompressionAlgorithm,
+    compression_level: i32,
     ops: &mut Vec<KeyValueStoreOp>,
 ) -> Result<(), Error> {
     let bytes = {
         let _overhead_timer = metrics::start_timer(&metrics::BEACON_STATE_WRITE_OVERHEAD_TIMES);
-        StorageContainer::new(state).as_ssz_bytes()
+        compression.compress(
+            StorageContainer::new(state).as_ssz_bytes(),
+            compression_level,
+        )?
     };
     metrics::inc_counter_by(&metrics::BEACON_STATE_WRITE_BYTES, bytes.len() as u64);
     metrics::inc_counter(&metrics::BEACON_STATE_WRITE_COUNT);
@@ -28,12 +34,13 @@ pub fn get_full_state<KV: KeyValueStore<E>, E: EthSpec>(
     match db.get_bytes(DBColumn::BeaconState.into(), state_root.as_bytes())? {
         Some(bytes) => {
             let overhead_timer = metrics::start_timer(&metrics::BEACON_STATE_READ_OVERHEAD_TIMES);
-            let container = StorageContainer::from_ssz_bytes(&bytes, spec)?;
+            let num_bytes = bytes.len();
+            let container = StorageContainer::from_ssz_bytes(&decompress(bytes)?, spec)?;
 
             metrics::stop_timer(overhead_timer);
             metrics::stop_timer(total_timer);
             metrics::inc_counter(&metrics::BEACON_STATE_READ_COUNT);
-            metrics::inc_counter_by(&metrics::BEACON_STATE_READ_BYTES, bytes.len() as u64);
+            metrics::inc_counter_by(&metrics::BEACON_STATE_READ_BYTES, num_bytes as u64);
 
             Ok(Some(container.try_into()?))
         }
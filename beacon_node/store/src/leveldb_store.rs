@@ -1,4 +1,6 @@
 use super::*;
+use crate::checksum::{append_checksum, is_checksummed_column, verify_checksum};
+use crate::config::{DBTuning, HOT_DB_TUNING};
 use crate::hot_cold_store::HotColdDBError;
 use leveldb::compaction::Compaction;
 use leveldb::database::batch::{Batch, Writebatch};
@@ -6,7 +8,7 @@ use leveldb::database::kv::KV;
 use leveldb::database::Database;
 use leveldb::error::Error as LevelDBError;
 use leveldb::iterator::{Iterable, KeyIterator, LevelDBIterator};
-use leveldb::options::{Options, ReadOptions, WriteOptions};
+use leveldb::options::{Compression, Options, ReadOptions, WriteOptions};
 use parking_lot::Mutex;
 use std::marker::PhantomData;
 use std::path::Path;
@@ -16,15 +18,29 @@ pub struct LevelDB<E: EthSpec> {
     db: Database<BytesKey>,
     /// A mutex to synchronise sensitive read-write transactions.
     transaction_mutex: Mutex<()>,
+    /// Whether values are stored with checksums. See `checksum`.
+    checksums: bool,
     _phantom: PhantomData<E>,
 }
 
 impl<E: EthSpec> LevelDB<E> {
     /// Open a database at `path`, creating a new database if one does not already exist.
     pub fn open(path: &Path) -> Result<Self, Error> {
+        Self::open_with_tuning(path, &HOT_DB_TUNING)
+    }
+
+    /// Open a database at `path` with backend options suited to the values it will hold.
+    pub fn open_with_tuning(path: &Path, tuning: &DBTuning) -> Result<Self, Error> {
         let mut options = Options::new();
 
         options.create_if_missing = true;
+        options.write_buffer_size = Some(tuning.write_buffer_size);
+        options.block_size = Some(tuning.block_size);
+        options.compression = if tuning.compression {
+            Compression::Snappy
+        } else {
+            Compression::No
+        };
 
         let db = Database::open(path, options)?;
         let transaction_mutex = Mutex::new(());
@@ -32,10 +48,37 @@ impl<E: EthSpec> LevelDB<E> {
         Ok(Self {
             db,
             transaction_mutex,
+            checksums: false,
             _phantom: PhantomData,
         })
     }
 
+    /// Enable or disable checksums, which must match the values already in the database.
+    pub fn set_checksums(&mut self, checksums: bool) {
+        self.checksums = checksums;
+    }
+
+    /// Append a checksum to `value` if it should have one.
+    fn encode_value(&self, col: &str, column_key: &[u8], value: &[u8]) -> Vec<u8> {
+        if self.checksums && is_checksummed_column(col) {
+            append_checksum(column_key, value)
+        } else {
+            value.to_vec()
+        }
+    }
+
+    /// Verify and remove the checksum from `bytes` if it should have one.
+    fn decode_value(&self, col: &str, column_key: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        if self.checksums && is_checksummed_column(col) {
+            verify_checksum(column_key, bytes).map_err(|e| {
+                metrics::inc_counter_vec(&metrics::DISK_DB_CORRUPTION_COUNT, &[col]);
+                e
+            })
+        } else {
+            Ok(bytes)
+        }
+    }
+
     fn read_options(&self) -> ReadOptions<BytesKey> {
         ReadOptions::new()
     }
@@ -58,13 +101,14 @@ impl<E: EthSpec> LevelDB<E> {
         opts: WriteOptions,
     ) -> Result<(), Error> {
         let column_key = get_key_for_col(col, key);
+        let val = self.encode_value(col, &column_key, val);
 
         metrics::inc_counter_vec(&metrics::DISK_DB_WRITE_COUNT, &[col]);
         metrics::inc_counter_vec_by(&metrics::DISK_DB_WRITE_BYTES, &[col], val.len() as u64);
         let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
 
         self.db
-            .put(opts, BytesKey::from_vec(column_key), val)
+            .put(opts, BytesKey::from_vec(column_key), &val)
             .map_err(Into::into)
     }
 
@@ -94,20 +138,15 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         metrics::inc_counter_vec(&metrics::DISK_DB_READ_COUNT, &[col]);
         let timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
 
-        self.db
-            .get(self.read_options(), BytesKey::from_vec(column_key))
-            .map_err(Into::into)
-            .map(|opt| {
-                opt.map(|bytes| {
-                    metrics::inc_counter_vec_by(
-                        &metrics::DISK_DB_READ_BYTES,
-                        &[col],
-                        bytes.len() as u64,
-                    );
-                    metrics::stop_timer(timer);
-                    bytes
-                })
-            })
+        let opt = self
+            .db
+            .get(self.read_options(), BytesKey::from_vec(column_key.clone()))?;
+        opt.map(|bytes| {
+            metrics::inc_counter_vec_by(&metrics::DISK_DB_READ_BYTES, &[col], bytes.len() as u64);
+            metrics::stop_timer(timer);
+            self.decode_value(col, &column_key, bytes)
+        })
+        .transpose()
     }
 
     /// Return `true` if `key` exists in `column`.
@@ -122,6 +161,22 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
             .map(|val| val.is_some())
     }
 
+    /// Return the number of bytes stored on disk for `key` in `column`.
+    ///
+    /// The value is only measured, so it is neither decoded nor added to the block cache.
+    fn value_size(&self, col: &str, key: &[u8]) -> Result<Option<usize>, Error> {
+        let column_key = get_key_for_col(col, key);
+
+        metrics::inc_counter_vec(&metrics::DISK_DB_EXISTS_COUNT, &[col]);
+
+        let mut read_options = self.read_options();
+        read_options.fill_cache = false;
+        self.db
+            .get(read_options, BytesKey::from_vec(column_key))
+            .map_err(Into::into)
+            .map(|val| val.map(|bytes| bytes.len()))
+    }
+
     /// Removes `key` from `column`.
     fn key_delete(&self, col: &str, key: &[u8]) -> Result<(), Error> {
         let column_key = get_key_for_col(col, key);
@@ -139,6 +194,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
             match op {
                 KeyValueStoreOp::PutKeyValue(key, value) => {
                     let col = get_col_from_key(&key).unwrap_or("unknown".to_owned());
+                    let value = self.encode_value(&col, &key, &value);
                     metrics::inc_counter_vec(&metrics::DISK_DB_WRITE_COUNT, &[&col]);
                     metrics::inc_counter_vec_by(
                         &metrics::DISK_DB_WRITE_BYTES,
@@ -194,6 +250,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
                             unexpected_key: bytes_key.clone(),
                         }
                     })?;
+                    let value = self.decode_value(column.as_str(), &bytes_key.key, value)?;
                     Ok((K::from_bytes(key)?, value))
                 }),
         )
@@ -209,6 +266,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
             iter.take_while(move |(key, _)| key.key.starts_with(start_key.key.as_slice()))
                 .map(move |(bytes_key, value)| {
                     let subkey = &bytes_key.key[column.as_bytes().len()..];
+                    let value = self.decode_value(column.as_str(), &bytes_key.key, value)?;
                     Ok((Vec::from(subkey), value))
                 }),
         )
@@ -7,9 +7,11 @@
 //!
 //! Provides a simple API for storing/retrieving all types that sometimes needs type-hints. See
 //! tests for implementation examples.
+pub mod checksum;
 mod chunk_writer;
 pub mod chunked_iter;
 pub mod chunked_vector;
+pub mod compression;
 pub mod config;
 pub mod consensus_context;
 pub mod errors;
@@ -21,8 +23,10 @@ mod leveldb_store;
 mod memory_store;
 pub mod metadata;
 pub mod metrics;
+pub mod parallel_iter;
 mod partial_beacon_state;
 pub mod reconstruct;
+mod root_lookup;
 pub mod state_cache;
 
 pub mod iter;
@@ -33,6 +37,7 @@ pub use self::consensus_context::OnDiskConsensusContext;
 pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split};
 pub use self::leveldb_store::LevelDB;
 pub use self::memory_store::MemoryStore;
+pub use self::parallel_iter::{ParallelFreezerIter, ParallelIterConfig, FREEZER_READER_THREADS};
 pub use self::partial_beacon_state::PartialBeaconState;
 pub use crate::metadata::BlobInfo;
 pub use errors::Error;
@@ -68,6 +73,11 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     /// Return `true` if `key` exists in `column`.
     fn key_exists(&self, column: &str, key: &[u8]) -> Result<bool, Error>;
 
+    /// Return the number of bytes stored for `key` in `column`, without decoding the value.
+    fn value_size(&self, column: &str, key: &[u8]) -> Result<Option<usize>, Error> {
+        Ok(self.get_bytes(column, key)?.map(|bytes| bytes.len()))
+    }
+
     /// Removes `key` from `column`.
     fn key_delete(&self, column: &str, key: &[u8]) -> Result<(), Error>;
 
@@ -228,6 +238,9 @@ pub enum DBColumn {
     BeaconMeta,
     #[strum(serialize = "blk")]
     BeaconBlock,
+    /// For the mapping from block roots to the slots of blocks in the `BeaconBlock` column.
+    #[strum(serialize = "bbs")]
+    BeaconBlockSlot,
     #[strum(serialize = "blb")]
     BeaconBlob,
     /// For full `BeaconState`s in the hot database (finalized or fork-boundary states).
@@ -248,6 +261,9 @@ pub enum DBColumn {
     BeaconChain,
     #[strum(serialize = "opo")]
     OpPool,
+    /// For the observed block producer, attester and aggregator caches.
+    #[strum(serialize = "obc")]
+    ObservedCaches,
     #[strum(serialize = "etc")]
     Eth1Cache,
     #[strum(serialize = "frk")]
@@ -299,6 +315,7 @@ impl DBColumn {
             Self::OverflowLRUCache => 33, // DEPRECATED
             Self::BeaconMeta
             | Self::BeaconBlock
+            | Self::BeaconBlockSlot
             | Self::BeaconState
             | Self::BeaconBlob
             | Self::BeaconStateSummary
@@ -306,6 +323,7 @@ impl DBColumn {
             | Self::ExecPayload
             | Self::BeaconChain
             | Self::OpPool
+            | Self::ObservedCaches
             | Self::Eth1Cache
             | Self::ForkChoice
             | Self::PubkeyCache
@@ -396,6 +414,55 @@ mod tests {
         test_impl(store);
     }
 
+    #[test]
+    fn simplediskdb_immutable_tuning() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+        let store = LevelDB::open_with_tuning(path, &config::IMMUTABLE_DB_TUNING).unwrap();
+
+        test_impl(store);
+    }
+
+    #[test]
+    fn simplediskdb_checksums() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+        let mut store = LevelDB::open(path).unwrap();
+        store.set_checksums(true);
+
+        test_impl(store);
+    }
+
+    #[test]
+    fn diskdb_detects_corruption() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+        let mut store = LevelDB::<MinimalEthSpec>::open(path).unwrap();
+        store.set_checksums(true);
+
+        let key = Hash256::random();
+        store.put(&key, &StorableThing { a: 1, b: 42 }).unwrap();
+
+        // Flip a bit in the stored value, bypassing the checksum.
+        let col = DBColumn::BeaconBlock.as_str();
+        store.set_checksums(false);
+        let mut bytes = store.get_bytes(col, key.as_bytes()).unwrap().unwrap();
+        bytes[0] ^= 1;
+        store.put_bytes(col, key.as_bytes(), &bytes).unwrap();
+        store.set_checksums(true);
+
+        match store.get::<StorableThing>(&key) {
+            Err(Error::Corruption {
+                column,
+                key: corrupt_key,
+            }) => {
+                assert_eq!(column, col);
+                assert_eq!(corrupt_key, key.as_bytes());
+            }
+            other => panic!("expected corruption, got {other:?}"),
+        }
+    }
+
     #[test]
     fn memorydb() {
         let store = MemoryStore::open();
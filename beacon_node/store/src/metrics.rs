@@ -50,6 +50,11 @@ lazy_static! {
         "Total number of deletions from the hot on-disk DB",
         &["col"],
     );
+    pub static ref DISK_DB_CORRUPTION_COUNT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "store_disk_db_corruption_count_total",
+        "Total number of values read from an on-disk DB with an invalid checksum",
+        &["col"],
+    );
     /*
      * Beacon State
      */
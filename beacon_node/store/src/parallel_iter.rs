@@ -0,0 +1,465 @@
+//! Parallel iteration over the finalized blocks and states in the freezer database.
+//!
+//! The requested slot range is split into chunks which are loaded on a pool of reader threads and
+//! yielded in slot order. Chunks of states are aligned to restore points, so that each restore
+//! point is loaded at most once per chunk. The chunks which have been loaded but not yet consumed
+//! are limited both in number and in size, so a slow consumer stalls the loading of further chunks
+//! rather than buffering the entire range.
+//!
+//! The reader threads are a fixed pool of plain OS threads shared by all iterators, rather than
+//! tasks on the executor or the global rayon pool, so that long-running analysis workloads don't
+//! compete with block import for threads, and concurrent iterations don't multiply the number of
+//! threads reading from the freezer.
+use crate::hot_cold_store::HotColdDB;
+use crate::{Error, ItemStore};
+use lazy_static::lazy_static;
+use parking_lot::{Condvar, Mutex};
+use ssz::Encode;
+use std::collections::{BTreeMap, VecDeque};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use types::{BeaconState, EthSpec, Hash256, SignedBlindedBeaconBlock, Slot};
+
+/// The number of reader threads shared by all parallel freezer iterators.
+pub const FREEZER_READER_THREADS: usize = 4;
+
+/// Configuration for `HotColdDB::par_iter_finalized_blocks` and
+/// `HotColdDB::par_iter_finalized_states`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParallelIterConfig {
+    /// The maximum number of chunks which may be loading at once. Loads are also limited by the
+    /// `FREEZER_READER_THREADS` shared by all iterators.
+    pub max_concurrent_loads: usize,
+    /// The maximum number of chunks which may be loaded but not yet consumed.
+    pub max_chunks_in_flight: usize,
+    /// The maximum size in bytes of the chunks which have been loaded but not yet consumed, as
+    /// measured by the SSZ length of their items. The next chunk to be consumed is always loaded,
+    /// even if it alone exceeds this limit.
+    pub max_bytes_in_flight: usize,
+    /// The number of slots covered by each chunk of blocks.
+    pub block_chunk_slots: u64,
+    /// The maximum number of states in each chunk of states.
+    pub states_per_chunk: usize,
+}
+
+impl Default for ParallelIterConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_loads: FREEZER_READER_THREADS,
+            max_chunks_in_flight: 8,
+            max_bytes_in_flight: 512 * 1024 * 1024,
+            block_chunk_slots: 256,
+            states_per_chunk: 4,
+        }
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed pool of threads which run the chunk loads of every iterator.
+struct ReaderPool {
+    jobs: Mutex<VecDeque<Job>>,
+    condvar: Condvar,
+}
+
+lazy_static! {
+    static ref READER_POOL: Result<Arc<ReaderPool>, String> =
+        ReaderPool::spawn(FREEZER_READER_THREADS);
+}
+
+impl ReaderPool {
+    fn spawn(threads: usize) -> Result<Arc<Self>, String> {
+        let pool = Arc::new(Self {
+            jobs: Mutex::new(VecDeque::new()),
+            condvar: Condvar::new(),
+        });
+        for i in 0..threads {
+            let pool = pool.clone();
+            std::thread::Builder::new()
+                .name(format!("freezer_reader_{}", i))
+                .spawn(move || pool.run())
+                .map_err(|e| format!("{:?}", e))?;
+        }
+        Ok(pool)
+    }
+
+    fn run(&self) {
+        loop {
+            let job = {
+                let mut jobs = self.jobs.lock();
+                loop {
+                    if let Some(job) = jobs.pop_front() {
+                        break job;
+                    }
+                    self.condvar.wait(&mut jobs);
+                }
+            };
+            job();
+        }
+    }
+
+    fn submit(&self, job: Job) {
+        self.jobs.lock().push_back(job);
+        self.condvar.notify_one();
+    }
+}
+
+fn reader_pool() -> Result<&'static ReaderPool, Error> {
+    READER_POOL
+        .as_deref()
+        .map_err(|e| Error::ParallelIterSpawnFailed(e.clone()))
+}
+
+/// An iterator over items which are loaded in chunks on the shared pool of reader threads.
+///
+/// Items are yielded in order. Iteration stops after the first error. Dropping the iterator stops
+/// the loading of further chunks.
+pub struct ParallelFreezerIter<T> {
+    shared: Arc<Shared<T>>,
+    pool: &'static ReaderPool,
+    next_chunk: usize,
+    current: std::vec::IntoIter<T>,
+}
+
+type LoadChunk<T> = dyn Fn(usize) -> Result<Vec<T>, Error> + Send + Sync;
+
+struct Shared<T> {
+    state: Mutex<SharedState<T>>,
+    condvar: Condvar,
+    config: ParallelIterConfig,
+    num_chunks: usize,
+    load_chunk: Box<LoadChunk<T>>,
+    item_bytes: fn(&T) -> usize,
+}
+
+struct SharedState<T> {
+    /// The index of the next chunk to be claimed by a reader.
+    next_unclaimed: usize,
+    /// The index of the next chunk to be consumed by the iterator.
+    next_unconsumed: usize,
+    /// The number of chunks which are being loaded.
+    loading: usize,
+    /// Chunks which have been loaded but not yet consumed, with their sizes in bytes.
+    loaded: BTreeMap<usize, Result<(Vec<T>, usize), Error>>,
+    /// The total size in bytes of the chunks in `loaded`.
+    bytes_in_flight: usize,
+    cancelled: bool,
+}
+
+impl<T: Send + 'static> Shared<T> {
+    /// Claim chunks and submit them to the pool until there are none left, or a limit is reached.
+    fn schedule(self: &Arc<Self>, pool: &'static ReaderPool) {
+        let mut state = self.state.lock();
+        while !state.cancelled
+            && state.next_unclaimed < self.num_chunks
+            && state.loading < self.config.max_concurrent_loads
+            && state.next_unclaimed < state.next_unconsumed + self.config.max_chunks_in_flight
+            && (state.bytes_in_flight < self.config.max_bytes_in_flight
+                || state.next_unclaimed == state.next_unconsumed)
+        {
+            let chunk = state.next_unclaimed;
+            state.next_unclaimed += 1;
+            state.loading += 1;
+            let shared = self.clone();
+            pool.submit(Box::new(move || shared.load(chunk, pool)));
+        }
+    }
+
+    /// Load `chunk` on a reader thread, and then schedule any chunks which are now permitted.
+    fn load(self: &Arc<Self>, chunk: usize, pool: &'static ReaderPool) {
+        let result = if self.state.lock().cancelled {
+            None
+        } else {
+            Some(
+                catch_unwind(AssertUnwindSafe(|| {
+                    let items = (self.load_chunk)(chunk)?;
+                    let bytes = items.iter().map(self.item_bytes).sum();
+                    Ok((items, bytes))
+                }))
+                .unwrap_or(Err(Error::ParallelIterReaderPanicked)),
+            )
+        };
+
+        let mut state = self.state.lock();
+        state.loading -= 1;
+        let Some(result) = result.filter(|_| !state.cancelled) else {
+            return;
+        };
+        if let Ok((_, bytes)) = &result {
+            state.bytes_in_flight += bytes;
+        }
+        state.loaded.insert(chunk, result);
+        self.condvar.notify_all();
+        drop(state);
+
+        self.schedule(pool);
+    }
+}
+
+impl<T: Send + 'static> ParallelFreezerIter<T> {
+    /// Load `num_chunks` chunks using `load_chunk` on the shared pool of reader threads.
+    ///
+    /// The size of each item is measured using `item_bytes`.
+    fn spawn<F>(
+        config: &ParallelIterConfig,
+        num_chunks: usize,
+        item_bytes: fn(&T) -> usize,
+        load_chunk: F,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(usize) -> Result<Vec<T>, Error> + Send + Sync + 'static,
+    {
+        let pool = reader_pool()?;
+        let iter = Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(SharedState {
+                    next_unclaimed: 0,
+                    next_unconsumed: 0,
+                    loading: 0,
+                    loaded: BTreeMap::new(),
+                    bytes_in_flight: 0,
+                    cancelled: false,
+                }),
+                condvar: Condvar::new(),
+                config: ParallelIterConfig {
+                    max_concurrent_loads: std::cmp::max(config.max_concurrent_loads, 1),
+                    max_chunks_in_flight: std::cmp::max(config.max_chunks_in_flight, 1),
+                    ..*config
+                },
+                num_chunks,
+                load_chunk: Box::new(load_chunk),
+                item_bytes,
+            }),
+            pool,
+            next_chunk: 0,
+            current: vec![].into_iter(),
+        };
+        iter.shared.schedule(pool);
+
+        Ok(iter)
+    }
+}
+
+impl<T: Send + 'static> Iterator for ParallelFreezerIter<T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.current.next() {
+                return Some(Ok(item));
+            }
+            if self.next_chunk >= self.shared.num_chunks {
+                return None;
+            }
+
+            let result = {
+                let mut state = self.shared.state.lock();
+                let result = loop {
+                    if let Some(result) = state.loaded.remove(&self.next_chunk) {
+                        break result;
+                    }
+                    self.shared.condvar.wait(&mut state);
+                };
+                if let Ok((_, bytes)) = &result {
+                    state.bytes_in_flight -= bytes;
+                }
+                state.next_unconsumed += 1;
+                result
+            };
+            self.next_chunk += 1;
+
+            match result {
+                Ok((items, _)) => {
+                    self.shared.schedule(self.pool);
+                    self.current = items.into_iter();
+                }
+                Err(e) => {
+                    self.next_chunk = self.shared.num_chunks;
+                    self.cancel();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl<T> ParallelFreezerIter<T> {
+    fn cancel(&self) {
+        let mut state = self.shared.state.lock();
+        state.cancelled = true;
+        state.loaded.clear();
+        state.bytes_in_flight = 0;
+    }
+}
+
+impl<T> Drop for ParallelFreezerIter<T> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Iterate over the finalized blocks with slots from `start_slot` to `end_slot` (inclusive)
+    /// in slot order, loading them on multiple reader threads.
+    pub fn par_iter_finalized_blocks(
+        self: &Arc<Self>,
+        start_slot: Slot,
+        end_slot: Slot,
+        config: ParallelIterConfig,
+    ) -> Result<ParallelFreezerIter<(Hash256, SignedBlindedBeaconBlock<E>)>, Error> {
+        self.check_finalized_range(end_slot)?;
+
+        let chunk_slots = std::cmp::max(config.block_chunk_slots, 1);
+        let num_chunks = if start_slot > end_slot {
+            0
+        } else {
+            ((end_slot - start_slot).as_u64() / chunk_slots + 1) as usize
+        };
+
+        let store = self.clone();
+        ParallelFreezerIter::spawn(
+            &config,
+            num_chunks,
+            |(_, block)| block.ssz_bytes_len(),
+            move |chunk| {
+                let chunk_start = start_slot + chunk as u64 * chunk_slots;
+                let chunk_end = std::cmp::min(chunk_start + (chunk_slots - 1), end_slot);
+                store.load_finalized_blocks(chunk_start, chunk_end)
+            },
+        )
+    }
+
+    /// Iterate over the finalized states at every `step` slots from `start_slot` up to `end_slot`
+    /// (inclusive), loading them on multiple reader threads.
+    ///
+    /// The states between each pair of restore points are split into chunks of at most
+    /// `config.states_per_chunk` states. Each chunk is loaded by a single reader, which loads the
+    /// first state and then replays blocks to reach the others.
+    pub fn par_iter_finalized_states(
+        self: &Arc<Self>,
+        start_slot: Slot,
+        end_slot: Slot,
+        step: u64,
+        config: ParallelIterConfig,
+    ) -> Result<ParallelFreezerIter<(Slot, BeaconState<E>)>, Error> {
+        self.check_finalized_range(end_slot)?;
+
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let states_per_chunk = std::cmp::max(config.states_per_chunk, 1);
+        let mut chunks: Vec<Vec<Slot>> = vec![];
+        let mut slot = start_slot;
+        while slot <= end_slot {
+            let restore_point = slot.as_u64() / slots_per_restore_point;
+            match chunks.last_mut() {
+                Some(chunk)
+                    if chunk.len() < states_per_chunk
+                        && chunk
+                            .last()
+                            .map(|last| last.as_u64() / slots_per_restore_point)
+                            == Some(restore_point) =>
+                {
+                    chunk.push(slot)
+                }
+                _ => chunks.push(vec![slot]),
+            }
+            slot += std::cmp::max(step, 1);
+        }
+
+        let store = self.clone();
+        let num_chunks = chunks.len();
+        ParallelFreezerIter::spawn(
+            &config,
+            num_chunks,
+            |(_, state)| state.ssz_bytes_len(),
+            move |chunk| store.load_finalized_states(&chunks[chunk]),
+        )
+    }
+
+    fn check_finalized_range(&self, end_slot: Slot) -> Result<(), Error> {
+        let split_slot = self.get_split_slot();
+        if end_slot >= split_slot {
+            return Err(Error::ParallelIterRangeNotFinalized {
+                end_slot,
+                split_slot,
+            });
+        }
+        Ok(())
+    }
+
+    /// Load the finalized blocks with slots from `start_slot` to `end_slot` (inclusive).
+    fn load_finalized_blocks(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<(Hash256, SignedBlindedBeaconBlock<E>)>, Error> {
+        // Start one slot early so that a block prior to `start_slot` isn't mistaken for a block in
+        // the range if `start_slot` is skipped.
+        let block_roots = self.forwards_block_roots_iterator_until(
+            start_slot.saturating_sub(1_u64),
+            end_slot,
+            || Err(Error::HistoryUnavailable),
+            &self.spec,
+        )?;
+
+        let mut prev_block_root = None;
+        let mut blocks = vec![];
+        for result in block_roots {
+            let (block_root, slot) = result?;
+            if slot >= start_slot && prev_block_root != Some(block_root) {
+                let block = self
+                    .get_blinded_block(&block_root)?
+                    .ok_or(Error::BlockNotFound(block_root))?;
+                blocks.push((block_root, block));
+            }
+            prev_block_root = Some(block_root);
+        }
+        Ok(blocks)
+    }
+
+    /// Load the finalized states at `slots`, which must be ascending.
+    fn load_finalized_states(&self, slots: &[Slot]) -> Result<Vec<(Slot, BeaconState<E>)>, Error> {
+        let (Some(&first_slot), Some(&last_slot)) = (slots.first(), slots.last()) else {
+            return Ok(vec![]);
+        };
+
+        let mut state = self
+            .load_cold_state_by_slot(first_slot)?
+            .ok_or(Error::HistoryUnavailable)?;
+        let mut blocks = if first_slot < last_slot {
+            self.load_finalized_blocks(first_slot + 1, last_slot)?
+        } else {
+            vec![]
+        }
+        .into_iter()
+        .map(|(_, block)| block)
+        .peekable();
+
+        let mut states = Vec::with_capacity(slots.len());
+        states.push((first_slot, state.clone()));
+        let mut prev_slot = first_slot;
+
+        for &slot in &slots[1..] {
+            let mut blocks_to_replay = vec![];
+            while let Some(block) = blocks.next_if(|block| block.slot() <= slot) {
+                blocks_to_replay.push(block);
+            }
+            let state_root_iter = self.forwards_state_roots_iterator_until(
+                prev_slot,
+                slot,
+                || Err(Error::HistoryUnavailable),
+                &self.spec,
+            )?;
+            state =
+                self.replay_blocks(state, blocks_to_replay, slot, Some(state_root_iter), None)?;
+            states.push((slot, state.clone()));
+            prev_slot = slot;
+        }
+
+        Ok(states)
+    }
+}
@@ -1,14 +1,18 @@
 //! Implementation of historic state reconstruction (given complete block history).
+//!
+//! Replay is sequential: the state at the start of each restore point range is only available
+//! once the previous range has been replayed, because a checkpoint synced node stores no freezer
+//! states between the genesis state and the split. Only block loading is parallelised, with the
+//! workers loading the blocks of the next range while the current range is replayed.
 use crate::hot_cold_store::{HotColdDB, HotColdDBError};
-use crate::{Error, ItemStore};
-use itertools::{process_results, Itertools};
+use crate::{Error, ItemStore, KeyValueStoreOp};
 use slog::info;
 use state_processing::{
     per_block_processing, per_slot_processing, BlockSignatureStrategy, ConsensusContext,
     VerifyBlockRoot,
 };
 use std::sync::Arc;
-use types::{EthSpec, Hash256};
+use types::{BeaconState, EthSpec, Hash256, SignedBlindedBeaconBlock, Slot};
 
 impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
 where
@@ -49,13 +53,18 @@ where
         // Use a dummy root, as we never read the block for the upper limit state.
         let upper_limit_block_root = Hash256::repeat_byte(0xff);
 
-        let block_root_iter = self.forwards_block_roots_iterator(
+        let mut block_root_iter = self.forwards_block_roots_iterator(
             lower_limit_slot,
             upper_limit_state,
             upper_limit_block_root,
             &self.spec,
         )?;
 
+        // The first block root is that of the lower limit state, which is already reconstructed.
+        let (mut prev_block_root, _) = block_root_iter
+            .next()
+            .ok_or(Error::StateReconstructionDidNotComplete)??;
+
         // The state to be advanced.
         let mut state = self
             .load_cold_state_by_slot(lower_limit_slot)?
@@ -63,97 +72,85 @@ where
 
         state.build_caches(&self.spec)?;
 
-        process_results(block_root_iter, |iter| -> Result<(), Error> {
-            let mut io_batch = vec![];
-
-            let mut prev_state_root = None;
-
-            for ((prev_block_root, _), (block_root, slot)) in iter.tuple_windows() {
-                let is_skipped_slot = prev_block_root == block_root;
-
-                let block = if is_skipped_slot {
-                    None
-                } else {
-                    Some(
-                        self.get_blinded_block(&block_root)?
-                            .ok_or(Error::BlockNotFound(block_root))?,
-                    )
-                };
-
-                // Advance state to slot.
-                per_slot_processing(&mut state, prev_state_root.take(), &self.spec)
-                    .map_err(HotColdDBError::BlockReplaySlotError)?;
-
-                // Apply block.
-                if let Some(block) = block {
-                    let mut ctxt = ConsensusContext::new(block.slot())
-                        .set_current_block_root(block_root)
-                        .set_proposer_index(block.message().proposer_index());
-
-                    per_block_processing(
-                        &mut state,
-                        &block,
-                        BlockSignatureStrategy::NoVerification,
-                        VerifyBlockRoot::True,
-                        &mut ctxt,
-                        &self.spec,
-                    )
-                    .map_err(HotColdDBError::BlockReplayBlockError)?;
-
-                    prev_state_root = Some(block.state_root());
-                }
+        let workers = self.config.reconstruct_workers.get();
+        let mut prev_state_root = None;
+
+        // Work one restore point range at a time. While a range is replayed, the blocks of the
+        // next range are loaded by the workers.
+        let mut range = self.next_reconstruction_range(
+            &mut block_root_iter,
+            &mut prev_block_root,
+            upper_limit_slot,
+        )?;
+        let mut blocks = self.load_reconstruction_blocks(&range, workers)?;
+
+        loop {
+            if range.is_empty() {
+                // Should always reach the `upper_limit_slot` and finish below.
+                return Err(Error::StateReconstructionDidNotComplete);
+            }
+
+            let next_range = self.next_reconstruction_range(
+                &mut block_root_iter,
+                &mut prev_block_root,
+                upper_limit_slot,
+            )?;
+
+            let (replay_result, next_blocks) = std::thread::scope(|scope| {
+                let loader = scope.spawn(|| self.load_reconstruction_blocks(&next_range, workers));
+                let replay_result = self.replay_reconstruction_range(
+                    &mut state,
+                    &mut prev_state_root,
+                    range,
+                    blocks,
+                );
+                let next_blocks = loader
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e));
+                (replay_result, next_blocks)
+            });
+
+            // Checkpoint progress at the end of the range, so that reconstruction resumes from
+            // here after a restart.
+            let (slot, state_root, io_batch) = replay_result?;
+
+            info!(
+                self.log,
+                "State reconstruction in progress";
+                "slot" => slot,
+                "remaining" => upper_limit_slot - 1 - slot
+            );
+
+            self.cold_db.do_atomically(io_batch)?;
 
-                let state_root = prev_state_root
-                    .ok_or(())
-                    .or_else(|_| state.update_tree_hash_cache())?;
-
-                // Stage state for storage in freezer DB.
-                self.store_cold_state(&state_root, &state, &mut io_batch)?;
-
-                // If the slot lies on an epoch boundary, commit the batch and update the anchor.
-                if slot % slots_per_restore_point == 0 || slot + 1 == upper_limit_slot {
-                    info!(
-                        self.log,
-                        "State reconstruction in progress";
-                        "slot" => slot,
-                        "remaining" => upper_limit_slot - 1 - slot
-                    );
-
-                    self.cold_db.do_atomically(std::mem::take(&mut io_batch))?;
-
-                    // Update anchor.
-                    let old_anchor = Some(anchor.clone());
-
-                    if slot + 1 == upper_limit_slot {
-                        // The two limits have met in the middle! We're done!
-                        // Perform one last integrity check on the state reached.
-                        let computed_state_root = state.update_tree_hash_cache()?;
-                        if computed_state_root != state_root {
-                            return Err(Error::StateReconstructionRootMismatch {
-                                slot,
-                                expected: state_root,
-                                computed: computed_state_root,
-                            });
-                        }
-
-                        self.compare_and_set_anchor_info_with_write(old_anchor, None)?;
-
-                        return Ok(());
-                    } else {
-                        // The lower limit has been raised, store it.
-                        anchor.state_lower_limit = slot;
-
-                        self.compare_and_set_anchor_info_with_write(
-                            old_anchor,
-                            Some(anchor.clone()),
-                        )?;
-                    }
+            // Update anchor.
+            let old_anchor = Some(anchor.clone());
+
+            if slot + 1 == upper_limit_slot {
+                // The two limits have met in the middle! We're done!
+                // Perform one last integrity check on the state reached.
+                let computed_state_root = state.update_tree_hash_cache()?;
+                if computed_state_root != state_root {
+                    return Err(Error::StateReconstructionRootMismatch {
+                        slot,
+                        expected: state_root,
+                        computed: computed_state_root,
+                    });
                 }
+
+                self.compare_and_set_anchor_info_with_write(old_anchor, None)?;
+
+                break;
+            } else {
+                // The lower limit has been raised, store it.
+                anchor.state_lower_limit = slot;
+
+                self.compare_and_set_anchor_info_with_write(old_anchor, Some(anchor.clone()))?;
             }
 
-            // Should always reach the `upper_limit_slot` and return early above.
-            Err(Error::StateReconstructionDidNotComplete)
-        })??;
+            range = next_range;
+            blocks = next_blocks?;
+        }
 
         // Check that the split point wasn't mutated during the state reconstruction process.
         // It shouldn't have been, due to the serialization of requests through the store migrator,
@@ -165,4 +162,124 @@ where
 
         Ok(())
     }
+
+    /// Take the block roots up to and including the next restore point (or the slot prior to
+    /// `upper_limit_slot`) from `block_root_iter`.
+    ///
+    /// A `None` root indicates a skipped slot.
+    fn next_reconstruction_range(
+        &self,
+        block_root_iter: &mut impl Iterator<Item = Result<(Hash256, Slot), Error>>,
+        prev_block_root: &mut Hash256,
+        upper_limit_slot: Slot,
+    ) -> Result<Vec<(Option<Hash256>, Slot)>, Error> {
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let mut range = vec![];
+
+        for res in block_root_iter.by_ref() {
+            let (block_root, slot) = res?;
+            let is_skipped_slot = *prev_block_root == block_root;
+            *prev_block_root = block_root;
+            range.push(((!is_skipped_slot).then_some(block_root), slot));
+
+            if slot % slots_per_restore_point == 0 || slot + 1 == upper_limit_slot {
+                break;
+            }
+        }
+
+        Ok(range)
+    }
+
+    /// Load the blocks for `range`, split between up to `workers` threads.
+    fn load_reconstruction_blocks(
+        &self,
+        range: &[(Option<Hash256>, Slot)],
+        workers: usize,
+    ) -> Result<Vec<Option<SignedBlindedBeaconBlock<E>>>, Error> {
+        let load_blocks = |range: &[(Option<Hash256>, Slot)]| {
+            range
+                .iter()
+                .map(|(block_root, _)| {
+                    block_root
+                        .map(|block_root| {
+                            self.get_blinded_block(&block_root)?
+                                .ok_or(Error::BlockNotFound(block_root))
+                        })
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>, Error>>()
+        };
+
+        if workers <= 1 || range.len() <= 1 {
+            return load_blocks(range);
+        }
+
+        let chunk_size = range.len().div_ceil(workers);
+        std::thread::scope(|scope| {
+            let handles = range
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || load_blocks(chunk)))
+                .collect::<Vec<_>>();
+
+            let mut blocks = Vec::with_capacity(range.len());
+            for handle in handles {
+                let chunk_blocks = handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+                blocks.extend(chunk_blocks);
+            }
+            Ok(blocks)
+        })
+    }
+
+    /// Apply the `blocks` of `range` to `state`, returning the final slot and state root along
+    /// with the freezer DB ops for the states reconstructed.
+    fn replay_reconstruction_range(
+        &self,
+        state: &mut BeaconState<E>,
+        prev_state_root: &mut Option<Hash256>,
+        range: Vec<(Option<Hash256>, Slot)>,
+        blocks: Vec<Option<SignedBlindedBeaconBlock<E>>>,
+    ) -> Result<(Slot, Hash256, Vec<KeyValueStoreOp>), Error> {
+        let mut io_batch = vec![];
+        let mut last = None;
+
+        for ((block_root, slot), block) in range.into_iter().zip(blocks) {
+            // Advance state to slot.
+            per_slot_processing(state, prev_state_root.take(), &self.spec)
+                .map_err(HotColdDBError::BlockReplaySlotError)?;
+
+            // Apply block.
+            if let (Some(block_root), Some(block)) = (block_root, block) {
+                let mut ctxt = ConsensusContext::new(block.slot())
+                    .set_current_block_root(block_root)
+                    .set_proposer_index(block.message().proposer_index());
+
+                per_block_processing(
+                    state,
+                    &block,
+                    BlockSignatureStrategy::NoVerification,
+                    VerifyBlockRoot::True,
+                    &mut ctxt,
+                    &self.spec,
+                )
+                .map_err(HotColdDBError::BlockReplayBlockError)?;
+
+                *prev_state_root = Some(block.state_root());
+            }
+
+            let state_root = match *prev_state_root {
+                Some(state_root) => state_root,
+                None => state.update_tree_hash_cache()?,
+            };
+
+            // Stage state for storage in freezer DB.
+            self.store_cold_state(&state_root, state, &mut io_batch)?;
+
+            last = Some((slot, state_root));
+        }
+
+        let (slot, state_root) = last.ok_or(Error::StateReconstructionDidNotComplete)?;
+        Ok((slot, state_root, io_batch))
+    }
 }
@@ -0,0 +1,86 @@
+//! Look-ups of the block and state roots at a slot which don't load any states.
+//!
+//! Roots prior to the split are read from the freezer's `block_roots` and `state_roots` vectors,
+//! whilst state roots after the split are found by scanning the hot state summaries. Each of
+//! these functions returns `None` if the root can't be found without loading a state, in which
+//! case the caller should fall back to iterating backwards from a state.
+use crate::hot_cold_store::{HotColdDB, HotStateSummary};
+use crate::{DBColumn, Error, ItemStore};
+use ssz::Decode;
+use types::{EthSpec, Hash256, Slot};
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Return the root of the canonical block at `slot` from the freezer database.
+    ///
+    /// As in `BeaconState::block_roots`, the root of the latest prior block is returned if `slot`
+    /// was skipped.
+    pub fn get_cold_block_root(&self, slot: Slot) -> Result<Option<Hash256>, Error> {
+        // Block roots are stored for all slots from the oldest block up to the split slot.
+        if slot < self.get_oldest_block_slot() || slot >= self.get_split_slot() {
+            return Ok(None);
+        }
+
+        self.forwards_block_roots_iterator_until(
+            slot,
+            slot,
+            || Err(Error::HistoryUnavailable),
+            &self.spec,
+        )?
+        .next()
+        .transpose()
+        .map(|next| next.map(|(block_root, _)| block_root))
+    }
+
+    /// Return the root of the canonical state at `slot` from the freezer database.
+    pub fn get_cold_state_root(&self, slot: Slot) -> Result<Option<Hash256>, Error> {
+        // State roots are stored for all slots prior to the latest restore point, apart from those
+        // between the historic state limits.
+        let (lower_limit, upper_limit) = self.get_historic_state_limits();
+        if self
+            .get_latest_restore_point_slot()
+            .map_or(true, |restore_point_slot| slot >= restore_point_slot)
+            || (slot > lower_limit && slot < upper_limit)
+        {
+            return Ok(None);
+        }
+
+        self.forwards_state_roots_iterator_until(
+            slot,
+            slot,
+            || Err(Error::HistoryUnavailable),
+            &self.spec,
+        )?
+        .next()
+        .transpose()
+        .map(|next| next.map(|(state_root, _)| state_root))
+    }
+
+    /// Return the root of the state in the hot database at `slot` whose latest block is
+    /// `latest_block_root`, found by scanning the hot state summaries.
+    pub fn get_hot_state_root(
+        &self,
+        slot: Slot,
+        latest_block_root: Hash256,
+    ) -> Result<Option<Hash256>, Error> {
+        if slot < self.get_split_slot() {
+            return Ok(None);
+        }
+
+        for res in self
+            .hot_db
+            .iter_column::<Hash256>(DBColumn::BeaconStateSummary)
+        {
+            let (state_root, summary_bytes) = res?;
+            let summary = HotStateSummary::from_ssz_bytes(&summary_bytes)?;
+            if summary.slot == slot && summary.latest_block_root == latest_block_root {
+                return Ok(Some(state_root));
+            }
+        }
+        Ok(None)
+    }
+}
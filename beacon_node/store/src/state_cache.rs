@@ -2,7 +2,11 @@ use crate::Error;
 use lru::LruCache;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::num::NonZeroUsize;
-use types::{BeaconState, ChainSpec, Epoch, EthSpec, Hash256, Slot};
+use std::sync::{Arc, Weak};
+use types::{
+    AttestationShufflingId, BeaconState, ChainSpec, CommitteeCache, Epoch, EthSpec, Hash256,
+    RelativeEpoch, Slot,
+};
 
 /// Fraction of the LRU cache to leave intact during culling.
 const CULL_EXEMPT_NUMERATOR: usize = 1;
@@ -36,6 +40,11 @@ pub struct StateCache<E: EthSpec> {
     states: LruCache<Hash256, BeaconState<E>>,
     block_map: BlockMap,
     max_epoch: Epoch,
+    /// The committee caches held by cached states, keyed by shuffling.
+    ///
+    /// States with the same shuffling share a single committee cache, rather than each holding
+    /// its own copy. Entries are dropped once no state holds their committee cache.
+    committee_caches: HashMap<AttestationShufflingId, Weak<CommitteeCache>>,
 }
 
 #[derive(Debug)]
@@ -53,6 +62,7 @@ impl<E: EthSpec> StateCache<E> {
             states: LruCache::new(capacity),
             block_map: BlockMap::default(),
             max_epoch: Epoch::new(0),
+            committee_caches: HashMap::new(),
         }
     }
 
@@ -68,7 +78,7 @@ impl<E: EthSpec> StateCache<E> {
         &mut self,
         state_root: Hash256,
         block_root: Hash256,
-        state: BeaconState<E>,
+        mut state: BeaconState<E>,
     ) -> Result<(), Error> {
         if state.slot() % E::slots_per_epoch() != 0 {
             return Err(Error::FinalizedStateUnaligned);
@@ -96,14 +106,20 @@ impl<E: EthSpec> StateCache<E> {
         }
 
         // Update finalized state.
+        self.share_committee_caches(block_root, &mut state)?;
         self.finalized_state = Some(FinalizedState { state_root, state });
         Ok(())
     }
 
-    /// Rebase the given state on the finalized state in order to reduce its memory consumption.
+    /// Rebase the given state on a cached state in order to reduce its memory consumption.
+    ///
+    /// The finalized state is used as the base unless it is so far behind `state` that their
+    /// block and state root vectors have been entirely overwritten, as happens during long periods
+    /// of non-finality. In that case the cached state with the closest slot to `state` is used
+    /// instead, as it is likely to share far more of its tree.
     ///
     /// This function should only be called on states that are likely not to already share tree
-    /// nodes with the finalized state, e.g. states loaded from disk.
+    /// nodes with the cached states, e.g. states loaded from disk.
     ///
     /// If the finalized state is not initialized this function is a no-op.
     pub fn rebase_on_finalized(
@@ -111,12 +127,27 @@ impl<E: EthSpec> StateCache<E> {
         state: &mut BeaconState<E>,
         spec: &ChainSpec,
     ) -> Result<(), Error> {
-        if let Some(finalized_state) = &self.finalized_state {
-            state.rebase_on(&finalized_state.state, spec)?;
+        if let Some(base) = self.rebase_base(state.slot()) {
+            state.rebase_on(base, spec)?;
         }
         Ok(())
     }
 
+    /// Return the cached state that a state at `slot` should be rebased on.
+    fn rebase_base(&self, slot: Slot) -> Option<&BeaconState<E>> {
+        let finalized_state = self.finalized_state.as_ref()?;
+
+        if slot >= finalized_state.state.slot() + E::slots_per_historical_root() as u64 {
+            self.states
+                .iter()
+                .map(|(_, cached_state)| cached_state)
+                .min_by_key(|cached_state| cached_state.slot().as_u64().abs_diff(slot.as_u64()))
+                .or(Some(&finalized_state.state))
+        } else {
+            Some(&finalized_state.state)
+        }
+    }
+
     /// Return a status indicating whether the state already existed in the cache.
     pub fn put_state(
         &mut self,
@@ -155,16 +186,63 @@ impl<E: EthSpec> StateCache<E> {
             self.cull(over_capacity + 1);
         }
 
-        // Insert the full state into the cache.
-        self.states.put(state_root, state.clone());
+        // Insert the full state into the cache, sharing its committee caches with other states.
+        let mut state = state.clone();
+        self.share_committee_caches(block_root, &mut state)?;
+        let slot = state.slot();
+        self.states.put(state_root, state);
 
         // Record the connection from block root and slot to this state.
-        let slot = state.slot();
         self.block_map.insert(block_root, slot, state_root);
 
         Ok(PutStateOutcome::New)
     }
 
+    /// Replace the committee caches of `state` with those already held by other cached states with
+    /// the same shuffling, and record any which aren't yet held.
+    ///
+    /// The `block_root` must be the root of the latest block applied to `state`.
+    fn share_committee_caches(
+        &mut self,
+        block_root: Hash256,
+        state: &mut BeaconState<E>,
+    ) -> Result<(), Error> {
+        // Forget committee caches which are no longer held by any state.
+        self.committee_caches
+            .retain(|_, committee_cache| committee_cache.strong_count() > 0);
+
+        for relative_epoch in [
+            RelativeEpoch::Previous,
+            RelativeEpoch::Current,
+            RelativeEpoch::Next,
+        ] {
+            let Ok(committee_cache) = state.committee_cache(relative_epoch) else {
+                continue;
+            };
+            let Ok(shuffling_id) = AttestationShufflingId::new(block_root, state, relative_epoch)
+            else {
+                continue;
+            };
+
+            match self
+                .committee_caches
+                .get(&shuffling_id)
+                .and_then(Weak::upgrade)
+            {
+                Some(shared) if !Arc::ptr_eq(&shared, committee_cache) => {
+                    // The shuffling ID determines the committees, so the caches are identical.
+                    state.set_committee_cache(relative_epoch, shared)?;
+                }
+                Some(_) => {}
+                None => {
+                    let committee_cache = Arc::downgrade(committee_cache);
+                    self.committee_caches.insert(shuffling_id, committee_cache);
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_by_state_root(&mut self, state_root: Hash256) -> Option<BeaconState<E>> {
         if let Some(ref finalized_state) = self.finalized_state {
             if state_root == finalized_state.state_root {
@@ -301,3 +379,121 @@ impl BlockMap {
         self.blocks.remove(block_root)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use beacon_chain::test_utils::BeaconChainHarness;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    fn get_state() -> BeaconState<E> {
+        let harness = BeaconChainHarness::builder(E::default())
+            .default_spec()
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build();
+        harness.advance_slot();
+        harness.get_current_state()
+    }
+
+    /// Return a copy of `state` at `slot` with distinct balances, as if loaded from disk.
+    fn state_at_slot(state: &BeaconState<E>, slot: u64) -> BeaconState<E> {
+        let mut state = state.clone();
+        *state.slot_mut() = Slot::new(slot);
+        for i in 0..state.balances().len() {
+            *state.get_balance_mut(i).unwrap() += slot;
+        }
+        state.apply_pending_mutations().unwrap();
+        state
+    }
+
+    /// Rebase `state` and check that it is unchanged by the rebase.
+    fn check_rebase(
+        cache: &StateCache<E>,
+        state: &mut BeaconState<E>,
+        expected_base_slot: u64,
+        spec: &ChainSpec,
+    ) {
+        let root = state.canonical_root().unwrap();
+        assert_eq!(
+            cache.rebase_base(state.slot()).unwrap().slot(),
+            expected_base_slot
+        );
+        cache.rebase_on_finalized(state, spec).unwrap();
+        assert_eq!(state.canonical_root().unwrap(), root);
+    }
+
+    #[test]
+    fn rebase_on_finalized_as_finalization_advances() {
+        let spec = E::default_spec();
+        let slots_per_epoch = E::slots_per_epoch();
+        let slots_per_historical_root = E::slots_per_historical_root() as u64;
+        let genesis_state = get_state();
+        let mut cache = StateCache::new(NonZeroUsize::new(8).unwrap());
+
+        // Without a finalized state rebasing is a no-op.
+        let mut state = state_at_slot(&genesis_state, 1);
+        let root = state.canonical_root().unwrap();
+        assert!(cache.rebase_base(state.slot()).is_none());
+        cache.rebase_on_finalized(&mut state, &spec).unwrap();
+        assert_eq!(state.canonical_root().unwrap(), root);
+
+        // Advance finalization one epoch at a time. States close to the finalized state are
+        // rebased on it.
+        for epoch in 0..4 {
+            let finalized_slot = epoch * slots_per_epoch;
+            let mut finalized_state = state_at_slot(&genesis_state, finalized_slot);
+            let finalized_root = finalized_state.canonical_root().unwrap();
+            cache
+                .update_finalized_state(
+                    finalized_root,
+                    Hash256::from_low_u64_be(finalized_slot),
+                    finalized_state,
+                )
+                .unwrap();
+
+            let mut state = state_at_slot(&genesis_state, finalized_slot + 1);
+            check_rebase(&cache, &mut state, finalized_slot, &spec);
+
+            // The finalized state is unaffected by rebasing states on it.
+            assert_eq!(
+                cache
+                    .get_by_state_root(finalized_root)
+                    .unwrap()
+                    .canonical_root()
+                    .unwrap(),
+                finalized_root
+            );
+        }
+        let finalized_slot = 3 * slots_per_epoch;
+
+        // During long non-finality states are rebased on the closest cached state.
+        let far_slots = [2, 3, 4].map(|i| finalized_slot + i * slots_per_historical_root);
+        for slot in far_slots {
+            let mut state = state_at_slot(&genesis_state, slot);
+            let state_root = state.canonical_root().unwrap();
+            cache
+                .put_state(state_root, Hash256::from_low_u64_be(slot), &state)
+                .unwrap();
+        }
+        for slot in far_slots {
+            let mut state = state_at_slot(&genesis_state, slot + 1);
+            check_rebase(&cache, &mut state, slot, &spec);
+        }
+
+        // Once finalization catches up, states are rebased on the finalized state again.
+        let finalized_slot = far_slots[1];
+        let mut finalized_state = state_at_slot(&genesis_state, finalized_slot);
+        cache
+            .update_finalized_state(
+                finalized_state.canonical_root().unwrap(),
+                Hash256::from_low_u64_be(finalized_slot),
+                finalized_state,
+            )
+            .unwrap();
+        let mut state = state_at_slot(&genesis_state, far_slots[2] - 1);
+        check_rebase(&cache, &mut state, finalized_slot, &spec);
+    }
+}
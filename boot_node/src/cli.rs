@@ -120,6 +120,36 @@ pub fn cli_app() -> Command {
                 .help("Disables discv5 packet filter. Useful for testing in smaller networks")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("http")
+                .long("http")
+                .help("Enable the HTTP API, which exposes the routing table, per-fork-digest peer \
+                      counts and allows the advertised ENR socket to be updated at runtime.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-address")
+                .long("http-address")
+                .requires("http")
+                .value_name("ADDRESS")
+                .help("Set the listen address for the HTTP API. The API is unauthenticated, \
+                      so it should not be exposed publicly.")
+                .default_value("127.0.0.1")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-port")
+                .long("http-port")
+                .requires("http")
+                .value_name("PORT")
+                .help("Set the listen TCP port for the HTTP API.")
+                .default_value("5056")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("network-dir")
             .value_name("NETWORK_DIR")
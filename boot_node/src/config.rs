@@ -8,7 +8,7 @@ use lighthouse_network::{
 };
 use serde::{Deserialize, Serialize};
 use ssz::Encode;
-use std::net::{SocketAddrV4, SocketAddrV6};
+use std::net::{IpAddr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::time::Duration;
 use std::{marker::PhantomData, path::PathBuf};
 use types::EthSpec;
@@ -20,6 +20,8 @@ pub struct BootNodeConfig<E: EthSpec> {
     pub local_enr: Enr,
     pub local_key: CombinedKey,
     pub discv5_config: discv5::Config,
+    /// The listen address of the HTTP API, if enabled.
+    pub http_address: Option<SocketAddr>,
     phantom: PhantomData<E>,
 }
 
@@ -163,11 +165,20 @@ impl<E: EthSpec> BootNodeConfig<E> {
             local_enr
         };
 
+        let http_address = if matches.get_flag("http") {
+            let address: IpAddr = clap_utils::parse_required(matches, "http-address")?;
+            let port: u16 = clap_utils::parse_required(matches, "http-port")?;
+            Some(SocketAddr::new(address, port))
+        } else {
+            None
+        };
+
         Ok(BootNodeConfig {
             boot_nodes,
             local_enr,
             local_key,
             discv5_config: network_config.discv5_config,
+            http_address,
             phantom: PhantomData,
         })
     }
@@ -185,6 +196,7 @@ pub struct BootNodeConfigSerialization {
     pub local_enr: Enr,
     pub disable_packet_filter: bool,
     pub enable_enr_auto_update: bool,
+    pub http_address: Option<SocketAddr>,
 }
 
 impl BootNodeConfigSerialization {
@@ -196,6 +208,7 @@ impl BootNodeConfigSerialization {
             local_enr,
             local_key: _,
             discv5_config,
+            http_address,
             phantom: _,
         } = config;
 
@@ -224,6 +237,7 @@ impl BootNodeConfigSerialization {
             local_enr: local_enr.clone(),
             disable_packet_filter: !discv5_config.enable_packet_filter,
             enable_enr_auto_update: discv5_config.enr_update,
+            http_address: *http_address,
         }
     }
 }
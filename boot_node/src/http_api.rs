@@ -0,0 +1,444 @@
+//! A minimal HTTP API for inspecting and updating a running boot node.
+//!
+//! The `Discv5` service is owned by the main server loop, so requests are forwarded to it over a
+//! channel and answered through a oneshot sender.
+
+use lighthouse_network::{discv5::Discv5, EnrExt, Eth2Enr};
+use serde::{Deserialize, Serialize};
+use slog::{info, Logger};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use tokio::sync::{mpsc, oneshot};
+use warp::Filter;
+
+/// The number of requests that may be queued for the server loop before requests are rejected.
+const REQUEST_CHANNEL_SIZE: usize = 16;
+
+/// Label used for routing table entries which do not advertise an `eth2` field.
+const UNKNOWN_FORK_DIGEST: &str = "unknown";
+
+/// A request from the HTTP server to the discv5 server loop.
+pub enum Request {
+    Enr(oneshot::Sender<EnrData>),
+    Peers(oneshot::Sender<Vec<PeerData>>),
+    PeerCounts(oneshot::Sender<PeerCountData>),
+    UpdateEnr(EnrUpdate, oneshot::Sender<Result<EnrData, String>>),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenericResponse<T> {
+    pub data: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnrData {
+    pub enr: String,
+    pub seq: u64,
+    pub node_id: String,
+    pub peer_id: String,
+    pub udp4_socket: Option<SocketAddr>,
+    pub udp6_socket: Option<SocketAddr>,
+    pub fork_digest: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeerData {
+    pub node_id: String,
+    pub peer_id: String,
+    pub enr: String,
+    pub connected: bool,
+    pub udp4_socket: Option<SocketAddr>,
+    pub udp6_socket: Option<SocketAddr>,
+    pub fork_digest: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeerCountData {
+    pub total: usize,
+    pub connected: usize,
+    /// Counts of routing table entries, keyed by the hex-encoded fork digest of their ENR.
+    pub fork_digests: BTreeMap<String, usize>,
+}
+
+/// A request to change the advertised UDP socket of the local ENR.
+///
+/// Missing fields are filled in from the current ENR, preferring IPv4.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnrUpdate {
+    #[serde(default)]
+    pub ip: Option<IpAddr>,
+    #[serde(default)]
+    pub udp_port: Option<u16>,
+}
+
+fn fork_digest(enr: &lighthouse_network::Enr) -> Option<String> {
+    enr.eth2()
+        .ok()
+        .map(|fork_id| hex::encode(fork_id.fork_digest))
+}
+
+pub fn enr_data(discv5: &Discv5) -> EnrData {
+    let enr = discv5.local_enr();
+    EnrData {
+        enr: enr.to_base64(),
+        seq: enr.seq(),
+        node_id: enr.node_id().to_string(),
+        peer_id: enr.peer_id().to_string(),
+        udp4_socket: enr.udp4_socket().map(Into::into),
+        udp6_socket: enr.udp6_socket().map(Into::into),
+        fork_digest: fork_digest(&enr),
+    }
+}
+
+pub fn peers(discv5: &Discv5) -> Vec<PeerData> {
+    discv5
+        .kbuckets()
+        .iter_ref()
+        .map(|entry| {
+            let enr = entry.node.value;
+            PeerData {
+                node_id: entry.node.key.preimage().to_string(),
+                peer_id: enr.peer_id().to_string(),
+                enr: enr.to_base64(),
+                connected: entry.status.is_connected(),
+                udp4_socket: enr.udp4_socket().map(Into::into),
+                udp6_socket: enr.udp6_socket().map(Into::into),
+                fork_digest: fork_digest(enr),
+            }
+        })
+        .collect()
+}
+
+pub fn peer_counts(discv5: &Discv5) -> PeerCountData {
+    let mut counts = PeerCountData {
+        total: 0,
+        connected: 0,
+        fork_digests: BTreeMap::new(),
+    };
+    for entry in discv5.kbuckets().iter_ref() {
+        counts.total += 1;
+        if entry.status.is_connected() {
+            counts.connected += 1;
+        }
+        let digest =
+            fork_digest(entry.node.value).unwrap_or_else(|| UNKNOWN_FORK_DIGEST.to_string());
+        *counts.fork_digests.entry(digest).or_default() += 1;
+    }
+    counts
+}
+
+/// Apply `update` to the local ENR, returning the resulting ENR.
+///
+/// A successful update increments the ENR sequence number, which peers learn of through
+/// subsequent PINGs and use to request the new record.
+pub fn update_enr(discv5: &mut Discv5, update: EnrUpdate, log: &Logger) -> Result<EnrData, String> {
+    let enr = discv5.local_enr();
+    let ip = update
+        .ip
+        .or_else(|| enr.ip4().map(IpAddr::V4))
+        .or_else(|| enr.ip6().map(IpAddr::V6))
+        .ok_or("the ENR has no IP address, one must be provided")?;
+    let port = update
+        .udp_port
+        .or_else(|| match ip {
+            IpAddr::V4(_) => enr.udp4(),
+            IpAddr::V6(_) => enr.udp6(),
+        })
+        .ok_or("the ENR has no UDP port for this IP version, one must be provided")?;
+    if port == 0 {
+        return Err("the UDP port must be non-zero".to_string());
+    }
+
+    let socket = SocketAddr::new(ip, port);
+    // The socket is a UDP socket.
+    const IS_TCP: bool = false;
+    if discv5.update_local_enr_socket(socket, IS_TCP) {
+        let enr = discv5.local_enr();
+        info!(
+            log,
+            "Local ENR updated";
+            "socket" => %socket,
+            "seq" => enr.seq(),
+            "enr" => enr.to_base64(),
+        );
+    }
+    Ok(enr_data(discv5))
+}
+
+/// Returns a channel receiving requests from the HTTP server along with the server future.
+pub fn serve(
+    listen_addr: SocketAddr,
+    log: Logger,
+) -> Result<(mpsc::Receiver<Request>, impl Future<Output = ()>), String> {
+    let (tx, rx) = mpsc::channel(REQUEST_CHANNEL_SIZE);
+
+    let (listening_socket, server) = warp::serve(routes(tx, log.clone()))
+        .try_bind_ephemeral(listen_addr)
+        .map_err(|e| format!("Unable to start boot node HTTP server: {e:?}"))?;
+
+    info!(log, "HTTP API started"; "listen_address" => %listening_socket);
+
+    Ok((rx, server))
+}
+
+/// The routes of the HTTP API, which forward requests to the server loop over `tx`.
+fn routes(
+    tx: mpsc::Sender<Request>,
+    log: Logger,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Infallible> + Clone {
+    let inner_tx = warp::any().map(move || tx.clone());
+
+    let base_path = warp::path("boot_node");
+
+    // GET boot_node/enr
+    let get_enr = base_path
+        .and(warp::path("enr"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(inner_tx.clone())
+        .then(|tx: mpsc::Sender<Request>| async move {
+            into_json_response(send_request(tx, Request::Enr).await).await
+        });
+
+    // GET boot_node/peers
+    let get_peers = base_path
+        .and(warp::path("peers"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(inner_tx.clone())
+        .then(|tx: mpsc::Sender<Request>| async move {
+            into_json_response(send_request(tx, Request::Peers).await).await
+        });
+
+    // GET boot_node/peer_count
+    let get_peer_count = base_path
+        .and(warp::path("peer_count"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(inner_tx.clone())
+        .then(|tx: mpsc::Sender<Request>| async move {
+            into_json_response(send_request(tx, Request::PeerCounts).await).await
+        });
+
+    // POST boot_node/enr
+    let post_enr = base_path
+        .and(warp::path("enr"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp_utils::json::json())
+        .and(inner_tx)
+        .then(|update: EnrUpdate, tx: mpsc::Sender<Request>| async move {
+            let result = send_request(tx, |sender| Request::UpdateEnr(update, sender))
+                .await
+                .and_then(|result| result.map_err(warp_utils::reject::custom_bad_request));
+            into_json_response(result).await
+        });
+
+    get_enr
+        .or(get_peers)
+        .or(get_peer_count)
+        .or(post_enr)
+        .recover(warp_utils::reject::handle_rejection)
+        .with(slog_logging(log))
+}
+
+/// Forward a request to the server loop and wait for its response.
+async fn send_request<T>(
+    tx: mpsc::Sender<Request>,
+    request: impl FnOnce(oneshot::Sender<T>) -> Request,
+) -> Result<T, warp::Rejection> {
+    let (sender, receiver) = oneshot::channel();
+    tx.try_send(request(sender))
+        .map_err(|e| warp_utils::reject::custom_server_error(format!("boot node is busy: {e}")))?;
+    receiver.await.map_err(|_| {
+        warp_utils::reject::custom_server_error("boot node dropped the request".to_string())
+    })
+}
+
+async fn into_json_response<T: Serialize>(
+    result: Result<T, warp::Rejection>,
+) -> warp::reply::Response {
+    let result = result.map(|data| warp::reply::json(&GenericResponse { data }));
+    warp_utils::reject::convert_rejection(result).await
+}
+
+fn slog_logging(log: Logger) -> warp::filters::log::Log<impl Fn(warp::filters::log::Info) + Clone> {
+    warp::log::custom(move |info| {
+        slog::debug!(
+            log,
+            "Processed HTTP API request";
+            "elapsed" => format!("{:?}", info.elapsed()),
+            "status" => info.status().to_string(),
+            "path" => info.path(),
+            "method" => info.method().to_string(),
+        );
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::test_logger;
+    use serde::de::DeserializeOwned;
+    use warp::http::StatusCode;
+
+    fn enr_data() -> EnrData {
+        EnrData {
+            enr: "enr:-test".to_string(),
+            seq: 1,
+            node_id: "node".to_string(),
+            peer_id: "peer".to_string(),
+            udp4_socket: Some(SocketAddr::new([127, 0, 0, 1].into(), 9000)),
+            udp6_socket: None,
+            fork_digest: Some("01020304".to_string()),
+        }
+    }
+
+    /// Answers requests from the HTTP API as the server loop would, with fixed responses.
+    fn spawn_server_loop(mut rx: mpsc::Receiver<Request>) {
+        tokio::spawn(async move {
+            while let Some(request) = rx.recv().await {
+                match request {
+                    Request::Enr(sender) => {
+                        let _ = sender.send(enr_data());
+                    }
+                    Request::Peers(sender) => {
+                        let _ = sender.send(vec![]);
+                    }
+                    Request::PeerCounts(sender) => {
+                        let _ = sender.send(PeerCountData {
+                            total: 2,
+                            connected: 1,
+                            fork_digests: BTreeMap::from([("01020304".to_string(), 2)]),
+                        });
+                    }
+                    Request::UpdateEnr(update, sender) => {
+                        let result = match update.udp_port {
+                            Some(0) => Err("the UDP port must be non-zero".to_string()),
+                            _ => Ok(EnrData {
+                                seq: 2,
+                                udp4_socket: update.ip.zip(update.udp_port).map(Into::into),
+                                ..enr_data()
+                            }),
+                        };
+                        let _ = sender.send(result);
+                    }
+                }
+            }
+        });
+    }
+
+    fn data<T: DeserializeOwned>(response: &warp::http::Response<warp::hyper::body::Bytes>) -> T {
+        serde_json::from_slice::<GenericResponse<T>>(response.body())
+            .unwrap()
+            .data
+    }
+
+    #[tokio::test]
+    async fn get_routes() {
+        let (tx, rx) = mpsc::channel(REQUEST_CHANNEL_SIZE);
+        spawn_server_loop(rx);
+        let routes = routes(tx, test_logger());
+
+        let response = warp::test::request()
+            .path("/boot_node/enr")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let enr: EnrData = data(&response);
+        assert_eq!(enr.seq, 1);
+        assert_eq!(enr.fork_digest.as_deref(), Some("01020304"));
+
+        let response = warp::test::request()
+            .path("/boot_node/peers")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(data::<Vec<PeerData>>(&response).is_empty());
+
+        let response = warp::test::request()
+            .path("/boot_node/peer_count")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let counts: PeerCountData = data(&response);
+        assert_eq!(counts.total, 2);
+        assert_eq!(counts.connected, 1);
+        assert_eq!(counts.fork_digests.get("01020304"), Some(&2));
+
+        let response = warp::test::request()
+            .path("/boot_node/unknown")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn post_enr() {
+        let (tx, rx) = mpsc::channel(REQUEST_CHANNEL_SIZE);
+        spawn_server_loop(rx);
+        let routes = routes(tx, test_logger());
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/boot_node/enr")
+            .json(&EnrUpdate {
+                ip: Some([10, 0, 0, 1].into()),
+                udp_port: Some(9001),
+            })
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let enr: EnrData = data(&response);
+        assert_eq!(enr.seq, 2);
+        assert_eq!(
+            enr.udp4_socket,
+            Some(SocketAddr::new([10, 0, 0, 1].into(), 9001))
+        );
+
+        // Errors from the server loop are returned as bad requests.
+        let response = warp::test::request()
+            .method("POST")
+            .path("/boot_node/enr")
+            .json(&EnrUpdate {
+                ip: None,
+                udp_port: Some(0),
+            })
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/boot_node/enr")
+            .body("not json")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn unanswered_requests_are_server_errors() {
+        // A server loop which has stopped drops its receiver.
+        let (tx, rx) = mpsc::channel(REQUEST_CHANNEL_SIZE);
+        drop(rx);
+        let routes = routes(tx, test_logger());
+        let response = warp::test::request()
+            .path("/boot_node/enr")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        // A server loop which drops the response sender.
+        let (tx, mut rx) = mpsc::channel(REQUEST_CHANNEL_SIZE);
+        tokio::spawn(async move { while rx.recv().await.is_some() {} });
+        let routes = routes(tx, test_logger());
+        let response = warp::test::request()
+            .path("/boot_node/peers")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
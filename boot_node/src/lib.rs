@@ -5,6 +5,7 @@ use slog::{o, Drain, Level, Logger};
 use eth2_network_config::Eth2NetworkConfig;
 mod cli;
 pub mod config;
+pub mod http_api;
 mod server;
 pub use cli::cli_app;
 use config::BootNodeConfig;
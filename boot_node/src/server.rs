@@ -2,6 +2,7 @@
 
 use super::BootNodeConfig;
 use crate::config::BootNodeConfigSerialization;
+use crate::http_api;
 use clap::ArgMatches;
 use eth2_network_config::Eth2NetworkConfig;
 use lighthouse_network::{
@@ -37,6 +38,7 @@ pub async fn run<E: EthSpec>(
         local_enr,
         local_key,
         discv5_config,
+        http_address,
         ..
     } = config;
 
@@ -97,6 +99,16 @@ pub async fn run<E: EthSpec>(
         let _ = discv5.find_node(NodeId::random()).await;
     }
 
+    // start the HTTP API, if enabled
+    let mut http_requests = match http_address {
+        Some(address) => {
+            let (requests, server) = http_api::serve(address, log.clone())?;
+            tokio::spawn(server);
+            Some(requests)
+        }
+        None => None,
+    };
+
     // respond with metrics every 10 seconds
     let mut metric_interval = tokio::time::interval(tokio::time::Duration::from_secs(10));
 
@@ -142,6 +154,23 @@ pub async fn run<E: EthSpec>(
                 );
 
             }
+            Some(request) = async { http_requests.as_mut()?.recv().await } => {
+                // The receivers may have been dropped, in which case there is nothing to do
+                match request {
+                    http_api::Request::Enr(sender) => {
+                        let _ = sender.send(http_api::enr_data(&discv5));
+                    }
+                    http_api::Request::Peers(sender) => {
+                        let _ = sender.send(http_api::peers(&discv5));
+                    }
+                    http_api::Request::PeerCounts(sender) => {
+                        let _ = sender.send(http_api::peer_counts(&discv5));
+                    }
+                    http_api::Request::UpdateEnr(update, sender) => {
+                        let _ = sender.send(http_api::update_enr(&mut discv5, update, &log));
+                    }
+                }
+            }
             Some(event) = event_stream.recv() => {
                 match event {
                     discv5::Event::Discovered(_enr) => {
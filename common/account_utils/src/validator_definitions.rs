@@ -162,6 +162,12 @@ pub struct ValidatorDefinition {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prefer_builder_proposals: Option<bool>,
+    /// The beacon nodes to request this validator's blocks from first, in order of preference.
+    ///
+    /// Each beacon node may use a builder connected to a different set of relays.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builder_beacon_nodes: Option<Vec<String>>,
     #[serde(default)]
     pub description: String,
     #[serde(flatten)]
@@ -207,6 +213,7 @@ impl ValidatorDefinition {
             builder_proposals,
             builder_boost_factor,
             prefer_builder_proposals,
+            builder_beacon_nodes: None,
             signing_definition: SigningDefinition::LocalKeystore {
                 voting_keystore_path,
                 voting_keystore_password_path,
@@ -357,6 +364,7 @@ impl ValidatorDefinitions {
                     builder_proposals: None,
                     builder_boost_factor: None,
                     prefer_builder_proposals: None,
+                    builder_beacon_nodes: None,
                     signing_definition: SigningDefinition::LocalKeystore {
                         voting_keystore_path,
                         voting_keystore_password_path,
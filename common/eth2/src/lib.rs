@@ -107,6 +107,15 @@ impl Error {
             Error::NoServerPubkey | Error::NoToken => None,
         }
     }
+
+    /// If the server returned a machine-readable error code, return it.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            Error::ServerMessage(msg) => msg.error_code,
+            Error::ServerIndexedMessage(msg) => msg.error_code,
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -1746,6 +1755,15 @@ impl BeaconNodeHttpClient {
 
     /// `GET node/health`
     pub async fn get_node_health(&self) -> Result<StatusCode, Error> {
+        self.get_node_health_with_query(&NodeHealthQuery::default())
+            .await
+    }
+
+    /// `GET node/health?syncing_status,syncing_tolerance`
+    pub async fn get_node_health_with_query(
+        &self,
+        query: &NodeHealthQuery,
+    ) -> Result<StatusCode, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
@@ -1753,8 +1771,23 @@ impl BeaconNodeHttpClient {
             .push("node")
             .push("health");
 
+        if let Some(syncing_status) = query.syncing_status {
+            path.query_pairs_mut()
+                .append_pair("syncing_status", &syncing_status.to_string());
+        }
+
+        if let Some(syncing_tolerance) = query.syncing_tolerance {
+            path.query_pairs_mut()
+                .append_pair("syncing_tolerance", &syncing_tolerance.to_string());
+        }
+
+        let syncing_status = query
+            .syncing_status
+            .and_then(|code| StatusCode::from_u16(code).ok())
+            .unwrap_or(StatusCode::PARTIAL_CONTENT);
+
         let status = self.client.get(path).send().await?.status();
-        if status == StatusCode::OK || status == StatusCode::PARTIAL_CONTENT {
+        if status == StatusCode::OK || status == syncing_status {
             Ok(status)
         } else {
             Err(Error::StatusCode(status))
@@ -2548,6 +2581,26 @@ impl BeaconNodeHttpClient {
     pub async fn get_events<E: EthSpec>(
         &self,
         topic: &[EventTopic],
+    ) -> Result<impl Stream<Item = Result<EventKind<E>, Error>>, Error> {
+        self.get_events_generic(topic, None).await
+    }
+
+    /// `GET events?topics`
+    ///
+    /// The stream ends with an error once `timeout` has elapsed, rather than once the timeout of
+    /// the underlying client has elapsed.
+    pub async fn get_events_with_timeout<E: EthSpec>(
+        &self,
+        topic: &[EventTopic],
+        timeout: Duration,
+    ) -> Result<impl Stream<Item = Result<EventKind<E>, Error>>, Error> {
+        self.get_events_generic(topic, Some(timeout)).await
+    }
+
+    async fn get_events_generic<E: EthSpec>(
+        &self,
+        topic: &[EventTopic],
+        timeout: Option<Duration>,
     ) -> Result<impl Stream<Item = Result<EventKind<E>, Error>>, Error> {
         let mut path = self.eth_path(V1)?;
         path.path_segments_mut()
@@ -2561,16 +2614,15 @@ impl BeaconNodeHttpClient {
             .join(",");
         path.query_pairs_mut().append_pair("topics", &topic_string);
 
-        Ok(self
-            .client
-            .get(path)
-            .send()
-            .await?
-            .bytes_stream()
-            .map(|next| match next {
-                Ok(bytes) => EventKind::from_sse_bytes(bytes.as_ref()),
-                Err(e) => Err(Error::HttpClient(e.into())),
-            }))
+        let mut builder = self.client.get(path);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        Ok(builder.send().await?.bytes_stream().map(|next| match next {
+            Ok(bytes) => EventKind::from_sse_bytes(bytes.as_ref()),
+            Err(e) => Err(Error::HttpClient(e.into())),
+        }))
     }
 
     /// `POST validator/duties/sync/{epoch}`
@@ -4,19 +4,27 @@ mod attestation_performance;
 pub mod attestation_rewards;
 mod block_packing_efficiency;
 mod block_rewards;
+mod churn_analysis;
+mod gossip_arrival_times;
 mod standard_block_rewards;
 mod sync_committee_rewards;
 
 use crate::{
+    ok_or_error,
     types::{
-        DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse, ValidatorId,
+        Checkpoint, DepositTreeSnapshot, Epoch, EthSpec, EventKind, FinalizedExecutionBlock,
+        ForkChoiceNode, ForkName, GenericResponse, LighthouseEventTopic, ProposerSlashing,
+        PublicKeyBytes, ValidatorId,
     },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot,
 };
+use futures::Stream;
+use futures_util::StreamExt;
 use proto_array::core::ProtoArray;
 use serde::{Deserialize, Serialize};
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
+use std::collections::BTreeMap;
 use store::{AnchorInfo, BlobInfo, Split, StoreConfig};
 
 pub use attestation_performance::{
@@ -24,9 +32,12 @@ pub use attestation_performance::{
 };
 pub use attestation_rewards::StandardAttestationRewards;
 pub use block_packing_efficiency::{
-    BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
+    BlockPackingEfficiency, BlockPackingEfficiencyQuery, ObservedBlockPacking,
+    ObservedBlockPackingResponse, ObservedProposerPacking, ProposerInfo, UniqueAttestation,
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
+pub use churn_analysis::{ChurnAnalysis, ProjectedChurn};
+pub use gossip_arrival_times::{GossipArrival, GossipArrivalKind, GossipArrivalTimesQuery};
 pub use lighthouse_network::{types::SyncState, PeerInfo};
 pub use standard_block_rewards::StandardBlockReward;
 pub use sync_committee_rewards::SyncCommitteeReward;
@@ -47,6 +58,76 @@ pub struct Peer<E: EthSpec> {
     pub peer_info: PeerInfo<E>,
 }
 
+/// A change in the connection state of a peer, emitted on the `peer` event topic.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerEventKind {
+    Connected,
+    Disconnected,
+    Banned,
+}
+
+/// Event emitted on the `peer` topic of `lighthouse/events`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SsePeer {
+    pub peer_id: String,
+    pub kind: PeerEventKind,
+    /// Whether the peer dialed us (`incoming`) or we dialed the peer (`outgoing`).
+    pub direction: Option<String>,
+    /// The goodbye reason code that ended the connection, if one was exchanged.
+    pub reason_code: Option<u64>,
+    /// A human-readable description of `reason_code`.
+    pub reason: Option<String>,
+    /// Whether we sent the goodbye, as opposed to receiving it from the peer.
+    pub goodbye_sent: Option<bool>,
+    pub client: String,
+    pub score: f64,
+    pub enr: Option<String>,
+}
+
+/// Event emitted on the `blob_sidecar_equivocation` topic of `lighthouse/events` when a proposer is
+/// observed signing conflicting blob sidecars for the same slot and index on gossip.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SseBlobSidecarEquivocation {
+    pub slot: Slot,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: u64,
+    /// The block root of the first sidecar observed for this index.
+    pub previous_block_root: Hash256,
+    /// The block root of the conflicting sidecar.
+    pub block_root: Hash256,
+}
+
+/// Event emitted on the `block_equivocation` topic of `lighthouse/events` when a proposer is observed
+/// signing two distinct blocks for the same slot, on gossip or via RPC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SseBlockEquivocation {
+    pub slot: Slot,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    /// The root of a block previously observed from this proposer at this slot.
+    pub previous_block_root: Hash256,
+    /// The root of the conflicting block.
+    pub block_root: Hash256,
+    /// The slashing constructed from the two blocks, if the header of the previous block was
+    /// available.
+    pub proposer_slashing: Option<ProposerSlashing>,
+}
+
+/// Event emitted periodically on the `genesis_countdown` topic of `lighthouse/events` while the
+/// beacon node is waiting for genesis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SseGenesisCountdown {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub genesis_time: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub seconds_to_genesis: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub connected_peers: u64,
+}
+
 /// The results of validators voting during an epoch.
 ///
 /// Provides information about the current and previous epochs.
@@ -297,6 +378,51 @@ impl Health {
     }
 }
 
+/// Information returned by `GET lighthouse/health` on a beacon node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BeaconNodeHealth {
+    #[serde(flatten)]
+    pub health: Health,
+    pub chain: ChainHealthReport,
+}
+
+/// The overall verdict of a `ChainHealthReport`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainHealthVerdict {
+    Healthy,
+    Degraded,
+}
+
+/// A condition which causes the chain to be considered degraded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DegradedReason {
+    /// Too many epochs have passed since the head's finalized checkpoint.
+    EpochsSinceFinalization,
+    /// Too many of the slots in the last epoch were missed.
+    MissedSlots,
+    /// Too few peers are connected.
+    LowPeerCount,
+}
+
+/// The health of the chain, as returned in the `chain` field of `GET lighthouse/health`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainHealthReport {
+    pub verdict: ChainHealthVerdict,
+    pub reasons: Vec<DegradedReason>,
+    /// `true` if the degraded-mode behaviours are currently active.
+    pub degraded_mode_active: bool,
+    pub current_slot: Slot,
+    pub head_slot: Slot,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub epochs_since_finalization: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub missed_slots_in_last_epoch: u64,
+    /// `None` if the peer count has not yet been reported by the network.
+    pub connected_peers: Option<usize>,
+}
+
 /// Indicates how up-to-date the Eth1 caches are.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Eth1SyncStatusData {
@@ -356,6 +482,63 @@ impl From<Eth1Block> for FinalizedExecutionBlock {
     }
 }
 
+/// The log levels of a Lighthouse process, in the format accepted by `--log-levels`.
+///
+/// E.g., `info,network=debug,store=info`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogLevelsData {
+    pub levels: String,
+}
+
+/// A runtime update to the target number of peers of a beacon node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TargetPeersData {
+    pub target_peers: usize,
+    /// The maximum number of excess peers to disconnect per heartbeat, if limited.
+    ///
+    /// A limit of zero would never disconnect excess peers, so it is rejected.
+    #[serde(default, deserialize_with = "non_zero_option")]
+    pub max_pruned_peers_per_heartbeat: Option<usize>,
+}
+
+fn non_zero_option<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Option::<usize>::deserialize(deserializer)? {
+        Some(0) => Err(serde::de::Error::custom("value must be greater than zero")),
+        value => Ok(value),
+    }
+}
+
+/// An estimate of the resources used by a beacon node, for diagnosing memory regressions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SystemProfile {
+    pub caches: Vec<CacheProfile>,
+    pub beacon_processor: BeaconProcessorProfile,
+    /// Statistics reported by the memory allocator, if it provides any.
+    pub allocator: Option<BTreeMap<String, u64>>,
+}
+
+/// The size of an in-memory cache.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CacheProfile {
+    pub name: String,
+    /// The number of items in the cache.
+    pub len: u64,
+    /// A rough estimate of the memory used by the cache, in bytes.
+    pub estimated_bytes: u64,
+}
+
+/// The depths of the queues feeding the beacon processor.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BeaconProcessorProfile {
+    /// The number of events waiting to be received by the beacon processor.
+    pub event_channel_depth: Option<u64>,
+    /// The number of items in each of the beacon processor's work queues.
+    pub work_queues: BTreeMap<String, u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DatabaseInfo {
     pub schema_version: u64,
@@ -365,6 +548,55 @@ pub struct DatabaseInfo {
     pub blob_info: BlobInfo,
 }
 
+/// The result of checking the execution engine against the requirements of a fork.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionReadiness {
+    pub ready: bool,
+    pub info: String,
+}
+
+/// Information returned by `GET lighthouse/fork_readiness`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ForkReadinessReport {
+    /// The fork in effect at the current slot.
+    pub current_fork: ForkName,
+    /// The fork following `current_fork`, if this node knows of one.
+    pub next_fork: Option<ForkName>,
+    /// The epoch at which `next_fork` is scheduled, if any.
+    pub next_fork_epoch: Option<Epoch>,
+    /// The execution engine readiness for `next_fork`, or `None` if the fork doesn't require any
+    /// changes to the execution engine.
+    pub execution: Option<ExecutionReadiness>,
+    /// `true` if `next_fork` requires the KZG trusted setup.
+    pub kzg_required: bool,
+    /// `true` if the KZG trusted setup is loaded.
+    pub kzg_loaded: bool,
+    /// `true` if the next fork is scheduled and all of the checks above passed.
+    pub ready: bool,
+}
+
+/// Information returned by `GET lighthouse/fork_choice`.
+///
+/// An extension of the standard `GET debug/fork_choice` response which includes the unrealized
+/// checkpoints used by pull-up tips.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LighthouseForkChoice {
+    pub justified_checkpoint: Checkpoint,
+    pub finalized_checkpoint: Checkpoint,
+    pub unrealized_justified_checkpoint: Checkpoint,
+    pub unrealized_finalized_checkpoint: Checkpoint,
+    pub proposer_boost_root: Hash256,
+    pub fork_choice_nodes: Vec<LighthouseForkChoiceNode>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LighthouseForkChoiceNode {
+    #[serde(flatten)]
+    pub node: ForkChoiceNode,
+    pub unrealized_justified_epoch: Option<Epoch>,
+    pub unrealized_finalized_epoch: Option<Epoch>,
+}
+
 impl BeaconNodeHttpClient {
     /// `GET lighthouse/health`
     pub async fn get_lighthouse_health(&self) -> Result<GenericResponse<Health>, Error> {
@@ -378,6 +610,20 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/health`, including the health of the chain.
+    pub async fn get_lighthouse_beacon_node_health(
+        &self,
+    ) -> Result<GenericResponse<BeaconNodeHealth>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("health");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/syncing`
     pub async fn get_lighthouse_syncing(&self) -> Result<GenericResponse<SyncState>, Error> {
         let mut path = self.server.full.clone();
@@ -410,6 +656,20 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/fork_choice`
+    pub async fn get_lighthouse_fork_choice(
+        &self,
+    ) -> Result<GenericResponse<LighthouseForkChoice>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("fork_choice");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/validator_inclusion/{epoch}/global`
     pub async fn get_lighthouse_validator_inclusion_global(
         &self,
@@ -445,6 +705,37 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/events?topics`
+    pub async fn get_lighthouse_events<E: EthSpec>(
+        &self,
+        topics: &[LighthouseEventTopic],
+    ) -> Result<impl Stream<Item = Result<EventKind<E>, Error>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("events");
+
+        let topic_string = topics
+            .iter()
+            .map(|topic| topic.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        path.query_pairs_mut().append_pair("topics", &topic_string);
+
+        Ok(self
+            .client
+            .get(path)
+            .send()
+            .await?
+            .bytes_stream()
+            .map(|next| match next {
+                Ok(bytes) => EventKind::from_sse_bytes(bytes.as_ref()),
+                Err(e) => Err(Error::HttpClient(e.into())),
+            }))
+    }
+
     /// `GET lighthouse/eth1/syncing`
     pub async fn get_lighthouse_eth1_syncing(
         &self,
@@ -502,6 +793,35 @@ impl BeaconNodeHttpClient {
         self.get_opt::<(), _>(path).await.map(|opt| opt.is_some())
     }
 
+    /// `GET lighthouse/system/profile`
+    pub async fn get_lighthouse_system_profile(
+        &self,
+    ) -> Result<GenericResponse<SystemProfile>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("system")
+            .push("profile");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/fork_readiness`
+    pub async fn get_lighthouse_fork_readiness(
+        &self,
+    ) -> Result<GenericResponse<ForkReadinessReport>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("fork_readiness");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/database/info`
     pub async fn get_lighthouse_database_info(&self) -> Result<DatabaseInfo, Error> {
         let mut path = self.server.full.clone();
@@ -528,6 +848,77 @@ impl BeaconNodeHttpClient {
         self.post_with_response(path, &()).await
     }
 
+    /// `POST lighthouse/validator_monitor/register`
+    ///
+    /// The `api_token` is the beacon node's `--http-api-token-file` token.
+    pub async fn post_lighthouse_validator_monitor_register(
+        &self,
+        pubkeys: &[PublicKeyBytes],
+        api_token: &str,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator_monitor")
+            .push("register");
+
+        let response = self
+            .client
+            .post(path)
+            .bearer_auth(api_token)
+            .json(&pubkeys)
+            .send()
+            .await?;
+        ok_or_error(response).await?;
+        Ok(())
+    }
+
+    /// `GET lighthouse/logging/levels`
+    pub async fn get_lighthouse_logging_levels(
+        &self,
+    ) -> Result<GenericResponse<LogLevelsData>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging")
+            .push("levels");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/logging/levels`
+    pub async fn post_lighthouse_logging_levels(
+        &self,
+        levels: &LogLevelsData,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging")
+            .push("levels");
+
+        self.post(path, levels).await
+    }
+
+    /// `POST lighthouse/peers/target`
+    pub async fn post_lighthouse_peers_target(&self, data: &TargetPeersData) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("target");
+
+        self.post(path, data).await
+    }
+
     ///
     /// Analysis endpoints.
     ///
@@ -574,6 +965,27 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET` lighthouse/analysis/block_packing?start_epoch,end_epoch
+    pub async fn get_lighthouse_analysis_observed_block_packing(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<ObservedBlockPackingResponse, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("block_packing");
+
+        path.query_pairs_mut()
+            .append_pair("start_epoch", &start_epoch.to_string())
+            .append_pair("end_epoch", &end_epoch.to_string());
+
+        self.get(path).await
+    }
+
     /// `GET` lighthouse/analysis/attestation_performance/{index}?start_epoch,end_epoch
     pub async fn get_lighthouse_analysis_attestation_performance(
         &self,
@@ -596,4 +1008,45 @@ impl BeaconNodeHttpClient {
 
         self.get(path).await
     }
+
+    /// `GET` lighthouse/analysis/churn
+    pub async fn get_lighthouse_analysis_churn(&self) -> Result<ChurnAnalysis, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("churn");
+
+        self.get(path).await
+    }
+
+    /// `GET` lighthouse/analysis/gossip_arrival_times?kind,start_slot,end_slot
+    pub async fn get_lighthouse_analysis_gossip_arrival_times(
+        &self,
+        query: &GossipArrivalTimesQuery,
+    ) -> Result<Vec<GossipArrival>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("gossip_arrival_times");
+
+        if let Some(kind) = query.kind {
+            path.query_pairs_mut().append_pair("kind", kind.as_str());
+        }
+        if let Some(start_slot) = query.start_slot {
+            path.query_pairs_mut()
+                .append_pair("start_slot", &start_slot.to_string());
+        }
+        if let Some(end_slot) = query.end_slot {
+            path.query_pairs_mut()
+                .append_pair("end_slot", &end_slot.to_string());
+        }
+
+        self.get(path).await
+    }
 }
@@ -32,3 +32,35 @@ pub struct BlockPackingEfficiencyQuery {
     pub start_epoch: Epoch,
     pub end_epoch: Epoch,
 }
+
+/// The packing of a single block, relative to the aggregates this node observed on gossip.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ObservedBlockPacking {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub proposer_index: ValidatorIndex,
+    /// Distinct `(slot, beacon_block_root)` votes for which an aggregate arrived before the start
+    /// of `slot` and which could be included in this block.
+    pub available_votes: usize,
+    /// The number of `available_votes` that were included in this block.
+    pub included_available_votes: usize,
+    /// Distinct votes included in this block for which no aggregate arrival was recorded.
+    pub included_unobserved_votes: usize,
+}
+
+/// The packing of all of a proposer's blocks in the queried range.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ObservedProposerPacking {
+    pub proposer_index: ValidatorIndex,
+    pub blocks: usize,
+    pub available_votes: usize,
+    pub included_available_votes: usize,
+    /// `included_available_votes / available_votes`, or `None` if there were no available votes.
+    pub efficiency: Option<f64>,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ObservedBlockPackingResponse {
+    pub blocks: Vec<ObservedBlockPacking>,
+    pub proposers: Vec<ObservedProposerPacking>,
+}
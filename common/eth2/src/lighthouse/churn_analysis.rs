@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use types::Epoch;
+
+/// The activation and exit queues of the head state, along with the epochs at which pending
+/// validators are projected to activate or exit.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ChurnAnalysis {
+    /// The current epoch of the head state.
+    pub epoch: Epoch,
+    pub active_validators: u64,
+    /// The number of validators which may exit per epoch prior to Electra.
+    pub churn_limit: u64,
+    /// The number of validators which may activate per epoch prior to Electra.
+    pub activation_churn_limit: u64,
+    /// The balance in Gwei which may be deposited or exited per epoch from Electra onwards.
+    pub activation_exit_balance_churn_limit: Option<u64>,
+    pub activation_queue_length: usize,
+    pub exit_queue_length: usize,
+    /// The epoch at which a validator initiating an exit now would exit.
+    pub next_exit_epoch: Epoch,
+    pub pending_activations: Vec<ProjectedChurn>,
+    pub pending_exits: Vec<ProjectedChurn>,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ProjectedChurn {
+    pub validator_index: u64,
+    /// `None` if the validator does not have the balance required to activate.
+    pub projected_epoch: Option<Epoch>,
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use types::{Hash256, Slot};
+
+/// The kinds of gossip message for which arrival times are recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GossipArrivalKind {
+    Block,
+    Aggregate,
+    BlobSidecar,
+}
+
+impl GossipArrivalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Aggregate => "aggregate",
+            Self::BlobSidecar => "blob_sidecar",
+        }
+    }
+}
+
+impl fmt::Display for GossipArrivalKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The arrival time of a single gossip message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GossipArrival {
+    pub kind: GossipArrivalKind,
+    pub slot: Slot,
+    /// The block to which the message relates. For aggregates this is the block being voted for.
+    pub block_root: Hash256,
+    /// The blob index of a blob sidecar, or the aggregator index of an aggregate.
+    pub index: Option<u64>,
+    /// Milliseconds between the start of `slot` and the arrival of the message.
+    pub delay_ms: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct GossipArrivalTimesQuery {
+    pub kind: Option<GossipArrivalKind>,
+    pub start_slot: Option<Slot>,
+    pub end_slot: Option<Slot>,
+}
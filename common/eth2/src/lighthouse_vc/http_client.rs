@@ -305,6 +305,51 @@ impl ValidatorClientHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/validator_registrations`
+    pub async fn get_lighthouse_validator_registrations(
+        &self,
+    ) -> Result<GenericResponse<Vec<ValidatorRegistrationStatus>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator_registrations");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/logging/levels`
+    pub async fn get_lighthouse_logging_levels(
+        &self,
+    ) -> Result<GenericResponse<LogLevelsData>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging")
+            .push("levels");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/logging/levels`
+    pub async fn post_lighthouse_logging_levels(
+        &self,
+        levels: &LogLevelsData,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging")
+            .push("levels");
+
+        self.post(path, levels).await
+    }
+
     /// `GET lighthouse/spec`
     pub async fn get_lighthouse_spec<T: Serialize + DeserializeOwned>(
         &self,
@@ -423,6 +468,7 @@ impl ValidatorClientHttpClient {
         builder_boost_factor: Option<u64>,
         prefer_builder_proposals: Option<bool>,
         graffiti: Option<GraffitiString>,
+        builder_beacon_nodes: Option<Vec<SensitiveUrl>>,
     ) -> Result<(), Error> {
         let mut path = self.server.full.clone();
 
@@ -441,6 +487,7 @@ impl ValidatorClientHttpClient {
                 builder_boost_factor,
                 prefer_builder_proposals,
                 graffiti,
+                builder_beacon_nodes,
             },
         )
         .await
@@ -527,6 +574,23 @@ impl ValidatorClientHttpClient {
         self.get_unsigned(url).await
     }
 
+    /// `POST lighthouse/auth/rotate`
+    ///
+    /// The returned token should be added to this client with `add_auth_token`.
+    pub async fn post_lighthouse_auth_rotate(
+        &self,
+    ) -> Result<GenericResponse<RotateApiTokenResponse>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("auth")
+            .push("rotate");
+
+        self.post(path, &()).await
+    }
+
     /// `GET eth/v1/keystores`
     pub async fn get_keystores(&self) -> Result<ListKeystoresResponse, Error> {
         let url = self.make_keystores_url()?;
@@ -1,10 +1,11 @@
 use account_utils::ZeroizeString;
 use eth2_keystore::Keystore;
 use graffiti::GraffitiString;
+use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-pub use crate::lighthouse::Health;
+pub use crate::lighthouse::{Health, LogLevelsData};
 pub use crate::lighthouse_vc::std_types::*;
 pub use crate::types::{GenericResponse, VersionData};
 pub use types::*;
@@ -14,6 +15,18 @@ pub struct ValidatorData {
     pub enabled: bool,
     pub description: String,
     pub voting_pubkey: PublicKeyBytes,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builder_proposals: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builder_boost_factor: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefer_builder_proposals: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builder_beacon_nodes: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -98,6 +111,9 @@ pub struct ValidatorPatchRequest {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prefer_builder_proposals: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builder_beacon_nodes: Option<Vec<SensitiveUrl>>,
 }
 
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
@@ -172,6 +188,47 @@ pub struct UpdateGasLimitRequest {
     pub gas_limit: u64,
 }
 
+/// The result of rotating the API token.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RotateApiTokenResponse {
+    /// The new API token, which has also been written to `token_path`.
+    pub token: String,
+    pub token_path: String,
+    /// The number of seconds for which the previous token will continue to be accepted.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub grace_period_seconds: u64,
+}
+
+/// The status of a validator's registration with the builder network.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ValidatorRegistrationStatus {
+    pub pubkey: PublicKeyBytes,
+    pub fee_recipient: Address,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gas_limit: u64,
+    /// The timestamp included in the signed registration.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub timestamp: u64,
+    /// `true` if this registration has been accepted by the beacon nodes at least once.
+    pub published: bool,
+    /// The epoch in which the registration was most recently accepted by the beacon nodes.
+    pub last_published_epoch: Option<Epoch>,
+    /// The error from the most recent attempt to publish the registration, if it failed.
+    pub error: Option<String>,
+    /// The beacon nodes which have accepted this registration and forwarded it to their builders.
+    #[serde(default)]
+    pub accepted_by: Vec<ValidatorRegistrationAcceptance>,
+}
+
+/// A beacon node's acceptance of a validator's registration.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ValidatorRegistrationAcceptance {
+    pub beacon_node: String,
+    /// The unix timestamp at which the beacon node most recently accepted the registration.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub accepted_at: u64,
+}
+
 #[derive(Deserialize)]
 pub struct VoluntaryExitQuery {
     pub epoch: Option<Epoch>,
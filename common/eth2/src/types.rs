@@ -20,7 +20,9 @@ use types::beacon_block_body::KzgCommitments;
 pub use types::*;
 
 #[cfg(feature = "lighthouse")]
-use crate::lighthouse::BlockReward;
+use crate::lighthouse::{
+    BlockReward, SseBlobSidecarEquivocation, SseBlockEquivocation, SseGenesisCountdown, SsePeer,
+};
 
 /// An API error serializable to JSON.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -35,8 +37,13 @@ pub enum Error {
 pub struct ErrorMessage {
     pub code: u16,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<ErrorCode>,
     #[serde(default)]
     pub stacktraces: Vec<String>,
+    /// The slot of the nearest state which can be served without exceeding the replay budget.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nearest_stored_slot: Option<Slot>,
 }
 
 /// An indexed API error serializable to JSON.
@@ -44,6 +51,8 @@ pub struct ErrorMessage {
 pub struct IndexedErrorMessage {
     pub code: u16,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<ErrorCode>,
     pub failures: Vec<Failure>,
 }
 
@@ -52,6 +61,8 @@ pub struct IndexedErrorMessage {
 pub struct Failure {
     pub index: u64,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<ErrorCode>,
 }
 
 impl Failure {
@@ -59,10 +70,48 @@ impl Failure {
         Self {
             index: index as u64,
             message,
+            error_code: None,
         }
     }
 }
 
+/// A stable, machine-readable identifier for the cause of an API error.
+///
+/// Unlike the `message` of an error, these codes won't change between releases, so API consumers
+/// may match on them. Codes added by newer servers are deserialized as `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The request was malformed, e.g. it had an invalid query or body.
+    BadRequest,
+    /// A submitted object failed validation.
+    InvalidObject,
+    /// The requested block is not known to the node.
+    UnknownBlock,
+    /// The requested state is not known to the node.
+    UnknownState,
+    /// The requested data has been pruned from the database, or was never backfilled.
+    Pruned,
+    /// The requested resource does not exist.
+    NotFound,
+    /// The node is syncing and can't serve the request.
+    NotSynced,
+    /// A submitted object was broadcast without being imported.
+    BroadcastWithoutImport,
+    /// The request did not include an API token.
+    Unauthorized,
+    /// The request included an invalid API token.
+    Forbidden,
+    UnsupportedMediaType,
+    MethodNotAllowed,
+    /// Serving the request would require replaying more blocks than the node allows.
+    ReplayBudgetExceeded,
+    /// The node encountered an unexpected error.
+    InternalError,
+    #[serde(other)]
+    Unknown,
+}
+
 /// The version of a single API endpoint, e.g. the `v1` in `/eth/v1/beacon/blocks`.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct EndpointVersion(pub u64);
@@ -596,6 +645,15 @@ pub struct VersionData {
     pub version: String,
 }
 
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct NodeHealthQuery {
+    /// The status code to return when the node is syncing or optimistic. Defaults to 206.
+    pub syncing_status: Option<u16>,
+    /// If set, the node is considered to be syncing only when its head is more than this many
+    /// slots behind the current slot.
+    pub syncing_tolerance: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SyncingData {
     pub is_syncing: bool,
@@ -1106,6 +1164,14 @@ pub enum EventKind<E: EthSpec> {
     AttesterSlashing(Box<AttesterSlashing<E>>),
     BlsToExecutionChange(Box<SignedBlsToExecutionChange>),
     BlockGossip(Box<BlockGossip>),
+    #[cfg(feature = "lighthouse")]
+    Peer(SsePeer),
+    #[cfg(feature = "lighthouse")]
+    BlobSidecarEquivocation(SseBlobSidecarEquivocation),
+    #[cfg(feature = "lighthouse")]
+    GenesisCountdown(SseGenesisCountdown),
+    #[cfg(feature = "lighthouse")]
+    BlockEquivocation(Box<SseBlockEquivocation>),
 }
 
 impl<E: EthSpec> EventKind<E> {
@@ -1129,6 +1195,14 @@ impl<E: EthSpec> EventKind<E> {
             EventKind::AttesterSlashing(_) => "attester_slashing",
             EventKind::BlsToExecutionChange(_) => "bls_to_execution_change",
             EventKind::BlockGossip(_) => "block_gossip",
+            #[cfg(feature = "lighthouse")]
+            EventKind::Peer(_) => "peer",
+            #[cfg(feature = "lighthouse")]
+            EventKind::BlobSidecarEquivocation(_) => "blob_sidecar_equivocation",
+            #[cfg(feature = "lighthouse")]
+            EventKind::GenesisCountdown(_) => "genesis_countdown",
+            #[cfg(feature = "lighthouse")]
+            EventKind::BlockEquivocation(_) => "block_equivocation",
         }
     }
 
@@ -1227,6 +1301,31 @@ impl<E: EthSpec> EventKind<E> {
             "block_gossip" => Ok(EventKind::BlockGossip(serde_json::from_str(data).map_err(
                 |e| ServerError::InvalidServerSentEvent(format!("Block Gossip: {:?}", e)),
             )?)),
+            #[cfg(feature = "lighthouse")]
+            "peer" => Ok(EventKind::Peer(serde_json::from_str(data).map_err(
+                |e| ServerError::InvalidServerSentEvent(format!("Peer: {:?}", e)),
+            )?)),
+            #[cfg(feature = "lighthouse")]
+            "blob_sidecar_equivocation" => Ok(EventKind::BlobSidecarEquivocation(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!(
+                        "Blob Sidecar Equivocation: {:?}",
+                        e
+                    ))
+                })?,
+            )),
+            #[cfg(feature = "lighthouse")]
+            "genesis_countdown" => Ok(EventKind::GenesisCountdown(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Genesis Countdown: {:?}", e))
+                })?,
+            )),
+            #[cfg(feature = "lighthouse")]
+            "block_equivocation" => Ok(EventKind::BlockEquivocation(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Block Equivocation: {:?}", e))
+                })?,
+            )),
             _ => Err(ServerError::InvalidServerSentEvent(
                 "Could not parse event tag".to_string(),
             )),
@@ -1317,6 +1416,55 @@ impl fmt::Display for EventTopic {
     }
 }
 
+/// Query for the Lighthouse-specific event stream at `lighthouse/events`.
+#[cfg(feature = "lighthouse")]
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LighthouseEventQuery {
+    #[serde(deserialize_with = "query_vec")]
+    pub topics: Vec<LighthouseEventTopic>,
+}
+
+/// Topics which are only available on the Lighthouse-specific event stream.
+#[cfg(feature = "lighthouse")]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LighthouseEventTopic {
+    Peer,
+    BlobSidecarEquivocation,
+    GenesisCountdown,
+    BlockEquivocation,
+}
+
+#[cfg(feature = "lighthouse")]
+impl FromStr for LighthouseEventTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "peer" => Ok(LighthouseEventTopic::Peer),
+            "blob_sidecar_equivocation" => Ok(LighthouseEventTopic::BlobSidecarEquivocation),
+            "genesis_countdown" => Ok(LighthouseEventTopic::GenesisCountdown),
+            "block_equivocation" => Ok(LighthouseEventTopic::BlockEquivocation),
+            _ => Err("event topic cannot be parsed.".to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "lighthouse")]
+impl fmt::Display for LighthouseEventTopic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LighthouseEventTopic::Peer => write!(f, "peer"),
+            LighthouseEventTopic::BlobSidecarEquivocation => {
+                write!(f, "blob_sidecar_equivocation")
+            }
+            LighthouseEventTopic::GenesisCountdown => write!(f, "genesis_countdown"),
+            LighthouseEventTopic::BlockEquivocation => write!(f, "block_equivocation"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Accept {
     Json,
@@ -11,6 +11,8 @@
 //! To add a new built-in testnet, add it to the `define_hardcoded_nets` invocation in the `eth2_config`
 //! crate.
 
+mod update;
+
 use bytes::Bytes;
 use discv5::enr::{CombinedKey, Enr};
 use eth2_config::{instantiate_hardcoded_nets, HardcodedNet};
@@ -24,15 +26,22 @@ use std::io::{Read, Write};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
-use types::{BeaconState, ChainSpec, Config, Epoch, EthSpec, EthSpecId, Hash256};
+use types::{apply_preset_dir, BeaconState, ChainSpec, Config, Epoch, EthSpec, EthSpecId, Hash256};
 use url::Url;
 
 pub use eth2_config::GenesisStateSource;
+pub use update::{
+    download_network_config_update, network_config_update_pubkey,
+    read_network_config_update_version, write_network_config_update_version, NetworkConfigManifest,
+    SignedNetworkConfigManifest, NETWORK_CONFIG_UPDATE_DOMAIN, NETWORK_CONFIG_UPDATE_PUBKEYS,
+    NETWORK_CONFIG_UPDATE_VERSION_FILE,
+};
 
 pub const DEPLOY_BLOCK_FILE: &str = "deposit_contract_block.txt";
 pub const BOOT_ENR_FILE: &str = "boot_enr.yaml";
 pub const GENESIS_STATE_FILE: &str = "genesis.ssz";
 pub const BASE_CONFIG_FILE: &str = "config.yaml";
+pub const PRESET_DIR: &str = "preset";
 
 // Creates definitions for:
 //
@@ -105,6 +114,9 @@ pub struct Eth2NetworkConfig {
     pub genesis_state_bytes: Option<GenesisStateBytes>,
     pub config: Config,
     pub kzg_trusted_setup: Option<Vec<u8>>,
+    /// A directory of preset files (e.g. `phase0.yaml`) which override the values of the
+    /// compiled-in preset when constructing the `ChainSpec`.
+    pub preset_dir: Option<PathBuf>,
 }
 
 impl Eth2NetworkConfig {
@@ -136,15 +148,19 @@ impl Eth2NetworkConfig {
                 .map(Into::into),
             config,
             kzg_trusted_setup,
+            preset_dir: None,
         })
     }
 
     /// Returns an identifier that should be used for selecting an `EthSpec` instance for this
     /// network configuration.
     pub fn eth_spec_id(&self) -> Result<EthSpecId, String> {
-        self.config
-            .eth_spec_id()
-            .ok_or_else(|| "Config does not match any known preset".to_string())
+        self.config.eth_spec_id().ok_or_else(|| {
+            format!(
+                "Config PRESET_BASE {:?} does not match any known preset",
+                self.config.preset_base
+            )
+        })
     }
 
     /// Returns `true` if this configuration contains a `BeaconState`.
@@ -173,14 +189,28 @@ impl Eth2NetworkConfig {
         }
     }
 
-    /// Construct a consolidated `ChainSpec` from the YAML config.
+    /// Construct a consolidated `ChainSpec` from the YAML config and any preset files.
     pub fn chain_spec<E: EthSpec>(&self) -> Result<ChainSpec, String> {
-        ChainSpec::from_config::<E>(&self.config).ok_or_else(|| {
+        if self.config.preset_base != E::spec_name().to_string() {
+            return Err(format!(
+                "Config PRESET_BASE {:?} does not match the {} preset in use",
+                self.config.preset_base,
+                E::spec_name()
+            ));
+        }
+
+        let mut spec = ChainSpec::from_config::<E>(&self.config).ok_or_else(|| {
             format!(
                 "YAML configuration incompatible with spec constants for {}",
                 E::spec_name()
             )
-        })
+        })?;
+
+        if let Some(preset_dir) = &self.preset_dir {
+            apply_preset_dir::<E>(preset_dir, &mut spec)?;
+        }
+
+        Ok(spec)
     }
 
     /// Attempts to deserialize `self.beacon_state`, returning an error if it's missing or invalid.
@@ -237,6 +267,23 @@ impl Eth2NetworkConfig {
         }
     }
 
+    /// Downloads the genesis state from `genesis_state_url`, regardless of `genesis_state_source`.
+    ///
+    /// The downloaded bytes must match the SHA256 `checksum`. This allows nodes on networks which
+    /// don't include a genesis state to start without waiting for genesis on the deposit contract.
+    pub async fn download_genesis_state<E: EthSpec>(
+        &self,
+        genesis_state_url: &str,
+        checksum: Hash256,
+        timeout: Duration,
+        log: &Logger,
+    ) -> Result<BeaconState<E>, String> {
+        let spec = self.chain_spec::<E>()?;
+        let bytes = download_genesis_state(&[genesis_state_url], timeout, checksum, log).await?;
+        BeaconState::from_ssz_bytes(bytes.as_ref(), &spec)
+            .map_err(|e| format!("Downloaded genesis state SSZ bytes are invalid: {:?}", e))
+    }
+
     fn get_genesis_state_from_bytes<E: EthSpec>(&self) -> Result<BeaconState<E>, String> {
         let spec = self.chain_spec::<E>()?;
         self.genesis_state_bytes
@@ -361,6 +408,8 @@ impl Eth2NetworkConfig {
 
         let kzg_trusted_setup = get_trusted_setup_from_config(&config);
 
+        let preset_dir = Some(base_dir.join(PRESET_DIR)).filter(|dir| dir.is_dir());
+
         Ok(Self {
             deposit_contract_deploy_block,
             boot_enr,
@@ -368,6 +417,7 @@ impl Eth2NetworkConfig {
             genesis_state_bytes: genesis_state_bytes.map(Into::into),
             config,
             kzg_trusted_setup,
+            preset_dir,
         })
     }
 }
@@ -461,8 +511,10 @@ fn parse_state_download_url(url: &str) -> Result<Url, String> {
 mod tests {
     use super::*;
     use ssz::Encode;
+    use std::net::TcpListener;
+    use std::thread;
     use tempfile::Builder as TempBuilder;
-    use types::{Eth1Data, GnosisEthSpec, MainnetEthSpec};
+    use types::{BasePreset, Eth1Data, GnosisEthSpec, MainnetEthSpec, PresetFile};
 
     type E = MainnetEthSpec;
 
@@ -540,6 +592,67 @@ mod tests {
         }
     }
 
+    /// Serves `body` in response to every HTTP request, returning the URL of the server.
+    fn serve_bytes(body: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("should bind to a free port");
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request);
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\n\
+                    Content-Type: application/octet-stream\r\n\
+                    Content-Length: {}\r\n\
+                    Connection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream
+                    .write_all(header.as_bytes())
+                    .and_then(|()| stream.write_all(&body));
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn download_pinned_genesis_state() {
+        let spec = E::default_spec();
+        let eth1_data = Eth1Data {
+            deposit_root: Hash256::zero(),
+            deposit_count: 0,
+            block_hash: Hash256::zero(),
+        };
+        let genesis_state_bytes = BeaconState::<E>::new(42, eth1_data, &spec).as_ssz_bytes();
+        let checksum = Hash256::from_slice(&Sha256::digest(&genesis_state_bytes));
+        let url = serve_bytes(genesis_state_bytes.clone());
+
+        let network = Eth2NetworkConfig {
+            deposit_contract_deploy_block: 0,
+            boot_enr: None,
+            genesis_state_source: GenesisStateSource::Unknown,
+            genesis_state_bytes: None,
+            config: Config::from_chain_spec::<E>(&spec),
+            kzg_trusted_setup: None,
+            preset_dir: None,
+        };
+        assert!(!network.genesis_state_is_known());
+        let log = logging::test_logger();
+
+        let genesis_state = network
+            .download_genesis_state::<E>(&url, checksum, Duration::from_secs(5), &log)
+            .await
+            .expect("should download the pinned genesis state");
+        assert_eq!(genesis_state.genesis_time(), 42);
+        assert_eq!(genesis_state.as_ssz_bytes(), genesis_state_bytes);
+
+        let error = network
+            .download_genesis_state::<E>(&url, Hash256::zero(), Duration::from_secs(5), &log)
+            .await
+            .expect_err("should reject a genesis state with the wrong checksum");
+        assert!(error.contains("did not match local checksum"), "{}", error);
+    }
+
     #[test]
     fn round_trip() {
         let spec = &E::default_spec();
@@ -559,6 +672,45 @@ mod tests {
         do_test::<E>(None, None, config);
     }
 
+    #[test]
+    fn preset_dir_overrides_chain_spec() {
+        let temp_dir = TempBuilder::new()
+            .prefix("eth2_testnet_test")
+            .tempdir()
+            .expect("should create temp dir");
+        let base_dir = temp_dir.path().join("my_testnet");
+        let mut spec = E::default_spec();
+        let testnet = Eth2NetworkConfig {
+            deposit_contract_deploy_block: 42,
+            boot_enr: None,
+            genesis_state_source: GenesisStateSource::Unknown,
+            genesis_state_bytes: None,
+            config: Config::from_chain_spec::<E>(&spec),
+            kzg_trusted_setup: None,
+            preset_dir: None,
+        };
+        testnet
+            .write_to_file(base_dir.clone(), false)
+            .expect("should write to file");
+
+        spec.shuffle_round_count = 10;
+        let preset_dir = base_dir.join(PRESET_DIR);
+        create_dir_all(&preset_dir).expect("should create preset dir");
+        std::fs::write(
+            preset_dir.join(BasePreset::FILE_NAME),
+            serde_yaml::to_string(&BasePreset::from_chain_spec::<E>(&spec)).unwrap(),
+        )
+        .expect("should write preset");
+
+        let loaded = Eth2NetworkConfig::load(base_dir).expect("should load struct");
+        assert_eq!(loaded.preset_dir, Some(preset_dir));
+        assert_eq!(loaded.chain_spec::<E>().unwrap(), spec);
+        assert!(loaded
+            .chain_spec::<GnosisEthSpec>()
+            .unwrap_err()
+            .contains("PRESET_BASE"));
+    }
+
     fn do_test<E: EthSpec>(
         boot_enr: Option<Vec<Enr<CombinedKey>>>,
         genesis_state: Option<BeaconState<E>>,
@@ -589,6 +741,7 @@ mod tests {
                 .map(Into::into),
             config,
             kzg_trusted_setup: Some(kzg_trusted_setup),
+            preset_dir: None,
         };
 
         testnet
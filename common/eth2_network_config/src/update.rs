@@ -0,0 +1,293 @@
+//! Provides signed "network config update" manifests, which allow the boot ENRs and deposit
+//! contract deploy block of a network to be refreshed at startup without a new release.
+//!
+//! A manifest is a YAML document which is signed with a BLS key. Only manifests signed by the key
+//! embedded in the binary for the relevant network (or supplied by the operator) are accepted.
+//!
+//! Each manifest carries a version and an expiry time. A manifest with a lower version than the
+//! last one applied, or which has expired, is rejected so that old manifests cannot be replayed.
+
+use discv5::enr::{CombinedKey, Enr};
+use pretty_reqwest_error::PrettyReqwestError;
+use reqwest::Client;
+use sensitive_url::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use slog::{info, Logger};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use types::{Hash256, PublicKey, SignatureBytes};
+use url::Url;
+
+/// The public keys which are permitted to sign network config updates, by network name.
+///
+/// No update keys are embedded yet, so networks can only be updated with a key supplied via
+/// `--network-config-update-pubkey`.
+pub const NETWORK_CONFIG_UPDATE_PUBKEYS: &[(&str, &str)] = &[];
+
+/// Prepended to the manifest bytes before hashing, so that a signature over a manifest can never
+/// be valid for any other message signed with the same key.
+pub const NETWORK_CONFIG_UPDATE_DOMAIN: &[u8] = b"LIGHTHOUSE_NETWORK_CONFIG_UPDATE_V1";
+
+/// The file in the data directory which records the version of the last applied manifest.
+pub const NETWORK_CONFIG_UPDATE_VERSION_FILE: &str = "network_config_update_version.txt";
+
+/// The updatable parts of a network's configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConfigManifest {
+    /// The `CONFIG_NAME` of the network this manifest applies to.
+    pub network: String,
+    /// Must never decrease between successive manifests for a network.
+    pub version: u64,
+    /// Unix timestamp in seconds after which the manifest is no longer valid.
+    pub expires_at: u64,
+    pub boot_enr: Vec<Enr<CombinedKey>>,
+    #[serde(default)]
+    pub deposit_contract_deploy_block: Option<u64>,
+}
+
+/// A `NetworkConfigManifest`, serialized as YAML, along with a signature over the SHA256 of
+/// `NETWORK_CONFIG_UPDATE_DOMAIN` followed by the YAML bytes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedNetworkConfigManifest {
+    pub manifest: String,
+    pub signature: SignatureBytes,
+}
+
+impl SignedNetworkConfigManifest {
+    pub fn signing_root(manifest: &str) -> Hash256 {
+        let mut hasher = Sha256::new();
+        hasher.update(NETWORK_CONFIG_UPDATE_DOMAIN);
+        hasher.update(manifest.as_bytes());
+        Hash256::from_slice(&hasher.finalize())
+    }
+
+    /// Verify the signature against `pubkey` and return the manifest, which must be for
+    /// `network_name`, unexpired at `now` (in Unix seconds) and no older than `min_version`.
+    pub fn verify(
+        &self,
+        pubkey: &PublicKey,
+        network_name: &str,
+        min_version: Option<u64>,
+        now: u64,
+    ) -> Result<NetworkConfigManifest, String> {
+        let signature = self
+            .signature
+            .decompress()
+            .map_err(|e| format!("Invalid manifest signature: {:?}", e))?;
+        if !signature.verify(pubkey, Self::signing_root(&self.manifest)) {
+            return Err("Manifest signature does not match the network update key".to_string());
+        }
+
+        let manifest: NetworkConfigManifest = serde_yaml::from_str(&self.manifest)
+            .map_err(|e| format!("Unable to parse manifest: {:?}", e))?;
+        if manifest.network != network_name {
+            return Err(format!(
+                "Manifest is for network {}, not {}",
+                manifest.network, network_name
+            ));
+        }
+        if manifest.expires_at <= now {
+            return Err(format!("Manifest expired at {}", manifest.expires_at));
+        }
+        if let Some(min_version) = min_version {
+            if manifest.version < min_version {
+                return Err(format!(
+                    "Manifest version {} is older than the applied version {}",
+                    manifest.version, min_version
+                ));
+            }
+        }
+
+        Ok(manifest)
+    }
+}
+
+/// Return the key permitted to sign updates for `network_name`, if any.
+pub fn network_config_update_pubkey(network_name: &str) -> Result<Option<PublicKey>, String> {
+    NETWORK_CONFIG_UPDATE_PUBKEYS
+        .iter()
+        .find(|(name, _)| *name == network_name)
+        .map(|(_, pubkey)| {
+            PublicKey::from_str(pubkey)
+                .map_err(|e| format!("Invalid network update key for {}: {}", network_name, e))
+        })
+        .transpose()
+}
+
+/// Read the version of the last manifest applied in `data_dir`, if any.
+pub fn read_network_config_update_version(data_dir: &Path) -> Result<Option<u64>, String> {
+    let path = data_dir.join(NETWORK_CONFIG_UPDATE_VERSION_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(&path)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
+        .trim()
+        .parse()
+        .map(Some)
+        .map_err(|e| {
+            format!(
+                "Invalid network config update version in {:?}: {:?}",
+                path, e
+            )
+        })
+}
+
+/// Record `version` as the last manifest version applied in `data_dir`.
+pub fn write_network_config_update_version(data_dir: &Path, version: u64) -> Result<(), String> {
+    let path = data_dir.join(NETWORK_CONFIG_UPDATE_VERSION_FILE);
+    fs::write(&path, version.to_string())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", path, e))
+}
+
+/// Download a signed manifest from `url` and verify it against `pubkey`.
+pub async fn download_network_config_update(
+    url: &str,
+    timeout: Duration,
+    pubkey: &PublicKey,
+    network_name: &str,
+    min_version: Option<u64>,
+    now: u64,
+    log: &Logger,
+) -> Result<NetworkConfigManifest, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid network config update URL: {:?}", e))?;
+    let redacted_url = SensitiveUrl::new(url.clone())
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| "<REDACTED>".to_string());
+
+    info!(
+        log,
+        "Downloading network config update";
+        "server" => &redacted_url,
+        "timeout" => ?timeout,
+    );
+
+    let bytes = Client::new()
+        .get(url)
+        .timeout(timeout)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| PrettyReqwestError::from(e).to_string())?
+        .bytes()
+        .await
+        .map_err(|e| PrettyReqwestError::from(e).to_string())?;
+
+    let signed: SignedNetworkConfigManifest = serde_yaml::from_slice(&bytes)
+        .map_err(|e| format!("Unable to parse signed manifest: {:?}", e))?;
+
+    signed.verify(pubkey, network_name, min_version, now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::Keypair;
+
+    const NETWORK: &str = "mainnet";
+    const VERSION: u64 = 5;
+    const EXPIRES_AT: u64 = 1_000;
+    const NOW: u64 = 500;
+
+    fn manifest_yaml(network: &str) -> String {
+        serde_yaml::to_string(&NetworkConfigManifest {
+            network: network.to_string(),
+            version: VERSION,
+            expires_at: EXPIRES_AT,
+            boot_enr: vec![],
+            deposit_contract_deploy_block: Some(42),
+        })
+        .unwrap()
+    }
+
+    fn sign(keypair: &Keypair, manifest: String) -> SignedNetworkConfigManifest {
+        let root = SignedNetworkConfigManifest::signing_root(&manifest);
+        SignedNetworkConfigManifest {
+            manifest,
+            signature: keypair.sk.sign(root).into(),
+        }
+    }
+
+    #[test]
+    fn valid_manifest_verifies() {
+        let keypair = Keypair::random();
+        let signed = sign(&keypair, manifest_yaml(NETWORK));
+        let manifest = signed.verify(&keypair.pk, NETWORK, None, NOW).unwrap();
+        assert_eq!(manifest.deposit_contract_deploy_block, Some(42));
+        assert_eq!(manifest.version, VERSION);
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        let signed = sign(&Keypair::random(), manifest_yaml(NETWORK));
+        assert!(signed
+            .verify(&Keypair::random().pk, NETWORK, None, NOW)
+            .is_err());
+    }
+
+    #[test]
+    fn tampered_manifest_is_rejected() {
+        let keypair = Keypair::random();
+        let mut signed = sign(&keypair, manifest_yaml(NETWORK));
+        signed.manifest = signed.manifest.replace("42", "43");
+        assert!(signed.verify(&keypair.pk, NETWORK, None, NOW).is_err());
+    }
+
+    #[test]
+    fn wrong_network_is_rejected() {
+        let keypair = Keypair::random();
+        let signed = sign(&keypair, manifest_yaml("holesky"));
+        assert!(signed.verify(&keypair.pk, NETWORK, None, NOW).is_err());
+    }
+
+    #[test]
+    fn signature_without_domain_is_rejected() {
+        let keypair = Keypair::random();
+        let manifest = manifest_yaml(NETWORK);
+        let root = Hash256::from_slice(&Sha256::digest(manifest.as_bytes()));
+        let signed = SignedNetworkConfigManifest {
+            manifest,
+            signature: keypair.sk.sign(root).into(),
+        };
+        assert!(signed.verify(&keypair.pk, NETWORK, None, NOW).is_err());
+    }
+
+    #[test]
+    fn expired_manifest_is_rejected() {
+        let keypair = Keypair::random();
+        let signed = sign(&keypair, manifest_yaml(NETWORK));
+        assert!(signed
+            .verify(&keypair.pk, NETWORK, None, EXPIRES_AT - 1)
+            .is_ok());
+        assert!(signed
+            .verify(&keypair.pk, NETWORK, None, EXPIRES_AT)
+            .is_err());
+    }
+
+    #[test]
+    fn older_version_is_rejected() {
+        let keypair = Keypair::random();
+        let signed = sign(&keypair, manifest_yaml(NETWORK));
+        // Re-applying the same version is permitted so that restarts keep working.
+        assert!(signed
+            .verify(&keypair.pk, NETWORK, Some(VERSION), NOW)
+            .is_ok());
+        assert!(signed
+            .verify(&keypair.pk, NETWORK, Some(VERSION + 1), NOW)
+            .is_err());
+    }
+
+    #[test]
+    fn version_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_network_config_update_version(dir.path()), Ok(None));
+        write_network_config_update_version(dir.path(), VERSION).unwrap();
+        assert_eq!(
+            read_network_config_update_version(dir.path()),
+            Ok(Some(VERSION))
+        );
+    }
+}
@@ -56,10 +56,10 @@
 use prometheus::{Error, HistogramOpts, Opts};
 use std::time::Duration;
 
-use prometheus::core::{Atomic, GenericGauge, GenericGaugeVec};
+use prometheus::core::{Atomic, GenericGauge, GenericGaugeVec, MetricVec, MetricVecBuilder};
 pub use prometheus::{
     exponential_buckets, linear_buckets,
-    proto::{Metric, MetricFamily, MetricType},
+    proto::{LabelPair, Metric, MetricFamily, MetricType},
     Encoder, Gauge, GaugeVec, Histogram, HistogramTimer, HistogramVec, IntCounter, IntCounterVec,
     IntGauge, IntGaugeVec, Result, TextEncoder, DEFAULT_BUCKETS,
 };
@@ -224,6 +224,17 @@ pub fn set_int_gauge(int_gauge_vec: &Result<IntGaugeVec>, name: &[&str], value:
     }
 }
 
+/// If `metric_vec.is_ok()`, removes the metric with the given label `values`, if it exists.
+pub fn remove_label_values<T: MetricVecBuilder>(
+    metric_vec: &Result<MetricVec<T>>,
+    values: &[&str],
+) {
+    if let Ok(metric_vec) = metric_vec {
+        // An error indicates that the metric does not exist, which is fine.
+        let _ = metric_vec.remove_label_values(values);
+    }
+}
+
 /// If `int_counter_vec.is_ok()`, returns a counter with the given `name`.
 pub fn get_int_counter(
     int_counter_vec: &Result<IntCounterVec>,
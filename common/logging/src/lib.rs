@@ -2,6 +2,10 @@ use lazy_static::lazy_static;
 use lighthouse_metrics::{
     inc_counter, try_create_int_counter, IntCounter, Result as MetricsResult,
 };
+use opentelemetry::trace::TraceError;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{trace::Tracer, Resource};
 use slog::Logger;
 use slog_term::Decorator;
 use std::io::{Result, Write};
@@ -14,11 +18,18 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 pub const MAX_MESSAGE_WIDTH: usize = 40;
 
+/// The target of the spans which trace blocks and blobs through the import pipeline.
+///
+/// These spans are only enabled when exporting to an OTLP endpoint.
+pub const IMPORT_SPAN_TARGET: &str = "block_import";
+
 pub mod async_record;
+mod log_levels;
 mod sse_logging_components;
 mod tracing_logging_layer;
 mod tracing_metrics_layer;
 
+pub use log_levels::{parse_level, LogLevelFilter, LogLevels};
 pub use sse_logging_components::SSELoggingComponents;
 pub use tracing_metrics_layer::MetricsLayer;
 
@@ -221,7 +232,12 @@ impl TimeLatch {
     }
 }
 
-pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
+/// Initialize the global `tracing` subscriber, which writes the logs of libp2p and discv5 to files in
+/// `base_tracing_log_path`.
+///
+/// If `otlp_endpoint` is set, the import pipeline spans are exported to an OTLP collector at that
+/// endpoint. This must be called from within a Tokio runtime in that case.
+pub fn create_tracing_layer(base_tracing_log_path: PathBuf, otlp_endpoint: Option<String>) {
     let filter_layer = match tracing_subscriber::EnvFilter::try_from_default_env()
         .or_else(|_| tracing_subscriber::EnvFilter::try_new("warn"))
     {
@@ -254,6 +270,20 @@ pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
         return;
     };
 
+    let otlp_layer = match otlp_endpoint.map(otlp_tracer).transpose() {
+        Ok(tracer) => tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer)),
+        Err(e) => {
+            eprintln!("Failed to initialize OTLP exporter {e}");
+            None
+        }
+    };
+
+    // The import spans are created at the info level, which is excluded by the default filter.
+    let filter_layer = match format!("{IMPORT_SPAN_TARGET}=info").parse() {
+        Ok(directive) if otlp_layer.is_some() => filter_layer.add_directive(directive),
+        _ => filter_layer,
+    };
+
     let (libp2p_non_blocking_writer, _libp2p_guard) = NonBlocking::new(libp2p_writer);
     let (discv5_non_blocking_writer, _discv5_guard) = NonBlocking::new(discv5_writer);
 
@@ -270,12 +300,38 @@ pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
         .finish()
         .with(MetricsLayer)
         .with(custom_layer)
+        .with(otlp_layer)
         .try_init()
     {
         eprintln!("Failed to initialize dependency logging {e}");
     }
 }
 
+/// Build a tracer which exports spans in batches to the OTLP collector at `endpoint`.
+fn otlp_tracer(endpoint: String) -> Result<Tracer, TraceError> {
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(
+            opentelemetry_sdk::trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                "lighthouse",
+            )])),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+}
+
+/// Export any spans which are yet to be sent to the OTLP collector, and stop the exporter.
+///
+/// This is a no-op if no OTLP endpoint was configured.
+pub fn shutdown_tracing() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
 /// Return a logger suitable for test usage.
 ///
 /// By default no logs will be printed, but they can be enabled via
@@ -0,0 +1,245 @@
+//! This module provides an implementation of `slog::Drain` which filters records using a default
+//! level and a set of per-module overrides, all of which may be changed at runtime.
+//!
+//! Levels are described using a comma-separated list of directives. A directive of the form
+//! `module=level` sets the level for `module` and all of its sub-modules, whilst a bare `level`
+//! sets the default level. For example, `info,network=debug,network::sync=trace`.
+//!
+//! The per-module overrides apply to every drain wrapped in a `LogLevelFilter`. A drain may use
+//! its own fixed default level (e.g., the log file, which has its own `--logfile-debug-level`),
+//! otherwise it uses the shared default level.
+
+use parking_lot::RwLock;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::fmt;
+use std::sync::Arc;
+
+/// Parse a level in the format used by the `--debug-level` flag.
+pub fn parse_level(level: &str) -> Result<Level, String> {
+    match level {
+        "info" => Ok(Level::Info),
+        "debug" => Ok(Level::Debug),
+        "trace" => Ok(Level::Trace),
+        "warn" => Ok(Level::Warning),
+        "error" => Ok(Level::Error),
+        "crit" => Ok(Level::Critical),
+        unknown => Err(format!("Unknown log level: {}", unknown)),
+    }
+}
+
+/// The inverse of `parse_level`.
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+        Level::Warning => "warn",
+        Level::Error => "error",
+        Level::Critical => "crit",
+    }
+}
+
+struct Levels {
+    default: Level,
+    /// Sorted so that the longest (i.e., most specific) module comes first.
+    overrides: Vec<(String, Level)>,
+}
+
+impl Levels {
+    fn override_for(&self, module: &str) -> Option<Level> {
+        self.overrides
+            .iter()
+            .find(|(prefix, _)| {
+                module
+                    .strip_prefix(prefix.as_str())
+                    .map_or(false, |rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .map(|(_, level)| *level)
+    }
+}
+
+/// A handle to a set of log levels that is shared between a `LogLevelFilter` and any component
+/// which may wish to update the levels (e.g., the HTTP API).
+#[derive(Clone)]
+pub struct LogLevels {
+    levels: Arc<RwLock<Levels>>,
+}
+
+impl LogLevels {
+    /// Create a new set of levels with no per-module overrides.
+    pub fn new(default: Level) -> Self {
+        Self {
+            levels: Arc::new(RwLock::new(Levels {
+                default,
+                overrides: vec![],
+            })),
+        }
+    }
+
+    /// Replace all per-module overrides with those in `directives`.
+    ///
+    /// If `directives` contains a bare level then the default level is also updated, otherwise
+    /// the default is left unchanged. No changes are made if any directive is invalid.
+    pub fn update(&self, directives: &str) -> Result<(), String> {
+        let mut default = None;
+        let mut overrides: Vec<(String, Level)> = vec![];
+
+        for directive in directives.split(',').map(str::trim) {
+            if directive.is_empty() {
+                continue;
+            }
+
+            if let Some((module, level)) = directive.split_once('=') {
+                let module = module.trim();
+                if module.is_empty() {
+                    return Err(format!("Missing module in log level: {}", directive));
+                }
+                let level = parse_level(level.trim())?;
+                // Later directives take precedence over earlier ones.
+                overrides.retain(|(existing, _)| existing != module);
+                overrides.push((module.to_string(), level));
+            } else {
+                default = Some(parse_level(directive)?);
+            }
+        }
+
+        overrides.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        let mut levels = self.levels.write();
+        if let Some(default) = default {
+            levels.default = default;
+        }
+        levels.overrides = overrides;
+
+        Ok(())
+    }
+
+    /// Returns `true` if a record from `module` at `level` should be logged.
+    pub fn is_enabled(&self, module: &str, level: Level) -> bool {
+        self.is_enabled_with_default(module, level, None)
+    }
+
+    /// Like `is_enabled`, but using `default` rather than the shared default level for modules
+    /// without an override.
+    fn is_enabled_with_default(&self, module: &str, level: Level, default: Option<Level>) -> bool {
+        let levels = self.levels.read();
+        let min_level = levels
+            .override_for(module)
+            .unwrap_or_else(|| default.unwrap_or(levels.default));
+        level.is_at_least(min_level)
+    }
+}
+
+impl fmt::Display for LogLevels {
+    /// Formats the levels as directives which may be passed to `Self::update`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let levels = self.levels.read();
+        write!(f, "{}", level_name(levels.default))?;
+        for (module, level) in &levels.overrides {
+            write!(f, ",{}={}", module, level_name(*level))?;
+        }
+        Ok(())
+    }
+}
+
+/// A drain which only passes records to `D` if they're permitted by the `LogLevels`.
+pub struct LogLevelFilter<D> {
+    drain: D,
+    levels: LogLevels,
+    /// Overrides the shared default level of `levels`, if set.
+    default: Option<Level>,
+}
+
+impl<D> LogLevelFilter<D> {
+    /// Filter records using the shared default level and per-module overrides of `levels`.
+    pub fn new(drain: D, levels: LogLevels) -> Self {
+        Self {
+            drain,
+            levels,
+            default: None,
+        }
+    }
+
+    /// Filter records using the per-module overrides of `levels`, and `default` for all other
+    /// modules.
+    pub fn with_default(drain: D, levels: LogLevels, default: Level) -> Self {
+        Self {
+            drain,
+            levels,
+            default: Some(default),
+        }
+    }
+}
+
+impl<D: Drain> Drain for LogLevelFilter<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, logger_values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if self
+            .levels
+            .is_enabled_with_default(record.module(), record.level(), self.default)
+        {
+            self.drain.log(record, logger_values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_overrides() {
+        let levels = LogLevels::new(Level::Info);
+        levels
+            .update("network=debug, network::sync=trace,store=error")
+            .unwrap();
+
+        assert!(levels.is_enabled("beacon_chain", Level::Info));
+        assert!(!levels.is_enabled("beacon_chain", Level::Debug));
+        assert!(levels.is_enabled("network::router", Level::Debug));
+        assert!(!levels.is_enabled("network::router", Level::Trace));
+        assert!(levels.is_enabled("network::sync::manager", Level::Trace));
+        assert!(!levels.is_enabled("store::hot_cold_store", Level::Warning));
+        // A module which merely shares a prefix is not affected.
+        assert!(!levels.is_enabled("network_utils", Level::Debug));
+
+        assert_eq!(
+            levels.to_string(),
+            "info,network::sync=trace,network=debug,store=error"
+        );
+    }
+
+    #[test]
+    fn update_replaces_overrides() {
+        let levels = LogLevels::new(Level::Info);
+        levels.update("network=debug").unwrap();
+        levels.update("warn,store=debug").unwrap();
+        assert_eq!(levels.to_string(), "warn,store=debug");
+
+        // Invalid directives leave the levels unchanged.
+        assert!(levels.update("error,network=loud").is_err());
+        assert!(levels.update("=debug").is_err());
+        assert_eq!(levels.to_string(), "warn,store=debug");
+    }
+
+    #[test]
+    fn fixed_default_level() {
+        let levels = LogLevels::new(Level::Info);
+        levels.update("network=trace").unwrap();
+
+        // A filter with a fixed default uses it for modules without an override...
+        assert!(levels.is_enabled_with_default("store", Level::Debug, Some(Level::Debug)));
+        assert!(!levels.is_enabled("store", Level::Debug));
+        // ...and the shared overrides for all others.
+        assert!(levels.is_enabled_with_default("network", Level::Trace, Some(Level::Debug)));
+
+        // Changing the shared default doesn't affect the fixed default.
+        levels.update("crit").unwrap();
+        assert!(levels.is_enabled_with_default("store", Level::Debug, Some(Level::Debug)));
+        assert!(!levels.is_enabled("store", Level::Error));
+    }
+}
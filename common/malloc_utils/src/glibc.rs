@@ -93,6 +93,23 @@ pub fn scrape_mallinfo_metrics() {
     set_gauge(&MALLINFO_KEEPCOST, unsigned_i64(mallinfo.keepcost));
 }
 
+/// Calls `mallinfo` and returns the results as `(name, value)` pairs.
+pub fn mallinfo_stats() -> Result<Vec<(&'static str, u64)>, String> {
+    let mallinfo = mallinfo();
+
+    Ok(vec![
+        ("arena", unsigned_i64(mallinfo.arena) as u64),
+        ("ordblks", unsigned_i64(mallinfo.ordblks) as u64),
+        ("smblks", unsigned_i64(mallinfo.smblks) as u64),
+        ("hblks", unsigned_i64(mallinfo.hblks) as u64),
+        ("hblkhd", unsigned_i64(mallinfo.hblkhd) as u64),
+        ("fsmblks", unsigned_i64(mallinfo.fsmblks) as u64),
+        ("uordblks", unsigned_i64(mallinfo.uordblks) as u64),
+        ("fordblks", unsigned_i64(mallinfo.fordblks) as u64),
+        ("keepcost", unsigned_i64(mallinfo.keepcost) as u64),
+    ])
+}
+
 /// Cast a C integer as returned by `mallinfo` to an unsigned i64.
 ///
 /// A cast from `i32` to `i64` preserves the sign bit, resulting in incorrect negative values.
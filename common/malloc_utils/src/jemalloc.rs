@@ -50,3 +50,22 @@ pub fn scrape_jemalloc_metrics_fallible() -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Returns the current `jemalloc` statistics as `(name, value)` pairs.
+pub fn jemalloc_stats() -> Result<Vec<(&'static str, u64)>, String> {
+    let read = || -> Result<_, Error> {
+        // Advance the epoch so that the underlying statistics are updated.
+        epoch::advance()?;
+
+        Ok(vec![
+            ("num_arenas", arenas::narenas::read()? as u64),
+            ("allocated", stats::allocated::read()? as u64),
+            ("active", stats::active::read()? as u64),
+            ("mapped", stats::mapped::read()? as u64),
+            ("metadata", stats::metadata::read()? as u64),
+            ("resident", stats::resident::read()? as u64),
+            ("retained", stats::retained::read()? as u64),
+        ])
+    };
+    read().map_err(|e| format!("Unable to read jemalloc stats: {:?}", e))
+}
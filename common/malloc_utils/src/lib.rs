@@ -43,6 +43,7 @@ pub use interface::*;
 ))]
 mod interface {
     pub use crate::glibc::configure_glibc_malloc as configure_memory_allocator;
+    pub use crate::glibc::mallinfo_stats as allocator_stats;
     pub use crate::glibc::scrape_mallinfo_metrics as scrape_allocator_metrics;
 }
 
@@ -53,6 +54,7 @@ mod interface {
         Ok(())
     }
 
+    pub use crate::jemalloc::jemalloc_stats as allocator_stats;
     pub use crate::jemalloc::scrape_jemalloc_metrics as scrape_allocator_metrics;
 }
 
@@ -68,4 +70,10 @@ mod interface {
 
     #[allow(dead_code)]
     pub fn scrape_allocator_metrics() {}
+
+    /// The system allocator does not provide any statistics.
+    #[allow(dead_code, clippy::unnecessary_wraps)]
+    pub fn allocator_stats() -> Result<Vec<(&'static str, u64)>, String> {
+        Ok(vec![])
+    }
 }
@@ -57,6 +57,8 @@ pub struct Config {
     pub freezer_db_path: Option<PathBuf>,
     /// User-defined update period in seconds.
     pub update_period_secs: Option<u64>,
+    /// Path to a file containing a token to send in the `Authorization: Bearer` header.
+    pub bearer_token_path: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -68,11 +70,27 @@ pub struct MonitoringHttpClient {
     freezer_db_path: Option<PathBuf>,
     update_period: Duration,
     monitoring_endpoint: SensitiveUrl,
+    bearer_token: Option<String>,
     log: slog::Logger,
 }
 
 impl MonitoringHttpClient {
     pub fn new(config: &Config, log: slog::Logger) -> Result<Self, String> {
+        let bearer_token = config
+            .bearer_token_path
+            .as_ref()
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .map(|token| token.trim().to_string())
+                    .map_err(|e| {
+                        format!(
+                            "Unable to read monitoring endpoint token from {:?}: {:?}",
+                            path, e
+                        )
+                    })
+            })
+            .transpose()?;
+
         Ok(Self {
             client: reqwest::Client::new(),
             db_path: config.db_path.clone(),
@@ -82,15 +100,18 @@ impl MonitoringHttpClient {
             ),
             monitoring_endpoint: SensitiveUrl::parse(&config.monitoring_endpoint)
                 .map_err(|e| format!("Invalid monitoring endpoint: {:?}", e))?,
+            bearer_token,
             log,
         })
     }
 
     /// Perform a HTTP POST request.
     async fn post<T: Serialize, U: IntoUrl>(&self, url: U, body: &T) -> Result<(), Error> {
-        let response = self
-            .client
-            .post(url)
+        let mut builder = self.client.post(url);
+        if let Some(token) = &self.bearer_token {
+            builder = builder.bearer_auth(token);
+        }
+        let response = builder
             .json(body)
             .timeout(Duration::from_secs(TIMEOUT_DURATION))
             .send()
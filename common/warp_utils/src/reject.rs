@@ -1,4 +1,4 @@
-use eth2::types::{ErrorMessage, Failure, IndexedErrorMessage};
+use eth2::types::{ErrorCode, ErrorMessage, Failure, IndexedErrorMessage, Slot};
 use std::convert::Infallible;
 use std::error::Error;
 use std::fmt;
@@ -73,6 +73,36 @@ pub fn custom_not_found(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(CustomNotFound(msg))
 }
 
+/// A not found error which identifies the kind of data that couldn't be found with an `ErrorCode`.
+#[derive(Debug)]
+pub struct CodedNotFound {
+    pub error_code: ErrorCode,
+    pub message: String,
+}
+
+impl Reject for CodedNotFound {}
+
+pub fn unknown_block(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(CodedNotFound {
+        error_code: ErrorCode::UnknownBlock,
+        message: msg,
+    })
+}
+
+pub fn unknown_state(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(CodedNotFound {
+        error_code: ErrorCode::UnknownState,
+        message: msg,
+    })
+}
+
+pub fn pruned(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(CodedNotFound {
+        error_code: ErrorCode::Pruned,
+        message: msg,
+    })
+}
+
 #[derive(Debug)]
 pub struct CustomBadRequest(pub String);
 
@@ -145,6 +175,22 @@ pub fn unsupported_media_type(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(UnsupportedMediaType(msg))
 }
 
+/// Loading the requested state would require replaying more blocks than the replay budget.
+#[derive(Debug)]
+pub struct ReplayBudgetExceeded {
+    pub message: String,
+    pub nearest_stored_slot: Slot,
+}
+
+impl Reject for ReplayBudgetExceeded {}
+
+pub fn replay_budget_exceeded(msg: String, nearest_stored_slot: Slot) -> warp::reject::Rejection {
+    warp::reject::custom(ReplayBudgetExceeded {
+        message: msg,
+        nearest_stored_slot,
+    })
+}
+
 #[derive(Debug)]
 pub struct IndexedBadRequestErrors {
     pub message: String,
@@ -162,15 +208,29 @@ pub fn indexed_bad_request(message: String, failures: Vec<Failure>) -> warp::rej
 pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, Infallible> {
     let code;
     let message;
+    let error_code;
+    let mut nearest_stored_slot = None;
 
     if let Some(e) = err.find::<crate::reject::IndexedBadRequestErrors>() {
         message = format!("BAD_REQUEST: {}", e.message);
         code = StatusCode::BAD_REQUEST;
 
+        // Each failure is an object which failed validation, unless stated otherwise.
+        let failures = e
+            .failures
+            .iter()
+            .cloned()
+            .map(|mut failure| {
+                failure.error_code.get_or_insert(ErrorCode::InvalidObject);
+                failure
+            })
+            .collect();
+
         let json = warp::reply::json(&IndexedErrorMessage {
             code: code.as_u16(),
             message,
-            failures: e.failures.clone(),
+            error_code: Some(ErrorCode::BadRequest),
+            failures,
         });
 
         return Ok(warp::reply::with_status(json, code));
@@ -179,39 +239,60 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
     if err.is_not_found() {
         code = StatusCode::NOT_FOUND;
         message = "NOT_FOUND".to_string();
+        error_code = ErrorCode::NotFound;
     } else if err.find::<crate::reject::UnsupportedMediaType>().is_some() {
         code = StatusCode::UNSUPPORTED_MEDIA_TYPE;
         message = "UNSUPPORTED_MEDIA_TYPE".to_string();
+        error_code = ErrorCode::UnsupportedMediaType;
     } else if let Some(e) = err.find::<crate::reject::CustomDeserializeError>() {
         message = format!("BAD_REQUEST: body deserialize error: {}", e.0);
         code = StatusCode::BAD_REQUEST;
+        error_code = ErrorCode::BadRequest;
     } else if let Some(e) = err.find::<warp::filters::body::BodyDeserializeError>() {
         message = format!("BAD_REQUEST: body deserialize error: {}", e);
         code = StatusCode::BAD_REQUEST;
+        error_code = ErrorCode::BadRequest;
     } else if let Some(e) = err.find::<warp::reject::InvalidQuery>() {
         code = StatusCode::BAD_REQUEST;
         message = format!("BAD_REQUEST: invalid query: {}", e);
+        error_code = ErrorCode::BadRequest;
     } else if let Some(e) = err.find::<crate::reject::BeaconChainError>() {
         code = StatusCode::INTERNAL_SERVER_ERROR;
         message = format!("UNHANDLED_ERROR: {:?}", e.0);
+        error_code = ErrorCode::InternalError;
     } else if let Some(e) = err.find::<crate::reject::BeaconStateError>() {
         code = StatusCode::INTERNAL_SERVER_ERROR;
         message = format!("UNHANDLED_ERROR: {:?}", e.0);
+        error_code = ErrorCode::InternalError;
     } else if let Some(e) = err.find::<crate::reject::SlotProcessingError>() {
         code = StatusCode::INTERNAL_SERVER_ERROR;
         message = format!("UNHANDLED_ERROR: {:?}", e.0);
+        error_code = ErrorCode::InternalError;
     } else if let Some(e) = err.find::<crate::reject::BlockProductionError>() {
         code = StatusCode::INTERNAL_SERVER_ERROR;
         message = format!("UNHANDLED_ERROR: {:?}", e.0);
+        error_code = ErrorCode::InternalError;
     } else if let Some(e) = err.find::<crate::reject::CustomNotFound>() {
         code = StatusCode::NOT_FOUND;
         message = format!("NOT_FOUND: {}", e.0);
+        error_code = ErrorCode::NotFound;
+    } else if let Some(e) = err.find::<crate::reject::CodedNotFound>() {
+        code = StatusCode::NOT_FOUND;
+        message = format!("NOT_FOUND: {}", e.message);
+        error_code = e.error_code;
+    } else if let Some(e) = err.find::<crate::reject::ReplayBudgetExceeded>() {
+        code = StatusCode::PAYLOAD_TOO_LARGE;
+        message = format!("PAYLOAD_TOO_LARGE: {}", e.message);
+        error_code = ErrorCode::ReplayBudgetExceeded;
+        nearest_stored_slot = Some(e.nearest_stored_slot);
     } else if let Some(e) = err.find::<crate::reject::CustomBadRequest>() {
         code = StatusCode::BAD_REQUEST;
         message = format!("BAD_REQUEST: {}", e.0);
+        error_code = ErrorCode::BadRequest;
     } else if let Some(e) = err.find::<crate::reject::CustomServerError>() {
         code = StatusCode::INTERNAL_SERVER_ERROR;
         message = format!("INTERNAL_SERVER_ERROR: {}", e.0);
+        error_code = ErrorCode::InternalError;
     } else if let Some(e) = err.find::<crate::reject::BroadcastWithoutImport>() {
         code = StatusCode::ACCEPTED;
         message = format!(
@@ -219,38 +300,49 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
             fully imported to the local database: {}",
             e.0
         );
+        error_code = ErrorCode::BroadcastWithoutImport;
     } else if let Some(e) = err.find::<crate::reject::ObjectInvalid>() {
         code = StatusCode::BAD_REQUEST;
         message = format!("BAD_REQUEST: Invalid object: {}", e.0);
+        error_code = ErrorCode::InvalidObject;
     } else if let Some(e) = err.find::<crate::reject::NotSynced>() {
         code = StatusCode::SERVICE_UNAVAILABLE;
         message = format!("SERVICE_UNAVAILABLE: beacon node is syncing: {}", e.0);
+        error_code = ErrorCode::NotSynced;
     } else if let Some(e) = err.find::<crate::reject::InvalidAuthorization>() {
         code = StatusCode::FORBIDDEN;
         message = format!("FORBIDDEN: Invalid auth token: {}", e.0);
+        error_code = ErrorCode::Forbidden;
     } else if let Some(e) = err.find::<warp::reject::MissingHeader>() {
         if e.name().eq("Authorization") {
             code = StatusCode::UNAUTHORIZED;
             message = "UNAUTHORIZED: missing Authorization header".to_string();
+            error_code = ErrorCode::Unauthorized;
         } else {
             code = StatusCode::BAD_REQUEST;
             message = format!("BAD_REQUEST: missing {} header", e.name());
+            error_code = ErrorCode::BadRequest;
         }
     } else if let Some(e) = err.find::<warp::reject::InvalidHeader>() {
         code = StatusCode::BAD_REQUEST;
         message = format!("BAD_REQUEST: invalid {} header", e.name());
+        error_code = ErrorCode::BadRequest;
     } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
         code = StatusCode::METHOD_NOT_ALLOWED;
         message = "METHOD_NOT_ALLOWED".to_string();
+        error_code = ErrorCode::MethodNotAllowed;
     } else {
         code = StatusCode::INTERNAL_SERVER_ERROR;
         message = "UNHANDLED_REJECTION".to_string();
+        error_code = ErrorCode::InternalError;
     }
 
     let json = warp::reply::json(&ErrorMessage {
         code: code.as_u16(),
         message,
+        error_code: Some(error_code),
         stacktraces: vec![],
+        nearest_stored_slot,
     });
 
     Ok(warp::reply::with_status(json, code))
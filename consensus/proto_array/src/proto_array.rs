@@ -853,6 +853,23 @@ impl ProtoArray {
         Ok(())
     }
 
+    /// Indicates if the block with `block_root` is viable for the head, or if its best descendant
+    /// is viable for the head. Returns `false` if the block is unknown.
+    pub fn block_leads_to_viable_head<E: EthSpec>(
+        &self,
+        block_root: &Hash256,
+        current_slot: Slot,
+    ) -> Result<bool, Error> {
+        match self
+            .indices
+            .get(block_root)
+            .and_then(|index| self.nodes.get(*index))
+        {
+            Some(node) => self.node_leads_to_viable_head::<E>(node, current_slot),
+            None => Ok(false),
+        }
+    }
+
     /// Indicates if the node itself is viable for the head, or if its best descendant is viable
     /// for the head.
     fn node_leads_to_viable_head<E: EthSpec>(
@@ -270,6 +270,7 @@ pub enum DoNotReOrg {
     HeadNotLate,
     NotProposing,
     ReOrgsDisabled,
+    ChainHealthDegraded,
 }
 
 impl std::fmt::Display for DoNotReOrg {
@@ -319,6 +320,9 @@ impl std::fmt::Display for DoNotReOrg {
             Self::ReOrgsDisabled => {
                 write!(f, "re-orgs disabled in config")
             }
+            Self::ChainHealthDegraded => {
+                write!(f, "re-orgs disabled whilst chain health is degraded")
+            }
         }
     }
 }
@@ -0,0 +1,161 @@
+use safe_arith::{ArithError, SafeArith};
+use types::{BeaconState, ChainSpec, Epoch, EthSpec, Unsigned};
+
+/// The assumed maximum fraction (as a percentage) of stake which may be safely lost between
+/// syncing a weak subjectivity checkpoint and the current epoch.
+pub const SAFETY_DECAY: u64 = 10;
+
+const ETH_TO_GWEI: u64 = 1_000_000_000;
+
+/// Compute the weak subjectivity period of `state`, as per the spec.
+///
+/// Unlike the spec this does not rely on any of the state's caches, so it can be called on a
+/// checkpoint state prior to it being loaded.
+///
+/// https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/weak-subjectivity.md#compute_weak_subjectivity_period
+/// https://github.com/ethereum/consensus-specs/blob/dev/specs/electra/weak-subjectivity.md#modified-compute_weak_subjectivity_period
+pub fn compute_weak_subjectivity_period<E: EthSpec>(
+    state: &BeaconState<E>,
+    spec: &ChainSpec,
+) -> Result<Epoch, ArithError> {
+    let current_epoch = state.current_epoch();
+    let mut active_validator_count = 0u64;
+    let mut total_active_balance = 0u64;
+    for validator in state.validators() {
+        if validator.is_active_at(current_epoch) {
+            active_validator_count.safe_add_assign(1)?;
+            total_active_balance.safe_add_assign(validator.effective_balance)?;
+        }
+    }
+    let total_active_balance =
+        std::cmp::max(total_active_balance, spec.effective_balance_increment);
+
+    let epochs = if state.fork_name_unchecked().electra_enabled() {
+        electra_weak_subjectivity_epochs(total_active_balance, spec)?
+    } else {
+        base_weak_subjectivity_epochs::<E>(active_validator_count, total_active_balance, spec)?
+    };
+    spec.min_validator_withdrawability_delay.safe_add(epochs)
+}
+
+/// The number of epochs added to `MIN_VALIDATOR_WITHDRAWABILITY_DELAY` prior to Electra.
+fn base_weak_subjectivity_epochs<E: EthSpec>(
+    active_validator_count: u64,
+    total_active_balance: u64,
+    spec: &ChainSpec,
+) -> Result<u64, ArithError> {
+    let n = active_validator_count;
+    if n == 0 {
+        return Ok(0);
+    }
+    let t = total_active_balance.safe_div(n)?.safe_div(ETH_TO_GWEI)?;
+    let max_t = spec.max_effective_balance.safe_div(ETH_TO_GWEI)?;
+    let delta = std::cmp::max(
+        spec.min_per_epoch_churn_limit,
+        n.safe_div(spec.churn_limit_quotient)?,
+    );
+    let max_delta = E::MaxDeposits::to_u64().safe_mul(E::slots_per_epoch())?;
+    let d = SAFETY_DECAY;
+
+    if max_t.safe_mul(d.safe_mul(3)?.safe_add(200)?)?
+        < t.safe_mul(d.safe_mul(12)?.safe_add(200)?)?
+    {
+        let epochs_for_validator_set_churn = n
+            .safe_mul(
+                t.safe_mul(d.safe_mul(12)?.safe_add(200)?)?
+                    .safe_sub(max_t.safe_mul(d.safe_mul(3)?.safe_add(200)?)?)?,
+            )?
+            .safe_div(
+                delta
+                    .safe_mul(600)?
+                    .safe_mul(t.safe_mul(2)?.safe_add(max_t)?)?,
+            )?;
+        let epochs_for_balance_top_ups = n
+            .safe_mul(d.safe_mul(3)?.safe_add(200)?)?
+            .safe_div(max_delta.safe_mul(600)?)?;
+        Ok(std::cmp::max(
+            epochs_for_validator_set_churn,
+            epochs_for_balance_top_ups,
+        ))
+    } else {
+        n.safe_mul(d)?
+            .safe_mul(t)?
+            .safe_mul(3)?
+            .safe_div(max_delta.safe_mul(200)?.safe_mul(max_t.safe_sub(t)?)?)
+    }
+}
+
+/// The number of epochs added to `MIN_VALIDATOR_WITHDRAWABILITY_DELAY` from Electra onwards.
+fn electra_weak_subjectivity_epochs(
+    total_active_balance: u64,
+    spec: &ChainSpec,
+) -> Result<u64, ArithError> {
+    let churn = std::cmp::max(
+        spec.min_per_epoch_churn_limit_electra,
+        total_active_balance.safe_div(spec.churn_limit_quotient)?,
+    );
+    let delta = churn.safe_sub(churn.safe_rem(spec.effective_balance_increment)?)?;
+    SAFETY_DECAY
+        .safe_mul(total_active_balance)?
+        .safe_div(delta.safe_mul(2)?.safe_mul(100)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    /// Values from the table in the phase0 weak subjectivity spec.
+    #[test]
+    fn base_matches_spec_table() {
+        let spec = MainnetEthSpec::default_spec();
+        for (average_balance_eth, validator_count, expected) in [
+            (28, 32_768, 504),
+            (28, 65_536, 752),
+            (28, 131_072, 1_248),
+            (28, 262_144, 2_241),
+            (28, 524_288, 2_241),
+            (28, 1_048_576, 2_241),
+            (32, 32_768, 665),
+            (32, 65_536, 1_075),
+            (32, 131_072, 1_894),
+            (32, 262_144, 3_532),
+            (32, 524_288, 3_532),
+            (32, 1_048_576, 3_532),
+        ] {
+            let total_active_balance = average_balance_eth * ETH_TO_GWEI * validator_count;
+            let epochs = base_weak_subjectivity_epochs::<MainnetEthSpec>(
+                validator_count,
+                total_active_balance,
+                &spec,
+            )
+            .unwrap();
+            assert_eq!(
+                spec.min_validator_withdrawability_delay + epochs,
+                Epoch::new(expected)
+            );
+        }
+    }
+
+    /// Values from the table in the Electra weak subjectivity spec.
+    #[test]
+    fn electra_matches_spec_table() {
+        let spec = MainnetEthSpec::default_spec();
+        for (total_active_balance_eth, expected) in [
+            (1_048_576, 665),
+            (2_097_152, 1_075),
+            (4_194_304, 1_894),
+            (8_388_608, 3_532),
+            (16_777_216, 3_532),
+            (33_554_432, 3_532),
+        ] {
+            let epochs =
+                electra_weak_subjectivity_epochs(total_active_balance_eth * ETH_TO_GWEI, &spec)
+                    .unwrap();
+            assert_eq!(
+                spec.min_validator_withdrawability_delay + epochs,
+                Epoch::new(expected)
+            );
+        }
+    }
+}
@@ -19,10 +19,12 @@ pub use verify_attestation::{
     verify_attestation_for_block_inclusion, verify_attestation_for_state,
 };
 pub use verify_bls_to_execution_change::verify_bls_to_execution_change;
+pub use verify_consolidation::verify_consolidation;
 pub use verify_deposit::{
     get_existing_validator_index, is_valid_deposit_signature, verify_deposit_merkle_proof,
 };
 pub use verify_exit::verify_exit;
+pub use verify_withdrawal_request::verify_withdrawal_request;
 
 pub mod altair;
 pub mod block_signature_verifier;
@@ -35,9 +37,11 @@ pub mod tests;
 mod verify_attestation;
 mod verify_attester_slashing;
 mod verify_bls_to_execution_change;
+mod verify_consolidation;
 mod verify_deposit;
 mod verify_exit;
 mod verify_proposer_slashing;
+mod verify_withdrawal_request;
 
 use crate::common::decrease_balance;
 
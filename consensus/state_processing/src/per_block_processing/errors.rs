@@ -53,6 +53,14 @@ pub enum BlockProcessingError {
         index: usize,
         reason: BlsExecutionChangeInvalid,
     },
+    WithdrawalRequestInvalid {
+        index: usize,
+        reason: WithdrawalRequestInvalid,
+    },
+    ConsolidationInvalid {
+        index: usize,
+        reason: ConsolidationInvalid,
+    },
     SyncAggregateInvalid {
         reason: SyncAggregateInvalid,
     },
@@ -97,38 +105,6 @@ pub enum BlockProcessingError {
         churn_limit: u64,
         minimum: u64,
     },
-    MatchingSourceTargetConsolidation {
-        index: u64,
-    },
-    InactiveConsolidationSource {
-        index: u64,
-        current_epoch: Epoch,
-    },
-    InactiveConsolidationTarget {
-        index: u64,
-        current_epoch: Epoch,
-    },
-    SourceValidatorExiting {
-        index: u64,
-    },
-    TargetValidatorExiting {
-        index: u64,
-    },
-    FutureConsolidationEpoch {
-        current_epoch: Epoch,
-        consolidation_epoch: Epoch,
-    },
-    NoSourceExecutionWithdrawalCredential {
-        index: u64,
-    },
-    NoTargetExecutionWithdrawalCredential {
-        index: u64,
-    },
-    MismatchedWithdrawalCredentials {
-        source_address: Address,
-        target_address: Address,
-    },
-    InavlidConsolidationSignature,
     PendingAttestationInElectra,
 }
 
@@ -237,7 +213,9 @@ impl_into_block_processing_error_with_index!(
     AttestationInvalid,
     DepositInvalid,
     ExitInvalid,
-    BlsExecutionChangeInvalid
+    BlsExecutionChangeInvalid,
+    WithdrawalRequestInvalid,
+    ConsolidationInvalid
 );
 
 pub type HeaderValidationError = BlockOperationError<HeaderInvalid>;
@@ -248,6 +226,8 @@ pub type SyncCommitteeMessageValidationError = BlockOperationError<SyncAggregate
 pub type DepositValidationError = BlockOperationError<DepositInvalid>;
 pub type ExitValidationError = BlockOperationError<ExitInvalid>;
 pub type BlsExecutionChangeValidationError = BlockOperationError<BlsExecutionChangeInvalid>;
+pub type WithdrawalRequestValidationError = BlockOperationError<WithdrawalRequestInvalid>;
+pub type ConsolidationValidationError = BlockOperationError<ConsolidationInvalid>;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum BlockOperationError<T> {
@@ -481,6 +461,65 @@ pub enum BlsExecutionChangeInvalid {
     BadSignature,
 }
 
+/// The reason that an `ExecutionLayerWithdrawalRequest` is ignored.
+///
+/// Requests are triggered on the execution layer, so an invalid request does not invalidate the
+/// block which carries it.
+#[derive(Debug, PartialEq, Clone)]
+pub enum WithdrawalRequestInvalid {
+    /// The partial withdrawal queue is full, so only full exits can be processed.
+    PendingPartialWithdrawalsFull,
+    /// The specified pubkey is not in the state's validator registry.
+    ValidatorUnknown,
+    /// The validator does not have execution withdrawal credentials.
+    NonExecutionWithdrawalCredentials(u64),
+    /// The request was not sent from the validator's withdrawal address.
+    SourceAddressMismatch {
+        withdrawal_address: Option<Address>,
+        source_address: Address,
+    },
+    /// The specified validator is not active.
+    NotActive(u64),
+    /// The specified validator has already initiated exit.
+    AlreadyInitiatedExit(u64),
+    /// The validator has not been active for long enough.
+    TooYoungToExit {
+        current_epoch: Epoch,
+        earliest_exit_epoch: Epoch,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConsolidationInvalid {
+    /// The source and target validators are the same, which would make the consolidation an exit.
+    MatchingSourceAndTarget(u64),
+    /// The specified source validator is not in the state's validator registry.
+    SourceUnknown(u64),
+    /// The specified target validator is not in the state's validator registry.
+    TargetUnknown(u64),
+    /// The source validator is not active.
+    SourceNotActive(u64),
+    /// The target validator is not active.
+    TargetNotActive(u64),
+    /// The source validator has already initiated exit.
+    SourceAlreadyInitiatedExit(u64),
+    /// The target validator has already initiated exit.
+    TargetAlreadyInitiatedExit(u64),
+    /// The consolidation is for a future epoch.
+    FutureEpoch { state: Epoch, consolidation: Epoch },
+    /// The source validator does not have execution withdrawal credentials.
+    SourceNonExecutionWithdrawalCredentials(u64),
+    /// The target validator does not have execution withdrawal credentials.
+    TargetNonExecutionWithdrawalCredentials(u64),
+    /// The source and target validators have different withdrawal addresses.
+    MismatchedWithdrawalAddresses {
+        source_address: Address,
+        target_address: Address,
+    },
+    /// The consolidation was not signed by both validators.
+    BadSignature,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum SyncAggregateInvalid {
     /// One or more of the aggregate public keys is invalid.
@@ -4,7 +4,6 @@ use crate::common::{
     slash_validator,
 };
 use crate::per_block_processing::errors::{BlockProcessingError, IntoWithIndex};
-use crate::signature_sets::consolidation_signature_set;
 use crate::VerifySignatures;
 use types::consts::altair::{PARTICIPATION_FLAG_WEIGHTS, PROPOSER_WEIGHT, WEIGHT_DENOMINATOR};
 use types::typenum::U33;
@@ -40,20 +39,32 @@ pub fn process_operations<E: EthSpec, Payload: AbstractExecPayload<E>>(
     }
 
     if state.fork_name_unchecked().electra_enabled() {
-        let requests = block_body.execution_payload()?.withdrawal_requests()?;
-        if let Some(requests) = requests {
-            process_execution_layer_withdrawal_requests(state, &requests, spec)?;
-        }
-        let receipts = block_body.execution_payload()?.deposit_requests()?;
-        if let Some(receipts) = receipts {
-            process_deposit_requests(state, &receipts, spec)?;
-        }
+        process_execution_requests(state, block_body, spec)?;
         process_consolidations(state, block_body.consolidations()?, verify_signatures, spec)?;
     }
 
     Ok(())
 }
 
+/// Process the requests which are triggered on the execution layer and carried in the payload.
+///
+/// Blinded payloads don't carry requests, so they are skipped.
+pub fn process_execution_requests<E: EthSpec, Payload: AbstractExecPayload<E>>(
+    state: &mut BeaconState<E>,
+    block_body: BeaconBlockBodyRef<E, Payload>,
+    spec: &ChainSpec,
+) -> Result<(), BlockProcessingError> {
+    let payload = block_body.execution_payload()?;
+    if let Some(requests) = payload.withdrawal_requests()? {
+        process_execution_layer_withdrawal_requests(state, &requests, spec)?;
+    }
+    if let Some(requests) = payload.deposit_requests()? {
+        process_deposit_requests(state, &requests, spec)?;
+    }
+
+    Ok(())
+}
+
 pub mod base {
     use super::*;
 
@@ -504,6 +515,9 @@ pub fn apply_deposit<E: EthSpec>(
         };
         state.validators_mut().push(validator)?;
         state.balances_mut().push(state_balance)?;
+        // Add the new pubkey to the pubkey cache now, so it remains in sync with the registry
+        // and later lookups don't need to catch it up.
+        state.update_pubkey_cache()?;
 
         // Altair or later initializations.
         if let Ok(previous_epoch_participation) = state.previous_epoch_participation_mut() {
@@ -528,60 +542,25 @@ pub fn apply_deposit<E: EthSpec>(
     Ok(())
 }
 
+/// Processes each `ExecutionLayerWithdrawalRequest`, ignoring any invalid requests.
 pub fn process_execution_layer_withdrawal_requests<E: EthSpec>(
     state: &mut BeaconState<E>,
     requests: &[ExecutionLayerWithdrawalRequest],
     spec: &ChainSpec,
 ) -> Result<(), BlockProcessingError> {
-    for request in requests {
+    for (i, request) in requests.iter().enumerate() {
         let amount = request.amount;
         let is_full_exit_request = amount == spec.full_exit_request_amount;
 
-        // If partial withdrawal queue is full, only full exits are processed
-        if state.pending_partial_withdrawals()?.len() == E::pending_partial_withdrawals_limit()
-            && !is_full_exit_request
-        {
-            continue;
-        }
-
-        // Verify pubkey exists
-        let index_opt = state.get_validator_index(&request.validator_pubkey)?;
-        let Some(index) = index_opt else {
-            continue;
+        // Requests are triggered on the execution layer, so invalid requests are ignored rather
+        // than invalidating the block.
+        let index = match verify_withdrawal_request(state, request, spec) {
+            Ok(index) => index,
+            Err(BlockOperationError::Invalid(_)) => continue,
+            Err(e) => return Err(e.into_with_index(i)),
         };
-
         let validator = state.get_validator(index)?;
 
-        // Verify withdrawal credentials
-        let has_correct_credential = validator.has_execution_withdrawal_credential(spec);
-        let is_correct_source_address = validator
-            .get_execution_withdrawal_address(spec)
-            .map(|addr| addr == request.source_address)
-            .unwrap_or(false);
-
-        if !(has_correct_credential && is_correct_source_address) {
-            continue;
-        }
-
-        // Verify the validator is active
-        if !validator.is_active_at(state.current_epoch()) {
-            continue;
-        }
-
-        // Verify exit has not been initiated
-        if validator.exit_epoch != spec.far_future_epoch {
-            continue;
-        }
-
-        // Verify the validator has been active long enough
-        if state.current_epoch()
-            < validator
-                .activation_epoch
-                .safe_add(spec.shard_committee_period)?
-        {
-            continue;
-        }
-
         let pending_balance_to_withdraw = state.get_pending_balance_to_withdraw(index)?;
         if is_full_exit_request {
             // Only exit validator if it has no pending withdrawals in the queue
@@ -678,101 +657,12 @@ pub fn process_consolidations<E: EthSpec>(
         }
     }
 
-    for signed_consolidation in consolidations {
-        let consolidation = signed_consolidation.message.clone();
-
-        // Verify that source != target, so a consolidation cannot be used as an exit.
-        block_verify! {
-            consolidation.source_index != consolidation.target_index,
-            BlockProcessingError::MatchingSourceTargetConsolidation  {
-                index: consolidation.source_index
-            }
-        }
+    for (i, signed_consolidation) in consolidations.iter().enumerate() {
+        verify_consolidation(state, signed_consolidation, verify_signatures, spec)
+            .map_err(|e| e.into_with_index(i))?;
 
+        let consolidation = &signed_consolidation.message;
         let source_validator = state.get_validator(consolidation.source_index as usize)?;
-        let target_validator = state.get_validator(consolidation.target_index as usize)?;
-
-        // Verify the source and the target are active
-        let current_epoch = state.current_epoch();
-        block_verify! {
-            source_validator.is_active_at(current_epoch),
-            BlockProcessingError::InactiveConsolidationSource{
-                index: consolidation.source_index,
-                current_epoch
-            }
-        }
-        block_verify! {
-            target_validator.is_active_at(current_epoch),
-            BlockProcessingError::InactiveConsolidationTarget{
-                index: consolidation.target_index,
-                current_epoch
-            }
-        }
-
-        // Verify exits for source and target have not been initiated
-        block_verify! {
-            source_validator.exit_epoch == spec.far_future_epoch,
-            BlockProcessingError::SourceValidatorExiting{
-                index: consolidation.source_index,
-            }
-        }
-        block_verify! {
-            target_validator.exit_epoch == spec.far_future_epoch,
-            BlockProcessingError::TargetValidatorExiting{
-                index: consolidation.target_index,
-            }
-        }
-
-        // Consolidations must specify an epoch when they become valid; they are not valid before then
-        block_verify! {
-            current_epoch >= consolidation.epoch,
-            BlockProcessingError::FutureConsolidationEpoch {
-                current_epoch,
-                consolidation_epoch: consolidation.epoch
-            }
-        }
-
-        // Verify the source and the target have Execution layer withdrawal credentials
-        block_verify! {
-            source_validator.has_execution_withdrawal_credential(spec),
-            BlockProcessingError::NoSourceExecutionWithdrawalCredential {
-                index: consolidation.source_index,
-            }
-        }
-        block_verify! {
-            target_validator.has_execution_withdrawal_credential(spec),
-            BlockProcessingError::NoTargetExecutionWithdrawalCredential {
-                index: consolidation.target_index,
-            }
-        }
-
-        // Verify the same withdrawal address
-        let source_address = source_validator
-            .get_execution_withdrawal_address(spec)
-            .ok_or(BeaconStateError::NonExecutionAddresWithdrawalCredential)?;
-        let target_address = target_validator
-            .get_execution_withdrawal_address(spec)
-            .ok_or(BeaconStateError::NonExecutionAddresWithdrawalCredential)?;
-        block_verify! {
-            source_address == target_address,
-            BlockProcessingError::MismatchedWithdrawalCredentials {
-                source_address,
-                target_address
-            }
-        }
-
-        if verify_signatures.is_true() {
-            let signature_set = consolidation_signature_set(
-                state,
-                |i| get_pubkey_from_state(state, i),
-                signed_consolidation,
-                spec,
-            )?;
-            block_verify! {
-                signature_set.verify(),
-                BlockProcessingError::InavlidConsolidationSignature
-            }
-        }
         let exit_epoch = state.compute_consolidation_epoch_and_update_churn(
             source_validator.effective_balance,
             spec,
@@ -3,17 +3,25 @@
 use crate::per_block_processing::errors::{
     AttestationInvalid, AttesterSlashingInvalid, BlockOperationError, BlockProcessingError,
     DepositInvalid, HeaderInvalid, IndexedAttestationInvalid, IntoWithIndex,
-    ProposerSlashingInvalid,
+    ProposerSlashingInvalid, WithdrawalRequestInvalid,
 };
 use crate::{per_block_processing, BlockReplayError, BlockReplayer};
 use crate::{
-    per_block_processing::{process_operations, verify_exit::verify_exit},
+    per_block_processing::{
+        process_operations,
+        process_operations::process_execution_layer_withdrawal_requests,
+        signature_sets::{consolidation_signature_set, get_pubkey_from_state},
+        verify_consolidation,
+        verify_exit::verify_exit,
+        verify_withdrawal_request,
+    },
     BlockSignatureStrategy, ConsensusContext, VerifyBlockRoot, VerifySignatures,
 };
 use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
 use lazy_static::lazy_static;
+use safe_arith::SafeArith;
 use ssz_types::Bitfield;
-use test_utils::generate_deterministic_keypairs;
+use test_utils::{generate_deterministic_keypairs, RngCore, SeedableRng, XorShiftRng};
 use types::*;
 
 pub const MAX_VALIDATOR_COUNT: usize = 97;
@@ -228,6 +236,27 @@ async fn valid_4_deposits() {
     assert_eq!(result, Ok(()));
 }
 
+#[tokio::test]
+async fn valid_deposits_update_pubkey_cache() {
+    let spec = MainnetEthSpec::default_spec();
+    let harness = get_harness::<MainnetEthSpec>(EPOCH_OFFSET, VALIDATOR_COUNT).await;
+    let mut state = harness.get_current_state();
+
+    let (deposits, state) = harness.make_deposits(&mut state, 4, None, None);
+    state.update_pubkey_cache().unwrap();
+
+    process_operations::process_deposits(state, &deposits, &spec).unwrap();
+
+    // Each new validator is added to the pubkey cache as its deposit is processed.
+    assert_eq!(state.pubkey_cache().len(), VALIDATOR_COUNT + 4);
+    for (i, deposit) in deposits.iter().enumerate() {
+        assert_eq!(
+            state.pubkey_cache().get(&deposit.data.pubkey),
+            Some(VALIDATOR_COUNT + i)
+        );
+    }
+}
+
 #[tokio::test]
 async fn invalid_deposit_deposit_count_too_big() {
     let spec = MainnetEthSpec::default_spec();
@@ -1095,6 +1124,306 @@ async fn fork_spanning_exit() {
     .expect_err("phase0 exit does not verify against bellatrix state");
 }
 
+#[tokio::test]
+async fn invalid_withdrawal_requests_ignored() {
+    let mut spec = ForkName::Electra.make_genesis_spec(MainnetEthSpec::default_spec());
+    spec.shard_committee_period = 0;
+
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(spec.clone())
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .mock_execution_layer()
+        .fresh_ephemeral_store()
+        .build();
+    let mut state = harness.get_current_state();
+
+    // Give the validator execution withdrawal credentials for `source_address`.
+    let source_address = Address::repeat_byte(0x42);
+    let mut withdrawal_credentials = [0; 32];
+    withdrawal_credentials[0] = spec.eth1_address_withdrawal_prefix_byte;
+    withdrawal_credentials[12..].copy_from_slice(source_address.as_bytes());
+    let validator = state.get_validator_mut(0).unwrap();
+    validator.withdrawal_credentials = Hash256::from(withdrawal_credentials);
+    let validator_pubkey = validator.pubkey;
+
+    let unknown_validator = ExecutionLayerWithdrawalRequest {
+        source_address,
+        validator_pubkey: PublicKeyBytes::empty(),
+        amount: spec.full_exit_request_amount,
+    };
+    assert_eq!(
+        verify_withdrawal_request(&mut state, &unknown_validator, &spec),
+        Err(BlockOperationError::Invalid(
+            WithdrawalRequestInvalid::ValidatorUnknown
+        ))
+    );
+
+    let wrong_source_address = ExecutionLayerWithdrawalRequest {
+        source_address: Address::repeat_byte(0x01),
+        validator_pubkey,
+        amount: spec.full_exit_request_amount,
+    };
+    assert_eq!(
+        verify_withdrawal_request(&mut state, &wrong_source_address, &spec),
+        Err(BlockOperationError::Invalid(
+            WithdrawalRequestInvalid::SourceAddressMismatch {
+                withdrawal_address: Some(source_address),
+                source_address: Address::repeat_byte(0x01),
+            }
+        ))
+    );
+
+    let full_exit = ExecutionLayerWithdrawalRequest {
+        source_address,
+        validator_pubkey,
+        amount: spec.full_exit_request_amount,
+    };
+    assert_eq!(
+        verify_withdrawal_request(&mut state, &full_exit, &spec),
+        Ok(0)
+    );
+
+    // The invalid requests are ignored rather than invalidating the block.
+    process_execution_layer_withdrawal_requests(
+        &mut state,
+        &[unknown_validator, wrong_source_address, full_exit.clone()],
+        &spec,
+    )
+    .expect("invalid requests should be ignored");
+    assert_ne!(
+        state.get_validator(0).unwrap().exit_epoch,
+        spec.far_future_epoch
+    );
+
+    // The validator has now initiated its exit, so the request is no longer valid.
+    assert_eq!(
+        verify_withdrawal_request(&mut state, &full_exit, &spec),
+        Err(BlockOperationError::Invalid(
+            WithdrawalRequestInvalid::AlreadyInitiatedExit(0)
+        ))
+    );
+}
+
+/// The checks applied to withdrawal requests before they were moved into
+/// `verify_withdrawal_request`, used as a reference for the new implementation.
+fn reference_withdrawal_request_index<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    request: &ExecutionLayerWithdrawalRequest,
+    spec: &ChainSpec,
+) -> Result<Option<usize>, BeaconStateError> {
+    let is_full_exit_request = request.amount == spec.full_exit_request_amount;
+    if state.pending_partial_withdrawals()?.len() == E::pending_partial_withdrawals_limit()
+        && !is_full_exit_request
+    {
+        return Ok(None);
+    }
+    let Some(index) = state.get_validator_index(&request.validator_pubkey)? else {
+        return Ok(None);
+    };
+    let validator = state.get_validator(index)?;
+    let has_correct_credential = validator.has_execution_withdrawal_credential(spec);
+    let is_correct_source_address = validator
+        .get_execution_withdrawal_address(spec)
+        .map(|addr| addr == request.source_address)
+        .unwrap_or(false);
+    if !(has_correct_credential && is_correct_source_address)
+        || !validator.is_active_at(state.current_epoch())
+        || validator.exit_epoch != spec.far_future_epoch
+        || state.current_epoch()
+            < validator
+                .activation_epoch
+                .safe_add(spec.shard_committee_period)?
+    {
+        return Ok(None);
+    }
+    Ok(Some(index))
+}
+
+/// The checks applied to consolidations before they were moved into `verify_consolidation`, used
+/// as a reference for the new implementation.
+fn reference_consolidation_is_valid<E: EthSpec>(
+    state: &BeaconState<E>,
+    signed_consolidation: &SignedConsolidation,
+    spec: &ChainSpec,
+) -> bool {
+    let consolidation = &signed_consolidation.message;
+    if consolidation.source_index == consolidation.target_index {
+        return false;
+    }
+    let (Ok(source_validator), Ok(target_validator)) = (
+        state.get_validator(consolidation.source_index as usize),
+        state.get_validator(consolidation.target_index as usize),
+    ) else {
+        return false;
+    };
+    let current_epoch = state.current_epoch();
+    if !source_validator.is_active_at(current_epoch)
+        || !target_validator.is_active_at(current_epoch)
+        || source_validator.exit_epoch != spec.far_future_epoch
+        || target_validator.exit_epoch != spec.far_future_epoch
+        || current_epoch < consolidation.epoch
+        || !source_validator.has_execution_withdrawal_credential(spec)
+        || !target_validator.has_execution_withdrawal_credential(spec)
+    {
+        return false;
+    }
+    let (Some(source_address), Some(target_address)) = (
+        source_validator.get_execution_withdrawal_address(spec),
+        target_validator.get_execution_withdrawal_address(spec),
+    ) else {
+        return false;
+    };
+    source_address == target_address
+        && consolidation_signature_set(
+            state,
+            |i| get_pubkey_from_state(state, i),
+            signed_consolidation,
+            spec,
+        )
+        .map_or(false, |signature_set| signature_set.verify())
+}
+
+const DIFFERENTIAL_VALIDATOR_COUNT: usize = 8;
+
+/// Randomise the withdrawal credentials, activation and exit of the first
+/// `DIFFERENTIAL_VALIDATOR_COUNT` validators, to cover each of the verification checks.
+fn randomise_validators<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    rng: &mut XorShiftRng,
+    addresses: &[Address],
+    spec: &ChainSpec,
+) {
+    for index in 0..DIFFERENTIAL_VALIDATOR_COUNT {
+        let mut withdrawal_credentials = [0; 32];
+        withdrawal_credentials[0] = match rng.next_u32() % 3 {
+            0 => spec.bls_withdrawal_prefix_byte,
+            1 => spec.eth1_address_withdrawal_prefix_byte,
+            _ => spec.compounding_withdrawal_prefix_byte,
+        };
+        let address = addresses[rng.next_u32() as usize % addresses.len()];
+        withdrawal_credentials[12..].copy_from_slice(address.as_bytes());
+
+        let validator = state.get_validator_mut(index).unwrap();
+        validator.withdrawal_credentials = Hash256::from(withdrawal_credentials);
+        validator.activation_epoch = match rng.next_u32() % 4 {
+            0 => spec.far_future_epoch,
+            _ => Epoch::new(0),
+        };
+        validator.exit_epoch = match rng.next_u32() % 4 {
+            0 => Epoch::new(0),
+            1 => Epoch::new(1),
+            _ => spec.far_future_epoch,
+        };
+    }
+}
+
+#[tokio::test]
+async fn withdrawal_requests_match_reference() {
+    let addresses = [Address::repeat_byte(0x01), Address::repeat_byte(0x02)];
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+
+    for shard_committee_period in [0, 256] {
+        let mut spec = ForkName::Electra.make_genesis_spec(MainnetEthSpec::default_spec());
+        spec.shard_committee_period = shard_committee_period;
+
+        let harness = BeaconChainHarness::builder(MainnetEthSpec)
+            .spec(spec.clone())
+            .deterministic_keypairs(VALIDATOR_COUNT)
+            .mock_execution_layer()
+            .fresh_ephemeral_store()
+            .build();
+
+        for _ in 0..16 {
+            let mut state = harness.get_current_state();
+            randomise_validators(&mut state, &mut rng, &addresses, &spec);
+
+            for index in 0..=DIFFERENTIAL_VALIDATOR_COUNT {
+                // The last index is used for an unknown validator.
+                let validator_pubkey = state
+                    .validators()
+                    .get(index)
+                    .filter(|_| index < DIFFERENTIAL_VALIDATOR_COUNT)
+                    .map_or_else(PublicKeyBytes::empty, |validator| validator.pubkey);
+                for source_address in addresses {
+                    for amount in [spec.full_exit_request_amount, 1] {
+                        let request = ExecutionLayerWithdrawalRequest {
+                            source_address,
+                            validator_pubkey,
+                            amount,
+                        };
+                        let expected =
+                            reference_withdrawal_request_index(&mut state, &request, &spec);
+                        let actual = match verify_withdrawal_request(&mut state, &request, &spec) {
+                            Ok(index) => Ok(Some(index)),
+                            Err(BlockOperationError::Invalid(_)) => Ok(None),
+                            Err(e) => Err(e),
+                        };
+                        assert_eq!(
+                            actual.is_ok(),
+                            expected.is_ok(),
+                            "{request:?}: {actual:?} != {expected:?}"
+                        );
+                        assert_eq!(
+                            actual.ok(),
+                            expected.ok(),
+                            "{request:?} verified differently"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn consolidations_match_reference() {
+    let spec = ForkName::Electra.make_genesis_spec(MainnetEthSpec::default_spec());
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(spec.clone())
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .mock_execution_layer()
+        .fresh_ephemeral_store()
+        .build();
+
+    // Sign every pair of validators once, including an unknown target validator.
+    let consolidations = (0..DIFFERENTIAL_VALIDATOR_COUNT as u64)
+        .flat_map(|source_index| {
+            (0..DIFFERENTIAL_VALIDATOR_COUNT as u64)
+                .map(move |target_index| (source_index, target_index))
+        })
+        .flat_map(|(source_index, target_index)| {
+            [0, 1].map(|epoch| {
+                harness.make_consolidation(source_index, target_index, Epoch::new(epoch))
+            })
+        })
+        .chain([SignedConsolidation {
+            message: Consolidation {
+                source_index: 0,
+                target_index: VALIDATOR_COUNT as u64,
+                epoch: Epoch::new(0),
+            },
+            signature: Signature::empty(),
+        }])
+        .collect::<Vec<_>>();
+
+    let addresses = [Address::repeat_byte(0x01), Address::repeat_byte(0x02)];
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+    let mut num_valid = 0;
+    for _ in 0..16 {
+        let mut state = harness.get_current_state();
+        randomise_validators(&mut state, &mut rng, &addresses, &spec);
+
+        for consolidation in &consolidations {
+            let expected = reference_consolidation_is_valid(&state, consolidation, &spec);
+            let actual = verify_consolidation(&state, consolidation, VerifySignatures::True, &spec);
+            assert_eq!(actual.is_ok(), expected, "{consolidation:?}: {actual:?}");
+            num_valid += usize::from(expected);
+        }
+    }
+    // Make sure the valid path was exercised.
+    assert!(num_valid > 0);
+}
+
 /// Check that the block replayer does not consume state roots unnecessarily.
 #[tokio::test]
 async fn block_replayer_peeking_state_roots() {
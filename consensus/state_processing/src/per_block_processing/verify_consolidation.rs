@@ -0,0 +1,115 @@
+use super::errors::{BlockOperationError, ConsolidationInvalid as Invalid};
+use crate::per_block_processing::signature_sets::{
+    consolidation_signature_set, get_pubkey_from_state,
+};
+use crate::VerifySignatures;
+use types::*;
+
+type Result<T> = std::result::Result<T, BlockOperationError<Invalid>>;
+
+fn error(reason: Invalid) -> BlockOperationError<Invalid> {
+    BlockOperationError::invalid(reason)
+}
+
+/// Indicates if a `SignedConsolidation` is valid to be included in a block in the current epoch
+/// of the given state.
+///
+/// This does not check the consolidation churn limit or the pending consolidations queue, which
+/// apply to all of the consolidations in a block.
+///
+/// Returns `Ok(())` if the `SignedConsolidation` is valid, otherwise indicates the reason for
+/// invalidity.
+pub fn verify_consolidation<E: EthSpec>(
+    state: &BeaconState<E>,
+    signed_consolidation: &SignedConsolidation,
+    verify_signatures: VerifySignatures,
+    spec: &ChainSpec,
+) -> Result<()> {
+    let consolidation = &signed_consolidation.message;
+    let source_index = consolidation.source_index;
+    let target_index = consolidation.target_index;
+
+    // Verify that source != target, so a consolidation cannot be used as an exit.
+    verify!(
+        source_index != target_index,
+        Invalid::MatchingSourceAndTarget(source_index)
+    );
+
+    let source_validator = state
+        .validators()
+        .get(source_index as usize)
+        .ok_or_else(|| error(Invalid::SourceUnknown(source_index)))?;
+    let target_validator = state
+        .validators()
+        .get(target_index as usize)
+        .ok_or_else(|| error(Invalid::TargetUnknown(target_index)))?;
+
+    // Verify the source and the target are active.
+    let current_epoch = state.current_epoch();
+    verify!(
+        source_validator.is_active_at(current_epoch),
+        Invalid::SourceNotActive(source_index)
+    );
+    verify!(
+        target_validator.is_active_at(current_epoch),
+        Invalid::TargetNotActive(target_index)
+    );
+
+    // Verify exits for source and target have not been initiated.
+    verify!(
+        source_validator.exit_epoch == spec.far_future_epoch,
+        Invalid::SourceAlreadyInitiatedExit(source_index)
+    );
+    verify!(
+        target_validator.exit_epoch == spec.far_future_epoch,
+        Invalid::TargetAlreadyInitiatedExit(target_index)
+    );
+
+    // Consolidations must specify an epoch when they become valid; they are not valid before then.
+    verify!(
+        current_epoch >= consolidation.epoch,
+        Invalid::FutureEpoch {
+            state: current_epoch,
+            consolidation: consolidation.epoch,
+        }
+    );
+
+    // Verify the source and the target have execution layer withdrawal credentials with the same
+    // address.
+    let source_address = source_validator
+        .get_execution_withdrawal_address(spec)
+        .ok_or_else(|| {
+            error(Invalid::SourceNonExecutionWithdrawalCredentials(
+                source_index,
+            ))
+        })?;
+    let target_address = target_validator
+        .get_execution_withdrawal_address(spec)
+        .ok_or_else(|| {
+            error(Invalid::TargetNonExecutionWithdrawalCredentials(
+                target_index,
+            ))
+        })?;
+    verify!(
+        source_address == target_address,
+        Invalid::MismatchedWithdrawalAddresses {
+            source_address,
+            target_address,
+        }
+    );
+
+    if verify_signatures.is_true() {
+        verify!(
+            consolidation_signature_set(
+                state,
+                |i| get_pubkey_from_state(state, i),
+                signed_consolidation,
+                spec
+            )?
+            .verify(),
+            Invalid::BadSignature
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use super::errors::{BlockOperationError, WithdrawalRequestInvalid as Invalid};
+use safe_arith::SafeArith;
+use types::*;
+
+type Result<T> = std::result::Result<T, BlockOperationError<Invalid>>;
+
+fn error(reason: Invalid) -> BlockOperationError<Invalid> {
+    BlockOperationError::invalid(reason)
+}
+
+/// Indicates if an `ExecutionLayerWithdrawalRequest` can be applied to the given `state`.
+///
+/// Returns the index of the validator to withdraw from if the request is valid, otherwise
+/// indicates the reason the request should be ignored.
+///
+/// Builds the pubkey cache if it is not already built.
+pub fn verify_withdrawal_request<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    request: &ExecutionLayerWithdrawalRequest,
+    spec: &ChainSpec,
+) -> Result<usize> {
+    let is_full_exit_request = request.amount == spec.full_exit_request_amount;
+
+    // If the partial withdrawal queue is full, only full exits are processed.
+    verify!(
+        is_full_exit_request
+            || state.pending_partial_withdrawals()?.len() < E::pending_partial_withdrawals_limit(),
+        Invalid::PendingPartialWithdrawalsFull
+    );
+
+    let index = state
+        .get_validator_index(&request.validator_pubkey)?
+        .ok_or_else(|| error(Invalid::ValidatorUnknown))?;
+    let validator = state.get_validator(index)?;
+
+    // Verify withdrawal credentials.
+    verify!(
+        validator.has_execution_withdrawal_credential(spec),
+        Invalid::NonExecutionWithdrawalCredentials(index as u64)
+    );
+    let withdrawal_address = validator.get_execution_withdrawal_address(spec);
+    verify!(
+        withdrawal_address == Some(request.source_address),
+        Invalid::SourceAddressMismatch {
+            withdrawal_address,
+            source_address: request.source_address,
+        }
+    );
+
+    // Verify the validator is active.
+    let current_epoch = state.current_epoch();
+    verify!(
+        validator.is_active_at(current_epoch),
+        Invalid::NotActive(index as u64)
+    );
+
+    // Verify exit has not been initiated.
+    verify!(
+        validator.exit_epoch == spec.far_future_epoch,
+        Invalid::AlreadyInitiatedExit(index as u64)
+    );
+
+    // Verify the validator has been active long enough.
+    let earliest_exit_epoch = validator
+        .activation_epoch
+        .safe_add(spec.shard_committee_period)?;
+    verify!(
+        current_epoch >= earliest_exit_epoch,
+        Invalid::TooYoungToExit {
+            current_epoch,
+            earliest_exit_epoch,
+        }
+    );
+
+    Ok(index)
+}
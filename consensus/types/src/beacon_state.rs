@@ -1949,6 +1949,18 @@ impl<E: EthSpec> BeaconState<E> {
         Ok(())
     }
 
+    /// Replaces the cache for `relative_epoch`, e.g. with an identical cache that is shared with
+    /// another state.
+    pub fn set_committee_cache(
+        &mut self,
+        relative_epoch: RelativeEpoch,
+        committee_cache: Arc<CommitteeCache>,
+    ) -> Result<(), Error> {
+        *self.committee_cache_at_index_mut(Self::committee_cache_index(relative_epoch))? =
+            committee_cache;
+        Ok(())
+    }
+
     /// Updates the pubkey cache, if required.
     ///
     /// Adds all `pubkeys` from the `validators` which are not already in the cache. Will
@@ -2045,6 +2057,19 @@ impl<E: EthSpec> BeaconState<E> {
         Ok(self.validators().tree_hash_root())
     }
 
+    /// Compute the tree hash root of the state, hashing the validators and balances in parallel.
+    ///
+    /// The validators and balances are by far the largest fields of the state. Their roots are
+    /// cached in their trees once computed, so hashing them concurrently up-front reduces the time
+    /// taken to hash the rest of the state.
+    pub fn update_tree_hash_cache_parallel(&mut self) -> Result<Hash256, Error> {
+        self.apply_pending_mutations()?;
+        let validators = self.validators();
+        let balances = self.balances();
+        rayon::join(|| validators.tree_hash_root(), || balances.tree_hash_root());
+        self.update_tree_hash_cache()
+    }
+
     /// Passing `previous_epoch` to this function rather than computing it internally provides
     /// a tangible speed improvement in state processing.
     pub fn is_eligible_validator(
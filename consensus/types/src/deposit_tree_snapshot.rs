@@ -75,10 +75,58 @@ impl DepositTreeSnapshot {
         self.calculate_root()
             .map_or(false, |calculated| self.deposit_root == calculated)
     }
+
+    /// Returns `true` if the snapshot is valid and finalizes exactly the deposits voted into the
+    /// chain by `eth1_data`.
+    ///
+    /// The execution block isn't compared, since a snapshot may be taken at a later block which
+    /// has the same deposits.
+    pub fn matches_eth1_data(&self, eth1_data: &Eth1Data) -> bool {
+        self.deposit_count == eth1_data.deposit_count
+            && self.deposit_root == eth1_data.deposit_root
+            && self.is_valid()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     ssz_tests!(DepositTreeSnapshot);
+
+    #[test]
+    fn matches_eth1_data() {
+        let snapshot = DepositTreeSnapshot {
+            execution_block_hash: Hash256::repeat_byte(1),
+            ..DepositTreeSnapshot::default()
+        };
+        let eth1_data = Eth1Data {
+            deposit_root: snapshot.deposit_root,
+            deposit_count: snapshot.deposit_count,
+            block_hash: snapshot.execution_block_hash,
+        };
+        assert!(snapshot.matches_eth1_data(&eth1_data));
+
+        assert!(!snapshot.matches_eth1_data(&Eth1Data {
+            deposit_count: 1,
+            ..eth1_data.clone()
+        }));
+        assert!(!snapshot.matches_eth1_data(&Eth1Data {
+            deposit_root: Hash256::repeat_byte(2),
+            ..eth1_data.clone()
+        }));
+        assert!(snapshot.matches_eth1_data(&Eth1Data {
+            block_hash: Hash256::repeat_byte(2),
+            ..eth1_data.clone()
+        }));
+
+        // A snapshot whose finalized hashes don't produce its deposit root.
+        let invalid = DepositTreeSnapshot {
+            deposit_root: Hash256::repeat_byte(3),
+            ..snapshot
+        };
+        assert!(!invalid.matches_eth1_data(&Eth1Data {
+            deposit_root: invalid.deposit_root,
+            ..eth1_data
+        }));
+    }
 }
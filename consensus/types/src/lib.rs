@@ -216,7 +216,8 @@ pub use crate::pending_balance_deposit::PendingBalanceDeposit;
 pub use crate::pending_consolidation::PendingConsolidation;
 pub use crate::pending_partial_withdrawal::PendingPartialWithdrawal;
 pub use crate::preset::{
-    AltairPreset, BasePreset, BellatrixPreset, CapellaPreset, DenebPreset, ElectraPreset,
+    apply_preset_dir, AltairPreset, BasePreset, BellatrixPreset, CapellaPreset, DenebPreset,
+    ElectraPreset, PresetFile,
 };
 pub use crate::proposer_preparation_data::ProposerPreparationData;
 pub use crate::proposer_slashing::ProposerSlashing;
@@ -1,5 +1,8 @@
 use crate::{ChainSpec, Epoch, EthSpec, Unsigned};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::fs::File;
+use std::path::Path;
 
 /// Value-level representation of an Ethereum consensus "preset".
 ///
@@ -214,6 +217,8 @@ pub struct DenebPreset {
     pub max_blob_commitments_per_block: u64,
     #[serde(with = "serde_utils::quoted_u64")]
     pub field_elements_per_blob: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub kzg_commitment_inclusion_proof_depth: u64,
 }
 
 impl DenebPreset {
@@ -222,6 +227,7 @@ impl DenebPreset {
             max_blobs_per_block: E::max_blobs_per_block() as u64,
             max_blob_commitments_per_block: E::max_blob_commitments_per_block() as u64,
             field_elements_per_blob: E::field_elements_per_blob() as u64,
+            kzg_commitment_inclusion_proof_depth: E::kzg_proof_inclusion_proof_depth() as u64,
         }
     }
 }
@@ -300,6 +306,231 @@ impl Eip7594Preset {
     }
 }
 
+/// A preset which can be loaded from one of the standard preset YAML files (e.g. `phase0.yaml`).
+///
+/// Some preset values are stored on the `ChainSpec` and may be changed at runtime, whilst the rest
+/// are compile-time constants of an `EthSpec` and must match it exactly.
+pub trait PresetFile: Serialize + DeserializeOwned + PartialEq {
+    /// The name of the file within a preset directory, as used by the consensus specs.
+    const FILE_NAME: &'static str;
+
+    /// Returns the preset values implied by `E` and `spec`.
+    fn from_eth_and_chain_spec<E: EthSpec>(spec: &ChainSpec) -> Self;
+
+    /// Copies the values which are stored on the `ChainSpec` into `spec`.
+    fn apply_to_chain_spec(&self, spec: &mut ChainSpec);
+}
+
+impl PresetFile for BasePreset {
+    const FILE_NAME: &'static str = "phase0.yaml";
+
+    fn from_eth_and_chain_spec<E: EthSpec>(spec: &ChainSpec) -> Self {
+        Self::from_chain_spec::<E>(spec)
+    }
+
+    fn apply_to_chain_spec(&self, spec: &mut ChainSpec) {
+        spec.max_committees_per_slot = self.max_committees_per_slot as usize;
+        spec.target_committee_size = self.target_committee_size as usize;
+        spec.shuffle_round_count = self.shuffle_round_count;
+        spec.hysteresis_quotient = self.hysteresis_quotient;
+        spec.hysteresis_downward_multiplier = self.hysteresis_downward_multiplier;
+        spec.hysteresis_upward_multiplier = self.hysteresis_upward_multiplier;
+        spec.safe_slots_to_update_justified = self.safe_slots_to_update_justified;
+        spec.min_deposit_amount = self.min_deposit_amount;
+        spec.max_effective_balance = self.max_effective_balance;
+        spec.effective_balance_increment = self.effective_balance_increment;
+        spec.min_attestation_inclusion_delay = self.min_attestation_inclusion_delay;
+        spec.min_seed_lookahead = self.min_seed_lookahead;
+        spec.max_seed_lookahead = self.max_seed_lookahead;
+        spec.min_epochs_to_inactivity_penalty = self.min_epochs_to_inactivity_penalty;
+        spec.base_reward_factor = self.base_reward_factor;
+        spec.whistleblower_reward_quotient = self.whistleblower_reward_quotient;
+        spec.proposer_reward_quotient = self.proposer_reward_quotient;
+        spec.inactivity_penalty_quotient = self.inactivity_penalty_quotient;
+        spec.min_slashing_penalty_quotient = self.min_slashing_penalty_quotient;
+        spec.proportional_slashing_multiplier = self.proportional_slashing_multiplier;
+    }
+}
+
+impl PresetFile for AltairPreset {
+    const FILE_NAME: &'static str = "altair.yaml";
+
+    fn from_eth_and_chain_spec<E: EthSpec>(spec: &ChainSpec) -> Self {
+        Self::from_chain_spec::<E>(spec)
+    }
+
+    fn apply_to_chain_spec(&self, spec: &mut ChainSpec) {
+        spec.inactivity_penalty_quotient_altair = self.inactivity_penalty_quotient_altair;
+        spec.min_slashing_penalty_quotient_altair = self.min_slashing_penalty_quotient_altair;
+        spec.proportional_slashing_multiplier_altair = self.proportional_slashing_multiplier_altair;
+        spec.epochs_per_sync_committee_period = self.epochs_per_sync_committee_period;
+        spec.min_sync_committee_participants = self.min_sync_committee_participants;
+    }
+}
+
+impl PresetFile for BellatrixPreset {
+    const FILE_NAME: &'static str = "bellatrix.yaml";
+
+    fn from_eth_and_chain_spec<E: EthSpec>(spec: &ChainSpec) -> Self {
+        Self::from_chain_spec::<E>(spec)
+    }
+
+    fn apply_to_chain_spec(&self, spec: &mut ChainSpec) {
+        spec.inactivity_penalty_quotient_bellatrix = self.inactivity_penalty_quotient_bellatrix;
+        spec.min_slashing_penalty_quotient_bellatrix = self.min_slashing_penalty_quotient_bellatrix;
+        spec.proportional_slashing_multiplier_bellatrix =
+            self.proportional_slashing_multiplier_bellatrix;
+    }
+}
+
+impl PresetFile for CapellaPreset {
+    const FILE_NAME: &'static str = "capella.yaml";
+
+    fn from_eth_and_chain_spec<E: EthSpec>(spec: &ChainSpec) -> Self {
+        Self::from_chain_spec::<E>(spec)
+    }
+
+    fn apply_to_chain_spec(&self, spec: &mut ChainSpec) {
+        spec.max_validators_per_withdrawals_sweep = self.max_validators_per_withdrawals_sweep;
+    }
+}
+
+impl PresetFile for DenebPreset {
+    const FILE_NAME: &'static str = "deneb.yaml";
+
+    fn from_eth_and_chain_spec<E: EthSpec>(spec: &ChainSpec) -> Self {
+        Self::from_chain_spec::<E>(spec)
+    }
+
+    fn apply_to_chain_spec(&self, _spec: &mut ChainSpec) {}
+}
+
+impl PresetFile for ElectraPreset {
+    const FILE_NAME: &'static str = "electra.yaml";
+
+    fn from_eth_and_chain_spec<E: EthSpec>(spec: &ChainSpec) -> Self {
+        Self::from_chain_spec::<E>(spec)
+    }
+
+    fn apply_to_chain_spec(&self, spec: &mut ChainSpec) {
+        spec.min_activation_balance = self.min_activation_balance;
+        spec.max_effective_balance_electra = self.max_effective_balance_electra;
+        spec.min_slashing_penalty_quotient_electra = self.min_slashing_penalty_quotient_electra;
+        spec.whistleblower_reward_quotient_electra = self.whistleblower_reward_quotient_electra;
+        spec.max_pending_partials_per_withdrawals_sweep =
+            self.max_pending_partials_per_withdrawals_sweep;
+    }
+}
+
+impl PresetFile for Eip7594Preset {
+    const FILE_NAME: &'static str = "eip7594.yaml";
+
+    fn from_eth_and_chain_spec<E: EthSpec>(spec: &ChainSpec) -> Self {
+        Self::from_chain_spec::<E>(spec)
+    }
+
+    fn apply_to_chain_spec(&self, _spec: &mut ChainSpec) {}
+}
+
+/// Applies the preset files in `dir` to `spec`, returning an error if any of them are invalid.
+///
+/// Missing files are skipped. Files must not contain unknown fields, and values which are
+/// compile-time constants of `E` must match `E` exactly.
+pub fn apply_preset_dir<E: EthSpec>(dir: &Path, spec: &mut ChainSpec) -> Result<(), String> {
+    apply_preset_file::<E, BasePreset>(dir, spec)?;
+    apply_preset_file::<E, AltairPreset>(dir, spec)?;
+    apply_preset_file::<E, BellatrixPreset>(dir, spec)?;
+    apply_preset_file::<E, CapellaPreset>(dir, spec)?;
+    apply_preset_file::<E, DenebPreset>(dir, spec)?;
+    apply_preset_file::<E, ElectraPreset>(dir, spec)?;
+    apply_preset_file::<E, Eip7594Preset>(dir, spec)
+}
+
+fn apply_preset_file<E: EthSpec, P: PresetFile>(
+    dir: &Path,
+    spec: &mut ChainSpec,
+) -> Result<(), String> {
+    let path = dir.join(P::FILE_NAME);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let file = File::open(&path)
+        .map_err(|e| format!("Unable to open preset {}: {:?}", path.display(), e))?;
+    let value: Value = serde_yaml::from_reader(file)
+        .map_err(|e| format!("Unable to parse preset {}: {:?}", path.display(), e))?;
+
+    let expected = preset_to_mapping(&P::from_eth_and_chain_spec::<E>(spec))?;
+    let found = value
+        .as_mapping()
+        .ok_or_else(|| format!("Preset {} is not a YAML mapping", path.display()))?;
+    let unknown_fields = found
+        .keys()
+        .filter(|key| !expected.contains_key(key))
+        .map(yaml_to_string)
+        .collect::<Vec<_>>();
+    if !unknown_fields.is_empty() {
+        return Err(format!(
+            "Unknown fields in preset {}: {}",
+            path.display(),
+            unknown_fields.join(", ")
+        ));
+    }
+
+    let preset: P = serde_yaml::from_value(value)
+        .map_err(|e| format!("Invalid preset {}: {:?}", path.display(), e))?;
+    preset.apply_to_chain_spec(spec);
+
+    // Anything that still differs after applying the preset is a compile-time constant.
+    let effective = P::from_eth_and_chain_spec::<E>(spec);
+    if effective != preset {
+        let file_values = preset_to_mapping(&preset)?;
+        let compiled_values = preset_to_mapping(&effective)?;
+        let mismatches = file_values
+            .iter()
+            .filter(|(key, value)| compiled_values.get(key) != Some(value))
+            .map(|(key, value)| {
+                format!(
+                    "{} (file: {}, {}: {})",
+                    yaml_to_string(key),
+                    yaml_to_string(value),
+                    E::spec_name(),
+                    compiled_values
+                        .get(key)
+                        .map(yaml_to_string)
+                        .unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>();
+        return Err(format!(
+            "Preset {} is incompatible with the compiled {} preset, these values can't be \
+             changed at runtime: {}",
+            path.display(),
+            E::spec_name(),
+            mismatches.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+fn preset_to_mapping<P: Serialize>(preset: &P) -> Result<Mapping, String> {
+    match serde_yaml::to_value(preset) {
+        Ok(Value::Mapping(mapping)) => Ok(mapping),
+        Ok(_) => Err("Preset did not serialize to a YAML mapping".to_string()),
+        Err(e) => Err(format!("Unable to serialize preset: {:?}", e)),
+    }
+}
+
+fn yaml_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default(),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -363,4 +594,60 @@ mod test {
     fn minimal_presets_consistent() {
         preset_test::<MinimalEthSpec>();
     }
+
+    fn apply_preset_dir_test<E: EthSpec>() {
+        let preset_dir = presets_base_path().join(E::spec_name().to_string());
+        let mut spec = E::default_spec();
+        apply_preset_dir::<E>(&preset_dir, &mut spec).unwrap();
+        assert_eq!(spec, E::default_spec());
+    }
+
+    #[test]
+    fn apply_standard_preset_dirs() {
+        apply_preset_dir_test::<MainnetEthSpec>();
+        apply_preset_dir_test::<GnosisEthSpec>();
+        apply_preset_dir_test::<MinimalEthSpec>();
+    }
+
+    /// Write the mainnet `phase0.yaml` to a temporary directory, replacing `from` with `to`.
+    fn modified_phase0(from: &str, to: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let phase0 =
+            std::fs::read_to_string(presets_base_path().join("mainnet").join("phase0.yaml"))
+                .unwrap();
+        assert!(phase0.contains(from));
+        std::fs::write(
+            dir.path().join(BasePreset::FILE_NAME),
+            phase0.replace(from, to),
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn apply_preset_dir_overrides_runtime_values() {
+        let dir = modified_phase0("SHUFFLE_ROUND_COUNT: 90", "SHUFFLE_ROUND_COUNT: 10");
+        let mut spec = MainnetEthSpec::default_spec();
+        apply_preset_dir::<MainnetEthSpec>(dir.path(), &mut spec).unwrap();
+        assert_eq!(spec.shuffle_round_count, 10);
+    }
+
+    #[test]
+    fn apply_preset_dir_rejects_compile_time_changes() {
+        let dir = modified_phase0("SLOTS_PER_EPOCH: 32", "SLOTS_PER_EPOCH: 8");
+        let mut spec = MainnetEthSpec::default_spec();
+        let err = apply_preset_dir::<MainnetEthSpec>(dir.path(), &mut spec).unwrap_err();
+        assert!(err.contains("SLOTS_PER_EPOCH"), "{}", err);
+    }
+
+    #[test]
+    fn apply_preset_dir_rejects_unknown_fields() {
+        let dir = modified_phase0(
+            "SLOTS_PER_EPOCH: 32",
+            "SLOTS_PER_EPOCH: 32\nNOT_A_PRESET_VALUE: 1",
+        );
+        let mut spec = MainnetEthSpec::default_spec();
+        let err = apply_preset_dir::<MainnetEthSpec>(dir.path(), &mut spec).unwrap_err();
+        assert!(err.contains("NOT_A_PRESET_VALUE"), "{}", err);
+    }
 }
@@ -105,6 +105,29 @@ impl<E: EthSpec, Payload: AbstractExecPayload<E>> SignedBeaconBlock<E, Payload>
         Self::from_ssz_bytes_with(bytes, |bytes| BeaconBlock::from_ssz_bytes(bytes, spec))
     }
 
+    /// Read the slot of an SSZ encoded block without decoding the rest of the block.
+    pub fn slot_from_ssz_bytes(bytes: &[u8]) -> Result<Slot, ssz::DecodeError> {
+        let mut builder = ssz::SszDecoderBuilder::new(bytes);
+
+        builder.register_anonymous_variable_length_item()?;
+        builder.register_type::<Signature>()?;
+
+        let mut decoder = builder.build()?;
+
+        // The slot is the first field of every `BeaconBlock` variant.
+        decoder.decode_next_with(|message| {
+            let slot_len = <Slot as ssz::Decode>::ssz_fixed_len();
+            let slot_bytes =
+                message
+                    .get(0..slot_len)
+                    .ok_or(ssz::DecodeError::InvalidByteLength {
+                        len: message.len(),
+                        expected: slot_len,
+                    })?;
+            <Slot as ssz::Decode>::from_ssz_bytes(slot_bytes)
+        })
+    }
+
     /// SSZ decode with explicit fork variant.
     pub fn from_ssz_bytes_for_fork(
         bytes: &[u8],
@@ -730,6 +753,7 @@ pub mod ssz_tagged_signed_beacon_block_arc {
 #[cfg(test)]
 mod test {
     use super::*;
+    use ssz::Encode;
 
     #[test]
     fn add_remove_payload_roundtrip() {
@@ -772,6 +796,26 @@ mod test {
         }
     }
 
+    #[test]
+    fn slot_from_ssz_bytes() {
+        type E = MainnetEthSpec;
+
+        let spec = &E::default_spec();
+        let mut block = BeaconBlockDeneb::empty(spec);
+        block.slot = Slot::new(1_234_567);
+        let block =
+            SignedBeaconBlock::<E>::from_block(BeaconBlock::Deneb(block), Signature::empty());
+        let (blinded_block, _): (SignedBlindedBeaconBlock<E>, _) = block.clone().into();
+
+        for bytes in [block.as_ssz_bytes(), blinded_block.as_ssz_bytes()] {
+            assert_eq!(
+                SignedBeaconBlock::<E>::slot_from_ssz_bytes(&bytes).unwrap(),
+                block.slot()
+            );
+        }
+        assert!(SignedBeaconBlock::<E>::slot_from_ssz_bytes(&[0; 4]).is_err());
+    }
+
     #[test]
     fn test_ssz_tagged_signed_beacon_block() {
         type E = MainnetEthSpec;
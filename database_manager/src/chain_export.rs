@@ -0,0 +1,529 @@
+//! Export of finalized blocks for external indexers, and import of those blocks into a database
+//! whose history is incomplete, e.g. after checkpoint sync.
+//!
+//! Blocks are exported without their execution payloads, as they are stored in the freezer
+//! database. Two formats are supported:
+//!
+//! - `jsonl`: one JSON object per line, holding the fork `version`, the `block_root`, the block as
+//!   `data` and optionally the `blobs` stored for the block, without their data.
+//! - `ssz`: the SSZ bytes of each block, prefixed by their length as a little-endian `u32`.
+//!
+//! An import must link to the database's existing history by block root, so blocks are imported
+//! from newest to oldest, and the newest block imported must be the parent of the oldest block in
+//! the database. Because each block root commits to the parent root, the imported blocks are
+//! authenticated by the block they link to and no signatures need to be verified.
+use beacon_chain::historical_blocks::{persisted_genesis_block_root, HistoricalBlockBatch};
+use beacon_chain::BeaconChainError;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use slog::{info, Logger};
+use ssz::Encode;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use store::{Error, HotColdDB, LevelDB};
+use types::{
+    EthSpec, ForkVersionedResponse, Hash256, KzgCommitment, KzgProof, SignedBlindedBeaconBlock,
+    Slot,
+};
+
+/// The number of blocks written to the database at once by an import.
+const IMPORT_BATCH_SIZE: usize = 1024;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize, ValueEnum)]
+pub enum ChainFormat {
+    #[clap(name = "jsonl")]
+    Jsonl,
+    #[clap(name = "ssz")]
+    Ssz,
+}
+
+/// The fields of an exported line in addition to the `version` and `data`.
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedBlockMetadata {
+    block_root: Hash256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    blobs: Option<Vec<BlobMetadata>>,
+}
+
+/// A blob sidecar without its blob or inclusion proof.
+#[derive(Debug, Serialize, Deserialize)]
+struct BlobMetadata {
+    #[serde(with = "serde_utils::quoted_u64")]
+    index: u64,
+    kzg_commitment: KzgCommitment,
+    kzg_proof: KzgProof,
+}
+
+type ExportedBlock<E> = ForkVersionedResponse<SignedBlindedBeaconBlock<E>, ExportedBlockMetadata>;
+
+pub struct ExportChainConfig {
+    pub start_slot: Slot,
+    pub end_slot: Option<Slot>,
+    pub format: ChainFormat,
+    pub blobs: bool,
+    pub output: Option<PathBuf>,
+}
+
+pub struct ImportChainConfig {
+    pub format: ChainFormat,
+    pub input: PathBuf,
+}
+
+/// Write the canonical blocks from `start_slot` to `end_slot` inclusive, which must be finalized,
+/// to `output` or stdout.
+pub fn export_chain<E: EthSpec>(
+    db: &HotColdDB<E, LevelDB<E>, LevelDB<E>>,
+    config: ExportChainConfig,
+    log: &Logger,
+) -> Result<(), String> {
+    if config.blobs && config.format != ChainFormat::Jsonl {
+        return Err("Blob metadata can only be exported as jsonl".into());
+    }
+
+    let split_slot = db.get_split_slot();
+    if split_slot == 0 {
+        return Err("No blocks have been finalized".into());
+    }
+    let end_slot = config.end_slot.unwrap_or(split_slot - 1);
+    if end_slot >= split_slot {
+        return Err(format!(
+            "Only finalized blocks can be exported, the end slot must be less than {split_slot}"
+        ));
+    }
+    let oldest_block_slot = db.get_oldest_block_slot();
+    if config.start_slot < oldest_block_slot || config.start_slot > end_slot {
+        return Err(format!(
+            "The start slot must be between the oldest block at slot {oldest_block_slot} and the \
+             end slot {end_slot}"
+        ));
+    }
+
+    let mut output: Box<dyn Write> = match &config.output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).map_err(|e| {
+                format!("Unable to create {}: {e}", path.display())
+            })?))
+        }
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let block_roots = db
+        .forwards_block_roots_iterator_until(
+            config.start_slot,
+            end_slot,
+            || Err(Error::HistoryUnavailable),
+            db.get_chain_spec(),
+        )
+        .map_err(|e| format!("Unable to iterate block roots: {e:?}"))?;
+
+    let mut prev_block_root = None;
+    let mut num_blocks = 0;
+    for res in block_roots {
+        let (block_root, _) = res.map_err(|e| format!("Unable to read block root: {e:?}"))?;
+        // Skipped slots repeat the root of the previous block.
+        if prev_block_root == Some(block_root) {
+            continue;
+        }
+        prev_block_root = Some(block_root);
+
+        let block = db
+            .get_blinded_block(&block_root)
+            .map_err(|e| format!("Unable to read block {block_root:?}: {e:?}"))?
+            .ok_or_else(|| format!("Missing block {block_root:?}"))?;
+        // The start slot may have been skipped.
+        if block.slot() < config.start_slot {
+            continue;
+        }
+
+        match config.format {
+            ChainFormat::Jsonl => {
+                let blobs = if config.blobs {
+                    let blobs = db
+                        .get_blobs(&block_root)
+                        .map_err(|e| format!("Unable to read blobs {block_root:?}: {e:?}"))?
+                        .unwrap_or_default();
+                    Some(
+                        blobs
+                            .iter()
+                            .map(|blob| BlobMetadata {
+                                index: blob.index,
+                                kzg_commitment: blob.kzg_commitment,
+                                kzg_proof: blob.kzg_proof,
+                            })
+                            .collect(),
+                    )
+                } else {
+                    None
+                };
+                let line = ExportedBlock {
+                    version: Some(block.fork_name_unchecked()),
+                    metadata: ExportedBlockMetadata { block_root, blobs },
+                    data: block,
+                };
+                serde_json::to_writer(&mut output, &line)
+                    .map_err(|e| format!("Unable to encode block {block_root:?}: {e}"))?;
+                output
+                    .write_all(b"\n")
+                    .map_err(|e| format!("Unable to write output: {e}"))?;
+            }
+            ChainFormat::Ssz => {
+                let bytes = block.as_ssz_bytes();
+                output
+                    .write_all(&(bytes.len() as u32).to_le_bytes())
+                    .and_then(|()| output.write_all(&bytes))
+                    .map_err(|e| format!("Unable to write output: {e}"))?;
+            }
+        }
+        num_blocks += 1;
+    }
+    output
+        .flush()
+        .map_err(|e| format!("Unable to write output: {e}"))?;
+
+    info!(
+        log,
+        "Exported blocks";
+        "count" => num_blocks,
+        "start_slot" => config.start_slot,
+        "end_slot" => end_slot,
+    );
+    Ok(())
+}
+
+/// Read blocks exported by `export_chain` from `input`, and import those prior to the oldest block
+/// in the database.
+///
+/// The input is indexed first, and then read in batches from the newest block to the oldest, so
+/// that only one batch of blocks is held in memory at once.
+pub fn import_chain<E: EthSpec>(
+    db: &HotColdDB<E, LevelDB<E>, LevelDB<E>>,
+    config: ImportChainConfig,
+    log: &Logger,
+) -> Result<(), String> {
+    import_chain_in_batches(db, config, IMPORT_BATCH_SIZE, log)
+}
+
+fn import_chain_in_batches<E: EthSpec>(
+    db: &HotColdDB<E, LevelDB<E>, LevelDB<E>>,
+    config: ImportChainConfig,
+    batch_size: usize,
+    log: &Logger,
+) -> Result<(), String> {
+    let format_err = |e: BeaconChainError| format!("Unable to import blocks: {e:?}");
+
+    let mut anchor = db
+        .get_anchor_info()
+        .ok_or("The database has no anchor, its block history is complete")?;
+    let genesis_block_root = persisted_genesis_block_root(db)
+        .map_err(format_err)?
+        .ok_or("The database has no persisted beacon chain")?;
+
+    let mut input = BufReader::new(
+        File::open(&config.input)
+            .map_err(|e| format!("Unable to open {}: {e}", config.input.display()))?,
+    );
+    let index = match config.format {
+        ChainFormat::Jsonl => index_jsonl_blocks(&mut input)?,
+        ChainFormat::Ssz => index_ssz_blocks(&mut input)?,
+    };
+
+    let mut num_blocks = 0;
+    let mut remaining = index.as_slice();
+    while !remaining.is_empty() && !anchor.oldest_block_parent.is_zero() {
+        let (rest, entries) = remaining.split_at(remaining.len().saturating_sub(batch_size));
+        remaining = rest;
+
+        let mut blocks = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let (block_root, block) = read_block(&mut input, entry, config.format, db)?;
+            if let Some((_, prev_block)) = blocks.last() {
+                if block.slot() <= prev_block.slot() {
+                    return Err(format!(
+                        "Block {block_root:?} at slot {} is out of order, blocks must be sorted \
+                         by slot",
+                        block.slot()
+                    ));
+                }
+            }
+            blocks.push((block_root, block));
+        }
+        blocks.retain(|(_, block)| block.slot() < anchor.oldest_block_slot);
+        if blocks.is_empty() {
+            continue;
+        }
+
+        let batch = HistoricalBlockBatch::new(
+            db,
+            &anchor,
+            genesis_block_root,
+            blocks
+                .into_iter()
+                .map(|(block_root, block)| (block_root, block, None))
+                .collect(),
+        )
+        .map_err(format_err)?;
+        num_blocks += batch.blocks.len();
+        anchor = batch
+            .write(db, anchor, db.get_blob_info())
+            .map_err(format_err)?;
+    }
+
+    info!(
+        log,
+        "Imported blocks";
+        "count" => num_blocks,
+        "oldest_block_slot" => anchor.oldest_block_slot,
+    );
+    Ok(())
+}
+
+/// The position of an exported block in the input.
+struct IndexEntry {
+    offset: u64,
+    len: usize,
+}
+
+fn index_jsonl_blocks(input: &mut impl BufRead) -> Result<Vec<IndexEntry>, String> {
+    let mut index = vec![];
+    let mut offset = 0;
+    let mut line = vec![];
+    loop {
+        line.clear();
+        let len = input
+            .read_until(b'\n', &mut line)
+            .map_err(|e| format!("Unable to read input: {e}"))?;
+        if len == 0 {
+            break;
+        }
+        if !line.iter().all(u8::is_ascii_whitespace) {
+            index.push(IndexEntry { offset, len });
+        }
+        offset += len as u64;
+    }
+    Ok(index)
+}
+
+fn index_ssz_blocks(input: &mut (impl Read + Seek)) -> Result<Vec<IndexEntry>, String> {
+    let mut index = vec![];
+    let mut offset = 0;
+    let mut len_bytes = [0; 4];
+    loop {
+        match input.read_exact(&mut len_bytes) {
+            Ok(()) => (),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Unable to read input: {e}")),
+        }
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        offset += len_bytes.len() as u64;
+        index.push(IndexEntry { offset, len });
+        offset += len as u64;
+        input
+            .seek(SeekFrom::Start(offset))
+            .map_err(|e| format!("Unable to read input: {e}"))?;
+    }
+    Ok(index)
+}
+
+/// Read and decode the block at `entry` in `input`, checking its root for the `jsonl` format.
+fn read_block<E: EthSpec>(
+    input: &mut (impl Read + Seek),
+    entry: &IndexEntry,
+    format: ChainFormat,
+    db: &HotColdDB<E, LevelDB<E>, LevelDB<E>>,
+) -> Result<(Hash256, SignedBlindedBeaconBlock<E>), String> {
+    let mut bytes = vec![0; entry.len];
+    input
+        .seek(SeekFrom::Start(entry.offset))
+        .and_then(|_| input.read_exact(&mut bytes))
+        .map_err(|e| format!("Unable to read block at offset {}: {e}", entry.offset))?;
+
+    match format {
+        ChainFormat::Jsonl => {
+            let exported: ExportedBlock<E> = serde_json::from_slice(&bytes)
+                .map_err(|e| format!("Unable to decode block at offset {}: {e}", entry.offset))?;
+            let block_root = exported.data.canonical_root();
+            if block_root != exported.metadata.block_root {
+                return Err(format!(
+                    "Block root at offset {} is {block_root:?}, expected {:?}",
+                    entry.offset, exported.metadata.block_root
+                ));
+            }
+            Ok((block_root, exported.data))
+        }
+        ChainFormat::Ssz => {
+            let block = SignedBlindedBeaconBlock::from_ssz_bytes(&bytes, db.get_chain_spec())
+                .map_err(|e| format!("Unable to decode block at offset {}: {e:?}", entry.offset))?;
+            Ok((block.canonical_root(), block))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::{
+        test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, DiskHarnessType,
+    };
+    use beacon_chain::WhenSlotSkipped;
+    use logging::test_logger;
+    use store::{AnchorInfo, StoreConfig, StoreOp};
+    use tempfile::{tempdir, TempDir};
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    const VALIDATOR_COUNT: usize = 24;
+
+    async fn finalized_harness(dir: &TempDir) -> BeaconChainHarness<DiskHarnessType<E>> {
+        let store = HotColdDB::open(
+            &dir.path().join("hot_db"),
+            &dir.path().join("cold_db"),
+            &dir.path().join("blobs_db"),
+            |_, _, _| Ok(()),
+            StoreConfig::default(),
+            test_spec::<E>(),
+            test_logger(),
+        )
+        .unwrap();
+        let harness = BeaconChainHarness::builder(MinimalEthSpec)
+            .spec(store.get_chain_spec().clone())
+            .deterministic_keypairs(VALIDATOR_COUNT)
+            .logger(store.logger().clone())
+            .fresh_disk_store(store)
+            .mock_execution_layer()
+            .build();
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                E::slots_per_epoch() as usize * 5,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        harness.chain.persist_head_and_fork_choice().unwrap();
+        harness
+    }
+
+    /// Delete the blocks between genesis and `slot`, and move the anchor forward to `slot`, as
+    /// after checkpoint sync.
+    fn forget_history(harness: &BeaconChainHarness<DiskHarnessType<E>>, slot: Slot) {
+        let db = &harness.chain.store;
+        let oldest_block = harness
+            .chain
+            .block_at_slot(slot, WhenSlotSkipped::Prev)
+            .unwrap()
+            .unwrap();
+        let ops = harness
+            .chain
+            .chain_dump()
+            .unwrap()
+            .into_iter()
+            .filter(|snapshot| {
+                snapshot.beacon_block.slot() > 0
+                    && snapshot.beacon_block.slot() < oldest_block.slot()
+            })
+            .map(|snapshot| StoreOp::DeleteBlock(snapshot.beacon_block_root))
+            .collect();
+        db.do_atomically_with_block_and_blobs_cache(ops).unwrap();
+        db.compare_and_set_anchor_info_with_write(
+            None,
+            Some(AnchorInfo {
+                anchor_slot: db.get_split_slot(),
+                oldest_block_slot: oldest_block.slot(),
+                oldest_block_parent: oldest_block.parent_root(),
+                state_upper_limit: Slot::new(0),
+                state_lower_limit: Slot::new(0),
+            }),
+        )
+        .unwrap();
+    }
+
+    async fn export_import_round_trip(format: ChainFormat) {
+        let dir = tempdir().unwrap();
+        let harness = finalized_harness(&dir).await;
+        let db = harness.chain.store.clone();
+        let log = test_logger();
+
+        let split_slot = db.get_split_slot();
+        let finalized_blocks = harness
+            .chain
+            .chain_dump()
+            .unwrap()
+            .into_iter()
+            .filter(|snapshot| snapshot.beacon_block.slot() < split_slot)
+            .map(|snapshot| {
+                let block_root = snapshot.beacon_block_root;
+                (
+                    block_root,
+                    db.get_blinded_block(&block_root).unwrap().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let path = dir.path().join("blocks");
+        export_chain(
+            &db,
+            ExportChainConfig {
+                start_slot: Slot::new(0),
+                end_slot: None,
+                format,
+                blobs: false,
+                output: Some(path.clone()),
+            },
+            &log,
+        )
+        .unwrap();
+
+        forget_history(&harness, split_slot - 1);
+        assert!(db.get_oldest_block_slot() > E::slots_per_epoch());
+
+        // Import in batches smaller than the chain, so that it is linked across batches.
+        import_chain_in_batches(
+            &db,
+            ImportChainConfig {
+                format,
+                input: path,
+            },
+            7,
+            &log,
+        )
+        .unwrap();
+
+        let anchor = db.get_anchor_info().unwrap();
+        assert_eq!(anchor.oldest_block_slot, 0);
+        assert!(anchor.oldest_block_parent.is_zero());
+        for (block_root, block) in finalized_blocks {
+            assert_eq!(db.get_blinded_block(&block_root).unwrap(), Some(block));
+        }
+        let block_roots = db
+            .forwards_block_roots_iterator_until(
+                Slot::new(0),
+                split_slot - 1,
+                || Err(Error::HistoryUnavailable),
+                db.get_chain_spec(),
+            )
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        for (block_root, slot) in block_roots {
+            assert_eq!(
+                harness
+                    .chain
+                    .block_root_at_slot(slot, WhenSlotSkipped::Prev)
+                    .unwrap(),
+                Some(block_root)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn jsonl_round_trip() {
+        export_import_round_trip(ChainFormat::Jsonl).await;
+    }
+
+    #[tokio::test]
+    async fn ssz_round_trip() {
+        export_import_round_trip(ChainFormat::Ssz).await;
+    }
+}
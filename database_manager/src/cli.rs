@@ -4,6 +4,7 @@ use clap_utils::FLAG_HEADER;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::chain_export::ChainFormat;
 use crate::InspectTarget;
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
@@ -79,6 +80,8 @@ pub enum DatabaseManagerSubcommand {
     PruneBlobs(PruneBlobs),
     PruneStates(PruneStates),
     Compact(Compact),
+    ExportChain(ExportChain),
+    ImportChain(ImportChain),
 }
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
@@ -91,6 +94,15 @@ pub struct Migrate {
         display_order = 0
     )]
     pub to: u64,
+
+    #[clap(
+        long,
+        help = "List the migration steps and estimate how long each will take, without \
+                modifying the database.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub dry_run: bool,
 }
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
@@ -227,3 +239,81 @@ pub struct Compact {
     )]
     pub output_dir: Option<PathBuf>,
 }
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(about = "Export finalized blocks, e.g. for an external indexer.")]
+pub struct ExportChain {
+    #[clap(
+        long,
+        value_name = "SLOT",
+        default_value_t = 0,
+        help = "The slot of the first block to export. Must not be prior to the oldest block \
+                in the database.",
+        display_order = 0
+    )]
+    pub start_slot: u64,
+
+    #[clap(
+        long,
+        value_name = "SLOT",
+        help = "The slot of the last block to export. Defaults to the slot prior to the \
+                latest finalized state.",
+        display_order = 0
+    )]
+    pub end_slot: Option<u64>,
+
+    #[clap(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = ChainFormat::Jsonl,
+        help = "Export one JSON object per line, or each block as SSZ prefixed by its length as \
+                a little-endian u32.",
+        display_order = 0
+    )]
+    pub format: ChainFormat,
+
+    #[clap(
+        long,
+        help = "Include the index and KZG commitment and proof of the blobs stored for each \
+                block. Requires the jsonl format.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub blobs: bool,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "File to write the blocks to. Defaults to stdout.",
+        display_order = 0
+    )]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Import blocks exported by export-chain into a database with incomplete history. \
+                The newest block imported must be the parent of the oldest block in the \
+                database."
+)]
+pub struct ImportChain {
+    #[clap(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = ChainFormat::Jsonl,
+        help = "The format the blocks were exported in.",
+        display_order = 0
+    )]
+    pub format: ChainFormat,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "File to read the blocks from. It is read from the newest block to the oldest, so \
+                it must be a file rather than a pipe.",
+        display_order = 0
+    )]
+    pub input: PathBuf,
+}
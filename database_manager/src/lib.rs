@@ -1,26 +1,33 @@
+mod chain_export;
 pub mod cli;
+use crate::chain_export::{export_chain, import_chain, ExportChainConfig, ImportChainConfig};
 use crate::cli::DatabaseManager;
 use crate::cli::Migrate;
 use crate::cli::PruneStates;
 use beacon_chain::{
-    builder::Witness, eth1_chain::CachingEth1Backend, schema_change::migrate_schema,
+    builder::Witness,
+    eth1_chain::CachingEth1Backend,
+    schema_change::{migrate_schema, migration_plan},
     slot_clock::SystemTimeSlotClock,
 };
 use beacon_node::{get_data_dir, get_slots_per_restore_point, ClientConfig};
 use clap::ArgMatches;
 use clap::ValueEnum;
-use cli::{Compact, Inspect};
+use cli::{Compact, ExportChain, ImportChain, Inspect};
 use environment::{Environment, RuntimeContext};
 use serde::{Deserialize, Serialize};
 use slog::{info, warn, Logger};
+use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use store::metadata::STATE_UPPER_LIMIT_NO_RETAIN;
 use store::{
     errors::Error,
-    metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION},
-    DBColumn, HotColdDB, KeyValueStore, LevelDB,
+    metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY},
+    DBColumn, HotColdDB, ItemStore, KeyValueStore, LevelDB,
 };
 use strum::{EnumString, EnumVariantNames};
 use types::{BeaconState, EthSpec, Slot};
@@ -287,12 +294,14 @@ pub fn compact_db<E: EthSpec>(
 
 pub struct MigrateConfig {
     to: SchemaVersion,
+    dry_run: bool,
 }
 
 fn parse_migrate_config(migrate_config: &Migrate) -> Result<MigrateConfig, String> {
     let to = SchemaVersion(migrate_config.to);
+    let dry_run = migrate_config.dry_run;
 
-    Ok(MigrateConfig { to })
+    Ok(MigrateConfig { to, dry_run })
 }
 
 pub fn migrate_db<E: EthSpec>(
@@ -338,6 +347,87 @@ pub fn migrate_db<E: EthSpec>(
     )
 }
 
+/// Log the steps required to migrate the database, along with an estimate of how long each will
+/// take based on the number of keys in the columns it affects.
+///
+/// The hot database is opened directly rather than via `HotColdDB::open` so that no migration,
+/// config update or garbage collection is run.
+pub fn migrate_db_dry_run<E: EthSpec>(
+    migrate_config: MigrateConfig,
+    client_config: ClientConfig,
+    log: Logger,
+) -> Result<(), String> {
+    let hot_path = client_config.get_db_path();
+    if !hot_path.exists() {
+        return Err(format!("No database found at {}", hot_path.display()));
+    }
+
+    let hot_db =
+        LevelDB::<E>::open(&hot_path).map_err(|e| format!("Unable to open hot DB: {e:?}"))?;
+    let from = hot_db
+        .get::<SchemaVersion>(&SCHEMA_VERSION_KEY)
+        .map_err(|e| format!("Unable to read schema version: {e:?}"))?
+        .ok_or("Database has no schema version")?;
+    let to = migrate_config.to;
+
+    let steps = migration_plan(from, to).map_err(|e| format!("Unable to plan migration: {e:?}"))?;
+
+    info!(
+        log,
+        "Planning database schema migration";
+        "from" => from.as_u64(),
+        "to" => to.as_u64(),
+        "steps" => steps.len(),
+    );
+
+    // Several steps may iterate the same column, only count its keys once.
+    let mut key_counts = HashMap::<&'static str, usize>::new();
+    let mut total_duration = Duration::ZERO;
+
+    for step in &steps {
+        let mut num_keys = 0;
+        for &column in step.affected_columns {
+            num_keys += match key_counts.get(column.as_str()) {
+                Some(count) => *count,
+                None => {
+                    let mut count = 0;
+                    for res in hot_db.iter_column_keys::<Vec<u8>>(column) {
+                        res.map_err(|e| format!("Unable to read {column:?} keys: {e:?}"))?;
+                        count += 1;
+                    }
+                    key_counts.insert(column.as_str(), count);
+                    count
+                }
+            };
+        }
+
+        let estimated_duration = if step.keys_per_second == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(num_keys as f64 / step.keys_per_second as f64)
+        };
+        total_duration += estimated_duration;
+
+        info!(
+            log,
+            "Migration step";
+            "from" => step.from.as_u64(),
+            "to" => step.to.as_u64(),
+            "operation" => step.description,
+            "columns" => ?step.affected_columns,
+            "keys" => num_keys,
+            "estimated_duration" => ?estimated_duration,
+        );
+    }
+
+    info!(
+        log,
+        "Dry run complete, the database has not been modified";
+        "estimated_duration" => ?total_duration,
+    );
+    Ok(())
+}
+
 pub fn prune_payloads<E: EthSpec>(
     client_config: ClientConfig,
     runtime_context: &RuntimeContext<E>,
@@ -473,6 +563,46 @@ pub fn prune_states<E: EthSpec>(
     Ok(())
 }
 
+fn parse_export_chain_config(export_chain_config: &ExportChain) -> ExportChainConfig {
+    ExportChainConfig {
+        start_slot: Slot::new(export_chain_config.start_slot),
+        end_slot: export_chain_config.end_slot.map(Slot::new),
+        format: export_chain_config.format,
+        blobs: export_chain_config.blobs,
+        output: export_chain_config.output.clone(),
+    }
+}
+
+fn parse_import_chain_config(import_chain_config: &ImportChain) -> ImportChainConfig {
+    ImportChainConfig {
+        format: import_chain_config.format,
+        input: import_chain_config.input.clone(),
+    }
+}
+
+/// Open the database for an export or import of blocks.
+fn open_db<E: EthSpec>(
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>, String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log,
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))
+}
+
 /// Run the database manager, returning an error string if the operation did not succeed.
 pub fn run<E: EthSpec>(
     cli_args: &ArgMatches,
@@ -487,7 +617,11 @@ pub fn run<E: EthSpec>(
     match &db_manager_config.subcommand {
         cli::DatabaseManagerSubcommand::Migrate(migrate_config) => {
             let migrate_config = parse_migrate_config(migrate_config)?;
-            migrate_db(migrate_config, client_config, &context, log).map_err(format_err)
+            if migrate_config.dry_run {
+                migrate_db_dry_run::<E>(migrate_config, client_config, log)
+            } else {
+                migrate_db(migrate_config, client_config, &context, log).map_err(format_err)
+            }
         }
         cli::DatabaseManagerSubcommand::Inspect(inspect_config) => {
             let inspect_config = parse_inspect_config(inspect_config)?;
@@ -530,5 +664,15 @@ pub fn run<E: EthSpec>(
             let compact_config = parse_compact_config(compact_config)?;
             compact_db::<E>(compact_config, client_config, log).map_err(format_err)
         }
+        cli::DatabaseManagerSubcommand::ExportChain(export_chain_config) => {
+            let export_chain_config = parse_export_chain_config(export_chain_config);
+            let db = open_db(client_config, &context, log.clone())?;
+            export_chain(&db, export_chain_config, &log)
+        }
+        cli::DatabaseManagerSubcommand::ImportChain(import_chain_config) => {
+            let import_chain_config = parse_import_chain_config(import_chain_config);
+            let db = open_db(client_config, &context, log.clone())?;
+            import_chain(&db, import_chain_config, &log)
+        }
     }
 }
@@ -0,0 +1,122 @@
+//! # Export Finalized
+//!
+//! Use this tool to export the finalized blocks and states of a stopped beacon node's database as
+//! SSZ files. The freezer database is read in parallel using `HotColdDB::par_iter_finalized_blocks`
+//! and `HotColdDB::par_iter_finalized_states`, with bounded memory.
+//!
+//! Blocks are written to `<output-dir>/blocks/block_<slot>_<root>.ssz` as blinded blocks. States
+//! are only exported if `--state-step` is provided, and are written to
+//! `<output-dir>/states/state_<slot>.ssz`.
+//!
+//! ## Examples
+//!
+//! Export the blocks of the first 100 epochs, and the state at the start of each epoch.
+//!
+//! ```ignore
+//! lcli export-finalized \
+//!     --datadir ~/.lighthouse/mainnet/beacon \
+//!     --start-slot 0 \
+//!     --end-slot 3199 \
+//!     --state-step 32 \
+//!     --output-dir /tmp/export
+//! ```
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2_network_config::Eth2NetworkConfig;
+use log::info;
+use ssz::Encode;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use store::{HotColdDB, LevelDB, ParallelIterConfig, StoreConfig};
+use types::{EthSpec, Slot};
+
+pub fn run<E: EthSpec>(
+    env: Environment<E>,
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let spec = network_config.chain_spec::<E>()?;
+    let log = env.core_context().log().clone();
+
+    let datadir: PathBuf = parse_required(matches, "datadir")?;
+    let output_dir: PathBuf = parse_required(matches, "output-dir")?;
+    let start_slot: Slot = parse_required(matches, "start-slot")?;
+    let end_slot: Slot = parse_required(matches, "end-slot")?;
+    let state_step: Option<u64> = parse_optional(matches, "state-step")?;
+    let slots_per_restore_point: Option<u64> = parse_optional(matches, "slots-per-restore-point")?;
+
+    let mut iter_config = ParallelIterConfig::default();
+    if let Some(max_concurrent_loads) = parse_optional(matches, "max-concurrent-loads")? {
+        iter_config.max_concurrent_loads = max_concurrent_loads;
+    }
+    if let Some(max_mib_in_flight) = parse_optional::<usize>(matches, "max-mib-in-flight")? {
+        iter_config.max_bytes_in_flight = max_mib_in_flight
+            .checked_mul(1024 * 1024)
+            .ok_or("--max-mib-in-flight is too large")?;
+    }
+
+    let mut store_config = StoreConfig::default();
+    if let Some(slots_per_restore_point) = slots_per_restore_point {
+        store_config.slots_per_restore_point = slots_per_restore_point;
+        store_config.slots_per_restore_point_set_explicitly = true;
+    }
+
+    let store = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &datadir.join("chain_db"),
+        &datadir.join("freezer_db"),
+        &datadir.join("blobs_db"),
+        |_, _, _| Ok(()),
+        store_config,
+        spec,
+        log,
+    )
+    .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+    let blocks_dir = output_dir.join("blocks");
+    fs::create_dir_all(&blocks_dir)
+        .map_err(|e| format!("Unable to create {}: {:?}", blocks_dir.display(), e))?;
+
+    let timer = Instant::now();
+    let mut num_blocks = 0;
+    for result in store
+        .par_iter_finalized_blocks(start_slot, end_slot, iter_config)
+        .map_err(|e| format!("Unable to iterate blocks: {:?}", e))?
+    {
+        let (block_root, block) = result.map_err(|e| format!("Unable to load block: {:?}", e))?;
+        write_ssz(
+            &blocks_dir.join(format!("block_{}_{:?}.ssz", block.slot(), block_root)),
+            &block.as_ssz_bytes(),
+        )?;
+        num_blocks += 1;
+    }
+    info!("Exported {} blocks in {:?}", num_blocks, timer.elapsed());
+
+    if let Some(state_step) = state_step {
+        let states_dir = output_dir.join("states");
+        fs::create_dir_all(&states_dir)
+            .map_err(|e| format!("Unable to create {}: {:?}", states_dir.display(), e))?;
+
+        let timer = Instant::now();
+        let mut num_states = 0;
+        for result in store
+            .par_iter_finalized_states(start_slot, end_slot, state_step, iter_config)
+            .map_err(|e| format!("Unable to iterate states: {:?}", e))?
+        {
+            let (slot, state) = result.map_err(|e| format!("Unable to load state: {:?}", e))?;
+            write_ssz(
+                &states_dir.join(format!("state_{}.ssz", slot)),
+                &state.as_ssz_bytes(),
+            )?;
+            num_states += 1;
+        }
+        info!("Exported {} states in {:?}", num_states, timer.elapsed());
+    }
+
+    Ok(())
+}
+
+fn write_ssz(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    fs::write(path, bytes).map_err(|e| format!("Unable to write {}: {:?}", path.display(), e))
+}
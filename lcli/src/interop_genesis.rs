@@ -0,0 +1,77 @@
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use eth2_network_config::Eth2NetworkConfig;
+use genesis::interop_genesis;
+use ssz::Encode;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{EthSpec, ExecutionPayloadHeader, ForkName};
+
+/// The file containing the SSZ encoded genesis state.
+pub const GENESIS_STATE_FILENAME: &str = "genesis.ssz";
+/// The file containing the JSON encoded deposit data of the interop validators.
+pub const DEPOSIT_DATA_FILENAME: &str = "deposit_data.json";
+
+pub fn run<E: EthSpec>(
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let validator_count: usize = parse_required(matches, "count")?;
+    let output_dir: PathBuf = parse_required(matches, "output-dir")?;
+    let genesis_time: u64 = match parse_optional(matches, "genesis-time")? {
+        Some(time) => time,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Unable to read system time: {:?}", e))?
+            .as_secs(),
+    };
+    let fork: ForkName = parse_required(matches, "fork")?;
+    let execution_payload_header = parse_optional::<PathBuf>(matches, "execution-payload-header")?
+        .map(|path| {
+            let bytes = fs::read(&path)
+                .map_err(|e| format!("Unable to read {}: {:?}", path.display(), e))?;
+            ExecutionPayloadHeader::<E>::from_ssz_bytes(&bytes, fork)
+                .map_err(|e| format!("Unable to decode execution payload header: {:?}", e))
+        })
+        .transpose()?;
+
+    if output_dir.exists() {
+        return Err(format!(
+            "{:?} already exists, will not override",
+            output_dir
+        ));
+    }
+
+    let spec = network_config.chain_spec::<E>()?;
+    let genesis = interop_genesis::<E>(
+        validator_count,
+        genesis_time,
+        fork,
+        execution_payload_header,
+        spec,
+    )?;
+
+    fs::create_dir_all(&output_dir).map_err(|e| format!("Unable to create output-dir: {:?}", e))?;
+
+    let mut state_file = File::create(output_dir.join(GENESIS_STATE_FILENAME))
+        .map_err(|e| format!("Unable to create {}: {:?}", GENESIS_STATE_FILENAME, e))?;
+    state_file
+        .write_all(&genesis.state.as_ssz_bytes())
+        .map_err(|e| format!("Unable to write {}: {:?}", GENESIS_STATE_FILENAME, e))?;
+
+    let deposit_file = File::create(output_dir.join(DEPOSIT_DATA_FILENAME))
+        .map_err(|e| format!("Unable to create {}: {:?}", DEPOSIT_DATA_FILENAME, e))?;
+    serde_json::to_writer_pretty(deposit_file, &genesis.deposit_data)
+        .map_err(|e| format!("Unable to write {}: {:?}", DEPOSIT_DATA_FILENAME, e))?;
+
+    println!(
+        "Wrote {} genesis state with {} validators to {}",
+        fork,
+        validator_count,
+        output_dir.display()
+    );
+
+    Ok(())
+}
@@ -1,10 +1,13 @@
 mod block_root;
 mod check_deposit_data;
+mod export_finalized;
 mod generate_bootnode_enr;
 mod indexed_attestations;
+mod interop_genesis;
 mod mnemonic_validators;
 mod mock_el;
 mod parse_ssz;
+mod replay_gossip;
 mod skip_slots;
 mod state_root;
 mod transition_blocks;
@@ -404,6 +407,56 @@ fn main() {
                         .display_order(0)
                 )
         )
+        .subcommand(
+            Command::new("interop-genesis")
+                .about("Produces a genesis state and deposit data for the deterministic interop \
+                        validator keypairs. For testing purposes only, DO NOT USE IN PRODUCTION!")
+                .arg(
+                    Arg::new("count")
+                        .long("count")
+                        .value_name("COUNT")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The number of interop validators in the genesis state.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("fork")
+                        .long("fork")
+                        .value_name("FORK")
+                        .action(ArgAction::Set)
+                        .default_value("deneb")
+                        .help("The fork of the genesis state. All prior forks are scheduled at \
+                               genesis.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("genesis-time")
+                        .long("genesis-time")
+                        .value_name("UNIX_SECONDS")
+                        .action(ArgAction::Set)
+                        .help("The genesis time of the state. Defaults to now.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("execution-payload-header")
+                        .long("execution-payload-header")
+                        .value_name("FILE")
+                        .action(ArgAction::Set)
+                        .help("Path to an SSZ execution payload header of the genesis fork to \
+                               include in the state.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("output-dir")
+                        .long("output-dir")
+                        .value_name("OUTPUT_DIRECTORY")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The directory in which to write the genesis state and deposit data.")
+                        .display_order(0)
+                )
+        )
         .subcommand(
             Command::new("block-root")
                 .about("Computes the block root of some block.")
@@ -552,6 +605,115 @@ fn main() {
                         .display_order(0)
                 )
         )
+        .subcommand(
+            Command::new("replay-gossip")
+                .about("Replays a gossip recording made with `lighthouse bn --record-gossip` into \
+                a fresh in-memory beacon chain started from genesis.")
+                .arg(
+                    Arg::new("genesis-state-path")
+                        .long("genesis-state-path")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Path to load the genesis BeaconState from as SSZ.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("recording-path")
+                        .long("recording-path")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Path of the gossip recording to replay.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("relative-timing")
+                        .long("relative-timing")
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help("Wait between records for the same time that elapsed between them \
+                            when they were recorded, rather than replaying them immediately.")
+                        .display_order(0)
+                )
+        )
+        .subcommand(
+            Command::new("export-finalized")
+                .about("Exports the finalized blocks and states of a stopped beacon node's \
+                database as SSZ files, reading the freezer database in parallel.")
+                .arg(
+                    Arg::new("datadir")
+                        .long("datadir")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The beacon node data directory containing the chain_db, \
+                            freezer_db and blobs_db directories.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("output-dir")
+                        .long("output-dir")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The directory in which to write the blocks and states.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("start-slot")
+                        .long("start-slot")
+                        .value_name("SLOT")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The first slot to export.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("end-slot")
+                        .long("end-slot")
+                        .value_name("SLOT")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The last slot to export, which must be finalized.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("state-step")
+                        .long("state-step")
+                        .value_name("SLOTS")
+                        .action(ArgAction::Set)
+                        .help("Export the state at every this many slots from the start slot. \
+                            States are not exported if this is not provided.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("slots-per-restore-point")
+                        .long("slots-per-restore-point")
+                        .value_name("SLOTS")
+                        .action(ArgAction::Set)
+                        .help("The slots-per-restore-point of the database, if it was not \
+                            created with the default.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("max-concurrent-loads")
+                        .long("max-concurrent-loads")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .help("The maximum number of chunks of blocks or states to load at once.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("max-mib-in-flight")
+                        .long("max-mib-in-flight")
+                        .value_name("MEBIBYTES")
+                        .action(ArgAction::Set)
+                        .help("The maximum size of the blocks or states which have been loaded \
+                            but not yet written.")
+                        .display_order(0)
+                )
+        )
         .get_matches();
 
     let result = matches
@@ -580,9 +742,12 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
         .initialize_logger(LoggerConfig {
             path: None,
             debug_level: String::from("trace"),
+            log_levels: None,
             logfile_debug_level: String::from("trace"),
             log_format: None,
             logfile_format: None,
+            json_log_path: None,
+            otlp_endpoint: None,
             log_color: false,
             disable_log_timestamp: false,
             max_log_size: 0,
@@ -644,6 +809,11 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
             .map_err(|e| format!("Failed to run mnemonic-validators command: {}", e)),
         Some(("indexed-attestations", matches)) => indexed_attestations::run::<E>(matches)
             .map_err(|e| format!("Failed to run indexed-attestations command: {}", e)),
+        Some(("interop-genesis", matches)) => {
+            let network_config = get_network_config()?;
+            interop_genesis::run::<E>(network_config, matches)
+                .map_err(|e| format!("Failed to run interop-genesis command: {}", e))
+        }
         Some(("block-root", matches)) => {
             let network_config = get_network_config()?;
             block_root::run::<E>(env, network_config, matches)
@@ -654,6 +824,16 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
             state_root::run::<E>(env, network_config, matches)
                 .map_err(|e| format!("Failed to run state-root command: {}", e))
         }
+        Some(("replay-gossip", matches)) => {
+            let network_config = get_network_config()?;
+            replay_gossip::run::<E>(env, network_config, matches)
+                .map_err(|e| format!("Failed to run replay-gossip command: {}", e))
+        }
+        Some(("export-finalized", matches)) => {
+            let network_config = get_network_config()?;
+            export_finalized::run::<E>(env, network_config, matches)
+                .map_err(|e| format!("Failed to run export-finalized command: {}", e))
+        }
         Some(("mock-el", matches)) => mock_el::run::<E>(env, matches)
             .map_err(|e| format!("Failed to run mock-el command: {}", e)),
         Some((other, _)) => Err(format!("Unknown subcommand {}. See --help.", other)),
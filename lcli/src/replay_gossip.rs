@@ -0,0 +1,84 @@
+//! # Replay Gossip
+//!
+//! Use this tool to replay a gossip recording made with `lighthouse bn --record-gossip` into a
+//! fresh, in-memory beacon chain. Each recorded object is passed back through gossip verification
+//! and import at the slot at which it was originally recorded, which makes it possible to
+//! reproduce consensus bugs offline.
+//!
+//! The chain is started from the supplied genesis state, so the recording must have been made by a
+//! node which received gossip from genesis (e.g. on a local testnet). Execution payloads are not
+//! verified; a mock execution layer accepts every payload.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! lcli replay-gossip \
+//!     --testnet-dir ~/.lighthouse/local-testnet/testnet \
+//!     --genesis-state-path ~/.lighthouse/local-testnet/testnet/genesis.ssz \
+//!     --recording-path /tmp/gossip.ssz \
+//!     --relative-timing
+//! ```
+use crate::transition_blocks::load_from_ssz_with;
+use beacon_chain::gossip_recorder::{read_gossip_records, ReplayTiming};
+use beacon_chain::test_utils::BeaconChainHarness;
+use clap::ArgMatches;
+use clap_utils::parse_required;
+use environment::Environment;
+use eth2_network_config::Eth2NetworkConfig;
+use log::info;
+use std::path::PathBuf;
+use types::{BeaconState, EthSpec};
+
+pub fn run<E: EthSpec>(
+    env: Environment<E>,
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let executor = env.core_context().executor;
+    let spec = network_config.chain_spec::<E>()?;
+
+    let genesis_state_path: PathBuf = parse_required(matches, "genesis-state-path")?;
+    let recording_path: PathBuf = parse_required(matches, "recording-path")?;
+    let timing = if matches.get_flag("relative-timing") {
+        ReplayTiming::Relative
+    } else {
+        ReplayTiming::Immediate
+    };
+
+    let genesis_state =
+        load_from_ssz_with(&genesis_state_path, &spec, BeaconState::from_ssz_bytes)?;
+    let records = read_gossip_records(&recording_path)?;
+    info!("Loaded {} gossip records", records.len());
+
+    let summary = executor
+        .handle()
+        .ok_or("shutdown in progress")?
+        .block_on(async move {
+            let harness = BeaconChainHarness::builder(E::default())
+                .spec(spec)
+                .keypairs(vec![])
+                .genesis_state_ephemeral_store(genesis_state)
+                .mock_execution_layer_all_payloads_valid()
+                .build();
+
+            let summary = harness.chain.replay_gossip(records, timing).await?;
+            harness.chain.recompute_head_at_current_slot().await;
+
+            let head = harness.chain.canonical_head.cached_head();
+            info!(
+                "Replay finished at slot {} with head {:?} at slot {}",
+                harness.chain.slot().map_err(|e| format!("{:?}", e))?,
+                head.head_block_root(),
+                head.head_slot()
+            );
+            Ok::<_, String>(summary)
+        })?;
+
+    info!("Blocks imported: {}", summary.blocks_imported);
+    info!("Blobs imported: {}", summary.blobs_imported);
+    info!("Attestations imported: {}", summary.attestations_imported);
+    info!("Aggregates imported: {}", summary.aggregates_imported);
+    info!("Rejected: {}", summary.rejected);
+
+    Ok(())
+}
@@ -12,9 +12,9 @@ use eth2_network_config::Eth2NetworkConfig;
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use futures::{future, StreamExt};
 
-use logging::SSELoggingComponents;
+use logging::{LogLevelFilter, LogLevels, SSELoggingComponents};
 use serde::{Deserialize, Serialize};
-use slog::{error, info, o, warn, Drain, Duplicate, Level, Logger};
+use slog::{error, info, o, warn, Drain, Duplicate, Logger};
 use sloggers::{file::FileLoggerBuilder, types::Format, types::Severity, Build};
 use std::fs::create_dir_all;
 use std::io::{Result as IOResult, Write};
@@ -50,9 +50,16 @@ const MAXIMUM_SHUTDOWN_TIME: u64 = 15;
 pub struct LoggerConfig {
     pub path: Option<PathBuf>,
     pub debug_level: String,
+    /// Per-module overrides of `debug_level`, e.g. `network=debug,store=info`.
+    pub log_levels: Option<String>,
     pub logfile_debug_level: String,
     pub log_format: Option<String>,
     pub logfile_format: Option<String>,
+    /// Also write logs as JSON to this file, or to stderr if it is `-`, filtered by `debug_level`
+    /// and `log_levels`.
+    pub json_log_path: Option<PathBuf>,
+    /// Export the import pipeline tracing spans to an OpenTelemetry collector at this endpoint.
+    pub otlp_endpoint: Option<String>,
     pub log_color: bool,
     pub disable_log_timestamp: bool,
     pub max_log_size: u64,
@@ -66,9 +73,12 @@ impl Default for LoggerConfig {
         LoggerConfig {
             path: None,
             debug_level: String::from("info"),
+            log_levels: None,
             logfile_debug_level: String::from("debug"),
             log_format: None,
             logfile_format: None,
+            json_log_path: None,
+            otlp_endpoint: None,
             log_color: false,
             disable_log_timestamp: false,
             max_log_size: 200,
@@ -91,6 +101,7 @@ pub struct RuntimeContext<E: EthSpec> {
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
     pub sse_logging_components: Option<SSELoggingComponents>,
+    pub log_levels: Option<LogLevels>,
 }
 
 impl<E: EthSpec> RuntimeContext<E> {
@@ -104,6 +115,7 @@ impl<E: EthSpec> RuntimeContext<E> {
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
             sse_logging_components: self.sse_logging_components.clone(),
+            log_levels: self.log_levels.clone(),
         }
     }
 
@@ -123,6 +135,7 @@ pub struct EnvironmentBuilder<E: EthSpec> {
     runtime: Option<Arc<Runtime>>,
     log: Option<Logger>,
     sse_logging_components: Option<SSELoggingComponents>,
+    log_levels: Option<LogLevels>,
     eth_spec_instance: E,
     eth2_config: Eth2Config,
     eth2_network_config: Option<Eth2NetworkConfig>,
@@ -135,6 +148,7 @@ impl EnvironmentBuilder<MinimalEthSpec> {
             runtime: None,
             log: None,
             sse_logging_components: None,
+            log_levels: None,
             eth_spec_instance: MinimalEthSpec,
             eth2_config: Eth2Config::minimal(),
             eth2_network_config: None,
@@ -149,6 +163,7 @@ impl EnvironmentBuilder<MainnetEthSpec> {
             runtime: None,
             log: None,
             sse_logging_components: None,
+            log_levels: None,
             eth_spec_instance: MainnetEthSpec,
             eth2_config: Eth2Config::mainnet(),
             eth2_network_config: None,
@@ -163,6 +178,7 @@ impl EnvironmentBuilder<GnosisEthSpec> {
             runtime: None,
             log: None,
             sse_logging_components: None,
+            log_levels: None,
             eth_spec_instance: GnosisEthSpec,
             eth2_config: Eth2Config::gnosis(),
             eth2_network_config: None,
@@ -235,17 +251,48 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let stdout_drain = match config.debug_level.as_str() {
-            "info" => stdout_drain.filter_level(Level::Info),
-            "debug" => stdout_drain.filter_level(Level::Debug),
-            "trace" => stdout_drain.filter_level(Level::Trace),
-            "warn" => stdout_drain.filter_level(Level::Warning),
-            "error" => stdout_drain.filter_level(Level::Error),
-            "crit" => stdout_drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let debug_level = logging::parse_level(&config.debug_level)
+            .map_err(|_| format!("Unknown debug-level: {}", config.debug_level))?;
+        let log_levels = LogLevels::new(debug_level);
+        if let Some(directives) = &config.log_levels {
+            log_levels
+                .update(directives)
+                .map_err(|e| format!("Invalid log-levels: {}", e))?;
+        }
+        self.log_levels = Some(log_levels.clone());
+
+        let stdout_drain = LogLevelFilter::new(stdout_drain, log_levels.clone());
 
-        let stdout_logger = Logger::root(stdout_drain.fuse(), o!());
+        let mut stdout_logger = Logger::root(stdout_drain.fuse(), o!());
+
+        // Write a separate stream of JSON logs, if requested.
+        if let Some(json_log_path) = &config.json_log_path {
+            let json_drain = if json_log_path.as_os_str() == "-" {
+                let stderr_drain = slog_json::Json::default(std::io::stderr()).fuse();
+                let stderr_drain = slog_async::Async::new(stderr_drain)
+                    .chan_size(LOG_CHANNEL_SIZE)
+                    .build();
+                Logger::root(stderr_drain.fuse(), o!())
+            } else {
+                let mut builder = FileLoggerBuilder::new(json_log_path);
+                builder
+                    .level(Severity::Trace)
+                    .channel_size(LOG_CHANNEL_SIZE)
+                    .format(Format::Json)
+                    .restrict_permissions(config.is_restricted);
+                if config.max_log_size > 0 && config.max_log_number > 0 {
+                    builder
+                        .rotate_size(config.max_log_size)
+                        .rotate_keep(config.max_log_number)
+                        .rotate_compress(config.compression);
+                }
+                builder
+                    .build()
+                    .map_err(|e| format!("Unable to build JSON logger: {}", e))?
+            };
+            let json_drain = LogLevelFilter::new(json_drain, log_levels.clone());
+            stdout_logger = Logger::root(Duplicate::new(stdout_logger, json_drain).fuse(), o!());
+        }
 
         // Disable file logging if values set to 0.
         if config.max_log_size == 0 || config.max_log_number == 0 {
@@ -284,18 +331,17 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             }
         }
 
-        let logfile_level = match config.logfile_debug_level.as_str() {
-            "info" => Severity::Info,
-            "debug" => Severity::Debug,
-            "trace" => Severity::Trace,
-            "warn" => Severity::Warning,
-            "error" => Severity::Error,
-            "crit" => Severity::Critical,
-            unknown => return Err(format!("Unknown loglevel-debug-level: {}", unknown)),
-        };
+        let logfile_level = logging::parse_level(&config.logfile_debug_level).map_err(|_| {
+            format!(
+                "Unknown loglevel-debug-level: {}",
+                config.logfile_debug_level
+            )
+        })?;
 
+        // The file logger passes all records to the `LogLevelFilter`, which applies
+        // `logfile_level` to all modules without a per-module override.
         let file_logger = FileLoggerBuilder::new(&path)
-            .level(logfile_level)
+            .level(Severity::Trace)
             .channel_size(LOG_CHANNEL_SIZE)
             .format(match config.logfile_format.as_deref() {
                 Some("JSON") => Format::Json,
@@ -307,6 +353,7 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             .restrict_permissions(config.is_restricted)
             .build()
             .map_err(|e| format!("Unable to build file logger: {}", e))?;
+        let file_logger = LogLevelFilter::with_default(file_logger, log_levels, logfile_level);
 
         let mut log = Logger::root(Duplicate::new(stdout_logger, file_logger).fuse(), o!());
 
@@ -355,6 +402,7 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             exit,
             log: self.log.ok_or("Cannot build environment without log")?,
             sse_logging_components: self.sse_logging_components,
+            log_levels: self.log_levels,
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             eth2_network_config: self.eth2_network_config.map(Arc::new),
@@ -374,6 +422,7 @@ pub struct Environment<E: EthSpec> {
     exit: async_channel::Receiver<()>,
     log: Logger,
     sse_logging_components: Option<SSELoggingComponents>,
+    log_levels: Option<LogLevels>,
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
@@ -401,6 +450,7 @@ impl<E: EthSpec> Environment<E> {
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
             sse_logging_components: self.sse_logging_components.clone(),
+            log_levels: self.log_levels.clone(),
         }
     }
 
@@ -417,6 +467,7 @@ impl<E: EthSpec> Environment<E> {
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
             sse_logging_components: self.sse_logging_components.clone(),
+            log_levels: self.log_levels.clone(),
         }
     }
 
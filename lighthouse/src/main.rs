@@ -1,7 +1,7 @@
 mod cli;
 mod metrics;
 
-use beacon_node::ProductionBeaconNode;
+use beacon_node::{ClientConfig, ProductionBeaconNode};
 use clap::FromArgMatches;
 use clap::Subcommand;
 use clap::{Arg, ArgAction, ArgMatches, Command};
@@ -10,7 +10,7 @@ use clap_utils::{
 };
 use cli::LighthouseSubcommands;
 use directory::{parse_path_or_default, DEFAULT_BEACON_NODE_DIR, DEFAULT_VALIDATOR_DIR};
-use environment::{EnvironmentBuilder, LoggerConfig};
+use environment::{EnvironmentBuilder, LoggerConfig, RuntimeContext};
 use eth2_network_config::{Eth2NetworkConfig, DEFAULT_HARDCODED_NETWORK, HARDCODED_NET_NAMES};
 use ethereum_hashing::have_sha_extensions;
 use futures::TryFutureExt;
@@ -19,8 +19,10 @@ use lighthouse_version::VERSION;
 use malloc_utils::configure_memory_allocator;
 use slog::{crit, info};
 use std::backtrace::Backtrace;
+use std::fs;
 use std::path::PathBuf;
 use std::process::exit;
+use std::sync::Arc;
 use task_executor::ShutdownReason;
 use types::{EthSpec, EthSpecId};
 use validator_client::ProductionValidatorClient;
@@ -237,6 +239,28 @@ fn main() {
                 .default_value("info")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("log-json-file")
+                .long("log-json-file")
+                .value_name("FILE")
+                .help("Also write logs as JSON to this file, or to stderr if it is `-`. Logs are \
+                    filtered by --debug-level and --log-levels, and the file is rotated according \
+                    to --logfile-max-size and --logfile-max-number.")
+                .action(ArgAction::Set)
+                .global(true)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("log-levels")
+                .long("log-levels")
+                .value_name("LEVELS")
+                .help("A comma-separated list of per-module overrides of --debug-level and \
+                    --logfile-debug-level, e.g. `network=debug,store=info`. A module's level \
+                    applies to all of its sub-modules unless they have an override of their own.")
+                .action(ArgAction::Set)
+                .global(true)
+                .display_order(0)
+        )
         .arg(
             Arg::new("datadir")
                 .long("datadir")
@@ -417,7 +441,8 @@ fn main() {
 
     let cli = LighthouseSubcommands::augment_subcommands(cli);
 
-    let matches = cli.get_matches();
+    // Keep the command so that the arguments of additional networks can be parsed later.
+    let matches = cli.clone().get_matches();
 
     // Configure the allocator early in the process, before it has the chance to use the default values for
     // anything important.
@@ -459,11 +484,26 @@ fn main() {
         }
 
         match eth_spec_id {
-            EthSpecId::Mainnet => run(EnvironmentBuilder::mainnet(), &matches, eth2_network_config),
+            EthSpecId::Mainnet => run(
+                EnvironmentBuilder::mainnet(),
+                &cli,
+                &matches,
+                eth2_network_config,
+            ),
             #[cfg(feature = "gnosis")]
-            EthSpecId::Gnosis => run(EnvironmentBuilder::gnosis(), &matches, eth2_network_config),
+            EthSpecId::Gnosis => run(
+                EnvironmentBuilder::gnosis(),
+                &cli,
+                &matches,
+                eth2_network_config,
+            ),
             #[cfg(feature = "spec-minimal")]
-            EthSpecId::Minimal => run(EnvironmentBuilder::minimal(), &matches, eth2_network_config),
+            EthSpecId::Minimal => run(
+                EnvironmentBuilder::minimal(),
+                &cli,
+                &matches,
+                eth2_network_config,
+            ),
             #[cfg(not(all(feature = "spec-minimal", feature = "gnosis")))]
             other => {
                 eprintln!(
@@ -492,6 +532,7 @@ fn main() {
 
 fn run<E: EthSpec>(
     environment_builder: EnvironmentBuilder<E>,
+    cli: &Command,
     matches: &ArgMatches,
     eth2_network_config: Eth2NetworkConfig,
 ) -> Result<(), String> {
@@ -506,6 +547,15 @@ fn run<E: EthSpec>(
         .get_one::<String>("debug-level")
         .ok_or("Expected --debug-level flag")?;
 
+    let log_levels = matches.get_one::<String>("log-levels");
+
+    let json_log_path: Option<PathBuf> = clap_utils::parse_optional(matches, "log-json-file")?;
+
+    let otlp_endpoint = matches
+        .subcommand_matches("beacon_node")
+        .and_then(|bn_matches| bn_matches.get_one::<String>("otlp-endpoint"))
+        .cloned();
+
     let log_format = matches.get_one::<String>("log-format");
 
     let log_color = matches.get_flag("log-color");
@@ -579,9 +629,12 @@ fn run<E: EthSpec>(
     let logger_config = LoggerConfig {
         path: log_path.clone(),
         debug_level: String::from(debug_level),
+        log_levels: log_levels.map(String::from),
         logfile_debug_level: String::from(logfile_debug_level),
         log_format: log_format.map(String::from),
         logfile_format: logfile_format.map(String::from),
+        json_log_path,
+        otlp_endpoint,
         log_color,
         disable_log_timestamp,
         max_log_size: logfile_max_size * 1_024 * 1_024,
@@ -627,7 +680,11 @@ fn run<E: EthSpec>(
 
     let path = tracing_log_path.clone().unwrap();
 
-    logging::create_tracing_layer(path);
+    {
+        // The OTLP exporter runs on the Tokio runtime.
+        let _runtime_guard = environment.runtime().enter();
+        logging::create_tracing_layer(path, logger_config.otlp_endpoint.clone());
+    }
 
     // Allow Prometheus to export the time at which the process was started.
     metrics::expose_process_start_time(&log);
@@ -653,15 +710,7 @@ fn run<E: EthSpec>(
     // Creating a command which can run both might be useful future works.
 
     // Print an indication of which network is currently in use.
-    let optional_testnet = clap_utils::parse_optional::<String>(matches, "network")?;
-    let optional_testnet_dir = clap_utils::parse_optional::<PathBuf>(matches, "testnet-dir")?;
-
-    let network_name = match (optional_testnet, optional_testnet_dir) {
-        (Some(testnet), None) => testnet,
-        (None, Some(testnet_dir)) => format!("custom ({})", testnet_dir.display()),
-        (None, None) => DEFAULT_HARDCODED_NETWORK.to_string(),
-        (Some(_), Some(_)) => panic!("CLI prevents both --network and --testnet-dir"),
-    };
+    let network_name = get_network_name(matches)?;
 
     if let Some(sub_matches) = matches.subcommand_matches(account_manager::CMD) {
         eprintln!("Running account manager for {} network", network_name);
@@ -701,9 +750,15 @@ fn run<E: EthSpec>(
         Some(("beacon_node", matches)) => {
             let context = environment.core_context();
             let log = context.log().clone();
-            let executor = context.executor.clone();
             let mut config = beacon_node::get_config::<E>(matches, &context)?;
             config.logger_config = logger_config;
+
+            let additional_networks =
+                get_additional_networks::<E>(cli, matches, &context, &config)?;
+            if !additional_networks.is_empty() {
+                config.http_metrics.network = Some(network_name.clone());
+            }
+
             // Dump configs if `dump-config` or `dump-chain-config` flags are set
             clap_utils::check_dump_configs::<_, E>(matches, &config, &context.eth2_config.spec)?;
 
@@ -713,19 +768,16 @@ fn run<E: EthSpec>(
                 return Ok(());
             }
 
-            executor.clone().spawn(
-                async move {
-                    if let Err(e) = ProductionBeaconNode::new(context.clone(), config).await {
-                        crit!(log, "Failed to start beacon node"; "reason" => e);
-                        // Ignore the error since it always occurs during normal operation when
-                        // shutting down.
-                        let _ = executor
-                            .shutdown_sender()
-                            .try_send(ShutdownReason::Failure("Failed to start beacon node"));
-                    }
-                },
-                "beacon_node",
-            );
+            spawn_beacon_node(context, config);
+            for (name, context, config) in additional_networks {
+                info!(
+                    log,
+                    "Starting beacon node for additional network";
+                    "name" => name,
+                    "datadir" => ?config.data_dir(),
+                );
+                spawn_beacon_node(context, config);
+            }
         }
         Some(("validator_client", matches)) => {
             let context = environment.core_context();
@@ -771,6 +823,9 @@ fn run<E: EthSpec>(
 
     environment.fire_signal();
 
+    // Export any remaining spans whilst the runtime is still running.
+    logging::shutdown_tracing();
+
     // Shutdown the environment once all tasks have completed.
     environment.shutdown_on_idle();
 
@@ -779,3 +834,158 @@ fn run<E: EthSpec>(
         ShutdownReason::Failure(msg) => Err(msg.to_string()),
     }
 }
+
+/// Returns a human-readable name for the network selected by `matches`.
+fn get_network_name(matches: &ArgMatches) -> Result<String, String> {
+    let optional_testnet = clap_utils::parse_optional::<String>(matches, "network")?;
+    let optional_testnet_dir = clap_utils::parse_optional::<PathBuf>(matches, "testnet-dir")?;
+
+    Ok(match (optional_testnet, optional_testnet_dir) {
+        (Some(testnet), None) => testnet,
+        (None, Some(testnet_dir)) => format!("custom ({})", testnet_dir.display()),
+        (None, None) => DEFAULT_HARDCODED_NETWORK.to_string(),
+        (Some(_), Some(_)) => panic!("CLI prevents both --network and --testnet-dir"),
+    })
+}
+
+/// Start a beacon node, shutting down the process if it fails to start.
+fn spawn_beacon_node<E: EthSpec>(context: RuntimeContext<E>, config: ClientConfig) {
+    let log = context.log().clone();
+    let executor = context.executor.clone();
+    executor.clone().spawn(
+        async move {
+            if let Err(e) = ProductionBeaconNode::new(context.clone(), config).await {
+                crit!(log, "Failed to start beacon node"; "reason" => e);
+                // Ignore the error since it always occurs during normal operation when
+                // shutting down.
+                let _ = executor
+                    .shutdown_sender()
+                    .try_send(ShutdownReason::Failure("Failed to start beacon node"));
+            }
+        },
+        "beacon_node",
+    );
+}
+
+/// Load the configs of the beacon nodes for each `--additional-network`, which share the runtime
+/// of the beacon node configured by `bn_matches`.
+///
+/// Each file contains the Lighthouse arguments for one network, separated by whitespace. Text
+/// following a `#` on a line is ignored. Every network must use its own data directory and ports,
+/// and its metrics are served by the primary network with its name as the `network` label.
+fn get_additional_networks<E: EthSpec>(
+    cli: &Command,
+    bn_matches: &ArgMatches,
+    context: &RuntimeContext<E>,
+    config: &ClientConfig,
+) -> Result<Vec<(String, RuntimeContext<E>, ClientConfig)>, String> {
+    let Some(paths) = bn_matches.get_many::<String>("additional-network") else {
+        return Ok(vec![]);
+    };
+
+    let mut data_dirs = vec![config.data_dir().clone()];
+    let mut ports = listen_ports(config);
+    let mut additional_networks = vec![];
+    for path in paths {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read additional network {path}: {e}"))?;
+        let args = contents.lines().flat_map(|line| {
+            line.split('#')
+                .next()
+                .unwrap_or_default()
+                .split_whitespace()
+        });
+        let matches = cli
+            .clone()
+            .try_get_matches_from(std::iter::once("lighthouse").chain(args))
+            .map_err(|e| format!("Invalid additional network {path}: {e}"))?;
+        let network_bn_matches = matches
+            .subcommand_matches("beacon_node")
+            .ok_or_else(|| format!("Additional network {path} must run the beacon_node"))?;
+        if network_bn_matches.contains_id("additional-network") {
+            return Err(format!(
+                "Additional network {path} must not set --additional-network"
+            ));
+        }
+
+        let eth2_network_config = get_eth2_network_config(&matches)?;
+        let eth_spec_id = eth2_network_config.eth_spec_id()?;
+        if eth_spec_id != E::spec_name() {
+            return Err(format!(
+                "Additional network {path} uses the {eth_spec_id:?} preset, but this network \
+                 uses {:?}",
+                E::spec_name()
+            ));
+        }
+
+        let name = get_network_name(&matches)?;
+        let mut network_context = context.service_context(name.clone());
+        network_context.eth2_config.spec = eth2_network_config.chain_spec::<E>()?;
+        network_context.eth2_network_config = Some(Arc::new(eth2_network_config));
+
+        let mut network_config =
+            beacon_node::get_config::<E>(network_bn_matches, &network_context)?;
+        network_config.logger_config = config.logger_config.clone();
+        if network_config.http_metrics.enabled {
+            return Err(format!(
+                "Additional network {path} must not enable --metrics, its metrics are served by \
+                 the primary network"
+            ));
+        }
+        if data_dirs.contains(network_config.data_dir()) {
+            return Err(format!(
+                "Additional network {path} must use a separate --datadir"
+            ));
+        }
+        data_dirs.push(network_config.data_dir().clone());
+        for port in listen_ports(&network_config) {
+            if ports.contains(&port) {
+                let (protocol, port) = port;
+                return Err(format!(
+                    "Additional network {path} uses {protocol} port {port}, which is already \
+                     used by another network"
+                ));
+            }
+            ports.push(port);
+        }
+        network_config.http_metrics.network = Some(name.clone());
+
+        additional_networks.push((name, network_context, network_config));
+    }
+    Ok(additional_networks)
+}
+
+/// Returns the `(protocol, port)` pairs that the beacon node configured by `config` listens on.
+///
+/// Ports set to zero are omitted, since they are chosen by the OS.
+fn listen_ports(config: &ClientConfig) -> Vec<(&'static str, u16)> {
+    let listen_addrs = config.network.listen_addrs();
+    let mut ports = vec![];
+    for (tcp_port, disc_port, quic_port) in listen_addrs
+        .v4()
+        .map(|addr| (addr.tcp_port, addr.disc_port, addr.quic_port))
+        .into_iter()
+        .chain(
+            listen_addrs
+                .v6()
+                .map(|addr| (addr.tcp_port, addr.disc_port, addr.quic_port)),
+        )
+    {
+        ports.push(("TCP", tcp_port));
+        ports.push(("UDP", disc_port));
+        if !config.network.disable_quic_support {
+            ports.push(("UDP", quic_port));
+        }
+    }
+    if config.http_api.enabled {
+        ports.push(("TCP", config.http_api.listen_port));
+    }
+    if config.http_metrics.enabled {
+        ports.push(("TCP", config.http_metrics.listen_port));
+    }
+
+    ports.retain(|(_, port)| *port != 0);
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
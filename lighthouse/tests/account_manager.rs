@@ -494,6 +494,7 @@ fn validator_import_launchpad() {
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        builder_beacon_nodes: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
@@ -618,6 +619,7 @@ fn validator_import_launchpad_no_password_then_add_password() {
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        builder_beacon_nodes: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
@@ -646,6 +648,7 @@ fn validator_import_launchpad_no_password_then_add_password() {
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        builder_beacon_nodes: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path: dst_keystore_dir.join(KEYSTORE_NAME),
@@ -750,6 +753,7 @@ fn validator_import_launchpad_password_file() {
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        builder_beacon_nodes: None,
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
             voting_keystore_password_path: None,
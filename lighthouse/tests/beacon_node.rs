@@ -5,11 +5,13 @@ use beacon_node::beacon_chain::chain_config::{
     DisallowedReOrgOffsets, DEFAULT_RE_ORG_CUTOFF_DENOMINATOR, DEFAULT_RE_ORG_HEAD_THRESHOLD,
     DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
 };
-use beacon_node::beacon_chain::graffiti_calculator::GraffitiOrigin;
+use beacon_node::beacon_chain::graffiti_calculator::{GraffitiOrigin, GraffitiWatermark};
+use beacon_node::beacon_chain::store::compression::CompressionAlgorithm;
 use beacon_processor::BeaconProcessorConfig;
 use eth1::Eth1Endpoint;
 use lighthouse_network::PeerId;
 use lighthouse_version;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
@@ -21,7 +23,9 @@ use std::string::ToString;
 use std::time::Duration;
 use tempfile::TempDir;
 use types::non_zero_usize::new_non_zero_usize;
-use types::{Address, Checkpoint, Epoch, ExecutionBlockHash, Hash256, MainnetEthSpec};
+use types::{
+    Address, Checkpoint, Epoch, ExecutionBlockHash, Hash256, MainnetEthSpec, PublicKeyBytes, Slot,
+};
 use unused_port::{unused_tcp4_port, unused_tcp6_port, unused_udp4_port, unused_udp6_port};
 
 const DEFAULT_ETH1_ENDPOINT: &str = "http://localhost:8545/";
@@ -136,6 +140,21 @@ fn allow_insecure_genesis_sync_enabled() {
         });
 }
 
+#[test]
+fn ignore_weak_subjectivity_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.ignore_weak_subjectivity));
+}
+
+#[test]
+fn ignore_weak_subjectivity_flag() {
+    CommandLineTest::new()
+        .flag("ignore-weak-subjectivity", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.ignore_weak_subjectivity));
+}
+
 #[test]
 fn wss_checkpoint_flag() {
     let state = Some(Checkpoint {
@@ -350,6 +369,136 @@ fn default_graffiti() {
         });
 }
 
+#[test]
+fn graffiti_watermark_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.graffiti_watermark, GraffitiWatermark::Disabled)
+        });
+}
+
+#[test]
+fn graffiti_watermark_flag() {
+    CommandLineTest::new()
+        .flag("graffiti", Some("nice-graffiti"))
+        .flag("graffiti-watermark", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.graffiti_watermark, GraffitiWatermark::Append)
+        });
+}
+
+#[test]
+fn graffiti_watermark_override_flag() {
+    CommandLineTest::new()
+        .flag("graffiti-watermark-override", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.graffiti_watermark, GraffitiWatermark::Override)
+        });
+}
+
+#[test]
+fn peer_filter_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("peer-filter.txt");
+    CommandLineTest::new()
+        .flag("peer-filter-file", path.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.peer_filter_file, Some(path.clone())));
+}
+
+fn additional_network_file(dir: &TempDir, name: &str, bn_args: &str) -> PathBuf {
+    let path = dir.path().join(format!("{name}.args"));
+    let args = format!(
+        "# Holesky\n--network holesky --datadir {}\nbeacon_node {bn_args}\n",
+        dir.path().join(name).display()
+    );
+    File::create(&path)
+        .and_then(|mut file| file.write_all(args.as_bytes()))
+        .expect("Unable to write additional network file");
+    path
+}
+
+#[test]
+fn additional_network_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = additional_network_file(&dir, "holesky", "--port 9100 --http-port 5153");
+    CommandLineTest::new()
+        .flag("additional-network", path.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.http_metrics.network, Some("mainnet".to_string()));
+        });
+}
+
+#[test]
+fn additional_network_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_metrics.network, None));
+}
+
+#[test]
+#[should_panic]
+fn additional_network_with_metrics_should_panic() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = additional_network_file(&dir, "holesky", "--metrics");
+    CommandLineTest::new()
+        .flag("additional-network", path.as_os_str().to_str())
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn additional_network_with_same_datadir_should_panic() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = additional_network_file(&dir, "holesky", "--port 9100");
+    CommandLineTest::new()
+        .flag("additional-network", path.as_os_str().to_str())
+        .flag("additional-network", path.as_os_str().to_str())
+        .run_with_zero_port();
+}
+
+#[test]
+fn additional_networks_with_separate_ports() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let first = additional_network_file(&dir, "first", "--port 9100 --http-port 5153");
+    let second = additional_network_file(&dir, "second", "--port 9200 --http-port 5253");
+    CommandLineTest::new()
+        .flag("additional-network", first.as_os_str().to_str())
+        .flag("additional-network", second.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.http_metrics.network, Some("mainnet".to_string()));
+        });
+}
+
+#[test]
+#[should_panic]
+fn additional_networks_with_same_port_should_panic() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let first = additional_network_file(&dir, "first", "--port 9100 --quic-port 9101");
+    let second = additional_network_file(&dir, "second", "--port 9200 --quic-port 9100");
+    CommandLineTest::new()
+        .flag("additional-network", first.as_os_str().to_str())
+        .flag("additional-network", second.as_os_str().to_str())
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn additional_network_with_same_port_as_primary_should_panic() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = additional_network_file(&dir, "holesky", "--port 9100");
+    CommandLineTest::new()
+        .flag("additional-network", path.as_os_str().to_str())
+        .flag("port", Some("9100"))
+        .set_allow_insecure_genesis_sync()
+        .run();
+}
+
 #[test]
 fn trusted_peers_flag() {
     let peers = vec![PeerId::random(), PeerId::random()];
@@ -522,6 +671,23 @@ fn execution_timeout_multiplier_flag() {
         });
 }
 #[test]
+fn engine_api_trace_dir_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let trace_dir = dir.path().join("engine-trace");
+    CommandLineTest::new()
+        .flag("execution-endpoint", Some("http://meow.cats"))
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag("engine-api-trace-dir", trace_dir.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(config.engine_api_trace_dir, Some(trace_dir.clone()));
+        });
+}
+#[test]
 fn bellatrix_execution_endpoints_flag() {
     run_bellatrix_execution_endpoints_flag_test("execution-endpoints")
 }
@@ -618,6 +784,93 @@ fn payload_builder_flags() {
     run_payload_builder_flag_test("payload-builders", "http://meow.cats,http://woof.dogs");
 }
 
+#[test]
+fn degraded_mode_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.chain.enable_degraded_mode);
+            assert_eq!(config.chain.degraded_epochs_since_finalization, 8);
+            assert_eq!(config.chain.degraded_missed_slots_per_epoch, 16);
+            assert_eq!(config.chain.degraded_min_peers, 5);
+        });
+}
+
+#[test]
+fn degraded_mode_flags() {
+    CommandLineTest::new()
+        .flag("enable-degraded-mode", None)
+        .flag("degraded-epochs-since-finalization", Some("4"))
+        .flag("degraded-missed-slots-per-epoch", Some("10"))
+        .flag("degraded-min-peers", Some("2"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.chain.enable_degraded_mode);
+            assert_eq!(config.chain.degraded_epochs_since_finalization, 4);
+            assert_eq!(config.chain.degraded_missed_slots_per_epoch, 10);
+            assert_eq!(config.chain.degraded_min_peers, 2);
+        });
+}
+
+#[test]
+fn emergency_pruning_threshold_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.emergency_pruning_threshold, None));
+}
+
+#[test]
+fn emergency_pruning_threshold_flag() {
+    CommandLineTest::new()
+        .flag("emergency-pruning-threshold", Some("200"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.emergency_pruning_threshold,
+                Some(200 * 1024 * 1024 * 1024)
+            )
+        });
+}
+
+#[test]
+#[should_panic]
+fn emergency_pruning_threshold_invalid() {
+    CommandLineTest::new()
+        .flag("emergency-pruning-threshold", Some("lots"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn emergency_pruning_threshold_overflow() {
+    CommandLineTest::new()
+        .flag(
+            "emergency-pruning-threshold",
+            Some(&(u64::MAX / 1024).to_string()),
+        )
+        .run_with_zero_port();
+}
+
+#[test]
+fn record_gossip_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.gossip_record_path, None));
+}
+
+#[test]
+fn record_gossip_flag() {
+    CommandLineTest::new()
+        .flag("record-gossip", Some("/tmp/gossip.ssz"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.gossip_record_path,
+                Some(PathBuf::from("/tmp/gossip.ssz"))
+            )
+        });
+}
+
 #[test]
 fn builder_fallback_flags() {
     run_payload_builder_flag_test_with_config(
@@ -678,6 +931,77 @@ fn builder_get_header_timeout() {
     );
 }
 
+#[test]
+fn builder_require_fee_recipient() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        None,
+        None,
+        |config| {
+            assert!(
+                !config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_require_fee_recipient
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-require-fee-recipient"),
+        None,
+        |config| {
+            assert!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_require_fee_recipient
+            );
+        },
+    );
+}
+
+#[test]
+fn builder_required_transactions() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        None,
+        None,
+        |config| {
+            assert!(config
+                .execution_layer
+                .as_ref()
+                .unwrap()
+                .builder_required_transactions
+                .is_empty());
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-required-transactions"),
+        Some(
+            "0x0101010101010101010101010101010101010101010101010101010101010101,\
+            0x0202020202020202020202020202020202020202020202020202020202020202",
+        ),
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_required_transactions,
+                vec![Hash256::repeat_byte(1), Hash256::repeat_byte(2)]
+            );
+        },
+    );
+}
+
 #[test]
 fn builder_user_agent() {
     run_payload_builder_flag_test_with_config(
@@ -1647,6 +1971,23 @@ fn http_allow_origin_all_flag() {
         .with_config(|config| assert_eq!(config.http_api.allow_origin, Some("*".to_string())));
 }
 
+#[test]
+fn http_allow_origin_multiple_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag(
+            "http-allow-origin",
+            Some("http://127.0.0.99,https://siren.example.com"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_api.allow_origin,
+                Some("http://127.0.0.99,https://siren.example.com".to_string())
+            );
+        });
+}
+
 #[test]
 fn http_allow_sync_stalled_flag() {
     CommandLineTest::new()
@@ -1675,6 +2016,49 @@ fn http_enable_beacon_processor() {
         .with_config(|config| assert_eq!(config.http_api.enable_beacon_processor, false));
 }
 #[test]
+fn http_enable_log_level_changes() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.enable_log_level_changes, false));
+
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-enable-log-level-changes", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.enable_log_level_changes, true));
+}
+#[test]
+fn http_enable_validator_monitor_registration() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.http_api.enable_validator_monitor_registration, false)
+        });
+
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-enable-validator-monitor-registration", None)
+        .flag("http-api-token-file", Some("/tmp/api-token"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.http_api.enable_validator_monitor_registration, true);
+            assert_eq!(
+                config.http_api.api_token_path,
+                Some(PathBuf::from("/tmp/api-token"))
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn http_enable_validator_monitor_registration_requires_api_token() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-enable-validator-monitor-registration", None)
+        .run_with_zero_port();
+}
+#[test]
 fn http_tls_flags() {
     CommandLineTest::new()
         .flag("http", None)
@@ -1693,6 +2077,24 @@ fn http_tls_flags() {
         });
 }
 
+#[test]
+fn http_tls_flags_without_enable_tls() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-tls-cert", Some("tests/tls/cert.pem"))
+        .flag("http-tls-key", Some("tests/tls/key.rsa"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let tls_config = config
+                .http_api
+                .tls_config
+                .as_ref()
+                .expect("tls_config was empty.");
+            assert_eq!(tls_config.cert, Path::new("tests/tls/cert.pem"));
+            assert_eq!(tls_config.key, Path::new("tests/tls/key.rsa"));
+        });
+}
+
 #[test]
 fn http_spec_fork_default() {
     CommandLineTest::new()
@@ -1837,6 +2239,47 @@ fn validator_monitor_metrics_threshold_custom() {
             assert_eq!(config.validator_monitor.individual_tracking_threshold, 42)
         });
 }
+#[test]
+fn validator_monitor_alert_webhook_flag() {
+    CommandLineTest::new()
+        .flag(
+            "validator-monitor-alert-webhook",
+            Some("http://localhost:9000/alerts"),
+        )
+        .flag(
+            "validator-monitor-alert-effectiveness-threshold",
+            Some("0.8"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config
+                    .validator_monitor
+                    .alert_webhook
+                    .as_ref()
+                    .map(|url| url.full.to_string()),
+                Some("http://localhost:9000/alerts".to_string())
+            );
+            assert_eq!(
+                config.validator_monitor.alert_effectiveness_threshold,
+                Some(0.8)
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn validator_monitor_alert_effectiveness_threshold_out_of_range() {
+    CommandLineTest::new()
+        .flag(
+            "validator-monitor-alert-webhook",
+            Some("http://localhost:9000/alerts"),
+        )
+        .flag(
+            "validator-monitor-alert-effectiveness-threshold",
+            Some("1.5"),
+        )
+        .run_with_zero_port();
+}
 
 // Tests for Store flags.
 #[test]
@@ -1953,6 +2396,19 @@ fn prune_payloads_on_startup_false() {
         .with_config(|config| assert!(!config.store.prune_payloads));
 }
 #[test]
+fn retain_abandoned_blocks_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.store.retain_abandoned_blocks));
+}
+#[test]
+fn retain_abandoned_blocks_flag() {
+    CommandLineTest::new()
+        .flag("retain-abandoned-blocks", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.retain_abandoned_blocks));
+}
+#[test]
 fn prune_blobs_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -1992,6 +2448,114 @@ fn blob_prune_margin_epochs_on_startup_ten() {
         .with_config(|config| assert!(config.store.blob_prune_margin_epochs == 10));
 }
 #[test]
+fn prune_history_before_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.prune_history_before, None));
+}
+#[test]
+fn prune_history_before_flag() {
+    CommandLineTest::new()
+        .flag("prune-history-before", Some("8192"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.prune_history_before, Some(Slot::new(8192))));
+}
+#[test]
+fn hot_db_compression_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.hot_db_compression, CompressionAlgorithm::None);
+            assert_eq!(config.store.hot_db_compression_level, 1);
+        });
+}
+#[test]
+fn hot_db_compression_flags() {
+    CommandLineTest::new()
+        .flag("hot-db-compression", Some("zstd"))
+        .flag("hot-db-compression-level", Some("3"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.hot_db_compression, CompressionAlgorithm::Zstd);
+            assert_eq!(config.store.hot_db_compression_level, 3);
+        });
+}
+#[test]
+fn blobs_db_compression_flag() {
+    CommandLineTest::new()
+        .flag("blobs-db-compression", Some("snappy"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.blobs_db_compression,
+                CompressionAlgorithm::Snappy
+            );
+            assert!(!config.store.effective_blobs_db_tuning().compression);
+        });
+}
+#[test]
+fn db_tuning_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.blobs_db_compression,
+                CompressionAlgorithm::None
+            );
+            assert_eq!(
+                config.store.hot_db_tuning.write_buffer_size,
+                4 * 1024 * 1024
+            );
+            assert_eq!(
+                config.store.cold_db_tuning.write_buffer_size,
+                64 * 1024 * 1024
+            );
+            assert!(config.store.effective_blobs_db_tuning().compression);
+        });
+}
+#[test]
+fn db_write_buffer_size_flags() {
+    CommandLineTest::new()
+        .flag("hot-db-write-buffer-size", Some("8"))
+        .flag("freezer-db-write-buffer-size", Some("128"))
+        .flag("blobs-db-write-buffer-size", Some("32"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.hot_db_tuning.write_buffer_size,
+                8 * 1024 * 1024
+            );
+            assert_eq!(
+                config.store.cold_db_tuning.write_buffer_size,
+                128 * 1024 * 1024
+            );
+            assert_eq!(
+                config.store.blobs_db_tuning.write_buffer_size,
+                32 * 1024 * 1024
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn db_write_buffer_size_zero() {
+    CommandLineTest::new()
+        .flag("hot-db-write-buffer-size", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
+fn db_checksums_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.store.db_checksums));
+}
+#[test]
+fn db_checksums_flag() {
+    CommandLineTest::new()
+        .flag("db-checksums", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.db_checksums));
+}
+#[test]
 fn reconstruct_historic_states_flag() {
     CommandLineTest::new()
         .flag("reconstruct-historic-states", None)
@@ -2005,6 +2569,20 @@ fn no_reconstruct_historic_states_flag() {
         .with_config(|config| assert!(!config.chain.reconstruct_historic_states));
 }
 #[test]
+fn reconstruct_historic_states_workers_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.reconstruct_workers.get(), 4));
+}
+#[test]
+fn reconstruct_historic_states_workers_flag() {
+    CommandLineTest::new()
+        .flag("reconstruct-historic-states", None)
+        .flag("reconstruct-historic-states-workers", Some("8"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.reconstruct_workers.get(), 8));
+}
+#[test]
 fn epochs_per_migration_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -2022,6 +2600,24 @@ fn epochs_per_migration_override() {
         .run_with_zero_port()
         .with_config(|config| assert_eq!(config.chain.epochs_per_migration, 128));
 }
+#[test]
+fn epochs_per_state_persist_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.epochs_per_state_persist,
+                beacon_node::beacon_chain::store::config::DEFAULT_EPOCHS_PER_STATE_PERSIST
+            )
+        });
+}
+#[test]
+fn epochs_per_state_persist_override() {
+    CommandLineTest::new()
+        .flag("epochs-per-state-persist", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.epochs_per_state_persist, 4));
+}
 
 // Tests for Slasher flags.
 // Using `--slasher-max-db-size` to work around https://github.com/sigp/lighthouse/issues/2342
@@ -2401,6 +2997,24 @@ fn monitoring_endpoint() {
             let api_conf = config.monitoring_api.as_ref().unwrap();
             assert_eq!(api_conf.monitoring_endpoint.as_str(), "http://example:8000");
             assert_eq!(api_conf.update_period_secs, Some(30));
+            assert_eq!(api_conf.bearer_token_path, None);
+        });
+}
+#[test]
+fn monitoring_endpoint_token_file() {
+    CommandLineTest::new()
+        .flag("monitoring-endpoint", Some("http://example:8000"))
+        .flag(
+            "monitoring-endpoint-token-file",
+            Some("/tmp/monitoring-token"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let api_conf = config.monitoring_api.as_ref().unwrap();
+            assert_eq!(
+                api_conf.bearer_token_path,
+                Some(PathBuf::from("/tmp/monitoring-token"))
+            );
         });
 }
 
@@ -2475,6 +3089,24 @@ fn logfile_format_flag() {
         });
 }
 #[test]
+fn otlp_endpoint_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.logger_config.otlp_endpoint, None));
+}
+#[test]
+fn otlp_endpoint_flag() {
+    CommandLineTest::new()
+        .flag("otlp-endpoint", Some("http://localhost:4317"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.logger_config.otlp_endpoint,
+                Some("http://localhost:4317".to_string())
+            )
+        });
+}
+#[test]
 fn sync_eth1_chain_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -2508,6 +3140,21 @@ fn sync_eth1_chain_disable_deposit_contract_sync_flag() {
         .with_config(|config| assert_eq!(config.sync_eth1_chain, false));
 }
 
+#[test]
+fn deposit_snapshot_rpc_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.network.enable_deposit_snapshot_rpc));
+}
+
+#[test]
+fn deposit_snapshot_rpc_enabled() {
+    CommandLineTest::new()
+        .flag("deposit-snapshot-rpc", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.network.enable_deposit_snapshot_rpc));
+}
+
 #[test]
 fn light_client_server_default() {
     CommandLineTest::new()
@@ -2624,7 +3271,16 @@ fn beacon_processor() {
         .flag("beacon-processor-reprocess-queue-len", Some("3"))
         .flag("beacon-processor-attestation-batch-size", Some("4"))
         .flag("beacon-processor-aggregate-batch-size", Some("5"))
+        .flag("beacon-processor-sync-signature-batch-size", Some("9"))
         .flag("disable-backfill-rate-limiting", None)
+        .flag(
+            "beacon-processor-queue-lengths",
+            Some("gossip_block=6,gossip_sync_contribution=7"),
+        )
+        .flag("beacon-processor-high-priority-lane", None)
+        .flag("beacon-processor-load-shedding-threshold", Some("8"))
+        .flag("beacon-processor-gossip-latency-budget", Some("250"))
+        .flag("beacon-processor-gossip-overflow-workers", Some("10"))
         .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
@@ -2635,12 +3291,45 @@ fn beacon_processor() {
                     max_scheduled_work_queue_len: 3,
                     max_gossip_attestation_batch_size: 4,
                     max_gossip_aggregate_batch_size: 5,
-                    enable_backfill_rate_limiting: false
+                    max_gossip_sync_signature_batch_size: 9,
+                    enable_backfill_rate_limiting: false,
+                    queue_length_overrides: HashMap::from([
+                        ("gossip_block".to_string(), 6),
+                        ("gossip_sync_contribution".to_string(), 7),
+                    ]),
+                    enable_high_priority_lane: true,
+                    load_shedding_threshold: Some(8),
+                    gossip_latency_budget: Some(Duration::from_millis(250)),
+                    gossip_overflow_workers: 10,
                 }
             )
         });
 }
 
+#[test]
+#[should_panic]
+fn beacon_processor_invalid_queue_lengths() {
+    CommandLineTest::new()
+        .flag("beacon-processor-queue-lengths", Some("gossip_block"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn beacon_processor_unknown_queue() {
+    CommandLineTest::new()
+        .flag("beacon-processor-queue-lengths", Some("gossip_blocks=6"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn beacon_processor_zero_queue_length() {
+    CommandLineTest::new()
+        .flag("beacon-processor-queue-lengths", Some("gossip_block=0"))
+        .run_with_zero_port();
+}
+
 #[test]
 #[should_panic]
 fn beacon_processor_zero_workers() {
@@ -2665,6 +3354,38 @@ fn http_sse_capacity_multiplier_override() {
         .with_config(|config| assert_eq!(config.http_api.sse_capacity_multiplier, 10));
 }
 
+#[test]
+fn http_historic_cache_size_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.historic_cache_size, 0));
+}
+
+#[test]
+fn http_historic_cache_size_override() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-historic-cache-size", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.historic_cache_size, 4));
+}
+
+#[test]
+fn http_state_replay_budget_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.state_replay_budget, None));
+}
+
+#[test]
+fn http_state_replay_budget_override() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-state-replay-budget", Some("256"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.state_replay_budget, Some(256)));
+}
+
 #[test]
 fn http_duplicate_block_status_default() {
     CommandLineTest::new()
@@ -2692,6 +3413,7 @@ fn genesis_state_url_default() {
         .with_config(|config| {
             assert_eq!(config.genesis_state_url, None);
             assert_eq!(config.genesis_state_url_timeout, Duration::from_secs(180));
+            assert_eq!(config.genesis_state_checksum, None);
         });
 }
 
@@ -2709,3 +3431,69 @@ fn genesis_state_url_value() {
             assert_eq!(config.genesis_state_url_timeout, Duration::from_secs(42));
         });
 }
+
+#[test]
+fn network_config_update_url_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network_config_update_url, None);
+            assert_eq!(
+                config.network_config_update_timeout,
+                Duration::from_secs(10)
+            );
+        });
+}
+
+#[test]
+fn network_config_update_url_value() {
+    CommandLineTest::new()
+        .flag(
+            "network-config-update-url",
+            Some("http://example.com/update.yaml"),
+        )
+        .flag("network-config-update-timeout", Some("42"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network_config_update_url.as_deref(),
+                Some("http://example.com/update.yaml")
+            );
+            assert_eq!(
+                config.network_config_update_timeout,
+                Duration::from_secs(42)
+            );
+        });
+}
+
+#[test]
+fn network_config_update_pubkey_value() {
+    let pubkey = "0xa1d1ad0714035353258038e964ae9675dc0252ee22cea896825c01458e1807bfad2f9969338798548d9858a571f7425c";
+    CommandLineTest::new()
+        .flag(
+            "network-config-update-url",
+            Some("http://example.com/update.yaml"),
+        )
+        .flag("network-config-update-pubkey", Some(pubkey))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network_config_update_pubkey,
+                Some(PublicKeyBytes::from_str(pubkey).unwrap())
+            );
+        });
+}
+
+#[test]
+fn genesis_state_checksum_value() {
+    CommandLineTest::new()
+        .flag("genesis-state-url", Some("http://genesis.com"))
+        .flag(
+            "genesis-state-checksum",
+            Some("0x0101010101010101010101010101010101010101010101010101010101010101"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.genesis_state_checksum, Some(Hash256::repeat_byte(1)));
+        });
+}
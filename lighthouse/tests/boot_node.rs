@@ -7,7 +7,7 @@ use lighthouse_network::discovery::ENR_FILENAME;
 use lighthouse_network::Enr;
 use std::fs::File;
 use std::io::Write;
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str::FromStr;
@@ -163,6 +163,37 @@ fn enable_enr_auto_update_flag() {
         });
 }
 
+#[test]
+fn http_flag() {
+    CommandLineTest::new()
+        .run_with_ip()
+        .with_config(|config| assert_eq!(config.http_address, None));
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_ip()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_address,
+                Some(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 5056))
+            )
+        });
+}
+
+#[test]
+fn http_address_and_port_flags() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-address", Some("0.0.0.0"))
+        .flag("http-port", Some("9999"))
+        .run_with_ip()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_address,
+                Some(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 9999))
+            )
+        });
+}
+
 #[test]
 fn network_dir_flag() {
     // Save enr to temp dir.
@@ -2,6 +2,7 @@ use validator_client::{config::DEFAULT_WEB3SIGNER_KEEP_ALIVE, ApiTopic, Config};
 
 use crate::exec::CommandLineTestExec;
 use bls::{Keypair, PublicKeyBytes};
+use eth2::types::BroadcastValidation;
 use sensitive_url::SensitiveUrl;
 use std::fs::File;
 use std::io::Write;
@@ -154,6 +155,27 @@ fn beacon_nodes_tls_certs_flag() {
         });
 }
 
+#[test]
+fn beacon_nodes_api_token_file_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.beacon_nodes_api_token_path, None));
+
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag(
+            "beacon-nodes-api-token-file",
+            dir.path().join("api-token.txt").to_str(),
+        )
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_nodes_api_token_path,
+                Some(dir.path().join("api-token.txt"))
+            )
+        });
+}
+
 // Tests for Graffiti flags.
 #[test]
 fn graffiti_flag() {
@@ -327,6 +349,53 @@ fn http_allow_keystore_export_present() {
         .with_config(|config| assert!(config.http_api.allow_keystore_export));
 }
 #[test]
+fn http_tls_flags() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-tls-cert", Some("tests/tls/cert.pem"))
+        .flag("http-tls-key", Some("tests/tls/key.rsa"))
+        .run()
+        .with_config(|config| {
+            let tls_config = config
+                .http_api
+                .tls_config
+                .as_ref()
+                .expect("tls_config was empty.");
+            assert_eq!(tls_config.cert, PathBuf::from("tests/tls/cert.pem"));
+            assert_eq!(tls_config.key, PathBuf::from("tests/tls/key.rsa"));
+        });
+}
+#[test]
+fn http_address_with_tls_flags() {
+    let addr = "127.0.0.99".parse::<IpAddr>().unwrap();
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-address", Some("127.0.0.99"))
+        .flag("http-tls-cert", Some("tests/tls/cert.pem"))
+        .flag("http-tls-key", Some("tests/tls/key.rsa"))
+        .run()
+        .with_config(|config| assert_eq!(config.http_api.listen_addr, addr));
+}
+#[test]
+fn http_token_grace_period_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.http_api.token_grace_period, Duration::from_secs(60))
+        });
+}
+#[test]
+fn http_token_grace_period_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-token-grace-period", Some("5"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.http_api.token_grace_period, Duration::from_secs(5))
+        });
+}
+#[test]
 fn http_store_keystore_passwords_in_secrets_dir_default() {
     CommandLineTest::new()
         .flag("http", None)
@@ -472,6 +541,24 @@ fn no_builder_boost_factor_flag() {
         .with_config(|config| assert_eq!(config.builder_boost_factor, None));
 }
 #[test]
+fn broadcast_validation_flag() {
+    CommandLineTest::new()
+        .flag("broadcast-validation", Some("consensus_and_equivocation"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.broadcast_validation,
+                Some(BroadcastValidation::ConsensusAndEquivocation)
+            )
+        });
+}
+#[test]
+fn no_broadcast_validation_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.broadcast_validation, None));
+}
+#[test]
 fn prefer_builder_proposals_flag() {
     CommandLineTest::new()
         .flag("prefer-builder-proposals", None)
@@ -500,6 +587,31 @@ fn builder_registration_timestamp_override_flag() {
         });
 }
 #[test]
+fn no_builder_registration_expiry_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(config.builder_registration_expiry.is_none()));
+}
+#[test]
+fn builder_registration_expiry_flag() {
+    CommandLineTest::new()
+        .flag("builder-registration-expiry", Some("3600"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.builder_registration_expiry,
+                Some(Duration::from_secs(3600))
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn builder_registration_expiry_zero_value() {
+    CommandLineTest::new()
+        .flag("builder-registration-expiry", Some("0"))
+        .run();
+}
+#[test]
 fn monitoring_endpoint() {
     CommandLineTest::new()
         .flag("monitoring-endpoint", Some("http://example:8000"))
@@ -509,6 +621,24 @@ fn monitoring_endpoint() {
             let api_conf = config.monitoring_api.as_ref().unwrap();
             assert_eq!(api_conf.monitoring_endpoint.as_str(), "http://example:8000");
             assert_eq!(api_conf.update_period_secs, Some(30));
+            assert_eq!(api_conf.bearer_token_path, None);
+        });
+}
+#[test]
+fn monitoring_endpoint_token_file() {
+    CommandLineTest::new()
+        .flag("monitoring-endpoint", Some("http://example:8000"))
+        .flag(
+            "monitoring-endpoint-token-file",
+            Some("/tmp/monitoring-token"),
+        )
+        .run()
+        .with_config(|config| {
+            let api_conf = config.monitoring_api.as_ref().unwrap();
+            assert_eq!(
+                api_conf.bearer_token_path,
+                Some(PathBuf::from("/tmp/monitoring-token"))
+            );
         });
 }
 
@@ -644,6 +774,78 @@ fn validator_disable_web3_signer_slashing_protection() {
         });
 }
 
+#[test]
+fn slashing_protection_minify_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.slashing_protection_minify);
+    });
+}
+
+#[test]
+fn slashing_protection_minify_flag() {
+    CommandLineTest::new()
+        .flag("slashing-protection-minify", None)
+        .run()
+        .with_config(|config| {
+            assert!(config.slashing_protection_minify);
+        });
+}
+
+#[test]
+fn proposer_duties_from_events_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.proposer_duties_from_events);
+    });
+}
+
+#[test]
+fn proposer_duties_from_events_flag() {
+    CommandLineTest::new()
+        .flag("proposer-duties-from-events", None)
+        .run()
+        .with_config(|config| {
+            assert!(config.proposer_duties_from_events);
+        });
+}
+
+#[test]
+fn precompute_selection_proofs_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.precompute_selection_proofs);
+    });
+}
+
+#[test]
+fn precompute_selection_proofs_flag() {
+    CommandLineTest::new()
+        .flag("precompute-selection-proofs", None)
+        .run()
+        .with_config(|config| {
+            assert!(config.precompute_selection_proofs);
+        });
+}
+
+#[test]
+fn chaos_config_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(config.chaos_config.is_none());
+    });
+}
+
+#[test]
+fn chaos_config_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("chaos.toml");
+    std::fs::write(&path, "[attestation]\ndrop_probability = 0.5\n")
+        .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("chaos-config", path.to_str())
+        .run()
+        .with_config(|config| {
+            assert!(config.chaos_config.is_some());
+        });
+}
+
 #[test]
 fn validator_web3_signer_keep_alive_default() {
     CommandLineTest::new().run().with_config(|config| {
@@ -469,8 +469,9 @@ impl<E: EthSpec> Operation<E> for ExecutionLayerWithdrawalRequest {
 }
 
 impl<E: EthSpec> Operation<E> for DepositRequest {
+    // TODO(electra): rename to `deposit_request` when the EF tests are updated.
     fn handler_name() -> String {
-        "deposit_request".into()
+        "deposit_receipt".into()
     }
 
     fn is_enabled_for_fork(fork_name: ForkName) -> bool {
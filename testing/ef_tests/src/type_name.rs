@@ -65,7 +65,8 @@ type_name_generic!(ContributionAndProof);
 type_name!(Deposit);
 type_name!(DepositData);
 type_name!(DepositMessage);
-type_name!(DepositRequest);
+// TODO(electra): rename to `DepositRequest` when the EF tests are updated.
+type_name!(DepositRequest, "DepositReceipt");
 type_name!(Eth1Data);
 type_name!(ExecutionLayerWithdrawalRequest);
 type_name_generic!(ExecutionPayload);
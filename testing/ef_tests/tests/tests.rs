@@ -101,9 +101,8 @@ fn operations_execution_layer_withdrawal_reqeusts() {
 #[test]
 #[cfg(not(feature = "fake_crypto"))]
 fn operations_deposit_requests() {
-    //TODO(electra): re-enable mainnet once they update the name for this
-    // OperationsHandler::<MinimalEthSpec, DepositRequest>::default().run();
-    // OperationsHandler::<MainnetEthSpec, DepositRequest>::default().run();
+    OperationsHandler::<MinimalEthSpec, DepositRequest>::default().run();
+    OperationsHandler::<MainnetEthSpec, DepositRequest>::default().run();
 }
 
 #[test]
@@ -239,7 +238,9 @@ macro_rules! ssz_static_test_no_run {
 
 #[cfg(feature = "fake_crypto")]
 mod ssz_static {
-    use ef_tests::{Handler, SszStaticHandler, SszStaticTHCHandler, SszStaticWithSpecHandler};
+    use ef_tests::{
+        Handler, SszStaticHandler, SszStaticTHCHandler, SszStaticWithSpecHandler, TypeName,
+    };
     use types::blob_sidecar::BlobIdentifier;
     use types::historical_summary::HistoricalSummary;
     use types::{
@@ -274,95 +275,145 @@ mod ssz_static {
     ssz_static_test!(validator, Validator);
     ssz_static_test!(voluntary_exit, VoluntaryExit);
 
-    #[test]
-    fn attestation() {
-        SszStaticHandler::<AttestationBase<MinimalEthSpec>, MinimalEthSpec>::pre_electra().run();
-        SszStaticHandler::<AttestationBase<MainnetEthSpec>, MainnetEthSpec>::pre_electra().run();
-        SszStaticHandler::<AttestationElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only()
-            .run();
-        SszStaticHandler::<AttestationElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only()
-            .run();
-    }
-
-    #[test]
-    fn attester_slashing() {
-        SszStaticHandler::<AttesterSlashingBase<MinimalEthSpec>, MinimalEthSpec>::pre_electra()
-            .run();
-        SszStaticHandler::<AttesterSlashingBase<MainnetEthSpec>, MainnetEthSpec>::pre_electra()
-            .run();
-        SszStaticHandler::<AttesterSlashingElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only()
-            .run();
-        SszStaticHandler::<AttesterSlashingElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only()
-            .run();
-    }
+    /// Run the SSZ static tests for every container which has a distinct type for `fork_name`.
+    ///
+    /// Most of these types have no internal indicator of which fork they are for, so they can't be
+    /// tested with a single handler across all forks. The `match` is intentionally exhaustive: adding
+    /// a new `ForkName` will fail to compile until that fork's containers are registered here.
+    ///
+    /// Every container is run, and a message is returned for each one that failed.
+    fn fork_variant_containers<E: EthSpec + TypeName>(fork_name: ForkName) -> Vec<String> {
+        let mut failures = vec![];
+
+        // The fork variants of a container share a handler name, so name the variant and spec in
+        // the failure message to make it clear which one failed.
+        macro_rules! run {
+            ($($typ:ident),+ $(,)?) => {
+                $(
+                    let result = std::panic::catch_unwind(|| {
+                        SszStaticHandler::<$typ<E>, E>::for_forks(vec![fork_name]).run()
+                    });
+                    if let Err(payload) = result {
+                        let message = payload
+                            .downcast_ref::<String>()
+                            .map(String::as_str)
+                            .or_else(|| payload.downcast_ref::<&str>().copied())
+                            .unwrap_or("unknown panic");
+                        failures.push(format!(
+                            "{} ({}, {}) failed: {}",
+                            stringify!($typ),
+                            fork_name,
+                            <E as TypeName>::name(),
+                            message
+                        ));
+                    }
+                )+
+            };
+        }
 
-    #[test]
-    fn indexed_attestation() {
-        SszStaticHandler::<IndexedAttestationBase<MinimalEthSpec>, MinimalEthSpec>::pre_electra()
-            .run();
-        SszStaticHandler::<IndexedAttestationBase<MainnetEthSpec>, MainnetEthSpec>::pre_electra()
-            .run();
-        SszStaticHandler::<IndexedAttestationElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only()
-            .run();
-        SszStaticHandler::<IndexedAttestationElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only()
-            .run();
-    }
+        match fork_name {
+            ForkName::Base => run!(
+                AttestationBase,
+                AttesterSlashingBase,
+                IndexedAttestationBase,
+                AggregateAndProofBase,
+                SignedAggregateAndProofBase,
+                BeaconBlockBodyBase,
+            ),
+            ForkName::Altair => run!(
+                AttestationBase,
+                AttesterSlashingBase,
+                IndexedAttestationBase,
+                AggregateAndProofBase,
+                SignedAggregateAndProofBase,
+                BeaconBlockBodyAltair,
+                LightClientBootstrapAltair,
+                LightClientHeaderAltair,
+                LightClientOptimisticUpdateAltair,
+                LightClientFinalityUpdateAltair,
+                LightClientUpdateAltair,
+            ),
+            // The light client containers are unchanged in Bellatrix.
+            ForkName::Bellatrix => run!(
+                AttestationBase,
+                AttesterSlashingBase,
+                IndexedAttestationBase,
+                AggregateAndProofBase,
+                SignedAggregateAndProofBase,
+                BeaconBlockBodyBellatrix,
+                ExecutionPayloadBellatrix,
+                ExecutionPayloadHeaderBellatrix,
+                LightClientBootstrapAltair,
+                LightClientHeaderAltair,
+                LightClientOptimisticUpdateAltair,
+                LightClientFinalityUpdateAltair,
+                LightClientUpdateAltair,
+            ),
+            ForkName::Capella => run!(
+                AttestationBase,
+                AttesterSlashingBase,
+                IndexedAttestationBase,
+                AggregateAndProofBase,
+                SignedAggregateAndProofBase,
+                BeaconBlockBodyCapella,
+                ExecutionPayloadCapella,
+                ExecutionPayloadHeaderCapella,
+                LightClientBootstrapCapella,
+                LightClientHeaderCapella,
+                LightClientOptimisticUpdateCapella,
+                LightClientFinalityUpdateCapella,
+                LightClientUpdateCapella,
+            ),
+            ForkName::Deneb => run!(
+                AttestationBase,
+                AttesterSlashingBase,
+                IndexedAttestationBase,
+                AggregateAndProofBase,
+                SignedAggregateAndProofBase,
+                BeaconBlockBodyDeneb,
+                ExecutionPayloadDeneb,
+                ExecutionPayloadHeaderDeneb,
+                LightClientBootstrapDeneb,
+                LightClientHeaderDeneb,
+                LightClientOptimisticUpdateDeneb,
+                LightClientFinalityUpdateDeneb,
+                LightClientUpdateDeneb,
+            ),
+            // TODO(electra) add `LightClientBootstrapElectra`, `LightClientFinalityUpdateElectra` and
+            // `LightClientUpdateElectra` once https://github.com/sigp/lighthouse/issues/6002 is resolved
+            ForkName::Electra => run!(
+                AttestationElectra,
+                AttesterSlashingElectra,
+                IndexedAttestationElectra,
+                AggregateAndProofElectra,
+                SignedAggregateAndProofElectra,
+                BeaconBlockBodyElectra,
+                ExecutionPayloadElectra,
+                ExecutionPayloadHeaderElectra,
+                LightClientHeaderElectra,
+                LightClientOptimisticUpdateElectra,
+            ),
+        }
 
-    #[test]
-    fn signed_aggregate_and_proof() {
-        SszStaticHandler::<SignedAggregateAndProofBase<MinimalEthSpec>, MinimalEthSpec>::pre_electra(
-        )
-        .run();
-        SszStaticHandler::<SignedAggregateAndProofBase<MainnetEthSpec>, MainnetEthSpec>::pre_electra(
-        )
-        .run();
-        SszStaticHandler::<SignedAggregateAndProofElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only(
-        )
-        .run();
-        SszStaticHandler::<SignedAggregateAndProofElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only(
-        )
-        .run();
+        failures
     }
 
     #[test]
-    fn aggregate_and_proof() {
-        SszStaticHandler::<AggregateAndProofBase<MinimalEthSpec>, MinimalEthSpec>::pre_electra()
-            .run();
-        SszStaticHandler::<AggregateAndProofBase<MainnetEthSpec>, MainnetEthSpec>::pre_electra()
-            .run();
-        SszStaticHandler::<AggregateAndProofElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only(
-        )
-        .run();
-        SszStaticHandler::<AggregateAndProofElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only(
-        )
-        .run();
-    }
-
-    // BeaconBlockBody has no internal indicator of which fork it is for, so we test it separately.
-    #[test]
-    fn beacon_block_body() {
-        SszStaticHandler::<BeaconBlockBodyBase<MinimalEthSpec>, MinimalEthSpec>::base_only().run();
-        SszStaticHandler::<BeaconBlockBodyBase<MainnetEthSpec>, MainnetEthSpec>::base_only().run();
-        SszStaticHandler::<BeaconBlockBodyAltair<MinimalEthSpec>, MinimalEthSpec>::altair_only()
-            .run();
-        SszStaticHandler::<BeaconBlockBodyAltair<MainnetEthSpec>, MainnetEthSpec>::altair_only()
-            .run();
-        SszStaticHandler::<BeaconBlockBodyBellatrix<MinimalEthSpec>, MinimalEthSpec>::bellatrix_only()
-            .run();
-        SszStaticHandler::<BeaconBlockBodyBellatrix<MainnetEthSpec>, MainnetEthSpec>::bellatrix_only()
-            .run();
-        SszStaticHandler::<BeaconBlockBodyCapella<MinimalEthSpec>, MinimalEthSpec>::capella_only()
-            .run();
-        SszStaticHandler::<BeaconBlockBodyCapella<MainnetEthSpec>, MainnetEthSpec>::capella_only()
-            .run();
-        SszStaticHandler::<BeaconBlockBodyDeneb<MinimalEthSpec>, MinimalEthSpec>::deneb_only()
-            .run();
-        SszStaticHandler::<BeaconBlockBodyDeneb<MainnetEthSpec>, MainnetEthSpec>::deneb_only()
-            .run();
-        SszStaticHandler::<BeaconBlockBodyElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only()
-            .run();
-        SszStaticHandler::<BeaconBlockBodyElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only()
-            .run();
+    fn fork_variants() {
+        let failures: Vec<String> = ForkName::list_all()
+            .into_iter()
+            .flat_map(|fork_name| {
+                let mut failures = fork_variant_containers::<MinimalEthSpec>(fork_name);
+                failures.extend(fork_variant_containers::<MainnetEthSpec>(fork_name));
+                failures
+            })
+            .collect();
+        assert!(
+            failures.is_empty(),
+            "{} fork variant container(s) failed:\n{}",
+            failures.len(),
+            failures.join("\n")
+        );
     }
 
     // Altair and later
@@ -376,146 +427,6 @@ mod ssz_static {
         .run();
     }
 
-    // LightClientBootstrap has no internal indicator of which fork it is for, so we test it separately.
-    #[test]
-    fn light_client_bootstrap() {
-        SszStaticHandler::<LightClientBootstrapAltair<MinimalEthSpec>, MinimalEthSpec>::altair_only()
-            .run();
-        SszStaticHandler::<LightClientBootstrapAltair<MainnetEthSpec>, MainnetEthSpec>::altair_only()
-            .run();
-        SszStaticHandler::<LightClientBootstrapAltair<MinimalEthSpec>, MinimalEthSpec>::bellatrix_only(
-        )
-        .run();
-        SszStaticHandler::<LightClientBootstrapAltair<MainnetEthSpec>, MainnetEthSpec>::bellatrix_only(
-        )
-        .run();
-        SszStaticHandler::<LightClientBootstrapCapella<MinimalEthSpec>, MinimalEthSpec>::capella_only()
-            .run();
-        SszStaticHandler::<LightClientBootstrapCapella<MainnetEthSpec>, MainnetEthSpec>::capella_only()
-            .run();
-        SszStaticHandler::<LightClientBootstrapDeneb<MinimalEthSpec>, MinimalEthSpec>::deneb_only()
-            .run();
-        SszStaticHandler::<LightClientBootstrapDeneb<MainnetEthSpec>, MainnetEthSpec>::deneb_only()
-            .run();
-        // TODO(electra) re-enable once https://github.com/sigp/lighthouse/issues/6002 is resolved
-        // SszStaticHandler::<LightClientBootstrapElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only()
-        //     .run();
-        // SszStaticHandler::<LightClientBootstrapElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only()
-        //     .run();
-    }
-
-    // LightClientHeader has no internal indicator of which fork it is for, so we test it separately.
-    #[test]
-    fn light_client_header() {
-        SszStaticHandler::<LightClientHeaderAltair<MinimalEthSpec>, MinimalEthSpec>::altair_only()
-            .run();
-        SszStaticHandler::<LightClientHeaderAltair<MainnetEthSpec>, MainnetEthSpec>::altair_only()
-            .run();
-        SszStaticHandler::<LightClientHeaderAltair<MinimalEthSpec>, MinimalEthSpec>::bellatrix_only()
-            .run();
-        SszStaticHandler::<LightClientHeaderAltair<MainnetEthSpec>, MainnetEthSpec>::bellatrix_only()
-            .run();
-
-        SszStaticHandler::<LightClientHeaderCapella<MinimalEthSpec>, MinimalEthSpec>::capella_only(
-        )
-        .run();
-        SszStaticHandler::<LightClientHeaderCapella<MainnetEthSpec>, MainnetEthSpec>::capella_only(
-        )
-        .run();
-
-        SszStaticHandler::<LightClientHeaderDeneb<MinimalEthSpec>, MinimalEthSpec>::deneb_only()
-            .run();
-        SszStaticHandler::<LightClientHeaderDeneb<MainnetEthSpec>, MainnetEthSpec>::deneb_only()
-            .run();
-        SszStaticHandler::<LightClientHeaderElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only(
-        )
-        .run();
-        SszStaticHandler::<LightClientHeaderElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only(
-        )
-        .run();
-    }
-
-    // LightClientOptimisticUpdate has no internal indicator of which fork it is for, so we test it separately.
-    #[test]
-    fn light_client_optimistic_update() {
-        SszStaticHandler::<LightClientOptimisticUpdateAltair<MinimalEthSpec>, MinimalEthSpec>::altair_only().run();
-        SszStaticHandler::<LightClientOptimisticUpdateAltair<MainnetEthSpec>, MainnetEthSpec>::altair_only().run();
-        SszStaticHandler::<LightClientOptimisticUpdateAltair<MinimalEthSpec>, MinimalEthSpec>::bellatrix_only().run();
-        SszStaticHandler::<LightClientOptimisticUpdateAltair<MainnetEthSpec>, MainnetEthSpec>::bellatrix_only().run();
-        SszStaticHandler::<LightClientOptimisticUpdateCapella<MinimalEthSpec>, MinimalEthSpec>::capella_only().run();
-        SszStaticHandler::<LightClientOptimisticUpdateCapella<MainnetEthSpec>, MainnetEthSpec>::capella_only().run();
-        SszStaticHandler::<LightClientOptimisticUpdateDeneb<MinimalEthSpec>, MinimalEthSpec>::deneb_only().run();
-        SszStaticHandler::<LightClientOptimisticUpdateDeneb<MainnetEthSpec>, MainnetEthSpec>::deneb_only().run();
-        SszStaticHandler::<LightClientOptimisticUpdateElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only().run();
-        SszStaticHandler::<LightClientOptimisticUpdateElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only().run();
-    }
-
-    // LightClientFinalityUpdate has no internal indicator of which fork it is for, so we test it separately.
-    #[test]
-    fn light_client_finality_update() {
-        SszStaticHandler::<LightClientFinalityUpdateAltair<MinimalEthSpec>, MinimalEthSpec>::altair_only(
-        )
-            .run();
-        SszStaticHandler::<LightClientFinalityUpdateAltair<MainnetEthSpec>, MainnetEthSpec>::altair_only(
-        )
-            .run();
-        SszStaticHandler::<LightClientFinalityUpdateAltair<MinimalEthSpec>, MinimalEthSpec>::bellatrix_only(
-        )
-            .run();
-        SszStaticHandler::<LightClientFinalityUpdateAltair<MainnetEthSpec>, MainnetEthSpec>::bellatrix_only(
-        )
-            .run();
-        SszStaticHandler::<LightClientFinalityUpdateCapella<MinimalEthSpec>, MinimalEthSpec>::capella_only(
-        )
-            .run();
-        SszStaticHandler::<LightClientFinalityUpdateCapella<MainnetEthSpec>, MainnetEthSpec>::capella_only(
-        )
-            .run();
-        SszStaticHandler::<LightClientFinalityUpdateDeneb<MinimalEthSpec>, MinimalEthSpec>::deneb_only(
-        )
-            .run();
-        SszStaticHandler::<LightClientFinalityUpdateDeneb<MainnetEthSpec>, MainnetEthSpec>::deneb_only(
-        )
-            .run();
-        // TODO(electra) re-enable once https://github.com/sigp/lighthouse/issues/6002 is resolved
-        // SszStaticHandler::<LightClientFinalityUpdateElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only(
-        // )
-        //     .run();
-        // SszStaticHandler::<LightClientFinalityUpdateElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only(
-        // )
-        //     .run();
-    }
-
-    // LightClientUpdate has no internal indicator of which fork it is for, so we test it separately.
-    #[test]
-    fn light_client_update() {
-        SszStaticHandler::<LightClientUpdateAltair<MinimalEthSpec>, MinimalEthSpec>::altair_only()
-            .run();
-        SszStaticHandler::<LightClientUpdateAltair<MainnetEthSpec>, MainnetEthSpec>::altair_only()
-            .run();
-        SszStaticHandler::<LightClientUpdateAltair<MinimalEthSpec>, MinimalEthSpec>::bellatrix_only()
-            .run();
-        SszStaticHandler::<LightClientUpdateAltair<MainnetEthSpec>, MainnetEthSpec>::bellatrix_only()
-            .run();
-        SszStaticHandler::<LightClientUpdateCapella<MinimalEthSpec>, MinimalEthSpec>::capella_only(
-        )
-        .run();
-        SszStaticHandler::<LightClientUpdateCapella<MainnetEthSpec>, MainnetEthSpec>::capella_only(
-        )
-        .run();
-        SszStaticHandler::<LightClientUpdateDeneb<MinimalEthSpec>, MinimalEthSpec>::deneb_only()
-            .run();
-        SszStaticHandler::<LightClientUpdateDeneb<MainnetEthSpec>, MainnetEthSpec>::deneb_only()
-            .run();
-        // TODO(electra) re-enable once https://github.com/sigp/lighthouse/issues/6002 is resolved
-        // SszStaticHandler::<LightClientUpdateElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only(
-        // )
-        // .run();
-        // SszStaticHandler::<LightClientUpdateElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only(
-        // )
-        // .run();
-    }
-
     #[test]
     fn signed_contribution_and_proof() {
         SszStaticHandler::<SignedContributionAndProof<MinimalEthSpec>, MinimalEthSpec>::altair_and_later().run();
@@ -552,47 +463,6 @@ mod ssz_static {
         SszStaticHandler::<SyncAggregatorSelectionData, MainnetEthSpec>::altair_and_later().run();
     }
 
-    // Bellatrix and later
-    #[test]
-    fn execution_payload() {
-        SszStaticHandler::<ExecutionPayloadBellatrix<MinimalEthSpec>, MinimalEthSpec>::bellatrix_only()
-            .run();
-        SszStaticHandler::<ExecutionPayloadBellatrix<MainnetEthSpec>, MainnetEthSpec>::bellatrix_only()
-            .run();
-        SszStaticHandler::<ExecutionPayloadCapella<MinimalEthSpec>, MinimalEthSpec>::capella_only()
-            .run();
-        SszStaticHandler::<ExecutionPayloadCapella<MainnetEthSpec>, MainnetEthSpec>::capella_only()
-            .run();
-        SszStaticHandler::<ExecutionPayloadDeneb<MinimalEthSpec>, MinimalEthSpec>::deneb_only()
-            .run();
-        SszStaticHandler::<ExecutionPayloadDeneb<MainnetEthSpec>, MainnetEthSpec>::deneb_only()
-            .run();
-        SszStaticHandler::<ExecutionPayloadElectra<MinimalEthSpec>, MinimalEthSpec>::electra_only()
-            .run();
-        SszStaticHandler::<ExecutionPayloadElectra<MainnetEthSpec>, MainnetEthSpec>::electra_only()
-            .run();
-    }
-
-    #[test]
-    fn execution_payload_header() {
-        SszStaticHandler::<ExecutionPayloadHeaderBellatrix<MinimalEthSpec>, MinimalEthSpec>::bellatrix_only()
-            .run();
-        SszStaticHandler::<ExecutionPayloadHeaderBellatrix<MainnetEthSpec>, MainnetEthSpec>::bellatrix_only()
-            .run();
-        SszStaticHandler::<ExecutionPayloadHeaderCapella<MinimalEthSpec>, MinimalEthSpec>
-            ::capella_only().run();
-        SszStaticHandler::<ExecutionPayloadHeaderCapella<MainnetEthSpec>, MainnetEthSpec>
-            ::capella_only().run();
-        SszStaticHandler::<ExecutionPayloadHeaderDeneb<MinimalEthSpec>, MinimalEthSpec>
-            ::deneb_only().run();
-        SszStaticHandler::<ExecutionPayloadHeaderDeneb<MainnetEthSpec>, MainnetEthSpec>
-            ::deneb_only().run();
-        SszStaticHandler::<ExecutionPayloadHeaderElectra<MinimalEthSpec>, MinimalEthSpec>
-            ::electra_only().run();
-        SszStaticHandler::<ExecutionPayloadHeaderElectra<MainnetEthSpec>, MainnetEthSpec>
-            ::electra_only().run();
-    }
-
     #[test]
     fn withdrawal() {
         SszStaticHandler::<Withdrawal, MinimalEthSpec>::capella_and_later().run();
@@ -635,12 +505,11 @@ mod ssz_static {
         SszStaticHandler::<Consolidation, MainnetEthSpec>::electra_and_later().run();
     }
 
-    // TODO(electra) re-enable when EF tests are updated
-    // #[test]
-    // fn deposit_request() {
-    //     SszStaticHandler::<DepositRequest, MinimalEthSpec>::electra_and_later().run();
-    //     SszStaticHandler::<DepositRequest, MainnetEthSpec>::electra_and_later().run();
-    // }
+    #[test]
+    fn deposit_request() {
+        SszStaticHandler::<DepositRequest, MinimalEthSpec>::electra_and_later().run();
+        SszStaticHandler::<DepositRequest, MainnetEthSpec>::electra_and_later().run();
+    }
 
     #[test]
     fn execution_layer_withdrawal_request() {
@@ -91,9 +91,12 @@ pub fn run_fallback_sim(matches: &ArgMatches) -> Result<(), String> {
         .initialize_logger(LoggerConfig {
             path: None,
             debug_level: log_level.clone(),
+            log_levels: None,
             logfile_debug_level: log_level.clone(),
             log_format: None,
             logfile_format: None,
+            json_log_path: None,
+            otlp_endpoint: None,
             log_color: false,
             disable_log_timestamp: false,
             max_log_size: 0,
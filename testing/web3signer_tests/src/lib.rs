@@ -77,6 +77,7 @@ mod tests {
     impl SignedObject for SignedBeaconBlock<E> {}
     impl SignedObject for SignedAggregateAndProof<E> {}
     impl SignedObject for SelectionProof {}
+    impl SignedObject for Vec<SelectionProof> {}
     impl SignedObject for SyncSelectionProof {}
     impl SignedObject for SyncCommitteeMessage {}
     impl SignedObject for SignedContributionAndProof<E> {}
@@ -424,6 +425,7 @@ mod tests {
                     builder_proposals: None,
                     builder_boost_factor: None,
                     prefer_builder_proposals: None,
+                    builder_beacon_nodes: None,
                     description: String::default(),
                     signing_definition: SigningDefinition::LocalKeystore {
                         voting_keystore_path: signer_rig.keystore_path.clone(),
@@ -450,6 +452,7 @@ mod tests {
                     builder_proposals: None,
                     builder_boost_factor: None,
                     prefer_builder_proposals: None,
+                    builder_beacon_nodes: None,
                     description: String::default(),
                     signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
                         url: signer_rig.url.to_string(),
@@ -628,6 +631,42 @@ mod tests {
                 .unwrap()
         })
         .await
+        .assert_signatures_match("selection_proofs", |pubkey, validator_store| async move {
+            // Enough slots to need more than one batch of requests to Web3Signer.
+            let requests = (0..2 * E::slots_per_epoch() + 1)
+                .map(|slot| (pubkey, Slot::new(slot)))
+                .collect::<Vec<_>>();
+
+            // An unknown validator fails without affecting the rest of the batch.
+            let unknown_pubkey = PublicKeyBytes::empty();
+            let mut requests_with_unknown = requests.clone();
+            requests_with_unknown.insert(1, (unknown_pubkey, Slot::new(0)));
+            let mut selection_proofs = validator_store
+                .produce_selection_proofs(&requests_with_unknown)
+                .await;
+            assert_eq!(
+                selection_proofs.remove(1),
+                Err(ValidatorStoreError::UnknownPubkey(unknown_pubkey))
+            );
+            let selection_proofs = selection_proofs
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            // The batch matches the proofs signed one at a time.
+            assert_eq!(selection_proofs.len(), requests.len());
+            for (selection_proof, (pubkey, slot)) in selection_proofs.iter().zip(requests) {
+                assert_eq!(
+                    selection_proof,
+                    &validator_store
+                        .produce_selection_proof(pubkey, slot)
+                        .await
+                        .unwrap()
+                );
+            }
+            selection_proofs
+        })
+        .await
         .assert_signatures_match(
             "validator_registration",
             |pubkey, validator_store| async move {
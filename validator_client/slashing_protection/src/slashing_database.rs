@@ -1012,6 +1012,30 @@ impl SlashingDatabase {
         Ok(())
     }
 
+    /// Prune the database down to the minimal low-watermark data for the given validator keys.
+    ///
+    /// Only the block with the highest slot and the attestation with the highest target epoch are
+    /// retained for each validator, which is equivalent to the minimal form of the EIP-3076
+    /// interchange format. Any message at or below these watermarks will be refused, so this is
+    /// always safe, but it does discard the history required for a complete export.
+    pub fn minify_all<'a>(
+        &self,
+        public_keys: impl Iterator<Item = &'a PublicKeyBytes>,
+    ) -> Result<(), NotSafe> {
+        // The pruning queries never delete the maximum row, so pruning up to the largest value
+        // that SQLite can represent leaves exactly one row per validator.
+        let max_sql_value = i64::MAX as u64;
+
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+        for pubkey in public_keys {
+            self.prune_signed_blocks(pubkey, Slot::new(max_sql_value), &txn)?;
+            self.prune_signed_attestations(pubkey, Epoch::new(max_sql_value), &txn)?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
     pub fn num_validator_rows(&self) -> Result<u32, NotSafe> {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction()?;
@@ -1250,4 +1274,54 @@ mod tests {
         })
         .unwrap();
     }
+
+    #[test]
+    fn minify_keeps_low_watermarks() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+        let pubkey = crate::test_utils::pubkey(0);
+        db.register_validator(pubkey).unwrap();
+
+        for i in 1..10 {
+            let signing_root = SigningRoot::from(Hash256::from_low_u64_be(i));
+            db.check_and_insert_block_signing_root(&pubkey, Slot::new(i), signing_root)
+                .unwrap();
+            db.check_and_insert_attestation_signing_root(
+                &pubkey,
+                Epoch::new(i - 1),
+                Epoch::new(i),
+                signing_root,
+            )
+            .unwrap();
+        }
+
+        db.minify_all(std::iter::once(&pubkey)).unwrap();
+
+        let interchange = db.export_all_interchange_info(Hash256::zero()).unwrap();
+        let data = &interchange.data[0];
+        assert_eq!(data.signed_blocks.len(), 1);
+        assert_eq!(data.signed_blocks[0].slot, Slot::new(9));
+        assert_eq!(data.signed_attestations.len(), 1);
+        assert_eq!(data.signed_attestations[0].target_epoch, Epoch::new(9));
+
+        // Messages below the watermarks must still be refused.
+        assert!(matches!(
+            db.check_and_insert_block_signing_root(&pubkey, Slot::new(5), SigningRoot::default()),
+            Err(NotSafe::InvalidBlock(
+                InvalidBlock::SlotViolatesLowerBound { .. }
+            ))
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation_signing_root(
+                &pubkey,
+                Epoch::new(8),
+                Epoch::new(8),
+                SigningRoot::default()
+            ),
+            Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::TargetLessThanOrEqLowerBound { .. }
+            ))
+        ));
+    }
 }
@@ -381,6 +381,14 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         self.candidates.len()
     }
 
+    /// The identifiers of all candidates, as used to record which beacon nodes accepted a request.
+    pub fn beacon_node_ids(&self) -> Vec<String> {
+        self.candidates
+            .iter()
+            .map(|candidate| candidate.beacon_node.to_string())
+            .collect()
+    }
+
     /// The count of synced and ready candidates.
     pub async fn num_synced(&self) -> usize {
         let mut n = 0;
@@ -485,6 +493,26 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         offline_on_failure: OfflineOnFailure,
         func: F,
     ) -> Result<O, Errors<Err>>
+    where
+        F: Fn(&'a BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<O, Err>>,
+        Err: Debug,
+    {
+        self.first_success_by_preference(require_synced, offline_on_failure, &[], func)
+            .await
+    }
+
+    /// Run `func` against each candidate in `self`, like `first_success`, except that the
+    /// candidates in `preferred` are tried before the others, in the order given.
+    ///
+    /// Candidates are identified by their redacted URL, i.e. `BeaconNodeHttpClient::to_string`.
+    pub async fn first_success_by_preference<'a, F, O, Err, R>(
+        &'a self,
+        require_synced: RequireSynced,
+        offline_on_failure: OfflineOnFailure,
+        preferred: &[String],
+        func: F,
+    ) -> Result<O, Errors<Err>>
     where
         F: Fn(&'a BeaconNodeHttpClient) -> R,
         R: Future<Output = Result<O, Err>>,
@@ -532,7 +560,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         // First pass: try `func` on all synced and ready candidates.
         //
         // This ensures that we always choose a synced node if it is available.
-        for candidate in &self.candidates {
+        for candidate in self.candidates_by_preference(preferred) {
             match candidate.status(RequireSynced::Yes).await {
                 Err(e @ CandidateError::NotSynced) if require_synced == false => {
                     // This client is unsynced we will try it after trying all synced clients
@@ -585,6 +613,22 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         Err(Errors(errors))
     }
 
+    /// Returns the candidates in `self`, with those in `preferred` moved to the front in the order
+    /// given. The other candidates keep their order.
+    fn candidates_by_preference(&self, preferred: &[String]) -> Vec<&CandidateBeaconNode<E>> {
+        let mut candidates = self.candidates.iter().collect::<Vec<_>>();
+        if !preferred.is_empty() {
+            candidates.sort_by_key(|candidate| {
+                let beacon_node_id = candidate.beacon_node.to_string();
+                preferred
+                    .iter()
+                    .position(|preferred| *preferred == beacon_node_id)
+                    .unwrap_or(preferred.len())
+            });
+        }
+        candidates
+    }
+
     /// Run `func` against all candidates in `self`, collecting the result of `func` against each
     /// candidate.
     ///
@@ -713,6 +757,25 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         topic: ApiTopic,
         func: F,
     ) -> Result<(), Errors<Err>>
+    where
+        F: Fn(&'a BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<(), Err>>,
+        Err: Debug,
+    {
+        self.request_by_preference(require_synced, offline_on_failure, topic, &[], func)
+            .await
+    }
+
+    /// Call `func` like `request`, trying the candidates in `preferred` first if the `topic` isn't
+    /// broadcast.
+    pub async fn request_by_preference<'a, F, Err, R>(
+        &'a self,
+        require_synced: RequireSynced,
+        offline_on_failure: OfflineOnFailure,
+        topic: ApiTopic,
+        preferred: &[String],
+        func: F,
+    ) -> Result<(), Errors<Err>>
     where
         F: Fn(&'a BeaconNodeHttpClient) -> R,
         R: Future<Output = Result<(), Err>>,
@@ -722,7 +785,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
             self.broadcast(require_synced, offline_on_failure, func)
                 .await
         } else {
-            self.first_success(require_synced, offline_on_failure, func)
+            self.first_success_by_preference(require_synced, offline_on_failure, preferred, func)
                 .await?;
             Ok(())
         }
@@ -749,8 +812,13 @@ impl ApiTopic {
 #[cfg(test)]
 mod test {
     use super::*;
+    use environment::null_logger;
+    use eth2::Timeouts;
+    use sensitive_url::SensitiveUrl;
+    use slot_clock::TestingSlotClock;
     use std::str::FromStr;
     use strum::VariantNames;
+    use types::MainnetEthSpec;
 
     #[test]
     fn api_topic_all() {
@@ -761,4 +829,58 @@ mod test {
             .map(|topic| ApiTopic::from_str(topic).unwrap())
             .eq(all.into_iter()));
     }
+
+    #[test]
+    fn preferred_candidates_are_first() {
+        let urls = [
+            "http://bn-1:5052",
+            "http://bn-2:5052",
+            "http://bn-3:5052",
+            "http://bn-4:5052",
+        ];
+        let candidates = urls
+            .iter()
+            .map(|url| {
+                CandidateBeaconNode::<MainnetEthSpec>::new(BeaconNodeHttpClient::new(
+                    SensitiveUrl::parse(url).unwrap(),
+                    Timeouts::set_all(Duration::from_secs(1)),
+                ))
+            })
+            .collect();
+        let beacon_nodes = BeaconNodeFallback::<TestingSlotClock, _>::new(
+            candidates,
+            vec![],
+            MainnetEthSpec::default_spec(),
+            null_logger().unwrap(),
+        );
+        let order = |preferred: &[&str]| {
+            let preferred = preferred
+                .iter()
+                .map(|url| SensitiveUrl::parse(url).unwrap().to_string())
+                .collect::<Vec<_>>();
+            beacon_nodes
+                .candidates_by_preference(&preferred)
+                .into_iter()
+                .map(|candidate| candidate.beacon_node.to_string())
+                .collect::<Vec<_>>()
+        };
+        let expected = |indices: &[usize]| {
+            indices
+                .iter()
+                .map(|i| format!("{}/", urls[*i]))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(order(&[]), expected(&[0, 1, 2, 3]));
+        assert_eq!(order(&["http://bn-3:5052"]), expected(&[2, 0, 1, 3]));
+        assert_eq!(
+            order(&["http://bn-4:5052", "http://bn-2:5052"]),
+            expected(&[3, 1, 0, 2])
+        );
+        // Unknown beacon nodes are ignored.
+        assert_eq!(
+            order(&["http://unknown:5052", "http://bn-2:5052"]),
+            expected(&[1, 0, 2, 3])
+        );
+    }
 }
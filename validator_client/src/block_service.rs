@@ -11,7 +11,7 @@ use crate::{
 };
 use bls::SignatureBytes;
 use environment::RuntimeContext;
-use eth2::types::{FullBlockContents, PublishBlockRequest};
+use eth2::types::{BroadcastValidation, FullBlockContents, PublishBlockRequest};
 use eth2::{BeaconNodeHttpClient, StatusCode};
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
@@ -59,6 +59,7 @@ pub struct BlockServiceBuilder<T, E: EthSpec> {
     context: Option<RuntimeContext<E>>,
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
+    broadcast_validation: Option<BroadcastValidation>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
@@ -71,6 +72,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
             context: None,
             graffiti: None,
             graffiti_file: None,
+            broadcast_validation: None,
         }
     }
 
@@ -109,6 +111,14 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
         self
     }
 
+    pub fn broadcast_validation(
+        mut self,
+        broadcast_validation: Option<BroadcastValidation>,
+    ) -> Self {
+        self.broadcast_validation = broadcast_validation;
+        self
+    }
+
     pub fn build(self) -> Result<BlockService<T, E>, String> {
         Ok(BlockService {
             inner: Arc::new(Inner {
@@ -127,6 +137,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                 proposer_nodes: self.proposer_nodes,
                 graffiti: self.graffiti,
                 graffiti_file: self.graffiti_file,
+                broadcast_validation: self.broadcast_validation,
             }),
         })
     }
@@ -137,6 +148,8 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
 pub struct ProposerFallback<T, E: EthSpec> {
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     proposer_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
+    // The beacon nodes preferred by the proposer, which are tried first within each set.
+    preferred_nodes: Vec<String>,
 }
 
 impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
@@ -155,10 +168,11 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
         // If there are proposer nodes, try calling `func` on them and return early if they are successful.
         if let Some(proposer_nodes) = &self.proposer_nodes {
             if proposer_nodes
-                .request(
+                .request_by_preference(
                     require_synced,
                     offline_on_failure,
                     ApiTopic::Blocks,
+                    &self.preferred_nodes,
                     func.clone(),
                 )
                 .await
@@ -170,7 +184,13 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
 
         // If the proposer nodes failed, try on the non-proposer nodes.
         self.beacon_nodes
-            .request(require_synced, offline_on_failure, ApiTopic::Blocks, func)
+            .request_by_preference(
+                require_synced,
+                offline_on_failure,
+                ApiTopic::Blocks,
+                &self.preferred_nodes,
+                func,
+            )
             .await
     }
 
@@ -189,7 +209,12 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
         // Try running `func` on the non-proposer beacon nodes.
         let beacon_nodes_result = self
             .beacon_nodes
-            .first_success(require_synced, offline_on_failure, func.clone())
+            .first_success_by_preference(
+                require_synced,
+                offline_on_failure,
+                &self.preferred_nodes,
+                func.clone(),
+            )
             .await;
 
         match (beacon_nodes_result, &self.proposer_nodes) {
@@ -200,7 +225,12 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
             // The non-proposer node call failed, try the same call on the proposer nodes.
             (Err(_), Some(proposer_nodes)) => {
                 proposer_nodes
-                    .first_success(require_synced, offline_on_failure, func)
+                    .first_success_by_preference(
+                        require_synced,
+                        offline_on_failure,
+                        &self.preferred_nodes,
+                        func,
+                    )
                     .await
             }
         }
@@ -216,6 +246,7 @@ pub struct Inner<T, E: EthSpec> {
     context: RuntimeContext<E>,
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
+    broadcast_validation: Option<BroadcastValidation>,
 }
 
 /// Attempts to produce attestations for any block producer(s) at the start of the epoch.
@@ -490,6 +521,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         let proposer_fallback = ProposerFallback {
             beacon_nodes: self.beacon_nodes.clone(),
             proposer_nodes: self.proposer_nodes.clone(),
+            preferred_nodes: self.validator_store.builder_beacon_nodes(&validator_pubkey),
         };
 
         info!(
@@ -557,20 +589,28 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                     &metrics::BLOCK_SERVICE_TIMES,
                     &[metrics::BEACON_BLOCK_HTTP_POST],
                 );
-                beacon_node
-                    .post_beacon_blocks(signed_block)
-                    .await
-                    .or_else(|e| handle_block_post_error(e, slot, log))?
+                let result = if let Some(validation_level) = self.broadcast_validation {
+                    beacon_node
+                        .post_beacon_blocks_v2(signed_block, Some(validation_level))
+                        .await
+                } else {
+                    beacon_node.post_beacon_blocks(signed_block).await
+                };
+                result.or_else(|e| handle_block_post_error(e, slot, log))?
             }
             SignedBlock::Blinded(signed_block) => {
                 let _post_timer = metrics::start_timer_vec(
                     &metrics::BLOCK_SERVICE_TIMES,
                     &[metrics::BLINDED_BEACON_BLOCK_HTTP_POST],
                 );
-                beacon_node
-                    .post_beacon_blinded_blocks(signed_block)
-                    .await
-                    .or_else(|e| handle_block_post_error(e, slot, log))?
+                let result = if let Some(validation_level) = self.broadcast_validation {
+                    beacon_node
+                        .post_beacon_blinded_blocks_v2(signed_block, Some(validation_level))
+                        .await
+                } else {
+                    beacon_node.post_beacon_blinded_blocks(signed_block).await
+                };
+                result.or_else(|e| handle_block_post_error(e, slot, log))?
             }
         }
         Ok::<_, BlockError>(())
@@ -0,0 +1,187 @@
+//! Failure injection for chaos testing.
+//!
+//! A chaos config is a TOML file with one table per duty type, for example:
+//!
+//! ```toml
+//! [attestation]
+//! drop_probability = 0.1
+//! delay_probability = 0.5
+//! delay_ms = 4000
+//!
+//! [block]
+//! drop_probability = 1.0
+//! start_epoch = 100
+//! end_epoch = 110
+//! ```
+//!
+//! Failures are injected by the `ValidatorStore` before a message is signed, so a dropped message
+//! is never signed, inserted into the slashing protection database or published, and a delayed
+//! message is published late. It is only intended for testing monitoring, alerting and beacon
+//! node fallback behaviour and must never be used on mainnet validators.
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use strum::IntoStaticStr;
+use types::Epoch;
+
+/// The duty types which failures can be injected into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, IntoStaticStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ChaosDuty {
+    Block,
+    Attestation,
+    Aggregate,
+    SyncCommitteeMessage,
+    SyncCommitteeContribution,
+}
+
+/// The failures to inject for a single duty type.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DutyChaos {
+    /// Probability in `[0, 1]` that the message is dropped.
+    pub drop_probability: f64,
+    /// Probability in `[0, 1]` that the message is delayed by `delay_ms`.
+    pub delay_probability: f64,
+    pub delay_ms: u64,
+    /// First epoch (inclusive) in which failures are injected.
+    pub start_epoch: Option<u64>,
+    /// Last epoch (inclusive) in which failures are injected.
+    pub end_epoch: Option<u64>,
+}
+
+/// What to do with a single message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChaosAction {
+    Proceed,
+    Delay(Duration),
+    Drop,
+}
+
+impl ChaosAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChaosAction::Proceed => "proceed",
+            ChaosAction::Delay(_) => "delay",
+            ChaosAction::Drop => "drop",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChaosConfig {
+    duties: HashMap<ChaosDuty, DutyChaos>,
+}
+
+impl ChaosConfig {
+    /// Reads and validates a chaos config from the TOML file at `path`.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read {}: {:?}", path.display(), e))?;
+        Self::from_toml(&contents)
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, String> {
+        let config: Self =
+            toml::from_str(contents).map_err(|e| format!("Invalid chaos config: {}", e))?;
+
+        for (duty, chaos) in &config.duties {
+            for probability in [chaos.drop_probability, chaos.delay_probability] {
+                if !(0.0..=1.0).contains(&probability) {
+                    return Err(format!(
+                        "Invalid chaos config for {:?}: probability {} is not in [0, 1]",
+                        duty, probability
+                    ));
+                }
+            }
+            if let (Some(start), Some(end)) = (chaos.start_epoch, chaos.end_epoch) {
+                if start > end {
+                    return Err(format!(
+                        "Invalid chaos config for {:?}: start_epoch is after end_epoch",
+                        duty
+                    ));
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Decides what to do with a message for `duty` in `epoch`.
+    pub fn action<R: Rng>(&self, duty: ChaosDuty, epoch: Epoch, rng: &mut R) -> ChaosAction {
+        let Some(chaos) = self.duties.get(&duty) else {
+            return ChaosAction::Proceed;
+        };
+
+        let epoch = epoch.as_u64();
+        if chaos.start_epoch.map_or(false, |start| epoch < start)
+            || chaos.end_epoch.map_or(false, |end| epoch > end)
+        {
+            return ChaosAction::Proceed;
+        }
+
+        if rng.gen_bool(chaos.drop_probability) {
+            ChaosAction::Drop
+        } else if rng.gen_bool(chaos.delay_probability) {
+            ChaosAction::Delay(Duration::from_millis(chaos.delay_ms))
+        } else {
+            ChaosAction::Proceed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    const CONFIG: &str = r#"
+        [block]
+        drop_probability = 1.0
+        start_epoch = 10
+        end_epoch = 20
+
+        [sync_committee_message]
+        delay_probability = 1.0
+        delay_ms = 1500
+    "#;
+
+    #[test]
+    fn actions() {
+        let config = ChaosConfig::from_toml(CONFIG).unwrap();
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        let block =
+            |epoch, rng: &mut SmallRng| config.action(ChaosDuty::Block, Epoch::new(epoch), rng);
+        assert_eq!(block(9, &mut rng), ChaosAction::Proceed);
+        assert_eq!(block(10, &mut rng), ChaosAction::Drop);
+        assert_eq!(block(20, &mut rng), ChaosAction::Drop);
+        assert_eq!(block(21, &mut rng), ChaosAction::Proceed);
+
+        assert_eq!(
+            config.action(ChaosDuty::SyncCommitteeMessage, Epoch::new(0), &mut rng),
+            ChaosAction::Delay(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            config.action(ChaosDuty::Attestation, Epoch::new(15), &mut rng),
+            ChaosAction::Proceed
+        );
+    }
+
+    #[test]
+    fn invalid_configs() {
+        for config in [
+            "[block]\ndrop_probability = 1.5",
+            "[block]\nstart_epoch = 2\nend_epoch = 1",
+            "[block]\ndrop_chance = 0.5",
+            "[proposal]\ndrop_probability = 0.5",
+        ] {
+            assert!(ChaosConfig::from_toml(config).is_err(), "{}", config);
+        }
+    }
+}
@@ -65,6 +65,19 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("broadcast-validation")
+                .long("broadcast-validation")
+                .value_name("LEVEL")
+                .help("The level of validation the beacon node should perform on proposed blocks \
+                       before broadcasting them. When set, blocks are published using the v2 \
+                       publish endpoints. Higher levels protect against publishing slashable \
+                       blocks at the cost of increased propagation latency. Default (when flag \
+                       is omitted) is to use the v1 endpoints, which perform gossip validation.")
+                .value_parser(["gossip", "consensus", "consensus_and_equivocation"])
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("validators-dir")
                 .long("validators-dir")
@@ -139,6 +152,16 @@ pub fn cli_app() -> Command {
                         delimiter, and must not be part of the certificate path.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("beacon-nodes-api-token-file")
+                .long("beacon-nodes-api-token-file")
+                .value_name("PATH")
+                .action(ArgAction::Set)
+                .help("Path to a file containing the token set with --http-api-token-file on the \
+                        beacon nodes. If supplied, the validator client registers its keys with \
+                        the validator monitors of the beacon nodes.")
+                .display_order(0)
+        )
         // This overwrites the graffiti configured in the beacon node.
         .arg(
             Arg::new("graffiti")
@@ -193,24 +216,25 @@ pub fn cli_app() -> Command {
                 .display_order(0)
         )
         /*
-         * Note: The HTTP server is **not** encrypted (i.e., not HTTPS) and therefore it is
-         * unsafe to publish on a public network.
+         * Note: Unless a TLS certificate and key are provided the HTTP server is **not**
+         * encrypted (i.e., not HTTPS) and therefore it is unsafe to publish on a public network.
          *
-         * If the `--http-address` flag is used, the `--unencrypted-http-transport` flag
-         * must also be used in order to make it clear to the user that this is unsafe.
+         * If the `--http-address` flag is used without TLS, the `--unencrypted-http-transport`
+         * flag must also be used in order to make it clear to the user that this is unsafe.
          */
          .arg(
              Arg::new("http-address")
                  .long("http-address")
                  .requires("http")
                  .value_name("ADDRESS")
-                 .help("Set the address for the HTTP address. The HTTP server is not encrypted \
-                        and therefore it is unsafe to publish on a public network. When this \
-                        flag is used, it additionally requires the explicit use of the \
+                 .help("Set the address for the HTTP address. Unless --http-tls-cert and \
+                        --http-tls-key are provided the HTTP server is not encrypted and \
+                        therefore it is unsafe to publish on a public network. When this \
+                        flag is used without TLS, it additionally requires the explicit use of the \
                         `--unencrypted-http-transport` flag to ensure the user is aware of the \
-                        risks involved. For access via the Internet, users should apply \
-                        transport-layer security like a HTTPS reverse-proxy or SSH tunnelling.")
-                .requires("unencrypted-http-transport")
+                        risks involved. For access via the Internet, users should serve the API \
+                        over TLS or apply transport-layer security like a HTTPS reverse-proxy \
+                        or SSH tunnelling.")
                 .display_order(0)
          )
          .arg(
@@ -269,6 +293,39 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("http-tls-cert")
+                .long("http-tls-cert")
+                .requires("http")
+                .requires("http-tls-key")
+                .value_name("PATH")
+                .help("The path of the certificate to be used when serving the HTTP API server \
+                    over TLS. Providing a certificate and key enables TLS.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-tls-key")
+                .long("http-tls-key")
+                .requires("http")
+                .requires("http-tls-cert")
+                .value_name("PATH")
+                .help("The path of the private key to be used when serving the HTTP API server \
+                    over TLS. Must not be password-protected. Providing a certificate and key \
+                    enables TLS.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-token-grace-period")
+                .long("http-token-grace-period")
+                .requires("http")
+                .value_name("SECONDS")
+                .help("The number of seconds for which the previous API token continues to be \
+                    accepted after the token is rotated via the HTTP API. Defaults to 60.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         /* Prometheus metrics HTTP server related arguments */
         .arg(
             Arg::new("metrics")
@@ -347,6 +404,16 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("monitoring-endpoint-token-file")
+                .long("monitoring-endpoint-token-file")
+                .value_name("PATH")
+                .help("Path to a file containing a token which will be sent to the \
+                       monitoring-endpoint in an `Authorization: Bearer` header.")
+                .requires("monitoring-endpoint")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("enable-doppelganger-protection")
                 .long("enable-doppelganger-protection")
@@ -384,6 +451,17 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("builder-registration-expiry")
+                .long("builder-registration-expiry")
+                .value_name("SECONDS")
+                .help("The age at which builders expire validator registrations. Registrations \
+                    are re-signed with a fresh timestamp within an epoch of this age, and are \
+                    re-published to any beacon node which has not accepted them within this \
+                    period, rather than waiting for the next scheduled submission.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("gas-limit")
                 .long("gas-limit")
@@ -444,6 +522,50 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("proposer-duties-from-events")
+                .long("proposer-duties-from-events")
+                .help("Subscribe to head events from the beacon node and refresh proposer duties \
+                       as soon as a re-org changes them, rather than downloading proposer duties \
+                       every slot. Proposer duties are still downloaded every slot whilst no \
+                       event stream is connected.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("precompute-selection-proofs")
+                .long("precompute-selection-proofs")
+                .help("Compute attestation selection proofs for the current and next epoch as soon \
+                       as attester duties arrive, signing them in batches, rather than shortly \
+                       before each duty. This improves the timeliness of aggregates when using a \
+                       remote signer.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("chaos-config")
+                .long("chaos-config")
+                .value_name("FILE")
+                .help("Path to a TOML file describing failures to inject into the signing of \
+                       each duty type, for testing monitoring and beacon node fallback. \
+                       Duties will be delayed or dropped. DO NOT USE ON MAINNET.")
+                .action(ArgAction::Set)
+                .display_order(0)
+                .hide(true)
+        )
+        .arg(
+            Arg::new("slashing-protection-minify")
+                .long("slashing-protection-minify")
+                .help("When periodically pruning the slashing protection database, keep only the \
+                       highest signed block and attestation for each validator (the minimal \
+                       EIP-3076 format) instead of recent history. This keeps the database small \
+                       but means that exports will only contain the minimal data.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-slashing-protection-web3signer")
                 .long("disable-slashing-protection-web3signer")
@@ -1,4 +1,5 @@
 use crate::beacon_node_fallback::ApiTopic;
+use crate::chaos::ChaosConfig;
 use crate::graffiti_file::GraffitiFile;
 use crate::{http_api, http_metrics};
 use clap::ArgMatches;
@@ -7,13 +8,13 @@ use directory::{
     get_network_dir, DEFAULT_HARDCODED_NETWORK, DEFAULT_ROOT_DIR, DEFAULT_SECRET_DIR,
     DEFAULT_VALIDATOR_DIR,
 };
-use eth2::types::Graffiti;
+use eth2::types::{BroadcastValidation, Graffiti};
 use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use slog::{info, warn, Logger};
 use std::fs;
 use std::net::IpAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use types::{Address, GRAFFITI_BYTES_LEN};
 
@@ -66,27 +67,48 @@ pub struct Config {
     pub builder_proposals: bool,
     /// Overrides the timestamp field in builder api ValidatorRegistrationV1
     pub builder_registration_timestamp_override: Option<u64>,
+    /// Re-sign and re-publish builder registrations before they become this old.
+    pub builder_registration_expiry: Option<Duration>,
     /// Fallback gas limit.
     pub gas_limit: Option<u64>,
     /// A list of custom certificates that the validator client will additionally use when
     /// connecting to a beacon node over SSL/TLS.
     pub beacon_nodes_tls_certs: Option<Vec<PathBuf>>,
+    /// Path to a file containing the token for the authenticated endpoints of the beacon nodes.
+    ///
+    /// Keys are only registered with the validator monitors of the beacon nodes if this is set.
+    pub beacon_nodes_api_token_path: Option<PathBuf>,
     /// Enables broadcasting of various requests (by topic) to all beacon nodes.
     pub broadcast_topics: Vec<ApiTopic>,
+    /// The validation level requested from the beacon node when publishing blocks.
+    ///
+    /// If `None`, blocks are published via the v1 endpoints.
+    pub broadcast_validation: Option<BroadcastValidation>,
     /// Enables a service which attempts to measure latency between the VC and BNs.
     pub enable_latency_measurement_service: bool,
     /// Defines the number of validators per `validator/register_validator` request sent to the BN.
     pub validator_registration_batch_size: usize,
     /// Enable slashing protection even while using web3signer keys.
     pub enable_web3signer_slashing_protection: bool,
+    /// Prune the slashing protection database down to the minimal low-watermark data for each
+    /// validator, rather than keeping a window of recent history.
+    pub slashing_protection_minify: bool,
     /// Specifies the boost factor, a percentage multiplier to apply to the builder's payload value.
     pub builder_boost_factor: Option<u64>,
     /// If true, Lighthouse will prefer builder proposals, if available.
     pub prefer_builder_proposals: bool,
     /// Whether we are running with distributed network support.
     pub distributed: bool,
+    /// If true, refresh proposer duties upon head events from the beacon node rather than polling
+    /// for them every slot.
+    pub proposer_duties_from_events: bool,
+    /// If true, compute attestation selection proofs for the current and next epoch as soon as
+    /// attester duties arrive, rather than shortly before each duty.
+    pub precompute_selection_proofs: bool,
     pub web3_signer_keep_alive_timeout: Option<Duration>,
     pub web3_signer_max_idle_connections: Option<usize>,
+    /// Failures to inject into signing for chaos testing.
+    pub chaos_config: Option<ChaosConfig>,
 }
 
 impl Default for Config {
@@ -121,18 +143,25 @@ impl Default for Config {
             enable_doppelganger_protection: false,
             enable_high_validator_count_metrics: false,
             beacon_nodes_tls_certs: None,
+            beacon_nodes_api_token_path: None,
             builder_proposals: false,
             builder_registration_timestamp_override: None,
+            builder_registration_expiry: None,
             gas_limit: None,
             broadcast_topics: vec![ApiTopic::Subscriptions],
+            broadcast_validation: None,
             enable_latency_measurement_service: true,
             validator_registration_batch_size: 500,
             enable_web3signer_slashing_protection: true,
+            slashing_protection_minify: false,
             builder_boost_factor: None,
             prefer_builder_proposals: false,
             distributed: false,
+            proposer_duties_from_events: false,
+            precompute_selection_proofs: false,
             web3_signer_keep_alive_timeout: DEFAULT_WEB3SIGNER_KEEP_ALIVE,
             web3_signer_max_idle_connections: None,
+            chaos_config: None,
         }
     }
 }
@@ -234,6 +263,9 @@ impl Config {
             config.beacon_nodes_tls_certs = Some(tls_certs.split(',').map(PathBuf::from).collect());
         }
 
+        config.beacon_nodes_api_token_path =
+            parse_optional(cli_args, "beacon-nodes-api-token-file")?;
+
         if cli_args.get_flag("distributed") {
             config.distributed = true;
         }
@@ -282,8 +314,25 @@ impl Config {
             config.http_api.enabled = true;
         }
 
+        if let (Some(cert), Some(key)) = (
+            cli_args.get_one::<String>("http-tls-cert"),
+            cli_args.get_one::<String>("http-tls-key"),
+        ) {
+            config.http_api.tls_config = Some(http_api::TlsConfig {
+                cert: cert
+                    .parse::<PathBuf>()
+                    .map_err(|_| "http-tls-cert is not a valid path name.")?,
+                key: key
+                    .parse::<PathBuf>()
+                    .map_err(|_| "http-tls-key is not a valid path name.")?,
+            });
+        }
+
         if let Some(address) = cli_args.get_one::<String>("http-address") {
-            if cli_args.get_flag("unencrypted-http-transport") {
+            // Serving over TLS is an alternative to acknowledging the unencrypted transport.
+            if cli_args.get_flag("unencrypted-http-transport")
+                || config.http_api.tls_config.is_some()
+            {
                 config.http_api.listen_addr = address
                     .parse::<IpAddr>()
                     .map_err(|_| "http-address is not a valid IP address.")?;
@@ -318,6 +367,10 @@ impl Config {
             config.http_api.store_passwords_in_secrets_dir = true;
         }
 
+        if let Some(grace_period) = parse_optional::<u64>(cli_args, "http-token-grace-period")? {
+            config.http_api.token_grace_period = Duration::from_secs(grace_period);
+        }
+
         /*
          * Prometheus metrics HTTP server
          */
@@ -361,10 +414,13 @@ impl Config {
         if let Some(monitoring_endpoint) = cli_args.get_one::<String>("monitoring-endpoint") {
             let update_period_secs =
                 clap_utils::parse_optional(cli_args, "monitoring-endpoint-period")?;
+            let bearer_token_path =
+                clap_utils::parse_optional(cli_args, "monitoring-endpoint-token-file")?;
             config.monitoring_api = Some(monitoring_api::Config {
                 db_path: None,
                 freezer_db_path: None,
                 update_period_secs,
+                bearer_token_path,
                 monitoring_endpoint: monitoring_endpoint.to_string(),
             });
         }
@@ -408,8 +464,17 @@ impl Config {
             );
         }
 
+        if let Some(expiry) = parse_optional::<u64>(cli_args, "builder-registration-expiry")? {
+            if expiry == 0 {
+                return Err("builder-registration-expiry cannot be 0".to_string());
+            }
+            config.builder_registration_expiry = Some(Duration::from_secs(expiry));
+        }
+
         config.builder_boost_factor = parse_optional(cli_args, "builder-boost-factor")?;
 
+        config.broadcast_validation = parse_optional(cli_args, "broadcast-validation")?;
+
         config.enable_latency_measurement_service =
             !cli_args.get_flag("disable-latency-measurement-service");
 
@@ -430,6 +495,22 @@ impl Config {
             return Err("validator-registration-batch-size cannot be 0".to_string());
         }
 
+        config.slashing_protection_minify = cli_args.get_flag("slashing-protection-minify");
+
+        config.proposer_duties_from_events = cli_args.get_flag("proposer-duties-from-events");
+
+        config.precompute_selection_proofs = cli_args.get_flag("precompute-selection-proofs");
+
+        if let Some(chaos_config_path) = cli_args.get_one::<String>("chaos-config") {
+            config.chaos_config = Some(ChaosConfig::from_file(Path::new(chaos_config_path))?);
+            warn!(
+                log,
+                "Chaos config enabled";
+                "msg" => "duties will be delayed or dropped, do not use this on mainnet",
+                "path" => chaos_config_path,
+            );
+        }
+
         config.enable_web3signer_slashing_protection =
             if cli_args.get_flag("disable-slashing-protection-web3signer") {
                 warn!(
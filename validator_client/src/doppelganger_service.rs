@@ -34,6 +34,7 @@ use crate::validator_store::ValidatorStore;
 use crate::OfflineOnFailure;
 use environment::RuntimeContext;
 use eth2::types::LivenessResponseData;
+use eth2::{BeaconNodeHttpClient, StatusCode};
 use parking_lot::RwLock;
 use slog::{crit, error, info, Logger};
 use slot_clock::SlotClock;
@@ -151,6 +152,45 @@ impl DoppelgangerState {
     }
 }
 
+/// Request the liveness data for `validator_indices` in `epoch` from a single BN.
+///
+/// The standard `validator/liveness/{epoch}` endpoint is preferred. If the BN doesn't support it
+/// (e.g. an older Lighthouse BN), fall back to the Lighthouse-specific `lighthouse/liveness`
+/// endpoint.
+async fn epoch_liveness(
+    beacon_node: &BeaconNodeHttpClient,
+    epoch: Epoch,
+    validator_indices: &[u64],
+) -> Result<Vec<LivenessResponseData>, String> {
+    match beacon_node
+        .post_validator_liveness_epoch(epoch, validator_indices)
+        .await
+    {
+        Ok(result) => Ok(result
+            .data
+            .into_iter()
+            .map(|response| LivenessResponseData {
+                index: response.index,
+                epoch,
+                is_live: response.is_live,
+            })
+            .collect()),
+        Err(e)
+            if matches!(
+                e.status(),
+                Some(StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED)
+            ) =>
+        {
+            beacon_node
+                .post_lighthouse_liveness(validator_indices, epoch)
+                .await
+                .map(|result| result.data)
+                .map_err(|e| format!("Failed query for validator liveness: {:?}", e))
+        }
+        Err(e) => Err(format!("Failed query for validator liveness: {:?}", e)),
+    }
+}
+
 /// Perform two requests to the BN to obtain the liveness data for `validator_indices`. One
 /// request will pertain to the `current_epoch`, the other to the `previous_epoch`.
 ///
@@ -179,21 +219,7 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
                 RequireSynced::Yes,
                 OfflineOnFailure::Yes,
                 |beacon_node| async {
-                    beacon_node
-                        .post_validator_liveness_epoch(previous_epoch, &validator_indices)
-                        .await
-                        .map_err(|e| format!("Failed query for validator liveness: {:?}", e))
-                        .map(|result| {
-                            result
-                                .data
-                                .into_iter()
-                                .map(|response| LivenessResponseData {
-                                    index: response.index,
-                                    epoch: previous_epoch,
-                                    is_live: response.is_live,
-                                })
-                                .collect()
-                        })
+                    epoch_liveness(beacon_node, previous_epoch, &validator_indices).await
                 },
             )
             .await
@@ -216,21 +242,7 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
             RequireSynced::Yes,
             OfflineOnFailure::Yes,
             |beacon_node| async {
-                beacon_node
-                    .post_validator_liveness_epoch(current_epoch, &validator_indices)
-                    .await
-                    .map_err(|e| format!("Failed query for validator liveness: {:?}", e))
-                    .map(|result| {
-                        result
-                            .data
-                            .into_iter()
-                            .map(|response| LivenessResponseData {
-                                index: response.index,
-                                epoch: current_epoch,
-                                is_live: response.is_live,
-                            })
-                            .collect()
-                    })
+                epoch_liveness(beacon_node, current_epoch, &validator_indices).await
             },
         )
         .await
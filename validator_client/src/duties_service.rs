@@ -6,6 +6,7 @@
 //! The `DutiesService` is also responsible for sending events to the `BlockService` which trigger
 //! block production.
 
+pub mod proposer_events;
 pub mod sync;
 
 use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, OfflineOnFailure, RequireSynced};
@@ -19,8 +20,8 @@ use environment::RuntimeContext;
 use eth2::types::{
     AttesterData, BeaconCommitteeSubscription, DutiesResponse, ProposerData, StateId, ValidatorId,
 };
-use futures::{stream, StreamExt};
 use parking_lot::RwLock;
+use proposer_events::ProposerEvents;
 use safe_arith::{ArithError, SafeArith};
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
@@ -94,6 +95,8 @@ const _: () = assert!(ATTESTATION_SUBSCRIPTION_OFFSETS[0] > MIN_ATTESTATION_SUBS
 pub enum Error {
     UnableToReadSlotClock,
     FailedToDownloadAttesters(#[allow(dead_code)] String),
+    FailedToDownloadProposers(#[allow(dead_code)] String),
+    FailedToSubscribe(#[allow(dead_code)] String),
     FailedToProduceSelectionProof(#[allow(dead_code)] ValidatorStoreError),
     InvalidModulo(#[allow(dead_code)] ArithError),
     Arith(#[allow(dead_code)] ArithError),
@@ -126,31 +129,41 @@ pub struct SubscriptionSlots {
     duty_slot: Slot,
 }
 
-/// Create a selection proof for `duty`.
+/// Create a selection proof for each of the `duties`, in the same order.
 ///
-/// Return `Ok(None)` if the attesting validator is not an aggregator.
-async fn make_selection_proof<T: SlotClock + 'static, E: EthSpec>(
-    duty: &AttesterData,
+/// The proofs are signed as a single batch. A result is `Ok(None)` if the attesting validator is not
+/// an aggregator.
+async fn make_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
+    duties: &[AttesterData],
     validator_store: &ValidatorStore<T, E>,
     spec: &ChainSpec,
-) -> Result<Option<SelectionProof>, Error> {
-    let selection_proof = validator_store
-        .produce_selection_proof(duty.pubkey, duty.slot)
-        .await
-        .map_err(Error::FailedToProduceSelectionProof)?;
-
-    selection_proof
-        .is_aggregator(duty.committee_length as usize, spec)
-        .map_err(Error::InvalidModulo)
-        .map(|is_aggregator| {
-            if is_aggregator {
-                Some(selection_proof)
-            } else {
-                // Don't bother storing the selection proof if the validator isn't an
-                // aggregator, we won't need it.
-                None
-            }
+) -> Vec<Result<Option<SelectionProof>, Error>> {
+    let requests = duties
+        .iter()
+        .map(|duty| (duty.pubkey, duty.slot))
+        .collect::<Vec<_>>();
+    let selection_proofs = validator_store.produce_selection_proofs(&requests).await;
+
+    duties
+        .iter()
+        .zip(selection_proofs)
+        .map(|(duty, selection_proof)| {
+            let selection_proof = selection_proof.map_err(Error::FailedToProduceSelectionProof)?;
+
+            selection_proof
+                .is_aggregator(duty.committee_length as usize, spec)
+                .map_err(Error::InvalidModulo)
+                .map(|is_aggregator| {
+                    if is_aggregator {
+                        Some(selection_proof)
+                    } else {
+                        // Don't bother storing the selection proof if the validator isn't an
+                        // aggregator, we won't need it.
+                        None
+                    }
+                })
         })
+        .collect()
 }
 
 impl DutyAndProof {
@@ -234,6 +247,11 @@ pub struct DutiesService<T, E: EthSpec> {
     pub enable_high_validator_count_metrics: bool,
     /// If this validator is running in distributed mode.
     pub distributed: bool,
+    /// If true, compute the selection proofs for all attester duties as soon as they arrive,
+    /// rather than shortly before each duty.
+    pub precompute_selection_proofs: bool,
+    /// Present if proposer duties are refreshed upon head events rather than every slot.
+    pub proposer_events: Option<ProposerEvents>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
@@ -377,6 +395,20 @@ pub fn start_update_service<T: SlotClock + 'static, E: EthSpec>(
         "duties_service_indices",
     );
 
+    /*
+     * Spawn the task which refreshes local block proposal duties upon head events, if enabled.
+     */
+    if core_duties_service.proposer_events.is_some() {
+        let duties_service = core_duties_service.clone();
+        let block_service_tx = block_service_tx.clone();
+        core_duties_service.context.executor.spawn(
+            async move {
+                proposer_events::subscribe_to_head_events(duties_service, block_service_tx).await
+            },
+            "duties_service_proposer_events",
+        );
+    }
+
     /*
      * Spawn the task which keeps track of local block proposal duties.
      */
@@ -468,6 +500,95 @@ pub fn start_update_service<T: SlotClock + 'static, E: EthSpec>(
     );
 }
 
+/// Download the duties of local validators for the genesis epoch prior to genesis.
+///
+/// This allows the first attestations to be produced in the genesis slot and the first proposals
+/// to be made without waiting for duties. Subscriptions for all genesis epoch attestations are
+/// sent immediately, since the regular subscription schedule starts too late for the first slots.
+pub async fn prepare_genesis_duties<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &Arc<DutiesService<T, E>>,
+) -> Result<(), Error> {
+    let log = duties_service.context.log();
+    let genesis_epoch = E::genesis_epoch();
+
+    poll_validator_indices(duties_service).await;
+
+    let local_pubkeys: HashSet<_> = duties_service
+        .validator_store
+        .voting_pubkeys(DoppelgangerStatus::ignored);
+    let local_indices = get_local_indices(duties_service, &local_pubkeys);
+
+    if local_indices.is_empty() {
+        debug!(log, "No active validators at genesis");
+        return Ok(());
+    }
+
+    poll_beacon_attesters_for_epoch(
+        duties_service,
+        genesis_epoch,
+        &local_indices,
+        &local_pubkeys,
+    )
+    .await?;
+    poll_beacon_proposers_for_epoch(duties_service, genesis_epoch, &local_pubkeys).await?;
+
+    let genesis_duties = duties_service
+        .attesters
+        .read()
+        .values()
+        .filter_map(|map| map.get(&genesis_epoch))
+        .map(|(_, duty_and_proof)| duty_and_proof.duty.clone())
+        .collect::<Vec<_>>();
+
+    // The selection proofs are usually computed in the background, but they are needed now so
+    // that aggregators in the first slots are subscribed to their subnets.
+    let selection_proofs = make_selection_proofs(
+        &genesis_duties,
+        &duties_service.validator_store,
+        &duties_service.spec,
+    )
+    .await;
+    let mut subscriptions = Vec::with_capacity(genesis_duties.len());
+    for (duty, selection_proof) in genesis_duties.into_iter().zip(selection_proofs) {
+        let is_aggregator = selection_proof?.is_some();
+        subscriptions.push(BeaconCommitteeSubscription {
+            validator_index: duty.validator_index,
+            committee_index: duty.committee_index,
+            committees_at_slot: duty.committees_at_slot,
+            slot: duty.slot,
+            is_aggregator,
+        });
+    }
+
+    if !subscriptions.is_empty() {
+        let subscriptions_ref = &subscriptions;
+        duties_service
+            .beacon_nodes
+            .request(
+                RequireSynced::No,
+                OfflineOnFailure::Yes,
+                ApiTopic::Subscriptions,
+                |beacon_node| async move {
+                    beacon_node
+                        .post_validator_beacon_committee_subscriptions(subscriptions_ref)
+                        .await
+                },
+            )
+            .await
+            .map_err(|e| Error::FailedToSubscribe(e.to_string()))?;
+    }
+
+    info!(
+        log,
+        "Prepared genesis duties";
+        "validators" => local_indices.len(),
+        "attestation_subscriptions" => subscriptions.len(),
+        "proposals" => duties_service.proposer_count(genesis_epoch),
+    );
+
+    Ok(())
+}
+
 /// Iterate through all the voting pubkeys in the `ValidatorStore` and attempt to learn any unknown
 /// validator indices.
 async fn poll_validator_indices<T: SlotClock + 'static, E: EthSpec>(
@@ -630,18 +751,7 @@ async fn poll_beacon_attesters<T: SlotClock + 'static, E: EthSpec>(
         .validator_store
         .voting_pubkeys(DoppelgangerStatus::ignored);
 
-    let local_indices = {
-        let mut local_indices = Vec::with_capacity(local_pubkeys.len());
-
-        let vals_ref = duties_service.validator_store.initialized_validators();
-        let vals = vals_ref.read();
-        for &pubkey in &local_pubkeys {
-            if let Some(validator_index) = vals.get_index(&pubkey) {
-                local_indices.push(validator_index)
-            }
-        }
-        local_indices
-    };
+    let local_indices = get_local_indices(duties_service, &local_pubkeys);
 
     // Download the duties and update the duties for the current epoch.
     if let Err(e) = poll_beacon_attesters_for_epoch(
@@ -971,6 +1081,19 @@ async fn poll_beacon_attesters_for_epoch<T: SlotClock + 'static, E: EthSpec>(
     Ok(())
 }
 
+/// Get the indices of the `local_pubkeys` which are known to the beacon chain.
+fn get_local_indices<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    local_pubkeys: &HashSet<PublicKeyBytes>,
+) -> Vec<u64> {
+    let vals_ref = duties_service.validator_store.initialized_validators();
+    let vals = vals_ref.read();
+    local_pubkeys
+        .iter()
+        .filter_map(|pubkey| vals.get_index(pubkey))
+        .collect()
+}
+
 /// Get a filtered list of local validators for which we don't already know their duties for that epoch
 fn get_uninitialized_validators<T: SlotClock + 'static, E: EthSpec>(
     duties_service: &Arc<DutiesService<T, E>>,
@@ -1056,8 +1179,9 @@ async fn post_validator_duties_attester<T: SlotClock + 'static, E: EthSpec>(
 
 /// Compute the attestation selection proofs for the `duties` and add them to the `attesters` map.
 ///
-/// Duties are computed in batches each slot. If a re-org is detected then the process will
-/// terminate early as it is assumed the selection proofs from `duties` are no longer relevant.
+/// Duties are computed in batches each slot, or all at once if `precompute_selection_proofs` is
+/// set. If a re-org is detected then the process will terminate early as it is assumed the
+/// selection proofs from `duties` are no longer relevant.
 async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
     duties_service: Arc<DutiesService<T, E>>,
     duties: Vec<AttesterData>,
@@ -1076,23 +1200,29 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
     // of selection proofs and insert them into the duties service `attesters` map.
     let slot_clock = &duties_service.slot_clock;
     let slot_offset = duties_service.slot_clock.slot_duration() / SELECTION_PROOF_SCHEDULE_DENOM;
+    let precompute = duties_service.precompute_selection_proofs;
 
     while !duties_by_slot.is_empty() {
         if let Some(duration) = slot_clock.duration_to_next_slot() {
-            sleep(duration.saturating_sub(slot_offset)).await;
+            // When pre-computing, sign all of the selection proofs as soon as the duties arrive.
+            if !precompute {
+                sleep(duration.saturating_sub(slot_offset)).await;
+            }
 
-            let Some(current_slot) = slot_clock.now() else {
+            // Prior to genesis, sign the selection proofs for the first slots ahead of time.
+            let Some(current_slot) = slot_clock.now_or_genesis() else {
+                sleep(duration).await;
                 continue;
             };
 
-            let selection_lookahead = if duties_service.distributed {
-                SELECTION_PROOF_SLOT_LOOKAHEAD_DVT
+            let lookahead_slot = if precompute {
+                Slot::new(u64::MAX)
+            } else if duties_service.distributed {
+                current_slot + SELECTION_PROOF_SLOT_LOOKAHEAD_DVT
             } else {
-                SELECTION_PROOF_SLOT_LOOKAHEAD
+                current_slot + SELECTION_PROOF_SLOT_LOOKAHEAD
             };
 
-            let lookahead_slot = current_slot + selection_lookahead;
-
             let mut relevant_duties = duties_by_slot.split_off(&lookahead_slot);
             std::mem::swap(&mut relevant_duties, &mut duties_by_slot);
 
@@ -1107,19 +1237,21 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
                 &[metrics::ATTESTATION_SELECTION_PROOFS],
             );
 
-            // Sign selection proofs (serially).
-            let duty_and_proof_results = stream::iter(relevant_duties.into_values().flatten())
-                .then(|duty| async {
-                    let opt_selection_proof = make_selection_proof(
-                        &duty,
-                        &duties_service.validator_store,
-                        &duties_service.spec,
-                    )
-                    .await?;
-                    Ok((duty, opt_selection_proof))
-                })
-                .collect::<Vec<_>>()
-                .await;
+            // Sign the selection proofs as a single batch.
+            let duties = relevant_duties.into_values().flatten().collect::<Vec<_>>();
+            let selection_proofs = make_selection_proofs(
+                &duties,
+                &duties_service.validator_store,
+                &duties_service.spec,
+            )
+            .await;
+            let duty_and_proof_results =
+                duties
+                    .into_iter()
+                    .zip(selection_proofs)
+                    .map(|(duty, selection_proof)| {
+                        selection_proof.map(|selection_proof| (duty, selection_proof))
+                    });
 
             // Add to attesters store.
             let mut attesters = duties_service.attesters.write();
@@ -1268,105 +1400,151 @@ async fn poll_beacon_proposers<T: SlotClock + 'static, E: EthSpec>(
         .validator_store
         .voting_pubkeys(DoppelgangerStatus::ignored);
 
+    // If proposer duties are refreshed upon head events, only download them if they might be
+    // missing or stale.
+    let cached = duties_service.proposers.read().contains_key(&current_epoch);
+    let should_poll = duties_service
+        .proposer_events
+        .as_ref()
+        .map_or(true, |events| events.should_poll(cached, &local_pubkeys));
+
     // Only download duties and push out additional block production events if we have some
     // validators.
-    if !local_pubkeys.is_empty() {
-        let download_result = duties_service
-            .beacon_nodes
-            .first_success(
-                RequireSynced::No,
-                OfflineOnFailure::Yes,
-                |beacon_node| async move {
-                    let _timer = metrics::start_timer_vec(
-                        &metrics::DUTIES_SERVICE_TIMES,
-                        &[metrics::PROPOSER_DUTIES_HTTP_GET],
-                    );
-                    beacon_node
-                        .get_validator_duties_proposer(current_epoch)
-                        .await
-                },
-            )
-            .await;
+    if !local_pubkeys.is_empty() && should_poll {
+        update_proposers_and_notify(
+            duties_service,
+            current_slot,
+            &initial_block_proposers,
+            &local_pubkeys,
+            block_service_tx,
+        )
+        .await;
+    }
 
-        match download_result {
-            Ok(response) => {
-                let dependent_root = response.dependent_root;
+    // Prune old duties.
+    duties_service
+        .proposers
+        .write()
+        .retain(|&epoch, _| epoch + HISTORICAL_DUTIES_EPOCHS >= current_epoch);
 
-                let relevant_duties = response
-                    .data
-                    .into_iter()
-                    .filter(|proposer_duty| local_pubkeys.contains(&proposer_duty.pubkey))
-                    .collect::<Vec<_>>();
+    Ok(())
+}
 
-                debug!(
-                    log,
-                    "Downloaded proposer duties";
-                    "dependent_root" => %dependent_root,
-                    "num_relevant_duties" => relevant_duties.len(),
-                );
+/// Download the proposer duties for the epoch of `current_slot`, then notify the block service of
+/// any proposers at `current_slot` which weren't in `initial_block_proposers`.
+async fn update_proposers_and_notify<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    current_slot: Slot,
+    initial_block_proposers: &HashSet<PublicKeyBytes>,
+    local_pubkeys: &HashSet<PublicKeyBytes>,
+    block_service_tx: &mut Sender<BlockServiceNotification>,
+) {
+    let log = duties_service.context.log();
+    let current_epoch = current_slot.epoch(E::slots_per_epoch());
 
-                if let Some((prior_dependent_root, _)) = duties_service
-                    .proposers
-                    .write()
-                    .insert(current_epoch, (dependent_root, relevant_duties))
-                {
-                    if dependent_root != prior_dependent_root {
-                        warn!(
-                            log,
-                            "Proposer duties re-org";
-                            "prior_dependent_root" => %prior_dependent_root,
-                            "dependent_root" => %dependent_root,
-                            "msg" => "this may happen from time to time"
-                        )
-                    }
-                }
+    // Don't return early on error, we still want to try and produce blocks using the cached
+    // values.
+    match poll_beacon_proposers_for_epoch(duties_service, current_epoch, local_pubkeys).await {
+        Ok(()) => {
+            if let Some(proposer_events) = &duties_service.proposer_events {
+                proposer_events.record_poll(local_pubkeys);
             }
-            // Don't return early here, we still want to try and produce blocks using the cached values.
-            Err(e) => error!(
-                log,
-                "Failed to download proposer duties";
-                "err" => %e,
-            ),
         }
+        Err(e) => error!(
+            log,
+            "Failed to download proposer duties";
+            "err" => ?e,
+        ),
+    }
 
-        // Compute the block proposers for this slot again, now that we've received an update from
-        // the BN.
-        //
-        // Then, compute the difference between these two sets to obtain a set of block proposers
-        // which were not included in the initial notification to the `BlockService`.
-        let additional_block_producers = duties_service
-            .block_proposers(current_slot)
-            .difference(&initial_block_proposers)
-            .copied()
-            .collect::<HashSet<PublicKeyBytes>>();
-
-        // If there are any new proposers for this slot, send a notification so they produce a
-        // block.
-        //
-        // See the function-level documentation for more reasoning about this behaviour.
-        if !additional_block_producers.is_empty() {
-            notify_block_production_service(
-                current_slot,
-                &additional_block_producers,
-                block_service_tx,
-                &duties_service.validator_store,
-                log,
-            )
-            .await;
-            debug!(
-                log,
-                "Detected new block proposer";
-                "current_slot" => current_slot,
-            );
-            metrics::inc_counter(&metrics::PROPOSAL_CHANGED);
-        }
+    // Compute the block proposers for this slot again, now that we've received an update from
+    // the BN.
+    //
+    // Then, compute the difference between these two sets to obtain a set of block proposers
+    // which were not included in the initial notification to the `BlockService`.
+    let additional_block_producers = duties_service
+        .block_proposers(current_slot)
+        .difference(initial_block_proposers)
+        .copied()
+        .collect::<HashSet<PublicKeyBytes>>();
+
+    // If there are any new proposers for this slot, send a notification so they produce a
+    // block.
+    //
+    // See the documentation of `poll_beacon_proposers` for more reasoning about this behaviour.
+    if !additional_block_producers.is_empty() {
+        notify_block_production_service(
+            current_slot,
+            &additional_block_producers,
+            block_service_tx,
+            &duties_service.validator_store,
+            log,
+        )
+        .await;
+        debug!(
+            log,
+            "Detected new block proposer";
+            "current_slot" => current_slot,
+        );
+        metrics::inc_counter(&metrics::PROPOSAL_CHANGED);
     }
+}
 
-    // Prune old duties.
-    duties_service
+/// Download the proposer duties for `epoch` and store those of the `local_pubkeys` in
+/// `duties_service.proposers`.
+async fn poll_beacon_proposers_for_epoch<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    epoch: Epoch,
+    local_pubkeys: &HashSet<PublicKeyBytes>,
+) -> Result<(), Error> {
+    let log = duties_service.context.log();
+
+    let response = duties_service
+        .beacon_nodes
+        .first_success(
+            RequireSynced::No,
+            OfflineOnFailure::Yes,
+            |beacon_node| async move {
+                let _timer = metrics::start_timer_vec(
+                    &metrics::DUTIES_SERVICE_TIMES,
+                    &[metrics::PROPOSER_DUTIES_HTTP_GET],
+                );
+                beacon_node.get_validator_duties_proposer(epoch).await
+            },
+        )
+        .await
+        .map_err(|e| Error::FailedToDownloadProposers(e.to_string()))?;
+
+    let dependent_root = response.dependent_root;
+
+    let relevant_duties = response
+        .data
+        .into_iter()
+        .filter(|proposer_duty| local_pubkeys.contains(&proposer_duty.pubkey))
+        .collect::<Vec<_>>();
+
+    debug!(
+        log,
+        "Downloaded proposer duties";
+        "dependent_root" => %dependent_root,
+        "num_relevant_duties" => relevant_duties.len(),
+    );
+
+    if let Some((prior_dependent_root, _)) = duties_service
         .proposers
         .write()
-        .retain(|&epoch, _| epoch + HISTORICAL_DUTIES_EPOCHS >= current_epoch);
+        .insert(epoch, (dependent_root, relevant_duties))
+    {
+        if dependent_root != prior_dependent_root {
+            warn!(
+                log,
+                "Proposer duties re-org";
+                "prior_dependent_root" => %prior_dependent_root,
+                "dependent_root" => %dependent_root,
+                "msg" => "this may happen from time to time"
+            )
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,220 @@
+//! Refreshes proposer duties upon head events from the beacon node.
+//!
+//! The proposer duties of an epoch only change when a re-org changes the block at the end of the
+//! previous epoch, which is identified by the `current_duty_dependent_root` of each head event.
+//! Whilst a head event stream is connected the duties service only downloads proposer duties at
+//! the start of each epoch, or as soon as a head event reveals that the cached duties are stale.
+//! Otherwise, proposer duties are downloaded every slot as usual.
+use crate::beacon_node_fallback::{OfflineOnFailure, RequireSynced};
+use crate::block_service::BlockServiceNotification;
+use crate::doppelganger_service::DoppelgangerStatus;
+use crate::duties_service::{update_proposers_and_notify, DutiesService, Error};
+use eth2::types::{EventKind, EventTopic, SseHead};
+use eth2::Error as ApiError;
+use futures::StreamExt;
+use parking_lot::RwLock;
+use slog::{debug, error, warn};
+use slot_clock::SlotClock;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::{sync::mpsc::Sender, time::sleep};
+use types::{EthSpec, PublicKeyBytes};
+
+/// Tracks whether the cached proposer duties are kept up-to-date by head events.
+#[derive(Default)]
+pub struct ProposerEvents {
+    /// Whether a head event stream is currently connected.
+    connected: AtomicBool,
+    /// The local pubkeys for which proposer duties were last downloaded whilst connected.
+    polled_pubkeys: RwLock<Option<HashSet<PublicKeyBytes>>>,
+}
+
+impl ProposerEvents {
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if proposer duties must be downloaded this slot, given whether duties are
+    /// cached for the current epoch and the current set of `local_pubkeys`.
+    pub fn should_poll(&self, cached: bool, local_pubkeys: &HashSet<PublicKeyBytes>) -> bool {
+        !self.is_connected()
+            || !cached
+            || self.polled_pubkeys.read().as_ref() != Some(local_pubkeys)
+    }
+
+    /// Record that proposer duties have been downloaded for `local_pubkeys`.
+    pub fn record_poll(&self, local_pubkeys: &HashSet<PublicKeyBytes>) {
+        *self.polled_pubkeys.write() = Some(local_pubkeys.clone());
+    }
+
+    /// Events may have been missed whilst disconnected, so the duties are downloaded again upon
+    /// each connection.
+    fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::Relaxed);
+        *self.polled_pubkeys.write() = None;
+    }
+}
+
+/// Subscribe to head events from the beacon nodes, refreshing the proposer duties whenever a head
+/// event reveals that they are stale. Reconnects whenever the stream ends.
+pub async fn subscribe_to_head_events<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: Arc<DutiesService<T, E>>,
+    mut block_service_tx: Sender<BlockServiceNotification>,
+) {
+    let Some(proposer_events) = duties_service.proposer_events.as_ref() else {
+        return;
+    };
+    let log = duties_service.context.log();
+    let slot_duration = duties_service.slot_clock.slot_duration();
+    // End each stream after an epoch rather than after the usual request timeout of a slot.
+    let stream_timeout = slot_duration * E::slots_per_epoch() as u32;
+
+    loop {
+        let result = duties_service
+            .beacon_nodes
+            .first_success(
+                RequireSynced::No,
+                OfflineOnFailure::No,
+                |beacon_node| async move {
+                    beacon_node
+                        .get_events_with_timeout::<E>(&[EventTopic::Head], stream_timeout)
+                        .await
+                },
+            )
+            .await;
+
+        match result {
+            Ok(mut events) => {
+                debug!(log, "Subscribed to head events");
+                proposer_events.set_connected(true);
+
+                while let Some(event) = events.next().await {
+                    match event {
+                        Ok(EventKind::Head(head)) => {
+                            if let Err(e) =
+                                on_head_event(&duties_service, &head, &mut block_service_tx).await
+                            {
+                                error!(
+                                    log,
+                                    "Failed to refresh proposer duties";
+                                    "error" => ?e
+                                );
+                            }
+                        }
+                        Ok(_) => (),
+                        Err(ApiError::HttpClient(e)) => {
+                            debug!(
+                                log,
+                                "Head event stream ended";
+                                "error" => %e
+                            );
+                            break;
+                        }
+                        // Keep-alive messages can't be parsed as events.
+                        Err(_) => (),
+                    }
+                }
+
+                proposer_events.set_connected(false);
+            }
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to subscribe to head events";
+                    "error" => %e
+                );
+                sleep(slot_duration).await;
+            }
+        }
+    }
+}
+
+/// Download the proposer duties for the current epoch if the dependent root of `head` differs
+/// from that of the cached duties.
+async fn on_head_event<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    head: &SseHead,
+    block_service_tx: &mut Sender<BlockServiceNotification>,
+) -> Result<(), Error> {
+    let current_slot = duties_service
+        .slot_clock
+        .now()
+        .ok_or(Error::UnableToReadSlotClock)?;
+    let current_epoch = current_slot.epoch(E::slots_per_epoch());
+    let head_epoch = head.slot.epoch(E::slots_per_epoch());
+
+    // If the head is in the previous epoch then it is the last block of that epoch, which
+    // determines the proposers of the current epoch.
+    let dependent_root = if head_epoch == current_epoch {
+        head.current_duty_dependent_root
+    } else if head_epoch + 1 == current_epoch {
+        head.block
+    } else {
+        return Ok(());
+    };
+
+    let cached_dependent_root = duties_service
+        .proposers
+        .read()
+        .get(&current_epoch)
+        .map(|(dependent_root, _)| *dependent_root);
+    if cached_dependent_root == Some(dependent_root) {
+        return Ok(());
+    }
+
+    let local_pubkeys: HashSet<_> = duties_service
+        .validator_store
+        .voting_pubkeys(DoppelgangerStatus::ignored);
+    if local_pubkeys.is_empty() {
+        return Ok(());
+    }
+
+    debug!(
+        duties_service.context.log(),
+        "Head event changed proposer duties";
+        "head_slot" => head.slot,
+        "dependent_root" => %dependent_root,
+        "cached_dependent_root" => ?cached_dependent_root,
+    );
+
+    let initial_block_proposers = duties_service.block_proposers(current_slot);
+    update_proposers_and_notify(
+        duties_service,
+        current_slot,
+        &initial_block_proposers,
+        &local_pubkeys,
+        block_service_tx,
+    )
+    .await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_poll_until_connected_and_polled() {
+        let events = ProposerEvents::default();
+        let pubkeys = HashSet::from([PublicKeyBytes::empty()]);
+
+        // Duties are polled every slot whilst disconnected.
+        events.record_poll(&pubkeys);
+        assert!(events.should_poll(true, &pubkeys));
+
+        // Duties are polled once more upon connection, in case events were missed.
+        events.set_connected(true);
+        assert!(events.should_poll(true, &pubkeys));
+        events.record_poll(&pubkeys);
+        assert!(!events.should_poll(true, &pubkeys));
+
+        // Duties are polled if they aren't cached for the current epoch or the validators change.
+        assert!(events.should_poll(false, &pubkeys));
+        assert!(events.should_poll(true, &HashSet::new()));
+
+        events.set_connected(false);
+        assert!(events.should_poll(true, &pubkeys));
+    }
+}
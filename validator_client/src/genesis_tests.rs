@@ -0,0 +1,267 @@
+//! Tests for the preparation done whilst waiting for genesis, run against a beacon node whose clock
+//! is set before genesis.
+#![cfg(test)]
+#![cfg(not(debug_assertions))]
+
+use crate::beacon_node_fallback::{BeaconNodeFallback, CandidateBeaconNode};
+use crate::duties_service::{prepare_genesis_duties, sync::SyncDutiesMap, DutiesService};
+use crate::initialized_validators::InitializedValidators;
+use crate::preparation_service::{PreparationService, PreparationServiceBuilder};
+use crate::{prepare_for_genesis, Config, ValidatorDefinitions, ValidatorStore};
+use ::http_api::test_utils::InteractiveTester;
+use account_utils::{validator_definitions::PasswordStorage, ZeroizeString};
+use environment::RuntimeContext;
+use eth2_config::Eth2Config;
+use logging::test_logger;
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use slot_clock::{SlotClock, TestingSlotClock};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::{tempdir, TempDir};
+use types::*;
+use validator_dir::{insecure_keys::INSECURE_PASSWORD, Builder as ValidatorDirBuilder};
+
+const VALIDATOR_COUNT: usize = 32;
+/// How long before genesis the validator client is started.
+const TIME_TO_GENESIS: Duration = Duration::from_secs(60);
+/// Shorter than the retry interval whilst waiting for genesis, so preparation which completes
+/// within it succeeded at the first attempt.
+const PREPARATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+type E = MainnetEthSpec;
+
+struct GenesisTester {
+    beacon_node: InteractiveTester<E>,
+    duties_service: Arc<DutiesService<TestingSlotClock, E>>,
+    preparation_service: PreparationService<TestingSlotClock, E>,
+    context: RuntimeContext<E>,
+    _validator_dir: TempDir,
+}
+
+impl GenesisTester {
+    async fn new(builder_proposals: bool) -> Self {
+        let beacon_node = InteractiveTester::<E>::new(None, VALIDATOR_COUNT).await;
+        let chain = beacon_node.harness.chain.clone();
+        let spec = chain.spec.clone();
+        let log = test_logger();
+
+        // Both clients are started before genesis.
+        let genesis_time = Duration::from_secs(chain.genesis_time);
+        chain
+            .slot_clock
+            .set_current_time(genesis_time - TIME_TO_GENESIS);
+        let slot_clock = TestingSlotClock::new(
+            spec.genesis_slot,
+            genesis_time,
+            Duration::from_secs(spec.seconds_per_slot),
+        );
+        slot_clock.set_current_time(genesis_time - TIME_TO_GENESIS);
+
+        let context = RuntimeContext {
+            executor: beacon_node.harness.runtime.task_executor.clone(),
+            eth_spec_instance: E::default(),
+            eth2_config: Eth2Config {
+                eth_spec_id: EthSpecId::Mainnet,
+                spec: spec.clone(),
+            },
+            eth2_network_config: None,
+            sse_logging_components: None,
+            log_levels: None,
+        };
+
+        let validator_dir = tempdir().unwrap();
+        let config = Config {
+            validator_dir: validator_dir.path().into(),
+            fee_recipient: Some(Address::repeat_byte(42)),
+            ..Config::default()
+        };
+        let validator_defs = ValidatorDefinitions::open_or_create(validator_dir.path()).unwrap();
+        let initialized_validators = InitializedValidators::from_definitions(
+            validator_defs,
+            validator_dir.path().into(),
+            Config::default(),
+            log.clone(),
+        )
+        .await
+        .unwrap();
+        let slashing_protection = SlashingDatabase::open_or_create(
+            &validator_dir.path().join(SLASHING_PROTECTION_FILENAME),
+        )
+        .unwrap();
+        let validator_store = Arc::new(ValidatorStore::<_, E>::new(
+            initialized_validators,
+            slashing_protection,
+            chain.genesis_validators_root,
+            spec.clone(),
+            None,
+            slot_clock.clone(),
+            &config,
+            context.executor.clone(),
+            log.clone(),
+        ));
+
+        for i in 0..VALIDATOR_COUNT {
+            let dir = ValidatorDirBuilder::new(validator_dir.path().into())
+                .insecure_voting_keypair(i)
+                .unwrap()
+                .store_withdrawal_keystore(false)
+                .build()
+                .unwrap();
+            // Drop the validator dir so that `add_validator_keystore` can re-lock the keystore.
+            let voting_keystore_path = dir.voting_keystore_path();
+            drop(dir);
+
+            let password = String::from_utf8(INSECURE_PASSWORD.to_vec()).unwrap();
+            validator_store
+                .add_validator_keystore(
+                    voting_keystore_path,
+                    PasswordStorage::ValidatorDefinitions(ZeroizeString::from(password)),
+                    true,
+                    None,
+                    None,
+                    None,
+                    Some(builder_proposals),
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        let mut beacon_nodes = BeaconNodeFallback::new(
+            vec![CandidateBeaconNode::new(beacon_node.client.clone())],
+            vec![],
+            spec.clone(),
+            log,
+        );
+        beacon_nodes.set_slot_clock(slot_clock.clone());
+        let beacon_nodes = Arc::new(beacon_nodes);
+
+        let duties_service = Arc::new(DutiesService {
+            attesters: <_>::default(),
+            proposers: <_>::default(),
+            sync_duties: SyncDutiesMap::new(false),
+            slot_clock: slot_clock.clone(),
+            beacon_nodes: beacon_nodes.clone(),
+            validator_store: validator_store.clone(),
+            unknown_validator_next_poll_slots: <_>::default(),
+            spec,
+            context: context.service_context("duties".into()),
+            enable_high_validator_count_metrics: false,
+            distributed: false,
+            precompute_selection_proofs: false,
+            proposer_events: None,
+        });
+
+        let preparation_service = PreparationServiceBuilder::new()
+            .slot_clock(slot_clock)
+            .validator_store(validator_store)
+            .beacon_nodes(beacon_nodes)
+            .runtime_context(context.service_context("preparation".into()))
+            .validator_registration_batch_size(VALIDATOR_COUNT)
+            .build()
+            .unwrap();
+
+        Self {
+            beacon_node,
+            duties_service,
+            preparation_service,
+            context,
+            _validator_dir: validator_dir,
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn genesis_duties_are_prepared_before_genesis() {
+    let mut tester = GenesisTester::new(false).await;
+    let genesis_epoch = E::genesis_epoch();
+
+    prepare_genesis_duties(&tester.duties_service)
+        .await
+        .unwrap();
+
+    let indices = (0..VALIDATOR_COUNT as u64).collect::<Vec<_>>();
+    let expected_attesters = tester
+        .beacon_node
+        .client
+        .post_validator_duties_attester(genesis_epoch, &indices)
+        .await
+        .unwrap()
+        .data;
+    let expected_proposers = tester
+        .beacon_node
+        .client
+        .get_validator_duties_proposer(genesis_epoch)
+        .await
+        .unwrap()
+        .data;
+
+    // Every validator has an attestation duty in the genesis epoch.
+    assert_eq!(expected_attesters.len(), VALIDATOR_COUNT);
+    {
+        let attesters = tester.duties_service.attesters.read();
+        for expected in &expected_attesters {
+            let (_, duty_and_proof) = attesters
+                .get(&expected.pubkey)
+                .and_then(|epochs| epochs.get(&genesis_epoch))
+                .expect("duty should be known before genesis");
+            assert_eq!(&duty_and_proof.duty, expected);
+        }
+    }
+
+    let proposers = tester.duties_service.proposers.read();
+    let (_, proposers) = proposers
+        .get(&genesis_epoch)
+        .expect("proposers should be known before genesis");
+    assert_eq!(proposers, &expected_proposers);
+
+    // Subnet subscriptions for the genesis epoch are sent to the beacon node.
+    assert!(tester
+        .beacon_node
+        .network_rx
+        .validator_subscription_recv
+        .try_recv()
+        .is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn prepare_for_genesis_completes_before_genesis() {
+    let tester = GenesisTester::new(false).await;
+
+    tokio::time::timeout(
+        PREPARATION_TIMEOUT,
+        prepare_for_genesis(
+            &tester.duties_service,
+            &tester.preparation_service,
+            &tester.context,
+        ),
+    )
+    .await
+    .expect("preparation should succeed without retrying");
+
+    assert_eq!(
+        tester.duties_service.attesters.read().len(),
+        VALIDATOR_COUNT
+    );
+    assert!(tester
+        .duties_service
+        .proposers
+        .read()
+        .contains_key(&E::genesis_epoch()));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unpublished_registrations_fail_genesis_preparation() {
+    // The beacon node has no builder, so the registrations cannot be published.
+    let tester = GenesisTester::new(true).await;
+
+    prepare_genesis_duties(&tester.duties_service)
+        .await
+        .unwrap();
+    assert!(tester
+        .preparation_service
+        .prepare_for_genesis(&tester.context.eth2_config.spec)
+        .await
+        .is_err());
+}
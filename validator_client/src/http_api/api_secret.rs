@@ -1,15 +1,24 @@
 use filesystem::create_with_600_perms;
+use parking_lot::RwLock;
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use warp::Filter;
 
 /// The name of the file which stores the API token.
 pub const PK_FILENAME: &str = "api-token.txt";
 
+/// The name of the file to which a rotated API token is written before it replaces `PK_FILENAME`.
+const PK_TEMP_FILENAME: &str = "api-token.txt.tmp";
+
 pub const PK_LEN: usize = 33;
 
+/// The default period for which the previous API token is still accepted after a rotation.
+pub const DEFAULT_TOKEN_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
 /// Contains a randomly generated string which is used for authorization of requests to the HTTP API.
 ///
 /// Provides convenience functions to ultimately provide:
@@ -23,11 +32,48 @@ pub const PK_LEN: usize = 33;
 ///
 ///  This scheme has since been tweaked to remove VC response signing and secp256k1 key generation.
 ///  https://github.com/sigp/lighthouse/issues/5423
+#[derive(Clone)]
 pub struct ApiSecret {
-    pk: String,
+    tokens: Arc<RwLock<Tokens>>,
     pk_path: PathBuf,
 }
 
+/// The tokens which are currently accepted by the HTTP API.
+struct Tokens {
+    current: String,
+    /// The token replaced by the most recent rotation, and the instant at which it stops being
+    /// accepted.
+    previous: Option<(String, Instant)>,
+}
+
+impl Tokens {
+    /// Returns `true` if `header` is a valid `Authorization` header for either the current token,
+    /// or the previous token if its grace period has not elapsed.
+    fn authorizes(&self, header: &str) -> bool {
+        self.authorizes_current(header)
+            || self.previous.as_ref().map_or(false, |(token, expiry)| {
+                Instant::now() < *expiry && auth_header_values(token).iter().any(|v| v == header)
+            })
+    }
+
+    /// Returns `true` if `header` is a valid `Authorization` header for the current token.
+    fn authorizes_current(&self, header: &str) -> bool {
+        auth_header_values(&self.current)
+            .iter()
+            .any(|v| v == header)
+    }
+}
+
+/// Errors that can occur when rotating the API token.
+#[derive(Debug)]
+pub enum RotateError {
+    /// The request was not authorized with the current token. Only the current token may rotate,
+    /// so that a token which has been replaced can't be used to obtain its successor.
+    NotCurrentToken,
+    /// The new token could not be written to disk.
+    Io(String),
+}
+
 impl ApiSecret {
     /// If the public key is already on-disk, use it.
     ///
@@ -39,12 +85,7 @@ impl ApiSecret {
         let pk_path = dir.as_ref().join(PK_FILENAME);
 
         if !pk_path.exists() {
-            let length = PK_LEN;
-            let pk: String = thread_rng()
-                .sample_iter(&Alphanumeric)
-                .take(length)
-                .map(char::from)
-                .collect();
+            let pk = random_token();
 
             // Create and write the public key to file with appropriate permissions
             create_with_600_perms(&pk_path, pk.to_string().as_bytes()).map_err(|e| {
@@ -61,12 +102,57 @@ impl ApiSecret {
             .map(|&c| char::from(c))
             .collect();
 
-        Ok(Self { pk, pk_path })
+        Ok(Self {
+            tokens: Arc::new(RwLock::new(Tokens {
+                current: pk,
+                previous: None,
+            })),
+            pk_path,
+        })
     }
 
     /// Returns the API token.
     pub fn api_token(&self) -> String {
-        self.pk.clone()
+        self.tokens.read().current.clone()
+    }
+
+    /// Replaces the API token with a new random token, returning the new token.
+    ///
+    /// The `authorization` header of the request must be valid for the current token. The previous
+    /// token is rejected even during its grace period, so that a leaked token can't be rotated
+    /// forward indefinitely.
+    ///
+    /// The new token is written to a temporary file which is then renamed over the token file, so
+    /// the token file always contains a complete token. The previous token continues to be accepted
+    /// for `grace_period`, giving API consumers time to read the new token.
+    pub fn rotate(
+        &self,
+        authorization: &str,
+        grace_period: Duration,
+    ) -> Result<String, RotateError> {
+        // Hold the lock while writing the file so that concurrent rotations can't leave the file
+        // and the in-memory token out of sync.
+        let mut tokens = self.tokens.write();
+        if !tokens.authorizes_current(authorization) {
+            return Err(RotateError::NotCurrentToken);
+        }
+        let pk = random_token();
+
+        let temp_path = self.pk_path.with_file_name(PK_TEMP_FILENAME);
+        create_with_600_perms(&temp_path, pk.as_bytes()).map_err(|e| {
+            RotateError::Io(format!(
+                "Unable to create file with permissions for {:?}: {:?}",
+                temp_path, e
+            ))
+        })?;
+        fs::rename(&temp_path, &self.pk_path).map_err(|e| {
+            RotateError::Io(format!("Unable to replace {:?}: {:?}", self.pk_path, e))
+        })?;
+
+        let previous = std::mem::replace(&mut tokens.current, pk.clone());
+        tokens.previous = Some((previous, Instant::now() + grace_period));
+
+        Ok(pk)
     }
 
     /// Returns the path for the API token file
@@ -74,34 +160,111 @@ impl ApiSecret {
         self.pk_path.clone()
     }
 
-    /// Returns the values of the `Authorization` header which indicate a valid incoming HTTP
-    /// request.
-    ///
-    /// For backwards-compatibility we accept the token in a basic authentication style, but this is
-    /// technically invalid according to RFC 7617 because the token is not a base64-encoded username
-    /// and password. As such, bearer authentication should be preferred.
-    fn auth_header_values(&self) -> Vec<String> {
-        vec![
-            format!("Basic {}", self.api_token()),
-            format!("Bearer {}", self.api_token()),
-        ]
-    }
-
     /// Returns a `warp` header which filters out request that have a missing or inaccurate
     /// `Authorization` header.
+    ///
+    /// The filter always checks against the latest tokens, so it remains valid across rotations.
     pub fn authorization_header_filter(&self) -> warp::filters::BoxedFilter<()> {
-        let expected = self.auth_header_values();
+        let tokens = self.tokens.clone();
         warp::any()
-            .map(move || expected.clone())
+            .map(move || tokens.clone())
             .and(warp::filters::header::header("Authorization"))
-            .and_then(move |expected: Vec<String>, header: String| async move {
-                if expected.contains(&header) {
-                    Ok(())
-                } else {
-                    Err(warp_utils::reject::invalid_auth(header))
-                }
-            })
+            .and_then(
+                move |tokens: Arc<RwLock<Tokens>>, header: String| async move {
+                    if tokens.read().authorizes(&header) {
+                        Ok(())
+                    } else {
+                        Err(warp_utils::reject::invalid_auth(header))
+                    }
+                },
+            )
             .untuple_one()
             .boxed()
     }
 }
+
+/// Generates a new random API token.
+fn random_token() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(PK_LEN)
+        .map(char::from)
+        .collect()
+}
+
+/// Returns the values of the `Authorization` header which indicate a valid incoming HTTP request
+/// for `token`.
+///
+/// For backwards-compatibility we accept the token in a basic authentication style, but this is
+/// technically invalid according to RFC 7617 because the token is not a base64-encoded username
+/// and password. As such, bearer authentication should be preferred.
+fn auth_header_values(token: &str) -> [String; 2] {
+    [format!("Basic {}", token), format!("Bearer {}", token)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn rotate_persists_token_and_expires_previous() {
+        let dir = tempdir().unwrap();
+        let secret = ApiSecret::create_or_open(dir.path()).unwrap();
+        let old_token = secret.api_token();
+
+        let new_token = secret
+            .rotate(&format!("Bearer {old_token}"), Duration::from_secs(60))
+            .unwrap();
+        assert_ne!(new_token, old_token);
+        assert_eq!(secret.api_token(), new_token);
+        assert_eq!(
+            fs::read_to_string(secret.api_token_path()).unwrap(),
+            new_token
+        );
+        assert!(!dir.path().join(PK_TEMP_FILENAME).exists());
+
+        // Re-opening the secret loads the rotated token.
+        let reopened = ApiSecret::create_or_open(dir.path()).unwrap();
+        assert_eq!(reopened.api_token(), new_token);
+
+        // Both tokens are accepted during the grace period.
+        let tokens = secret.tokens.read();
+        assert!(tokens.authorizes(&format!("Bearer {new_token}")));
+        assert!(tokens.authorizes(&format!("Bearer {old_token}")));
+        drop(tokens);
+
+        // Without a grace period only the newest token is accepted.
+        let newest_token = secret
+            .rotate(&format!("Basic {new_token}"), Duration::ZERO)
+            .unwrap();
+        let tokens = secret.tokens.read();
+        assert!(tokens.authorizes(&format!("Bearer {newest_token}")));
+        assert!(!tokens.authorizes(&format!("Bearer {new_token}")));
+        assert!(!tokens.authorizes(&format!("Bearer {old_token}")));
+    }
+    #[test]
+    fn rotate_requires_current_token() {
+        let dir = tempdir().unwrap();
+        let secret = ApiSecret::create_or_open(dir.path()).unwrap();
+        let old_token = secret.api_token();
+        let new_token = secret
+            .rotate(&format!("Bearer {old_token}"), Duration::from_secs(60))
+            .unwrap();
+
+        // The previous token is still accepted during its grace period, but may not rotate.
+        assert!(secret
+            .tokens
+            .read()
+            .authorizes(&format!("Bearer {old_token}")));
+        assert!(matches!(
+            secret.rotate(&format!("Bearer {old_token}"), Duration::from_secs(60)),
+            Err(RotateError::NotCurrentToken)
+        ));
+        assert_eq!(secret.api_token(), new_token);
+        assert_eq!(
+            fs::read_to_string(secret.api_token_path()).unwrap(),
+            new_token
+        );
+    }
+}
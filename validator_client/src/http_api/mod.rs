@@ -11,24 +11,25 @@ pub mod test_utils;
 use crate::http_api::graffiti::{delete_graffiti, get_graffiti, set_graffiti};
 
 use crate::http_api::create_signed_voluntary_exit::create_signed_voluntary_exit;
+use crate::preparation_service::ValidatorRegistrationStatuses;
 use crate::{determine_graffiti, GraffitiFile, ValidatorStore};
 use account_utils::{
     mnemonic_from_phrase,
     validator_definitions::{SigningDefinition, ValidatorDefinition, Web3SignerDefinition},
 };
-pub use api_secret::ApiSecret;
+pub use api_secret::{ApiSecret, RotateError, DEFAULT_TOKEN_GRACE_PERIOD};
 use create_validator::{
     create_validators_mnemonic, create_validators_web3signer, get_voting_password_storage,
 };
 use eth2::lighthouse_vc::{
     std_types::{AuthResponse, GetFeeRecipientResponse, GetGasLimitResponse},
     types::{
-        self as api_types, GenericResponse, GetGraffitiResponse, Graffiti, PublicKey,
-        PublicKeyBytes, SetGraffitiRequest,
+        self as api_types, GenericResponse, GetGraffitiResponse, Graffiti, LogLevelsData,
+        PublicKey, PublicKeyBytes, RotateApiTokenResponse, SetGraffitiRequest,
     },
 };
 use lighthouse_version::version_with_platform;
-use logging::SSELoggingComponents;
+use logging::{LogLevels, SSELoggingComponents};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use slog::{crit, info, warn, Logger};
@@ -38,7 +39,9 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use sysinfo::{System, SystemExt};
 use system_health::observe_system_health_vc;
 use task_executor::TaskExecutor;
@@ -81,10 +84,19 @@ pub struct Context<T: SlotClock, E: EthSpec> {
     pub config: Config,
     pub log: Logger,
     pub sse_logging_components: Option<SSELoggingComponents>,
+    pub log_levels: Option<LogLevels>,
+    pub validator_registration_statuses: Option<Arc<ValidatorRegistrationStatuses>>,
     pub slot_clock: T,
     pub _phantom: PhantomData<E>,
 }
 
+/// The certificate and key used to serve the HTTP API over TLS.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
 /// Configuration for the HTTP server.
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -94,6 +106,9 @@ pub struct Config {
     pub allow_origin: Option<String>,
     pub allow_keystore_export: bool,
     pub store_passwords_in_secrets_dir: bool,
+    pub tls_config: Option<TlsConfig>,
+    /// The period for which the previous API token is still accepted after a rotation.
+    pub token_grace_period: Duration,
 }
 
 impl Default for Config {
@@ -105,6 +120,8 @@ impl Default for Config {
             allow_origin: None,
             allow_keystore_export: false,
             store_passwords_in_secrets_dir: false,
+            tls_config: None,
+            token_grace_period: DEFAULT_TOKEN_GRACE_PERIOD,
         }
     }
 }
@@ -155,6 +172,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     }
 
     let authorization_header_filter = ctx.api_secret.authorization_header_filter();
+    let token_grace_period = config.token_grace_period;
     let mut api_token_path = ctx.api_secret.api_token_path();
 
     // Attempt to convert the path to an absolute path, but don't error if it fails.
@@ -180,6 +198,17 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    let inner_registration_statuses = ctx.validator_registration_statuses.clone();
+    let registration_statuses_filter = warp::any()
+        .map(move || inner_registration_statuses.clone())
+        .and_then(|statuses: Option<_>| async move {
+            statuses.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(
+                    "validator registrations are not initialized.".to_string(),
+                )
+            })
+        });
+
     let inner_task_executor = ctx.task_executor.clone();
     let task_executor_filter = warp::any().map(move || inner_task_executor.clone());
 
@@ -223,10 +252,26 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     let api_token_path_inner = api_token_path.clone();
     let api_token_path_filter = warp::any().map(move || api_token_path_inner.clone());
 
+    let inner_api_secret = ctx.api_secret.clone();
+    let api_secret_filter = warp::any().map(move || inner_api_secret.clone());
+
     // Filter for SEE Logging events
     let inner_components = ctx.sse_logging_components.clone();
     let sse_component_filter = warp::any().map(move || inner_components.clone());
 
+    let inner_log_levels = ctx.log_levels.clone();
+    let log_levels_filter =
+        warp::any()
+            .map(move || inner_log_levels.clone())
+            .and_then(|log_levels| async move {
+                match log_levels {
+                    Some(log_levels) => Ok(log_levels),
+                    None => Err(warp_utils::reject::custom_not_found(
+                        "Log levels cannot be changed at runtime.".to_string(),
+                    )),
+                }
+            });
+
     // Create a `warp` filter that provides access to local system information.
     let system_info = Arc::new(RwLock::new(sysinfo::System::new()));
     {
@@ -308,6 +353,10 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         enabled: def.enabled,
                         description: def.description.clone(),
                         voting_pubkey: PublicKeyBytes::from(&def.voting_public_key),
+                        builder_proposals: def.builder_proposals,
+                        builder_boost_factor: def.builder_boost_factor,
+                        prefer_builder_proposals: def.prefer_builder_proposals,
+                        builder_beacon_nodes: def.builder_beacon_nodes.clone(),
                     })
                     .collect::<Vec<_>>();
 
@@ -334,6 +383,10 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                             enabled: def.enabled,
                             description: def.description.clone(),
                             voting_pubkey: PublicKeyBytes::from(&def.voting_public_key),
+                            builder_proposals: def.builder_proposals,
+                            builder_boost_factor: def.builder_boost_factor,
+                            prefer_builder_proposals: def.prefer_builder_proposals,
+                            builder_beacon_nodes: def.builder_beacon_nodes.clone(),
                         })
                         .ok_or_else(|| {
                             warp_utils::reject::custom_not_found(format!(
@@ -364,6 +417,33 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    // GET lighthouse/validator_registrations
+    let get_lighthouse_validator_registrations = warp::path("lighthouse")
+        .and(warp::path("validator_registrations"))
+        .and(warp::path::end())
+        .and(registration_statuses_filter)
+        .then(|statuses: Arc<ValidatorRegistrationStatuses>| {
+            blocking_json_task(move || {
+                let mut statuses = statuses.read().values().cloned().collect::<Vec<_>>();
+                statuses.sort_by(|a, b| a.pubkey.as_serialized().cmp(b.pubkey.as_serialized()));
+                Ok(api_types::GenericResponse::from(statuses))
+            })
+        });
+
+    // GET lighthouse/logging/levels
+    let get_lighthouse_logging_levels = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path("levels"))
+        .and(warp::path::end())
+        .and(log_levels_filter.clone())
+        .then(|log_levels: LogLevels| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(LogLevelsData {
+                    levels: log_levels.to_string(),
+                }))
+            })
+        });
+
     let get_lighthouse_ui_graffiti = warp::path("lighthouse")
         .and(warp::path("ui"))
         .and(warp::path("graffiti"))
@@ -575,11 +655,74 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         enabled: body.enable,
                         description: validator_def.description,
                         voting_pubkey: keypair.pk.into(),
+                        builder_proposals: validator_def.builder_proposals,
+                        builder_boost_factor: validator_def.builder_boost_factor,
+                        prefer_builder_proposals: validator_def.prefer_builder_proposals,
+                        builder_beacon_nodes: validator_def.builder_beacon_nodes,
+                    }))
+                })
+            },
+        );
+
+    // POST lighthouse/auth/rotate
+    let post_lighthouse_auth_rotate = warp::path("lighthouse")
+        .and(warp::path("auth"))
+        .and(warp::path("rotate"))
+        .and(warp::path::end())
+        .and(warp::header::<String>("Authorization"))
+        .and(api_secret_filter)
+        .and(api_token_path_filter.clone())
+        .and(log_filter.clone())
+        .then(
+            move |authorization: String,
+                  api_secret: ApiSecret,
+                  token_path: PathBuf,
+                  log: Logger| {
+                blocking_json_task(move || {
+                    let token = api_secret
+                        .rotate(&authorization, token_grace_period)
+                        .map_err(|e| match e {
+                            RotateError::NotCurrentToken => warp_utils::reject::invalid_auth(
+                                "only the current API token may rotate the token".to_string(),
+                            ),
+                            RotateError::Io(e) => warp_utils::reject::custom_server_error(e),
+                        })?;
+                    info!(
+                        log,
+                        "Rotated API token";
+                        "grace_period_seconds" => token_grace_period.as_secs(),
+                    );
+                    Ok(GenericResponse::from(RotateApiTokenResponse {
+                        token,
+                        token_path: token_path.display().to_string(),
+                        grace_period_seconds: token_grace_period.as_secs(),
                     }))
                 })
             },
         );
 
+    // POST lighthouse/logging/levels
+    let post_lighthouse_logging_levels = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path("levels"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(log_levels_filter)
+        .and(log_filter.clone())
+        .then(|body: LogLevelsData, log_levels: LogLevels, log: Logger| {
+            blocking_json_task(move || {
+                log_levels
+                    .update(&body.levels)
+                    .map_err(warp_utils::reject::custom_bad_request)?;
+                info!(
+                    log,
+                    "Updated log levels";
+                    "levels" => %log_levels,
+                );
+                Ok(())
+            })
+        });
+
     // POST lighthouse/validators/web3signer
     let post_validators_web3signer = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -605,6 +748,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                 builder_proposals: web3signer.builder_proposals,
                                 builder_boost_factor: web3signer.builder_boost_factor,
                                 prefer_builder_proposals: web3signer.prefer_builder_proposals,
+                                builder_beacon_nodes: None,
                                 description: web3signer.description,
                                 signing_definition: SigningDefinition::Web3Signer(
                                     Web3SignerDefinition {
@@ -656,6 +800,10 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                     }
 
                     let maybe_graffiti = body.graffiti.clone().map(Into::into);
+                    let maybe_builder_beacon_nodes: Option<Vec<String>> = body
+                        .builder_beacon_nodes
+                        .as_ref()
+                        .map(|beacon_nodes| beacon_nodes.iter().map(ToString::to_string).collect());
                     let initialized_validators_rw_lock = validator_store.initialized_validators();
                     let initialized_validators = initialized_validators_rw_lock.upgradable_read();
 
@@ -698,6 +846,10 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                 && equal_or_none(
                                     initialized_validator.get_graffiti(),
                                     maybe_graffiti,
+                                )
+                                && equal_or_none(
+                                    Some(initialized_validator.get_builder_beacon_nodes().to_vec()),
+                                    maybe_builder_beacon_nodes,
                                 ) =>
                         {
                             Ok(())
@@ -710,7 +862,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                 && body.builder_boost_factor.is_none()
                                 && body.builder_proposals.is_none()
                                 && body.prefer_builder_proposals.is_none()
-                                && maybe_graffiti.is_none() =>
+                                && maybe_graffiti.is_none()
+                                && body.builder_beacon_nodes.is_none() =>
                         {
                             Ok(())
                         }
@@ -733,6 +886,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                                 body.builder_boost_factor,
                                                 body.prefer_builder_proposals,
                                                 body.graffiti,
+                                                body.builder_beacon_nodes,
                                             ),
                                     )
                                     .map_err(|e| {
@@ -1253,6 +1407,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_validators_pubkey)
                         .or(get_lighthouse_ui_health)
                         .or(get_lighthouse_ui_graffiti)
+                        .or(get_lighthouse_logging_levels)
+                        .or(get_lighthouse_validator_registrations)
                         .or(get_fee_recipient)
                         .or(get_gas_limit)
                         .or(get_graffiti)
@@ -1271,6 +1427,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(post_std_keystores)
                         .or(post_std_remotekeys)
                         .or(post_graffiti)
+                        .or(post_lighthouse_logging_levels)
+                        .or(post_lighthouse_auth_rotate)
                         .recover(warp_utils::reject::handle_rejection),
                 ))
                 .or(warp::patch()
@@ -1293,12 +1451,30 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
         .with(cors_builder.build());
 
-    let (listening_socket, server) = warp::serve(routes).try_bind_with_graceful_shutdown(
-        SocketAddr::new(config.listen_addr, config.listen_port),
-        async {
-            shutdown.await;
-        },
-    )?;
+    let http_socket = SocketAddr::new(config.listen_addr, config.listen_port);
+    let (listening_socket, server): (SocketAddr, Pin<Box<dyn Future<Output = ()> + Send>>) =
+        match &config.tls_config {
+            Some(tls_config) => {
+                let (socket, server) = warp::serve(routes)
+                    .tls()
+                    .cert_path(&tls_config.cert)
+                    .key_path(&tls_config.key)
+                    .try_bind_with_graceful_shutdown(http_socket, async {
+                        shutdown.await;
+                    })?;
+
+                info!(log, "HTTP API is being served over TLS";);
+
+                (socket, Box::pin(server))
+            }
+            None => {
+                let (socket, server) =
+                    warp::serve(routes).try_bind_with_graceful_shutdown(http_socket, async {
+                        shutdown.await;
+                    })?;
+                (socket, Box::pin(server))
+            }
+        };
 
     info!(
         log,
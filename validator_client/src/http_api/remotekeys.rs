@@ -13,7 +13,7 @@ use slot_clock::SlotClock;
 use std::sync::Arc;
 use task_executor::TaskExecutor;
 use tokio::runtime::Handle;
-use types::{EthSpec, PublicKeyBytes};
+use types::{EthSpec, PublicKey, PublicKeyBytes};
 use url::Url;
 use warp::Rejection;
 use warp_utils::reject::custom_server_error;
@@ -102,18 +102,8 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
         .decompress()
         .map_err(|_| format!("invalid pubkey: {}", pubkey))?;
 
-    if let Some(def) = validator_store
-        .initialized_validators()
-        .read()
-        .validator_definitions()
-        .iter()
-        .find(|def| def.voting_public_key == pubkey)
-    {
-        if def.signing_definition.is_local_keystore() {
-            return Err("Pubkey already present in local keystore.".into());
-        } else if def.enabled {
-            return Ok(ImportRemotekeyStatus::Duplicate);
-        }
+    if let Some(status) = existing_remotekey_status(&pubkey, validator_store)? {
+        return Ok(status);
     }
 
     // Remotekeys are stored as web3signers.
@@ -127,6 +117,7 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        builder_beacon_nodes: None,
         description: String::from("Added by remotekey API"),
         signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
             url,
@@ -136,13 +127,43 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
             client_identity_password: None,
         }),
     };
-    handle
-        .block_on(validator_store.add_validator(web3signer_validator))
-        .map_err(|e| format!("failed to initialize validator: {:?}", e))?;
+    if let Err(e) = handle.block_on(validator_store.add_validator(web3signer_validator)) {
+        // A concurrent request may have imported the same key since it was checked above, in
+        // which case the key is a duplicate rather than an error.
+        if let Some(status) = existing_remotekey_status(&pubkey, validator_store)? {
+            return Ok(status);
+        }
+        return Err(format!("failed to initialize validator: {:?}", e));
+    }
 
     Ok(ImportRemotekeyStatus::Imported)
 }
 
+/// Returns the status of importing `pubkey` if it already has an enabled validator definition, or
+/// an error if it is already present as a local keystore.
+fn existing_remotekey_status<T: SlotClock + 'static, E: EthSpec>(
+    pubkey: &PublicKey,
+    validator_store: &ValidatorStore<T, E>,
+) -> Result<Option<ImportRemotekeyStatus>, String> {
+    let initialized_validators = validator_store.initialized_validators();
+    let initialized_validators = initialized_validators.read();
+    let Some(def) = initialized_validators
+        .validator_definitions()
+        .iter()
+        .find(|def| def.voting_public_key == *pubkey)
+    else {
+        return Ok(None);
+    };
+
+    if def.signing_definition.is_local_keystore() {
+        Err("Pubkey already present in local keystore.".into())
+    } else if def.enabled {
+        Ok(Some(ImportRemotekeyStatus::Duplicate))
+    } else {
+        Ok(None)
+    }
+}
+
 pub fn delete<T: SlotClock + 'static, E: EthSpec>(
     request: DeleteRemotekeysRequest,
     validator_store: Arc<ValidatorStore<T, E>>,
@@ -1,7 +1,7 @@
 use crate::doppelganger_service::DoppelgangerService;
 use crate::key_cache::{KeyCache, CACHE_FILENAME};
 use crate::{
-    http_api::{ApiSecret, Config as HttpConfig, Context},
+    http_api::{ApiSecret, Config as HttpConfig, Context, DEFAULT_TOKEN_GRACE_PERIOD},
     initialized_validators::{InitializedValidators, OnDecryptFailure},
     Config, ValidatorDefinitions, ValidatorStore,
 };
@@ -136,6 +136,8 @@ impl ApiTester {
             config: http_config,
             log,
             sse_logging_components: None,
+            log_levels: None,
+            validator_registration_statuses: Some(Default::default()),
             slot_clock,
             _phantom: PhantomData,
         });
@@ -179,6 +181,8 @@ impl ApiTester {
             allow_origin: None,
             allow_keystore_export: true,
             store_passwords_in_secrets_dir: false,
+            tls_config: None,
+            token_grace_period: DEFAULT_TOKEN_GRACE_PERIOD,
         }
     }
 
@@ -551,6 +555,7 @@ impl ApiTester {
                 None,
                 None,
                 None,
+                None,
             )
             .await
             .unwrap();
@@ -601,6 +606,7 @@ impl ApiTester {
                 None,
                 None,
                 None,
+                None,
             )
             .await
             .unwrap();
@@ -631,6 +637,7 @@ impl ApiTester {
                 None,
                 None,
                 None,
+                None,
             )
             .await
             .unwrap();
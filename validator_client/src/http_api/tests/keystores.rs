@@ -470,7 +470,16 @@ async fn import_and_delete_conflicting_web3_signer_keystores() {
         for pubkey in &pubkeys {
             tester
                 .client
-                .patch_lighthouse_validators(pubkey, Some(false), None, None, None, None, None)
+                .patch_lighthouse_validators(
+                    pubkey,
+                    Some(false),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
                 .await
                 .unwrap();
         }
@@ -1601,6 +1610,51 @@ async fn import_only_duplicate_remotekeys() {
     .await
 }
 
+#[tokio::test]
+async fn import_same_remotekey_concurrently() {
+    run_test(|tester| async move {
+        let remotekey = new_remotekey_validator().1;
+        let request = ImportRemotekeysRequest {
+            remote_keys: vec![remotekey.clone()],
+        };
+
+        // Exactly one of the concurrent imports should succeed, and the others should be reported
+        // as duplicates rather than errors.
+        let import_results =
+            futures::future::join_all((0..4).map(|_| tester.client.post_remotekeys(&request)))
+                .await;
+        let statuses = import_results
+            .into_iter()
+            .map(|res| res.unwrap().data[0].status)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses
+                .iter()
+                .filter(|status| **status == ImportRemotekeyStatus::Imported)
+                .count(),
+            1
+        );
+        assert_eq!(
+            statuses
+                .iter()
+                .filter(|status| **status == ImportRemotekeyStatus::Duplicate)
+                .count(),
+            3
+        );
+
+        let get_res = tester.client.get_remotekeys().await.unwrap();
+        check_remotekey_get_response(
+            &get_res,
+            vec![SingleListRemotekeysResponse {
+                pubkey: remotekey.pubkey,
+                url: remotekey.url,
+                readonly: false,
+            }],
+        );
+    })
+    .await
+}
+
 #[tokio::test]
 async fn import_some_duplicate_remotekeys() {
     run_test(|tester| async move {
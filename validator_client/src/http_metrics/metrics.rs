@@ -19,6 +19,12 @@ pub const ATTESTATIONS_HTTP_POST: &str = "attestations_http_post";
 pub const AGGREGATES: &str = "aggregates";
 pub const AGGREGATES_HTTP_GET: &str = "aggregates_http_get";
 pub const AGGREGATES_HTTP_POST: &str = "aggregates_http_post";
+pub const SYNC_COMMITTEE_MESSAGES: &str = "sync_committee_messages";
+pub const SYNC_COMMITTEE_BLOCK_ROOT_HTTP_GET: &str = "sync_committee_block_root_http_get";
+pub const SYNC_COMMITTEE_MESSAGES_HTTP_POST: &str = "sync_committee_messages_http_post";
+pub const SYNC_COMMITTEE_CONTRIBUTIONS: &str = "sync_committee_contributions";
+pub const SYNC_COMMITTEE_CONTRIBUTIONS_HTTP_GET: &str = "sync_committee_contributions_http_get";
+pub const SYNC_COMMITTEE_CONTRIBUTIONS_HTTP_POST: &str = "sync_committee_contributions_http_post";
 pub const CURRENT_EPOCH: &str = "current_epoch";
 pub const NEXT_EPOCH: &str = "next_epoch";
 pub const UPDATE_INDICES: &str = "update_indices";
@@ -53,6 +59,11 @@ lazy_static::lazy_static! {
         "Number of total validators (enabled and disabled)"
     );
 
+    pub static ref CHAOS_INJECTED_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_chaos_injected_total",
+        "Total count of messages delayed or dropped by the chaos config",
+        &["duty", "action"]
+    );
     pub static ref SIGNED_BLOCKS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
         "vc_signed_beacon_blocks_total",
         "Total count of attempted block signings",
@@ -108,6 +119,11 @@ lazy_static::lazy_static! {
         "Duration to perform attestation service tasks",
         &["task"]
     );
+    pub static ref SYNC_COMMITTEE_SERVICE_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "vc_sync_committee_service_task_times_seconds",
+        "Duration to perform sync committee service tasks",
+        &["task"]
+    );
     pub static ref SLASHING_PROTECTION_PRUNE_TIMES: Result<Histogram> = try_create_histogram(
         "vc_slashing_protection_prune_times_seconds",
         "Time required to prune the slashing protection DB",
@@ -175,8 +191,8 @@ lazy_static::lazy_static! {
      */
     pub static ref SIGNING_TIMES: Result<HistogramVec> = try_create_histogram_vec(
         "vc_signing_times_seconds",
-        "Duration to obtain a signature",
-        &["type"]
+        "Duration to obtain a signature, by the type of signer and message",
+        &["type", "message"]
     );
     pub static ref BLOCK_SIGNING_TIMES: Result<Histogram> = try_create_histogram(
         "vc_block_signing_times_seconds",
@@ -20,6 +20,7 @@ use lighthouse_metrics::set_gauge;
 use lockfile::{Lockfile, LockfileError};
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use reqwest::{Certificate, Client, Error as ReqwestError, Identity};
+use sensitive_url::SensitiveUrl;
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
@@ -101,6 +102,8 @@ pub enum Error {
     SlotClock,
     /// The URL for the remote signer cannot be parsed.
     InvalidWeb3SignerUrl(String),
+    /// The URL of a preferred beacon node cannot be parsed.
+    InvalidBuilderBeaconNodeUrl(String),
     /// Unable to read the root certificate file for the remote signer.
     InvalidWeb3SignerRootCertificateFile(io::Error),
     InvalidWeb3SignerRootCertificate(ReqwestError),
@@ -134,6 +137,8 @@ pub struct InitializedValidator {
     builder_proposals: Option<bool>,
     builder_boost_factor: Option<u64>,
     prefer_builder_proposals: Option<bool>,
+    /// The redacted URLs of the beacon nodes to request blocks from first.
+    builder_beacon_nodes: Vec<String>,
     /// The validators index in `state.validators`, to be updated by an external service.
     index: Option<u64>,
 }
@@ -174,6 +179,10 @@ impl InitializedValidator {
         self.builder_proposals
     }
 
+    pub fn get_builder_beacon_nodes(&self) -> &[String] {
+        &self.builder_beacon_nodes
+    }
+
     pub fn get_index(&self) -> Option<u64> {
         self.index
     }
@@ -353,6 +362,9 @@ impl InitializedValidator {
             builder_proposals: def.builder_proposals,
             builder_boost_factor: def.builder_boost_factor,
             prefer_builder_proposals: def.prefer_builder_proposals,
+            builder_beacon_nodes: redact_beacon_node_urls(
+                def.builder_beacon_nodes.as_deref().unwrap_or_default(),
+            )?,
             index: None,
         })
     }
@@ -394,6 +406,18 @@ fn build_web3_signer_url(base_url: &str, voting_public_key: &PublicKey) -> Resul
     Url::parse(base_url)?.join(&format!("api/v1/eth2/sign/{}", voting_public_key))
 }
 
+/// Parse the URLs of the beacon nodes preferred by a validator, returning their redacted forms, as
+/// used to identify beacon nodes by `BeaconNodeFallback`.
+fn redact_beacon_node_urls(urls: &[String]) -> Result<Vec<String>, Error> {
+    urls.iter()
+        .map(|url| {
+            SensitiveUrl::parse(url)
+                .map(|url| url.to_string())
+                .map_err(|e| Error::InvalidBuilderBeaconNodeUrl(format!("{:?}", e)))
+        })
+        .collect()
+}
+
 fn build_web3_signer_client(
     root_certificate_path: Option<PathBuf>,
     client_identity_path: Option<PathBuf>,
@@ -857,6 +881,15 @@ impl InitializedValidators {
             .and_then(|v| v.prefer_builder_proposals)
     }
 
+    /// Returns the beacon nodes to request blocks from first for a given public key specified in
+    /// the `ValidatorDefinitions`.
+    pub fn builder_beacon_nodes(&self, public_key: &PublicKeyBytes) -> Vec<String> {
+        self.validators
+            .get(public_key)
+            .map(|v| v.builder_beacon_nodes.clone())
+            .unwrap_or_default()
+    }
+
     /// Returns an `Option` of a reference to an `InitializedValidator` for a given public key specified in the
     /// `ValidatorDefinitions`.
     pub fn validator(&self, public_key: &PublicKeyBytes) -> Option<&InitializedValidator> {
@@ -887,7 +920,16 @@ impl InitializedValidators {
         builder_boost_factor: Option<u64>,
         prefer_builder_proposals: Option<bool>,
         graffiti: Option<GraffitiString>,
+        builder_beacon_nodes: Option<Vec<SensitiveUrl>>,
     ) -> Result<(), Error> {
+        // Only the redacted URLs are stored, so that credentials aren't written to disk.
+        let builder_beacon_nodes = builder_beacon_nodes.map(|beacon_nodes| {
+            beacon_nodes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        });
+
         if let Some(def) = self
             .definitions
             .as_mut_slice()
@@ -913,6 +955,9 @@ impl InitializedValidators {
             if let Some(prefer_builder_proposals) = prefer_builder_proposals {
                 def.prefer_builder_proposals = Some(prefer_builder_proposals);
             }
+            if let Some(builder_beacon_nodes) = builder_beacon_nodes.clone() {
+                def.builder_beacon_nodes = Some(builder_beacon_nodes);
+            }
         }
 
         self.update_validators().await?;
@@ -937,6 +982,9 @@ impl InitializedValidators {
             if let Some(prefer_builder_proposals) = prefer_builder_proposals {
                 val.prefer_builder_proposals = Some(prefer_builder_proposals);
             }
+            if let Some(builder_beacon_nodes) = builder_beacon_nodes {
+                val.builder_beacon_nodes = builder_beacon_nodes;
+            }
         }
 
         self.definitions
@@ -1,9 +1,11 @@
 mod attestation_service;
 mod beacon_node_fallback;
 mod block_service;
+mod chaos;
 mod check_synced;
 mod cli;
 mod duties_service;
+mod genesis_tests;
 mod graffiti_file;
 mod http_metrics;
 mod key_cache;
@@ -39,7 +41,7 @@ use account_utils::validator_definitions::ValidatorDefinitions;
 use attestation_service::{AttestationService, AttestationServiceBuilder};
 use block_service::{BlockService, BlockServiceBuilder};
 use clap::ArgMatches;
-use duties_service::{sync::SyncDutiesMap, DutiesService};
+use duties_service::{proposer_events::ProposerEvents, sync::SyncDutiesMap, DutiesService};
 use environment::RuntimeContext;
 use eth2::{reqwest::ClientBuilder, types::Graffiti, BeaconNodeHttpClient, StatusCode, Timeouts};
 use http_api::ApiSecret;
@@ -50,7 +52,8 @@ use reqwest::Certificate;
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
-use std::fs::File;
+use std::fs::{self, File};
+use std::future::Future;
 use std::io::Read;
 use std::marker::PhantomData;
 use std::net::SocketAddr;
@@ -484,6 +487,10 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             context: duties_context,
             enable_high_validator_count_metrics: config.enable_high_validator_count_metrics,
             distributed: config.distributed,
+            precompute_selection_proofs: config.precompute_selection_proofs,
+            proposer_events: config
+                .proposer_duties_from_events
+                .then(ProposerEvents::default),
         });
 
         // Update the metrics server.
@@ -498,7 +505,8 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("block".into()))
             .graffiti(config.graffiti)
-            .graffiti_file(config.graffiti_file.clone());
+            .graffiti_file(config.graffiti_file.clone())
+            .broadcast_validation(config.broadcast_validation);
 
         // If we have proposer nodes, add them to the block service builder.
         if proposer_nodes_num > 0 {
@@ -515,13 +523,30 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             .runtime_context(context.service_context("attestation".into()))
             .build()?;
 
+        let validator_monitor_api_token = config
+            .beacon_nodes_api_token_path
+            .as_ref()
+            .map(|path| {
+                fs::read_to_string(path)
+                    .map(|token| token.trim().to_string())
+                    .map_err(|e| {
+                        format!(
+                            "Unable to read beacon node API token from {:?}: {:?}",
+                            path, e
+                        )
+                    })
+            })
+            .transpose()?;
+
         let preparation_service = PreparationServiceBuilder::new()
             .slot_clock(slot_clock.clone())
             .validator_store(validator_store.clone())
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("preparation".into()))
             .builder_registration_timestamp_override(config.builder_registration_timestamp_override)
+            .builder_registration_expiry(config.builder_registration_expiry)
             .validator_registration_batch_size(config.validator_registration_batch_size)
+            .validator_monitor_api_token(validator_monitor_api_token)
             .build()?;
 
         let sync_committee_service = SyncCommitteeService::new(
@@ -571,6 +596,10 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
                 spec: self.context.eth2_config.spec.clone(),
                 config: self.config.http_api.clone(),
                 sse_logging_components: self.context.sse_logging_components.clone(),
+                log_levels: self.context.log_levels.clone(),
+                validator_registration_statuses: Some(
+                    self.preparation_service.validator_registration_statuses(),
+                ),
                 slot_clock: self.slot_clock.clone(),
                 log: log.clone(),
                 _phantom: PhantomData,
@@ -592,8 +621,25 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             None
         };
 
-        // Wait until genesis has occurred.
-        wait_for_genesis(&self.beacon_nodes, self.genesis_time, &self.context).await?;
+        // Start the attestation service before genesis so that it attests in the genesis slot
+        // using the duties prepared whilst waiting.
+        self.attestation_service
+            .clone()
+            .start_update_service(&self.context.eth2_config.spec)
+            .map_err(|e| format!("Unable to start attestation service: {}", e))?;
+
+        // Wait until genesis has occurred, preparing the first duties in the meantime.
+        wait_for_genesis(
+            &self.beacon_nodes,
+            self.genesis_time,
+            &self.context,
+            prepare_for_genesis(
+                &self.duties_service,
+                &self.preparation_service,
+                &self.context,
+            ),
+        )
+        .await?;
 
         duties_service::start_update_service(self.duties_service.clone(), block_service_tx);
 
@@ -602,11 +648,6 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             .start_update_service(block_service_rx)
             .map_err(|e| format!("Unable to start block service: {}", e))?;
 
-        self.attestation_service
-            .clone()
-            .start_update_service(&self.context.eth2_config.spec)
-            .map_err(|e| format!("Unable to start attestation service: {}", e))?;
-
         self.sync_committee_service
             .clone()
             .start_update_service(&self.context.eth2_config.spec)
@@ -745,6 +786,7 @@ async fn wait_for_genesis<E: EthSpec>(
     beacon_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
     genesis_time: u64,
     context: &RuntimeContext<E>,
+    prepare: impl Future<Output = ()>,
 ) -> Result<(), String> {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -763,10 +805,17 @@ async fn wait_for_genesis<E: EthSpec>(
             "seconds_to_wait" => (genesis_time - now).as_secs()
         );
 
-        // Start polling the node for pre-genesis information, cancelling the polling as soon as the
-        // timer runs out.
+        // Start polling the node for pre-genesis information and preparing for genesis,
+        // cancelling both as soon as the timer runs out.
+        let poll_and_prepare = async {
+            let (result, ()) = tokio::join!(
+                poll_whilst_waiting_for_genesis(beacon_nodes, genesis_time, context.log()),
+                prepare
+            );
+            result
+        };
         tokio::select! {
-            result = poll_whilst_waiting_for_genesis(beacon_nodes, genesis_time, context.log()) => result?,
+            result = poll_and_prepare => result?,
             () = sleep(genesis_time - now) => ()
         };
 
@@ -786,6 +835,52 @@ async fn wait_for_genesis<E: EthSpec>(
     Ok(())
 }
 
+/// Download the duties for the genesis epoch and publish builder registrations, retrying on failure
+/// until both have succeeded.
+async fn prepare_for_genesis<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &Arc<DutiesService<T, E>>,
+    preparation_service: &PreparationService<T, E>,
+    context: &RuntimeContext<E>,
+) {
+    let log = context.log();
+    let spec = &context.eth2_config.spec;
+    let mut duties_prepared = false;
+    let mut registrations_prepared = false;
+
+    loop {
+        if !duties_prepared {
+            match duties_service::prepare_genesis_duties(duties_service).await {
+                Ok(()) => duties_prepared = true,
+                Err(e) => warn!(
+                    log,
+                    "Unable to prepare genesis duties";
+                    "error" => ?e,
+                    "retry_in" => format!("{} seconds", WAITING_FOR_GENESIS_POLL_TIME.as_secs()),
+                ),
+            }
+        }
+
+        // Registrations require the validator indices learnt whilst preparing duties.
+        if duties_prepared && !registrations_prepared {
+            match preparation_service.prepare_for_genesis(spec).await {
+                Ok(()) => registrations_prepared = true,
+                Err(e) => warn!(
+                    log,
+                    "Unable to publish registrations before genesis";
+                    "error" => e,
+                    "retry_in" => format!("{} seconds", WAITING_FOR_GENESIS_POLL_TIME.as_secs()),
+                ),
+            }
+        }
+
+        if duties_prepared && registrations_prepared {
+            break;
+        }
+
+        sleep(WAITING_FOR_GENESIS_POLL_TIME).await;
+    }
+}
+
 /// Request the version from the node, looping back and trying again on failure. Exit once the node
 /// has been contacted.
 async fn poll_whilst_waiting_for_genesis<E: EthSpec>(
@@ -3,17 +3,20 @@ use crate::validator_store::{DoppelgangerStatus, Error as ValidatorStoreError, V
 use crate::OfflineOnFailure;
 use bls::PublicKeyBytes;
 use environment::RuntimeContext;
-use parking_lot::RwLock;
+use eth2::lighthouse_vc::types::{ValidatorRegistrationAcceptance, ValidatorRegistrationStatus};
+use eth2::StatusCode;
+use parking_lot::{Mutex, RwLock};
 use slog::{debug, error, info, warn};
 use slot_clock::SlotClock;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::hash::Hash;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{sleep, Duration};
 use types::{
-    Address, ChainSpec, EthSpec, ProposerPreparationData, SignedValidatorRegistrationData,
+    Address, ChainSpec, Epoch, EthSpec, ProposerPreparationData, SignedValidatorRegistrationData,
     ValidatorRegistrationData,
 };
 
@@ -23,6 +26,18 @@ const PROPOSER_PREPARATION_LOOKAHEAD_EPOCHS: u64 = 2;
 /// Number of epochs to wait before re-submitting validator registration.
 const EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION: u64 = 1;
 
+/// Number of times to retry publishing a batch of validator registrations before giving up until
+/// the next slot.
+const VALIDATOR_REGISTRATION_RETRIES: u32 = 2;
+
+/// The delay before the first retry of a batch of validator registrations. The delay doubles with
+/// each subsequent retry.
+const VALIDATOR_REGISTRATION_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// The status of the most recent registration of each validator, shared with the HTTP API.
+pub type ValidatorRegistrationStatuses =
+    RwLock<HashMap<PublicKeyBytes, ValidatorRegistrationStatus>>;
+
 /// Builds an `PreparationService`.
 pub struct PreparationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     validator_store: Option<Arc<ValidatorStore<T, E>>>,
@@ -30,7 +45,9 @@ pub struct PreparationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     context: Option<RuntimeContext<E>>,
     builder_registration_timestamp_override: Option<u64>,
+    builder_registration_expiry: Option<Duration>,
     validator_registration_batch_size: Option<usize>,
+    validator_monitor_api_token: Option<String>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> PreparationServiceBuilder<T, E> {
@@ -41,7 +58,9 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationServiceBuilder<T, E> {
             beacon_nodes: None,
             context: None,
             builder_registration_timestamp_override: None,
+            builder_registration_expiry: None,
             validator_registration_batch_size: None,
+            validator_monitor_api_token: None,
         }
     }
 
@@ -73,6 +92,14 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationServiceBuilder<T, E> {
         self
     }
 
+    pub fn builder_registration_expiry(
+        mut self,
+        builder_registration_expiry: Option<Duration>,
+    ) -> Self {
+        self.builder_registration_expiry = builder_registration_expiry;
+        self
+    }
+
     pub fn validator_registration_batch_size(
         mut self,
         validator_registration_batch_size: usize,
@@ -81,6 +108,11 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationServiceBuilder<T, E> {
         self
     }
 
+    pub fn validator_monitor_api_token(mut self, api_token: Option<String>) -> Self {
+        self.validator_monitor_api_token = api_token;
+        self
+    }
+
     pub fn build(self) -> Result<PreparationService<T, E>, String> {
         Ok(PreparationService {
             inner: Arc::new(Inner {
@@ -98,10 +130,15 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationServiceBuilder<T, E> {
                     .ok_or("Cannot build PreparationService without runtime_context")?,
                 builder_registration_timestamp_override: self
                     .builder_registration_timestamp_override,
+                builder_registration_expiry: self.builder_registration_expiry,
                 validator_registration_batch_size: self.validator_registration_batch_size.ok_or(
                     "Cannot build PreparationService without validator_registration_batch_size",
                 )?,
                 validator_registration_cache: RwLock::new(HashMap::new()),
+                validator_registration_statuses: Arc::new(RwLock::new(HashMap::new())),
+                validator_monitor_registration_cache: RwLock::new(HashSet::new()),
+                validator_monitor_unsupported: RwLock::new(HashSet::new()),
+                validator_monitor_api_token: self.validator_monitor_api_token,
             }),
         })
     }
@@ -114,10 +151,20 @@ pub struct Inner<T, E: EthSpec> {
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     context: RuntimeContext<E>,
     builder_registration_timestamp_override: Option<u64>,
+    // Registrations are re-signed and re-published before they become this old.
+    builder_registration_expiry: Option<Duration>,
     // Used to track unpublished validator registration changes.
     validator_registration_cache:
         RwLock<HashMap<ValidatorRegistrationKey, SignedValidatorRegistrationData>>,
     validator_registration_batch_size: usize,
+    // Used to track whether each validator's registration has been published.
+    validator_registration_statuses: Arc<ValidatorRegistrationStatuses>,
+    // Used to track which keys have been registered with the beacon nodes' validator monitors.
+    validator_monitor_registration_cache: RwLock<HashSet<PublicKeyBytes>>,
+    // Beacon nodes which don't serve the validator monitor registration endpoint.
+    validator_monitor_unsupported: RwLock<HashSet<String>>,
+    // The token for the validator monitor registration endpoint. Keys are only registered if set.
+    validator_monitor_api_token: Option<String>,
 }
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone)]
@@ -143,6 +190,20 @@ impl From<ValidatorRegistrationData> for ValidatorRegistrationKey {
     }
 }
 
+/// Returns the status of a registration which has not yet been published.
+fn unpublished_status(data: &ValidatorRegistrationData) -> ValidatorRegistrationStatus {
+    ValidatorRegistrationStatus {
+        pubkey: data.pubkey,
+        fee_recipient: data.fee_recipient,
+        gas_limit: data.gas_limit,
+        timestamp: data.timestamp,
+        published: false,
+        last_published_epoch: None,
+        error: None,
+        accepted_by: vec![],
+    }
+}
+
 /// Attempts to produce proposer preparations for all known validators at the beginning of each epoch.
 pub struct PreparationService<T, E: EthSpec> {
     inner: Arc<Inner<T, E>>,
@@ -165,6 +226,11 @@ impl<T, E: EthSpec> Deref for PreparationService<T, E> {
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
+    /// Returns the status of the most recent registration of each validator.
+    pub fn validator_registration_statuses(&self) -> Arc<ValidatorRegistrationStatuses> {
+        self.validator_registration_statuses.clone()
+    }
+
     pub fn start_update_service(self, spec: &ChainSpec) -> Result<(), String> {
         self.clone().start_validator_registration_service(spec)?;
         self.start_proposer_prepare_service(spec)
@@ -234,6 +300,8 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
                     error!(log,"Error during validator registration";"error" => ?e);
                 }
 
+                self.register_validators_with_monitor().await;
+
                 // Wait one slot if the register validator request fails or if we should not publish at the current slot.
                 if let Some(duration_to_next_slot) = self.slot_clock.duration_to_next_slot() {
                     sleep(duration_to_next_slot).await;
@@ -248,6 +316,33 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
         Ok(())
     }
 
+    /// Publish proposer preparations and builder registrations prior to genesis, so that they are
+    /// known to the beacon nodes and builders before the first proposal.
+    ///
+    /// Returns an error if any builder registration could not be published.
+    pub async fn prepare_for_genesis(&self, spec: &ChainSpec) -> Result<(), String> {
+        if self.should_publish_at_current_slot(spec) {
+            self.prepare_proposers_and_publish(spec).await?;
+        }
+
+        self.register_validators().await?;
+
+        let unpublished = self
+            .validator_registration_statuses
+            .read()
+            .values()
+            .filter(|status| !status.published)
+            .count();
+        if unpublished > 0 {
+            return Err(format!(
+                "{} validator registrations are unpublished",
+                unpublished
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Return `true` if the current slot is close to or past the Bellatrix fork epoch.
     ///
     /// This avoids spamming the BN with preparations before the Bellatrix fork epoch, which may
@@ -373,19 +468,63 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
         let registration_keys = self.collect_validator_registration_keys();
 
         let mut changed_keys = vec![];
+        let mut expired_keys = vec![];
+        let now = unix_timestamp()?;
 
         // Need to scope this so the read lock is not held across an await point (I don't know why
         // but the explicit `drop` is not enough).
         {
             let guard = self.validator_registration_cache.read();
+            let mut statuses = self.validator_registration_statuses.write();
+
+            // Forget about validators which have been removed or are no longer using a builder.
+            statuses.retain(|pubkey, _| registration_keys.iter().any(|key| key.pubkey == *pubkey));
+
+            // Forget about acceptances by beacon nodes which are no longer configured, and those
+            // which have expired, so that they aren't treated as stale forever.
+            let beacon_node_ids = self.beacon_nodes.beacon_node_ids();
+            for status in statuses.values_mut() {
+                prune_acceptances(status, &beacon_node_ids, |accepted_at| {
+                    self.is_expired(accepted_at, now)
+                });
+            }
+
             for key in registration_keys.iter() {
-                if !guard.contains_key(key) {
+                // Registrations which failed to publish are retried at every slot, rather than
+                // waiting for the next epoch.
+                let is_unpublished = is_unpublished(statuses.get(&key.pubkey), key);
+                // Registrations which are about to expire are re-signed with a fresh timestamp.
+                let is_expiring = self.builder_registration_timestamp_override.is_none()
+                    && guard.get(key).map_or(false, |signed| {
+                        self.is_near_expiry(signed.message.timestamp, now)
+                    });
+                // Registrations which a beacon node has not accepted recently are re-published
+                // immediately, rather than waiting for the next scheduled submission.
+                let is_stale = statuses.get(&key.pubkey).map_or(false, |status| {
+                    is_stale(status, |accepted_at| self.is_near_expiry(accepted_at, now))
+                });
+                if is_expiring {
+                    expired_keys.push(key.clone());
+                }
+                if !guard.contains_key(key) || is_unpublished || is_expiring || is_stale {
                     changed_keys.push(key.clone());
                 }
             }
             drop(guard);
         }
 
+        if !expired_keys.is_empty() {
+            debug!(
+                self.context.log(),
+                "Refreshing expiring validator registrations";
+                "count" => expired_keys.len(),
+            );
+            let mut cache = self.validator_registration_cache.write();
+            for key in &expired_keys {
+                cache.remove(key);
+            }
+        }
+
         // Check if any have changed or it's been `EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION`.
         if let Some(slot) = self.slot_clock.now() {
             if slot % (E::slots_per_epoch() * EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION) == 0 {
@@ -395,11 +534,120 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
                 self.publish_validator_registration_data(changed_keys)
                     .await?;
             }
+        } else if !changed_keys.is_empty() && self.slot_clock.is_prior_to_genesis() == Some(true) {
+            // Register with builders prior to genesis so that the first proposers can use them.
+            self.publish_validator_registration_data(changed_keys)
+                .await?;
         }
 
         Ok(())
     }
 
+    /// Returns `true` if a registration signed or accepted at `timestamp` will expire within the
+    /// next epoch, and so should be refreshed now.
+    fn is_near_expiry(&self, timestamp: u64, now: u64) -> bool {
+        let margin = self.slot_clock.slot_duration() * E::slots_per_epoch() as u32;
+        expires_within(timestamp, now, self.builder_registration_expiry, margin)
+    }
+
+    /// Returns `true` if a registration signed or accepted at `timestamp` has expired.
+    fn is_expired(&self, timestamp: u64, now: u64) -> bool {
+        expires_within(
+            timestamp,
+            now,
+            self.builder_registration_expiry,
+            Duration::ZERO,
+        )
+    }
+
+    /// Register all validator keys with the validator monitors of the beacon nodes.
+    ///
+    /// Keys are published whenever they change and at the start of every epoch, so that beacon
+    /// nodes which restart are repopulated. Beacon nodes ignore the request unless they are
+    /// running with `--validator-monitor-auto`.
+    ///
+    /// A beacon node which responds with a 404 doesn't serve the endpoint, and isn't sent any more
+    /// keys until the validator client restarts.
+    ///
+    /// The endpoint is authenticated, so nothing is sent unless the validator client has the API
+    /// token of the beacon nodes.
+    async fn register_validators_with_monitor(&self) {
+        let log = self.context.log();
+
+        let Some(api_token) = self.validator_monitor_api_token.as_deref() else {
+            return;
+        };
+
+        let pubkeys: Vec<_> = self
+            .validator_store
+            .voting_pubkeys(DoppelgangerStatus::ignored);
+
+        let is_changed = {
+            let cache = self.validator_monitor_registration_cache.read();
+            pubkeys.len() != cache.len() || pubkeys.iter().any(|pubkey| !cache.contains(pubkey))
+        };
+        let is_epoch_start = self
+            .slot_clock
+            .now()
+            .map_or(false, |slot| slot % E::slots_per_epoch() == 0);
+
+        if !is_changed && !is_epoch_start {
+            return;
+        }
+
+        let pubkeys_slice = pubkeys.as_slice();
+        match self
+            .beacon_nodes
+            .broadcast(
+                RequireSynced::No,
+                OfflineOnFailure::No,
+                |beacon_node| async move {
+                    let beacon_node_id = beacon_node.to_string();
+                    if self
+                        .validator_monitor_unsupported
+                        .read()
+                        .contains(&beacon_node_id)
+                    {
+                        return Ok(());
+                    }
+
+                    match beacon_node
+                        .post_lighthouse_validator_monitor_register(pubkeys_slice, api_token)
+                        .await
+                    {
+                        Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => {
+                            debug!(
+                                log,
+                                "Beacon node does not support validator monitor registration";
+                                "endpoint" => %beacon_node_id,
+                            );
+                            self.validator_monitor_unsupported
+                                .write()
+                                .insert(beacon_node_id);
+                            Ok(())
+                        }
+                        result => result,
+                    }
+                },
+            )
+            .await
+        {
+            Ok(()) => {
+                debug!(
+                    log,
+                    "Registered validators with validator monitor";
+                    "count" => pubkeys.len(),
+                );
+                *self.validator_monitor_registration_cache.write() = pubkeys.into_iter().collect();
+            }
+            Err(e) => warn!(
+                log,
+                "Unable to register validators with validator monitor";
+                "error" => %e,
+            ),
+        }
+    }
+
     async fn publish_validator_registration_data(
         &self,
         registration_keys: Vec<ValidatorRegistrationKey>,
@@ -414,16 +662,23 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
                 self.validator_registration_cache.read().get(&key).cloned();
 
             let signed_data = if let Some(signed_data) = cached_registration_opt {
+                let message = &signed_data.message;
+                let mut statuses = self.validator_registration_statuses.write();
+                let is_current = statuses.get(&message.pubkey).map_or(false, |status| {
+                    status.fee_recipient == message.fee_recipient
+                        && status.gas_limit == message.gas_limit
+                        && status.timestamp == message.timestamp
+                });
+                if !is_current {
+                    statuses.insert(message.pubkey, unpublished_status(message));
+                }
                 signed_data
             } else {
                 let timestamp =
                     if let Some(timestamp) = self.builder_registration_timestamp_override {
                         timestamp
                     } else {
-                        SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .map_err(|e| format!("{e:?}"))?
-                            .as_secs()
+                        unix_timestamp()?
                     };
 
                 let ValidatorRegistrationKey {
@@ -468,6 +723,10 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
                     .write()
                     .insert(key, signed_data.clone());
 
+                self.validator_registration_statuses
+                    .write()
+                    .insert(pubkey, unpublished_status(&signed_data.message));
+
                 signed_data
             };
             signed.push(signed_data);
@@ -475,17 +734,34 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
 
         if !signed.is_empty() {
             for batch in signed.chunks(self.validator_registration_batch_size) {
-                match self
-                    .beacon_nodes
-                    .broadcast(
-                        RequireSynced::No,
-                        OfflineOnFailure::No,
-                        |beacon_node| async move {
-                            beacon_node.post_validator_register_validator(batch).await
-                        },
-                    )
-                    .await
+                let accepted_by = Mutex::new(vec![]);
+                let result = self
+                    .publish_validator_registration_batch(batch, &accepted_by)
+                    .await;
+                let accepted_by = accepted_by.into_inner();
+                let accepted_at = unix_timestamp()?;
+
+                let current_epoch = self
+                    .slot_clock
+                    .now()
+                    .map(|slot| slot.epoch(E::slots_per_epoch()));
+                // Scoped so that the lock is not held across an await point.
                 {
+                    let mut statuses = self.validator_registration_statuses.write();
+                    for registration in batch {
+                        if let Some(status) = statuses.get_mut(&registration.message.pubkey) {
+                            update_status(
+                                status,
+                                &result,
+                                &accepted_by,
+                                accepted_at,
+                                current_epoch,
+                            );
+                        }
+                    }
+                }
+
+                match result {
                     Ok(()) => info!(
                         log,
                         "Published validator registrations to the builder network";
@@ -495,12 +771,172 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
                         log,
                         "Unable to publish validator registrations to the builder network";
                         "error" => %e,
+                        "count" => batch.len(),
                     ),
                 }
             }
         }
         Ok(())
     }
+
+    /// Publish a batch of validator registrations, retrying with an exponential backoff if all
+    /// beacon nodes fail.
+    ///
+    /// The beacon nodes which accept the batch are added to `accepted_by`.
+    async fn publish_validator_registration_batch(
+        &self,
+        batch: &[SignedValidatorRegistrationData],
+        accepted_by: &Mutex<Vec<String>>,
+    ) -> Result<(), String> {
+        let log = self.context.log();
+
+        retry_with_backoff(
+            VALIDATOR_REGISTRATION_RETRIES,
+            VALIDATOR_REGISTRATION_RETRY_DELAY,
+            move || {
+                self.beacon_nodes.broadcast(
+                    RequireSynced::No,
+                    OfflineOnFailure::No,
+                    move |beacon_node| async move {
+                        beacon_node.post_validator_register_validator(batch).await?;
+                        let beacon_node = beacon_node.to_string();
+                        let mut accepted_by = accepted_by.lock();
+                        if !accepted_by.contains(&beacon_node) {
+                            accepted_by.push(beacon_node);
+                        }
+                        Ok::<_, eth2::Error>(())
+                    },
+                )
+            },
+            |e, attempt, delay| {
+                debug!(
+                    log,
+                    "Retrying validator registration batch";
+                    "error" => %e,
+                    "attempt" => attempt,
+                    "delay_ms" => delay.as_millis(),
+                )
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Returns `true` if the registration for `key` has not been published with its current fee
+/// recipient and gas limit.
+fn is_unpublished(
+    status: Option<&ValidatorRegistrationStatus>,
+    key: &ValidatorRegistrationKey,
+) -> bool {
+    status.map_or(true, |status| {
+        !status.published
+            || status.fee_recipient != key.fee_recipient
+            || status.gas_limit != key.gas_limit
+    })
+}
+
+/// Returns `true` if a registration signed or accepted at `timestamp` expires within `margin` of
+/// `now`, given that registrations last for `expiry`. Registrations never expire if `expiry` is
+/// `None`.
+fn expires_within(timestamp: u64, now: u64, expiry: Option<Duration>, margin: Duration) -> bool {
+    let Some(expiry) = expiry else {
+        return false;
+    };
+    now.saturating_sub(timestamp) >= expiry.saturating_sub(margin).as_secs()
+}
+
+/// Returns `true` if any beacon node which accepted the registration is due to have it refreshed.
+///
+/// Each beacon node forwards the registration to its own relays, so each acceptance expires
+/// independently of the others.
+fn is_stale(status: &ValidatorRegistrationStatus, is_near_expiry: impl Fn(u64) -> bool) -> bool {
+    status
+        .accepted_by
+        .iter()
+        .any(|acceptance| is_near_expiry(acceptance.accepted_at))
+}
+
+/// Remove the acceptances of beacon nodes which aren't in `beacon_nodes`, and those which have
+/// expired.
+fn prune_acceptances(
+    status: &mut ValidatorRegistrationStatus,
+    beacon_nodes: &[String],
+    is_expired: impl Fn(u64) -> bool,
+) {
+    status.accepted_by.retain(|acceptance| {
+        beacon_nodes.contains(&acceptance.beacon_node) && !is_expired(acceptance.accepted_at)
+    });
+}
+
+/// Update the status of a registration with the `result` of publishing it.
+///
+/// The beacon nodes in `accepted_by` are recorded as having accepted the registration at
+/// `accepted_at`, even if the batch failed on the other beacon nodes.
+fn update_status(
+    status: &mut ValidatorRegistrationStatus,
+    result: &Result<(), String>,
+    accepted_by: &[String],
+    accepted_at: u64,
+    current_epoch: Option<Epoch>,
+) {
+    for beacon_node in accepted_by {
+        match status
+            .accepted_by
+            .iter_mut()
+            .find(|acceptance| acceptance.beacon_node == *beacon_node)
+        {
+            Some(acceptance) => acceptance.accepted_at = accepted_at,
+            None => status.accepted_by.push(ValidatorRegistrationAcceptance {
+                beacon_node: beacon_node.clone(),
+                accepted_at,
+            }),
+        }
+    }
+    match result {
+        Ok(()) => {
+            status.published = true;
+            status.last_published_epoch = current_epoch;
+            status.error = None;
+        }
+        Err(e) => status.error = Some(e.clone()),
+    }
+}
+
+/// Call `f` until it succeeds, retrying at most `retries` times. The delay before the first retry
+/// is `delay`, and doubles with each subsequent retry.
+///
+/// `on_retry` is called with the error, the number of the retry and the delay before each retry.
+async fn retry_with_backoff<F, R, T, U>(
+    retries: u32,
+    mut delay: Duration,
+    mut f: F,
+    on_retry: impl Fn(&U, u32, Duration),
+) -> Result<T, U>
+where
+    F: FnMut() -> R,
+    R: Future<Output = Result<T, U>>,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < retries => {
+                attempt += 1;
+                on_retry(&e, attempt, delay);
+                sleep(delay).await;
+                delay *= 2;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn unix_timestamp() -> Result<u64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .map_err(|e| format!("{e:?}"))
 }
 
 /// A helper struct, used for passing data from the validator store to services.
@@ -510,3 +946,240 @@ pub struct ProposalData {
     pub(crate) gas_limit: u64,
     pub(crate) builder_proposals: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    fn key(gas_limit: u64) -> ValidatorRegistrationKey {
+        ValidatorRegistrationKey {
+            fee_recipient: Address::repeat_byte(1),
+            gas_limit,
+            pubkey: PublicKeyBytes::empty(),
+        }
+    }
+
+    fn status(key: &ValidatorRegistrationKey) -> ValidatorRegistrationStatus {
+        unpublished_status(&ValidatorRegistrationData {
+            fee_recipient: key.fee_recipient,
+            gas_limit: key.gas_limit,
+            timestamp: 42,
+            pubkey: key.pubkey,
+        })
+    }
+
+    #[tokio::test]
+    async fn retry_succeeds_after_failures() {
+        let attempts = Cell::new(0);
+        let delays = RefCell::new(vec![]);
+
+        let result = retry_with_backoff(
+            2,
+            Duration::from_millis(1),
+            || {
+                attempts.set(attempts.get() + 1);
+                let attempt = attempts.get();
+                async move {
+                    if attempt < 3 {
+                        Err(attempt)
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            },
+            |_, _, delay| delays.borrow_mut().push(delay),
+        )
+        .await;
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(attempts.get(), 3);
+        assert_eq!(
+            delays.into_inner(),
+            vec![Duration::from_millis(1), Duration::from_millis(2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_gives_up_after_retries() {
+        let attempts = Cell::new(0);
+        let retries = RefCell::new(vec![]);
+
+        let result: Result<(), _> = retry_with_backoff(
+            2,
+            Duration::from_millis(1),
+            || {
+                attempts.set(attempts.get() + 1);
+                let attempt = attempts.get();
+                async move { Err(attempt) }
+            },
+            |e, retry, _| retries.borrow_mut().push((*e, retry)),
+        )
+        .await;
+
+        assert_eq!(result, Err(3));
+        assert_eq!(attempts.get(), 3);
+        assert_eq!(retries.into_inner(), vec![(1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn registration_is_republished_when_changed() {
+        let key = key(30_000_000);
+        assert!(is_unpublished(None, &key));
+
+        let mut status = status(&key);
+        assert!(is_unpublished(Some(&status), &key));
+
+        update_status(&mut status, &Ok(()), &[], 0, None);
+        assert!(!is_unpublished(Some(&status), &key));
+
+        let mut changed_fee_recipient = key.clone();
+        changed_fee_recipient.fee_recipient = Address::repeat_byte(2);
+        assert!(is_unpublished(Some(&status), &changed_fee_recipient));
+        assert!(is_unpublished(Some(&status), &self::key(36_000_000)));
+    }
+
+    #[test]
+    fn status_records_failures_and_acceptances() {
+        let key = key(30_000_000);
+        let mut status = status(&key);
+        let beacon_node_1 = "http://bn-1/".to_string();
+        let beacon_node_2 = "http://bn-2/".to_string();
+
+        // The batch failed on one beacon node, but was accepted by the other.
+        update_status(
+            &mut status,
+            &Err("bn-2 is down".into()),
+            &[beacon_node_1.clone()],
+            10,
+            Some(Epoch::new(1)),
+        );
+        assert!(!status.published);
+        assert_eq!(status.last_published_epoch, None);
+        assert_eq!(status.error.as_deref(), Some("bn-2 is down"));
+        assert_eq!(
+            status.accepted_by,
+            vec![ValidatorRegistrationAcceptance {
+                beacon_node: beacon_node_1.clone(),
+                accepted_at: 10,
+            }]
+        );
+
+        // A later attempt succeeds on both, which refreshes the first acceptance rather than
+        // duplicating it.
+        update_status(
+            &mut status,
+            &Ok(()),
+            &[beacon_node_1.clone(), beacon_node_2.clone()],
+            20,
+            Some(Epoch::new(2)),
+        );
+        assert!(status.published);
+        assert_eq!(status.last_published_epoch, Some(Epoch::new(2)));
+        assert_eq!(status.error, None);
+        assert_eq!(
+            status.accepted_by,
+            vec![
+                ValidatorRegistrationAcceptance {
+                    beacon_node: beacon_node_1,
+                    accepted_at: 20,
+                },
+                ValidatorRegistrationAcceptance {
+                    beacon_node: beacon_node_2,
+                    accepted_at: 20,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn registrations_expire_within_margin() {
+        let expiry = Some(Duration::from_secs(100));
+        let margin = Duration::from_secs(10);
+
+        assert!(!expires_within(1000, 1089, expiry, margin));
+        assert!(expires_within(1000, 1090, expiry, margin));
+        assert!(!expires_within(1000, 1099, expiry, Duration::ZERO));
+        assert!(expires_within(1000, 1100, expiry, Duration::ZERO));
+        // A margin longer than the expiry refreshes at every opportunity.
+        assert!(expires_within(1000, 1000, expiry, Duration::from_secs(200)));
+        // Without an expiry, registrations are never refreshed.
+        assert!(!expires_within(0, u64::MAX, None, margin));
+    }
+
+    #[test]
+    fn staleness_is_checked_per_beacon_node() {
+        let key = key(30_000_000);
+        let mut status = status(&key);
+        let beacon_node_1 = "http://bn-1/".to_string();
+        let beacon_node_2 = "http://bn-2/".to_string();
+        let expiry = Some(Duration::from_secs(100));
+        let margin = Duration::from_secs(10);
+
+        // A registration which no beacon node has accepted isn't stale, it's unpublished.
+        assert!(!is_stale(&status, |t| expires_within(
+            t, 1000, expiry, margin
+        )));
+
+        update_status(&mut status, &Ok(()), &[beacon_node_1.clone()], 1000, None);
+        update_status(&mut status, &Ok(()), &[beacon_node_2.clone()], 1050, None);
+        assert!(!is_stale(&status, |t| expires_within(
+            t, 1050, expiry, margin
+        )));
+
+        // Only the acceptance by the first beacon node is near expiry, which is enough to
+        // refresh the registration.
+        assert!(is_stale(&status, |t| expires_within(
+            t, 1090, expiry, margin
+        )));
+
+        // Once refreshed on the first beacon node, the registration is fresh again.
+        update_status(&mut status, &Ok(()), &[beacon_node_1], 1090, None);
+        assert!(!is_stale(&status, |t| expires_within(
+            t, 1090, expiry, margin
+        )));
+        assert!(is_stale(&status, |t| expires_within(
+            t, 1140, expiry, margin
+        )));
+    }
+
+    #[test]
+    fn acceptances_are_pruned() {
+        let key = key(30_000_000);
+        let mut status = status(&key);
+        let beacon_node_1 = "http://bn-1/".to_string();
+        let beacon_node_2 = "http://bn-2/".to_string();
+        let beacon_node_3 = "http://bn-3/".to_string();
+        let expiry = Some(Duration::from_secs(100));
+
+        update_status(&mut status, &Ok(()), &[beacon_node_1.clone()], 1000, None);
+        update_status(
+            &mut status,
+            &Ok(()),
+            &[beacon_node_2.clone(), beacon_node_3.clone()],
+            1050,
+            None,
+        );
+
+        // The third beacon node has been removed, and the first beacon node's acceptance has
+        // expired because it has been offline since.
+        prune_acceptances(&mut status, &[beacon_node_1, beacon_node_2.clone()], |t| {
+            expires_within(t, 1100, expiry, Duration::ZERO)
+        });
+        assert_eq!(
+            status.accepted_by,
+            vec![ValidatorRegistrationAcceptance {
+                beacon_node: beacon_node_2,
+                accepted_at: 1050,
+            }]
+        );
+
+        // Neither gone beacon node keeps the registration stale.
+        assert!(!is_stale(&status, |t| expires_within(
+            t,
+            1100,
+            expiry,
+            Duration::from_secs(10)
+        )));
+    }
+}
@@ -5,9 +5,11 @@
 
 use crate::http_metrics::metrics;
 use eth2_keystore::Keystore;
+use futures::future::join_all;
 use lockfile::Lockfile;
 use parking_lot::Mutex;
 use reqwest::{header::ACCEPT, Client};
+use std::cmp::min;
 use std::path::PathBuf;
 use std::sync::Arc;
 use task_executor::TaskExecutor;
@@ -19,7 +21,11 @@ pub use web3signer::Web3SignerObject;
 
 mod web3signer;
 
-#[derive(Debug, PartialEq)]
+/// The maximum number of requests to a Web3Signer instance that are sent together when signing a
+/// batch of messages.
+pub const WEB3SIGNER_BATCH_SIZE: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Error {
     InconsistentDomains {
         message_type_domain: Domain,
@@ -71,6 +77,22 @@ impl<'a, E: EthSpec, Payload: AbstractExecPayload<E>> SignableMessage<'a, E, Pay
             SignableMessage::VoluntaryExit(exit) => exit.signing_root(domain),
         }
     }
+
+    /// Returns a label for the kind of message, for use in metrics.
+    pub fn metrics_label(&self) -> &'static str {
+        match self {
+            SignableMessage::RandaoReveal(_) => "randao_reveal",
+            SignableMessage::BeaconBlock(_) => "beacon_block",
+            SignableMessage::AttestationData(_) => "attestation",
+            SignableMessage::SignedAggregateAndProof(_) => "aggregate_and_proof",
+            SignableMessage::SelectionProof(_) => "selection_proof",
+            SignableMessage::SyncSelectionProof(_) => "sync_selection_proof",
+            SignableMessage::SyncCommitteeSignature { .. } => "sync_committee_message",
+            SignableMessage::SignedContributionAndProof(_) => "contribution_and_proof",
+            SignableMessage::ValidatorRegistration(_) => "validator_registration",
+            SignableMessage::VoluntaryExit(_) => "voluntary_exit",
+        }
+    }
 }
 
 /// A method used by a validator to sign messages.
@@ -95,6 +117,13 @@ pub enum SigningMethod {
     },
 }
 
+/// A message to be signed by `get_signatures_batch`.
+pub struct BatchSigningRequest<'a, E: EthSpec, Payload: AbstractExecPayload<E> = FullPayload<E>> {
+    pub signing_method: Arc<SigningMethod>,
+    pub signable_message: SignableMessage<'a, E, Payload>,
+    pub signing_context: SigningContext,
+}
+
 /// The additional information used to construct a signature. Mostly used for protection from replay
 /// attacks.
 pub struct SigningContext {
@@ -164,10 +193,13 @@ impl SigningMethod {
         executor: &TaskExecutor,
         fork_info: Option<ForkInfo>,
     ) -> Result<Signature, Error> {
+        let message_label = signable_message.metrics_label();
         match self {
             SigningMethod::LocalKeystore { voting_keypair, .. } => {
-                let _timer =
-                    metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::LOCAL_KEYSTORE]);
+                let _timer = metrics::start_timer_vec(
+                    &metrics::SIGNING_TIMES,
+                    &[metrics::LOCAL_KEYSTORE, message_label],
+                );
 
                 let voting_keypair = voting_keypair.clone();
                 // Spawn a blocking task to produce the signature. This avoids blocking the core
@@ -187,8 +219,10 @@ impl SigningMethod {
                 http_client,
                 ..
             } => {
-                let _timer =
-                    metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::WEB3SIGNER]);
+                let _timer = metrics::start_timer_vec(
+                    &metrics::SIGNING_TIMES,
+                    &[metrics::WEB3SIGNER, message_label],
+                );
 
                 // Map the message into a Web3Signer type.
                 let object = match signable_message {
@@ -259,3 +293,109 @@ impl SigningMethod {
         }
     }
 }
+
+/// Return the signatures of a batch of messages, in the same order as `requests`.
+///
+/// All messages for local keystores are signed in a single blocking task. Requests to Web3Signer
+/// are sent in batches of up to `WEB3SIGNER_BATCH_SIZE` which are awaited together, so the latency
+/// of the remote signer is paid once per batch rather than once per message.
+pub async fn get_signatures_batch<E: EthSpec, Payload: AbstractExecPayload<E>>(
+    requests: Vec<BatchSigningRequest<'_, E, Payload>>,
+    spec: &ChainSpec,
+    executor: &TaskExecutor,
+) -> Vec<Result<Signature, Error>> {
+    // Every entry is overwritten below.
+    let mut signatures = Vec::with_capacity(requests.len());
+    signatures.resize_with(requests.len(), || Err(Error::ShuttingDown));
+
+    let mut local_requests = vec![];
+    let mut remote_requests = vec![];
+    for (i, request) in requests.into_iter().enumerate() {
+        let BatchSigningRequest {
+            signing_method,
+            signable_message,
+            signing_context,
+        } = request;
+        let signing_root = signable_message.signing_root(signing_context.domain_hash(spec));
+
+        match signing_method.as_ref() {
+            SigningMethod::LocalKeystore { voting_keypair, .. } => {
+                local_requests.push((
+                    i,
+                    voting_keypair.clone(),
+                    signing_root,
+                    signable_message.metrics_label(),
+                ));
+            }
+            SigningMethod::Web3Signer { .. } => {
+                let fork_info = Some(ForkInfo {
+                    fork: signing_context.fork,
+                    genesis_validators_root: signing_context.genesis_validators_root,
+                });
+                remote_requests.push((
+                    i,
+                    signing_method,
+                    signable_message,
+                    signing_root,
+                    fork_info,
+                ));
+            }
+        }
+    }
+
+    if let Some(&(_, _, _, message_label)) = local_requests.first() {
+        let _timer = metrics::start_timer_vec(
+            &metrics::SIGNING_TIMES,
+            &[metrics::LOCAL_KEYSTORE, message_label],
+        );
+
+        let (indices, keypairs_and_roots): (Vec<_>, Vec<_>) = local_requests
+            .into_iter()
+            .map(|(i, voting_keypair, signing_root, _)| (i, (voting_keypair, signing_root)))
+            .unzip();
+        // Spawn a single blocking task to produce all of the signatures. This avoids blocking the
+        // core tokio executor.
+        let result = match executor.spawn_blocking_handle(
+            move || {
+                keypairs_and_roots
+                    .into_iter()
+                    .map(|(voting_keypair, signing_root)| voting_keypair.sk.sign(signing_root))
+                    .collect::<Vec<_>>()
+            },
+            "local_keystore_batch_signer",
+        ) {
+            Some(handle) => handle.await.map_err(|e| Error::TokioJoin(e.to_string())),
+            None => Err(Error::ShuttingDown),
+        };
+
+        match result {
+            Ok(local_signatures) => {
+                for (i, signature) in indices.into_iter().zip(local_signatures) {
+                    signatures[i] = Ok(signature);
+                }
+            }
+            Err(e) => {
+                for i in indices {
+                    signatures[i] = Err(e.clone());
+                }
+            }
+        }
+    }
+
+    while !remote_requests.is_empty() {
+        let batch_size = min(remote_requests.len(), WEB3SIGNER_BATCH_SIZE);
+        let batch = remote_requests.drain(..batch_size).map(
+            |(i, signing_method, signable_message, signing_root, fork_info)| async move {
+                let result = signing_method
+                    .get_signature_from_root(signable_message, signing_root, executor, fork_info)
+                    .await;
+                (i, result)
+            },
+        );
+        for (i, result) in join_all(batch).await {
+            signatures[i] = result;
+        }
+    }
+
+    signatures
+}
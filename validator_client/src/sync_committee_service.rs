@@ -1,6 +1,7 @@
 use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
 use crate::{
     duties_service::DutiesService,
+    http_metrics::metrics,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
     OfflineOnFailure,
 };
@@ -183,6 +184,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
                 |beacon_node| async move {
+                    let _timer = metrics::start_timer_vec(
+                        &metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+                        &[metrics::SYNC_COMMITTEE_BLOCK_ROOT_HTTP_GET],
+                    );
                     match beacon_node.get_beacon_blocks_root(BlockId::Head).await {
                         Ok(Some(block)) if block.execution_optimistic == Some(false) => {
                             Ok(block)
@@ -252,6 +257,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         validator_duties: Vec<SyncDuty>,
     ) -> Result<(), ()> {
         let log = self.context.log();
+        let _timer = metrics::start_timer_vec(
+            &metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+            &[metrics::SYNC_COMMITTEE_MESSAGES],
+        );
 
         // Create futures to produce sync committee signatures.
         let signature_futures = validator_duties.iter().map(|duty| async move {
@@ -304,6 +313,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 OfflineOnFailure::Yes,
                 ApiTopic::SyncCommittee,
                 |beacon_node| async move {
+                    let _timer = metrics::start_timer_vec(
+                        &metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+                        &[metrics::SYNC_COMMITTEE_MESSAGES_HTTP_POST],
+                    );
                     beacon_node
                         .post_beacon_pool_sync_committee_signatures(committee_signatures)
                         .await
@@ -367,6 +380,12 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
     ) -> Result<(), ()> {
         sleep_until(aggregate_instant).await;
 
+        // Start the metrics timer *after* we've done the delay.
+        let _timer = metrics::start_timer_vec(
+            &metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+            &[metrics::SYNC_COMMITTEE_CONTRIBUTIONS],
+        );
+
         let log = self.context.log();
 
         let contribution = &self
@@ -375,6 +394,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
                 |beacon_node| async move {
+                    let _timer = metrics::start_timer_vec(
+                        &metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+                        &[metrics::SYNC_COMMITTEE_CONTRIBUTIONS_HTTP_GET],
+                    );
                     let sync_contribution_data = SyncContributionData {
                         slot,
                         beacon_block_root,
@@ -457,6 +480,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
                 |beacon_node| async move {
+                    let _timer = metrics::start_timer_vec(
+                        &metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+                        &[metrics::SYNC_COMMITTEE_CONTRIBUTIONS_HTTP_POST],
+                    );
                     beacon_node
                         .post_validator_contribution_and_proofs(signed_contributions)
                         .await
@@ -1,8 +1,12 @@
 use crate::{
+    chaos::{ChaosAction, ChaosConfig, ChaosDuty},
     doppelganger_service::DoppelgangerService,
     http_metrics::metrics,
     initialized_validators::InitializedValidators,
-    signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod},
+    signing_method::{
+        get_signatures_batch, BatchSigningRequest, Error as SigningError, SignableMessage,
+        SigningContext, SigningMethod,
+    },
     Config,
 };
 use account_utils::validator_definitions::{PasswordStorage, ValidatorDefinition};
@@ -10,7 +14,7 @@ use parking_lot::{Mutex, RwLock};
 use slashing_protection::{
     interchange::Interchange, InterchangeError, NotSafe, Safe, SlashingDatabase,
 };
-use slog::{crit, error, info, warn, Logger};
+use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::marker::PhantomData;
 use std::path::Path;
@@ -40,6 +44,7 @@ pub enum Error {
     GreaterThanCurrentEpoch { epoch: Epoch, current_epoch: Epoch },
     UnableToSignAttestation(AttestationError),
     UnableToSign(SigningError),
+    DroppedByChaosConfig(ChaosDuty),
 }
 
 impl From<SigningError> for Error {
@@ -62,6 +67,7 @@ pub struct ValidatorStore<T, E: EthSpec> {
     validators: Arc<RwLock<InitializedValidators>>,
     slashing_protection: SlashingDatabase,
     slashing_protection_last_prune: Arc<Mutex<Epoch>>,
+    slashing_protection_minify: bool,
     genesis_validators_root: Hash256,
     spec: Arc<ChainSpec>,
     log: Logger,
@@ -73,6 +79,7 @@ pub struct ValidatorStore<T, E: EthSpec> {
     enable_web3signer_slashing_protection: bool,
     prefer_builder_proposals: bool,
     builder_boost_factor: Option<u64>,
+    chaos_config: Option<ChaosConfig>,
     task_executor: TaskExecutor,
     _phantom: PhantomData<E>,
 }
@@ -96,6 +103,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             validators: Arc::new(RwLock::new(validators)),
             slashing_protection,
             slashing_protection_last_prune: Arc::new(Mutex::new(Epoch::new(0))),
+            slashing_protection_minify: config.slashing_protection_minify,
             genesis_validators_root,
             spec: Arc::new(spec),
             log,
@@ -107,6 +115,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             enable_web3signer_slashing_protection: config.enable_web3signer_slashing_protection,
             prefer_builder_proposals: config.prefer_builder_proposals,
             builder_boost_factor: config.builder_boost_factor,
+            chaos_config: config.chaos_config.clone(),
             task_executor,
             _phantom: PhantomData,
         }
@@ -408,6 +417,14 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.read().graffiti(validator_pubkey)
     }
 
+    /// Returns the redacted URLs of the beacon nodes to request blocks from first for the given
+    /// public key, in order of preference.
+    pub fn builder_beacon_nodes(&self, validator_pubkey: &PublicKeyBytes) -> Vec<String> {
+        self.validators
+            .read()
+            .builder_beacon_nodes(validator_pubkey)
+    }
+
     /// Returns the fee recipient for the given public key. The priority order for fetching
     /// the fee recipient is:
     /// 1. validator_definitions.yml
@@ -549,6 +566,47 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         })
     }
 
+    /// Injects the failures configured by `--chaos-config` for a `duty` message in `epoch`.
+    ///
+    /// Returns an error if the message should be dropped.
+    async fn inject_chaos(&self, duty: ChaosDuty, epoch: Epoch) -> Result<(), Error> {
+        let Some(chaos_config) = &self.chaos_config else {
+            return Ok(());
+        };
+
+        let action = chaos_config.action(duty, epoch, &mut rand::thread_rng());
+        if action != ChaosAction::Proceed {
+            metrics::inc_counter_vec(
+                &metrics::CHAOS_INJECTED_TOTAL,
+                &[duty.into(), action.as_str()],
+            );
+        }
+
+        match action {
+            ChaosAction::Proceed => Ok(()),
+            ChaosAction::Delay(delay) => {
+                debug!(
+                    self.log,
+                    "Delaying message due to chaos config";
+                    "duty" => ?duty,
+                    "epoch" => epoch,
+                    "delay" => ?delay,
+                );
+                tokio::time::sleep(delay).await;
+                Ok(())
+            }
+            ChaosAction::Drop => {
+                warn!(
+                    self.log,
+                    "Dropping message due to chaos config";
+                    "duty" => ?duty,
+                    "epoch" => epoch,
+                );
+                Err(Error::DroppedByChaosConfig(duty))
+            }
+        }
+    }
+
     pub async fn sign_block<Payload: AbstractExecPayload<E>>(
         &self,
         validator_pubkey: PublicKeyBytes,
@@ -570,6 +628,8 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         }
 
         let signing_epoch = block.epoch();
+        self.inject_chaos(ChaosDuty::Block, signing_epoch).await?;
+
         let signing_context = self.signing_context(Domain::BeaconProposer, signing_epoch);
         let domain_hash = signing_context.domain_hash(&self.spec);
 
@@ -648,6 +708,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             });
         }
 
+        self.inject_chaos(ChaosDuty::Attestation, attestation.data().target.epoch)
+            .await?;
+
         // Get the signing method and check doppelganger protection.
         let signing_method = self.doppelganger_checked_signing_method(validator_pubkey)?;
 
@@ -793,6 +856,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         selection_proof: SelectionProof,
     ) -> Result<SignedAggregateAndProof<E>, Error> {
         let signing_epoch = aggregate.data().target.epoch;
+        self.inject_chaos(ChaosDuty::Aggregate, signing_epoch)
+            .await?;
+
         let signing_context = self.signing_context(Domain::AggregateAndProof, signing_epoch);
 
         let message =
@@ -849,6 +915,56 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(signature.into())
     }
 
+    /// Produce a `SelectionProof` for each `(validator_pubkey, slot)` pair, in the same order.
+    ///
+    /// The proofs are signed together using `get_signatures_batch`, which batches the requests to
+    /// remote signers.
+    pub async fn produce_selection_proofs(
+        &self,
+        requests: &[(PublicKeyBytes, Slot)],
+    ) -> Vec<Result<SelectionProof, Error>> {
+        // Doppelganger protection is bypassed for the same reasons as `produce_selection_proof`.
+        let signing_methods = requests
+            .iter()
+            .map(|(validator_pubkey, _)| {
+                self.doppelganger_bypassed_signing_method(*validator_pubkey)
+            })
+            .collect::<Vec<_>>();
+
+        let batch = requests
+            .iter()
+            .zip(&signing_methods)
+            .filter_map(|((_, slot), signing_method)| {
+                let signing_epoch = slot.epoch(E::slots_per_epoch());
+                Some(BatchSigningRequest::<E, BlindedPayload<E>> {
+                    signing_method: signing_method.as_ref().ok()?.clone(),
+                    signable_message: SignableMessage::SelectionProof(*slot),
+                    signing_context: self.signing_context(Domain::SelectionProof, signing_epoch),
+                })
+            })
+            .collect();
+        let mut signatures = get_signatures_batch(batch, &self.spec, &self.task_executor)
+            .await
+            .into_iter();
+
+        signing_methods
+            .into_iter()
+            .map(|signing_method| {
+                signing_method?;
+                // There is one signature for each request with a known signing method.
+                let signature = signatures
+                    .next()
+                    .ok_or(Error::UnableToSign(SigningError::ShuttingDown))?
+                    .map_err(Error::UnableToSign)?;
+                metrics::inc_counter_vec(
+                    &metrics::SIGNED_SELECTION_PROOFS_TOTAL,
+                    &[metrics::SUCCESS],
+                );
+                Ok(signature.into())
+            })
+            .collect()
+    }
+
     /// Produce a `SyncSelectionProof` for `slot` signed by the secret key of `validator_pubkey`.
     pub async fn produce_sync_selection_proof(
         &self,
@@ -894,6 +1010,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         validator_pubkey: &PublicKeyBytes,
     ) -> Result<SyncCommitteeMessage, Error> {
         let signing_epoch = slot.epoch(E::slots_per_epoch());
+        self.inject_chaos(ChaosDuty::SyncCommitteeMessage, signing_epoch)
+            .await?;
+
         let signing_context = self.signing_context(Domain::SyncCommittee, signing_epoch);
 
         // Bypass `with_validator_signing_method`: sync committee messages are not slashable.
@@ -933,6 +1052,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         selection_proof: SyncSelectionProof,
     ) -> Result<SignedContributionAndProof<E>, Error> {
         let signing_epoch = contribution.slot.epoch(E::slots_per_epoch());
+        self.inject_chaos(ChaosDuty::SyncCommitteeContribution, signing_epoch)
+            .await?;
+
         let signing_context = self.signing_context(Domain::ContributionAndProof, signing_epoch);
 
         // Bypass `with_validator_signing_method`: sync committee messages are not slashable.
@@ -1037,6 +1159,22 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
 
         let all_pubkeys: Vec<_> = self.voting_pubkeys(DoppelgangerStatus::ignored);
 
+        if self.slashing_protection_minify {
+            if let Err(e) = self.slashing_protection.minify_all(all_pubkeys.iter()) {
+                error!(
+                    self.log,
+                    "Error during minification of slashing protection DB";
+                    "error" => ?e,
+                );
+                return;
+            }
+
+            *last_prune = current_epoch;
+
+            info!(self.log, "Completed minification of slashing protection DB");
+            return;
+        }
+
         if let Err(e) = self
             .slashing_protection
             .prune_all_signed_attestations(all_pubkeys.iter(), new_min_target_epoch)
@@ -143,6 +143,7 @@ impl ValidatorSpecification {
                     builder_boost_factor,
                     prefer_builder_proposals,
                     None, // Grafitti field is not maintained between validator moves.
+                    None, // Nor are the beacon nodes, which are specific to each VC.
                 )
                 .await
                 .map_err(UploadError::PatchValidatorFailed)?;
@@ -0,0 +1,4 @@
+DROP TABLE sync_committee_misses;
+
+ALTER TABLE beacon_blocks
+DROP COLUMN sync_committee_participation;
@@ -0,0 +1,8 @@
+ALTER TABLE beacon_blocks
+ADD COLUMN sync_committee_participation integer;
+
+CREATE TABLE sync_committee_misses (
+    slot integer REFERENCES beacon_blocks(slot) ON DELETE CASCADE,
+    index integer NOT NULL,
+    PRIMARY KEY(slot, index)
+)
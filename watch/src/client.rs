@@ -4,6 +4,7 @@ use crate::database::models::{
     WatchBeaconBlock, WatchCanonicalSlot, WatchProposerInfo, WatchValidator,
 };
 use crate::suboptimal_attestations::WatchAttestation;
+use crate::sync_committee::WatchSyncCommitteeMiss;
 
 use eth2::types::BlockId;
 use reqwest::Client;
@@ -155,6 +156,20 @@ impl WatchHttpClient {
         self.get_opt(url).await
     }
 
+    pub async fn get_sync_committee_misses(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<Vec<WatchSyncCommitteeMiss>>, Error> {
+        let url = self
+            .server
+            .join("v1/")?
+            .join("blocks/")?
+            .join(&format!("{block_id}/"))?
+            .join("sync_committee_misses")?;
+
+        self.get_opt(url).await
+    }
+
     pub async fn get_all_validators(&self) -> Result<Option<Vec<WatchValidator>>, Error> {
         let url = self.server.join("v1/")?.join("validators/")?.join("all")?;
 
@@ -47,6 +47,12 @@ pub use crate::suboptimal_attestations::{
     WatchAttestation, WatchSuboptimalAttestation,
 };
 
+#[allow(unused_imports)]
+pub use crate::sync_committee::{
+    get_sync_committee_misses_by_index, get_sync_committee_misses_by_slot,
+    insert_batch_sync_committee_misses, WatchSyncCommitteeMiss,
+};
+
 #[allow(unused_imports)]
 pub use crate::blockprint::{
     get_blockprint_by_root, get_blockprint_by_slot, get_highest_blockprint, get_lowest_blockprint,
@@ -159,6 +165,12 @@ pub fn insert_beacon_block<E: EthSpec>(
         .and_then(|payload| payload.execution_payload_capella().ok())
         .map(|payload| payload.withdrawals.len() as i32);
 
+    let sync_committee_participation: Option<i32> = block_message
+        .body()
+        .sync_aggregate()
+        .ok()
+        .map(|sync_aggregate| sync_aggregate.num_set_bits() as i32);
+
     let block_to_add = WatchBeaconBlock {
         slot,
         root,
@@ -166,6 +178,7 @@ pub fn insert_beacon_block<E: EthSpec>(
         attestation_count,
         transaction_count,
         withdrawal_count,
+        sync_committee_participation,
     };
 
     let proposer_info_to_add = WatchProposerInfo {
@@ -26,6 +26,10 @@ pub struct WatchBeaconBlock {
     pub attestation_count: i32,
     pub transaction_count: Option<i32>,
     pub withdrawal_count: Option<i32>,
+    /// The number of sync committee members whose signatures were included in the block.
+    ///
+    /// `None` for blocks prior to Altair.
+    pub sync_committee_participation: Option<i32>,
 }
 
 #[derive(Clone, Debug, Queryable, Insertable, Serialize, Deserialize)]
@@ -16,6 +16,7 @@ diesel::table! {
         attestation_count -> Int4,
         transaction_count -> Nullable<Int4>,
         withdrawal_count -> Nullable<Int4>,
+        sync_committee_participation -> Nullable<Int4>,
     }
 }
 
@@ -71,6 +72,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    sync_committee_misses (slot, index) {
+        slot -> Int4,
+        index -> Int4,
+    }
+}
+
 diesel::table! {
     validators (index) {
         index -> Int4,
@@ -88,6 +96,7 @@ diesel::joinable!(proposer_info -> beacon_blocks (slot));
 diesel::joinable!(proposer_info -> validators (proposer_index));
 diesel::joinable!(suboptimal_attestations -> canonical_slots (epoch_start_slot));
 diesel::joinable!(suboptimal_attestations -> validators (index));
+diesel::joinable!(sync_committee_misses -> beacon_blocks (slot));
 
 diesel::allow_tables_to_appear_in_same_query!(
     active_config,
@@ -98,5 +107,6 @@ diesel::allow_tables_to_appear_in_same_query!(
     canonical_slots,
     proposer_info,
     suboptimal_attestations,
+    sync_committee_misses,
     validators,
 );
@@ -26,3 +26,14 @@ pub fn run_migrations(config: &Config) -> PgConnection {
     conn.begin_test_transaction().unwrap();
     conn
 }
+
+/// Reverts the most recently applied migration.
+/// Equivalent to `diesel migration revert`.
+///
+/// Contains `unwrap`s so is only suitable for test code.
+pub fn revert_last_migration(config: &Config) {
+    let database_url = config.clone().build_database_url();
+    let mut conn = PgConnection::establish(&database_url).unwrap();
+    let migrations = FileBasedMigrations::find_migrations_directory().unwrap();
+    conn.revert_last_migration(migrations).unwrap();
+}
@@ -9,4 +9,5 @@ pub mod database;
 pub mod logger;
 pub mod server;
 pub mod suboptimal_attestations;
+pub mod sync_committee;
 pub mod updater;
@@ -20,6 +20,8 @@ mod server;
 #[cfg(unix)]
 mod suboptimal_attestations;
 #[cfg(unix)]
+mod sync_committee;
+#[cfg(unix)]
 mod updater;
 
 #[cfg(unix)]
@@ -4,12 +4,13 @@ use crate::blockprint::blockprint_routes;
 use crate::config::Config as FullConfig;
 use crate::database::{self, PgPool};
 use crate::suboptimal_attestations::{attestation_routes, blockprint_attestation_routes};
+use crate::sync_committee::sync_committee_routes;
 use axum::{
     http::{StatusCode, Uri},
     routing::get,
     Extension, Json, Router,
 };
-use eth2::types::ErrorMessage;
+use eth2::types::{ErrorCode, ErrorMessage};
 use log::info;
 use std::future::{Future, IntoFuture};
 use std::net::{SocketAddr, TcpListener};
@@ -98,7 +99,8 @@ pub fn start_server(
         .merge(attestation_routes())
         .merge(blockprint_routes())
         .merge(block_packing_routes())
-        .merge(block_rewards_routes());
+        .merge(block_rewards_routes())
+        .merge(sync_committee_routes());
 
     if config.blockprint.enabled && config.updater.attestations {
         routes = routes.merge(blockprint_attestation_routes())
@@ -130,7 +132,9 @@ async fn route_not_found(uri: Uri) -> (StatusCode, Json<ErrorMessage>) {
         Json(ErrorMessage {
             code: StatusCode::METHOD_NOT_ALLOWED.as_u16(),
             message: format!("No route for {uri}"),
+            error_code: Some(ErrorCode::MethodNotAllowed),
             stacktraces: vec![],
+            nearest_stored_slot: None,
         }),
     )
 }
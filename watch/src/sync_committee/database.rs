@@ -0,0 +1,78 @@
+use crate::database::{
+    schema::sync_committee_misses, watch_types::WatchSlot, Error, PgConn, MAX_SIZE_BATCH_INSERT,
+};
+
+use diesel::prelude::*;
+use diesel::{Insertable, Queryable};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+#[derive(Clone, Debug, PartialEq, Queryable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = sync_committee_misses)]
+pub struct WatchSyncCommitteeMiss {
+    pub slot: WatchSlot,
+    pub index: i32,
+}
+
+/// Insert a batch of values into the `sync_committee_misses` table.
+///
+/// On a conflict, it will do nothing, leaving the old value.
+pub fn insert_batch_sync_committee_misses(
+    conn: &mut PgConn,
+    misses: Vec<WatchSyncCommitteeMiss>,
+) -> Result<(), Error> {
+    use self::sync_committee_misses::dsl::*;
+
+    let mut count = 0;
+    let timer = Instant::now();
+
+    for chunk in misses.chunks(MAX_SIZE_BATCH_INSERT) {
+        count += diesel::insert_into(sync_committee_misses)
+            .values(chunk)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+    }
+
+    let time_taken = timer.elapsed();
+    debug!("Sync committee misses inserted, count: {count}, time taken: {time_taken:?}");
+    Ok(())
+}
+
+/// Selects all rows of the `sync_committee_misses` table corresponding to a given `slot_query`.
+pub fn get_sync_committee_misses_by_slot(
+    conn: &mut PgConn,
+    slot_query: WatchSlot,
+) -> Result<Vec<WatchSyncCommitteeMiss>, Error> {
+    use self::sync_committee_misses::dsl::*;
+    let timer = Instant::now();
+
+    let result = sync_committee_misses
+        .filter(slot.eq(slot_query))
+        .order_by(index.asc())
+        .load::<WatchSyncCommitteeMiss>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!("Sync committee misses requested: {slot_query}, time_taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Selects all rows of the `sync_committee_misses` table corresponding to a given `index_query`.
+pub fn get_sync_committee_misses_by_index(
+    conn: &mut PgConn,
+    index_query: i32,
+) -> Result<Vec<WatchSyncCommitteeMiss>, Error> {
+    use self::sync_committee_misses::dsl::*;
+    let timer = Instant::now();
+
+    let result = sync_committee_misses
+        .filter(index.eq(index_query))
+        .order_by(slot.asc())
+        .load::<WatchSyncCommitteeMiss>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!(
+        "Sync committee misses requested for validator: {index_query}, time_taken: {time_taken:?}"
+    );
+    Ok(result)
+}
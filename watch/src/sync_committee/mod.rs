@@ -0,0 +1,53 @@
+pub mod database;
+pub mod server;
+
+use crate::database::watch_types::WatchSlot;
+use crate::updater::error::Error;
+
+pub use database::{
+    get_sync_committee_misses_by_index, get_sync_committee_misses_by_slot,
+    insert_batch_sync_committee_misses, WatchSyncCommitteeMiss,
+};
+pub use server::sync_committee_routes;
+
+use eth2::{types::StateId, BeaconNodeHttpClient};
+use types::{EthSpec, SignedBeaconBlock, Slot};
+
+/// Sends a request to `beacon/states/{slot}/sync_committees`.
+/// Returns the validator indices of the sync committee active at `slot`, in committee order.
+pub async fn get_sync_committee(bn: &BeaconNodeHttpClient, slot: Slot) -> Result<Vec<u64>, Error> {
+    Ok(bn
+        .get_beacon_states_sync_committees(StateId::Slot(slot), None)
+        .await?
+        .data
+        .validators)
+}
+
+/// Compares the `sync_aggregate` of `block` against `committee` and returns a
+/// `WatchSyncCommitteeMiss` for each member whose signature was not included.
+///
+/// Returns an empty vector for blocks prior to Altair.
+pub fn get_sync_committee_misses<E: EthSpec>(
+    block: &SignedBeaconBlock<E>,
+    committee: &[u64],
+) -> Vec<WatchSyncCommitteeMiss> {
+    let Ok(sync_aggregate) = block.message().body().sync_aggregate() else {
+        return vec![];
+    };
+    let slot = WatchSlot::from_slot(block.slot());
+
+    committee
+        .iter()
+        .enumerate()
+        .filter(|(position, _)| {
+            !sync_aggregate
+                .sync_committee_bits
+                .get(*position)
+                .unwrap_or(false)
+        })
+        .map(|(_, validator_index)| WatchSyncCommitteeMiss {
+            slot,
+            index: *validator_index as i32,
+        })
+        .collect()
+}
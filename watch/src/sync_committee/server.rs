@@ -0,0 +1,47 @@
+use crate::database::{get_beacon_block_by_root, get_connection, PgPool, WatchHash, WatchSlot};
+use crate::server::Error;
+use crate::sync_committee::database::{
+    get_sync_committee_misses_by_index, get_sync_committee_misses_by_slot, WatchSyncCommitteeMiss,
+};
+
+use axum::{extract::Path, routing::get, Extension, Json, Router};
+use eth2::types::BlockId;
+use std::str::FromStr;
+
+pub async fn get_sync_committee_misses(
+    Path(block_query): Path<String>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<WatchSyncCommitteeMiss>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    let slot = match BlockId::from_str(&block_query).map_err(|_| Error::BadRequest)? {
+        BlockId::Root(root) => get_beacon_block_by_root(&mut conn, WatchHash::from_hash(root))?
+            .map(|block| block.slot)
+            .ok_or(Error::NotFound)?,
+        BlockId::Slot(slot) => WatchSlot::from_slot(slot),
+        _ => return Err(Error::BadRequest),
+    };
+    Ok(Json(get_sync_committee_misses_by_slot(&mut conn, slot)?))
+}
+
+pub async fn get_validator_sync_committee_misses(
+    Path(validator_index): Path<i32>,
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<WatchSyncCommitteeMiss>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    Ok(Json(get_sync_committee_misses_by_index(
+        &mut conn,
+        validator_index,
+    )?))
+}
+
+pub fn sync_committee_routes() -> Router {
+    Router::new()
+        .route(
+            "/v1/blocks/:block/sync_committee_misses",
+            get(get_sync_committee_misses),
+        )
+        .route(
+            "/v1/validators/:validator/sync_committee_misses",
+            get(get_validator_sync_committee_misses),
+        )
+}
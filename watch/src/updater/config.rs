@@ -20,6 +20,9 @@ pub const fn block_rewards() -> bool {
 pub const fn block_packing() -> bool {
     true
 }
+pub const fn sync_committee() -> bool {
+    true
+}
 
 fn beacon_node_url() -> String {
     BEACON_NODE_URL.to_string()
@@ -48,6 +51,9 @@ pub struct Config {
     /// Whether to sync the block_packing table.
     #[serde(default = "block_packing")]
     pub block_packing: bool,
+    /// Whether to sync the sync_committee_misses table.
+    #[serde(default = "sync_committee")]
+    pub sync_committee: bool,
 }
 
 impl Default for Config {
@@ -60,6 +66,7 @@ impl Default for Config {
             proposer_info: proposer_info(),
             block_rewards: block_rewards(),
             block_packing: block_packing(),
+            sync_committee: sync_committee(),
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::blockprint::WatchBlockprintClient;
 use crate::config::Config as FullConfig;
 use crate::database::{self, PgPool, WatchCanonicalSlot, WatchHash, WatchSlot};
+use crate::sync_committee::{get_sync_committee, get_sync_committee_misses};
 use crate::updater::{Config, Error, WatchSpec};
 use beacon_node::beacon_chain::BeaconChainError;
 use eth2::{
@@ -8,9 +9,9 @@ use eth2::{
     BeaconNodeHttpClient, SensitiveUrl,
 };
 use log::{debug, error, info, warn};
-use std::collections::HashSet;
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::marker::PhantomData;
-use types::{BeaconBlockHeader, EthSpec, Hash256, SignedBeaconBlock, Slot};
+use types::{BeaconBlockHeader, Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
 use crate::updater::{get_beacon_block, get_header, get_validators};
 
@@ -99,11 +100,28 @@ impl<E: EthSpec> UpdateHandler<E> {
     pub async fn update_unknown_blocks(&mut self) -> Result<(), Error> {
         let mut conn = database::get_connection(&self.pool)?;
         let roots = database::get_unknown_canonical_blocks(&mut conn)?;
+        // Cache the sync committee for each epoch to avoid re-requesting it for every block.
+        let mut sync_committees: HashMap<Epoch, Vec<u64>> = HashMap::new();
         for root in roots {
             let block_opt: Option<SignedBeaconBlock<E>> =
                 get_beacon_block(&self.bn, BlockId::Root(root.as_hash())).await?;
             if let Some(block) = block_opt {
+                let misses = if self.config.sync_committee
+                    && block.message().body().sync_aggregate().is_ok()
+                {
+                    let epoch = block.slot().epoch(self.slots_per_epoch);
+                    let committee = match sync_committees.entry(epoch) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            entry.insert(get_sync_committee(&self.bn, block.slot()).await?)
+                        }
+                    };
+                    get_sync_committee_misses(&block, committee)
+                } else {
+                    vec![]
+                };
                 database::insert_beacon_block(&mut conn, block, root)?;
+                database::insert_batch_sync_committee_misses(&mut conn, misses)?;
             }
         }
 
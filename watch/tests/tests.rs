@@ -2,10 +2,15 @@
 #![cfg(unix)]
 
 use beacon_chain::{
-    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
+    test_utils::{
+        test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
+    },
     ChainConfig,
 };
-use eth2::{types::BlockId, BeaconNodeHttpClient, SensitiveUrl, Timeouts};
+use eth2::{
+    types::{BlockId, StateId},
+    BeaconNodeHttpClient, SensitiveUrl, Timeouts,
+};
 use http_api::test_utils::{create_api_server, ApiServer};
 use log::error;
 use logging::test_logger;
@@ -18,7 +23,7 @@ use std::time::Duration;
 use testcontainers::{clients::Cli, core::WaitFor, Image, RunnableImage};
 use tokio::{runtime, task::JoinHandle};
 use tokio_postgres::{config::Config as PostgresConfig, Client, NoTls};
-use types::{Hash256, MainnetEthSpec, Slot};
+use types::{ChainSpec, Epoch, Hash256, MainnetEthSpec, Slot};
 use unused_port::unused_tcp4_port;
 use url::Url;
 use watch::{
@@ -122,8 +127,12 @@ struct TesterBuilder {
 
 impl TesterBuilder {
     pub async fn new() -> TesterBuilder {
+        Self::new_with_spec(None).await
+    }
+
+    pub async fn new_with_spec(spec: Option<ChainSpec>) -> TesterBuilder {
         let harness = BeaconChainHarness::builder(E::default())
-            .default_spec()
+            .spec_or_default(spec)
             .chain_config(ChainConfig {
                 reconstruct_historic_states: true,
                 ..ChainConfig::default()
@@ -615,6 +624,65 @@ impl Tester {
 
         self
     }
+
+    /// Check that every block in the `beacon_blocks` table has a `sync_committee_misses` entry
+    /// for each sync committee member whose signature was not included.
+    ///
+    /// The harness does not produce sync committee contributions, so every member should miss.
+    pub async fn assert_all_blocks_have_sync_committee_misses(&mut self) -> &mut Self {
+        let pool = database::build_connection_pool(&self.config.database).unwrap();
+
+        let mut conn = database::get_connection(&pool).unwrap();
+        let highest_block_slot = database::get_highest_beacon_block(&mut conn)
+            .unwrap()
+            .unwrap()
+            .slot
+            .as_slot();
+        let lowest_block_slot = database::get_lowest_beacon_block(&mut conn)
+            .unwrap()
+            .unwrap()
+            .slot
+            .as_slot();
+        for slot in lowest_block_slot.as_u64()..=highest_block_slot.as_u64() {
+            let Some(block) =
+                database::get_beacon_block_by_slot(&mut conn, WatchSlot::new(slot)).unwrap()
+            else {
+                continue;
+            };
+            assert_eq!(block.sync_committee_participation, Some(0));
+
+            let mut committee = self
+                .updater
+                .bn
+                .get_beacon_states_sync_committees(StateId::Slot(Slot::new(slot)), None)
+                .await
+                .unwrap()
+                .data
+                .validators
+                .into_iter()
+                .map(|index| index as i32)
+                .collect::<Vec<_>>();
+            committee.sort_unstable();
+            committee.dedup();
+
+            let misses = database::get_sync_committee_misses_by_slot(&mut conn, block.slot)
+                .unwrap()
+                .into_iter()
+                .map(|miss| miss.index)
+                .collect::<Vec<_>>();
+            assert_eq!(misses, committee);
+
+            let api_misses = self
+                .client
+                .get_sync_committee_misses(BlockId::Slot(Slot::new(slot)))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(api_misses.len(), committee.len());
+        }
+
+        self
+    }
 }
 
 pub fn random_dbname() -> String {
@@ -1290,3 +1358,61 @@ async fn large_chain() {
         .assert_all_blocks_have_metadata()
         .await;
 }
+
+#[cfg(unix)]
+#[tokio::test]
+async fn chain_grows_with_sync_committee_misses() {
+    let mut spec = test_spec::<E>();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    let builder = TesterBuilder::new_with_spec(Some(spec)).await;
+
+    let docker = Cli::default();
+    let image = RunnableImage::from(Postgres::default())
+        .with_mapped_port((builder.config.database.port, 5432));
+    let _node = docker.run(image);
+
+    let pool = builder.initialize_database().await;
+    let mut tester = builder.build(pool).await;
+
+    tester
+        .extend_chain(SLOTS_PER_EPOCH)
+        .await
+        .perform_head_update()
+        .await
+        .update_unknown_blocks()
+        .await
+        .assert_canonical_chain_consistent(0)
+        .await
+        .assert_all_blocks_have_sync_committee_misses()
+        .await;
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn sync_committee_migration_reverts() {
+    let builder = TesterBuilder::new().await;
+
+    let docker = Cli::default();
+    let image = RunnableImage::from(Postgres::default())
+        .with_mapped_port((builder.config.database.port, 5432));
+    let _node = docker.run(image);
+
+    let pool = builder.initialize_database().await;
+    let config = &builder.config.database;
+    let mut conn = database::get_connection(&pool).unwrap();
+
+    // The migration is applied.
+    database::get_highest_beacon_block(&mut conn).unwrap();
+    database::get_sync_committee_misses_by_slot(&mut conn, WatchSlot::new(0)).unwrap();
+
+    // Reverting drops both the `sync_committee_participation` column and the
+    // `sync_committee_misses` table.
+    database::utils::revert_last_migration(config);
+    assert!(database::get_highest_beacon_block(&mut conn).is_err());
+    assert!(database::get_sync_committee_misses_by_slot(&mut conn, WatchSlot::new(0)).is_err());
+
+    // Re-applying the migration restores them.
+    database::utils::run_migrations(config);
+    database::get_highest_beacon_block(&mut conn).unwrap();
+    database::get_sync_committee_misses_by_slot(&mut conn, WatchSlot::new(0)).unwrap();
+}